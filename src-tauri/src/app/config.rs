@@ -1,9 +1,19 @@
+use super::error::{AppError, AppResult, SettingError};
+use crate::core::parser::{default_web_engines, validate_url_template, WebSearchEngine};
+use crate::core::screenshot::save::SaveFormat;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Shape of [`AppConfig`] this build writes; see `app::migration`
+pub const CONFIG_VERSION: u32 = 3;
 
 /// Main application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// Shape the settings were saved in; older files are migrated on load
+    #[serde(default = "default_config_version")]
+    pub version: u32,
     pub general: GeneralConfig,
     pub features: FeaturesConfig,
     pub appearance: AppearanceConfig,
@@ -13,6 +23,35 @@ pub struct AppConfig {
     pub screenshot: ScreenshotConfig,
     pub ai: AIConfig,
     pub web_search: WebSearchConfig,
+    #[serde(default)]
+    pub calculator: CalculatorConfig,
+    #[serde(default)]
+    pub result_actions: ResultActionsConfig,
+    #[serde(default)]
+    pub launch: LaunchConfig,
+    #[serde(default)]
+    pub browser_history: BrowserHistoryConfig,
+    #[serde(default)]
+    pub search_cache: SearchCacheConfig,
+    #[serde(default)]
+    pub full_text: FullTextConfig,
+    #[serde(default)]
+    pub icon_cache: IconCacheConfig,
+    #[serde(default)]
+    pub recent_items: RecentItemsConfig,
+    #[serde(default)]
+    pub plugin_search: PluginSearchConfig,
+    #[serde(default)]
+    pub plugin_sandbox: PluginSandboxConfig,
+    /// Paths kept at the top of file/app results when they match, in order
+    #[serde(default)]
+    pub pinned_items: Vec<String>,
+    #[serde(default)]
+    pub quick_note: QuickNoteConfig,
+    #[serde(default)]
+    pub dictionary: DictionaryConfig,
+    #[serde(default)]
+    pub autohide: AutohideConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +70,9 @@ pub struct FeaturesConfig {
     pub clipboard: bool,
     pub screenshot: bool,
     pub ai: bool,
+    /// Lets "> command" run through the system shell; off unless the user opts in
+    #[serde(default)]
+    pub enable_shell_commands: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +89,12 @@ pub struct ShortcutsConfig {
     pub clipboard: String,
     pub screenshot: String,
     pub ai_chat: String,
+    /// Saves the clipboard text as a quick note; empty leaves it unbound
+    #[serde(default)]
+    pub quick_note: String,
+    /// Pastes the next older history item on each press; empty leaves it unbound
+    #[serde(default)]
+    pub clipboard_ring: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +105,70 @@ pub struct IndexerConfig {
     pub file_types: Vec<String>,
     pub max_file_size: u64,
     pub index_hidden: bool,
+    #[serde(default)]
+    pub scan_preset: ScanPreset,
+    /// Order of Everything file results (Windows)
+    #[serde(default)]
+    pub file_sort: FileSortOrder,
+    /// Everything results must lie under one of these folders or globs;
+    /// empty means anywhere
+    #[serde(default)]
+    pub search_include: Vec<String>,
+    /// Everything results matching any of these globs are dropped
+    #[serde(default = "default_search_exclude")]
+    pub search_exclude: Vec<String>,
+    /// Match the query against full paths instead of file names
+    #[serde(default)]
+    pub match_path: bool,
+    /// File/app results per page when `search` is not given a limit
+    #[serde(default = "default_result_limit")]
+    pub result_limit: usize,
+}
+
+fn default_result_limit() -> usize {
+    50
+}
+
+fn default_search_exclude() -> Vec<String> {
+    [
+        "**/*uninstall*",
+        "**/*卸载*",
+        "**/$Recycle.Bin/**",
+        "**/System Volume Information/**",
+    ]
+    .iter()
+    .map(|glob| glob.to_string())
+    .collect()
+}
+
+/// Sort orders offered for file search results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileSortOrder {
+    NameAscending,
+    NameDescending,
+    PathAscending,
+    /// Largest first
+    SizeDescending,
+    SizeAscending,
+    /// Most recently modified first
+    #[default]
+    DateModifiedDescending,
+    DateModifiedAscending,
+    /// Most often opened (through Everything) first
+    RunCountDescending,
+}
+
+/// How hard background indexing may hit the disk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanPreset {
+    /// One directory at a time with frequent pauses (spinning disks, network drives)
+    Low,
+    #[default]
+    Normal,
+    /// Parallel walking without throttling
+    Aggressive,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,17 +178,128 @@ pub struct ClipboardConfig {
     pub retention_days: usize,
     pub filter_sensitive: bool,
     pub exclude_apps: Vec<String>,
+    /// Budget for stored clipboard data (mostly images); oldest large blobs are pruned first
+    #[serde(default = "default_clipboard_max_total_bytes")]
+    pub max_total_bytes: u64,
+    /// Store only a redacted preview for sensitive items and hide them from search
+    #[serde(default = "default_true")]
+    pub mask_sensitive: bool,
+    /// Source apps (e.g. password managers) whose content is always sensitive
+    #[serde(default = "default_sensitive_apps")]
+    pub sensitive_apps: Vec<String>,
+    /// Sensitive items, which keep their original text for pasting, are
+    /// deleted this many minutes after they were copied unless favorited
+    #[serde(default = "default_sensitive_retention_minutes")]
+    pub sensitive_retention_minutes: u64,
+    /// Paste text items without formatting by default
+    #[serde(default)]
+    pub paste_as_plain_text: bool,
+    /// Encrypt stored text and data with a key kept in the OS keychain.
+    /// Takes effect after a restart.
+    #[serde(default)]
+    pub encrypt: bool,
+    /// How long after pasting an item the clipboard it replaced is put back,
+    /// in milliseconds. Slow target apps may read the clipboard late; raise
+    /// this if they paste the old contents.
+    #[serde(default = "default_paste_restore_delay_ms")]
+    pub paste_restore_delay_ms: u64,
+    /// Folder shared between devices (e.g. by Syncthing or Dropbox). New
+    /// text items are written to it and items from other devices are
+    /// imported; sensitive items never leave this device. Ignored while
+    /// `encrypt` is on.
+    #[serde(default)]
+    pub sync_folder: Option<PathBuf>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_sensitive_apps() -> Vec<String> {
+    crate::core::clipboard::filter::DEFAULT_SENSITIVE_APPS
+        .iter()
+        .map(|app| app.to_string())
+        .collect()
+}
+
+fn default_clipboard_max_total_bytes() -> u64 {
+    256 * 1024 * 1024
+}
+
+fn default_paste_restore_delay_ms() -> u64 {
+    500
+}
+
+fn default_sensitive_retention_minutes() -> u64 {
+    60
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScreenshotConfig {
+    /// "png", "jpg" or "webp"
     pub format: String,
     pub quality: u8,
     pub save_dir: PathBuf,
     pub auto_save: bool,
 
+    /// Name of quick-saved captures, without extension; supports `{date}`,
+    /// `{time}` and `{timestamp}`
+    #[serde(default = "default_screenshot_file_name_template")]
+    pub file_name_template: String,
+
     #[serde(default)]
     pub ocr_auto_copy: bool,
+
+    /// What the capture hotkey does
+    #[serde(default)]
+    pub capture_mode: CaptureMode,
+
+    /// What happens once the region is captured
+    #[serde(default)]
+    pub after_capture: AfterCapture,
+
+    /// How long to wait for OmniBox's own windows to disappear before the
+    /// screen is grabbed, in milliseconds
+    #[serde(default = "default_hide_windows_delay_ms")]
+    pub hide_windows_delay_ms: u64,
+
+    /// Show the windows hidden for a capture again once the overlay closes
+    #[serde(default = "default_true")]
+    pub restore_windows_after_capture: bool,
+}
+
+fn default_screenshot_file_name_template() -> String {
+    crate::core::screenshot::save::DEFAULT_FILE_NAME_TEMPLATE.to_string()
+}
+
+fn default_hide_windows_delay_ms() -> u64 {
+    120
+}
+
+/// Capture hotkey behavior
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMode {
+    /// Show the region selection overlay
+    #[default]
+    Region,
+    /// Capture the foreground window's bounds directly
+    ActiveWindow,
+    /// Capture the whole monitor under the cursor
+    Fullscreen,
+    /// Re-capture the last selected region
+    LastRegion,
+}
+
+/// Follow-up after a region has been captured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AfterCapture {
+    /// Show the toolbar (copy/save/pin) after selecting; direct captures go to the clipboard
+    #[default]
+    Edit,
+    /// Pin the capture immediately, skipping the toolbar
+    Pin,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,25 +310,266 @@ pub struct AIConfig {
     pub model: String,
     pub temperature: f32,
     pub max_tokens: u32,
+    /// Default stop sequences sent with every request
+    #[serde(default)]
+    pub stop: Vec<String>,
+    /// Azure OpenAI deployment name (provider "azure")
+    #[serde(default)]
+    pub deployment: Option<String>,
+    /// Azure OpenAI `api-version` (provider "azure")
+    #[serde(default)]
+    pub api_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebSearchConfig {
     pub default_engine: String,
-    pub engines: Vec<SearchEngine>,
+    pub engines: Vec<WebSearchEngine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalculatorConfig {
+    /// Exchange rate endpoint returning `{ "rates": { "EUR": 0.92, ... } }`
+    pub currency_api_url: String,
+}
+
+impl Default for CalculatorConfig {
+    fn default() -> Self {
+        Self {
+            currency_api_url: "https://open.er-api.com/v6/latest/USD".to_string(),
+        }
+    }
+}
+
+/// Enter + modifier bindings for search results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultActionsConfig {
+    /// Result type ("app", "file", "*" for any) -> modifier ("ctrl", "alt+shift", ...) -> action
+    pub bindings: HashMap<String, HashMap<String, String>>,
+}
+
+impl Default for ResultActionsConfig {
+    fn default() -> Self {
+        Self {
+            bindings: crate::core::actions::default_bindings(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaunchConfig {
+    /// Apps that are always run as administrator (Windows): full paths, or
+    /// bare file names like "regedit.exe"
+    #[serde(default)]
+    pub always_elevate: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserHistoryConfig {
+    pub enabled: bool,
+    /// Profiles to search, as "Browser/Profile" or a profile name; empty = all
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    /// Ignore visits older than this
+    pub max_age_days: u32,
+}
+
+impl Default for BrowserHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            profiles: Vec::new(),
+            max_age_days: 90,
+        }
+    }
+}
+
+/// Cache of recent file/app search results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchCacheConfig {
+    /// Number of queries kept; 0 disables the cache
+    pub capacity: usize,
+    /// How long results stay valid, in milliseconds
+    pub ttl_ms: u64,
+}
+
+impl Default for SearchCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            ttl_ms: 3000,
+        }
+    }
+}
+
+/// On-disk cache of extracted icons
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IconCacheConfig {
+    /// Least recently used icons are evicted above this size; 0 disables the cap
+    pub max_bytes: u64,
+}
+
+impl Default for IconCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// Recently opened items shown for an empty query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentItemsConfig {
+    /// When off, launches are not recorded and nothing is shown
+    pub enabled: bool,
+    /// Items shown for an empty query
+    pub limit: usize,
+}
+
+impl Default for RecentItemsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            limit: 10,
+        }
+    }
+}
+
+/// Results from enabled search plugins, merged into file/app search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginSearchConfig {
+    pub enabled: bool,
+    /// How long each plugin may take before its results are dropped
+    pub timeout_ms: u64,
+}
+
+impl Default for PluginSearchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_ms: 300,
+        }
+    }
+}
+
+/// Where quick notes are appended
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickNoteConfig {
+    /// Folder of the notes file; the Documents folder when unset
+    pub directory: Option<PathBuf>,
+    pub file_name: String,
+}
+
+impl Default for QuickNoteConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            file_name: "quick-notes.md".to_string(),
+        }
+    }
+}
+
+/// Where "def <word>" looks up definitions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictionaryConfig {
+    /// Endpoint with a `{word}` placeholder, answering like dictionaryapi.dev
+    pub api_url: String,
+}
+
+impl Default for DictionaryConfig {
+    fn default() -> Self {
+        Self {
+            api_url: "https://api.dictionaryapi.dev/api/v2/entries/en/{word}".to_string(),
+        }
+    }
+}
+
+/// Limits on each plugin call; a plugin exceeding one is stopped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginSandboxConfig {
+    pub timeout_ms: u64,
+    /// Output a plugin may write in response to one call
+    pub max_output_bytes: usize,
+    /// Memory of the plugin process; 0 means unlimited
+    pub max_memory_bytes: u64,
+}
+
+impl Default for PluginSandboxConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 10_000,
+            max_output_bytes: 1024 * 1024,
+            max_memory_bytes: 512 * 1024 * 1024,
+        }
+    }
+}
+
+/// How quickly the launcher hides when it loses focus, and how far apart
+/// global shortcut presses must be to count twice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutohideConfig {
+    /// Hide the launcher when it loses focus; `OMNIBOX_AUTOHIDE_ON_BLUR`
+    /// overrides this
+    #[serde(default = "default_true")]
+    pub on_blur: bool,
+    /// Blurs this soon after showing are ignored until the launcher gets
+    /// focus; focus can flap while a window is shown
+    pub show_blur_grace_ms: u64,
+    /// Blurs this soon after the launcher got focus are ignored
+    pub focus_blur_grace_ms: u64,
+    pub main_shortcut_debounce_ms: u64,
+    pub clipboard_shortcut_debounce_ms: u64,
+    pub capture_shortcut_debounce_ms: u64,
+}
+
+impl Default for AutohideConfig {
+    fn default() -> Self {
+        Self {
+            on_blur: true,
+            show_blur_grace_ms: 2000,
+            focus_blur_grace_ms: 250,
+            main_shortcut_debounce_ms: 350,
+            clipboard_shortcut_debounce_ms: 400,
+            capture_shortcut_debounce_ms: 500,
+        }
+    }
 }
 
+/// File-content search ("ft " prefix)
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SearchEngine {
-    pub name: String,
-    pub keyword: String,
-    pub url: String,
-    pub icon: Option<String>,
+pub struct FullTextConfig {
+    /// Folders whose files are indexed by content
+    pub directories: Vec<PathBuf>,
+    /// Extensions of text-like files to read (lowercase, without the dot)
+    pub extensions: Vec<String>,
+    /// Larger files are skipped
+    pub max_file_size: u64,
+}
+
+impl Default for FullTextConfig {
+    fn default() -> Self {
+        Self {
+            directories: Vec::new(),
+            extensions: [
+                "txt", "md", "markdown", "log", "csv", "json", "yaml", "yml", "toml", "ini", "xml",
+                "html", "css", "js", "ts", "rs", "py", "go", "java", "c", "cpp", "h", "sh",
+            ]
+            .iter()
+            .map(|ext| ext.to_string())
+            .collect(),
+            max_file_size: 1024 * 1024,
+        }
+    }
+}
+
+fn default_config_version() -> u32 {
+    CONFIG_VERSION
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             general: GeneralConfig {
                 language: "en".to_string(),
                 auto_start: false,
@@ -119,6 +583,7 @@ impl Default for AppConfig {
                 clipboard: true,
                 screenshot: true,
                 ai: false,
+                enable_shell_commands: false,
             },
             appearance: AppearanceConfig {
                 theme: "auto".to_string(),
@@ -131,6 +596,8 @@ impl Default for AppConfig {
                 clipboard: "CommandOrControl+Shift+V".to_string(),
                 screenshot: "CommandOrControl+Shift+S".to_string(),
                 ai_chat: "CommandOrControl+Shift+A".to_string(),
+                quick_note: String::new(),
+                clipboard_ring: String::new(),
             },
             indexer: IndexerConfig {
                 enabled: true,
@@ -139,6 +606,12 @@ impl Default for AppConfig {
                 file_types: vec![],
                 max_file_size: 100 * 1024 * 1024, // 100MB
                 index_hidden: false,
+                scan_preset: ScanPreset::Normal,
+                file_sort: FileSortOrder::default(),
+                search_include: vec![],
+                search_exclude: default_search_exclude(),
+                match_path: false,
+                result_limit: default_result_limit(),
             },
             clipboard: ClipboardConfig {
                 enabled: true,
@@ -146,13 +619,26 @@ impl Default for AppConfig {
                 retention_days: 30,
                 filter_sensitive: true,
                 exclude_apps: vec![],
+                max_total_bytes: default_clipboard_max_total_bytes(),
+                mask_sensitive: true,
+                sensitive_apps: default_sensitive_apps(),
+                sensitive_retention_minutes: default_sensitive_retention_minutes(),
+                paste_as_plain_text: false,
+                encrypt: false,
+                paste_restore_delay_ms: default_paste_restore_delay_ms(),
+                sync_folder: None,
             },
             screenshot: ScreenshotConfig {
                 format: "png".to_string(),
                 quality: 90,
                 save_dir: PathBuf::new(),
                 auto_save: false,
+                file_name_template: default_screenshot_file_name_template(),
                 ocr_auto_copy: false,
+                capture_mode: CaptureMode::Region,
+                after_capture: AfterCapture::Edit,
+                hide_windows_delay_ms: default_hide_windows_delay_ms(),
+                restore_windows_after_capture: true,
             },
             ai: AIConfig {
                 provider: "openai".to_string(),
@@ -161,30 +647,492 @@ impl Default for AppConfig {
                 model: "gpt-4".to_string(),
                 temperature: 0.7,
                 max_tokens: 2000,
+                stop: Vec::new(),
+                deployment: None,
+                api_version: None,
             },
             web_search: WebSearchConfig {
                 default_engine: "google".to_string(),
-                engines: vec![
-                    SearchEngine {
-                        name: "Google".to_string(),
-                        keyword: "gg".to_string(),
-                        url: "https://www.google.com/search?q={query}".to_string(),
-                        icon: None,
-                    },
-                    SearchEngine {
-                        name: "Baidu".to_string(),
-                        keyword: "bd".to_string(),
-                        url: "https://www.baidu.com/s?wd={query}".to_string(),
-                        icon: None,
-                    },
-                    SearchEngine {
-                        name: "GitHub".to_string(),
-                        keyword: "gh".to_string(),
-                        url: "https://github.com/search?q={query}".to_string(),
-                        icon: None,
-                    },
-                ],
+                engines: default_web_engines(),
             },
+            calculator: CalculatorConfig::default(),
+            result_actions: ResultActionsConfig::default(),
+            launch: LaunchConfig::default(),
+            browser_history: BrowserHistoryConfig::default(),
+            search_cache: SearchCacheConfig::default(),
+            icon_cache: IconCacheConfig::default(),
+            full_text: FullTextConfig::default(),
+            recent_items: RecentItemsConfig::default(),
+            plugin_search: PluginSearchConfig::default(),
+            plugin_sandbox: PluginSandboxConfig::default(),
+            pinned_items: Vec::new(),
+            quick_note: QuickNoteConfig::default(),
+            dictionary: DictionaryConfig::default(),
+            autohide: AutohideConfig::default(),
+        }
+    }
+}
+
+/// Most file/app results a page may hold
+const MAX_RESULT_LIMIT: usize = 1000;
+/// Longest blur grace; past this the launcher would seem not to auto-hide
+const MAX_AUTOHIDE_GRACE_MS: u64 = 10_000;
+/// A week
+const MAX_SENSITIVE_RETENTION_MINUTES: u64 = 7 * 24 * 60;
+/// Longest shortcut debounce; past this real presses would be dropped
+const MAX_SHORTCUT_DEBOUNCE_MS: u64 = 2000;
+
+impl AppConfig {
+    /// Applies a partial update. Objects in `patch` are merged field by
+    /// field, any other value replaces the current one, so fields the patch
+    /// leaves out keep their values. A value of the wrong type is rejected
+    /// with the path of the field.
+    pub fn merge(&self, patch: serde_json::Value) -> AppResult<AppConfig> {
+        let mut merged = serde_json::to_value(self)?;
+        merge_json(&mut merged, patch);
+        serde_path_to_error::deserialize(merged).map_err(|e| AppError::InvalidConfig {
+            field: e.path().to_string(),
+            message: e.inner().to_string(),
+        })
+    }
+
+    /// Rejects values that deserialize fine but would break the launcher,
+    /// naming every offending field
+    pub fn validate(&self) -> AppResult<()> {
+        reject(self.problems())
+    }
+
+    /// Like [`validate`](Self::validate), but lets through values that
+    /// `current` already has, so a bad value stored in the settings file
+    /// does not block changes to unrelated settings
+    pub fn validate_update(&self, current: &AppConfig) -> AppResult<()> {
+        let existing = current.problems();
+        reject(
+            self.problems()
+                .into_iter()
+                .filter(|p| !existing.contains(p))
+                .collect(),
+        )
+    }
+
+    fn problems(&self) -> Vec<SettingError> {
+        let mut problems = Vec::new();
+
+        if !(0.0..=1.0).contains(&self.appearance.transparency) {
+            problems.push(invalid(
+                "appearance.transparency",
+                "must be between 0 and 1",
+            ));
+        }
+        if self.appearance.window_radius < 0 {
+            problems.push(invalid("appearance.window_radius", "must not be negative"));
+        }
+
+        let shortcuts = [
+            // (field, value, may be left empty)
+            ("shortcuts.main", &self.shortcuts.main, false),
+            ("shortcuts.clipboard", &self.shortcuts.clipboard, false),
+            ("shortcuts.screenshot", &self.shortcuts.screenshot, false),
+            ("shortcuts.ai_chat", &self.shortcuts.ai_chat, false),
+            ("shortcuts.quick_note", &self.shortcuts.quick_note, true),
+            (
+                "shortcuts.clipboard_ring",
+                &self.shortcuts.clipboard_ring,
+                true,
+            ),
+        ];
+        for (field, shortcut, optional) in shortcuts {
+            if optional && shortcut.is_empty() {
+                continue;
+            }
+            if let Err(e) = shortcut.parse::<tauri_plugin_global_shortcut::Shortcut>() {
+                problems.push(invalid(
+                    field,
+                    format!("'{}' is not a valid shortcut ({})", shortcut, e),
+                ));
+            }
         }
+
+        if !(1..=MAX_RESULT_LIMIT).contains(&self.indexer.result_limit) {
+            problems.push(invalid(
+                "indexer.result_limit",
+                format!("must be between 1 and {}", MAX_RESULT_LIMIT),
+            ));
+        }
+        if self.clipboard.history_limit == 0 {
+            problems.push(invalid("clipboard.history_limit", "must be at least 1"));
+        }
+        if !(1..=MAX_SENSITIVE_RETENTION_MINUTES)
+            .contains(&self.clipboard.sensitive_retention_minutes)
+        {
+            problems.push(invalid(
+                "clipboard.sensitive_retention_minutes",
+                format!("must be between 1 and {}", MAX_SENSITIVE_RETENTION_MINUTES),
+            ));
+        }
+        if !(50..=10_000).contains(&self.clipboard.paste_restore_delay_ms) {
+            problems.push(invalid(
+                "clipboard.paste_restore_delay_ms",
+                "must be between 50 and 10000",
+            ));
+        }
+        if self
+            .clipboard
+            .sync_folder
+            .as_deref()
+            .is_some_and(|folder| !folder.is_absolute())
+        {
+            problems.push(invalid("clipboard.sync_folder", "must be an absolute path"));
+        }
+        if !(1..=100).contains(&self.screenshot.quality) {
+            problems.push(invalid("screenshot.quality", "must be between 1 and 100"));
+        }
+        if SaveFormat::parse(&self.screenshot.format).is_none() {
+            problems.push(invalid("screenshot.format", "must be png, jpg or webp"));
+        }
+        if self.screenshot.file_name_template.trim().is_empty() {
+            problems.push(invalid(
+                "screenshot.file_name_template",
+                "must not be empty",
+            ));
+        }
+        if self.screenshot.hide_windows_delay_ms > 2000 {
+            problems.push(invalid(
+                "screenshot.hide_windows_delay_ms",
+                "must be at most 2000",
+            ));
+        }
+
+        if !self.ai.api_url.is_empty() {
+            problems.extend(check_http_url("ai.api_url", &self.ai.api_url));
+        }
+        if !(0.0..=2.0).contains(&self.ai.temperature) {
+            problems.push(invalid("ai.temperature", "must be between 0 and 2"));
+        }
+        if self.ai.max_tokens == 0 {
+            problems.push(invalid("ai.max_tokens", "must be at least 1"));
+        }
+        problems.extend(check_http_url(
+            "calculator.currency_api_url",
+            &self.calculator.currency_api_url,
+        ));
+        if !self.dictionary.api_url.contains("{word}") {
+            problems.push(invalid("dictionary.api_url", "must contain {word}"));
+        }
+        problems.extend(check_http_url(
+            "dictionary.api_url",
+            &self.dictionary.api_url,
+        ));
+
+        for (i, engine) in self.web_search.engines.iter().enumerate() {
+            if engine.keyword.trim().is_empty() || engine.keyword.contains(char::is_whitespace) {
+                problems.push(invalid(
+                    &format!("web_search.engines[{}].keyword", i),
+                    "must be a single non-empty word",
+                ));
+            }
+            if !validate_url_template(&engine.url_template) {
+                problems.push(invalid(
+                    &format!("web_search.engines[{}].url_template", i),
+                    "must contain {query}",
+                ));
+            }
+        }
+
+        if self
+            .quick_note
+            .directory
+            .as_deref()
+            .is_some_and(|dir| !dir.is_absolute())
+        {
+            problems.push(invalid("quick_note.directory", "must be an absolute path"));
+        }
+        let note_file = Path::new(&self.quick_note.file_name);
+        if note_file.file_name().map(|name| name.len()) != Some(self.quick_note.file_name.len()) {
+            problems.push(invalid(
+                "quick_note.file_name",
+                "must be a file name, not a path",
+            ));
+        }
+
+        let graces = [
+            (
+                "autohide.show_blur_grace_ms",
+                self.autohide.show_blur_grace_ms,
+            ),
+            (
+                "autohide.focus_blur_grace_ms",
+                self.autohide.focus_blur_grace_ms,
+            ),
+        ];
+        for (field, ms) in graces {
+            if ms > MAX_AUTOHIDE_GRACE_MS {
+                problems.push(invalid(
+                    field,
+                    format!("must be at most {}", MAX_AUTOHIDE_GRACE_MS),
+                ));
+            }
+        }
+        let debounces = [
+            (
+                "autohide.main_shortcut_debounce_ms",
+                self.autohide.main_shortcut_debounce_ms,
+            ),
+            (
+                "autohide.clipboard_shortcut_debounce_ms",
+                self.autohide.clipboard_shortcut_debounce_ms,
+            ),
+            (
+                "autohide.capture_shortcut_debounce_ms",
+                self.autohide.capture_shortcut_debounce_ms,
+            ),
+        ];
+        for (field, ms) in debounces {
+            if ms > MAX_SHORTCUT_DEBOUNCE_MS {
+                problems.push(invalid(
+                    field,
+                    format!("must be at most {}", MAX_SHORTCUT_DEBOUNCE_MS),
+                ));
+            }
+        }
+
+        if self.plugin_search.timeout_ms == 0 {
+            problems.push(invalid("plugin_search.timeout_ms", "must be at least 1"));
+        }
+        if self.plugin_sandbox.timeout_ms == 0 {
+            problems.push(invalid("plugin_sandbox.timeout_ms", "must be at least 1"));
+        }
+        if self.plugin_sandbox.max_output_bytes == 0 {
+            problems.push(invalid(
+                "plugin_sandbox.max_output_bytes",
+                "must be at least 1",
+            ));
+        }
+        problems
+    }
+}
+
+fn reject(problems: Vec<SettingError>) -> AppResult<()> {
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::InvalidSettings(problems))
+    }
+}
+
+fn invalid(field: &str, message: impl Into<String>) -> SettingError {
+    SettingError {
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+fn check_http_url(field: &str, url: &str) -> Option<SettingError> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => None,
+        Ok(_) => Some(invalid(
+            field,
+            format!("'{}' must be an http or https URL", url),
+        )),
+        Err(e) => Some(invalid(
+            field,
+            format!("'{}' is not a valid URL ({})", url, e),
+        )),
+    }
+}
+
+/// Merges `patch` into `target`: objects recursively, anything else by
+/// replacement
+pub(crate) fn merge_json(target: &mut serde_json::Value, patch: serde_json::Value) {
+    match (target, patch) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                match target.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field_of<T: std::fmt::Debug>(result: AppResult<T>) -> String {
+        match result {
+            Err(AppError::InvalidConfig { field, .. }) => field,
+            other => panic!("expected InvalidConfig, got {:?}", other),
+        }
+    }
+
+    fn fields_of<T: std::fmt::Debug>(result: AppResult<T>) -> Vec<String> {
+        match result {
+            Err(AppError::InvalidSettings(problems)) => {
+                problems.into_iter().map(|p| p.field).collect()
+            }
+            other => panic!("expected InvalidSettings, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_merge_preserves_unspecified_fields() {
+        let mut current = AppConfig::default();
+        current.ai.model = "gpt-4o".to_string();
+        current.clipboard.history_limit = 200;
+
+        let merged = current
+            .merge(json!({
+                "indexer": { "result_limit": 20 },
+                "appearance": { "theme": "dark" },
+            }))
+            .unwrap();
+
+        assert_eq!(merged.indexer.result_limit, 20);
+        assert_eq!(merged.appearance.theme, "dark");
+        assert_eq!(
+            merged.appearance.accent_color,
+            current.appearance.accent_color
+        );
+        assert_eq!(
+            merged.indexer.search_exclude,
+            current.indexer.search_exclude
+        );
+        assert_eq!(merged.ai.model, "gpt-4o");
+        assert_eq!(merged.clipboard.history_limit, 200);
+        assert!(merged.validate().is_ok());
+    }
+
+    #[test]
+    fn test_merge_rejects_wrong_types_by_field() {
+        let current = AppConfig::default();
+        assert_eq!(
+            field_of(current.merge(json!({ "indexer": { "result_limit": -1 } }))),
+            "indexer.result_limit"
+        );
+        assert_eq!(
+            field_of(current.merge(json!({ "clipboard": { "enabled": "yes" } }))),
+            "clipboard.enabled"
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_values() {
+        let default = AppConfig::default();
+        assert!(default.validate().is_ok());
+
+        let cases = [
+            (
+                json!({ "indexer": { "result_limit": 0 } }),
+                "indexer.result_limit",
+            ),
+            (
+                json!({ "indexer": { "result_limit": 5000 } }),
+                "indexer.result_limit",
+            ),
+            (
+                json!({ "appearance": { "transparency": 1.5 } }),
+                "appearance.transparency",
+            ),
+            (
+                json!({ "clipboard": { "paste_restore_delay_ms": 0 } }),
+                "clipboard.paste_restore_delay_ms",
+            ),
+            (
+                json!({ "clipboard": { "sensitive_retention_minutes": 0 } }),
+                "clipboard.sensitive_retention_minutes",
+            ),
+            (
+                json!({ "clipboard": { "sync_folder": "shared/clipboard" } }),
+                "clipboard.sync_folder",
+            ),
+            (
+                json!({ "quick_note": { "directory": "notes" } }),
+                "quick_note.directory",
+            ),
+            (
+                json!({ "screenshot": { "quality": 0 } }),
+                "screenshot.quality",
+            ),
+            (
+                json!({ "screenshot": { "format": "gif" } }),
+                "screenshot.format",
+            ),
+            (
+                json!({ "screenshot": { "file_name_template": " " } }),
+                "screenshot.file_name_template",
+            ),
+            (
+                json!({ "screenshot": { "hide_windows_delay_ms": 5000 } }),
+                "screenshot.hide_windows_delay_ms",
+            ),
+            (
+                json!({ "autohide": { "show_blur_grace_ms": 60_000 } }),
+                "autohide.show_blur_grace_ms",
+            ),
+            (
+                json!({ "autohide": { "capture_shortcut_debounce_ms": 5000 } }),
+                "autohide.capture_shortcut_debounce_ms",
+            ),
+            (
+                json!({ "shortcuts": { "main": "Ctrl+" } }),
+                "shortcuts.main",
+            ),
+            (
+                json!({ "shortcuts": { "clipboard": "Hyper+Space" } }),
+                "shortcuts.clipboard",
+            ),
+            (json!({ "ai": { "api_url": "not a url" } }), "ai.api_url"),
+            (
+                json!({ "calculator": { "currency_api_url": "ftp://rates.example" } }),
+                "calculator.currency_api_url",
+            ),
+            (
+                json!({ "dictionary": { "api_url": "https://dict.example/en" } }),
+                "dictionary.api_url",
+            ),
+            (
+                json!({ "dictionary": { "api_url": "ftp://dict.example/{word}" } }),
+                "dictionary.api_url",
+            ),
+        ];
+        for (patch, field) in cases {
+            let config = default.merge(patch).unwrap();
+            assert_eq!(fields_of(config.validate()), [field]);
+        }
+    }
+
+    #[test]
+    fn test_validate_names_every_bad_field() {
+        let config = AppConfig::default()
+            .merge(json!({ "screenshot": { "quality": 0 }, "ai": { "max_tokens": 0 } }))
+            .unwrap();
+        assert_eq!(
+            fields_of(config.validate()),
+            ["screenshot.quality", "ai.max_tokens"]
+        );
+    }
+
+    #[test]
+    fn test_stored_bad_value_does_not_block_other_updates() {
+        let current = AppConfig::default()
+            .merge(json!({ "screenshot": { "quality": 0 } }))
+            .unwrap();
+
+        let unrelated = current
+            .merge(json!({ "ai": { "temperature": 1.0 } }))
+            .unwrap();
+        assert!(unrelated.validate_update(&current).is_ok());
+
+        let worse = current.merge(json!({ "ai": { "max_tokens": 0 } })).unwrap();
+        assert_eq!(
+            fields_of(worse.validate_update(&current)),
+            ["ai.max_tokens"]
+        );
     }
 }
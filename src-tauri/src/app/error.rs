@@ -23,6 +23,15 @@ pub enum AppError {
     #[error("Configuration error: {0}")]
     Config(String),
 
+    /// A setting does not have the type the app expects; `field` is its
+    /// path, e.g. `indexer.result_limit`
+    #[error("Invalid setting {field}: {message}")]
+    InvalidConfig { field: String, message: String },
+
+    /// Every setting that failed validation, not just the first
+    #[error("Invalid settings: {}", join(.0))]
+    InvalidSettings(Vec<SettingError>),
+
     #[error("Parser error: {0}")]
     Parser(String),
 
@@ -41,16 +50,55 @@ pub enum AppError {
     #[error("API error: {0}")]
     Api(String),
 
+    /// A remote API answered with an error status
+    #[error("{service} API error ({status}): {message}")]
+    ApiStatus {
+        service: String,
+        status: u16,
+        message: String,
+    },
+
     #[error("Parse error: {0}")]
     Parse(String),
 
+    /// The command was given nothing it can act on, e.g. an empty note
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// The user declined an OS prompt (e.g. UAC) or a newer request took
+    /// over; not a failure
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
     #[error("Not found: {0}")]
     NotFound(String),
 
+    /// The OS refused, e.g. acting on an elevated process
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
 
+/// A setting with a value the app cannot use; `field` is its path, e.g.
+/// `indexer.result_limit`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingError {
+    pub field: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SettingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+fn join(errors: &[SettingError]) -> String {
+    errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
 impl From<tauri_plugin_clipboard_manager::Error> for AppError {
     fn from(err: tauri_plugin_clipboard_manager::Error) -> Self {
         AppError::Clipboard(err.to_string())
@@ -63,6 +111,23 @@ impl From<tauri_plugin_shell::Error> for AppError {
     }
 }
 
+impl From<crate::core::indexer::IndexerError> for AppError {
+    fn from(err: crate::core::indexer::IndexerError) -> Self {
+        use crate::core::indexer::IndexerError;
+        match err {
+            IndexerError::Io(e) => AppError::Io(e),
+            IndexerError::PathNotFound(_) | IndexerError::NotARoot(_) => AppError::NotFound(err.to_string()),
+            IndexerError::Watch(_) | IndexerError::Metadata { .. } | IndexerError::Snapshot(_) => {
+                AppError::Unknown(err.to_string())
+            }
+            IndexerError::AlreadyIndexed(_)
+            | IndexerError::InsideRoot { .. }
+            | IndexerError::NotADirectory(_)
+            | IndexerError::Unresolvable { .. } => AppError::Config(err.to_string()),
+        }
+    }
+}
+
 impl serde::Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
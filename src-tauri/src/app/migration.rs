@@ -0,0 +1,317 @@
+//! Upgrades saved settings from older config shapes.
+//!
+//! Every config file carries a `version`; files without one predate
+//! versioning and count as version 1. On load, [`migrate`] runs one step per
+//! version until the file has the current shape, and the file as it was is
+//! kept next to it as a backup.
+
+use super::config::{merge_json, AppConfig, CONFIG_VERSION};
+use super::error::{AppError, AppResult};
+use crate::core::parser::default_web_engines;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Step `i` upgrades a version `i + 1` config to version `i + 2`
+const MIGRATIONS: [fn(&mut Value); (CONFIG_VERSION - 1) as usize] = [v1_to_v2, v2_to_v3];
+
+/// Version 1 files could leave out sections and fields that had no serde
+/// default, which made them fail to load; fill those in from the defaults
+fn v1_to_v2(config: &mut Value) {
+    let mut upgraded = serde_json::to_value(AppConfig::default()).unwrap_or_default();
+    merge_json(&mut upgraded, config.take());
+    *config = upgraded;
+}
+
+/// Web search keywords used to be built in; version 2 files only list the
+/// engines the settings page showed then. Add the default engines whose
+/// keyword is missing, so those keywords keep working.
+fn v2_to_v3(config: &mut Value) {
+    let Some(engines) = config
+        .get_mut("web_search")
+        .and_then(|web_search| web_search.get_mut("engines"))
+        .and_then(Value::as_array_mut)
+    else {
+        return;
+    };
+    let has_keyword = |engines: &[Value], keyword: &str| {
+        engines.iter().any(|engine| {
+            engine
+                .get("keyword")
+                .and_then(Value::as_str)
+                .is_some_and(|k| k.trim().eq_ignore_ascii_case(keyword))
+        })
+    };
+    for engine in default_web_engines() {
+        if !has_keyword(&engines[..], &engine.keyword) {
+            if let Ok(engine) = serde_json::to_value(engine) {
+                engines.push(engine);
+            }
+        }
+    }
+}
+
+/// Version recorded in a config file
+fn version_of(config: &Value) -> AppResult<u32> {
+    match config.get("version") {
+        None | Some(Value::Null) => Ok(1),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|&v| v >= 1)
+            .ok_or_else(|| AppError::Config(format!("Invalid config version {}", version))),
+    }
+}
+
+/// Upgrades `config` in place to [`CONFIG_VERSION`] and returns the
+/// version it had. A config from a newer app version is an error.
+pub fn migrate(config: &mut Value) -> AppResult<u32> {
+    if !config.is_object() {
+        return Err(AppError::Config("Config must be an object".to_string()));
+    }
+    let from = version_of(config)?;
+    if from > CONFIG_VERSION {
+        return Err(AppError::Config(format!(
+            "Config version {} is newer than this app supports ({})",
+            from, CONFIG_VERSION
+        )));
+    }
+    for (i, step) in MIGRATIONS.iter().enumerate().skip(from as usize - 1) {
+        step(config);
+        config["version"] = Value::from(i as u32 + 2);
+    }
+    Ok(from)
+}
+
+/// Migrates and deserializes a config, e.g. one being imported
+pub fn parse_config(mut config: Value) -> AppResult<AppConfig> {
+    migrate(&mut config)?;
+    Ok(serde_json::from_value(config)?)
+}
+
+/// Reads and parses the config file at `path` without rewriting it.
+/// `Ok(false)` when there is no file yet.
+pub fn check_config_file(path: &Path) -> AppResult<bool> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    parse_config(serde_json::from_str(&content)?)?.validate()?;
+    Ok(true)
+}
+
+/// Loads the config file at `path`, or defaults when there is none.
+///
+/// An older file is migrated and rewritten; the original is kept as
+/// `<file>.v<version>.bak`. A file that is unreadable or from a newer app
+/// version is left alone and defaults are used, so the launcher still starts.
+pub fn load_config_file(path: &Path) -> AppConfig {
+    match try_load(path) {
+        Ok(Some(config)) => config,
+        Ok(None) => AppConfig::default(),
+        Err(e) => {
+            tracing::warn!("Using default settings, could not load {:?}: {}", path, e);
+            AppConfig::default()
+        }
+    }
+}
+
+fn try_load(path: &Path) -> AppResult<Option<AppConfig>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut value: Value = serde_json::from_str(&content)?;
+    let from = migrate(&mut value)?;
+    let config: AppConfig = serde_json::from_value(value)?;
+
+    if from < CONFIG_VERSION {
+        let backup = backup_path(path, from);
+        std::fs::write(&backup, &content)?;
+        save_config_file(path, &config)?;
+        tracing::info!(
+            "Migrated settings from version {} to {} (backup at {:?})",
+            from,
+            CONFIG_VERSION,
+            backup
+        );
+    }
+    Ok(Some(config))
+}
+
+/// Writes `config` to `path` through a temporary file, so a crash mid-write
+/// cannot leave a truncated config behind. A file there that could not be
+/// loaded (corrupt, or from a newer app version) is first copied to
+/// `<file>.invalid.bak`, so saving over the defaults does not lose it.
+pub fn save_config_file(path: &Path, config: &AppConfig) -> AppResult<()> {
+    if let Ok(content) = std::fs::read_to_string(path) {
+        let loads = serde_json::from_str(&content).map_err(AppError::from).and_then(parse_config);
+        if let Err(e) = loads {
+            let backup = invalid_backup_path(path);
+            std::fs::write(&backup, &content)?;
+            tracing::warn!("Kept settings that could not be loaded ({}) at {:?}", e, backup);
+        }
+    }
+
+    let json = serde_json::to_string_pretty(config)?;
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, json)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+fn backup_path(path: &Path, version: u32) -> PathBuf {
+    path.with_file_name(format!("{}.v{}.bak", file_name(path), version))
+}
+
+fn invalid_backup_path(path: &Path) -> PathBuf {
+    path.with_file_name(format!("{}.invalid.bak", file_name(path)))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "config.json".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Settings as saved before versioning: no `version`, sections added
+    /// since are missing, and some fields are left out
+    fn v1_config() -> Value {
+        json!({
+            "general": { "language": "zh", "auto_start": true, "check_updates": false },
+            "shortcuts": {
+                "main": "Alt+Space",
+                "clipboard": "CommandOrControl+Shift+V",
+                "screenshot": "CommandOrControl+Shift+S",
+                "ai_chat": "CommandOrControl+Shift+A"
+            },
+            "clipboard": {
+                "enabled": true,
+                "history_limit": 300,
+                "retention_days": 7,
+                "filter_sensitive": true,
+                "exclude_apps": ["KeePass.exe"]
+            },
+            "ai": { "provider": "ollama", "api_key": "", "api_url": "http://localhost:11434", "model": "llama3" },
+            "web_search": {
+                "default_engine": "gg",
+                "engines": [{ "name": "Google", "keyword": "gg", "url": "https://www.google.com/search?q={query}", "icon": null }]
+            }
+        })
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("omnibox_config_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_v1_config_upgrades_with_user_values() {
+        let config = parse_config(v1_config()).unwrap();
+
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.general.language, "zh");
+        assert!(config.general.auto_start);
+        assert_eq!(config.shortcuts.main, "Alt+Space");
+        assert_eq!(config.clipboard.history_limit, 300);
+        assert_eq!(config.clipboard.exclude_apps, vec!["KeePass.exe"]);
+        assert_eq!(config.ai.provider, "ollama");
+        assert_eq!(config.ai.model, "llama3");
+        assert_eq!(config.web_search.engines[0].url_template, "https://www.google.com/search?q={query}");
+
+        // Missing parts come from the defaults
+        let defaults = AppConfig::default();
+        assert_eq!(config.ai.temperature, defaults.ai.temperature);
+        assert_eq!(config.appearance.theme, defaults.appearance.theme);
+        assert_eq!(config.indexer.result_limit, defaults.indexer.result_limit);
+        assert_eq!(config.clipboard.max_total_bytes, defaults.clipboard.max_total_bytes);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_v2_config_gets_the_missing_default_engines() {
+        let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+        value["version"] = json!(2);
+        value["web_search"]["engines"] = json!([
+            { "name": "My Google", "keyword": "GG", "url": "https://www.google.de/search?q={query}" },
+            { "name": "Baidu", "keyword": "bd", "url": "https://www.baidu.com/s?wd={query}" },
+            { "name": "GitHub", "keyword": "gh", "url": "https://github.com/search?q={query}" }
+        ]);
+        assert_eq!(migrate(&mut value).unwrap(), 2);
+        let config: AppConfig = serde_json::from_value(value).unwrap();
+
+        let keywords: Vec<&str> = config.web_search.engines.iter().map(|e| e.keyword.as_str()).collect();
+        assert_eq!(keywords, ["GG", "bd", "gh", "bi", "ddg", "so", "yt", "tw", "npm", "crate"]);
+        assert_eq!(config.web_search.engines[0].url_template, "https://www.google.de/search?q={query}");
+    }
+
+    #[test]
+    fn test_current_config_is_unchanged() {
+        let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+        let before = value.clone();
+        assert_eq!(migrate(&mut value).unwrap(), CONFIG_VERSION);
+        assert_eq!(value, before);
+    }
+
+    #[test]
+    fn test_load_migrates_file_and_keeps_backup() {
+        let dir = temp_dir();
+        let path = dir.join("config.json");
+        let original = v1_config().to_string();
+        std::fs::write(&path, &original).unwrap();
+
+        let config = load_config_file(&path);
+
+        assert_eq!(config.shortcuts.main, "Alt+Space");
+        assert_eq!(std::fs::read_to_string(dir.join("config.json.v1.bak")).unwrap(), original);
+        let saved: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["version"], json!(CONFIG_VERSION));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_future_version_loads_defaults_and_keeps_file() {
+        let dir = temp_dir();
+        let path = dir.join("config.json");
+        let future = json!({ "version": CONFIG_VERSION + 1, "general": { "language": "fr" } }).to_string();
+        std::fs::write(&path, &future).unwrap();
+
+        let config = load_config_file(&path);
+
+        assert_eq!(config.general.language, AppConfig::default().general.language);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), future);
+        assert!(parse_config(serde_json::from_str(&future).unwrap()).is_err());
+
+        // Saving over the defaults keeps the newer file aside
+        save_config_file(&path, &config).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("config.json.invalid.bak")).unwrap(), future);
+        assert!(check_config_file(&path).unwrap());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_keeps_corrupt_file_aside() {
+        let dir = temp_dir();
+        let path = dir.join("config.json");
+        std::fs::write(&path, "{ \"general\": ").unwrap();
+
+        save_config_file(&path, &load_config_file(&path)).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("config.json.invalid.bak")).unwrap(), "{ \"general\": ");
+
+        // A file that loads is replaced without a backup
+        std::fs::remove_file(dir.join("config.json.invalid.bak")).unwrap();
+        save_config_file(&path, &AppConfig::default()).unwrap();
+        assert!(!dir.join("config.json.invalid.bak").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
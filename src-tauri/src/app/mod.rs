@@ -1,3 +1,6 @@
 pub mod config;
 pub mod error;
+pub mod migration;
+pub mod shortcuts;
+pub mod startup;
 pub mod state;
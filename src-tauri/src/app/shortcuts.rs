@@ -0,0 +1,105 @@
+// Global shortcuts bound from the settings. Unlike the fixed launcher
+// shortcuts registered in main, these follow config changes.
+use super::config::ShortcutsConfig;
+use super::state::AppState;
+use crate::commands::{clipboard, system};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Binds the configured shortcuts; called once AppState (and with it the
+/// config) is loaded
+pub fn register(app_handle: &AppHandle, shortcuts: &ShortcutsConfig) {
+    register_quick_note(app_handle, &shortcuts.quick_note);
+    register_clipboard_ring(app_handle, &shortcuts.clipboard_ring);
+}
+
+/// Moves each shortcut that changed between `old` and `new` to its new keys
+pub fn rebind(app_handle: &AppHandle, old: &ShortcutsConfig, new: &ShortcutsConfig) {
+    if old.quick_note != new.quick_note {
+        unregister(app_handle, &old.quick_note);
+        register_quick_note(app_handle, &new.quick_note);
+    }
+    if old.clipboard_ring != new.clipboard_ring {
+        unregister(app_handle, &old.clipboard_ring);
+        register_clipboard_ring(app_handle, &new.clipboard_ring);
+    }
+}
+
+fn unregister(app_handle: &AppHandle, shortcut: &str) {
+    let Some(parsed) = parse(shortcut) else {
+        return;
+    };
+    if let Err(e) = app_handle.global_shortcut().unregister(parsed) {
+        tracing::warn!("Failed to unregister shortcut {}: {}", shortcut, e);
+    }
+}
+
+/// `None` for an unbound (empty) or unparsable shortcut
+fn parse(shortcut: &str) -> Option<Shortcut> {
+    if shortcut.is_empty() {
+        return None;
+    }
+    match shortcut.parse::<Shortcut>() {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            tracing::warn!("Invalid shortcut '{}': {}", shortcut, e);
+            None
+        }
+    }
+}
+
+/// Saves the clipboard text as a quick note
+fn register_quick_note(app_handle: &AppHandle, shortcut: &str) {
+    let Some(parsed) = parse(shortcut) else {
+        return;
+    };
+
+    let result = app_handle.global_shortcut().on_shortcut(parsed, |app, _shortcut, event| {
+        if event.state != ShortcutState::Pressed {
+            return;
+        }
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let Some(state) = app.try_state::<AppState>() else {
+                return;
+            };
+            match system::save_clipboard_note(&state).await {
+                Ok(path) => {
+                    let _ = app.emit("quick-note:saved", path);
+                }
+                Err(e) => tracing::warn!("Quick note failed: {}", e),
+            }
+        });
+    });
+    match result {
+        Ok(()) => tracing::info!("Quick note shortcut registered: {}", shortcut),
+        Err(e) => tracing::warn!("Failed to register quick note shortcut {}: {}", shortcut, e),
+    }
+}
+
+/// Pastes on release, so the shortcut's own keys are not held down during
+/// the simulated paste
+fn register_clipboard_ring(app_handle: &AppHandle, shortcut: &str) {
+    let Some(parsed) = parse(shortcut) else {
+        return;
+    };
+
+    let result = app_handle.global_shortcut().on_shortcut(parsed, |app, _shortcut, event| {
+        if event.state != ShortcutState::Released {
+            return;
+        }
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let Some(state) = app.try_state::<AppState>() else {
+                return;
+            };
+            if let Err(e) = clipboard::clipboard_ring_paste_next(state).await {
+                tracing::warn!("Clipboard ring paste failed: {}", e);
+            }
+        });
+    });
+    match result {
+        Ok(()) => tracing::info!("Clipboard ring shortcut registered: {}", shortcut),
+        Err(e) => tracing::warn!("Failed to register clipboard ring shortcut {}: {}", shortcut, e),
+    }
+}
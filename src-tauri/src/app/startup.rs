@@ -0,0 +1,89 @@
+// Progress of the background AppState initialization, so the launcher can
+// say "starting up" or show the error instead of returning no results
+use super::error::AppError;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Event carrying each new [`StartupStatus`]
+pub const STATUS_EVENT: &str = "app-state-status";
+/// Tries before giving up on a transient failure
+pub const MAX_INIT_ATTEMPTS: u32 = 4;
+/// Wait before the second try; doubled for each later one
+const FIRST_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+static STATUS: Mutex<StartupStatus> = Mutex::new(StartupStatus::Starting { attempt: 1 });
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum StartupStatus {
+    /// `attempt` counts from 1
+    Starting { attempt: u32 },
+    Ready,
+    /// Search and history stay unavailable until the app is restarted
+    Failed { error: String, attempts: u32 },
+}
+
+/// Status as last set
+pub fn current() -> StartupStatus {
+    STATUS
+        .lock()
+        .map(|status| status.clone())
+        .unwrap_or(StartupStatus::Starting { attempt: 1 })
+}
+
+pub fn set(status: StartupStatus) {
+    if let Ok(mut current) = STATUS.lock() {
+        *current = status;
+    }
+}
+
+/// [`set`] and tell the windows
+pub fn publish(app: &AppHandle, status: StartupStatus) {
+    set(status.clone());
+    let _ = app.emit(STATUS_EVENT, status);
+}
+
+/// Failures worth another try, like a database locked by a previous
+/// instance that is still exiting
+pub fn is_transient(error: &AppError) -> bool {
+    matches!(error, AppError::Database(_) | AppError::Sqlx(_) | AppError::Io(_))
+}
+
+/// Wait after failed attempt number `attempt` (counting from 1)
+pub fn retry_delay(attempt: u32) -> Duration {
+    FIRST_RETRY_DELAY * 2u32.saturating_pow(attempt.saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_reflects_init_failure() {
+        set(StartupStatus::Failed {
+            error: "Database error: database is locked".to_string(),
+            attempts: MAX_INIT_ATTEMPTS,
+        });
+        assert_eq!(
+            serde_json::to_value(current()).unwrap(),
+            serde_json::json!({
+                "state": "failed",
+                "error": "Database error: database is locked",
+                "attempts": 4,
+            })
+        );
+
+        set(StartupStatus::Ready);
+        assert_eq!(current(), StartupStatus::Ready);
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        assert_eq!(retry_delay(1), Duration::from_millis(500));
+        assert_eq!(retry_delay(3), Duration::from_secs(2));
+        assert!(is_transient(&AppError::Database("database is locked".to_string())));
+        assert!(!is_transient(&AppError::Unknown("Failed to get app data dir".to_string())));
+    }
+}
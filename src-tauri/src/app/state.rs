@@ -1,39 +1,79 @@
-use super::config::AppConfig;
-use super::error::AppResult;
-use crate::core::clipboard::{ClipboardMonitor, ClipboardStorage, ClipboardWindowManager};
-use crate::core::indexer::{Indexer, ScanConfig};
-use crate::core::plugin::PluginManager;
+use super::config::{AppConfig, ClipboardConfig};
+use super::error::{AppError, AppResult};
+use super::migration;
+use super::shortcuts;
+use crate::core::clipboard::{
+    crypto, ClipboardCipher, ClipboardMonitor, ClipboardSnapshot, ClipboardStorage, ClipboardSync, ClipboardWindowManager,
+    ImportSummary, PruneLimits, RestoreQueue,
+};
+use crate::core::autohide;
+use crate::core::indexer::{resolve_root, ContentOptions, Indexer, IndexerError, ScanConfig, SearchFilter};
+use crate::core::plugin::{PluginManager, SandboxLimits};
+use crate::core::workflow::WorkflowStore;
+use crate::core::query_generation::QueryGeneration;
+use crate::core::search_cache::SearchCache;
+use crate::commands::search::SearchResult;
 use crate::storage::{Database, IconCache};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use tauri::{AppHandle, Manager};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
 #[cfg(windows)]
 use crate::app_indexer::AppIndexer;
 
+/// Settings file in the app data directory
+const CONFIG_FILE: &str = "config.json";
+/// Saved file index in the app data directory
+const INDEX_SNAPSHOT_FILE: &str = "file_index.json";
+/// How often the file index is saved while it changes, so a crash loses
+/// little of what the watcher picked up
+const INDEX_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Saved app list in the app data directory (Windows)
+#[cfg(windows)]
+const APP_CACHE_FILE: &str = "app_cache.json";
+
 /// Global application state
 #[derive(Clone)]
 pub struct AppState {
     app_handle: AppHandle,
     pub config: Arc<RwLock<AppConfig>>,
+    /// Where `update_config` saves the settings
+    config_path: PathBuf,
+    /// Held across each settings change, from reading the old settings to
+    /// applying the new ones, so concurrent changes do not overwrite each other
+    config_edit: Arc<Mutex<()>>,
     pub indexer: Arc<Indexer>,
+    /// Where the file index is saved between launches
+    index_snapshot_path: PathBuf,
+    /// Indexer generation the saved file index holds
+    index_snapshot_generation: Arc<AtomicU64>,
+    /// When the file index was last brought in line with the disk; changes
+    /// the watcher missed are in directories modified since
+    index_synced_at: Arc<RwLock<Option<SystemTime>>>,
     pub db: Arc<Database>,
     pub icon_cache: Arc<IconCache>,
     pub plugin_manager: Arc<RwLock<PluginManager>>,
+    pub workflows: Arc<WorkflowStore>,
+    /// Recent file/app search results
+    pub search_cache: Arc<RwLock<SearchCache<SearchResult>>>,
+    /// Lets a new `search` call supersede the one still running
+    pub search_generation: Arc<QueryGeneration>,
+    /// Clipboard contents to put back after pasting history items
+    pub paste_restore: Arc<RestoreQueue<ClipboardSnapshot>>,
     clipboard_storage: Arc<RwLock<Option<Arc<ClipboardStorage>>>>,
     clipboard_monitor: Arc<RwLock<Option<Arc<ClipboardMonitor>>>>,
     clipboard_window_manager: Arc<RwLock<Option<Arc<ClipboardWindowManager>>>>,
+    /// Watcher of `clipboard.sync_folder`, while one is set
+    clipboard_sync: Arc<RwLock<Option<ClipboardSync>>>,
     #[cfg(windows)]
     pub app_indexer: Arc<AppIndexer>,
 }
 
 impl AppState {
     pub async fn new(app_handle: AppHandle) -> AppResult<Self> {
-        let config = Arc::new(RwLock::new(AppConfig::default()));
-        
-        // Create indexer with default configuration
-        let indexer = Arc::new(Indexer::new(ScanConfig::default()));
-
         // Get app data directory
         let app_data_dir = app_handle
             .path()
@@ -45,6 +85,22 @@ impl AppState {
             std::fs::create_dir_all(&app_data_dir)?;
         }
 
+        // Load saved settings, upgrading older files
+        let config_path = app_data_dir.join(CONFIG_FILE);
+        let app_config = migration::load_config_file(&config_path);
+
+        // Create indexer using the configured scan preset
+        let scan_config = ScanConfig::default().with_preset(app_config.indexer.scan_preset);
+        let indexer = Arc::new(Indexer::new(scan_config));
+        let search_cache = SearchCache::new(
+            app_config.search_cache.capacity,
+            Duration::from_millis(app_config.search_cache.ttl_ms),
+        );
+        let icon_cache_max_bytes = app_config.icon_cache.max_bytes;
+        let sandbox_limits = SandboxLimits::from(&app_config.plugin_sandbox);
+        autohide::apply(&app_config.autohide);
+        let config = Arc::new(RwLock::new(app_config));
+
         // Initialize database
         let db_path = app_data_dir.join("omnibox.db");
         let db = Arc::new(
@@ -60,23 +116,40 @@ impl AppState {
                 .await
                 .map_err(|e| crate::app::error::AppError::Unknown(format!("Failed to initialize icon cache: {}", e)))?
         );
+        icon_cache.set_max_bytes(icon_cache_max_bytes);
+        IconCache::start_eviction(icon_cache.clone());
 
         // Initialize plugin manager
         let plugins_dir = app_data_dir.join("plugins");
-        let plugin_manager = PluginManager::new(plugins_dir);
+        let plugin_manager = PluginManager::with_limits(plugins_dir, sandbox_limits);
         if let Err(e) = plugin_manager.init().await {
             tracing::warn!("Failed to initialize plugin manager: {}", e);
         }
 
+        let workflows = WorkflowStore::new(app_data_dir.join("workflows"));
+
         // Initialize app indexer (Windows only)
         #[cfg(windows)]
         let app_indexer = {
             let indexer = Arc::new(AppIndexer::new());
-            // Initialize synchronously to ensure apps are available on first search
-            tracing::info!("Initializing AppIndexer...");
-            match indexer.init().await {
-                Ok(count) => tracing::info!("AppIndexer initialized with {} apps", count),
-                Err(e) => tracing::error!("Failed to initialize AppIndexer: {}", e),
+            let cache_path = app_data_dir.join(APP_CACHE_FILE);
+            let rescan = |indexer: Arc<AppIndexer>, cache_path: PathBuf| async move {
+                match indexer.init().await {
+                    Ok(count) => tracing::info!("AppIndexer initialized with {} apps", count),
+                    Err(e) => tracing::error!("Failed to initialize AppIndexer: {}", e),
+                }
+                if let Err(e) = indexer.save_cache(&cache_path) {
+                    tracing::warn!("{}", e);
+                }
+            };
+            if let Some(count) = indexer.load_cache(&cache_path) {
+                // Search the cached list right away and rescan in the background
+                tracing::info!("AppIndexer loaded {} cached apps", count);
+                tauri::async_runtime::spawn(rescan(indexer.clone(), cache_path));
+            } else {
+                // Initialize synchronously to ensure apps are available on first search
+                tracing::info!("Initializing AppIndexer...");
+                rescan(indexer.clone(), cache_path).await;
             }
             indexer
         };
@@ -84,13 +157,23 @@ impl AppState {
         Ok(Self {
             app_handle,
             config,
+            config_path,
+            config_edit: Arc::new(Mutex::new(())),
             indexer,
+            index_snapshot_path: app_data_dir.join(INDEX_SNAPSHOT_FILE),
+            index_snapshot_generation: Arc::new(AtomicU64::new(0)),
+            index_synced_at: Arc::new(RwLock::new(None)),
             db,
             icon_cache,
             plugin_manager: Arc::new(RwLock::new(plugin_manager)),
+            workflows: Arc::new(workflows),
+            search_cache: Arc::new(RwLock::new(search_cache)),
+            search_generation: Arc::new(QueryGeneration::new()),
+            paste_restore: Arc::new(RestoreQueue::default()),
             clipboard_storage: Arc::new(RwLock::new(None)),
             clipboard_monitor: Arc::new(RwLock::new(None)),
             clipboard_window_manager: Arc::new(RwLock::new(None)),
+            clipboard_sync: Arc::new(RwLock::new(None)),
             #[cfg(windows)]
             app_indexer,
         })
@@ -100,30 +183,108 @@ impl AppState {
         self.config.read().await.clone()
     }
 
+    /// The settings file `update_config` writes
+    pub fn config_path(&self) -> &std::path::Path {
+        &self.config_path
+    }
+
+    /// Saves the settings, then applies them
     pub async fn update_config(&self, new_config: AppConfig) -> AppResult<()> {
+        let _edit = self.config_edit.lock().await;
+        self.apply_config(new_config).await
+    }
+
+    /// Changes the current settings with `edit`, then saves and applies them
+    /// like `update_config`. No other change lands in between; an error from
+    /// `edit` leaves the settings as they were.
+    pub async fn edit_config<R>(&self, edit: impl FnOnce(&mut AppConfig) -> AppResult<R>) -> AppResult<R> {
+        let _edit = self.config_edit.lock().await;
+        let mut config = self.get_config().await;
+        let result = edit(&mut config)?;
+        self.apply_config(config).await?;
+        Ok(result)
+    }
+
+    async fn apply_config(&self, new_config: AppConfig) -> AppResult<()> {
+        migration::save_config_file(&self.config_path, &new_config)?;
+        let clipboard_config = new_config.clipboard.clone();
+        let cache_config = new_config.search_cache.clone();
+        self.icon_cache.set_max_bytes(new_config.icon_cache.max_bytes);
+        autohide::apply(&new_config.autohide);
         let mut config = self.config.write().await;
-        *config = new_config;
+        let old_shortcuts = std::mem::replace(&mut *config, new_config).shortcuts;
+        shortcuts::rebind(&self.app_handle, &old_shortcuts, &config.shortcuts);
+        drop(config);
+
+        self.search_cache
+            .write()
+            .await
+            .configure(cache_config.capacity, Duration::from_millis(cache_config.ttl_ms));
+
+        if let Some(monitor) = self.clipboard_monitor.read().await.as_ref() {
+            monitor.set_prune_limits(PruneLimits::from(&clipboard_config)).await;
+            monitor
+                .set_sensitive_options(clipboard_config.mask_sensitive, &clipboard_config.sensitive_apps)
+                .await;
+            self.configure_clipboard_sync(monitor, &clipboard_config).await;
+        }
         Ok(())
     }
     
-    /// Initialize file indexing for common directories
+    /// Initialize file indexing for the configured roots, or common
+    /// directories when none are configured
     pub async fn initialize_indexing(&self) -> AppResult<()> {
-        // Get home directory
-        if let Ok(home) = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
-            let home_path = std::path::Path::new(&home);
-            
-            // Index common directories
-            let dirs_to_index = vec![
-                home_path.join("Documents"),
-                home_path.join("Desktop"),
-                home_path.join("Downloads"),
-            ];
-            
-            for dir in dirs_to_index {
-                if dir.exists() {
-                    let _ = self.indexer.index_directory(&dir).await;
+        *self.index_synced_at.write().await = Some(SystemTime::now());
+        let mut dirs_to_index = self.get_config().await.indexer.index_paths;
+        if dirs_to_index.is_empty() {
+            if let Ok(home) = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
+                let home_path = std::path::Path::new(&home);
+                dirs_to_index = vec![
+                    home_path.join("Documents"),
+                    home_path.join("Desktop"),
+                    home_path.join("Downloads"),
+                ];
+            }
+        }
+
+        // Start from the index saved by the last run, if any; changes made
+        // to the disk since are found by the time it was saved
+        let saved_at = std::fs::metadata(&self.index_snapshot_path).and_then(|m| m.modified());
+        let restored = match self.indexer.load_snapshot(&self.index_snapshot_path).await {
+            Ok(count) => {
+                tracing::info!("Restored {} indexed files", count);
+                saved_at.ok()
+            }
+            Err(IndexerError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                tracing::warn!("Rebuilding the file index: {}", e);
+                None
+            }
+        };
+
+        let wanted: Vec<PathBuf> = dirs_to_index
+            .iter()
+            .filter(|dir| dir.exists())
+            .filter_map(|dir| resolve_root(dir).ok())
+            .collect();
+        if let Some(saved_at) = restored {
+            // Roots dropped from the settings since the snapshot was saved
+            for root in self.indexer.roots().await {
+                if !wanted.contains(&root) {
+                    if let Err(e) = self.indexer.remove_root(&root).await {
+                        tracing::warn!("Failed to drop stale root {:?}: {}", root, e);
+                    }
                 }
             }
+            let (dropped, added) = self.indexer.reconcile(saved_at).await;
+            tracing::info!("Reconciled file index: {} dropped, {} added", dropped, added);
+        }
+
+        let roots = self.indexer.roots().await;
+        for dir in wanted.into_iter().filter(|dir| !roots.contains(dir)) {
+            if let Err(e) = self.indexer.add_root(&dir, false).await {
+                tracing::warn!("Failed to index {:?}: {}", dir, e);
+            }
         }
         
         // Index macOS Applications with display names (for Chinese search support)
@@ -144,8 +305,7 @@ impl AppState {
                 };
                 
                 if let Err(e) = self.indexer.add_file_with_display_name(&app.path, display_name).await {
-                    // Ignore "already indexed" errors
-                    if !e.contains("already indexed") {
+                    if !matches!(e, crate::core::indexer::IndexerError::AlreadyIndexed(_)) {
                         tracing::debug!("Failed to index app {:?}: {}", app.path, e);
                     }
                 } else {
@@ -156,10 +316,91 @@ impl AppState {
             tracing::info!("Indexed {} applications with display names", indexed_count);
         }
         
+        if !self.get_config().await.full_text.directories.is_empty() {
+            self.index_full_text().await;
+        }
+
         tracing::info!("Indexing completed, total files: {}", self.indexer.file_count().await);
+        self.save_index_snapshot().await;
+
+        let state = self.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(INDEX_SNAPSHOT_INTERVAL).await;
+                state.save_index_snapshot_if_changed().await;
+            }
+        });
         Ok(())
     }
     
+    /// Store the indexer's current roots in `indexer.index_paths`
+    pub async fn save_index_roots(&self) -> AppResult<()> {
+        // Like `edit_config`, but the roots are read under the lock too, so
+        // of two root changes saving at once the later one saves both
+        {
+            let _edit = self.config_edit.lock().await;
+            let mut config = self.get_config().await;
+            config.indexer.index_paths = self.indexer.roots().await;
+            self.apply_config(config).await?;
+        }
+        self.save_index_snapshot().await;
+        Ok(())
+    }
+
+    /// Save the file index for the next launch. Failures are only logged;
+    /// the next launch then rescans.
+    pub async fn save_index_snapshot(&self) {
+        let generation = self.indexer.generation();
+        match self.indexer.save_snapshot(&self.index_snapshot_path).await {
+            Ok(()) => self.index_snapshot_generation.store(generation, Ordering::SeqCst),
+            Err(e) => tracing::warn!("Failed to save the file index: {}", e),
+        }
+    }
+
+    /// Pick up files added or deleted that the watcher missed, reading only
+    /// directories modified since the index was last in line with the disk.
+    /// Returns (dropped, added).
+    pub async fn refresh_index(&self) -> (usize, usize) {
+        let now = SystemTime::now();
+        let since = self.index_synced_at.write().await.replace(now);
+        let changes = self.indexer.reconcile(since.unwrap_or(SystemTime::UNIX_EPOCH)).await;
+        self.save_index_snapshot().await;
+        changes
+    }
+
+    /// [`Self::save_index_snapshot`] unless the index is unchanged since the
+    /// last save. Before the first save (indexing still running) nothing is
+    /// written, so a half-built index never replaces the saved one.
+    pub async fn save_index_snapshot_if_changed(&self) {
+        let saved = self.index_snapshot_generation.load(Ordering::SeqCst);
+        if saved != 0 && saved != self.indexer.generation() {
+            self.save_index_snapshot().await;
+        }
+    }
+
+    /// Rebuild the full-text index from `full_text.directories`
+    pub async fn index_full_text(&self) -> usize {
+        let app_config = self.get_config().await;
+        let config = app_config.full_text;
+        let excluded: Vec<String> = app_config
+            .indexer
+            .exclude_paths
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        let options = ContentOptions {
+            extensions: config.extensions,
+            max_file_size: config.max_file_size,
+            filter: SearchFilter {
+                exclude_paths: (!excluded.is_empty()).then_some(excluded),
+                ..Default::default()
+            },
+        };
+        let count = self.indexer.index_contents(&config.directories, &options).await;
+        tracing::info!("Full-text index built: {} files", count);
+        count
+    }
+
     /// Index applications from a directory (macOS .app bundles) - legacy method
     #[cfg(target_os = "macos")]
     async fn index_applications(&self, dir: &std::path::Path) -> AppResult<()> {
@@ -176,8 +417,7 @@ impl AppState {
                 if ext == "app" {
                     // Add the .app bundle itself to the index
                     if let Err(e) = self.indexer.add_file(&path).await {
-                        // Ignore "already indexed" errors
-                        if !e.contains("already indexed") {
+                        if !matches!(e, crate::core::indexer::IndexerError::AlreadyIndexed(_)) {
                             tracing::debug!("Failed to index app {:?}: {}", path, e);
                         }
                     } else {
@@ -195,13 +435,45 @@ impl AppState {
     pub async fn clipboard_storage(&self) -> AppResult<Arc<ClipboardStorage>> {
         let mut storage = self.clipboard_storage.write().await;
         if storage.is_none() {
-            let pool = self.db.pool().clone();
-            let clipboard_storage = Arc::new(ClipboardStorage::new(pool).await?);
+            let clipboard_storage = Arc::new(self.open_clipboard_storage().await?);
             *storage = Some(clipboard_storage.clone());
         }
         Ok(storage.as_ref().unwrap().clone())
     }
 
+    /// Open clipboard storage with the keychain key when one exists, and
+    /// bring existing rows in line with `clipboard.encrypt`
+    async fn open_clipboard_storage(&self) -> AppResult<ClipboardStorage> {
+        let pool = self.db.pool().clone();
+        let encrypt = self.get_config().await.clipboard.encrypt;
+        let data_dir = self
+            .app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::Unknown(format!("Failed to get app data dir: {}", e)))?;
+
+        // A key is only created when encrypting, but an existing one is
+        // always loaded so rows sealed earlier can still be read
+        let key = tokio::task::spawn_blocking(move || crypto::load_key(&data_dir, encrypt))
+            .await
+            .map_err(|e| AppError::Unknown(e.to_string()))?;
+        let key = match key {
+            Ok(key) => key,
+            Err(e) if !encrypt => {
+                tracing::warn!("Clipboard key unavailable: {}", e);
+                None
+            }
+            Err(e) => return Err(e),
+        };
+
+        let Some(key) = key else {
+            return ClipboardStorage::new(pool).await;
+        };
+        let storage = ClipboardStorage::with_encryption(pool, ClipboardCipher::new(&key), encrypt).await?;
+        storage.migrate_encryption().await?;
+        Ok(storage)
+    }
+
     /// Get or create clipboard monitor
     pub async fn clipboard_monitor(&self) -> AppResult<Arc<ClipboardMonitor>> {
         let mut monitor = self.clipboard_monitor.write().await;
@@ -211,12 +483,61 @@ impl AppState {
             // Set storage for the monitor
             let storage = self.clipboard_storage().await?;
             clipboard_monitor.set_storage(storage).await;
+            let clipboard_config = self.get_config().await.clipboard;
+            clipboard_monitor
+                .set_prune_limits(PruneLimits::from(&clipboard_config))
+                .await;
+            clipboard_monitor
+                .set_sensitive_options(clipboard_config.mask_sensitive, &clipboard_config.sensitive_apps)
+                .await;
+            self.configure_clipboard_sync(&clipboard_monitor, &clipboard_config)
+                .await;
             
             *monitor = Some(clipboard_monitor.clone());
         }
         Ok(monitor.as_ref().unwrap().clone())
     }
 
+    /// Mirror new items to `clipboard.sync_folder` and import what other
+    /// devices put there, replacing the previous sync folder. No folder, or
+    /// encrypted history, turns syncing off.
+    async fn configure_clipboard_sync(&self, monitor: &ClipboardMonitor, config: &ClipboardConfig) {
+        if config.encrypt && config.sync_folder.is_some() {
+            tracing::debug!("Clipboard sync is off while history is encrypted");
+        }
+        let folder = config.sync_folder.clone().filter(|_| !config.encrypt);
+        let mut sync = self.clipboard_sync.write().await;
+        if sync.as_ref().map(|current| current.folder()) == folder.as_deref() {
+            return;
+        }
+        // Stop watching the old folder before the new one is imported
+        *sync = None;
+        monitor.set_sync_folder(None).await;
+        let Some(folder) = folder else {
+            return;
+        };
+
+        let storage = match self.clipboard_storage().await {
+            Ok(storage) => storage,
+            Err(e) => {
+                tracing::warn!("Clipboard sync unavailable: {}", e);
+                return;
+            }
+        };
+        let app_handle = self.app_handle.clone();
+        let on_import = move |summary: ImportSummary| {
+            tracing::debug!("Imported {} synced clipboard items", summary.imported);
+            let _ = tauri::Emitter::emit(&app_handle, "clipboard-changed", ());
+        };
+        match ClipboardSync::start(folder.clone(), storage, on_import).await {
+            Ok(started) => {
+                *sync = Some(started);
+                monitor.set_sync_folder(Some(folder)).await;
+            }
+            Err(e) => tracing::warn!("Failed to start clipboard sync in {:?}: {}", folder, e),
+        }
+    }
+
     /// Get or create clipboard window manager
     pub async fn clipboard_window_manager(&self) -> AppResult<Arc<ClipboardWindowManager>> {
         let mut manager = self.clipboard_window_manager.write().await;
@@ -227,6 +548,15 @@ impl AppState {
         Ok(manager.as_ref().unwrap().clone())
     }
 
+    /// Changes whenever any search index changes; cached results from an
+    /// older generation are stale
+    pub fn index_generation(&self) -> u64 {
+        let generation = self.indexer.generation();
+        #[cfg(windows)]
+        let generation = generation.wrapping_add(self.app_indexer.generation());
+        generation
+    }
+
     /// Get app handle for Tauri operations
     pub fn app_handle(&self) -> &AppHandle {
         &self.app_handle
@@ -6,6 +6,7 @@
 //! - Abbreviation matching (wx -> 微信)
 
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::env;
 
@@ -13,15 +14,17 @@ use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use parking_lot::RwLock;
 use pinyin::ToPinyin;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
+use crate::utils::text::fold_for_search;
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Data Structures
 // ═══════════════════════════════════════════════════════════════════════════════
 
 /// Represents an indexed application entry
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AppEntry {
     /// Display name (e.g., "微信", "Google Chrome")
     pub name: String,
@@ -57,6 +60,16 @@ pub enum MatchType {
     PinyinInitials,
 }
 
+/// Format version of the app list cache; caches of other versions are ignored
+pub const APP_CACHE_VERSION: u32 = 1;
+
+/// The scanned app list as saved between launches
+#[derive(Serialize, Deserialize)]
+struct AppCache {
+    version: u32,
+    entries: Vec<AppEntry>,
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // App Indexer
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -66,6 +79,8 @@ pub struct AppIndexer {
     entries: Arc<RwLock<Vec<AppEntry>>>,
     /// Fuzzy matcher
     matcher: SkimMatcherV2,
+    /// Bumped whenever the app list is rescanned
+    generation: AtomicU64,
 }
 
 impl Default for AppIndexer {
@@ -79,6 +94,7 @@ impl AppIndexer {
         Self {
             entries: Arc::new(RwLock::new(Vec::new())),
             matcher: SkimMatcherV2::default().smart_case(),
+            generation: AtomicU64::new(0),
         }
     }
 
@@ -92,6 +108,7 @@ impl AppIndexer {
         
         let count = entries.len();
         *self.entries.write() = entries;
+        self.generation.fetch_add(1, Ordering::SeqCst);
         
         tracing::info!("AppIndexer initialized with {} apps", count);
         Ok(count)
@@ -244,7 +261,10 @@ impl AppIndexer {
             return Vec::new();
         }
         
-        let query_lower = query.to_lowercase();
+        // Names and queries are compared without case or diacritics
+        // ("cafe" finds "Café"); pinyin is ASCII already
+        let query_exact = query.to_lowercase();
+        let query_lower = fold_for_search(query);
         let entries = self.entries.read();
         let mut results: Vec<AppSearchResult> = Vec::new();
         
@@ -252,11 +272,14 @@ impl AppIndexer {
             let mut best_score: i64 = 0;
             let mut best_match_type = MatchType::FuzzyName;
             
-            // 1. Exact name match (highest priority)
-            let name_lower = entry.name.to_lowercase();
-            if name_lower == query_lower {
+            // 1. Exact name match (highest priority), spelled as typed first
+            let name_lower = fold_for_search(&entry.name);
+            if entry.name.to_lowercase() == query_exact {
                 best_score = 10000;
                 best_match_type = MatchType::ExactName;
+            } else if name_lower == query_lower {
+                best_score = 9500;
+                best_match_type = MatchType::ExactName;
             } else if name_lower.starts_with(&query_lower) {
                 best_score = 8000 + (100 - name_lower.len() as i64).max(0);
                 best_match_type = MatchType::ExactName;
@@ -347,10 +370,50 @@ impl AppIndexer {
         self.entries.read().len()
     }
 
+    /// Counter that changes whenever the app list changes
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
     /// Refresh the index
     pub async fn refresh(&self) -> Result<usize, String> {
         self.init().await
     }
+
+    /// Load the app list saved by `save_cache`, so search works before the
+    /// first scan finishes. Returns `None` when there is no usable cache.
+    pub fn load_cache(&self, path: &Path) -> Option<usize> {
+        let data = std::fs::read(path).ok()?;
+        let cache: AppCache = match serde_json::from_slice(&data) {
+            Ok(cache) => cache,
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable app cache {:?}: {}", path, e);
+                return None;
+            }
+        };
+        if cache.version != APP_CACHE_VERSION {
+            tracing::info!("Ignoring app cache of version {}", cache.version);
+            return None;
+        }
+
+        let count = cache.entries.len();
+        *self.entries.write() = cache.entries;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        Some(count)
+    }
+
+    /// Save the current app list for the next launch
+    pub fn save_cache(&self, path: &Path) -> Result<(), String> {
+        let cache = AppCache {
+            version: APP_CACHE_VERSION,
+            entries: self.entries.read().clone(),
+        };
+        let data = serde_json::to_vec(&cache).map_err(|e| format!("Failed to serialize app cache: {}", e))?;
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, data)
+            .and_then(|_| std::fs::rename(&temp, path))
+            .map_err(|e| format!("Failed to write app cache: {}", e))
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -372,6 +435,71 @@ mod tests {
         assert_eq!(initials, "qqyy");
     }
 
+    #[test]
+    fn test_cache_round_trip() {
+        let path = std::env::temp_dir().join(format!("omnibox_apps_{}.json", uuid::Uuid::new_v4()));
+        let (pinyin_full, pinyin_initials) = AppIndexer::to_pinyin("微信");
+        let entry = AppEntry {
+            name: "微信".to_string(),
+            pinyin_full,
+            pinyin_initials,
+            path: "C:\\ProgramData\\Start Menu\\微信.lnk".to_string(),
+            extension: "lnk".to_string(),
+            is_start_menu: true,
+        };
+        let indexer = AppIndexer::new();
+        *indexer.entries.write() = vec![entry.clone()];
+        indexer.save_cache(&path).unwrap();
+
+        let restored = AppIndexer::new();
+        assert_eq!(restored.load_cache(&path), Some(1));
+        assert_eq!(*restored.entries.read(), vec![entry]);
+        assert_eq!(restored.search("wx", 5)[0].entry.name, "微信");
+
+        std::fs::write(&path, r#"{"version":0,"entries":[]}"#).unwrap();
+        assert_eq!(AppIndexer::new().load_cache(&path), None);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    fn indexer_with(names: &[&str]) -> AppIndexer {
+        let indexer = AppIndexer::new();
+        *indexer.entries.write() = names
+            .iter()
+            .map(|name| {
+                let (pinyin_full, pinyin_initials) = AppIndexer::to_pinyin(name);
+                AppEntry {
+                    name: name.to_string(),
+                    pinyin_full,
+                    pinyin_initials,
+                    path: format!("C:\\Apps\\{}.lnk", name),
+                    extension: "lnk".to_string(),
+                    is_start_menu: true,
+                }
+            })
+            .collect();
+        indexer
+    }
+
+    #[test]
+    fn test_accent_insensitive_names() {
+        let indexer = indexer_with(&["Café Manager", "Mañana", "Straße Maps", "Cafe", "Notepad"]);
+        let names = |query: &str| -> Vec<String> {
+            indexer.search(query, 10).into_iter().map(|r| r.entry.name).collect()
+        };
+
+        assert_eq!(names("mañana")[0], "Mañana");
+        assert_eq!(names("manana")[0], "Mañana");
+        assert_eq!(names("strasse")[0], "Straße Maps");
+        assert!(names("cafe man").contains(&"Café Manager".to_string()));
+        // The display name is kept as is
+        assert_eq!(indexer.search("straße", 1)[0].entry.name, "Straße Maps");
+
+        // The exact spelling ranks first, then a folded exact name
+        assert_eq!(names("cafe")[0], "Cafe");
+        assert_eq!(names("café manager")[0], "Café Manager");
+        assert_eq!(names("café")[0], "Cafe");
+    }
+
     #[test]
     fn test_pinyin_mixed() {
         let (full, initials) = AppIndexer::to_pinyin("Chrome 浏览器");
@@ -73,7 +73,7 @@ pub async fn get_element_rect_at(x: i32, y: i32) -> AppResult<Option<Rect>> {
 }
 
 #[cfg(windows)]
-fn get_element_rect_at_blocking(x: i32, y: i32) -> AppResult<Option<Rect>> {
+pub(crate) fn get_element_rect_at_blocking(x: i32, y: i32) -> AppResult<Option<Rect>> {
     use windows::Win32::Foundation::POINT;
 
     let automation = ensure_uia_context()?;
@@ -116,7 +116,7 @@ fn get_element_rect_at_blocking(x: i32, y: i32) -> AppResult<Option<Rect>> {
 }
 
 #[cfg(not(windows))]
-fn get_element_rect_at_blocking(_x: i32, _y: i32) -> AppResult<Option<Rect>> {
+pub(crate) fn get_element_rect_at_blocking(_x: i32, _y: i32) -> AppResult<Option<Rect>> {
     // Non-Windows stub
     Ok(None)
 }
@@ -196,3 +196,186 @@ pub async fn get_element_rects_batch(points: Vec<(i32, i32)>) -> AppResult<Vec<O
     .await
     .map_err(|e| AppError::Unknown(format!("UIA batch join error: {e}")))
 }
+
+/// A top-level window offered by window switching
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WindowInfo {
+    /// Window handle, passed back to `focus_window`
+    pub hwnd: i64,
+    pub title: String,
+    /// Executable name of the owning process
+    pub process: String,
+    pub pid: u32,
+    pub minimized: bool,
+}
+
+impl WindowInfo {
+    /// Case-insensitive match on title or process name; an empty query matches all
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.title.to_lowercase().contains(&query)
+            || self.process.to_lowercase().contains(&query)
+    }
+}
+
+/// List visible top-level windows of other apps whose title or process
+/// name contains `query`.
+#[tauri::command]
+pub async fn list_windows(query: Option<String>) -> AppResult<Vec<WindowInfo>> {
+    let query = query.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok(list_windows_blocking()?
+            .into_iter()
+            .filter(|w| w.matches(&query))
+            .collect())
+    })
+    .await
+    .map_err(|e| AppError::Unknown(format!("Window enumeration join error: {e}")))?
+}
+
+/// Bring a window to the foreground, restoring it first if minimized
+#[tauri::command]
+pub async fn focus_window(hwnd: i64) -> AppResult<()> {
+    tauri::async_runtime::spawn_blocking(move || focus_window_blocking(hwnd))
+        .await
+        .map_err(|e| AppError::Unknown(format!("Focus join error: {e}")))?
+}
+
+#[cfg(windows)]
+pub(crate) fn list_windows_blocking() -> AppResult<Vec<WindowInfo>> {
+    use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::EnumWindows;
+
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let handles = &mut *(lparam.0 as *mut Vec<HWND>);
+        handles.push(hwnd);
+        BOOL(1)
+    }
+
+    let mut handles: Vec<HWND> = Vec::new();
+    unsafe { EnumWindows(Some(collect), LPARAM(&mut handles as *mut Vec<HWND> as isize)) }
+        .map_err(|e| AppError::Unknown(format!("EnumWindows failed: {e}")))?;
+
+    let system = sysinfo::System::new_with_specifics(
+        sysinfo::RefreshKind::new().with_processes(sysinfo::ProcessRefreshKind::new()),
+    );
+    let own_pid = std::process::id();
+
+    Ok(handles
+        .into_iter()
+        .filter_map(|hwnd| unsafe { switchable_window(hwnd) })
+        .filter(|w| w.pid != own_pid)
+        .map(|mut w| {
+            if let Some(process) = system.process(sysinfo::Pid::from_u32(w.pid)) {
+                w.process = process.name().to_string();
+            }
+            w
+        })
+        .collect())
+}
+
+/// Describe `hwnd` if it is a window a user would switch to: visible,
+/// titled, unowned, not a tool window and not cloaked (hidden UWP frames,
+/// windows on other virtual desktops).
+#[cfg(windows)]
+unsafe fn switchable_window(hwnd: windows::Win32::Foundation::HWND) -> Option<WindowInfo> {
+    use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindow, GetWindowLongPtrW, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
+        IsIconic, IsWindowVisible, GWL_EXSTYLE, GW_OWNER, WS_EX_TOOLWINDOW,
+    };
+
+    if !IsWindowVisible(hwnd).as_bool() {
+        return None;
+    }
+    if GetWindow(hwnd, GW_OWNER).is_ok_and(|owner| !owner.is_invalid()) {
+        return None;
+    }
+    if GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW.0 != 0 {
+        return None;
+    }
+    let mut cloaked: u32 = 0;
+    let cloaked_ok = DwmGetWindowAttribute(
+        hwnd,
+        DWMWA_CLOAKED,
+        &mut cloaked as *mut u32 as *mut std::ffi::c_void,
+        std::mem::size_of::<u32>() as u32,
+    )
+    .is_ok();
+    if cloaked_ok && cloaked != 0 {
+        return None;
+    }
+
+    let len = GetWindowTextLengthW(hwnd);
+    if len <= 0 {
+        return None;
+    }
+    let mut buf = vec![0u16; len as usize + 1];
+    let copied = GetWindowTextW(hwnd, &mut buf);
+    let title = String::from_utf16_lossy(&buf[..copied.max(0) as usize]);
+    if title.trim().is_empty() {
+        return None;
+    }
+
+    let mut pid = 0u32;
+    GetWindowThreadProcessId(hwnd, Some(&mut pid));
+
+    Some(WindowInfo {
+        hwnd: hwnd.0 as i64,
+        title,
+        process: String::new(),
+        pid,
+        minimized: IsIconic(hwnd).as_bool(),
+    })
+}
+
+#[cfg(windows)]
+fn focus_window_blocking(hwnd: i64) -> AppResult<()> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{IsIconic, IsWindow, SetForegroundWindow, ShowWindow, SW_RESTORE};
+
+    let hwnd = HWND(hwnd as isize as *mut std::ffi::c_void);
+    unsafe {
+        if !IsWindow(hwnd).as_bool() {
+            return Err(AppError::NotFound("The window no longer exists".to_string()));
+        }
+        if IsIconic(hwnd).as_bool() {
+            let _ = ShowWindow(hwnd, SW_RESTORE);
+        }
+        if !SetForegroundWindow(hwnd).as_bool() {
+            return Err(AppError::Unknown("Windows refused to bring the window to the foreground".to_string()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub(crate) fn list_windows_blocking() -> AppResult<Vec<WindowInfo>> {
+    Err(AppError::Unknown("Window switching is only supported on Windows".to_string()))
+}
+
+#[cfg(not(windows))]
+fn focus_window_blocking(_hwnd: i64) -> AppResult<()> {
+    Err(AppError::Unknown("Window switching is only supported on Windows".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_matches_title_or_process() {
+        let window = WindowInfo {
+            hwnd: 1,
+            title: "Pull requests - Google Chrome".to_string(),
+            process: "chrome.exe".to_string(),
+            pid: 42,
+            minimized: false,
+        };
+        assert!(window.matches(""));
+        assert!(window.matches("CHROME"));
+        assert!(window.matches(" pull "));
+        assert!(!window.matches("firefox"));
+    }
+}
@@ -1,10 +1,15 @@
 use crate::app::{error::AppResult, state::AppState};
-use crate::core::ai::{AIAttachment, AIClient, AIConversation, AIMessage, AIProviderConfig, PresetPrompt};
+use crate::app::config::AIConfig;
+use crate::core::ai::{AIAttachment, AIClient, AIConversation, AIMessage, AIProviderConfig, CallOverrides, PresetPrompt, ProviderTestError, StreamEvent, TokenUsage};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, State};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Quick queries answer inline in the search box, so cap them unless the
+/// caller asks for more
+const QUICK_QUERY_MAX_TOKENS: u32 = 512;
+
 /// Shared AI client state
 pub struct AIState {
     pub client: Arc<RwLock<AIClient>>,
@@ -24,6 +29,25 @@ impl Default for AIState {
     }
 }
 
+/// Build the provider config from settings, applying any per-call overrides
+fn provider_config(ai: &AIConfig, overrides: Option<&CallOverrides>) -> AppResult<AIProviderConfig> {
+    let config = AIProviderConfig {
+        provider: ai.provider.clone(),
+        api_key: ai.api_key.clone(),
+        api_url: ai.api_url.clone(),
+        model: ai.model.clone(),
+        temperature: ai.temperature,
+        max_tokens: ai.max_tokens,
+        stop: ai.stop.clone(),
+        deployment: ai.deployment.clone(),
+        api_version: ai.api_version.clone(),
+    };
+    match overrides {
+        Some(overrides) => config.with_overrides(overrides),
+        None => Ok(config),
+    }
+}
+
 /// Create a new conversation
 #[tauri::command]
 pub async fn ai_create_conversation(
@@ -61,7 +85,9 @@ pub async fn ai_delete_conversation(
     ai_state: State<'_, AIState>,
 ) -> AppResult<()> {
     let client = ai_state.client.read().await;
-    client.delete_conversation(&id).await
+    client.delete_conversation(&id).await?;
+    super::system::RECENTLY_CLOSED.lock().forget_conversation(&id);
+    Ok(())
 }
 
 /// Clear all conversations
@@ -80,19 +106,11 @@ pub async fn ai_chat(
     conversation_id: String,
     message: String,
     attachments: Option<Vec<AIAttachment>>,
+    overrides: Option<CallOverrides>,
     state: State<'_, AppState>,
     ai_state: State<'_, AIState>,
 ) -> AppResult<AIMessage> {
-    let config = state.config.read().await;
-    let provider_config = AIProviderConfig {
-        provider: config.ai.provider.clone(),
-        api_key: config.ai.api_key.clone(),
-        api_url: config.ai.api_url.clone(),
-        model: config.ai.model.clone(),
-        temperature: config.ai.temperature,
-        max_tokens: config.ai.max_tokens,
-    };
-    drop(config);
+    let provider_config = provider_config(&state.config.read().await.ai, overrides.as_ref())?;
 
     let client = ai_state.client.read().await;
     client.chat(&conversation_id, message, attachments, &provider_config).await
@@ -104,20 +122,12 @@ pub async fn ai_chat_stream(
     conversation_id: String,
     message: String,
     attachments: Option<Vec<AIAttachment>>,
+    overrides: Option<CallOverrides>,
     app: AppHandle,
     state: State<'_, AppState>,
     ai_state: State<'_, AIState>,
 ) -> AppResult<String> {
-    let config = state.config.read().await;
-    let provider_config = AIProviderConfig {
-        provider: config.ai.provider.clone(),
-        api_key: config.ai.api_key.clone(),
-        api_url: config.ai.api_url.clone(),
-        model: config.ai.model.clone(),
-        temperature: config.ai.temperature,
-        max_tokens: config.ai.max_tokens,
-    };
-    drop(config);
+    let provider_config = provider_config(&state.config.read().await.ai, overrides.as_ref())?;
 
     let client = ai_state.client.read().await;
     
@@ -128,6 +138,7 @@ pub async fn ai_chat_stream(
         content: message.clone(),
         timestamp: chrono::Utc::now().timestamp(),
         attachments: attachments.clone(),
+        usage: None,
     };
     client.add_message(&conversation_id, user_msg).await?;
 
@@ -143,12 +154,13 @@ pub async fn ai_chat_stream(
             content: system_prompt.clone(),
             timestamp: 0,
             attachments: None,
+            usage: None,
         });
     }
     messages.extend(conversation.messages);
 
     // Create channel for streaming
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(100);
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<StreamEvent>(100);
     let assistant_msg_id = uuid::Uuid::new_v4().to_string();
 
     // Spawn task to handle streaming
@@ -156,29 +168,52 @@ pub async fn ai_chat_stream(
     let stream_config = provider_config.clone();
     let stream_app = app.clone();
     let stream_msg_id = assistant_msg_id.clone();
+    let prompt = messages.clone();
 
     tokio::spawn(async move {
         let mut full_response = String::new();
+        let mut reported_usage = None;
         
         // Start streaming
         let _ = stream_app.emit("ai-stream-start", &stream_msg_id);
         
-        if let Err(e) = provider.chat_stream(messages, &stream_config, tx).await {
+        // Forward events while the provider is still producing them
+        let (result, _) = tokio::join!(
+            provider.chat_stream(messages, &stream_config, tx),
+            async {
+                while let Some(event) = rx.recv().await {
+                    match event {
+                        StreamEvent::Text(chunk) => {
+                            full_response.push_str(&chunk);
+                            let _ = stream_app.emit("ai-stream-chunk", serde_json::json!({
+                                "id": stream_msg_id,
+                                "chunk": chunk,
+                            }));
+                        }
+                        StreamEvent::ToolCall { name, arguments } => {
+                            let _ = stream_app.emit("ai-stream-tool-call", serde_json::json!({
+                                "id": stream_msg_id,
+                                "name": name,
+                                "arguments": arguments,
+                            }));
+                        }
+                        StreamEvent::Usage(usage) => reported_usage = Some(usage),
+                    }
+                }
+            }
+        );
+        if let Err(e) = result {
             let _ = stream_app.emit("ai-stream-error", e.to_string());
             return;
         }
 
-        while let Some(chunk) = rx.recv().await {
-            full_response.push_str(&chunk);
-            let _ = stream_app.emit("ai-stream-chunk", serde_json::json!({
-                "id": stream_msg_id,
-                "chunk": chunk,
-            }));
-        }
-
+        // Not every provider reports usage for streamed responses
+        let usage = reported_usage
+            .unwrap_or_else(|| TokenUsage::estimate(&prompt, &full_response));
         let _ = stream_app.emit("ai-stream-end", serde_json::json!({
             "id": stream_msg_id,
             "content": full_response,
+            "usage": usage,
         }));
     });
 
@@ -191,6 +226,7 @@ pub async fn ai_save_response(
     conversation_id: String,
     message_id: String,
     content: String,
+    usage: Option<TokenUsage>,
     ai_state: State<'_, AIState>,
 ) -> AppResult<AIMessage> {
     let client = ai_state.client.read().await;
@@ -201,12 +237,23 @@ pub async fn ai_save_response(
         content,
         timestamp: chrono::Utc::now().timestamp(),
         attachments: None,
+        usage,
     };
 
     client.add_message(&conversation_id, assistant_msg.clone()).await?;
     Ok(assistant_msg)
 }
 
+/// Get the token usage accumulated by a conversation
+#[tauri::command]
+pub async fn ai_get_usage(
+    conversation_id: String,
+    ai_state: State<'_, AIState>,
+) -> AppResult<TokenUsage> {
+    let client = ai_state.client.read().await;
+    client.get_usage(&conversation_id).await
+}
+
 /// Get preset prompts
 #[tauri::command]
 pub async fn ai_get_presets(
@@ -258,14 +305,8 @@ pub async fn ai_get_models(
 ) -> AppResult<Vec<String>> {
     let config = state.config.read().await;
     let provider_name = provider.unwrap_or_else(|| config.ai.provider.clone());
-    let provider_config = AIProviderConfig {
-        provider: provider_name.clone(),
-        api_key: config.ai.api_key.clone(),
-        api_url: config.ai.api_url.clone(),
-        model: config.ai.model.clone(),
-        temperature: config.ai.temperature,
-        max_tokens: config.ai.max_tokens,
-    };
+    let mut provider_config = provider_config(&config.ai, None)?;
+    provider_config.provider = provider_name.clone();
     drop(config);
 
     let client = ai_state.client.read().await;
@@ -273,6 +314,17 @@ pub async fn ai_get_models(
     provider.list_models(&provider_config).await
 }
 
+/// Check a provider configuration before it is saved by sending a one-token
+/// ping. Returns the round trip in milliseconds.
+#[tauri::command]
+pub async fn ai_test_provider(
+    config: AIProviderConfig,
+    ai_state: State<'_, AIState>,
+) -> Result<u64, ProviderTestError> {
+    let client = ai_state.client.read().await;
+    client.test_provider(&config).await
+}
+
 // Legacy commands for backward compatibility
 #[tauri::command]
 pub async fn get_ai_conversations(
@@ -286,20 +338,17 @@ pub async fn get_ai_conversations(
 #[tauri::command]
 pub async fn ai_quick_query(
     prompt: String,
+    overrides: Option<CallOverrides>,
     app: AppHandle,
     state: State<'_, AppState>,
     ai_state: State<'_, AIState>,
 ) -> AppResult<String> {
-    let config = state.config.read().await;
-    let provider_config = AIProviderConfig {
-        provider: config.ai.provider.clone(),
-        api_key: config.ai.api_key.clone(),
-        api_url: config.ai.api_url.clone(),
-        model: config.ai.model.clone(),
-        temperature: config.ai.temperature,
-        max_tokens: config.ai.max_tokens,
-    };
-    drop(config);
+    let ai_config = state.config.read().await.ai.clone();
+    let mut overrides = overrides.unwrap_or_default();
+    overrides
+        .max_tokens
+        .get_or_insert(ai_config.max_tokens.min(QUICK_QUERY_MAX_TOKENS));
+    let provider_config = provider_config(&ai_config, Some(&overrides))?;
 
     // Validate API key
     if provider_config.api_key.is_empty() {
@@ -318,11 +367,12 @@ pub async fn ai_quick_query(
             content: prompt,
             timestamp: chrono::Utc::now().timestamp(),
             attachments: None,
+            usage: None,
         }
     ];
 
     // Create channel for streaming
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(100);
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<StreamEvent>(100);
     let query_id = uuid::Uuid::new_v4().to_string();
 
     // Spawn task to handle streaming
@@ -337,7 +387,23 @@ pub async fn ai_quick_query(
         // Start streaming - emit to main window
         let _ = stream_app.emit("ai-quick-start", &stream_query_id);
         
-        if let Err(e) = provider.chat_stream(messages, &stream_config, tx).await {
+        // Quick queries offer no tools, so only text is shown
+        let (result, _) = tokio::join!(
+            provider.chat_stream(messages, &stream_config, tx),
+            async {
+                while let Some(event) = rx.recv().await {
+                    if let StreamEvent::Text(chunk) = event {
+                        full_response.push_str(&chunk);
+                        let _ = stream_app.emit("ai-quick-chunk", serde_json::json!({
+                            "id": stream_query_id,
+                            "chunk": chunk,
+                            "content": full_response.clone(),
+                        }));
+                    }
+                }
+            }
+        );
+        if let Err(e) = result {
             let _ = stream_app.emit("ai-quick-error", serde_json::json!({
                 "id": stream_query_id,
                 "error": e.to_string(),
@@ -345,15 +411,6 @@ pub async fn ai_quick_query(
             return;
         }
 
-        while let Some(chunk) = rx.recv().await {
-            full_response.push_str(&chunk);
-            let _ = stream_app.emit("ai-quick-chunk", serde_json::json!({
-                "id": stream_query_id,
-                "chunk": chunk,
-                "content": full_response.clone(),
-            }));
-        }
-
         let _ = stream_app.emit("ai-quick-end", serde_json::json!({
             "id": stream_query_id,
             "content": full_response,
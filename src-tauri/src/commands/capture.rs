@@ -1,4 +1,12 @@
+use crate::app::config::{AfterCapture, CaptureMode};
 use crate::app::error::{AppError, AppResult};
+use crate::app::state::AppState;
+use crate::core::screenshot::hide::HiddenWindows;
+use crate::core::screenshot::last::{self as last_capture, CaptureFrame, LastCapture};
+use crate::core::screenshot::save;
+use crate::core::screenshot::{
+    self, CaptureFinish, CapturePlan, CaptureResult, PickedColor, SaveFormat, ScreenRect,
+};
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
 use once_cell::sync::Lazy;
@@ -12,23 +20,27 @@ use tokio::sync::Mutex as TokioMutex;
 #[derive(Clone, Serialize)]
 pub struct PinPayload {
     data: String,
+    /// MIME type of the base64 `data`
+    mime_type: &'static str,
     width: u32,
     height: u32,
 }
 
 static PIN_PAYLOADS: Lazy<Mutex<HashMap<String, PinPayload>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 
+/// Everything needed to recreate a closed pin window
 #[derive(Clone)]
-#[allow(dead_code)]
-struct CapturePng {
-    id: u64,
-    png_bytes: Vec<u8>,
-    width: u32,
-    height: u32,
-    file_path: Option<std::path::PathBuf>,
+pub struct RetainedPin {
+    payload: PinPayload,
+    x: f64,
+    y: f64,
 }
 
-static LAST_CAPTURE_PNG: Lazy<Mutex<Option<CapturePng>>> = Lazy::new(|| Mutex::new(None));
+// OmniBox windows hidden while the overlay capture is open
+static HIDDEN_FOR_CAPTURE: Lazy<Mutex<HiddenWindows>> = Lazy::new(|| Mutex::new(HiddenWindows::default()));
+
+// Last selected region in physical screen coordinates (for CaptureMode::LastRegion)
+static LAST_CAPTURE_REGION: Lazy<Mutex<Option<ScreenRect>>> = Lazy::new(|| Mutex::new(None));
 
 // Track frame IDs for delivery
 static CAPTURE_FRAME_ID: AtomicU64 = AtomicU64::new(0);
@@ -196,13 +208,24 @@ pub async fn init_capture(app: tauri::AppHandle) -> AppResult<()> {
         tracing::info!("Capture window hidden for capture");
     }
 
-    // Small delay to ensure window is fully hidden
-    tokio::time::sleep(std::time::Duration::from_millis(80)).await;
+    // Keep the launcher, clipboard, settings and pin windows out of the
+    // screenshot; they come back when the overlay closes
+    let windows: Vec<_> = app.webview_windows().into_values().collect();
+    let hidden = HiddenWindows::hide_all(&windows, &["capture"]);
+    if !hidden.is_empty() {
+        tracing::info!("Windows hidden for capture: {:?}", hidden.labels());
+    }
+    HIDDEN_FOR_CAPTURE.lock().merge(hidden);
+    let mut restore_on_error = RestoreOnError { app: &app, armed: true };
+
+    // Small delay to ensure the windows are fully hidden
+    let (settle_delay, _) = window_hiding(&app).await;
+    tokio::time::sleep(settle_delay).await;
 
     // Step 2: Capture screen
     tracing::info!("Capturing screen...");
-    let (png_bytes, width, height, mon_x, mon_y, mon_w, mon_h) = 
-        tauri::async_runtime::spawn_blocking(move || -> AppResult<(Vec<u8>, u32, u32, i32, i32, u32, u32)> {
+    let (png_bytes, width, height, mon_x, mon_y, mon_w, mon_h, mon_scale) = 
+        tauri::async_runtime::spawn_blocking(move || -> AppResult<(Vec<u8>, u32, u32, i32, i32, u32, u32, f64)> {
             use image::codecs::png::{CompressionType, FilterType, PngEncoder};
             use image::{ColorType, ImageEncoder};
 
@@ -220,6 +243,7 @@ pub async fn init_capture(app: tauri::AppHandle) -> AppResult<()> {
             let mon_y = monitor.y().unwrap_or(0);
             let mon_w = monitor.width().unwrap_or(1920);
             let mon_h = monitor.height().unwrap_or(1080);
+            let mon_scale = monitor.scale_factor().unwrap_or(1.0) as f64;
 
             let img = monitor
                 .capture_image()
@@ -236,7 +260,7 @@ pub async fn init_capture(app: tauri::AppHandle) -> AppResult<()> {
                 .write_image(&raw, width, height, ColorType::Rgba8)
                 .map_err(|e| AppError::Unknown(format!("Failed to encode PNG: {e}")))?;
 
-            Ok((out, width, height, mon_x, mon_y, mon_w, mon_h))
+            Ok((out, width, height, mon_x, mon_y, mon_w, mon_h, mon_scale))
         })
         .await
         .map_err(|e| AppError::Unknown(format!("Capture task join failed: {e}")))??;
@@ -260,22 +284,28 @@ pub async fn init_capture(app: tauri::AppHandle) -> AppResult<()> {
         }
     }
 
-    *LAST_CAPTURE_PNG.lock() = Some(CapturePng {
+    last_capture::store(CaptureFrame {
         id: frame_id,
         png_bytes: png_bytes.clone(),
         width,
         height,
+        monitor_x: mon_x,
+        monitor_y: mon_y,
+        scale_factor: mon_scale,
         file_path: file_path.clone(),
     });
 
     // Build payload - include monitor position for coordinate conversion
     // Always send base64 data for reliability (convertFileSrc can have issues)
+    // afterCapture tells the overlay whether to pin as soon as the selection is made
+    let (_, after_capture) = configured_capture(&app).await;
     let payload = serde_json::json!({
         "data": BASE64.encode(&png_bytes),
         "width": width,
         "height": height,
         "monitorX": mon_x,
         "monitorY": mon_y,
+        "afterCapture": after_capture,
     });
 
     // Also save to file for debugging (optional)
@@ -336,10 +366,262 @@ pub async fn init_capture(app: tauri::AppHandle) -> AppResult<()> {
         tracing::info!("Capture window shown: pos={:?} size={:?}", actual_pos, actual_size);
     }
 
+    restore_on_error.armed = false;
     tracing::info!("=== Capture init complete ===");
     Ok(())
 }
 
+/// Shows the windows `init_capture` hid if it returns before the overlay
+/// is up; otherwise nothing would bring them back
+struct RestoreOnError<'a> {
+    app: &'a tauri::AppHandle,
+    armed: bool,
+}
+
+impl Drop for RestoreOnError<'_> {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        let hidden = std::mem::take(&mut *HIDDEN_FOR_CAPTURE.lock());
+        let windows: Vec<_> = self.app.webview_windows().into_values().collect();
+        hidden.restore(&windows);
+    }
+}
+
+/// Configured output format and quality for saved and pinned captures;
+/// PNG at the default quality until AppState is ready
+async fn output_format(app: &tauri::AppHandle) -> (SaveFormat, u8) {
+    match app.try_state::<AppState>() {
+        Some(state) => {
+            let screenshot = state.get_config().await.screenshot;
+            let format = SaveFormat::parse(&screenshot.format).unwrap_or(SaveFormat::Png);
+            (format, screenshot.quality)
+        }
+        None => (SaveFormat::Png, 90),
+    }
+}
+
+/// Configured capture mode and follow-up.
+/// AppState is initialized in the background; before that, use the defaults.
+async fn configured_capture(app: &tauri::AppHandle) -> (CaptureMode, AfterCapture) {
+    match app.try_state::<AppState>() {
+        Some(state) => {
+            let screenshot = state.get_config().await.screenshot;
+            (screenshot.capture_mode, screenshot.after_capture)
+        }
+        None => (CaptureMode::default(), AfterCapture::default()),
+    }
+}
+
+/// `screenshot.hide_windows_delay_ms` and
+/// `screenshot.restore_windows_after_capture`
+async fn window_hiding(app: &tauri::AppHandle) -> (std::time::Duration, bool) {
+    let screenshot = match app.try_state::<AppState>() {
+        Some(state) => state.get_config().await.screenshot,
+        None => crate::app::config::AppConfig::default().screenshot,
+    };
+    (
+        std::time::Duration::from_millis(screenshot.hide_windows_delay_ms),
+        screenshot.restore_windows_after_capture,
+    )
+}
+
+/// Show the windows `init_capture` hid, unless turned off in the settings
+async fn restore_hidden_windows(app: &tauri::AppHandle) {
+    let hidden = std::mem::take(&mut *HIDDEN_FOR_CAPTURE.lock());
+    if hidden.is_empty() || !window_hiding(app).await.1 {
+        return;
+    }
+    let windows: Vec<_> = app.webview_windows().into_values().collect();
+    hidden.restore(&windows);
+}
+
+/// Entry point for the capture hotkey: dispatches on `screenshot.capture_mode`.
+///
+/// Region mode (and modes that have no rectangle to capture yet) shows the
+/// selection overlay; the others capture directly. Direct captures go to the
+/// clipboard, or straight into a pin window when `screenshot.after_capture`
+/// is `pin` (the overlay handles that mode itself once a region is selected).
+pub async fn capture_with_configured_mode(app: tauri::AppHandle) -> AppResult<()> {
+    let (mode, after_capture) = configured_capture(&app).await;
+
+    let last_region = *LAST_CAPTURE_REGION.lock();
+    let plan = screenshot::plan_capture(mode, last_region, screenshot::foreground_window_rect);
+    let finish = screenshot::finish_capture(after_capture, plan);
+    tracing::info!("Capture hotkey: mode={:?} plan={:?} finish={:?}", mode, plan, finish);
+
+    if plan == CapturePlan::Overlay {
+        return init_capture(app).await;
+    }
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let engine = screenshot::get_engine();
+        match plan {
+            CapturePlan::Region(rect) => engine.capture_region(&rect),
+            _ => engine.capture_at_cursor(),
+        }
+    })
+    .await
+    .map_err(|e| AppError::Unknown(format!("Capture task join failed: {e}")))??;
+
+    if finish == CaptureFinish::Pin {
+        let origin = match plan {
+            CapturePlan::Region(rect) => (rect.x, rect.y),
+            _ => (result.monitor.x, result.monitor.y),
+        };
+        return pin_direct_capture(&app, result, origin);
+    }
+    finish_direct_capture(&app, result)
+}
+
+/// Pin a capture taken without the overlay where it was captured.
+/// `origin` is the captured area's top-left corner in physical pixels.
+fn pin_direct_capture(app: &tauri::AppHandle, result: CaptureResult, origin: (i32, i32)) -> AppResult<()> {
+    let scale = if result.monitor.scale_factor > 0.0 {
+        result.monitor.scale_factor
+    } else {
+        1.0
+    };
+    let pin = RetainedPin {
+        payload: PinPayload {
+            data: BASE64.encode(&result.png_bytes),
+            mime_type: SaveFormat::Png.mime_type(),
+            width: (result.width as f64 / scale).round() as u32,
+            height: (result.height as f64 / scale).round() as u32,
+        },
+        x: origin.0 as f64 / scale,
+        y: origin.1 as f64 / scale,
+    };
+    open_pin_window(app, pin)?;
+    tracing::info!("Direct capture pinned: {}x{}", result.width, result.height);
+    Ok(())
+}
+
+/// Store a capture taken without the overlay and copy it to the clipboard
+fn finish_direct_capture(app: &tauri::AppHandle, result: CaptureResult) -> AppResult<()> {
+    let frame_id = CAPTURE_FRAME_ID.fetch_add(1, Ordering::Relaxed) + 1;
+
+    copy_png_to_clipboard(&result.png_bytes)?;
+    tracing::info!("Direct capture copied to clipboard: {}x{}", result.width, result.height);
+
+    last_capture::store(CaptureFrame {
+        id: frame_id,
+        png_bytes: result.png_bytes,
+        width: result.width,
+        height: result.height,
+        monitor_x: result.monitor.x,
+        monitor_y: result.monitor.y,
+        scale_factor: result.monitor.scale_factor,
+        file_path: None,
+    });
+
+    let _ = app.emit("capture:completed", serde_json::json!({
+        "width": result.width,
+        "height": result.height,
+    }));
+    Ok(())
+}
+
+/// Put a PNG on the clipboard as an image
+fn copy_png_to_clipboard(png_bytes: &[u8]) -> AppResult<()> {
+    let rgba = image::load_from_memory(png_bytes)
+        .map_err(|e| AppError::Unknown(format!("Failed to decode PNG: {e}")))?
+        .to_rgba8();
+    let (width, height) = rgba.dimensions();
+    arboard::Clipboard::new()
+        .and_then(|mut clip| {
+            clip.set_image(arboard::ImageData {
+                width: width as usize,
+                height: height as usize,
+                bytes: std::borrow::Cow::Owned(rgba.into_raw()),
+            })
+        })
+        .map_err(|e| AppError::Clipboard(format!("Clipboard write failed: {e}")))
+}
+
+/// The last screenshot as base64 PNG, to re-open it after the overlay was
+/// dismissed
+#[tauri::command]
+pub async fn get_last_capture() -> AppResult<LastCapture> {
+    last_capture::last_capture()
+}
+
+/// Copy the last screenshot to the clipboard
+#[tauri::command]
+pub async fn copy_last_capture() -> AppResult<()> {
+    let last = last_capture::latest().ok_or_else(|| AppError::NotFound("No screenshot has been taken yet".into()))?;
+    let (width, height) = (last.width, last.height);
+    tauri::async_runtime::spawn_blocking(move || copy_png_to_clipboard(&last.png_bytes))
+        .await
+        .map_err(|e| AppError::Unknown(format!("Copy task join failed: {e}")))??;
+    tracing::info!("Last capture copied to clipboard: {}x{}", width, height);
+    Ok(())
+}
+
+/// Map a selection in capture-webview CSS pixels to physical screen coordinates
+fn selection_to_screen_rect(
+    last: &CaptureFrame,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    viewport_width: u32,
+    viewport_height: u32,
+) -> ScreenRect {
+    let (src_x, src_y, width, height) = screenshot::selection_to_image_rect(
+        (x, y, width, height),
+        last.scale_factor,
+        (viewport_width, viewport_height),
+        (last.width, last.height),
+    );
+
+    ScreenRect {
+        x: last.monitor_x + src_x as i32,
+        y: last.monitor_y + src_y as i32,
+        width,
+        height,
+    }
+}
+
+/// Remember the user's selection so `CaptureMode::LastRegion` can repeat it.
+/// Coordinates are capture-webview CSS pixels, like `create_pin_window_from_selection`.
+#[tauri::command]
+pub async fn remember_capture_region(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    viewport_width: u32,
+    viewport_height: u32,
+) -> AppResult<()> {
+    let Some(last) = last_capture::latest() else {
+        return Ok(());
+    };
+    let rect = selection_to_screen_rect(&last, x, y, width, height, viewport_width, viewport_height);
+    if rect.width > 0 && rect.height > 0 {
+        *LAST_CAPTURE_REGION.lock() = Some(rect);
+    }
+    Ok(())
+}
+
+/// Eyedropper: color of the pixel at physical screen coordinates `(x, y)`,
+/// on any monitor
+#[tauri::command]
+pub async fn pick_color_at(x: i32, y: i32) -> AppResult<PickedColor> {
+    tauri::async_runtime::spawn_blocking(move || screenshot::get_engine().pick_color_at(x, y))
+        .await
+        .map_err(|e| AppError::Unknown(format!("Color pick task join failed: {e}")))?
+}
+
+/// Eyedropper at the current cursor position
+#[tauri::command]
+pub async fn pick_color_at_cursor() -> AppResult<PickedColor> {
+    let (x, y) = screenshot::cursor_position()
+        .ok_or_else(|| AppError::Unknown("Cursor position is not available on this platform".into()))?;
+    pick_color_at(x, y).await
+}
+
 #[tauri::command]
 pub async fn hide_capture_window(app: tauri::AppHandle) -> AppResult<()> {
     if let Some(win) = app.get_webview_window("capture") {
@@ -348,6 +630,7 @@ pub async fn hide_capture_window(app: tauri::AppHandle) -> AppResult<()> {
         let _ = win.hide();
         tracing::info!("Capture window hidden");
     }
+    restore_hidden_windows(&app).await;
     Ok(())
 }
 
@@ -375,19 +658,30 @@ pub async fn save_capture(app: tauri::AppHandle, png_bytes: Vec<u8>) -> AppResul
         tracing::info!("Screenshot saved to clipboard: {}x{}", w, h);
     }
 
+    // The clipboard always gets RGBA; the saved copy uses the configured format
+    if let Some(state) = app.try_state::<AppState>() {
+        if state.get_config().await.screenshot.auto_save {
+            if let Err(e) = quick_save(&app, png_bytes).await {
+                tracing::error!("Auto-save failed: {e}");
+            }
+        }
+    }
+
     // Hide capture window
     if let Some(win) = app.get_webview_window("capture") {
         let _ = win.set_ignore_cursor_events(true);
         let _ = win.hide();
     }
+    restore_hidden_windows(&app).await;
 
     Ok(())
 }
 
-/// Save PNG to a user-selected file path (frontend picks the path).
-/// `image_data` can be either raw base64 or a full data URL.
+/// Save a PNG to a user-selected file path (frontend picks the path),
+/// re-encoded for the path's extension; other extensions get the
+/// configured format. `image_data` can be either raw base64 or a full data URL.
 #[tauri::command]
-pub async fn save_capture_file(path: String, image_data: String) -> AppResult<()> {
+pub async fn save_capture_file(app: tauri::AppHandle, path: String, image_data: String) -> AppResult<()> {
     let b64 = image_data
         .split(',')
         .last()
@@ -398,12 +692,112 @@ pub async fn save_capture_file(path: String, image_data: String) -> AppResult<()
         .decode(b64)
         .map_err(|e| AppError::Unknown(format!("Failed to decode base64: {e}")))?;
 
+    let (configured, quality) = output_format(&app).await;
+    let format = SaveFormat::from_path(std::path::Path::new(&path)).unwrap_or(configured);
+    let bytes = tauri::async_runtime::spawn_blocking(move || save::encode(&bytes, format, quality))
+        .await
+        .map_err(|e| AppError::Unknown(format!("Encode task join failed: {e}")))??;
+
     std::fs::write(&path, bytes)
         .map_err(|e| AppError::Unknown(format!("Failed to write file: {e}")))?;
 
     Ok(())
 }
 
+/// "Click to capture": captures just the UI element (button, panel, ...)
+/// under screen point `(x, y)`, found through UI Automation. Parts of the
+/// element off the monitor are clamped away. Returns the PNG as base64.
+#[tauri::command]
+pub async fn capture_element_at(x: i32, y: i32) -> AppResult<String> {
+    tauri::async_runtime::spawn_blocking(move || -> AppResult<String> {
+        let element = crate::automation::get_element_rect_at_blocking(x, y)?
+            .ok_or_else(|| AppError::NotFound(format!("No UI element at ({x}, {y})")))?;
+        let rect = ScreenRect {
+            x: element.left,
+            y: element.top,
+            width: element.width() as u32,
+            height: element.height() as u32,
+        };
+        let result = screenshot::get_engine().capture_element(&rect, x, y)?;
+        Ok(BASE64.encode(&result.png_bytes))
+    })
+    .await
+    .map_err(|e| AppError::Unknown(format!("Capture task join failed: {e}")))?
+}
+
+/// Scrolling capture: stitches PNG `frames`, each with the scroll offset in
+/// pixels it was taken at, into one long image. The frontend scrolls and
+/// captures; overlaps between frames are matched and removed here.
+/// Returns the composed PNG as base64, like the other capture payloads.
+#[tauri::command]
+pub async fn assemble_scroll_capture(frames: Vec<(Vec<u8>, i32)>) -> AppResult<String> {
+    tauri::async_runtime::spawn_blocking(move || -> AppResult<String> {
+        let frames = frames
+            .into_iter()
+            .enumerate()
+            .map(|(i, (png, offset))| {
+                let img = image::load_from_memory(&png)
+                    .map_err(|e| AppError::Unknown(format!("Failed to decode frame {i}: {e}")))?;
+                Ok((img.to_rgba8(), offset))
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        let stitched = screenshot::scroll::assemble(frames)?;
+        let png = screenshot::encode_image(
+            stitched.as_raw(),
+            stitched.width(),
+            stitched.height(),
+            SaveFormat::Png,
+            100,
+        )?;
+        Ok(BASE64.encode(png))
+    })
+    .await
+    .map_err(|e| AppError::Unknown(format!("Stitch task join failed: {e}")))?
+}
+
+/// Quick save: writes the last capture to `screenshot.save_dir` (the
+/// Pictures folder when unset) under the configured name and format.
+/// Returns the path written.
+#[tauri::command]
+pub async fn save_capture_auto(app: tauri::AppHandle) -> AppResult<String> {
+    let png_bytes = last_capture::latest()
+        .map(|last| last.png_bytes)
+        .ok_or_else(|| AppError::NotFound("No capture to save".into()))?;
+
+    let path = quick_save(&app, png_bytes).await?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Writes `png_bytes` under the configured directory, name and format
+async fn quick_save(app: &tauri::AppHandle, png_bytes: Vec<u8>) -> AppResult<std::path::PathBuf> {
+    let state = app
+        .try_state::<AppState>()
+        .ok_or_else(|| AppError::NotFound("Settings are not loaded yet".into()))?;
+    let config = state.get_config().await.screenshot;
+    let format = SaveFormat::parse(&config.format)
+        .ok_or_else(|| AppError::Config(format!("Unsupported screenshot format '{}'", config.format)))?;
+    let dir = if config.save_dir.as_os_str().is_empty() {
+        app.path()
+            .picture_dir()
+            .or_else(|_| app.path().app_data_dir())
+            .map_err(|e| AppError::NotFound(format!("No folder for screenshots: {e}")))?
+    } else {
+        config.save_dir
+    };
+    let name = save::render_file_name(&config.file_name_template, chrono::Local::now());
+
+    let path = tauri::async_runtime::spawn_blocking(move || {
+        let bytes = save::encode(&png_bytes, format, config.quality)?;
+        save::write_unique(&dir, &name, format.extension(), &bytes)
+    })
+    .await
+    .map_err(|e| AppError::Unknown(format!("Save task join failed: {e}")))??;
+
+    tracing::info!("Capture saved to {}", path.display());
+    Ok(path)
+}
+
 /// Clipboard fallback using base64 to avoid huge JSON arrays over IPC.
 /// `image_data` can be either raw base64 or a full data URL.
 #[tauri::command]
@@ -433,14 +827,22 @@ pub async fn create_pin_window(
     x: i32,
     y: i32,
 ) -> AppResult<()> {
-    use tauri::WebviewWindowBuilder;
-    use std::sync::atomic::{AtomicU32, Ordering};
-    
-    // Generate unique window ID
-    static PIN_COUNTER: AtomicU32 = AtomicU32::new(0);
-    let pin_id = PIN_COUNTER.fetch_add(1, Ordering::Relaxed);
-    let window_label = format!("pin_{}", pin_id);
-    
+    show_pin_window(&app, image_data, SaveFormat::Png.mime_type(), width, height, x, y)?;
+    restore_hidden_windows(&app).await;
+    Ok(())
+}
+
+/// Pin base64 `image_data` at capture webview coords (`x`, `y`) and hide the
+/// capture window
+fn show_pin_window(
+    app: &tauri::AppHandle,
+    image_data: String,
+    mime_type: &'static str,
+    width: u32,
+    height: u32,
+    x: i32,
+    y: i32,
+) -> AppResult<()> {
     // Convert selection coords (capture webview coords) -> screen coords by adding capture window position.
     // This prevents pins from showing up off-screen on multi-monitor / non-zero positioned windows.
     let mut pos_x = x as f64;
@@ -453,35 +855,69 @@ pub async fn create_pin_window(
         }
     }
 
+    open_pin_window(
+        app,
+        RetainedPin {
+            payload: PinPayload {
+                data: image_data,
+                mime_type,
+                width,
+                height,
+            },
+            x: pos_x,
+            y: pos_y,
+        },
+    )?;
+    
+    // Hide capture window after creating pin
+    if let Some(win) = app.get_webview_window("capture") {
+        let _ = win.set_ignore_cursor_events(true);
+        let _ = win.hide();
+    }
+    
+    Ok(())
+}
+
+/// Build a pin window at screen position (`pin.x`, `pin.y`).
+/// The payload is retained until the window is destroyed so it can be
+/// reopened via `system::reopen_recently_closed`.
+pub(crate) fn open_pin_window(app: &tauri::AppHandle, pin: RetainedPin) -> AppResult<String> {
+    use tauri::WebviewWindowBuilder;
+    use std::sync::atomic::AtomicU32;
+
+    // Generate unique window ID
+    static PIN_COUNTER: AtomicU32 = AtomicU32::new(0);
+    let pin_id = PIN_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let window_label = format!("pin_{}", pin_id);
+    let (width, height) = (pin.payload.width, pin.payload.height);
+
     tracing::info!(
         "Creating pin window: {} at ({}, {}) size {}x{}",
         window_label,
-        pos_x,
-        pos_y,
+        pin.x,
+        pin.y,
         width,
         height
     );
 
     // Store payload for reliable retrieval (pin window pulls on mount).
     // This avoids duplicating a huge base64 string across multiple IPC paths.
-    PIN_PAYLOADS.lock().insert(
-        window_label.clone(),
-        PinPayload {
-            data: image_data,
-            width,
-            height,
-        },
-    );
+    PIN_PAYLOADS
+        .lock()
+        .insert(window_label.clone(), pin.payload.clone());
+    super::system::RECENTLY_CLOSED
+        .lock()
+        .pin_opened(window_label.clone(), pin.clone());
     
     // Build the pin window - keep URL small; send image via event / payload pull.
     let pin_window = WebviewWindowBuilder::new(
-        &app,
+        app,
         &window_label,
         tauri::WebviewUrl::App("/pin".into()),
     )
     .title("Pin")
     .inner_size(width as f64, height as f64)
-    .position(pos_x, pos_y)
+    .position(pin.x, pin.y)
     .decorations(false)
     .transparent(true)
     .always_on_top(true)
@@ -493,16 +929,18 @@ pub async fn create_pin_window(
     .build()
     .map_err(|e| AppError::Unknown(format!("Failed to create pin window: {e}")))?;
 
+    let closed_label = window_label.clone();
+    pin_window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Destroyed = event {
+            PIN_PAYLOADS.lock().remove(&closed_label);
+            super::system::RECENTLY_CLOSED.lock().pin_closed(&closed_label);
+        }
+    });
+
     let _ = pin_window.set_focus();
     
-    // Hide capture window after creating pin
-    if let Some(win) = app.get_webview_window("capture") {
-        let _ = win.set_ignore_cursor_events(true);
-        let _ = win.hide();
-    }
-    
     tracing::info!("Pin window created successfully");
-    Ok(())
+    Ok(window_label)
 }
 
 /// Pin window pulls its payload on mount (reliable even if initial event was missed)
@@ -540,70 +978,52 @@ pub async fn create_pin_window_from_selection(
     }
 
     // Snapshot last capture bytes (avoid holding lock across heavy work)
-    let last = LAST_CAPTURE_PNG
-        .lock()
-        .as_ref()
-        .cloned()
+    let last = last_capture::latest()
         .ok_or_else(|| AppError::NotFound("No capture frame available".into()))?;
 
-    let (img_w, img_h) = (last.width, last.height);
-    let (vw, vh) = (
-        std::cmp::max(1, viewport_width) as f64,
-        std::cmp::max(1, viewport_height) as f64,
+    *LAST_CAPTURE_REGION.lock() = Some(selection_to_screen_rect(
+        &last,
+        x,
+        y,
+        width,
+        height,
+        viewport_width,
+        viewport_height,
+    ));
+
+    // Map CSS pixels -> image pixels (clamped to the image)
+    let (src_x, src_y, src_w, src_h) = screenshot::selection_to_image_rect(
+        (x, y, width, height),
+        last.scale_factor,
+        (viewport_width, viewport_height),
+        (last.width, last.height),
     );
 
-    // Map CSS pixels -> image pixels
-    let scale_x = img_w as f64 / vw;
-    let scale_y = img_h as f64 / vh;
-
-    let mut src_x = ((x as f64) * scale_x).round() as i64;
-    let mut src_y = ((y as f64) * scale_y).round() as i64;
-    let mut src_w = ((width as f64) * scale_x).round() as i64;
-    let mut src_h = ((height as f64) * scale_y).round() as i64;
-
-    // Clamp
-    if src_x < 0 { src_x = 0 }
-    if src_y < 0 { src_y = 0 }
-    if src_w < 1 { src_w = 1 }
-    if src_h < 1 { src_h = 1 }
-
-    let max_x = img_w as i64;
-    let max_y = img_h as i64;
-    if src_x > max_x { src_x = max_x }
-    if src_y > max_y { src_y = max_y }
-    if src_x + src_w > max_x { src_w = max_x.saturating_sub(src_x) }
-    if src_y + src_h > max_y { src_h = max_y.saturating_sub(src_y) }
-
-    // Heavy work: decode PNG, crop, encode PNG, base64
-    let cropped_b64 = tauri::async_runtime::spawn_blocking(move || -> AppResult<String> {
-        use image::codecs::png::{CompressionType, FilterType, PngEncoder};
-        use image::{ColorType, ImageEncoder};
+    let (format, quality) = output_format(&app).await;
 
+    // Heavy work: decode PNG, crop, encode, base64
+    let cropped_b64 = tauri::async_runtime::spawn_blocking(move || -> AppResult<String> {
         let img = image::load_from_memory(&last.png_bytes)
             .map_err(|e| AppError::Unknown(format!("Failed to decode last capture PNG: {e}")))?
             .to_rgba8();
 
         let view = image::imageops::crop_imm(
             &img,
-            src_x as u32,
-            src_y as u32,
-            src_w as u32,
-            src_h as u32,
+            src_x,
+            src_y,
+            src_w,
+            src_h,
         )
         .to_image();
 
-        let raw = view.into_raw();
-        let mut out = Vec::new();
-        let encoder = PngEncoder::new_with_quality(&mut out, CompressionType::Fast, FilterType::NoFilter);
-        encoder
-            .write_image(&raw, src_w as u32, src_h as u32, ColorType::Rgba8)
-            .map_err(|e| AppError::Unknown(format!("Failed to encode cropped PNG: {e}")))?;
-
+        let out = screenshot::encode_image(view.as_raw(), src_w, src_h, format, quality)?;
         Ok(BASE64.encode(&out))
     })
     .await
     .map_err(|e| AppError::Unknown(format!("Crop task join failed: {e}")))??;
 
-    // Reuse the existing pin creator: x/y are still capture webview coords for placement.
-    create_pin_window(app, cropped_b64, width, height, x, y).await
+    // x/y are still capture webview coords for placement.
+    show_pin_window(&app, cropped_b64, format.mime_type(), width, height, x, y)?;
+    restore_hidden_windows(&app).await;
+    Ok(())
 }
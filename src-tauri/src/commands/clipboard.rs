@@ -1,6 +1,16 @@
-use crate::app::{error::AppResult, state::AppState};
+use crate::app::{error::{AppError, AppResult}, state::AppState};
+use crate::core::clipboard::{
+    paste, ring, transfer, ClipboardContent, ClipboardHistoryItem, ClipboardSnapshot, ClipboardTypeFilter, CombinedText,
+    ImportSummary, PruneLimits, Transform, Transformed,
+};
+use crate::ocr;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use std::time::Duration;
+use tauri::{Emitter, Manager, State};
+
+/// Time for focus to return to the target app after hiding the clipboard window
+const PASTE_FOCUS_DELAY: Duration = Duration::from_millis(80);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClipboardItem {
@@ -9,15 +19,36 @@ pub struct ClipboardItem {
     pub content: String,
     pub timestamp: i64,
     pub favorite: bool,
+    /// PNG data URL of an image item's thumbnail
+    pub thumbnail: Option<String>,
 }
 
-/// Get clipboard history
+/// Get clipboard history, optionally filtered to one content type
+/// ("text", "image", "files" or "link") and/or favorites
 #[tauri::command]
 pub async fn get_clipboard_history(
+    content_type: Option<String>,
+    favorites_only: Option<bool>,
+    limit: Option<i32>,
+    offset: Option<i32>,
     state: State<'_, AppState>,
 ) -> AppResult<Vec<ClipboardItem>> {
+    let filter = match content_type.as_deref() {
+        None | Some("") | Some("all") => None,
+        Some(value) => Some(ClipboardTypeFilter::parse(value).ok_or_else(|| {
+            AppError::Clipboard(format!("Unknown clipboard content type: {}", value))
+        })?),
+    };
+
     let storage = state.clipboard_storage().await?;
-    let items = storage.get_history(100, 0).await?;
+    let items = storage
+        .get_history_filtered(
+            filter,
+            favorites_only.unwrap_or(false),
+            limit.unwrap_or(100),
+            offset.unwrap_or(0),
+        )
+        .await?;
     
     let clipboard_items = items
         .into_iter()
@@ -27,32 +58,261 @@ pub async fn get_clipboard_history(
             content: item.plain_text.unwrap_or_default(),
             timestamp: item.created_at.timestamp(),
             favorite: item.is_favorite,
+            thumbnail: item
+                .thumbnail
+                .map(|png| format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(png))),
         })
         .collect();
     
     Ok(clipboard_items)
 }
 
-/// Paste clipboard item
+/// Paste clipboard item into the app that had focus before the clipboard
+/// window. Text items are pasted as plain text when
+/// `clipboard.paste_as_plain_text` is enabled. What was on the clipboard is
+/// restored `clipboard.paste_restore_delay_ms` after the paste.
 #[tauri::command]
 pub async fn paste_clipboard_item(
     id: String,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
+    paste_item(&id, None, &state).await
+}
+
+/// Paste only the plain text of a clipboard item, dropping any rich data
+#[tauri::command]
+pub async fn paste_as_plain_text(
+    id: String,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    paste_item(&id, Some(true), &state).await
+}
+
+/// Paste the text of a clipboard item after applying `transform`: "plain",
+/// "markdown_to_html", "trim", "single_line", "uppercase" or "lowercase"
+#[tauri::command]
+pub async fn paste_clipboard_item_transformed(
+    id: String,
+    transform: String,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let transform = Transform::parse(&transform)
+        .ok_or_else(|| AppError::Clipboard(format!("Unknown paste transform: {}", transform)))?;
     let storage = state.clipboard_storage().await?;
-    if let Some(item) = storage.get_by_id(&id).await? {
-        // Write content to clipboard (synchronous operation)
-        use tauri_plugin_clipboard_manager::ClipboardExt;
-        if let Some(text) = item.plain_text {
-            state.app_handle().clipboard().write_text(text)?;
+    let Some(item) = storage.get_by_id(&id).await? else {
+        return Ok(());
+    };
+    if item.content_type == "image" {
+        return Err(AppError::Clipboard("Image items cannot be transformed".to_string()));
+    }
+    let Some(text) = item.pasteable_text() else {
+        return Ok(());
+    };
+
+    paste_content(transform.apply(&text), state.inner()).await?;
+    storage.increment_access_count(&id).await?;
+    Ok(())
+}
+
+/// Start the clipboard ring over, so the next ring paste is the most recent item
+#[tauri::command]
+pub async fn clipboard_ring_reset() -> AppResult<()> {
+    ring::reset();
+    Ok(())
+}
+
+/// Paste the next history item in the clipboard ring: the most recent item
+/// first, then each older one, wrapping around after the oldest. The order
+/// is the history's when the ring started, so the bump each paste makes
+/// does not move the ring. Returns the pasted item's id, or `None` when the
+/// history is empty.
+#[tauri::command]
+pub async fn clipboard_ring_paste_next(state: State<'_, AppState>) -> AppResult<Option<String>> {
+    let storage = state.clipboard_storage().await?;
+    let mut next = ring::advance();
+    // Not started yet, or the item was deleted since: start over on the
+    // current history
+    if next.is_none() || storage.get_by_id(next.as_deref().unwrap_or_default()).await?.is_none() {
+        next = ring::start(storage.history_ids().await?);
+    }
+    let Some(id) = next else {
+        return Ok(None);
+    };
+    paste_item(&id, None, &state).await?;
+    Ok(Some(id))
+}
+
+/// Pastes HTML items with their formatting unless `plain_only`. `None`
+/// follows `clipboard.paste_as_plain_text`, which only applies to text items.
+async fn paste_item(id: &str, plain_only: Option<bool>, state: &AppState) -> AppResult<()> {
+    let storage = state.clipboard_storage().await?;
+    let Some(item) = storage.get_by_id(id).await? else {
+        return Ok(());
+    };
+    let plain_only = match plain_only {
+        Some(plain_only) => plain_only,
+        None => {
+            matches!(item.content_type.as_str(), "text" | "html")
+                && state.get_config().await.clipboard.paste_as_plain_text
         }
-        
-        // Update access count
-        storage.increment_access_count(&id).await?;
+    };
+    if plain_only && item.content_type == "image" {
+        return Err(AppError::Clipboard(
+            "Image items cannot be pasted as plain text".to_string(),
+        ));
+    }
+
+    let Some(text) = item.pasteable_text() else {
+        return Ok(());
+    };
+    let content = match item.html() {
+        Some(html) if !plain_only => Transformed::Html { html, alt_text: text },
+        _ => Transformed::Text(text),
+    };
+
+    paste_content(content, state).await?;
+    storage.increment_access_count(id).await?;
+    Ok(())
+}
+
+/// Put `content` on the clipboard, paste it into the focused app and restore
+/// the previous clipboard afterwards
+pub(crate) async fn paste_content(content: Transformed, state: &AppState) -> AppResult<()> {
+    // A paste still waiting to restore keeps its snapshot, so this one does
+    // not mistake that paste's item for the user's clipboard
+    let generation = state.paste_restore.begin(|| match ClipboardSnapshot::capture() {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            tracing::warn!("Not restoring the clipboard after paste: {}", e);
+            None
+        }
+    });
+
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+    let clipboard = state.app_handle().clipboard();
+    match content {
+        Transformed::Text(text) => clipboard.write_text(text)?,
+        Transformed::Html { html, alt_text } => clipboard.write_html(html, Some(alt_text))?,
     }
+
+    if let Some(window) = state.app_handle().get_webview_window("clipboard") {
+        let _ = window.hide();
+    }
+    tokio::time::sleep(PASTE_FOCUS_DELAY).await;
+    let pasted = tauri::async_runtime::spawn_blocking(paste::send_paste_keystroke)
+        .await
+        .map_err(|e| AppError::Unknown(format!("Paste task join failed: {e}")))?;
+    if let Err(e) = pasted {
+        // Leave the item copied so the user can paste it themselves
+        tracing::warn!("Failed to send the paste keystroke: {}", e);
+        state.paste_restore.abandon(generation);
+        return Ok(());
+    }
+
+    let delay = Duration::from_millis(state.get_config().await.clipboard.paste_restore_delay_ms);
+    let queue = state.paste_restore.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        let Some(snapshot) = queue.finish(generation) else {
+            return;
+        };
+        let restored = tauri::async_runtime::spawn_blocking(move || snapshot.restore()).await;
+        if let Ok(Err(e)) = restored {
+            tracing::warn!("Failed to restore the clipboard after paste: {}", e);
+        }
+    });
     Ok(())
 }
 
+/// Join the text of the history items `ids`, in that order, with
+/// `separator` and copy the result. Images and files are skipped and
+/// counted. With `save`, the result is also added to the history right away.
+#[tauri::command]
+pub async fn combine_clipboard_items(
+    ids: Vec<String>,
+    separator: String,
+    save: Option<bool>,
+    state: State<'_, AppState>,
+) -> AppResult<CombinedText> {
+    let storage = state.clipboard_storage().await?;
+    let combined = storage.combine_text(&ids, &separator).await?;
+    if combined.combined == 0 {
+        return Err(AppError::Clipboard(
+            "None of the selected items contain text".to_string(),
+        ));
+    }
+
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+    state.app_handle().clipboard().write_text(combined.text.clone())?;
+
+    if save.unwrap_or(false) {
+        let content = ClipboardContent::Text {
+            content: combined.text.clone(),
+            plain_text: combined.text.clone(),
+        };
+        // Like the monitor: a masked item keeps the preview as its text and
+        // the original in `data`
+        let masked = combined.sensitive && state.get_config().await.clipboard.mask_sensitive;
+        let (plain_text, data) = if masked {
+            (combined.preview.clone(), Some(combined.text.clone().into_bytes()))
+        } else {
+            (combined.text.clone(), None)
+        };
+        let item = ClipboardHistoryItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            content_type: content.content_type().to_string(),
+            content_hash: content.hash(),
+            plain_text: Some(plain_text),
+            data,
+            thumbnail: None,
+            source_app: None,
+            source_window: None,
+            is_favorite: false,
+            is_sensitive: combined.sensitive,
+            created_at: chrono::Utc::now(),
+            accessed_at: None,
+            access_count: 0,
+        };
+        let saved = storage.upsert_item(&item).await?;
+        let _ = state.app_handle().emit("clipboard-changed", saved.id());
+    }
+    Ok(combined)
+}
+
+/// Extract text from an image clipboard item, optionally copying it to the
+/// clipboard
+#[tauri::command]
+pub async fn ocr_clipboard_item(
+    id: String,
+    copy: Option<bool>,
+    language: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<ocr::OcrResult> {
+    let storage = state.clipboard_storage().await?;
+    let item = storage
+        .get_by_id(&id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Clipboard item {}", id)))?;
+    let data = match (item.content_type.as_str(), item.data) {
+        ("image", Some(data)) => data,
+        (content_type, _) => {
+            return Err(AppError::Clipboard(format!(
+                "Only image items can be read with OCR, this one is {}",
+                content_type
+            )))
+        }
+    };
+
+    let result = ocr::recognize_image_bytes(data, language)
+        .await
+        .map_err(AppError::Unknown)?;
+    if copy.unwrap_or(false) && !result.text.is_empty() {
+        use tauri_plugin_clipboard_manager::ClipboardExt;
+        state.app_handle().clipboard().write_text(result.text.clone())?;
+    }
+    Ok(result)
+}
+
 /// Toggle clipboard favorite status
 #[tauri::command]
 pub async fn toggle_clipboard_favorite(
@@ -75,6 +335,15 @@ pub async fn delete_clipboard_item(
     Ok(())
 }
 
+/// Delete every history item except favorites
+#[tauri::command]
+pub async fn clear_clipboard_history(state: State<'_, AppState>) -> AppResult<()> {
+    let storage = state.clipboard_storage().await?;
+    storage.clear_all().await?;
+    let _ = state.app_handle().emit("clipboard-changed", ());
+    Ok(())
+}
+
 /// Show clipboard window
 #[tauri::command]
 pub async fn show_clipboard_window(state: State<'_, AppState>) -> AppResult<()> {
@@ -89,3 +358,33 @@ pub async fn hide_clipboard_window(state: State<'_, AppState>) -> AppResult<()>
     window_manager.hide().await
 }
 
+/// Write the whole clipboard history to a portable file.
+/// Returns the number of items exported.
+#[tauri::command]
+pub async fn export_clipboard_history(
+    path: String,
+    state: State<'_, AppState>,
+) -> AppResult<usize> {
+    let storage = state.clipboard_storage().await?;
+    transfer::export_history(&storage, std::path::Path::new(&path)).await
+}
+
+/// Read a file written by `export_clipboard_history`. Content already in
+/// history is skipped; without `merge` the history is cleared first.
+#[tauri::command]
+pub async fn import_clipboard_history(
+    path: String,
+    merge: bool,
+    state: State<'_, AppState>,
+) -> AppResult<ImportSummary> {
+    let storage = state.clipboard_storage().await?;
+    let summary = transfer::import_history(&storage, std::path::Path::new(&path), merge).await?;
+    // A large import can go over the history limits until the next prune
+    let limits = PruneLimits::from(&state.get_config().await.clipboard);
+    storage
+        .prune(limits.max_items, limits.max_age_days, limits.max_total_bytes)
+        .await?;
+    storage.expire_sensitive(limits.sensitive_max_age_minutes).await?;
+    let _ = state.app_handle().emit("clipboard-changed", ());
+    Ok(summary)
+}
@@ -4,4 +4,6 @@ pub mod capture;
 pub mod plugin;
 pub mod search;
 pub mod settings;
+pub mod snippet;
 pub mod system;
+pub mod workflow;
@@ -4,8 +4,9 @@
 use crate::app::state::AppState;
 use crate::core::plugin::{
     InstalledPlugin, MarketplacePlugin, MarketplaceFilter, MarketplaceResponse,
-    PluginUpdateInfo, PluginPermission, PluginError
+    PluginAction, PluginUpdateInfo, PluginPermission, PluginError
 };
+use std::collections::HashMap;
 use tauri::State;
 
 /// 获取所有已安装的插件
@@ -158,3 +159,44 @@ pub async fn revoke_plugin_permission(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// 执行插件动作，返回插件的结果
+#[tauri::command]
+pub async fn execute_plugin_action(
+    state: State<'_, AppState>,
+    plugin_id: String,
+    action: PluginAction,
+) -> Result<serde_json::Value, String> {
+    let plugin_manager = state.plugin_manager.read().await;
+    plugin_manager
+        .execute_action(&plugin_id, &action)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取插件配置
+#[tauri::command]
+pub async fn get_plugin_config(
+    state: State<'_, AppState>,
+    plugin_id: String,
+) -> Result<HashMap<String, serde_json::Value>, String> {
+    let plugin_manager = state.plugin_manager.read().await;
+    plugin_manager
+        .get_config(&plugin_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 更新插件配置，不符合插件 schema 时返回出错的键
+#[tauri::command]
+pub async fn set_plugin_config(
+    state: State<'_, AppState>,
+    plugin_id: String,
+    config: HashMap<String, serde_json::Value>,
+) -> Result<(), String> {
+    let plugin_manager = state.plugin_manager.read().await;
+    plugin_manager
+        .set_config(&plugin_id, config)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,10 +1,29 @@
-use crate::app::{error::AppResult, state::AppState};
-use crate::core::parser::{Parser, ParseResult, Calculator};
-use base64::Engine;
+use crate::app::{config::FileSortOrder, error::{AppError, AppResult}, state::AppState};
+use crate::commands::system;
+use crate::automation;
+use crate::core::actions;
+use crate::core::browser_history;
+use crate::core::category_filter::{self, Categorized};
+use crate::core::refine::{self, Refinable};
+use crate::core::palette::PaletteRegistry;
+use crate::core::snippet;
+use crate::core::plugin::{self, PluginSearchResult};
+use crate::core::process;
+use crate::core::recent::{self, RecentItem};
+use crate::core::indexer::{hash_file, DuplicateOptions};
+#[cfg(windows)]
+use crate::core::indexer::SearchFilter;
+use crate::core::pinned::{self, Pinnable};
+use crate::core::parser::{currency, dictionary, emoji, is_calculation, is_currency_conversion, text_case, Calculator, Parser, ParseResult};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use tauri::State;
-use std::path::Path;
-use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[cfg(windows)]
 use crate::app_indexer::AppIndexer;
@@ -30,6 +49,74 @@ pub struct SearchAction {
     pub payload: Option<String>,
 }
 
+impl Categorized for SearchResult {
+    fn category(&self) -> &str {
+        &self.category
+    }
+
+    fn kind(&self) -> &str {
+        &self.r#type
+    }
+}
+
+impl Refinable for SearchResult {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn subtitle(&self) -> Option<&str> {
+        self.subtitle.as_deref()
+    }
+}
+
+impl Pinnable for SearchResult {
+    fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    fn score(&self) -> i32 {
+        self.score
+    }
+
+    fn set_score(&mut self, score: i32) {
+        self.score = score;
+    }
+
+    fn from_pinned_path(path: &str) -> Option<Self> {
+        let file = Path::new(path);
+        let kind = pinned::kind(path)?;
+        let extension = file
+            .extension()
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let is_app = is_application_smart(path, &extension) || extension.eq_ignore_ascii_case("app");
+        let (result_type, category) = if is_app {
+            ("app", "Application")
+        } else if kind == pinned::PinKind::Folder {
+            ("folder", "File")
+        } else {
+            ("file", "File")
+        };
+        Some(SearchResult {
+            id: format!("pinned-{}", path),
+            r#type: result_type.to_string(),
+            title: file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string()),
+            subtitle: Some(path.to_string()),
+            icon: None,
+            path: Some(path.to_string()),
+            category: category.to_string(),
+            score: 0,
+            action: SearchAction {
+                r#type: "open".to_string(),
+                payload: Some(path.to_string()),
+            },
+        })
+    }
+}
+
 /// Get app icon as base64 data URL (cached)
 #[cfg(any(target_os = "macos", target_os = "windows"))]
 async fn get_app_icon(app_path: &Path, state: &State<'_, AppState>) -> Option<String> {
@@ -53,28 +140,31 @@ async fn get_app_icon(_app_path: &Path, _state: &State<'_, AppState>) -> Option<
     None
 }
 
+/// Letter tile data URL used when no real icon is available
+async fn fallback_icon(name: &str, state: &State<'_, AppState>) -> Option<String> {
+    match state.icon_cache.letter_tile(name).await {
+        Ok(base64_data) => Some(format!("data:image/png;base64,{}", base64_data)),
+        Err(e) => {
+            tracing::debug!("Failed to render letter tile for {}: {}", name, e);
+            None
+        }
+    }
+}
+
 /// Get Windows system (Explorer) icon as base64 data URL (cached)
 #[cfg(windows)]
 async fn get_system_icon(path: &Path, state: &State<'_, AppState>) -> Option<String> {
-    if let Some(cached) = state.icon_cache.get_icon(path).await {
-        return Some(format!("data:image/png;base64,{}", cached));
-    }
-
-    match crate::platform::windows::extract_file_icon(path).await {
-        Some(icon_data) => {
-            let _ = state.icon_cache.cache_icon(path, &icon_data).await;
-            Some(format!(
-                "data:image/png;base64,{}",
-                base64::engine::general_purpose::STANDARD.encode(&icon_data)
-            ))
-        }
-        None => None,
-    }
+    state
+        .icon_cache
+        .file_icon(path)
+        .await
+        .map(|base64_data| format!("data:image/png;base64,{}", base64_data))
 }
 
-#[cfg(not(windows))]
-async fn get_system_icon(_path: &Path, _state: &State<'_, AppState>) -> Option<String> {
-    None
+/// Ranking points earned by past launches of `path`
+#[cfg(windows)]
+async fn launch_bonus(path: &Path, state: &State<'_, AppState>) -> i32 {
+    state.indexer.access_bonus(path).await.round() as i32
 }
 
 /// Check if a file is an application based on path and extension
@@ -108,13 +198,21 @@ fn is_application_smart(path: &str, extension: &str) -> bool {
 
 /// Search apps using AppIndexer (Rust indexer with pinyin support)
 #[cfg(windows)]
-async fn search_apps_with_indexer(query: &str, indexer: &AppIndexer, state: &State<'_, AppState>) -> Vec<SearchResult> {
-    let app_results = indexer.search(query, 20);
+async fn search_apps_with_indexer(
+    query: &str,
+    max_results: usize,
+    indexer: &AppIndexer,
+    state: &State<'_, AppState>,
+) -> Vec<SearchResult> {
+    let app_results = indexer.search(query, max_results);
 
     let mut out = Vec::with_capacity(app_results.len());
     for (idx, result) in app_results.into_iter().enumerate() {
         let path_buf = std::path::PathBuf::from(&result.entry.path);
-        let icon_data_url = get_app_icon(&path_buf, state).await;
+        let icon_data_url = match get_app_icon(&path_buf, state).await {
+            Some(icon) => Some(icon),
+            None => fallback_icon(&result.entry.name, state).await,
+        };
         let fallback = if result.entry.extension == "lnk" { "🔗" } else { "🚀" };
 
         out.push(SearchResult {
@@ -125,7 +223,7 @@ async fn search_apps_with_indexer(query: &str, indexer: &AppIndexer, state: &Sta
             icon: Some(icon_data_url.unwrap_or_else(|| fallback.to_string())),
             path: Some(result.entry.path.clone()),
             category: "Application".to_string(),
-            score: result.score as i32,
+            score: result.score as i32 + launch_bonus(&path_buf, state).await,
             action: SearchAction {
                 r#type: "open".to_string(),
                 payload: Some(result.entry.path.clone()),
@@ -138,10 +236,26 @@ async fn search_apps_with_indexer(query: &str, indexer: &AppIndexer, state: &Sta
 
 /// Search files using Everything (file search engine)
 #[cfg(windows)]
-async fn search_files_with_everything(query: &str, state: &State<'_, AppState>) -> Result<Vec<SearchResult>, String> {
-    tracing::debug!("Searching files with Everything: {}", query);
+async fn search_files_with_everything(
+    query: &str,
+    sort: FileSortOrder,
+    max_results: usize,
+    state: &State<'_, AppState>,
+) -> Result<Vec<SearchResult>, String> {
+    tracing::debug!("Searching files with Everything: {} ({:?})", query, sort);
+
+    let indexer_config = state.get_config().await.indexer;
+    let options = everything_service::SearchOptions {
+        sort,
+        match_path: indexer_config.match_path,
+        filter: SearchFilter {
+            include_globs: Some(indexer_config.search_include),
+            exclude_globs: Some(indexer_config.search_exclude),
+            ..Default::default()
+        },
+    };
     
-    match everything_service::search_files(query.to_string(), Some(50)).await {
+    match everything_service::search_files(query.to_string(), Some(max_results as u32), options).await {
         Ok(file_results) => {
             tracing::debug!("Everything returned {} results", file_results.len());
             
@@ -170,10 +284,11 @@ async fn search_files_with_everything(query: &str, state: &State<'_, AppState>)
                     result.filename.clone()
                 };
 
+                // File icons are filled in by `hybrid_search`, which warms them together
                 let icon = if is_app {
                     get_app_icon(path, state).await
                 } else {
-                    get_system_icon(path, state).await
+                    None
                 };
 
                 out.push(SearchResult {
@@ -184,7 +299,8 @@ async fn search_files_with_everything(query: &str, state: &State<'_, AppState>)
                     icon,
                     path: Some(result.path.clone()),
                     category: result.category.clone(),
-                    score: if is_app { 2000 - idx as i32 } else { 1000 - idx as i32 },
+                    score: if is_app { 2000 - idx as i32 } else { 1000 - idx as i32 }
+                        + launch_bonus(path, state).await,
                     action: SearchAction {
                         r#type: "open".to_string(),
                         payload: Some(result.path.clone()),
@@ -266,14 +382,13 @@ async fn fallback_search_desktop(query: &str, state: &State<'_, AppState>) -> Ve
             }
 
             let is_folder = entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false);
-            let icon = get_system_icon(&path, state).await;
 
             out.push(SearchResult {
                 id: format!("desktop-{}", out.len()),
                 r#type: if is_folder { "folder".to_string() } else { "file".to_string() },
                 title: name,
                 subtitle: Some(full.clone()),
-                icon,
+                icon: None,
                 path: Some(full.clone()),
                 category: "File".to_string(),
                 score: 900 - out.len() as i32,
@@ -288,20 +403,47 @@ async fn fallback_search_desktop(query: &str, state: &State<'_, AppState>) -> Ve
     out
 }
 
+/// Shown instead of silently empty file results while Everything.exe is not running
+#[cfg(windows)]
+fn everything_not_running_hint() -> SearchResult {
+    SearchResult {
+        id: "everything-not-running".to_string(),
+        r#type: "command".to_string(),
+        title: "Everything is not running".to_string(),
+        subtitle: Some("Start Everything to search files on this PC".to_string()),
+        icon: None,
+        path: None,
+        category: "File".to_string(),
+        score: 0,
+        action: SearchAction {
+            r#type: "none".to_string(),
+            payload: None,
+        },
+    }
+}
+
+/// File results whose icons `hybrid_search` extracts concurrently
+#[cfg(windows)]
+const ICON_WARM_COUNT: usize = 30;
+
+/// Most file/app results searched to fill a page of filtered results
+const MAX_FILTERED_SEARCH: usize = 2000;
+
 /// Hybrid search: Apps (Rust indexer) + Files (Everything)
-/// Apps always appear before files, with deduplication
+/// Apps always appear before files, with deduplication. Each source returns
+/// up to `max_results`, so the merged list covers every page up to there.
 #[cfg(windows)]
-async fn hybrid_search(query: &str, state: &State<'_, AppState>) -> Vec<SearchResult> {
+async fn hybrid_search(query: &str, sort: FileSortOrder, max_results: usize, state: &State<'_, AppState>) -> Vec<SearchResult> {
     tracing::info!("Hybrid search for: '{}'", query);
     
     // Run both searches
-    let app_results = search_apps_with_indexer(query, &state.app_indexer, state).await;
+    let app_results = search_apps_with_indexer(query, max_results, &state.app_indexer, state).await;
     tracing::debug!("AppIndexer returned {} results", app_results.len());
     
     let mut file_results = Vec::new();
     let mut everything_failed = false;
     
-    match search_files_with_everything(query, state).await {
+    match search_files_with_everything(query, sort, max_results, state).await {
         Ok(v) => {
             tracing::debug!("Everything returned {} file results", v.len());
             file_results = v;
@@ -311,6 +453,11 @@ async fn hybrid_search(query: &str, state: &State<'_, AppState>) -> Vec<SearchRe
             everything_failed = true;
         }
     };
+    let service_down = everything_failed
+        && matches!(
+            tokio::task::spawn_blocking(everything_service::status).await,
+            Ok(everything_service::EverythingStatus::NotRunning)
+        );
 
     // Desktop fallback when Everything fails OR returns empty results
     // This helps when Everything.exe isn't running, indexing isn't ready, or file is only on Desktop.
@@ -356,6 +503,8 @@ async fn hybrid_search(query: &str, state: &State<'_, AppState>) -> Vec<SearchRe
             if let Some(icon) = get_app_icon(&pb, state).await {
                 r.icon = Some(icon);
                 upgraded += 1;
+            } else if let Some(tile) = fallback_icon(&r.title, state).await {
+                r.icon = Some(tile);
             }
         }
     }
@@ -383,9 +532,32 @@ async fn hybrid_search(query: &str, state: &State<'_, AppState>) -> Vec<SearchRe
     // Merge: Apps first, then Files
     let mut results = app_results;
     results.extend(deduplicated_files);
+    if service_down {
+        results.push(everything_not_running_hint());
+    }
     
     // Sort by score descending
-    results.sort_by(|a, b| b.score.cmp(&a.score));
+    results.sort_by(rank_order);
+
+    // Extract the top results' file icons concurrently, then fill every
+    // result without an icon (mostly cache hits after warming)
+    let missing: Vec<usize> = results
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| r.icon.is_none() && r.path.is_some())
+        .map(|(i, _)| i)
+        .collect();
+    let warm_paths = missing
+        .iter()
+        .take(ICON_WARM_COUNT)
+        .filter_map(|&i| results[i].path.as_ref().map(PathBuf::from))
+        .collect();
+    state.icon_cache.warm(warm_paths).await;
+    for i in missing {
+        if let Some(path) = results[i].path.clone() {
+            results[i].icon = get_system_icon(Path::new(&path), state).await;
+        }
+    }
     
     tracing::info!("Hybrid search returned {} total results", results.len());
     
@@ -394,10 +566,10 @@ async fn hybrid_search(query: &str, state: &State<'_, AppState>) -> Vec<SearchRe
 
 /// Search using indexer (fallback for non-Windows)
 #[cfg(not(windows))]
-async fn search_with_indexer(query: &str, state: &State<'_, AppState>) -> Vec<SearchResult> {
+async fn search_with_indexer(query: &str, max_results: usize, state: &State<'_, AppState>) -> Vec<SearchResult> {
     use crate::core::indexer::FileEntry;
     
-    let file_entries = state.indexer.search(query).await;
+    let file_entries = state.indexer.search(query, 0, max_results).await;
     
     let mut results = Vec::new();
     for (idx, entry) in file_entries.iter().enumerate() {
@@ -406,8 +578,11 @@ async fn search_with_indexer(query: &str, state: &State<'_, AppState>) -> Vec<Se
             .unwrap_or(false);
         
         let (result_type, icon) = if is_app {
-            let icon = get_app_icon(&entry.path, state).await
-                .unwrap_or_else(|| "🚀".to_string());
+            let name = entry.display_name.as_ref().unwrap_or(&entry.name);
+            let icon = match get_app_icon(&entry.path, state).await {
+                Some(icon) => icon,
+                None => fallback_icon(name, state).await.unwrap_or_else(|| "🚀".to_string()),
+            };
             ("app".to_string(), Some(icon))
         } else {
             let icon = get_file_icon(&entry.path);
@@ -431,7 +606,9 @@ async fn search_with_indexer(query: &str, state: &State<'_, AppState>) -> Vec<Se
             icon,
             path: Some(entry.path.to_string_lossy().to_string()),
             category,
-            score: idx as i32,
+            // Same scale as Everything file results, so plugin results
+            // merge in by score on every platform
+            score: 1000 - idx as i32,
             action: SearchAction {
                 r#type: "open".to_string(),
                 payload: Some(entry.path.to_string_lossy().to_string()),
@@ -441,35 +618,240 @@ async fn search_with_indexer(query: &str, state: &State<'_, AppState>) -> Vec<Se
     results
 }
 
-/// Search command
+/// Order of file/app results: score descending, ties by path, title and id.
+/// The order is total, so every page of a query is cut from the same list.
+fn rank_order(a: &SearchResult, b: &SearchResult) -> std::cmp::Ordering {
+    b.score
+        .cmp(&a.score)
+        .then_with(|| a.path.cmp(&b.path))
+        .then_with(|| a.title.cmp(&b.title))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+/// File/app search through the result cache. Returns the merged, sorted
+/// results up to `max_results`; callers page through them.
+async fn cached_file_search(
+    query: &str,
+    sort: FileSortOrder,
+    max_results: usize,
+    state: &State<'_, AppState>,
+) -> Vec<SearchResult> {
+    let started = Instant::now();
+    let generation = state.index_generation();
+    let cache_key = format!("{:?}\u{0}{}\u{0}{}", sort, max_results, query);
+
+    let cached = state.search_cache.write().await.get(&cache_key, generation, started);
+    if let Some(mut results) = cached {
+        let stats = state.search_cache.read().await.stats();
+        tracing::debug!(
+            "Search cache hit for '{}' in {:?} (hit rate {:.0}%)",
+            query,
+            started.elapsed(),
+            stats.hit_rate() * 100.0
+        );
+        merge_by_score(&mut results, plugin_results(query, max_results, state).await);
+        apply_pinned(&mut results, query, state).await;
+        results.truncate(max_results);
+        return results;
+    }
+
+    // Use hybrid search on Windows (App Indexer + Everything)
+    #[cfg(windows)]
+    let built_in = hybrid_search(query, sort, max_results, state);
+
+    // Fallback to indexer search on non-Windows platforms
+    #[cfg(not(windows))]
+    let built_in = search_with_indexer(query, max_results, state);
+
+    // Plugins run alongside; each is cut off at its timeout
+    let (mut results, from_plugins) = tokio::join!(built_in, plugin_results(query, max_results, state));
+
+    // Only built-in results are cached: plugin results are live
+    let mut cache = state.search_cache.write().await;
+    cache.insert(&cache_key, results.clone(), generation, Instant::now());
+    tracing::debug!(
+        "Search cache miss for '{}', searched in {:?} (hit rate {:.0}%)",
+        query,
+        started.elapsed(),
+        cache.stats().hit_rate() * 100.0
+    );
+    drop(cache);
+
+    merge_by_score(&mut results, from_plugins);
+    apply_pinned(&mut results, query, state).await;
+    results.truncate(max_results);
+    results
+}
+
+/// File/app results in `categories` up to the end of `page`. The filter
+/// runs before paging, so a page holds `limit` matching results; more
+/// results are searched until there are enough or the search runs out.
+async fn filtered_file_search(
+    query: &str,
+    sort: FileSortOrder,
+    page: Page,
+    limit: usize,
+    categories: &[String],
+    state: &State<'_, AppState>,
+) -> Vec<SearchResult> {
+    let wanted = page.offset.saturating_add(limit);
+    let mut fetch = wanted;
+    loop {
+        let mut results = cached_file_search(query, sort, fetch, state).await;
+        let exhausted = results.len() < fetch;
+        category_filter::filter_categories(&mut results, categories);
+        if results.len() >= wanted || exhausted || fetch >= MAX_FILTERED_SEARCH {
+            return results;
+        }
+        fetch = fetch.saturating_mul(4).min(MAX_FILTERED_SEARCH);
+    }
+}
+
+/// Puts pinned items matching `query` first. Applied after the cache, so
+/// pinning takes effect on the next keystroke.
+async fn apply_pinned(results: &mut Vec<SearchResult>, query: &str, state: &State<'_, AppState>) {
+    let pins = state.config.read().await.pinned_items.clone();
+    if pins.is_empty() {
+        return;
+    }
+    pinned::apply_pins(results, &pins, query);
+
+    // Pins the search missed come without an icon
+    for result in results.iter_mut().filter(|r| r.icon.is_none() && r.id.starts_with("pinned-")) {
+        if let Some(path) = result.path.clone() {
+            result.icon = state
+                .icon_cache
+                .file_icon(Path::new(&path))
+                .await
+                .map(|base64_data| format!("data:image/png;base64,{}", base64_data));
+        }
+    }
+}
+
+/// Adds `extra` to `results`, highest score first; equal scores keep
+/// built-in results ahead
+fn merge_by_score(results: &mut Vec<SearchResult>, extra: Vec<SearchResult>) {
+    if extra.is_empty() {
+        return;
+    }
+    results.extend(extra);
+    let is_plugin = |result: &SearchResult| result.r#type == "plugin";
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| is_plugin(a).cmp(&is_plugin(b)))
+            .then_with(|| rank_order(a, b))
+    });
+}
+
+/// Results of enabled search plugins, or nothing when `plugin_search` is off
+async fn plugin_results(query: &str, max_results: usize, state: &State<'_, AppState>) -> Vec<SearchResult> {
+    let config = state.get_config().await.plugin_search;
+    if !config.enabled {
+        return Vec::new();
+    }
+    let manager = state.plugin_manager.read().await;
+    plugin::search_plugins(&*manager, query, max_results, Duration::from_millis(config.timeout_ms))
+        .await
+        .into_iter()
+        .map(|(plugin_id, result)| plugin_search_result(&plugin_id, result))
+        .collect()
+}
+
+/// Plugin scores (0.0-1.0) map onto the file result scale (up to 1000);
+/// the action payload carries what `execute_plugin_action` needs
+fn plugin_search_result(plugin_id: &str, result: PluginSearchResult) -> SearchResult {
+    let payload = serde_json::json!({ "pluginId": plugin_id, "action": result.action });
+    SearchResult {
+        id: format!("plugin-{}-{}", plugin_id, result.id),
+        r#type: "plugin".to_string(),
+        title: result.title,
+        subtitle: result.subtitle,
+        icon: result.icon,
+        path: None,
+        category: "Plugin".to_string(),
+        score: (result.score.clamp(0.0, 1.0) * 1000.0) as i32,
+        action: SearchAction {
+            r#type: "plugin-action".to_string(),
+            payload: Some(payload.to_string()),
+        },
+    }
+}
+
+/// Search command.
+///
+/// Each call supersedes the previous one: a search still running when a
+/// newer query arrives stops and returns `AppError::Cancelled`, so its
+/// results can never land after the newer ones. `sort` overrides
+/// `indexer.file_sort` for this query.
+///
+/// File/app results are paged: `limit` (default `indexer.result_limit`)
+/// results starting at `offset`. Pages are cut from one merged, sorted list,
+/// so asking for the next page ("show more") continues where the last ended.
+///
+/// `categories` keeps only results whose category ("Application", "File",
+/// ...) or type ("folder", ...) is listed, for filter chips.
 #[tauri::command]
 pub async fn search(
     query: String,
+    sort: Option<FileSortOrder>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    categories: Option<Vec<String>>,
+    refine: Option<String>,
     state: State<'_, AppState>,
 ) -> AppResult<Vec<SearchResult>> {
-    let parser = Parser::new();
+    let generation = state.search_generation.begin();
+    let page = Page {
+        offset: offset.unwrap_or(0),
+        limit,
+    };
+    let categories = categories.unwrap_or_default();
+    let mut results = state
+        .search_generation
+        .run(generation, run_search(query, sort, page, &categories, &state))
+        .await
+        .ok_or_else(|| AppError::Cancelled("Search superseded by a newer query".to_string()))?;
+    // File/app pages are already filtered; this covers the other results
+    category_filter::filter_categories(&mut results, &categories);
+    refine::refine_results(&mut results, refine.as_deref().unwrap_or_default());
+    Ok(results)
+}
+
+/// Which file/app results `search` returns
+#[derive(Debug, Clone, Copy)]
+struct Page {
+    offset: usize,
+    /// `None` uses `indexer.result_limit`
+    limit: Option<usize>,
+}
+
+async fn run_search(
+    query: String,
+    sort: Option<FileSortOrder>,
+    page: Page,
+    categories: &[String],
+    state: &State<'_, AppState>,
+) -> Vec<SearchResult> {
+    let config = state.get_config().await;
+    let sort = sort.unwrap_or(config.indexer.file_sort);
+    let limit = page.limit.unwrap_or(config.indexer.result_limit);
+    let parser = Parser::with_engines(&config.web_search.engines);
     let parse_result = parser.parse(&query);
 
     let results = match parse_result {
-        ParseResult::Empty => Vec::new(),
+        ParseResult::Empty => recent_results(config.recent_items.limit, state).await,
         
-        ParseResult::FileOrApp(q) => {
-            // Use hybrid search on Windows (App Indexer + Everything)
-            #[cfg(windows)]
-            {
-                hybrid_search(&q, &state).await
-            }
-            
-            #[cfg(not(windows))]
-            {
-                // Fallback to indexer search on non-Windows platforms
-                search_with_indexer(&q, &state).await
-            }
-        }
+        ParseResult::FileOrApp(q) => filtered_file_search(&q, sort, page, limit, categories, state)
+            .await
+            .into_iter()
+            .skip(page.offset)
+            .take(limit)
+            .collect(),
         
         ParseResult::Calculator(expr) => {
             // Evaluate calculator expression using new Calculator
-            match evaluate_expression(&expr) {
+            match evaluate_expression(&expr, state).await {
                 Ok(result) => vec![SearchResult {
                     id: "calc".to_string(),
                     r#type: "calculator".to_string(),
@@ -501,6 +883,40 @@ pub async fn search(
             }
         }
         
+        ParseResult::DateMath(expr) => {
+            let language = state.get_config().await.general.language;
+            match Calculator::new().evaluate_date(&expr, &language) {
+                Ok(result) => vec![SearchResult {
+                    id: "date".to_string(),
+                    r#type: "calculator".to_string(),
+                    title: result.clone(),
+                    subtitle: Some(format!("= {}", expr)),
+                    icon: None,
+                    path: None,
+                    category: "Utility".to_string(),
+                    score: 0,
+                    action: SearchAction {
+                        r#type: "copy".to_string(),
+                        payload: Some(result),
+                    },
+                }],
+                Err(e) => vec![SearchResult {
+                    id: "date-error".to_string(),
+                    r#type: "calculator".to_string(),
+                    title: "Error".to_string(),
+                    subtitle: Some(e),
+                    icon: None,
+                    path: None,
+                    category: "Utility".to_string(),
+                    score: 0,
+                    action: SearchAction {
+                        r#type: "none".to_string(),
+                        payload: None,
+                    },
+                }],
+            }
+        }
+
         ParseResult::WebSearch { engine, query, url } => {
             vec![SearchResult {
                 id: "web-search".to_string(),
@@ -586,6 +1002,16 @@ pub async fn search(
             }]
         }
         
+        ParseResult::TextCase(text) => {
+            let text = if text.is_empty() {
+                use tauri_plugin_clipboard_manager::ClipboardExt;
+                state.app_handle().clipboard().read_text().unwrap_or_default()
+            } else {
+                text
+            };
+            text_case_results(&text)
+        }
+
         ParseResult::Command(cmd) => {
             vec![SearchResult {
                 id: "command".to_string(),
@@ -602,9 +1028,539 @@ pub async fn search(
                 },
             }]
         }
+
+        ParseResult::FullText(q) => full_text_results(&q, state).await,
+
+        ParseResult::KillProcess(q) => process_results(q).await,
+
+        ParseResult::WindowSwitch(q) => window_results(q).await,
+
+        ParseResult::Define(word) => definition_results(&word, state).await,
+
+        ParseResult::Emoji(q) => emoji_results(&q, limit),
+
+        ParseResult::Snippet(q) => snippet_results(&q, state).await,
+
+        ParseResult::Palette(q) => palette_results(&q, limit, state).await,
     };
 
-    Ok(results)
+    results
+}
+
+/// Recently opened items, newest first; empty when disabled in the config
+async fn recent_results(limit: usize, state: &AppState) -> Vec<SearchResult> {
+    let config = state.get_config().await;
+    if !config.recent_items.enabled || limit == 0 {
+        return Vec::new();
+    }
+
+    // Both sources overlap, so each is asked for more than `limit`
+    let fetch = limit.saturating_mul(2);
+    let mut items: Vec<RecentItem> = match state.db.recent_launches(i64::try_from(fetch).unwrap_or(i64::MAX)).await {
+        Ok(launches) => launches
+            .into_iter()
+            .map(|usage| RecentItem {
+                path: PathBuf::from(usage.app_path),
+                name: usage.app_name,
+                last_used: usage.last_launched_at.unwrap_or(0),
+            })
+            .collect(),
+        Err(e) => {
+            tracing::debug!("Failed to read launch history: {}", e);
+            Vec::new()
+        }
+    };
+    let shell_items = tokio::task::spawn_blocking(move || recent::shell_recent_items(fetch))
+        .await
+        .unwrap_or_default();
+    items.extend(shell_items);
+
+    let mut results = Vec::new();
+    for (idx, item) in recent::existing_recent_items(items, limit).await.into_iter().enumerate() {
+        let path = item.path.to_string_lossy().to_string();
+        let icon = state
+            .icon_cache
+            .file_icon(&item.path)
+            .await
+            .map(|base64_data| format!("data:image/png;base64,{}", base64_data));
+        results.push(SearchResult {
+            id: format!("recent-{}", path),
+            r#type: "file".to_string(),
+            title: item.name,
+            subtitle: Some(path.clone()),
+            icon,
+            path: Some(path.clone()),
+            category: "Recent".to_string(),
+            score: 100 - idx as i32,
+            action: SearchAction {
+                r#type: "open".to_string(),
+                payload: Some(path),
+            },
+        });
+    }
+    results
+}
+
+/// Recently opened files, folders and apps
+#[tauri::command]
+pub async fn get_recent_items(limit: Option<usize>, state: State<'_, AppState>) -> AppResult<Vec<SearchResult>> {
+    let limit = match limit {
+        Some(limit) => limit,
+        None => state.get_config().await.recent_items.limit,
+    };
+    Ok(recent_results(limit, &state).await)
+}
+
+/// Keep `path` at the top of results that match it; new pins go last
+#[tauri::command]
+pub async fn pin_result(path: String, state: State<'_, AppState>) -> AppResult<Vec<String>> {
+    state
+        .edit_config(|config| {
+            if !config.pinned_items.iter().any(|p| p.to_lowercase() == path.to_lowercase()) {
+                config.pinned_items.push(path);
+            }
+            Ok(config.pinned_items.clone())
+        })
+        .await
+}
+
+/// Stop pinning `path`
+#[tauri::command]
+pub async fn unpin_result(path: String, state: State<'_, AppState>) -> AppResult<Vec<String>> {
+    state
+        .edit_config(|config| {
+            config.pinned_items.retain(|p| p.to_lowercase() != path.to_lowercase());
+            Ok(config.pinned_items.clone())
+        })
+        .await
+}
+
+/// Definitions of a word or phrase, as shown for "def <word>"
+#[tauri::command]
+pub async fn define_word(word: String, state: State<'_, AppState>) -> AppResult<Vec<SearchResult>> {
+    Ok(definition_results(&word, &state).await)
+}
+
+/// One result per definition; selecting one copies it. Unknown words and
+/// failed lookups give a single row saying so.
+async fn definition_results(word: &str, state: &AppState) -> Vec<SearchResult> {
+    let word = dictionary::normalize_word(word);
+    if word.is_empty() {
+        return Vec::new();
+    }
+
+    let message = |id: &str, title: String, subtitle: Option<String>| SearchResult {
+        id: id.to_string(),
+        r#type: "definition".to_string(),
+        title,
+        subtitle,
+        icon: None,
+        path: None,
+        category: "Utility".to_string(),
+        score: 0,
+        action: SearchAction {
+            r#type: "none".to_string(),
+            payload: None,
+        },
+    };
+
+    let api_url = state.get_config().await.dictionary.api_url;
+    match dictionary::define(&api_url, &word).await {
+        Ok(definitions) if definitions.is_empty() => {
+            vec![message("definition-none", format!("No definitions found for '{}'", word), None)]
+        }
+        Ok(definitions) => definitions
+            .into_iter()
+            .enumerate()
+            .map(|(idx, definition)| {
+                let detail = definition.detail();
+                SearchResult {
+                    id: format!("definition-{}", idx),
+                    r#type: "definition".to_string(),
+                    title: definition.text.clone(),
+                    subtitle: Some(if detail.is_empty() { word.clone() } else { format!("{} · {}", word, detail) }),
+                    icon: None,
+                    path: None,
+                    category: "Utility".to_string(),
+                    score: 100 - idx as i32,
+                    action: SearchAction {
+                        r#type: "copy".to_string(),
+                        payload: Some(definition.text),
+                    },
+                }
+            })
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Dictionary lookup for '{}' failed: {}", word, e);
+            vec![message("definition-error", "Error".to_string(), Some(e.to_string()))]
+        }
+    }
+}
+
+/// Emoji and symbols for "e <name>"; selecting one copies it
+fn emoji_results(query: &str, limit: usize) -> Vec<SearchResult> {
+    emoji::search(query, limit)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, found)| SearchResult {
+            id: format!("emoji-{}", idx),
+            r#type: "emoji".to_string(),
+            title: format!("{}  {}", found.glyph, found.name),
+            subtitle: found.shortcode,
+            icon: None,
+            path: None,
+            category: "Utility".to_string(),
+            score: 100 - idx as i32,
+            action: SearchAction {
+                r#type: "copy".to_string(),
+                payload: Some(found.glyph),
+            },
+        })
+        .collect()
+}
+
+/// Saved snippets for "snip <keyword>"; selecting one pastes its expansion
+async fn snippet_results(query: &str, state: &AppState) -> Vec<SearchResult> {
+    let snippets = match state.db.list_snippets().await {
+        Ok(snippets) => snippets,
+        Err(e) => {
+            tracing::warn!("Failed to load snippets: {}", e);
+            return Vec::new();
+        }
+    };
+
+    snippet::matching(snippets, query)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, found)| {
+            let preview: String = found.expansion.lines().next().unwrap_or_default().chars().take(80).collect();
+            SearchResult {
+                id: format!("snippet-{}", found.id),
+                r#type: "snippet".to_string(),
+                title: found.keyword,
+                subtitle: Some(preview),
+                icon: None,
+                path: None,
+                category: "Utility".to_string(),
+                score: 100 - idx as i32,
+                action: SearchAction {
+                    r#type: "paste-snippet".to_string(),
+                    payload: Some(found.id.to_string()),
+                },
+            }
+        })
+        .collect()
+}
+
+/// App actions for "cmd <name>": the built-in commands and those of enabled
+/// plugins, with the ones run most often and most recently first
+async fn palette_results(query: &str, limit: usize, state: &AppState) -> Vec<SearchResult> {
+    let mut registry = PaletteRegistry::new();
+    for command in state.plugin_manager.read().await.palette_commands().await {
+        registry.register(command);
+    }
+    let usage = state.db.palette_usage().await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to load palette history: {}", e);
+        HashMap::new()
+    });
+
+    registry
+        .search(query, &usage, chrono::Utc::now().timestamp())
+        .into_iter()
+        .take(limit)
+        .enumerate()
+        .map(|(idx, command)| SearchResult {
+            id: format!("palette-{}", command.id),
+            r#type: "command".to_string(),
+            title: command.label.clone(),
+            subtitle: Some(command.description.clone()),
+            icon: None,
+            path: None,
+            category: "Command".to_string(),
+            score: 100 - idx as i32,
+            action: SearchAction {
+                r#type: "palette-command".to_string(),
+                payload: serde_json::to_string(command).ok(),
+            },
+        })
+        .collect()
+}
+
+/// Count a run of the palette command `id`, so it ranks higher next time
+#[tauri::command]
+pub async fn record_palette_use(id: String, state: State<'_, AppState>) -> AppResult<()> {
+    state.db.record_palette_use(&id).await
+}
+
+/// Running processes for "kill <name>". Selecting one asks for confirmation
+/// in the UI, which then calls `kill_process`.
+async fn process_results(query: String) -> Vec<SearchResult> {
+    let processes = tokio::task::spawn_blocking(move || {
+        process::list_processes(&query, system::PROCESS_LIMIT)
+    })
+    .await
+    .unwrap_or_default();
+
+    processes
+        .into_iter()
+        .enumerate()
+        .map(|(idx, p)| SearchResult {
+            id: format!("process-{}", p.pid),
+            r#type: "process".to_string(),
+            title: p.name,
+            subtitle: Some(format!("PID {} · {}", p.pid, process::format_memory(p.memory))),
+            icon: None,
+            path: None,
+            category: "Process".to_string(),
+            score: 100 - idx as i32,
+            action: SearchAction {
+                r#type: "kill-process".to_string(),
+                payload: Some(p.pid.to_string()),
+            },
+        })
+        .collect()
+}
+
+/// Open windows for "w <title>". Selecting one calls `focus_window`.
+async fn window_results(query: String) -> Vec<SearchResult> {
+    let windows = tokio::task::spawn_blocking(automation::list_windows_blocking)
+        .await
+        .ok()
+        .and_then(|result| result.ok())
+        .unwrap_or_default();
+
+    windows
+        .into_iter()
+        .filter(|w| w.matches(&query))
+        .enumerate()
+        .map(|(idx, w)| SearchResult {
+            id: format!("window-{}", w.hwnd),
+            r#type: "window".to_string(),
+            subtitle: Some(if w.minimized {
+                format!("{} · minimized", w.process)
+            } else {
+                w.process.clone()
+            }),
+            title: w.title,
+            icon: None,
+            path: None,
+            category: "Window".to_string(),
+            score: 100 - idx as i32,
+            action: SearchAction {
+                r#type: "focus-window".to_string(),
+                payload: Some(w.hwnd.to_string()),
+            },
+        })
+        .collect()
+}
+
+/// Files whose content contains the query, with the matching text as subtitle
+async fn full_text_results(query: &str, state: &State<'_, AppState>) -> Vec<SearchResult> {
+    state
+        .indexer
+        .search_contents(query, 20)
+        .await
+        .into_iter()
+        .enumerate()
+        .map(|(idx, found)| {
+            let path = found.path.to_string_lossy().to_string();
+            SearchResult {
+                id: format!("ft-{}", path),
+                r#type: "file".to_string(),
+                title: found
+                    .path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.clone()),
+                subtitle: Some(found.snippet),
+                icon: Some("📄".to_string()),
+                path: Some(path.clone()),
+                category: "File".to_string(),
+                score: idx as i32,
+                action: SearchAction {
+                    r#type: "open".to_string(),
+                    payload: Some(path),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Set the folders searched by content ("ft " prefix) and rebuild their
+/// index. Returns the number of files indexed.
+#[tauri::command]
+pub async fn set_full_text_directories(
+    directories: Vec<PathBuf>,
+    state: State<'_, AppState>,
+) -> AppResult<usize> {
+    if let Some(missing) = directories.iter().find(|dir| !dir.is_dir()) {
+        return Err(AppError::NotFound(format!("Folder not found: {}", missing.display())));
+    }
+
+    state
+        .edit_config(|config| {
+            config.full_text.directories = directories;
+            Ok(())
+        })
+        .await?;
+
+    Ok(state.index_full_text().await)
+}
+
+/// Directories indexed for file search
+#[tauri::command]
+pub async fn get_index_roots(state: State<'_, AppState>) -> AppResult<Vec<PathBuf>> {
+    Ok(state.indexer.roots().await)
+}
+
+/// Index and watch another directory. Returns the total number of indexed files.
+#[tauri::command]
+pub async fn add_index_root(path: PathBuf, state: State<'_, AppState>) -> AppResult<usize> {
+    if std::fs::symlink_metadata(&path).is_err() {
+        return Err(AppError::NotFound(format!("Folder not found: {}", path.display())));
+    }
+    state.indexer.add_root(&path, true).await?;
+    state.save_index_roots().await?;
+    Ok(state.indexer.file_count().await)
+}
+
+/// Index health for the diagnostics panel
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStats {
+    pub file_count: usize,
+    /// Start Menu apps (Windows only)
+    pub app_count: Option<usize>,
+    pub everything_available: bool,
+    /// Whether Everything.exe answers, as opposed to only the DLL being loaded (Windows only)
+    #[cfg(windows)]
+    pub everything_status: everything_service::EverythingStatus,
+    pub watcher_running: bool,
+    /// Unix time (seconds) of the last directory scan
+    pub last_refresh: Option<i64>,
+}
+
+/// Outcome of running a query that should always match
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexSelfTest {
+    pub query: String,
+    pub result_count: usize,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Report index state so "search finds nothing" can be diagnosed
+#[tauri::command]
+pub async fn index_stats(state: State<'_, AppState>) -> AppResult<IndexStats> {
+    #[cfg(windows)]
+    let (app_count, everything_available) = (Some(state.app_indexer.app_count()), everything_service::is_available());
+    #[cfg(not(windows))]
+    let (app_count, everything_available) = (None, false);
+
+    Ok(IndexStats {
+        file_count: state.indexer.file_count().await,
+        app_count,
+        everything_available,
+        #[cfg(windows)]
+        everything_status: tokio::task::spawn_blocking(everything_service::status)
+            .await
+            .map_err(|e| AppError::Unknown(format!("Everything probe failed: {}", e)))?,
+        watcher_running: state.indexer.is_watching().await,
+        last_refresh: state.indexer.last_refresh(),
+    })
+}
+
+/// Icons for many paths in one call, as `data:` URLs keyed by path. The
+/// frontend asks for the rows it is about to show that still lack an icon.
+#[tauri::command]
+pub async fn extract_icons_batch(
+    paths: Vec<String>,
+    state: State<'_, AppState>,
+) -> AppResult<HashMap<String, Option<String>>> {
+    Ok(state.icon_cache.data_urls(paths).await)
+}
+
+/// Search for something that must exist: our own executable through
+/// Everything on Windows, an already indexed file name otherwise.
+#[tauri::command]
+pub async fn index_self_test(state: State<'_, AppState>) -> AppResult<IndexSelfTest> {
+    let started = Instant::now();
+
+    #[cfg(windows)]
+    if everything_service::is_available() {
+        let query = std::env::current_exe()?
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let outcome = everything_service::search_files(query.clone(), Some(10), Default::default())
+            .await
+            .map(|results| results.len());
+        return Ok(self_test_report(query, outcome, started));
+    }
+
+    let (query, outcome) = match state.indexer.sample_name().await {
+        Some(name) => {
+            let count = state.indexer.search(&name, 0, 20).await.len();
+            (name, Ok(count))
+        }
+        None => (String::new(), Err("The file index is empty".to_string())),
+    };
+    Ok(self_test_report(query, outcome, started))
+}
+
+fn self_test_report(query: String, outcome: Result<usize, String>, started: Instant) -> IndexSelfTest {
+    let (result_count, error) = match outcome {
+        Ok(0) => (0, Some("Query returned no results".to_string())),
+        Ok(count) => (count, None),
+        Err(e) => (0, Some(e)),
+    };
+    IndexSelfTest {
+        query,
+        result_count,
+        duration_ms: started.elapsed().as_millis() as u64,
+        error,
+    }
+}
+
+/// Stop indexing a directory and drop its files. Returns the total number of indexed files.
+#[tauri::command]
+pub async fn remove_index_root(path: PathBuf, state: State<'_, AppState>) -> AppResult<usize> {
+    state.indexer.remove_root(&path).await?;
+    state.save_index_roots().await?;
+    Ok(state.indexer.file_count().await)
+}
+
+/// Rescan the indexed directories for files added or deleted while they
+/// were not watched. Returns the total number of indexed files.
+#[tauri::command]
+pub async fn refresh_index(state: State<'_, AppState>) -> AppResult<usize> {
+    let (dropped, added) = state.refresh_index().await;
+    tracing::info!("Refreshed file index: {} dropped, {} added", dropped, added);
+    Ok(state.indexer.file_count().await)
+}
+
+/// One copyable result per case style
+fn text_case_results(text: &str) -> Vec<SearchResult> {
+    if text_case::tokenize(text).is_empty() {
+        return Vec::new();
+    }
+
+    text_case::convert_all(text)
+        .into_iter()
+        .map(|(style, converted)| SearchResult {
+            id: format!("case-{}", style.id()),
+            r#type: "text-case".to_string(),
+            title: converted.clone(),
+            subtitle: Some(style.label().to_string()),
+            icon: None,
+            path: None,
+            category: "Utility".to_string(),
+            score: 0,
+            action: SearchAction {
+                r#type: "copy".to_string(),
+                payload: Some(converted),
+            },
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -618,9 +1574,9 @@ pub struct CalculatorResult {
 #[tauri::command]
 pub async fn calculate(
     expression: String,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> AppResult<CalculatorResult> {
-    let result = match evaluate_expression(&expression) {
+    let result = match evaluate_expression(&expression, &state).await {
         Ok(value) => value,
         Err(e) => e,
     };
@@ -632,12 +1588,294 @@ pub async fn calculate(
     })
 }
 
+/// Inline calculator result for input that is clearly a calculation, without
+/// running the search pipeline. `None` when the input is not a calculation
+/// or cannot be evaluated, so the UI can simply show nothing.
+#[tauri::command]
+pub async fn quick_eval(expression: String, state: State<'_, AppState>) -> AppResult<Option<String>> {
+    let trimmed = expression.trim();
+    let expr = match trimmed.strip_prefix('=') {
+        Some(rest) => rest.trim(),
+        None if is_calculation(trimmed) => trimmed,
+        None => return Ok(None),
+    };
+    if expr.is_empty() {
+        return Ok(None);
+    }
+
+    let calc = calculator_for(expr, &state).await;
+    Ok(calc
+        .evaluate_with_unit(expr)
+        .ok()
+        .filter(|evaluation| evaluation.value.is_finite())
+        .map(|evaluation| calc.format_result(evaluation.value, evaluation.unit.as_deref())))
+}
+
 /// Evaluate a mathematical expression with unit conversion support
-fn evaluate_expression(expr: &str) -> Result<String, String> {
-    let calc = Calculator::new();
-    
-    match calc.evaluate(expr) {
-        Ok(value) => Ok(calc.format_result(value)),
-        Err(e) => Err(e),
+async fn evaluate_expression(expr: &str, state: &State<'_, AppState>) -> Result<String, String> {
+    let calc = calculator_for(expr, state).await;
+    let evaluation = calc.evaluate_with_unit(expr)?;
+    Ok(calc.format_result(evaluation.value, evaluation.unit.as_deref()))
+}
+
+/// Calculator for `expr`. Currency rates are only fetched when the
+/// expression is a conversion that involves a currency code.
+async fn calculator_for(expr: &str, state: &State<'_, AppState>) -> Calculator {
+    if !is_currency_conversion(expr) {
+        return Calculator::new();
+    }
+    let endpoint = state.get_config().await.calculator.currency_api_url;
+    match currency::get_rates(&endpoint).await {
+        Ok(rates) => Calculator::with_currency_rates(rates),
+        Err(e) => {
+            tracing::warn!("Failed to load currency rates: {}", e);
+            Calculator::new()
+        }
+    }
+}
+
+/// The parts of a search result needed to run an action on it
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActionTarget {
+    pub r#type: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    pub action: SearchAction,
+}
+
+/// Run the action bound to Enter + `modifier` for a search result.
+///
+/// Returns the name of the action that was executed. Actions other than the
+/// built-in path/clipboard ones (e.g. "web-search", "ai-query") are handled by
+/// the UI and rejected here.
+#[tauri::command]
+pub async fn execute_action(
+    target: ActionTarget,
+    modifier: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<String> {
+    let bindings = state.get_config().await.result_actions.bindings;
+    let action = actions::resolve_action(
+        &bindings,
+        &target.r#type,
+        modifier.as_deref().unwrap_or(""),
+        &target.action.r#type,
+    );
+
+    let app = state.app_handle();
+    let path = target
+        .path
+        .clone()
+        .filter(|p| !p.is_empty())
+        .or_else(|| target.action.payload.clone());
+    let require_path = || {
+        path.clone()
+            .ok_or_else(|| AppError::NotFound(format!("Result has no path for action '{}'", action)))
+    };
+
+    tracing::info!("Executing action '{}' for {} result", action, target.r#type);
+
+    match action.as_str() {
+        actions::ACTION_OPEN => system::launch_file(&state, &require_path()?).await?,
+        actions::ACTION_RUN_AS_ADMIN => system::launch_elevated(require_path()?).await?,
+        actions::ACTION_REVEAL => system::reveal_path(app, &require_path()?)?,
+        actions::ACTION_OPEN_WITH => system::open_with_chooser(app, &require_path()?)?,
+        actions::ACTION_COPY_PATH => system::write_clipboard_text(app, require_path()?)?,
+        // Reported as a clipboard error so the launcher shows it instead of
+        // falling back to opening the item
+        actions::ACTION_COPY_DATA_URL => system::copy_data_url(app, require_path()?)
+            .await
+            .map_err(|e| match e {
+                AppError::Clipboard(_) => e,
+                other => AppError::Clipboard(other.to_string()),
+            })?,
+        actions::ACTION_COPY => {
+            use tauri_plugin_clipboard_manager::ClipboardExt;
+            let text = target.action.payload.clone().unwrap_or_default();
+            app.clipboard().write_text(text)?;
+        }
+        other => {
+            return Err(AppError::Unknown(format!(
+                "Action '{}' must be handled by the UI",
+                other
+            )))
+        }
+    }
+
+    Ok(action)
+}
+
+/// Search browser history of the allowed profiles, best matches first.
+/// Results open their URL like web results do.
+#[tauri::command]
+pub async fn search_browser_history(
+    query: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<SearchResult>> {
+    let config = state.get_config().await.browser_history;
+    let query = query.trim();
+    if !config.enabled || query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let profiles: Vec<_> = browser_history::discover_profiles()
+        .into_iter()
+        .filter(|profile| profile.is_allowed(&config.profiles))
+        .collect();
+    let entries =
+        browser_history::search(&profiles, query, config.max_age_days, limit.unwrap_or(20)).await;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| SearchResult {
+            id: format!("history-{}", entry.url),
+            r#type: "history".to_string(),
+            title: entry.title,
+            subtitle: Some(entry.url.clone()),
+            icon: None,
+            path: None,
+            category: "Web".to_string(),
+            score: entry.frecency.min(i32::MAX as i64) as i32,
+            action: SearchAction {
+                r#type: "web-search".to_string(),
+                payload: Some(entry.url),
+            },
+        })
+        .collect())
+}
+
+/// Cancellation flag of the running duplicate scan, if any
+static DUPLICATE_SCAN: Lazy<Mutex<Option<Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateFile {
+    pub path: String,
+    /// "reveal" and "delete-duplicate" actions for this copy
+    pub actions: Vec<SearchAction>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCluster {
+    pub size: u64,
+    pub hash: String,
+    pub files: Vec<DuplicateFile>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicatesResult {
+    pub clusters: Vec<DuplicateCluster>,
+    /// False if the scan was cancelled or stopped at the work cap
+    pub complete: bool,
+}
+
+/// Find duplicate files (same size and content) among indexed files.
+/// Starting a new scan cancels the previous one.
+#[tauri::command]
+pub async fn find_duplicates(
+    root: Option<String>,
+    min_size: Option<u64>,
+    state: State<'_, AppState>,
+) -> AppResult<DuplicatesResult> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    if let Some(previous) = DUPLICATE_SCAN.lock().replace(cancel.clone()) {
+        previous.store(true, Ordering::SeqCst);
+    }
+
+    let mut options = DuplicateOptions::default();
+    if let Some(min_size) = min_size {
+        options.min_size = min_size;
+    }
+    let root = root.filter(|r| !r.is_empty()).map(std::path::PathBuf::from);
+    let report = state
+        .indexer
+        .find_duplicates(root.as_deref(), &options, &cancel)
+        .await;
+
+    {
+        let mut current = DUPLICATE_SCAN.lock();
+        if current.as_ref().is_some_and(|c| Arc::ptr_eq(c, &cancel)) {
+            *current = None;
+        }
+    }
+
+    let clusters = report
+        .groups
+        .into_iter()
+        .map(|group| DuplicateCluster {
+            size: group.size,
+            hash: group.hash,
+            files: group
+                .paths
+                .into_iter()
+                .map(|path| {
+                    let path = path.to_string_lossy().to_string();
+                    let action = |r#type: &str| SearchAction {
+                        r#type: r#type.to_string(),
+                        payload: Some(path.clone()),
+                    };
+                    DuplicateFile {
+                        actions: vec![action(actions::ACTION_REVEAL), action("delete-duplicate")],
+                        path,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(DuplicatesResult {
+        clusters,
+        complete: report.complete,
+    })
+}
+
+/// Cancel the running duplicate scan; it returns the groups found so far
+#[tauri::command]
+pub async fn cancel_find_duplicates() -> AppResult<()> {
+    if let Some(cancel) = DUPLICATE_SCAN.lock().take() {
+        cancel.store(true, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+/// Delete one copy of a duplicate. `keep` must still exist with identical
+/// content, so the last copy can never be deleted this way.
+#[tauri::command]
+pub async fn delete_duplicate(
+    path: String,
+    keep: String,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let (target, kept) = (std::path::PathBuf::from(&path), std::path::PathBuf::from(&keep));
+    if target == kept {
+        return Err(AppError::Unknown("Cannot delete the copy being kept".to_string()));
+    }
+
+    let (target_hash, kept_hash) = {
+        let (target, kept) = (target.clone(), kept.clone());
+        tokio::task::spawn_blocking(move || Ok::<_, std::io::Error>((hash_file(&target)?, hash_file(&kept)?)))
+            .await
+            .map_err(|e| AppError::Unknown(e.to_string()))??
+    };
+    if target_hash != kept_hash {
+        return Err(AppError::Unknown(format!("{} and {} are no longer identical", path, keep)));
+    }
+
+    tokio::fs::remove_file(&target).await?;
+    state.indexer.remove_file(&target).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selecting_heart_copies_it() {
+        let results = emoji_results("heart", 5);
+        let heart = &results[0];
+        assert_eq!(heart.action.r#type, "copy");
+        assert_eq!(heart.action.payload.as_deref(), Some("\u{2764}\u{FE0F}"));
+        assert_eq!(heart.title, "\u{2764}\u{FE0F}  red heart");
     }
 }
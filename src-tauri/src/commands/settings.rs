@@ -1,4 +1,5 @@
-use crate::app::{config::AppConfig, error::AppResult, state::AppState};
+use crate::app::{config::{AppConfig, AutohideConfig}, error::{AppError, AppResult}, migration, state::AppState};
+use crate::core::parser::{validate_url_template, WebSearchEngine};
 use std::path::PathBuf;
 use tauri::State;
 
@@ -8,13 +9,22 @@ pub async fn get_config(state: State<'_, AppState>) -> AppResult<AppConfig> {
     Ok(state.get_config().await)
 }
 
-/// Update application config
+/// Update application config. `config` may be partial: fields it leaves
+/// out keep their current values. A new invalid value rejects the whole
+/// update, naming every bad field. Returns the updated config.
 #[tauri::command]
 pub async fn update_config(
-    config: AppConfig,
+    config: serde_json::Value,
     state: State<'_, AppState>,
-) -> AppResult<()> {
-    state.update_config(config).await
+) -> AppResult<AppConfig> {
+    state
+        .edit_config(|current| {
+            let updated = current.merge(config)?;
+            updated.validate_update(current)?;
+            *current = updated;
+            Ok(current.clone())
+        })
+        .await
 }
 
 /// Reset config to defaults
@@ -25,6 +35,30 @@ pub async fn reset_config(state: State<'_, AppState>) -> AppResult<AppConfig> {
     Ok(default_config)
 }
 
+/// Delete every cached icon. Returns the number of icons removed.
+#[tauri::command]
+pub async fn clear_icon_cache(state: State<'_, AppState>) -> AppResult<usize> {
+    state.icon_cache.clear().await
+}
+
+/// Replace the launcher blur graces and shortcut debounces; they apply
+/// from the next focus change or shortcut press
+#[tauri::command]
+pub async fn update_autohide(
+    autohide: AutohideConfig,
+    state: State<'_, AppState>,
+) -> AppResult<AutohideConfig> {
+    state
+        .edit_config(|current| {
+            let mut config = current.clone();
+            config.autohide = autohide.clone();
+            config.validate_update(current)?;
+            *current = config;
+            Ok(autohide)
+        })
+        .await
+}
+
 /// Export config to file
 #[tauri::command]
 pub async fn export_config(path: PathBuf, state: State<'_, AppState>) -> AppResult<()> {
@@ -63,7 +97,62 @@ pub async fn import_config(path: PathBuf, state: State<'_, AppState>) -> AppResu
     }
     
     let yaml = std::fs::read_to_string(canonical_path)?;
-    let config: AppConfig = serde_yaml::from_str(&yaml)?;
+    // Exports from older versions are upgraded like the settings file
+    let config = migration::parse_config(serde_yaml::from_str(&yaml)?)?;
+    config.validate()?;
     state.update_config(config.clone()).await?;
     Ok(config)
 }
+
+/// List configured web search engines
+#[tauri::command]
+pub async fn list_search_engines(state: State<'_, AppState>) -> AppResult<Vec<WebSearchEngine>> {
+    Ok(state.get_config().await.web_search.engines)
+}
+
+/// Add a web search engine, replacing any engine with the same keyword
+#[tauri::command]
+pub async fn add_search_engine(
+    engine: WebSearchEngine,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<WebSearchEngine>> {
+    let keyword = engine.keyword.trim().to_lowercase();
+    if keyword.is_empty() || keyword.contains(char::is_whitespace) {
+        return Err(AppError::Config(
+            "Search keyword must be a single non-empty word".to_string(),
+        ));
+    }
+    if !validate_url_template(&engine.url_template) {
+        return Err(AppError::Config(
+            "Search URL template must contain {query}".to_string(),
+        ));
+    }
+
+    state
+        .edit_config(|config| {
+            let engines = &mut config.web_search.engines;
+            engines.retain(|e| !e.keyword.eq_ignore_ascii_case(&keyword));
+            engines.push(WebSearchEngine { keyword, ..engine });
+            Ok(engines.clone())
+        })
+        .await
+}
+
+/// Remove the web search engine bound to `keyword`
+#[tauri::command]
+pub async fn remove_search_engine(
+    keyword: String,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<WebSearchEngine>> {
+    state
+        .edit_config(|config| {
+            let engines = &mut config.web_search.engines;
+            let before = engines.len();
+            engines.retain(|e| !e.keyword.eq_ignore_ascii_case(keyword.trim()));
+            if engines.len() == before {
+                return Err(AppError::NotFound(format!("Search engine '{}' not found", keyword)));
+            }
+            Ok(engines.clone())
+        })
+        .await
+}
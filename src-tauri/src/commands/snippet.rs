@@ -0,0 +1,49 @@
+use crate::app::{error::{AppError, AppResult}, state::AppState};
+use crate::commands::clipboard::paste_content;
+use crate::core::clipboard::Transformed;
+use crate::core::snippet::{self, Snippet};
+use tauri::State;
+
+/// Save a snippet; an existing snippet with the same keyword gets the new text
+#[tauri::command]
+pub async fn add_snippet(keyword: String, expansion: String, state: State<'_, AppState>) -> AppResult<Snippet> {
+    let keyword = snippet::normalize_keyword(&keyword)
+        .ok_or_else(|| AppError::Parse("A snippet keyword must be a single word".to_string()))?;
+    if expansion.is_empty() {
+        return Err(AppError::Parse("A snippet needs text to expand to".to_string()));
+    }
+    state.db.add_snippet(&keyword, &expansion).await
+}
+
+#[tauri::command]
+pub async fn delete_snippet(id: i64, state: State<'_, AppState>) -> AppResult<()> {
+    if !state.db.delete_snippet(id).await? {
+        return Err(AppError::NotFound(format!("Snippet {} not found", id)));
+    }
+    Ok(())
+}
+
+/// All snippets, by keyword
+#[tauri::command]
+pub async fn list_snippets(state: State<'_, AppState>) -> AppResult<Vec<Snippet>> {
+    state.db.list_snippets().await
+}
+
+/// Expand a snippet's placeholders and paste the result into the focused
+/// app, restoring the clipboard afterwards like history pastes
+#[tauri::command]
+pub async fn paste_snippet(id: i64, state: State<'_, AppState>) -> AppResult<()> {
+    let snippet = state
+        .db
+        .get_snippet(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Snippet {} not found", id)))?;
+    let clipboard = if snippet::uses_clipboard(&snippet.expansion) {
+        use tauri_plugin_clipboard_manager::ClipboardExt;
+        state.app_handle().clipboard().read_text().unwrap_or_default()
+    } else {
+        String::new()
+    };
+    let text = snippet::expand(&snippet.expansion, chrono::Local::now(), &clipboard);
+    paste_content(Transformed::Text(text), state.inner()).await
+}
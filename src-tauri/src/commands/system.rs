@@ -1,33 +1,294 @@
-use crate::app::{error::AppResult, state::AppState};
-use tauri::{Manager, State};
+use crate::app::startup::{self, StartupStatus};
+use crate::app::{error::{AppError, AppResult}, migration, state::AppState};
+use crate::commands::capture::{self, RetainedPin};
+use crate::core::data_url;
+use crate::core::diagnostics::{CheckResult, DiagnosticsReport};
+use crate::core::elevation;
+use crate::core::launch::{self, LaunchKind};
+use crate::core::open_with::OpenWithApp;
+use crate::core::process::{self, ProcessInfo};
+use crate::core::quick_note::append_note;
+use crate::core::shell::{self, CommandOutput};
+use crate::core::recovery::{ClosedItem, RecoveryStack};
+use crate::core::screenshot;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{Emitter, Manager, State};
 use tauri_plugin_shell::ShellExt;
 
+/// Recently closed pins and AI conversations
+pub(crate) static RECENTLY_CLOSED: Lazy<Mutex<RecoveryStack<RetainedPin>>> =
+    Lazy::new(|| Mutex::new(RecoveryStack::default()));
+
 /// Open path in system file manager or default application
 #[tauri::command]
 pub async fn open_path(path: String, state: State<'_, AppState>) -> AppResult<()> {
     tracing::info!("Opening path: {}", path);
-    
-    // Use tauri-plugin-shell to open the path
-    let shell = state.app_handle().shell();
+    let always_elevate = state.get_config().await.launch.always_elevate;
+    if cfg!(target_os = "windows") && elevation::should_always_elevate(&always_elevate, &path) {
+        return launch_elevated(path).await;
+    }
+    open_with_default(&state.app_handle(), &path)
+}
+
+/// Open a file with `app_path` (an executable, shortcut or app bundle)
+/// instead of its default handler
+#[tauri::command]
+pub async fn open_with(path: String, app_path: String) -> AppResult<()> {
+    tracing::info!("Opening {} with {}", path, app_path);
+    if !std::path::Path::new(&path).exists() {
+        return Err(AppError::NotFound(path));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let request = crate::core::open_with::windows_launch(&path, &app_path);
+        tauri::async_runtime::spawn_blocking(move || crate::platform::windows::shell_execute(&request))
+            .await
+            .map_err(|e| AppError::Unknown(e.to_string()))?
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let output = tokio::process::Command::new("open")
+            .args(crate::core::open_with::macos_open_args(&path, &app_path))
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(AppError::Shell(format!(
+                "Failed to open {} with {}: {}",
+                path,
+                app_path,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let _ = app_path;
+        Err(AppError::Shell("Open with is not supported on this platform".to_string()))
+    }
+}
+
+/// Apps registered for the file's type, for an "Open with…" menu. Empty
+/// when there are none or the platform cannot list them.
+#[tauri::command]
+pub async fn get_open_with_candidates(path: String) -> AppResult<Vec<OpenWithApp>> {
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    {
+        Ok(crate::platform::open_with_candidates(std::path::Path::new(&path)).await)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let _ = path;
+        Ok(Vec::new())
+    }
+}
+
+/// Launch a file search result and report whether it started.
+///
+/// Shortcuts, executables and app bundles start via `platform::launch_app`,
+/// folders open in Explorer/Finder and documents with their default handler.
+/// Successful launches count towards the result's ranking.
+#[tauri::command]
+pub async fn launch_path(path: String, state: State<'_, AppState>) -> AppResult<()> {
+    launch_file(&state, &path).await
+}
+
+pub(crate) async fn launch_file(state: &AppState, path: &str) -> AppResult<()> {
+    let target = std::path::Path::new(path);
+    if !target.exists() {
+        return Err(AppError::NotFound(path.to_string()));
+    }
+
+    let kind = launch::classify(target);
+    tracing::info!("Launching {:?}: {}", kind, path);
+    let config = state.get_config().await;
+    let always_elevate = &config.launch.always_elevate;
+    match kind {
+        LaunchKind::App
+            if cfg!(target_os = "windows")
+                && elevation::should_always_elevate(always_elevate, path) =>
+        {
+            launch_elevated(path.to_string()).await?
+        }
+        LaunchKind::App => launch_app(target).await?,
+        LaunchKind::Folder | LaunchKind::Document => open_with_default(state.app_handle(), path)?,
+    }
+
+    // Cached results were ranked without this launch
+    state.indexer.record_access_path(target).await;
+    state.search_cache.write().await.invalidate();
+    if config.recent_items.enabled {
+        let name = target
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        if let Err(e) = state.db.record_app_launch(path, &name).await {
+            tracing::debug!("Failed to record launch of {}: {}", path, e);
+        }
+    }
+    Ok(())
+}
+
+/// Start an application without waiting for it to exit
+async fn launch_app(path: &std::path::Path) -> AppResult<()> {
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    {
+        crate::platform::launch_app(path).await.map_err(AppError::Shell)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        Err(AppError::Shell(format!(
+            "Launching applications is not supported on this platform: {}",
+            path.display()
+        )))
+    }
+}
+
+/// Launch an app or executable as administrator (Windows only).
+/// Declining the UAC prompt returns a `Cancelled` error.
+#[tauri::command]
+pub async fn launch_elevated(path: String) -> AppResult<()> {
+    tracing::info!("Launching elevated: {}", path);
+    tauri::async_runtime::spawn_blocking(move || run_elevated(&path))
+        .await
+        .map_err(|e| AppError::Unknown(e.to_string()))?
+}
+
+/// Run `path` with the `runas` verb
+fn run_elevated(path: &str) -> AppResult<()> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::platform::windows::shell_execute(&elevation::elevated_launch(path))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        Err(AppError::Unknown(
+            "Run as administrator is only supported on Windows".to_string(),
+        ))
+    }
+}
+
+/// Open a path with its default handler via tauri-plugin-shell
+pub(crate) fn open_with_default(app: &tauri::AppHandle, path: &str) -> AppResult<()> {
+    let shell = app.shell();
     
     #[cfg(target_os = "macos")]
     {
-        shell.command("open").arg(&path).spawn()?;
+        shell.command("open").arg(path).spawn()?;
     }
     
     #[cfg(target_os = "windows")]
     {
-        shell.command("explorer").arg(&path).spawn()?;
+        shell.command("explorer").arg(path).spawn()?;
     }
     
     #[cfg(target_os = "linux")]
     {
-        shell.command("xdg-open").arg(&path).spawn()?;
+        shell.command("xdg-open").arg(path).spawn()?;
     }
     
     Ok(())
 }
 
+/// Show a file or folder in Explorer/Finder with the item selected.
+/// Returns once the file manager has been started.
+#[tauri::command]
+pub async fn reveal_in_file_manager(path: String, state: State<'_, AppState>) -> AppResult<()> {
+    tracing::info!("Revealing path: {}", path);
+    reveal_path(state.app_handle(), &path)
+}
+
+/// Copy a result's path to the clipboard
+#[tauri::command]
+pub async fn copy_path_to_clipboard(path: String, state: State<'_, AppState>) -> AppResult<()> {
+    write_clipboard_text(state.app_handle(), path)
+}
+
+/// Copy an image file to the clipboard as a `data:` URL. Files over
+/// `data_url::MAX_DATA_URL_FILE_BYTES` are refused.
+#[tauri::command]
+pub async fn copy_file_as_data_url(path: String, state: State<'_, AppState>) -> AppResult<()> {
+    copy_data_url(state.app_handle(), path).await
+}
+
+pub(crate) fn write_clipboard_text(app: &tauri::AppHandle, text: String) -> AppResult<()> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+    app.clipboard().write_text(text)?;
+    Ok(())
+}
+
+pub(crate) async fn copy_data_url(app: &tauri::AppHandle, path: String) -> AppResult<()> {
+    let url = tokio::task::spawn_blocking(move || {
+        data_url::image_data_url(std::path::Path::new(&path), data_url::MAX_DATA_URL_FILE_BYTES)
+    })
+    .await
+    .map_err(|e| AppError::Unknown(format!("Reading file failed: {}", e)))??;
+    write_clipboard_text(app, url)
+}
+
+/// Show a path in the system file manager with the item selected
+pub(crate) fn reveal_path(app: &tauri::AppHandle, path: &str) -> AppResult<()> {
+    if !std::path::Path::new(path).exists() {
+        return Err(AppError::NotFound(format!("Cannot reveal missing path: {}", path)));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        app.shell().command("open").args(["-R", path]).spawn()?;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // Explorer only understands `/select,"path"` verbatim, which the
+        // usual argument quoting would escape
+        use std::os::windows::process::CommandExt;
+        let _ = app;
+        std::process::Command::new("explorer")
+            .raw_arg(format!("/select,\"{}\"", path))
+            .spawn()?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // No portable "select" support; open the containing folder instead
+        let parent = std::path::Path::new(path)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        app.shell().command("xdg-open").arg(parent).spawn()?;
+    }
+
+    Ok(())
+}
+
+/// Ask the OS which application to open a path with.
+/// Falls back to the default handler where no chooser is available.
+pub(crate) fn open_with_chooser(app: &tauri::AppHandle, path: &str) -> AppResult<()> {
+    #[cfg(target_os = "windows")]
+    {
+        app.shell()
+            .command("rundll32")
+            .args(["shell32.dll,OpenAs_RunDLL", path])
+            .spawn()?;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        open_with_default(app, path)
+    }
+}
+
 /// Open URL in default browser
 #[tauri::command]
 pub async fn open_url(url: String, state: State<'_, AppState>) -> AppResult<()> {
@@ -128,3 +389,198 @@ pub async fn app_ready(window: tauri::Window) -> Result<(), String> {
     
     Ok(())
 }
+
+/// What `reopen_recently_closed` restored
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReopenedItem {
+    Pin { label: String },
+    Conversation { conversation_id: String },
+}
+
+/// Frontend reports which conversation the AI panel shows (None when cleared)
+#[tauri::command]
+pub async fn set_active_conversation(conversation_id: Option<String>) -> AppResult<()> {
+    RECENTLY_CLOSED.lock().set_active_conversation(conversation_id);
+    Ok(())
+}
+
+/// Record that the AI panel was closed, so its conversation can be reopened
+pub fn note_ai_panel_closed() {
+    RECENTLY_CLOSED.lock().conversation_closed();
+}
+
+/// Restore the most recently closed pin window or AI conversation
+#[tauri::command]
+pub async fn reopen_recently_closed(state: State<'_, AppState>) -> AppResult<ReopenedItem> {
+    let app = state.app_handle();
+    let item = RECENTLY_CLOSED
+        .lock()
+        .pop()
+        .ok_or_else(|| AppError::NotFound("Nothing to reopen".to_string()))?;
+
+    match item {
+        ClosedItem::Pin(pin) => {
+            let label = capture::open_pin_window(app, pin)?;
+            Ok(ReopenedItem::Pin { label })
+        }
+        ClosedItem::Conversation(conversation_id) => {
+            let window = app
+                .get_webview_window("ai")
+                .ok_or_else(|| AppError::NotFound("AI window not available".to_string()))?;
+            if let Some(main_window) = app.get_webview_window("main") {
+                let _ = main_window.hide();
+            }
+            window.show()?;
+            window.set_focus()?;
+            window.emit("ai:open-conversation", &conversation_id)?;
+            Ok(ReopenedItem::Conversation { conversation_id })
+        }
+    }
+}
+
+/// Processes offered for one "kill" query
+pub(crate) const PROCESS_LIMIT: usize = 20;
+
+/// Running processes whose name contains `query`, largest first
+#[tauri::command]
+pub async fn list_processes(query: String) -> AppResult<Vec<ProcessInfo>> {
+    tokio::task::spawn_blocking(move || process::list_processes(&query, PROCESS_LIMIT))
+        .await
+        .map_err(|e| AppError::Unknown(format!("Process listing failed: {}", e)))
+}
+
+/// Force-quit a process. Never triggered by a search result on its own;
+/// the UI calls this after the user confirms.
+#[tauri::command]
+pub async fn kill_process(pid: u32) -> AppResult<()> {
+    tokio::task::spawn_blocking(move || process::kill_process(pid))
+        .await
+        .map_err(|e| AppError::Unknown(format!("Kill task failed: {}", e)))?
+}
+
+/// Run a "> command" through the system shell. Refused unless enabled in
+/// settings; like `kill_process`, only called after the user confirms.
+#[tauri::command]
+pub async fn execute_system_command(cmd: String, state: State<'_, AppState>) -> AppResult<CommandOutput> {
+    let enabled = state.get_config().await.features.enable_shell_commands;
+    shell::run_command(&cmd, enabled, shell::COMMAND_TIMEOUT).await
+}
+
+/// Append `text` to the quick notes file under a timestamp header, creating
+/// it if missing. Returns the file's path.
+#[tauri::command]
+pub async fn quick_note(text: String, state: State<'_, AppState>) -> AppResult<PathBuf> {
+    save_quick_note(&state, text).await
+}
+
+/// Save the current clipboard text as a quick note
+#[tauri::command]
+pub async fn quick_note_from_clipboard(state: State<'_, AppState>) -> AppResult<PathBuf> {
+    save_clipboard_note(&state).await
+}
+
+/// What the quick note shortcut runs
+pub async fn save_clipboard_note(state: &AppState) -> AppResult<PathBuf> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+    let text = state.app_handle().clipboard().read_text()?;
+    save_quick_note(state, text).await
+}
+
+async fn save_quick_note(state: &AppState, text: String) -> AppResult<PathBuf> {
+    let path = quick_note_path(state).await?;
+    let note_path = path.clone();
+    tokio::task::spawn_blocking(move || append_note(&note_path, &text, chrono::Local::now()))
+        .await
+        .map_err(|e| AppError::Unknown(format!("Saving note failed: {}", e)))??;
+    tracing::info!("Saved quick note to {:?}", path);
+    Ok(path)
+}
+
+/// Notes file from the settings; the Documents folder unless one is set
+async fn quick_note_path(state: &AppState) -> AppResult<PathBuf> {
+    let config = state.get_config().await.quick_note;
+    let directory = match config.directory {
+        Some(directory) => directory,
+        None => state
+            .app_handle()
+            .path()
+            .document_dir()
+            .or_else(|_| state.app_handle().path().app_data_dir())
+            .map_err(|e| AppError::NotFound(format!("No folder for quick notes: {}", e)))?,
+    };
+    Ok(directory.join(config.file_name))
+}
+
+/// Whether the background initialization has finished, is still running or
+/// failed. Works before that state exists, unlike the other commands.
+#[tauri::command]
+pub async fn get_app_state_status() -> AppResult<StartupStatus> {
+    Ok(startup::current())
+}
+
+/// Check the services the launcher depends on. Each check reports on its
+/// own, so one failure does not hide the others.
+#[tauri::command]
+pub async fn run_diagnostics(state: State<'_, AppState>) -> AppResult<DiagnosticsReport> {
+    Ok(diagnostics_report(&state).await)
+}
+
+/// [`run_diagnostics`] without the command wrapper, for the startup check
+pub async fn diagnostics_report(state: &AppState) -> DiagnosticsReport {
+    let mut checks = Vec::new();
+
+    let database = state.db.ping().await.map(|_| "Reachable".to_string());
+    checks.push(CheckResult::from_outcome("Database", database.map_err(|e| e.to_string())));
+
+    let icon_cache = state.icon_cache.check_writable().await.map(|_| "Writable".to_string());
+    checks.push(CheckResult::from_outcome("Icon cache", icon_cache.map_err(|e| e.to_string())));
+
+    #[cfg(windows)]
+    checks.push(CheckResult::from_outcome(
+        "Everything",
+        if crate::everything_service::is_available() {
+            Ok("Available".to_string())
+        } else {
+            Err("Everything is not running; file search falls back to the built-in index".to_string())
+        },
+    ));
+    #[cfg(not(windows))]
+    checks.push(CheckResult::skipped("Everything", "Only used on Windows"));
+
+    let monitors = tokio::task::spawn_blocking(|| screenshot::get_engine().refresh_monitors())
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|found| found.map_err(|e| e.to_string()))
+        .and_then(|monitors| match monitors.len() {
+            0 => Err("No monitors found".to_string()),
+            1 => Ok("1 monitor".to_string()),
+            n => Ok(format!("{} monitors", n)),
+        });
+    checks.push(CheckResult::from_outcome("Monitors", monitors));
+
+    #[cfg(windows)]
+    checks.push(CheckResult::from_outcome(
+        "OCR",
+        crate::ocr::ocr_available_languages().await.and_then(|languages| {
+            if languages.is_empty() {
+                Err(crate::ocr::NO_LANGUAGE_PACK_ERROR.to_string())
+            } else {
+                Ok(languages.join(", "))
+            }
+        }),
+    ));
+    #[cfg(not(windows))]
+    checks.push(CheckResult::skipped("OCR", "Only supported on Windows"));
+
+    let config = migration::check_config_file(state.config_path()).map(|found| {
+        if found {
+            "Readable".to_string()
+        } else {
+            "Not saved yet; using defaults".to_string()
+        }
+    });
+    checks.push(CheckResult::from_outcome("Config", config.map_err(|e| e.to_string())));
+
+    DiagnosticsReport::new(checks, chrono::Utc::now().timestamp())
+}
@@ -0,0 +1,27 @@
+//! Workflow Commands
+//! Save, load and run workflows built from plugin nodes
+
+use crate::app::state::AppState;
+use crate::core::workflow::{self, Workflow, WorkflowRun};
+use tauri::State;
+
+/// Save a workflow under its id; drafts may still be incomplete
+#[tauri::command]
+pub async fn save_workflow(state: State<'_, AppState>, workflow: Workflow) -> Result<(), String> {
+    state.workflows.save(&workflow).map_err(|e| e.to_string())
+}
+
+/// Load a saved workflow
+#[tauri::command]
+pub async fn load_workflow(state: State<'_, AppState>, id: String) -> Result<Workflow, String> {
+    state.workflows.load(&id).map_err(|e| e.to_string())
+}
+
+/// Check and run a workflow, returning every node's outputs
+#[tauri::command]
+pub async fn run_workflow(state: State<'_, AppState>, workflow: Workflow) -> Result<WorkflowRun, String> {
+    let plugin_manager = state.plugin_manager.read().await;
+    workflow::run_workflow(&workflow, &*plugin_manager)
+        .await
+        .map_err(|e| e.to_string())
+}
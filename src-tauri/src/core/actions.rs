@@ -0,0 +1,160 @@
+//! Result actions bound to Enter + modifier keys
+//!
+//! Plain Enter always runs the result's primary action (`SearchAction::type`).
+//! Modifier combinations are looked up per result type in the configured
+//! bindings, then in the `"*"` wildcard entry, and otherwise fall back to the
+//! primary action.
+
+use std::collections::HashMap;
+
+/// Binding key that applies to every result type
+pub const ANY_RESULT_TYPE: &str = "*";
+
+/// Open the result with its default handler
+pub const ACTION_OPEN: &str = "open";
+/// Show the file in the system file manager
+pub const ACTION_REVEAL: &str = "reveal";
+/// Copy the result's path to the clipboard
+pub const ACTION_COPY_PATH: &str = "copy_path";
+/// Copy the result's payload to the clipboard
+pub const ACTION_COPY: &str = "copy";
+/// Copy an image file to the clipboard as a base64 data URL
+pub const ACTION_COPY_DATA_URL: &str = "copy_data_url";
+/// Let the user choose an application to open the file with
+pub const ACTION_OPEN_WITH: &str = "open_with";
+/// Launch the app with administrator rights (Windows only)
+pub const ACTION_RUN_AS_ADMIN: &str = "run_as_admin";
+
+/// Normalize a modifier combination to a canonical key.
+///
+/// Accepts aliases (`control`, `cmd`, `meta`, `option`) and any order, and
+/// ignores an `enter` token: `"Shift+Ctrl+Enter"` -> `"ctrl+shift"`.
+/// Returns an empty string for plain Enter.
+pub fn normalize_modifier(modifier: &str) -> String {
+    let mut ctrl = false;
+    let mut alt = false;
+    let mut shift = false;
+
+    for part in modifier.split('+').map(|p| p.trim().to_lowercase()) {
+        match part.as_str() {
+            "ctrl" | "control" | "cmd" | "command" | "meta" | "super" => ctrl = true,
+            "alt" | "option" | "opt" => alt = true,
+            "shift" => shift = true,
+            _ => {}
+        }
+    }
+
+    [(ctrl, "ctrl"), (alt, "alt"), (shift, "shift")]
+        .iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Resolve which action a modifier combination triggers for a result type.
+///
+/// `bindings` maps result type -> normalized modifier -> action name.
+pub fn resolve_action(
+    bindings: &HashMap<String, HashMap<String, String>>,
+    result_type: &str,
+    modifier: &str,
+    primary: &str,
+) -> String {
+    let modifier = normalize_modifier(modifier);
+    if modifier.is_empty() {
+        return primary.to_string();
+    }
+
+    let lookup = |key: &str| {
+        bindings.get(key).and_then(|by_modifier| {
+            by_modifier
+                .iter()
+                .find(|(m, _)| normalize_modifier(m) == modifier)
+                .map(|(_, action)| action.clone())
+        })
+    };
+
+    lookup(result_type)
+        .or_else(|| lookup(ANY_RESULT_TYPE))
+        .unwrap_or_else(|| primary.to_string())
+}
+
+/// Default bindings: Ctrl = reveal, Alt = copy path, Shift = open with, for
+/// results that point at something on disk. Alt+Shift copies a file as a
+/// data URL. On Windows, Ctrl+Shift runs apps as administrator.
+pub fn default_bindings() -> HashMap<String, HashMap<String, String>> {
+    let path_actions: HashMap<String, String> = [
+        ("ctrl", ACTION_REVEAL),
+        ("alt", ACTION_COPY_PATH),
+        ("shift", ACTION_OPEN_WITH),
+    ]
+    .into_iter()
+    .map(|(m, a)| (m.to_string(), a.to_string()))
+    .collect();
+
+    let mut bindings: HashMap<String, HashMap<String, String>> = ["app", "file", "folder"]
+        .into_iter()
+        .map(|t| (t.to_string(), path_actions.clone()))
+        .collect();
+
+    if let Some(file) = bindings.get_mut("file") {
+        file.insert("alt+shift".to_string(), ACTION_COPY_DATA_URL.to_string());
+    }
+
+    if cfg!(target_os = "windows") {
+        if let Some(app) = bindings.get_mut("app") {
+            app.insert("ctrl+shift".to_string(), ACTION_RUN_AS_ADMIN.to_string());
+        }
+    }
+
+    bindings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_modifier() {
+        assert_eq!(normalize_modifier(""), "");
+        assert_eq!(normalize_modifier("Enter"), "");
+        assert_eq!(normalize_modifier("Control"), "ctrl");
+        assert_eq!(normalize_modifier("Shift+Ctrl+Enter"), "ctrl+shift");
+        assert_eq!(normalize_modifier("option"), "alt");
+    }
+
+    #[test]
+    fn test_default_modifiers_resolve_for_files() {
+        let bindings = default_bindings();
+        assert_eq!(resolve_action(&bindings, "file", "", "open"), ACTION_OPEN);
+        assert_eq!(resolve_action(&bindings, "file", "ctrl", "open"), ACTION_REVEAL);
+        assert_eq!(resolve_action(&bindings, "file", "alt", "open"), ACTION_COPY_PATH);
+        assert_eq!(resolve_action(&bindings, "app", "shift", "open"), ACTION_OPEN_WITH);
+        assert_eq!(resolve_action(&bindings, "file", "shift+alt", "open"), ACTION_COPY_DATA_URL);
+    }
+
+    #[test]
+    fn test_unmapped_falls_back_to_primary() {
+        let bindings = default_bindings();
+        assert_eq!(resolve_action(&bindings, "file", "ctrl+alt", "open"), "open");
+        assert_eq!(resolve_action(&bindings, "calculator", "ctrl", "copy"), "copy");
+        assert_eq!(resolve_action(&HashMap::new(), "file", "ctrl", "open"), "open");
+    }
+
+    #[test]
+    fn test_type_bindings_override_wildcard() {
+        let mut bindings = HashMap::new();
+        bindings.insert(
+            ANY_RESULT_TYPE.to_string(),
+            HashMap::from([("ctrl".to_string(), ACTION_COPY.to_string())]),
+        );
+        bindings.insert(
+            "file".to_string(),
+            HashMap::from([("Control".to_string(), ACTION_REVEAL.to_string())]),
+        );
+
+        assert_eq!(resolve_action(&bindings, "file", "ctrl", "open"), ACTION_REVEAL);
+        assert_eq!(resolve_action(&bindings, "web-search", "ctrl", "web-search"), ACTION_COPY);
+    }
+}
@@ -1,11 +1,11 @@
 // Anthropic Claude API client implementation
 
-use super::{AIMessage, AIProvider, AIProviderConfig};
+use super::{api_status_error, forward_stream, logging, AIMessage, AIProvider, AIProviderConfig, ChatReply, StreamEvent, StreamParser, TokenUsage};
 use crate::app::error::{AppError, AppResult};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tokio::sync::mpsc;
-use futures_util::StreamExt;
 
 pub struct AnthropicClient {
     http_client: Client,
@@ -21,9 +21,12 @@ impl AnthropicClient {
 struct AnthropicRequest {
     model: String,
     max_tokens: u32,
+    temperature: f32,
     messages: Vec<AnthropicMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     system: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop_sequences: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
 }
@@ -61,6 +64,15 @@ struct ImageSource {
 #[derive(Debug, Deserialize)]
 struct AnthropicResponse {
     content: Vec<AnthropicResponseContent>,
+    usage: Option<AnthropicUsage>,
+}
+
+/// Usage as reported by the Messages API. Stream events carry only part of
+/// it: `message_start` the input tokens, `message_delta` the output tokens.
+#[derive(Debug, Default, Deserialize)]
+struct AnthropicUsage {
+    input_tokens: Option<u32>,
+    output_tokens: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -74,7 +86,26 @@ struct AnthropicResponseContent {
 struct AnthropicStreamEvent {
     #[serde(rename = "type")]
     event_type: String,
+    /// Content block the event belongs to
+    index: Option<usize>,
+    content_block: Option<AnthropicContentBlock>,
     delta: Option<AnthropicDelta>,
+    /// Set on `message_start`
+    message: Option<AnthropicStreamMessage>,
+    /// Set on `message_delta`
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicStreamMessage {
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicContentBlock {
+    #[serde(rename = "type")]
+    block_type: String,
+    name: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -82,6 +113,95 @@ struct AnthropicDelta {
     #[serde(rename = "type")]
     delta_type: Option<String>,
     text: Option<String>,
+    /// Fragment of a tool_use block's input (`input_json_delta`)
+    partial_json: Option<String>,
+}
+
+/// Parses the Messages API event stream. `tool_use` blocks are collected
+/// from their `input_json_delta`s and emitted when the block stops.
+#[derive(Default)]
+struct AnthropicStreamParser {
+    /// Open tool_use blocks by index: tool name and input JSON so far
+    tool_uses: HashMap<usize, (String, String)>,
+    /// Usage reported so far, sent on `message_stop`
+    usage: Option<TokenUsage>,
+    done: bool,
+}
+
+impl AnthropicStreamParser {
+    fn record_usage(&mut self, usage: AnthropicUsage) {
+        let current = self.usage.unwrap_or_default();
+        self.usage = Some(TokenUsage::new(
+            usage.input_tokens.unwrap_or(current.prompt_tokens),
+            usage.output_tokens.unwrap_or(current.completion_tokens),
+        ));
+    }
+}
+
+impl StreamParser for AnthropicStreamParser {
+    fn line(&mut self, line: &str) -> Vec<StreamEvent> {
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            return Vec::new();
+        };
+        let Ok(event) = serde_json::from_str::<AnthropicStreamEvent>(data) else {
+            return Vec::new();
+        };
+        let index = event.index.unwrap_or_default();
+
+        match event.event_type.as_str() {
+            "message_start" | "message_delta" => {
+                let usage = event.usage.or(event.message.and_then(|m| m.usage));
+                if let Some(usage) = usage {
+                    self.record_usage(usage);
+                }
+                Vec::new()
+            }
+            "content_block_start" => {
+                if let Some(block) = event.content_block.filter(|b| b.block_type == "tool_use") {
+                    self.tool_uses
+                        .insert(index, (block.name.unwrap_or_default(), String::new()));
+                }
+                Vec::new()
+            }
+            "content_block_delta" => {
+                let Some(delta) = event.delta else {
+                    return Vec::new();
+                };
+                match delta.delta_type.as_deref() {
+                    Some("input_json_delta") => {
+                        if let Some((_, input)) = self.tool_uses.get_mut(&index) {
+                            input.push_str(delta.partial_json.as_deref().unwrap_or_default());
+                        }
+                        Vec::new()
+                    }
+                    _ => delta
+                        .text
+                        .filter(|text| !text.is_empty())
+                        .map(StreamEvent::Text)
+                        .into_iter()
+                        .collect(),
+                }
+            }
+            "content_block_stop" => self
+                .tool_uses
+                .remove(&index)
+                .map(|(name, input)| StreamEvent::ToolCall {
+                    name,
+                    arguments: if input.is_empty() { "{}".to_string() } else { input },
+                })
+                .into_iter()
+                .collect(),
+            "message_stop" => {
+                self.done = true;
+                self.usage.take().map(StreamEvent::Usage).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.done
+    }
 }
 
 fn convert_messages(messages: Vec<AIMessage>) -> (Option<String>, Vec<AnthropicMessage>) {
@@ -97,8 +217,8 @@ fn convert_messages(messages: Vec<AIMessage>) -> (Option<String>, Vec<AnthropicM
         let content = if let Some(attachments) = msg.attachments {
             let mut parts = vec![AnthropicContentPart::Text { text: msg.content }];
             for attachment in attachments {
-                if attachment.attachment_type == "image" {
-                    let media_type = attachment.mime_type.unwrap_or_else(|| "image/png".to_string());
+                if attachment.is_image() {
+                    let media_type = attachment.image_mime_type();
                     parts.push(AnthropicContentPart::Image {
                         source: ImageSource {
                             source_type: "base64".to_string(),
@@ -122,28 +242,48 @@ fn convert_messages(messages: Vec<AIMessage>) -> (Option<String>, Vec<AnthropicM
     (system_prompt, converted)
 }
 
+fn parse_response(response: AnthropicResponse) -> AppResult<ChatReply> {
+    let content = response
+        .content
+        .into_iter()
+        .find_map(|c| c.text)
+        .ok_or_else(|| AppError::Api("No response from Anthropic".to_string()))?;
+    let usage = response.usage.map(|usage| {
+        TokenUsage::new(usage.input_tokens.unwrap_or(0), usage.output_tokens.unwrap_or(0))
+    });
+    Ok(ChatReply { content, usage })
+}
+
+fn build_request(messages: Vec<AIMessage>, config: &AIProviderConfig, stream: bool) -> AppResult<AnthropicRequest> {
+    config.ensure_images_supported(&messages)?;
+    let (system_prompt, converted_messages) = convert_messages(messages);
+
+    Ok(AnthropicRequest {
+        model: config.model.clone(),
+        max_tokens: config.max_tokens,
+        temperature: config.temperature,
+        messages: converted_messages,
+        system: system_prompt,
+        stop_sequences: config.stop.clone(),
+        stream: stream.then_some(true),
+    })
+}
+
 #[async_trait::async_trait]
 impl AIProvider for AnthropicClient {
     async fn chat(
         &self,
         messages: Vec<AIMessage>,
         config: &AIProviderConfig,
-    ) -> AppResult<String> {
+    ) -> AppResult<ChatReply> {
         let api_url = if config.api_url.is_empty() {
             "https://api.anthropic.com/v1/messages".to_string()
         } else {
             format!("{}/messages", config.api_url.trim_end_matches('/'))
         };
 
-        let (system_prompt, converted_messages) = convert_messages(messages);
-
-        let request = AnthropicRequest {
-            model: config.model.clone(),
-            max_tokens: config.max_tokens,
-            messages: converted_messages,
-            system: system_prompt,
-            stream: None,
-        };
+        logging::request("anthropic", &api_url, config, &messages);
+        let request = build_request(messages, config, false)?;
 
         let response = self
             .http_client
@@ -156,28 +296,27 @@ impl AIProvider for AnthropicClient {
             .await
             .map_err(|e| AppError::Network(e.to_string()))?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Api(format!("Anthropic API error: {}", error_text)));
+            logging::response("anthropic", status, Some(&error_text), config);
+            return Err(api_status_error("Anthropic", status, error_text));
         }
+        logging::response("anthropic", status, None, config);
 
         let result: AnthropicResponse = response
             .json()
             .await
             .map_err(|e| AppError::Parse(e.to_string()))?;
 
-        result
-            .content
-            .first()
-            .and_then(|c| c.text.clone())
-            .ok_or_else(|| AppError::Api("No response from Anthropic".to_string()))
+        parse_response(result)
     }
 
     async fn chat_stream(
         &self,
         messages: Vec<AIMessage>,
         config: &AIProviderConfig,
-        on_chunk: mpsc::Sender<String>,
+        on_event: mpsc::Sender<StreamEvent>,
     ) -> AppResult<()> {
         let api_url = if config.api_url.is_empty() {
             "https://api.anthropic.com/v1/messages".to_string()
@@ -185,15 +324,8 @@ impl AIProvider for AnthropicClient {
             format!("{}/messages", config.api_url.trim_end_matches('/'))
         };
 
-        let (system_prompt, converted_messages) = convert_messages(messages);
-
-        let request = AnthropicRequest {
-            model: config.model.clone(),
-            max_tokens: config.max_tokens,
-            messages: converted_messages,
-            system: system_prompt,
-            stream: Some(true),
-        };
+        logging::request("anthropic", &api_url, config, &messages);
+        let request = build_request(messages, config, true)?;
 
         let response = self
             .http_client
@@ -206,34 +338,15 @@ impl AIProvider for AnthropicClient {
             .await
             .map_err(|e| AppError::Network(e.to_string()))?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Api(format!("Anthropic API error: {}", error_text)));
-        }
-
-        let mut stream = response.bytes_stream();
-
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| AppError::Network(e.to_string()))?;
-            let text = String::from_utf8_lossy(&chunk);
-
-            for line in text.lines() {
-                if line.starts_with("data: ") {
-                    let data = &line[6..];
-                    if let Ok(event) = serde_json::from_str::<AnthropicStreamEvent>(data) {
-                        if event.event_type == "content_block_delta" {
-                            if let Some(delta) = event.delta {
-                                if let Some(text) = delta.text {
-                                    let _ = on_chunk.send(text).await;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+            logging::response("anthropic", status, Some(&error_text), config);
+            return Err(api_status_error("Anthropic", status, error_text));
         }
+        logging::response("anthropic", status, None, config);
 
-        Ok(())
+        forward_stream(response, AnthropicStreamParser::default(), &on_event).await
     }
 
     async fn list_models(&self, _config: &AIProviderConfig) -> AppResult<Vec<String>> {
@@ -247,3 +360,145 @@ impl AIProvider for AnthropicClient {
         ])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ai::tests::{image_message, parse_fixture, test_config};
+    use crate::core::ai::CallOverrides;
+
+    fn messages() -> Vec<AIMessage> {
+        ["system", "user"]
+            .iter()
+            .map(|role| AIMessage {
+                id: role.to_string(),
+                role: role.to_string(),
+                content: format!("{} text", role),
+                timestamp: 0,
+                attachments: None,
+                usage: None,
+            })
+            .collect()
+    }
+
+    /// Recorded stream: a text block followed by a tool_use block
+    const TOOL_USE_STREAM: &str = r#"event: message_start
+data: {"type":"message_start","message":{"id":"msg_1","type":"message","role":"assistant","content":[],"model":"claude-3-5-sonnet-20241022","usage":{"input_tokens":12,"output_tokens":1}}}
+
+event: content_block_start
+data: {"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Checking the weather."}}
+
+event: content_block_stop
+data: {"type":"content_block_stop","index":0}
+
+event: content_block_start
+data: {"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_1","name":"get_weather","input":{}}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":""}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"city\": \"Par"}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"is\"}"}}
+
+event: content_block_stop
+data: {"type":"content_block_stop","index":1}
+
+event: message_delta
+data: {"type":"message_delta","delta":{"stop_reason":"tool_use","stop_sequence":null},"usage":{"output_tokens":40}}
+
+event: message_stop
+data: {"type":"message_stop"}
+
+"#;
+
+    #[test]
+    fn test_stream_emits_text_then_tool_use() {
+        let mut parser = AnthropicStreamParser::default();
+        let events = parse_fixture(&mut parser, TOOL_USE_STREAM);
+
+        assert_eq!(
+            events,
+            vec![
+                StreamEvent::Text("Checking the weather.".to_string()),
+                StreamEvent::ToolCall {
+                    name: "get_weather".to_string(),
+                    arguments: r#"{"city": "Paris"}"#.to_string(),
+                },
+                StreamEvent::Usage(TokenUsage::new(12, 40)),
+            ]
+        );
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_response_usage_is_captured() {
+        let body = r#"{
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-3-5-haiku-20241022",
+            "content": [{"type": "text", "text": "Paris."}],
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 15, "output_tokens": 4}
+        }"#;
+        let reply = parse_response(serde_json::from_str(body).unwrap()).unwrap();
+
+        assert_eq!(reply.content, "Paris.");
+        assert_eq!(reply.usage, Some(TokenUsage::new(15, 4)));
+    }
+
+    #[test]
+    fn test_request_uses_config_defaults() {
+        let body = serde_json::to_value(build_request(messages(), &test_config("anthropic"), false).unwrap()).unwrap();
+
+        assert_eq!(body["max_tokens"], 2000);
+        assert_eq!(body["system"], "system text");
+        assert!(body.get("stop_sequences").is_none());
+    }
+
+    #[test]
+    fn test_request_includes_overrides() {
+        let config = test_config("anthropic")
+            .with_overrides(&CallOverrides {
+                max_tokens: Some(64),
+                temperature: Some(1.0),
+                stop: Some(vec!["\n\nHuman:".to_string()]),
+            })
+            .unwrap();
+        let body = serde_json::to_value(build_request(messages(), &config, true).unwrap()).unwrap();
+
+        assert_eq!(body["max_tokens"], 64);
+        assert_eq!(body["temperature"], 1.0);
+        assert_eq!(body["stop_sequences"], serde_json::json!(["\n\nHuman:"]));
+    }
+
+    #[test]
+    fn test_request_includes_image_blocks() {
+        let mut config = test_config("anthropic");
+        config.model = "claude-3-5-sonnet-20241022".to_string();
+        let body = serde_json::to_value(build_request(vec![image_message()], &config, false).unwrap()).unwrap();
+
+        let blocks = &body["messages"][0]["content"];
+        assert_eq!(blocks[0], serde_json::json!({ "type": "text", "text": "What is this?" }));
+        assert_eq!(
+            blocks[1],
+            serde_json::json!({
+                "type": "image",
+                "source": { "type": "base64", "media_type": "image/jpeg", "data": "aGVsbG8=" },
+            })
+        );
+
+        config.model = "claude-2.1".to_string();
+        assert!(matches!(
+            build_request(vec![image_message()], &config, false),
+            Err(AppError::Config(_))
+        ));
+    }
+}
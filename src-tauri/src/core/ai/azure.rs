@@ -0,0 +1,173 @@
+// Azure OpenAI API client implementation
+//
+// Request and response bodies match OpenAI's; only the URL scheme and the
+// auth header differ, so the OpenAI request builder and SSE parser are reused.
+
+use super::openai::{build_request, parse_response, OpenAIResponse, OpenAIStreamParser};
+use super::{api_status_error, forward_stream, logging, AIMessage, AIProvider, AIProviderConfig, ChatReply, StreamEvent};
+use crate::app::error::{AppError, AppResult};
+use reqwest::{Client, Response, StatusCode};
+use tokio::sync::mpsc;
+
+/// Used when the config does not name an `api-version`
+pub const DEFAULT_API_VERSION: &str = "2024-06-01";
+
+pub struct AzureOpenAIClient {
+    http_client: Client,
+}
+
+impl AzureOpenAIClient {
+    pub fn new(http_client: Client) -> Self {
+        Self { http_client }
+    }
+
+    async fn post(
+        &self,
+        messages: Vec<AIMessage>,
+        config: &AIProviderConfig,
+        stream: bool,
+    ) -> AppResult<Response> {
+        let api_url = chat_url(config)?;
+        logging::request("azure", &api_url, config, &messages);
+        let request = build_request(messages, config, stream)?;
+
+        let response = self
+            .http_client
+            .post(&api_url)
+            .header("api-key", &config.api_key)
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        check_status(response, config).await
+    }
+}
+
+/// `{endpoint}/openai/deployments/{deployment}/chat/completions?api-version=...`
+fn chat_url(config: &AIProviderConfig) -> AppResult<String> {
+    let endpoint = config.api_url.trim().trim_end_matches('/');
+    if endpoint.is_empty() {
+        return Err(AppError::Config(
+            "Azure OpenAI needs the resource endpoint as the API URL".to_string(),
+        ));
+    }
+    let deployment = config
+        .deployment
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .unwrap_or(config.model.as_str());
+    if deployment.is_empty() {
+        return Err(AppError::Config("Azure OpenAI deployment is not set".to_string()));
+    }
+    let api_version = config
+        .api_version
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .unwrap_or(DEFAULT_API_VERSION);
+
+    Ok(format!(
+        "{}/openai/deployments/{}/chat/completions?api-version={}",
+        endpoint,
+        urlencoding::encode(deployment),
+        urlencoding::encode(api_version)
+    ))
+}
+
+async fn check_status(response: Response, config: &AIProviderConfig) -> AppResult<Response> {
+    let status = response.status();
+    if status.is_success() {
+        logging::response("azure", status, None, config);
+        return Ok(response);
+    }
+    let error_text = response.text().await.unwrap_or_default();
+    logging::response("azure", status, Some(&error_text), config);
+    let message = match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            "rejected the API key. Check the key belongs to this resource.".to_string()
+        }
+        StatusCode::NOT_FOUND => format!("deployment or api-version not found: {}", error_text),
+        _ => error_text,
+    };
+    Err(api_status_error("Azure OpenAI", status, message))
+}
+
+#[async_trait::async_trait]
+impl AIProvider for AzureOpenAIClient {
+    async fn chat(
+        &self,
+        messages: Vec<AIMessage>,
+        config: &AIProviderConfig,
+    ) -> AppResult<ChatReply> {
+        let response = self.post(messages, config, false).await?;
+
+        let result: OpenAIResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::Parse(e.to_string()))?;
+
+        parse_response(result)
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<AIMessage>,
+        config: &AIProviderConfig,
+        on_event: mpsc::Sender<StreamEvent>,
+    ) -> AppResult<()> {
+        let response = self.post(messages, config, true).await?;
+        forward_stream(response, OpenAIStreamParser::default(), &on_event).await
+    }
+
+    async fn list_models(&self, config: &AIProviderConfig) -> AppResult<Vec<String>> {
+        // Requests go to a single deployment, so that is the only "model"
+        let deployment = config.deployment.clone().unwrap_or_else(|| config.model.clone());
+        Ok(if deployment.is_empty() { Vec::new() } else { vec![deployment] })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ai::tests::test_config;
+
+    fn azure_config() -> AIProviderConfig {
+        let mut config = test_config("azure");
+        config.api_url = "https://contoso.openai.azure.com/".to_string();
+        config.deployment = Some("gpt4o-prod".to_string());
+        config.api_version = Some("2024-10-21".to_string());
+        config
+    }
+
+    #[test]
+    fn test_chat_url_from_endpoint_deployment_and_version() {
+        assert_eq!(
+            chat_url(&azure_config()).unwrap(),
+            "https://contoso.openai.azure.com/openai/deployments/gpt4o-prod/chat/completions?api-version=2024-10-21"
+        );
+    }
+
+    #[test]
+    fn test_chat_url_defaults() {
+        let mut config = azure_config();
+        config.deployment = None;
+        config.api_version = Some(" ".to_string());
+        assert_eq!(
+            chat_url(&config).unwrap(),
+            format!(
+                "https://contoso.openai.azure.com/openai/deployments/model/chat/completions?api-version={}",
+                DEFAULT_API_VERSION
+            )
+        );
+    }
+
+    #[test]
+    fn test_chat_url_requires_endpoint() {
+        let mut config = azure_config();
+        config.api_url = String::new();
+        assert!(matches!(chat_url(&config), Err(AppError::Config(_))));
+    }
+}
@@ -0,0 +1,462 @@
+// Google Gemini API client implementation (Generative Language API)
+
+use super::{api_status_error, forward_stream, logging, AIMessage, AIProvider, AIProviderConfig, ChatReply, StreamEvent, StreamParser, TokenUsage};
+use crate::app::error::{AppError, AppResult};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+const DEFAULT_API_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+pub struct GeminiClient {
+    http_client: Client,
+}
+
+impl GeminiClient {
+    pub fn new(http_client: Client) -> Self {
+        Self { http_client }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest {
+    contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<GeminiContent>,
+    generation_config: GenerationConfig,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerationConfig {
+    temperature: f32,
+    max_output_tokens: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop_sequences: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GeminiContent {
+    /// "user" or "model"; omitted for the system instruction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    #[serde(default)]
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiPart {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inline_data: Option<InlineData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    function_call: Option<FunctionCall>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InlineData {
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FunctionCall {
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+/// Body of `generateContent`, and of each `streamGenerateContent` event
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+    usage_metadata: Option<GeminiUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCandidate {
+    #[serde(default)]
+    content: GeminiContent,
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUsage {
+    #[serde(default)]
+    prompt_token_count: u32,
+    #[serde(default)]
+    candidates_token_count: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiModelsResponse {
+    #[serde(default)]
+    models: Vec<GeminiModel>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiModel {
+    /// "models/gemini-1.5-pro"
+    name: String,
+    #[serde(default)]
+    supported_generation_methods: Vec<String>,
+}
+
+/// Parses `data:` lines of `streamGenerateContent?alt=sse`. Each event is a
+/// complete response; usage is cumulative, so only the last one is sent.
+#[derive(Default)]
+struct GeminiStreamParser {
+    usage: Option<TokenUsage>,
+    done: bool,
+}
+
+impl StreamParser for GeminiStreamParser {
+    fn line(&mut self, line: &str) -> Vec<StreamEvent> {
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            return Vec::new();
+        };
+        let Ok(response) = serde_json::from_str::<GeminiResponse>(data) else {
+            return Vec::new();
+        };
+
+        if let Some(usage) = response.usage_metadata {
+            self.usage = Some(usage.into());
+        }
+        let mut events = Vec::new();
+        if let Some(candidate) = response.candidates.into_iter().next() {
+            events.extend(candidate.content.parts.into_iter().filter_map(part_event));
+            if candidate.finish_reason.is_some() {
+                self.done = true;
+                events.extend(self.usage.take().map(StreamEvent::Usage));
+            }
+        }
+        events
+    }
+
+    fn finish(&mut self) -> Vec<StreamEvent> {
+        self.usage.take().map(StreamEvent::Usage).into_iter().collect()
+    }
+
+    fn done(&self) -> bool {
+        self.done
+    }
+}
+
+impl From<GeminiUsage> for TokenUsage {
+    fn from(usage: GeminiUsage) -> Self {
+        TokenUsage::new(usage.prompt_token_count, usage.candidates_token_count)
+    }
+}
+
+fn part_event(part: GeminiPart) -> Option<StreamEvent> {
+    if let Some(call) = part.function_call {
+        return Some(StreamEvent::ToolCall {
+            name: call.name,
+            arguments: if call.args.is_null() { "{}".to_string() } else { call.args.to_string() },
+        });
+    }
+    part.text.filter(|t| !t.is_empty()).map(StreamEvent::Text)
+}
+
+/// Gemini has no system role: system messages become `systemInstruction`,
+/// "assistant" becomes "model", and consecutive turns of the same role are
+/// merged because the API expects them to alternate.
+fn convert_messages(messages: Vec<AIMessage>) -> (Option<GeminiContent>, Vec<GeminiContent>) {
+    let mut system_parts = Vec::new();
+    let mut contents: Vec<GeminiContent> = Vec::new();
+
+    for msg in messages {
+        let text_part = GeminiPart {
+            text: Some(msg.content),
+            ..Default::default()
+        };
+        if msg.role == "system" {
+            system_parts.push(text_part);
+            continue;
+        }
+
+        let role = if msg.role == "assistant" { "model" } else { "user" };
+        let mut parts = vec![text_part];
+        for attachment in msg.attachments.into_iter().flatten().filter(|a| a.is_image()) {
+            parts.push(GeminiPart {
+                inline_data: Some(InlineData {
+                    mime_type: attachment.image_mime_type(),
+                    data: attachment.data,
+                }),
+                ..Default::default()
+            });
+        }
+
+        match contents.last_mut() {
+            Some(last) if last.role.as_deref() == Some(role) => last.parts.extend(parts),
+            _ => contents.push(GeminiContent {
+                role: Some(role.to_string()),
+                parts,
+            }),
+        }
+    }
+
+    let system_instruction = (!system_parts.is_empty()).then(|| GeminiContent {
+        role: None,
+        parts: system_parts,
+    });
+    (system_instruction, contents)
+}
+
+fn build_request(messages: Vec<AIMessage>, config: &AIProviderConfig) -> AppResult<GeminiRequest> {
+    config.ensure_images_supported(&messages)?;
+    let (system_instruction, contents) = convert_messages(messages);
+
+    Ok(GeminiRequest {
+        contents,
+        system_instruction,
+        generation_config: GenerationConfig {
+            temperature: config.temperature,
+            max_output_tokens: config.max_tokens,
+            stop_sequences: config.stop.clone(),
+        },
+    })
+}
+
+/// `{base}/models/{model}:{method}`. The API key goes in the
+/// `x-goog-api-key` header, so it never shows up in URLs in errors or logs.
+fn model_url(config: &AIProviderConfig, method: &str) -> String {
+    let base = if config.api_url.is_empty() {
+        DEFAULT_API_URL
+    } else {
+        config.api_url.trim_end_matches('/')
+    };
+    let model = config.model.strip_prefix("models/").unwrap_or(&config.model);
+    format!("{}/models/{}:{}", base, model, method)
+}
+
+fn parse_response(response: GeminiResponse) -> AppResult<ChatReply> {
+    let content: String = response
+        .candidates
+        .into_iter()
+        .next()
+        .ok_or_else(|| AppError::Api("No response from Gemini".to_string()))?
+        .content
+        .parts
+        .into_iter()
+        .filter_map(|part| part.text)
+        .collect();
+    Ok(ChatReply {
+        content,
+        usage: response.usage_metadata.map(TokenUsage::from),
+    })
+}
+
+#[async_trait::async_trait]
+impl AIProvider for GeminiClient {
+    async fn chat(
+        &self,
+        messages: Vec<AIMessage>,
+        config: &AIProviderConfig,
+    ) -> AppResult<ChatReply> {
+        let api_url = model_url(config, "generateContent");
+        logging::request("gemini", &api_url, config, &messages);
+        let request = build_request(messages, config)?;
+
+        let response = self
+            .http_client
+            .post(&api_url)
+            .header("x-goog-api-key", &config.api_key)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            logging::response("gemini", status, Some(&error_text), config);
+            return Err(api_status_error("Gemini", status, error_text));
+        }
+        logging::response("gemini", status, None, config);
+
+        let result: GeminiResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::Parse(e.to_string()))?;
+
+        parse_response(result)
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<AIMessage>,
+        config: &AIProviderConfig,
+        on_event: mpsc::Sender<StreamEvent>,
+    ) -> AppResult<()> {
+        let api_url = model_url(config, "streamGenerateContent");
+        logging::request("gemini", &api_url, config, &messages);
+        let request = build_request(messages, config)?;
+
+        let response = self
+            .http_client
+            .post(&api_url)
+            .header("x-goog-api-key", &config.api_key)
+            .query(&[("alt", "sse")])
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            logging::response("gemini", status, Some(&error_text), config);
+            return Err(api_status_error("Gemini", status, error_text));
+        }
+        logging::response("gemini", status, None, config);
+
+        forward_stream(response, GeminiStreamParser::default(), &on_event).await
+    }
+
+    async fn list_models(&self, config: &AIProviderConfig) -> AppResult<Vec<String>> {
+        let base = if config.api_url.is_empty() {
+            DEFAULT_API_URL
+        } else {
+            config.api_url.trim_end_matches('/')
+        };
+
+        let response = self
+            .http_client
+            .get(format!("{}/models", base))
+            .header("x-goog-api-key", &config.api_key)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            // Return default models if API call fails
+            return Ok(vec![
+                "gemini-1.5-pro".to_string(),
+                "gemini-1.5-flash".to_string(),
+                "gemini-2.0-flash".to_string(),
+            ]);
+        }
+
+        let result: GeminiModelsResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::Parse(e.to_string()))?;
+
+        Ok(result
+            .models
+            .into_iter()
+            .filter(|m| m.supported_generation_methods.iter().any(|g| g == "generateContent"))
+            .map(|m| m.name.trim_start_matches("models/").to_string())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ai::tests::{image_message, parse_fixture, test_config};
+
+    fn message(role: &str, content: &str) -> AIMessage {
+        AIMessage {
+            id: role.to_string(),
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: 0,
+            attachments: None,
+            usage: None,
+        }
+    }
+
+    #[test]
+    fn test_roles_map_to_contents() {
+        let messages = vec![
+            message("system", "Be brief."),
+            message("user", "Hi"),
+            message("assistant", "Hello!"),
+            message("user", "Weather?"),
+            image_message(),
+        ];
+        let body = serde_json::to_value(build_request(messages, &test_config("gemini")).unwrap()).unwrap();
+
+        assert_eq!(body["systemInstruction"], serde_json::json!({ "parts": [{ "text": "Be brief." }] }));
+        let contents = body["contents"].as_array().unwrap();
+        let roles: Vec<_> = contents.iter().map(|c| c["role"].as_str().unwrap()).collect();
+        assert_eq!(roles, ["user", "model", "user"]);
+
+        // The two trailing user turns are merged, keeping the image
+        let parts = &contents[2]["parts"];
+        assert_eq!(parts[0]["text"], "Weather?");
+        assert_eq!(parts[1]["text"], "What is this?");
+        assert_eq!(
+            parts[2]["inlineData"],
+            serde_json::json!({ "mimeType": "image/jpeg", "data": "aGVsbG8=" })
+        );
+        assert_eq!(body["generationConfig"]["maxOutputTokens"], 2000);
+        assert!(body["generationConfig"].get("stopSequences").is_none());
+    }
+
+    #[test]
+    fn test_model_url() {
+        let mut config = test_config("gemini");
+        config.model = "models/gemini-1.5-flash".to_string();
+        assert_eq!(
+            model_url(&config, "generateContent"),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-1.5-flash:generateContent"
+        );
+
+        config.api_url = "https://proxy.example.com/v1/".to_string();
+        assert_eq!(
+            model_url(&config, "streamGenerateContent"),
+            "https://proxy.example.com/v1/models/gemini-1.5-flash:streamGenerateContent"
+        );
+    }
+
+    #[test]
+    fn test_stream_emits_text_and_usage() {
+        let fixture = r#"data: {"candidates":[{"content":{"parts":[{"text":"Hel"}],"role":"model"},"index":0}],"usageMetadata":{"promptTokenCount":9,"candidatesTokenCount":1,"totalTokenCount":10}}
+
+data: {"candidates":[{"content":{"parts":[{"text":"lo, 世界"}],"role":"model"},"index":0}],"usageMetadata":{"promptTokenCount":9,"candidatesTokenCount":4,"totalTokenCount":13}}
+
+data: {"candidates":[{"content":{"parts":[{"functionCall":{"name":"open_app","args":{"name":"Notes"}}}],"role":"model"},"finishReason":"STOP","index":0}],"usageMetadata":{"promptTokenCount":9,"candidatesTokenCount":7,"totalTokenCount":16}}
+
+"#;
+        let mut parser = GeminiStreamParser::default();
+        let events = parse_fixture(&mut parser, fixture);
+
+        assert_eq!(
+            events,
+            vec![
+                StreamEvent::Text("Hel".to_string()),
+                StreamEvent::Text("lo, 世界".to_string()),
+                StreamEvent::ToolCall {
+                    name: "open_app".to_string(),
+                    arguments: r#"{"name":"Notes"}"#.to_string(),
+                },
+                StreamEvent::Usage(TokenUsage::new(9, 7)),
+            ]
+        );
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_response_joins_text_parts() {
+        let response: GeminiResponse = serde_json::from_str(
+            r#"{"candidates":[{"content":{"parts":[{"text":"Hello"},{"text":" there"}],"role":"model"},"finishReason":"STOP"}],"usageMetadata":{"promptTokenCount":5,"candidatesTokenCount":2,"totalTokenCount":7}}"#,
+        )
+        .unwrap();
+        let reply = parse_response(response).unwrap();
+
+        assert_eq!(reply.content, "Hello there");
+        assert_eq!(reply.usage, Some(TokenUsage::new(5, 2)));
+    }
+}
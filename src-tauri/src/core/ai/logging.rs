@@ -0,0 +1,155 @@
+// Debug logging for provider requests
+//
+// Every provider logs through these helpers so the output looks the same
+// across providers. Message contents are reduced to their length and a hash
+// unless `OMNIBOX_AI_LOG_BODIES=1` is set; the API key is never logged.
+
+use super::{AIMessage, AIProviderConfig};
+use reqwest::StatusCode;
+
+/// Dev flag that logs full message contents
+const LOG_BODIES_ENV: &str = "OMNIBOX_AI_LOG_BODIES";
+
+const REDACTED: &str = "[REDACTED]";
+
+fn log_bodies() -> bool {
+    std::env::var(LOG_BODIES_ENV).map(|v| v.trim() == "1").unwrap_or(false)
+}
+
+/// Replace every occurrence of the configured API key
+fn redact(text: &str, config: &AIProviderConfig) -> String {
+    let key = config.api_key.trim();
+    if key.is_empty() {
+        text.to_string()
+    } else {
+        text.replace(key, REDACTED)
+    }
+}
+
+/// Length and hash of the conversation, or the messages themselves when
+/// `OMNIBOX_AI_LOG_BODIES=1`
+fn describe_messages(messages: &[AIMessage], config: &AIProviderConfig) -> String {
+    if log_bodies() {
+        let contents: Vec<_> = messages
+            .iter()
+            .map(|m| format!("{}: {}", m.role, m.content))
+            .collect();
+        return redact(&contents.join(" | "), config);
+    }
+    let mut context = md5::Context::new();
+    let mut chars = 0;
+    for message in messages {
+        context.consume(message.role.as_bytes());
+        context.consume(message.content.as_bytes());
+        chars += message.content.chars().count();
+    }
+    format!("{} chars, md5 {:x}", chars, context.compute())
+}
+
+/// Log an outgoing request at debug level
+pub(super) fn request(provider: &str, endpoint: &str, config: &AIProviderConfig, messages: &[AIMessage]) {
+    tracing::debug!(
+        provider,
+        endpoint = %redact(endpoint, config),
+        model = %config.model,
+        messages = messages.len(),
+        content = %describe_messages(messages, config),
+        "AI request"
+    );
+}
+
+/// Log the response status; error bodies are logged with the key redacted
+pub(super) fn response(provider: &str, status: StatusCode, error_body: Option<&str>, config: &AIProviderConfig) {
+    match error_body {
+        Some(body) => tracing::debug!(
+            provider,
+            status = status.as_u16(),
+            body = %redact(body, config),
+            "AI request failed"
+        ),
+        None => tracing::debug!(provider, status = status.as_u16(), "AI response"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ai::tests::test_config;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture(log: impl FnOnce()) -> String {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, log);
+        let bytes = captured.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_api_key_never_logged() {
+        let mut config = test_config("openai");
+        config.api_key = "sk-secret-123".to_string();
+        let messages = vec![AIMessage {
+            id: "1".to_string(),
+            role: "user".to_string(),
+            content: "my key is sk-secret-123".to_string(),
+            timestamp: 0,
+            attachments: None,
+            usage: None,
+        }];
+
+        let output = capture(|| {
+            request("openai", "https://api.example.com/v1?key=sk-secret-123", &config, &messages);
+            response(
+                "openai",
+                StatusCode::UNAUTHORIZED,
+                Some(r#"{"error":"Incorrect API key provided: sk-secret-123"}"#),
+                &config,
+            );
+        });
+
+        assert!(output.contains("AI request"), "{}", output);
+        assert!(output.contains("status=401"), "{}", output);
+        assert!(output.contains(REDACTED), "{}", output);
+        assert!(!output.contains("sk-secret-123"), "{}", output);
+    }
+
+    #[test]
+    fn test_contents_summarised_by_default() {
+        let config = test_config("openai");
+        let messages = vec![AIMessage {
+            id: "1".to_string(),
+            role: "user".to_string(),
+            content: "private note".to_string(),
+            timestamp: 0,
+            attachments: None,
+            usage: None,
+        }];
+
+        let output = capture(|| request("openai", "https://api.example.com", &config, &messages));
+
+        assert!(output.contains("messages=1"), "{}", output);
+        assert!(output.contains("12 chars"), "{}", output);
+        assert!(!output.contains("private note"), "{}", output);
+    }
+}
@@ -5,15 +5,21 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 mod openai;
 mod anthropic;
 mod ollama;
+mod azure;
+mod gemini;
+mod logging;
 
 pub use openai::OpenAIClient;
 pub use anthropic::AnthropicClient;
 pub use ollama::OllamaClient;
+pub use azure::AzureOpenAIClient;
+pub use gemini::GeminiClient;
 
 /// AI Message structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +30,69 @@ pub struct AIMessage {
     pub timestamp: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attachments: Option<Vec<AIAttachment>>,
+    /// Tokens used to produce this message (assistant messages only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+}
+
+/// Token counts for one request, or summed over a conversation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+    /// Counted locally because the provider reported nothing
+    #[serde(default)]
+    pub estimated: bool,
+}
+
+/// Rough characters per token for English-like text, used when a provider
+/// reports no usage
+const CHARS_PER_TOKEN: usize = 4;
+
+impl TokenUsage {
+    pub fn new(prompt_tokens: u32, completion_tokens: u32) -> Self {
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+            estimated: false,
+        }
+    }
+
+    /// Approximate usage from the text sent and received
+    pub fn estimate(messages: &[AIMessage], completion: &str) -> Self {
+        let prompt: usize = messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+        Self {
+            estimated: true,
+            ..Self::new(prompt as u32, estimate_tokens(completion) as u32)
+        }
+    }
+
+    /// Add another request's usage to this running total
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+        self.estimated |= other.estimated;
+    }
+}
+
+/// Token estimate for `text`: about one token per four characters, and at
+/// least one per word (CJK text has no spaces but ~1 token per character)
+fn estimate_tokens(text: &str) -> usize {
+    let is_wide = |c: &char| (*c as u32) >= 0x2E80;
+    let narrow: String = text.chars().filter(|c| !is_wide(c)).collect();
+    let wide = text.chars().filter(is_wide).count();
+    narrow.chars().count().div_ceil(CHARS_PER_TOKEN).max(narrow.split_whitespace().count()) + wide
+}
+
+/// A provider's answer to a non-streaming chat request
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatReply {
+    pub content: String,
+    /// `None` if the provider did not report usage
+    pub usage: Option<TokenUsage>,
 }
 
 /// AI Attachment (image, file, etc.)
@@ -37,6 +106,31 @@ pub struct AIAttachment {
     pub mime_type: Option<String>,
 }
 
+impl AIAttachment {
+    pub fn is_image(&self) -> bool {
+        self.attachment_type == "image"
+    }
+
+    /// Media type sent to the provider. Falls back to the file extension
+    /// when the frontend did not supply an image type.
+    pub fn image_mime_type(&self) -> String {
+        if let Some(mime) = self.mime_type.as_deref().filter(|m| m.starts_with("image/")) {
+            return mime.to_string();
+        }
+        let ext = std::path::Path::new(&self.name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase);
+        match ext.as_deref() {
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            _ => "image/png",
+        }
+        .to_string()
+    }
+}
+
 /// AI Conversation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIConversation {
@@ -47,6 +141,9 @@ pub struct AIConversation {
     pub updated_at: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub system_prompt: Option<String>,
+    /// Tokens used by all assistant replies so far
+    #[serde(default)]
+    pub usage: TokenUsage,
 }
 
 /// Preset Prompt
@@ -68,11 +165,247 @@ pub struct AIProviderConfig {
     pub model: String,
     pub temperature: f32,
     pub max_tokens: u32,
+    #[serde(default)]
+    pub stop: Vec<String>,
+    /// Azure deployment name; defaults to `model`
+    #[serde(default)]
+    pub deployment: Option<String>,
+    /// Azure `api-version` query parameter
+    #[serde(default)]
+    pub api_version: Option<String>,
+}
+
+/// Per-call overrides for generation parameters; unset fields fall back to
+/// the provider config
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallOverrides {
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub stop: Option<Vec<String>>,
+}
+
+/// Known request limits for a provider
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProviderLimits {
+    pub max_temperature: f32,
+    /// Maximum number of stop sequences, if the provider documents one
+    pub max_stop_sequences: Option<usize>,
+}
+
+impl ProviderLimits {
+    pub fn for_provider(provider: &str) -> Self {
+        match provider.to_lowercase().as_str() {
+            "anthropic" => Self {
+                max_temperature: 1.0,
+                max_stop_sequences: None,
+            },
+            "ollama" => Self {
+                max_temperature: 2.0,
+                max_stop_sequences: None,
+            },
+            "gemini" => Self {
+                max_temperature: 2.0,
+                max_stop_sequences: Some(5),
+            },
+            // OpenAI and compatible APIs
+            _ => Self {
+                max_temperature: 2.0,
+                max_stop_sequences: Some(4),
+            },
+        }
+    }
 }
 
+impl AIProviderConfig {
+    /// Apply per-call overrides, validating them against the provider's limits
+    pub fn with_overrides(&self, overrides: &CallOverrides) -> AppResult<Self> {
+        let limits = ProviderLimits::for_provider(&self.provider);
+        let mut config = self.clone();
+
+        if let Some(max_tokens) = overrides.max_tokens {
+            if max_tokens == 0 {
+                return Err(AppError::Config("max_tokens must be greater than 0".to_string()));
+            }
+            config.max_tokens = max_tokens;
+        }
+
+        if let Some(temperature) = overrides.temperature {
+            if !(0.0..=limits.max_temperature).contains(&temperature) {
+                return Err(AppError::Config(format!(
+                    "temperature must be between 0 and {} for {}",
+                    limits.max_temperature, self.provider
+                )));
+            }
+            config.temperature = temperature;
+        }
+
+        if let Some(stop) = &overrides.stop {
+            if stop.iter().any(|s| s.is_empty()) {
+                return Err(AppError::Config("Stop sequences must not be empty".to_string()));
+            }
+            if let Some(max) = limits.max_stop_sequences {
+                if stop.len() > max {
+                    return Err(AppError::Config(format!(
+                        "{} accepts at most {} stop sequences",
+                        self.provider, max
+                    )));
+                }
+            }
+            config.stop = stop.clone();
+        }
+
+        Ok(config)
+    }
+
+    /// Whether the configured model accepts image input. Unknown OpenAI and
+    /// Anthropic models are assumed to; Ollama models only when they are a
+    /// known vision family.
+    pub fn supports_images(&self) -> bool {
+        let model = self.model.to_lowercase();
+        match self.provider.to_lowercase().as_str() {
+            "ollama" => OLLAMA_VISION_MODELS.iter().any(|m| model.contains(m)),
+            _ => !TEXT_ONLY_MODELS.iter().any(|m| {
+                // Exact name or a dated snapshot of it. A dot starts a new
+                // model (gpt-4.1 has vision), so point releases are listed.
+                model.strip_prefix(m).is_some_and(|rest| {
+                    let mut rest = rest.chars();
+                    match rest.next() {
+                        None => true,
+                        Some('-') => rest.next().is_some_and(|c| c.is_ascii_digit()),
+                        Some(_) => false,
+                    }
+                })
+            }),
+        }
+    }
+
+    /// Reject image attachments the configured model would ignore
+    pub fn ensure_images_supported(&self, messages: &[AIMessage]) -> AppResult<()> {
+        let has_images = messages
+            .iter()
+            .flat_map(|m| m.attachments.iter().flatten())
+            .any(AIAttachment::is_image);
+        if has_images && !self.supports_images() {
+            return Err(AppError::Config(format!(
+                "Model '{}' does not accept images. Choose a vision model or remove the image.",
+                self.model
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Hosted models without image input, including their dated snapshots
+/// (`gpt-4-0613`, `claude-instant-1.2`)
+const TEXT_ONLY_MODELS: &[&str] = &[
+    "gpt-3.5-turbo",
+    "gpt-4",
+    "o1-mini",
+    "o3-mini",
+    "claude-2",
+    "claude-2.0",
+    "claude-2.1",
+    "claude-instant",
+];
+
+/// Ollama model families that accept images
+const OLLAMA_VISION_MODELS: &[&str] = &[
+    "llava",
+    "vision",
+    "moondream",
+    "minicpm-v",
+    "qwen2.5vl",
+    "gemma3",
+    "llama4",
+];
+
 /// Streaming chunk callback type
 pub type StreamCallback = Box<dyn Fn(String) + Send + Sync>;
 
+/// One item of a streamed response
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    /// A piece of the assistant's text
+    Text(String),
+    /// A tool call, sent once its arguments are complete. `arguments` is the
+    /// JSON text the model produced.
+    ToolCall { name: String, arguments: String },
+    /// Token usage, sent at the end by providers that report it
+    Usage(TokenUsage),
+}
+
+/// Turns the lines of a provider's streaming response into events
+trait StreamParser {
+    /// Events for one complete line
+    fn line(&mut self, line: &str) -> Vec<StreamEvent>;
+
+    /// Events still pending when the stream ends
+    fn finish(&mut self) -> Vec<StreamEvent> {
+        Vec::new()
+    }
+
+    /// Whether the provider signalled the end of the response
+    fn done(&self) -> bool;
+}
+
+/// Splits a byte stream into lines. Partial lines (and partial UTF-8
+/// sequences) are kept until the rest arrives in a later chunk.
+#[derive(Default)]
+struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let mut lines = Vec::new();
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            lines.push(String::from_utf8_lossy(&line).trim_end().to_string());
+        }
+        lines
+    }
+
+    /// The unterminated last line, if any
+    fn finish(&mut self) -> Option<String> {
+        let line = String::from_utf8_lossy(&std::mem::take(&mut self.pending)).trim_end().to_string();
+        (!line.is_empty()).then_some(line)
+    }
+}
+
+/// Read a streaming HTTP response to the end, sending parsed events
+async fn forward_stream(
+    response: reqwest::Response,
+    mut parser: impl StreamParser,
+    on_event: &tokio::sync::mpsc::Sender<StreamEvent>,
+) -> AppResult<()> {
+    use futures_util::StreamExt;
+
+    let mut stream = response.bytes_stream();
+    let mut lines = LineBuffer::default();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| AppError::Network(e.to_string()))?;
+        for line in lines.push(&chunk) {
+            for event in parser.line(&line) {
+                let _ = on_event.send(event).await;
+            }
+        }
+        if parser.done() {
+            break;
+        }
+    }
+
+    let rest = lines.finish().map(|line| parser.line(&line)).unwrap_or_default();
+    for event in rest.into_iter().chain(parser.finish()) {
+        let _ = on_event.send(event).await;
+    }
+    Ok(())
+}
+
 /// AI Provider trait - implement for each provider
 #[async_trait::async_trait]
 pub trait AIProvider: Send + Sync {
@@ -81,20 +414,72 @@ pub trait AIProvider: Send + Sync {
         &self,
         messages: Vec<AIMessage>,
         config: &AIProviderConfig,
-    ) -> AppResult<String>;
+    ) -> AppResult<ChatReply>;
 
-    /// Send a chat message with streaming response
+    /// Send a chat message with streaming response. Text and tool calls
+    /// are sent on `on_event` as they arrive.
     async fn chat_stream(
         &self,
         messages: Vec<AIMessage>,
         config: &AIProviderConfig,
-        on_chunk: tokio::sync::mpsc::Sender<String>,
+        on_event: tokio::sync::mpsc::Sender<StreamEvent>,
     ) -> AppResult<()>;
 
     /// Get available models
     async fn list_models(&self, config: &AIProviderConfig) -> AppResult<Vec<String>>;
 }
 
+/// Error for a provider that answered with a non-success status
+fn api_status_error(service: &str, status: reqwest::StatusCode, message: String) -> AppError {
+    AppError::ApiStatus {
+        service: service.to_string(),
+        status: status.as_u16(),
+        message,
+    }
+}
+
+/// How long `AIClient::test_provider` waits for the ping reply
+pub const PROVIDER_TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a provider configuration failed `AIClient::test_provider`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum ProviderTestError {
+    /// API key missing, invalid or not allowed to use the model
+    Auth(String),
+    /// Could not reach the API URL
+    Network(String),
+    /// The model or deployment does not exist
+    Model(String),
+    /// No reply within `PROVIDER_TEST_TIMEOUT`
+    Timeout,
+    Other(String),
+}
+
+impl ProviderTestError {
+    pub fn from_error(err: AppError) -> Self {
+        match err {
+            AppError::ApiStatus { status, message, .. } => {
+                let text = message.to_lowercase();
+                match status {
+                    401 | 403 => Self::Auth(message),
+                    404 => Self::Model(message),
+                    // Gemini reports a bad key as 400 API_KEY_INVALID
+                    400 | 422 if text.contains("api key") || text.contains("api_key") => {
+                        Self::Auth(message)
+                    }
+                    400 | 422 if text.contains("model") => Self::Model(message),
+                    _ => Self::Other(format!("{} ({})", message, status)),
+                }
+            }
+            AppError::Network(message) => Self::Network(message),
+            AppError::Http(e) if e.is_timeout() => Self::Timeout,
+            AppError::Http(e) => Self::Network(e.to_string()),
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
 /// AI Client - manages conversations and provider interactions
 pub struct AIClient {
     http_client: Client,
@@ -117,10 +502,37 @@ impl AIClient {
             "openai" => Box::new(OpenAIClient::new(self.http_client.clone())),
             "anthropic" => Box::new(AnthropicClient::new(self.http_client.clone())),
             "ollama" => Box::new(OllamaClient::new(self.http_client.clone())),
+            "azure" => Box::new(AzureOpenAIClient::new(self.http_client.clone())),
+            "gemini" => Box::new(GeminiClient::new(self.http_client.clone())),
             _ => Box::new(OpenAIClient::new(self.http_client.clone())), // Default to OpenAI
         }
     }
 
+    /// Send a one-token ping with `config` and return the round trip in ms.
+    /// Makes a single attempt, giving up after `PROVIDER_TEST_TIMEOUT`.
+    pub async fn test_provider(&self, config: &AIProviderConfig) -> Result<u64, ProviderTestError> {
+        let config = AIProviderConfig {
+            max_tokens: 1,
+            ..config.clone()
+        };
+        let ping = AIMessage {
+            id: "ping".to_string(),
+            role: "user".to_string(),
+            content: "ping".to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+            attachments: None,
+            usage: None,
+        };
+
+        let provider = self.get_provider(&config.provider);
+        let started = std::time::Instant::now();
+        match tokio::time::timeout(PROVIDER_TEST_TIMEOUT, provider.chat(vec![ping], &config)).await {
+            Ok(Ok(_)) => Ok(started.elapsed().as_millis() as u64),
+            Ok(Err(e)) => Err(ProviderTestError::from_error(e)),
+            Err(_) => Err(ProviderTestError::Timeout),
+        }
+    }
+
     /// Create a new conversation
     pub async fn create_conversation(&self, title: Option<String>, system_prompt: Option<String>) -> AIConversation {
         let id = uuid::Uuid::new_v4().to_string();
@@ -133,6 +545,7 @@ impl AIClient {
             created_at: now,
             updated_at: now,
             system_prompt,
+            usage: TokenUsage::default(),
         };
 
         let mut conversations = self.conversations.write().await;
@@ -160,6 +573,9 @@ impl AIClient {
         let mut conversations = self.conversations.write().await;
         
         if let Some(conv) = conversations.get_mut(conversation_id) {
+            if let Some(usage) = &message.usage {
+                conv.usage.add(usage);
+            }
             conv.messages.push(message);
             conv.updated_at = chrono::Utc::now().timestamp();
             
@@ -181,6 +597,14 @@ impl AIClient {
         }
     }
 
+    /// Tokens used by a conversation so far
+    pub async fn get_usage(&self, conversation_id: &str) -> AppResult<TokenUsage> {
+        self.get_conversation(conversation_id)
+            .await
+            .map(|conv| conv.usage)
+            .ok_or_else(|| AppError::NotFound("Conversation not found".to_string()))
+    }
+
     /// Delete a conversation
     pub async fn delete_conversation(&self, id: &str) -> AppResult<()> {
         let mut conversations = self.conversations.write().await;
@@ -209,6 +633,7 @@ impl AIClient {
             content: user_message,
             timestamp: chrono::Utc::now().timestamp(),
             attachments,
+            usage: None,
         };
 
         // Add user message to conversation
@@ -227,21 +652,26 @@ impl AIClient {
                 content: system_prompt.clone(),
                 timestamp: 0,
                 attachments: None,
+                usage: None,
             });
         }
         messages.extend(conversation.messages);
 
         // Get provider and send request
         let provider = self.get_provider(&config.provider);
-        let response_content = provider.chat(messages, config).await?;
+        let reply = provider.chat(messages.clone(), config).await?;
+        let usage = reply
+            .usage
+            .unwrap_or_else(|| TokenUsage::estimate(&messages, &reply.content));
 
         // Create assistant message
         let assistant_msg = AIMessage {
             id: uuid::Uuid::new_v4().to_string(),
             role: "assistant".to_string(),
-            content: response_content,
+            content: reply.content,
             timestamp: chrono::Utc::now().timestamp(),
             attachments: None,
+            usage: Some(usage),
         };
 
         // Add assistant message to conversation
@@ -315,3 +745,243 @@ impl Default for AIClient {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(super) fn test_config(provider: &str) -> AIProviderConfig {
+        AIProviderConfig {
+            provider: provider.to_string(),
+            api_key: "key".to_string(),
+            api_url: String::new(),
+            model: "model".to_string(),
+            temperature: 0.7,
+            max_tokens: 2000,
+            stop: Vec::new(),
+            deployment: None,
+            api_version: None,
+        }
+    }
+
+    /// Run a recorded response through `parser`, split into small chunks
+    /// the way it may arrive over the network
+    pub(super) fn parse_fixture(parser: &mut impl StreamParser, fixture: &str) -> Vec<StreamEvent> {
+        let mut lines = LineBuffer::default();
+        let mut events = Vec::new();
+        for chunk in fixture.as_bytes().chunks(7) {
+            for line in lines.push(chunk) {
+                events.extend(parser.line(&line));
+            }
+        }
+        if let Some(line) = lines.finish() {
+            events.extend(parser.line(&line));
+        }
+        events.extend(parser.finish());
+        events
+    }
+
+    #[test]
+    fn test_line_buffer_joins_split_lines() {
+        let mut lines = LineBuffer::default();
+        let text = "data: héllo\r\ndata: [DONE]";
+        let bytes = text.as_bytes();
+
+        assert!(lines.push(&bytes[..8]).is_empty());
+        assert_eq!(lines.push(&bytes[8..]), vec!["data: héllo"]);
+        assert_eq!(lines.finish().as_deref(), Some("data: [DONE]"));
+        assert_eq!(lines.finish(), None);
+    }
+
+    /// User message with one JPEG attachment
+    pub(super) fn image_message() -> AIMessage {
+        AIMessage {
+            id: "1".to_string(),
+            role: "user".to_string(),
+            content: "What is this?".to_string(),
+            timestamp: 0,
+            attachments: Some(vec![AIAttachment {
+                attachment_type: "image".to_string(),
+                name: "photo.jpg".to_string(),
+                data: "aGVsbG8=".to_string(),
+                mime_type: Some("image/jpeg".to_string()),
+            }]),
+            usage: None,
+        }
+    }
+
+    fn message(role: &str, content: &str, usage: Option<TokenUsage>) -> AIMessage {
+        AIMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: 0,
+            attachments: None,
+            usage,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conversation_sums_message_usage() {
+        let client = AIClient::new();
+        let conversation = client.create_conversation(None, None).await;
+
+        client.add_message(&conversation.id, message("user", "hi", None)).await.unwrap();
+        client
+            .add_message(&conversation.id, message("assistant", "hello", Some(TokenUsage::new(10, 5))))
+            .await
+            .unwrap();
+        client
+            .add_message(&conversation.id, message("assistant", "again", Some(TokenUsage::new(20, 7))))
+            .await
+            .unwrap();
+
+        let usage = client.get_usage(&conversation.id).await.unwrap();
+        assert_eq!(usage, TokenUsage::new(30, 12));
+        assert_eq!(usage.total_tokens, 42);
+        assert!(client.get_usage("missing").await.is_err());
+    }
+
+    #[test]
+    fn test_estimate_marks_usage_as_estimated() {
+        let prompt = vec![message("user", "What is the capital of France?", None)];
+        let usage = TokenUsage::estimate(&prompt, "Paris.");
+
+        assert!(usage.estimated);
+        assert_eq!(usage.prompt_tokens, 8);
+        assert_eq!(usage.completion_tokens, 2);
+        assert_eq!(usage.total_tokens, 10);
+        assert_eq!(estimate_tokens("你好世界"), 4);
+    }
+
+    #[test]
+    fn test_no_overrides_keeps_config() {
+        let config = test_config("openai");
+        let effective = config.with_overrides(&CallOverrides::default()).unwrap();
+
+        assert_eq!(effective.max_tokens, 2000);
+        assert_eq!(effective.temperature, 0.7);
+        assert!(effective.stop.is_empty());
+    }
+
+    #[test]
+    fn test_overrides_replace_config() {
+        let config = test_config("openai");
+        let overrides = CallOverrides {
+            max_tokens: Some(64),
+            temperature: Some(0.0),
+            stop: Some(vec!["\n\n".to_string()]),
+        };
+        let effective = config.with_overrides(&overrides).unwrap();
+
+        assert_eq!(effective.max_tokens, 64);
+        assert_eq!(effective.temperature, 0.0);
+        assert_eq!(effective.stop, vec!["\n\n".to_string()]);
+    }
+
+    #[test]
+    fn test_overrides_validated_against_provider_limits() {
+        let temperature = CallOverrides {
+            temperature: Some(1.5),
+            ..Default::default()
+        };
+        assert!(test_config("openai").with_overrides(&temperature).is_ok());
+        assert!(test_config("anthropic").with_overrides(&temperature).is_err());
+
+        let stop = CallOverrides {
+            stop: Some((0..5).map(|i| i.to_string()).collect()),
+            ..Default::default()
+        };
+        assert!(test_config("openai").with_overrides(&stop).is_err());
+        assert!(test_config("anthropic").with_overrides(&stop).is_ok());
+
+        let zero_tokens = CallOverrides {
+            max_tokens: Some(0),
+            ..Default::default()
+        };
+        assert!(test_config("ollama").with_overrides(&zero_tokens).is_err());
+    }
+
+    #[test]
+    fn test_image_mime_type_falls_back_to_extension() {
+        let mut attachment = image_message().attachments.unwrap().remove(0);
+        assert_eq!(attachment.image_mime_type(), "image/jpeg");
+
+        attachment.mime_type = None;
+        attachment.name = "screen.WEBP".to_string();
+        assert_eq!(attachment.image_mime_type(), "image/webp");
+
+        attachment.mime_type = Some(String::new());
+        attachment.name = "clipboard".to_string();
+        assert_eq!(attachment.image_mime_type(), "image/png");
+    }
+
+    #[test]
+    fn test_text_only_models_reject_images() {
+        let mut config = test_config("openai");
+        for (provider, model, vision) in [
+            ("openai", "gpt-4o", true),
+            ("openai", "gpt-4-turbo", true),
+            ("openai", "gpt-4", false),
+            ("openai", "gpt-4-0613", false),
+            ("openai", "gpt-4.1", true),
+            ("openai", "gpt-4.1-mini", true),
+            ("openai", "gpt-4.1-2025-04-14", true),
+            ("openai", "gpt-4.5-preview", true),
+            ("openai", "gpt-3.5-turbo-0125", false),
+            ("openai", "gpt-3.5-turbo", false),
+            ("anthropic", "claude-3-5-sonnet-20241022", true),
+            ("anthropic", "claude-2.1", false),
+            ("anthropic", "claude-instant-1.2", false),
+            ("ollama", "llava:13b", true),
+            ("ollama", "llama3.2-vision", true),
+            ("ollama", "llama3", false),
+        ] {
+            config.provider = provider.to_string();
+            config.model = model.to_string();
+            assert_eq!(config.supports_images(), vision, "{}/{}", provider, model);
+        }
+
+        config.provider = "openai".to_string();
+        config.model = "gpt-3.5-turbo".to_string();
+        let err = config.ensure_images_supported(&[image_message()]).unwrap_err();
+        assert!(matches!(err, AppError::Config(ref msg) if msg.contains("gpt-3.5-turbo")));
+        assert!(config.ensure_images_supported(&[message("user", "hi", None)]).is_ok());
+    }
+
+    #[test]
+    fn test_provider_errors_by_status() {
+        let status_error = |status: u16, message: &str| {
+            ProviderTestError::from_error(AppError::ApiStatus {
+                service: "Test".to_string(),
+                status,
+                message: message.to_string(),
+            })
+        };
+
+        assert!(matches!(status_error(401, "invalid key"), ProviderTestError::Auth(_)));
+        assert!(matches!(status_error(403, "forbidden"), ProviderTestError::Auth(_)));
+        assert!(matches!(status_error(404, "no such model"), ProviderTestError::Model(_)));
+        assert!(matches!(
+            status_error(400, r#"{"error":{"status":"INVALID_ARGUMENT","message":"API key not valid."}}"#),
+            ProviderTestError::Auth(_)
+        ));
+        assert!(matches!(
+            status_error(400, r#"{"error":{"message":"The model `gpt-9` does not exist"}}"#),
+            ProviderTestError::Model(_)
+        ));
+        assert_eq!(status_error(500, "oops"), ProviderTestError::Other("oops (500)".to_string()));
+        assert_eq!(
+            ProviderTestError::from_error(AppError::Network("connection refused".to_string())),
+            ProviderTestError::Network("connection refused".to_string())
+        );
+    }
+
+    #[test]
+    fn test_provider_error_serializes_kind() {
+        let json = serde_json::to_value(ProviderTestError::Auth("bad key".to_string())).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "auth", "message": "bad key" }));
+        let json = serde_json::to_value(ProviderTestError::Timeout).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "timeout" }));
+    }
+}
@@ -1,11 +1,10 @@
 // Ollama API client implementation (local LLM)
 
-use super::{AIMessage, AIProvider, AIProviderConfig};
+use super::{api_status_error, forward_stream, logging, AIAttachment, AIMessage, AIProvider, AIProviderConfig, ChatReply, StreamEvent, StreamParser, TokenUsage};
 use crate::app::error::{AppError, AppResult};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
-use futures_util::StreamExt;
 
 pub struct OllamaClient {
     http_client: Client,
@@ -30,6 +29,8 @@ struct OllamaRequest {
 struct OllamaOptions {
     temperature: f32,
     num_predict: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,6 +44,24 @@ struct OllamaMessage {
 #[derive(Debug, Deserialize)]
 struct OllamaResponse {
     message: OllamaResponseMessage,
+    #[serde(flatten)]
+    counts: OllamaCounts,
+}
+
+/// Token counts Ollama reports with the final response. `prompt_eval_count`
+/// is left out when the prompt was served from cache.
+#[derive(Debug, Default, Deserialize)]
+struct OllamaCounts {
+    prompt_eval_count: Option<u32>,
+    eval_count: Option<u32>,
+}
+
+impl OllamaCounts {
+    /// `None` if Ollama sent no counts; callers estimate instead
+    fn usage(&self) -> Option<TokenUsage> {
+        self.eval_count
+            .map(|eval| TokenUsage::new(self.prompt_eval_count.unwrap_or(0), eval))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -54,6 +73,8 @@ struct OllamaResponseMessage {
 struct OllamaStreamResponse {
     message: Option<OllamaStreamMessage>,
     done: bool,
+    #[serde(flatten)]
+    counts: OllamaCounts,
 }
 
 #[derive(Debug, Deserialize)]
@@ -61,6 +82,32 @@ struct OllamaStreamMessage {
     content: String,
 }
 
+/// Parses Ollama's newline-delimited JSON stream; text and final usage only
+#[derive(Default)]
+struct OllamaStreamParser {
+    done: bool,
+}
+
+impl StreamParser for OllamaStreamParser {
+    fn line(&mut self, line: &str) -> Vec<StreamEvent> {
+        let Ok(response) = serde_json::from_str::<OllamaStreamResponse>(line) else {
+            return Vec::new();
+        };
+        self.done = response.done;
+        let text = response
+            .message
+            .map(|message| message.content)
+            .filter(|content| !content.is_empty())
+            .map(StreamEvent::Text);
+        let usage = response.done.then(|| response.counts.usage()).flatten().map(StreamEvent::Usage);
+        text.into_iter().chain(usage).collect()
+    }
+
+    fn done(&self) -> bool {
+        self.done
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct OllamaModelsResponse {
     models: Vec<OllamaModel>,
@@ -78,7 +125,7 @@ fn convert_messages(messages: Vec<AIMessage>) -> Vec<OllamaMessage> {
             let images = msg.attachments.and_then(|attachments| {
                 let image_data: Vec<String> = attachments
                     .into_iter()
-                    .filter(|a| a.attachment_type == "image")
+                    .filter(AIAttachment::is_image)
                     .map(|a| a.data)
                     .collect();
                 if image_data.is_empty() {
@@ -97,28 +144,35 @@ fn convert_messages(messages: Vec<AIMessage>) -> Vec<OllamaMessage> {
         .collect()
 }
 
+fn build_request(messages: Vec<AIMessage>, config: &AIProviderConfig, stream: bool) -> AppResult<OllamaRequest> {
+    config.ensure_images_supported(&messages)?;
+    Ok(OllamaRequest {
+        model: config.model.clone(),
+        messages: convert_messages(messages),
+        stream,
+        options: Some(OllamaOptions {
+            temperature: config.temperature,
+            num_predict: config.max_tokens,
+            stop: config.stop.clone(),
+        }),
+    })
+}
+
 #[async_trait::async_trait]
 impl AIProvider for OllamaClient {
     async fn chat(
         &self,
         messages: Vec<AIMessage>,
         config: &AIProviderConfig,
-    ) -> AppResult<String> {
+    ) -> AppResult<ChatReply> {
         let api_url = if config.api_url.is_empty() {
             "http://localhost:11434/api/chat".to_string()
         } else {
             format!("{}/api/chat", config.api_url.trim_end_matches('/'))
         };
 
-        let request = OllamaRequest {
-            model: config.model.clone(),
-            messages: convert_messages(messages),
-            stream: false,
-            options: Some(OllamaOptions {
-                temperature: config.temperature,
-                num_predict: config.max_tokens,
-            }),
-        };
+        logging::request("ollama", &api_url, config, &messages);
+        let request = build_request(messages, config, false)?;
 
         let response = self
             .http_client
@@ -129,24 +183,30 @@ impl AIProvider for OllamaClient {
             .await
             .map_err(|e| AppError::Network(format!("Failed to connect to Ollama: {}. Make sure Ollama is running.", e)))?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Api(format!("Ollama API error: {}", error_text)));
+            logging::response("ollama", status, Some(&error_text), config);
+            return Err(api_status_error("Ollama", status, error_text));
         }
+        logging::response("ollama", status, None, config);
 
         let result: OllamaResponse = response
             .json()
             .await
             .map_err(|e| AppError::Parse(e.to_string()))?;
 
-        Ok(result.message.content)
+        Ok(ChatReply {
+            usage: result.counts.usage(),
+            content: result.message.content,
+        })
     }
 
     async fn chat_stream(
         &self,
         messages: Vec<AIMessage>,
         config: &AIProviderConfig,
-        on_chunk: mpsc::Sender<String>,
+        on_event: mpsc::Sender<StreamEvent>,
     ) -> AppResult<()> {
         let api_url = if config.api_url.is_empty() {
             "http://localhost:11434/api/chat".to_string()
@@ -154,15 +214,8 @@ impl AIProvider for OllamaClient {
             format!("{}/api/chat", config.api_url.trim_end_matches('/'))
         };
 
-        let request = OllamaRequest {
-            model: config.model.clone(),
-            messages: convert_messages(messages),
-            stream: true,
-            options: Some(OllamaOptions {
-                temperature: config.temperature,
-                num_predict: config.max_tokens,
-            }),
-        };
+        logging::request("ollama", &api_url, config, &messages);
+        let request = build_request(messages, config, true)?;
 
         let response = self
             .http_client
@@ -173,34 +226,15 @@ impl AIProvider for OllamaClient {
             .await
             .map_err(|e| AppError::Network(format!("Failed to connect to Ollama: {}. Make sure Ollama is running.", e)))?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Api(format!("Ollama API error: {}", error_text)));
+            logging::response("ollama", status, Some(&error_text), config);
+            return Err(api_status_error("Ollama", status, error_text));
         }
+        logging::response("ollama", status, None, config);
 
-        let mut stream = response.bytes_stream();
-
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| AppError::Network(e.to_string()))?;
-            let text = String::from_utf8_lossy(&chunk);
-
-            for line in text.lines() {
-                if !line.is_empty() {
-                    if let Ok(response) = serde_json::from_str::<OllamaStreamResponse>(line) {
-                        if let Some(message) = response.message {
-                            if !message.content.is_empty() {
-                                let _ = on_chunk.send(message.content).await;
-                            }
-                        }
-                        if response.done {
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-
-        Ok(())
+        forward_stream(response, OllamaStreamParser::default(), &on_event).await
     }
 
     async fn list_models(&self, config: &AIProviderConfig) -> AppResult<Vec<String>> {
@@ -237,3 +271,87 @@ impl AIProvider for OllamaClient {
         Ok(models)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ai::tests::{image_message, parse_fixture, test_config};
+    use crate::core::ai::CallOverrides;
+
+    fn user_message() -> Vec<AIMessage> {
+        vec![AIMessage {
+            id: "1".to_string(),
+            role: "user".to_string(),
+            content: "hi".to_string(),
+            timestamp: 0,
+            attachments: None,
+            usage: None,
+        }]
+    }
+
+    #[test]
+    fn test_stream_emits_text_until_done() {
+        let fixture = r#"{"model":"llama3.2","message":{"role":"assistant","content":"Hi"},"done":false}
+{"model":"llama3.2","message":{"role":"assistant","content":" there"},"done":false}
+{"model":"llama3.2","message":{"role":"assistant","content":""},"done":true}
+"#;
+        let mut parser = OllamaStreamParser::default();
+        let events = parse_fixture(&mut parser, fixture);
+
+        assert_eq!(events, vec![StreamEvent::Text("Hi".to_string()), StreamEvent::Text(" there".to_string())]);
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_final_chunk_reports_usage() {
+        let fixture = r#"{"model":"llama3.2","message":{"role":"assistant","content":"Hi"},"done":false}
+{"model":"llama3.2","message":{"role":"assistant","content":""},"done":true,"total_duration":5191566416,"prompt_eval_count":26,"eval_count":3}
+"#;
+        let events = parse_fixture(&mut OllamaStreamParser::default(), fixture);
+
+        assert_eq!(events, vec![StreamEvent::Text("Hi".to_string()), StreamEvent::Usage(TokenUsage::new(26, 3))]);
+    }
+
+    #[test]
+    fn test_request_uses_config_defaults() {
+        let body = serde_json::to_value(build_request(user_message(), &test_config("ollama"), false).unwrap()).unwrap();
+
+        assert_eq!(body["options"]["num_predict"], 2000);
+        assert!(body["options"].get("stop").is_none());
+    }
+
+    #[test]
+    fn test_request_includes_overrides() {
+        let config = test_config("ollama")
+            .with_overrides(&CallOverrides {
+                max_tokens: Some(32),
+                stop: Some(vec!["###".to_string()]),
+                ..Default::default()
+            })
+            .unwrap();
+        let body = serde_json::to_value(build_request(user_message(), &config, true).unwrap()).unwrap();
+
+        assert_eq!(body["options"]["num_predict"], 32);
+        assert_eq!(body["options"]["stop"], serde_json::json!(["###"]));
+        assert_eq!(body["stream"], true);
+    }
+
+    #[test]
+    fn test_request_includes_images() {
+        let mut config = test_config("ollama");
+        config.model = "llava:13b".to_string();
+        let body = serde_json::to_value(build_request(vec![image_message()], &config, false).unwrap()).unwrap();
+
+        assert_eq!(body["messages"][0]["content"], "What is this?");
+        assert_eq!(body["messages"][0]["images"], serde_json::json!(["aGVsbG8="]));
+
+        let body = serde_json::to_value(build_request(user_message(), &config, false).unwrap()).unwrap();
+        assert!(body["messages"][0].get("images").is_none());
+
+        config.model = "llama3".to_string();
+        assert!(matches!(
+            build_request(vec![image_message()], &config, false),
+            Err(AppError::Config(_))
+        ));
+    }
+}
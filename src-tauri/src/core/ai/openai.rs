@@ -1,11 +1,11 @@
 // OpenAI API client implementation
 
-use super::{AIMessage, AIProvider, AIProviderConfig};
+use super::{api_status_error, forward_stream, logging, AIMessage, AIProvider, AIProviderConfig, ChatReply, StreamEvent, StreamParser, TokenUsage};
 use crate::app::error::{AppError, AppResult};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use tokio::sync::mpsc;
-use futures_util::StreamExt;
 
 pub struct OpenAIClient {
     http_client: Client,
@@ -18,13 +18,23 @@ impl OpenAIClient {
 }
 
 #[derive(Debug, Serialize)]
-struct OpenAIRequest {
+pub(super) struct OpenAIRequest {
     model: String,
     messages: Vec<OpenAIMessage>,
     temperature: f32,
     max_tokens: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
+}
+
+#[derive(Debug, Serialize)]
+struct StreamOptions {
+    /// Ask for a final chunk carrying the request's usage
+    include_usage: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -55,8 +65,25 @@ struct ImageUrl {
 }
 
 #[derive(Debug, Deserialize)]
-struct OpenAIResponse {
+pub(super) struct OpenAIResponse {
     choices: Vec<OpenAIChoice>,
+    usage: Option<OpenAIUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIUsage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    total_tokens: u32,
+}
+
+impl From<OpenAIUsage> for TokenUsage {
+    fn from(usage: OpenAIUsage) -> Self {
+        Self {
+            total_tokens: usage.total_tokens,
+            ..TokenUsage::new(usage.prompt_tokens, usage.completion_tokens)
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -71,17 +98,101 @@ struct OpenAIResponseMessage {
 
 #[derive(Debug, Deserialize)]
 struct OpenAIStreamResponse {
+    #[serde(default)]
     choices: Vec<OpenAIStreamChoice>,
+    /// Only set on the last chunk, whose `choices` is empty
+    usage: Option<OpenAIUsage>,
 }
 
 #[derive(Debug, Deserialize)]
 struct OpenAIStreamChoice {
+    #[serde(default)]
     delta: OpenAIDelta,
+    finish_reason: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct OpenAIDelta {
     content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<OpenAIToolCallDelta>,
+}
+
+/// Fragment of a tool call; fragments with the same `index` belong together
+#[derive(Debug, Deserialize)]
+struct OpenAIToolCallDelta {
+    index: usize,
+    function: Option<OpenAIFunctionDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIFunctionDelta {
+    name: Option<String>,
+    arguments: Option<String>,
+}
+
+/// Parses `data:` lines of the chat completions stream, collecting tool call
+/// fragments until the choice finishes
+#[derive(Default)]
+pub(super) struct OpenAIStreamParser {
+    tool_calls: BTreeMap<usize, (String, String)>,
+    done: bool,
+}
+
+impl OpenAIStreamParser {
+    fn flush_tool_calls(&mut self) -> Vec<StreamEvent> {
+        std::mem::take(&mut self.tool_calls)
+            .into_values()
+            .map(|(name, arguments)| StreamEvent::ToolCall {
+                name,
+                arguments: if arguments.is_empty() { "{}".to_string() } else { arguments },
+            })
+            .collect()
+    }
+}
+
+impl StreamParser for OpenAIStreamParser {
+    fn line(&mut self, line: &str) -> Vec<StreamEvent> {
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            return Vec::new();
+        };
+        if data == "[DONE]" {
+            self.done = true;
+            return self.flush_tool_calls();
+        }
+        let Ok(response) = serde_json::from_str::<OpenAIStreamResponse>(data) else {
+            return Vec::new();
+        };
+
+        let mut events = Vec::new();
+        if let Some(usage) = response.usage {
+            events.push(StreamEvent::Usage(usage.into()));
+        }
+        if let Some(choice) = response.choices.into_iter().next() {
+            if let Some(content) = choice.delta.content.filter(|c| !c.is_empty()) {
+                events.push(StreamEvent::Text(content));
+            }
+            for call in choice.delta.tool_calls {
+                let (name, arguments) = self.tool_calls.entry(call.index).or_default();
+                if let Some(function) = call.function {
+                    name.push_str(function.name.as_deref().unwrap_or_default());
+                    arguments.push_str(function.arguments.as_deref().unwrap_or_default());
+                }
+            }
+            if choice.finish_reason.is_some() {
+                events.extend(self.flush_tool_calls());
+            }
+        }
+        events
+    }
+
+    fn finish(&mut self) -> Vec<StreamEvent> {
+        self.flush_tool_calls()
+    }
+
+    fn done(&self) -> bool {
+        self.done
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -101,11 +212,10 @@ fn convert_messages(messages: Vec<AIMessage>) -> Vec<OpenAIMessage> {
             let content = if let Some(attachments) = msg.attachments {
                 let mut parts = vec![OpenAIContentPart::Text { text: msg.content }];
                 for attachment in attachments {
-                    if attachment.attachment_type == "image" {
-                        let mime = attachment.mime_type.unwrap_or_else(|| "image/png".to_string());
+                    if attachment.is_image() {
                         parts.push(OpenAIContentPart::ImageUrl {
                             image_url: ImageUrl {
-                                url: format!("data:{};base64,{}", mime, attachment.data),
+                                url: format!("data:{};base64,{}", attachment.image_mime_type(), attachment.data),
                             },
                         });
                     }
@@ -123,26 +233,47 @@ fn convert_messages(messages: Vec<AIMessage>) -> Vec<OpenAIMessage> {
         .collect()
 }
 
+pub(super) fn build_request(messages: Vec<AIMessage>, config: &AIProviderConfig, stream: bool) -> AppResult<OpenAIRequest> {
+    config.ensure_images_supported(&messages)?;
+    Ok(OpenAIRequest {
+        model: config.model.clone(),
+        messages: convert_messages(messages),
+        temperature: config.temperature,
+        max_tokens: config.max_tokens,
+        stop: config.stop.clone(),
+        stream: stream.then_some(true),
+        stream_options: stream.then_some(StreamOptions { include_usage: true }),
+    })
+}
+
+pub(super) fn parse_response(response: OpenAIResponse) -> AppResult<ChatReply> {
+    let content = response
+        .choices
+        .into_iter()
+        .next()
+        .map(|c| c.message.content)
+        .ok_or_else(|| AppError::Api("No response from OpenAI".to_string()))?;
+    Ok(ChatReply {
+        content,
+        usage: response.usage.map(TokenUsage::from),
+    })
+}
+
 #[async_trait::async_trait]
 impl AIProvider for OpenAIClient {
     async fn chat(
         &self,
         messages: Vec<AIMessage>,
         config: &AIProviderConfig,
-    ) -> AppResult<String> {
+    ) -> AppResult<ChatReply> {
         let api_url = if config.api_url.is_empty() {
             "https://api.openai.com/v1/chat/completions".to_string()
         } else {
             format!("{}/chat/completions", config.api_url.trim_end_matches('/'))
         };
 
-        let request = OpenAIRequest {
-            model: config.model.clone(),
-            messages: convert_messages(messages),
-            temperature: config.temperature,
-            max_tokens: config.max_tokens,
-            stream: None,
-        };
+        logging::request("openai", &api_url, config, &messages);
+        let request = build_request(messages, config, false)?;
 
         let response = self
             .http_client
@@ -154,28 +285,27 @@ impl AIProvider for OpenAIClient {
             .await
             .map_err(|e| AppError::Network(e.to_string()))?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Api(format!("OpenAI API error: {}", error_text)));
+            logging::response("openai", status, Some(&error_text), config);
+            return Err(api_status_error("OpenAI", status, error_text));
         }
+        logging::response("openai", status, None, config);
 
         let result: OpenAIResponse = response
             .json()
             .await
             .map_err(|e| AppError::Parse(e.to_string()))?;
 
-        result
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| AppError::Api("No response from OpenAI".to_string()))
+        parse_response(result)
     }
 
     async fn chat_stream(
         &self,
         messages: Vec<AIMessage>,
         config: &AIProviderConfig,
-        on_chunk: mpsc::Sender<String>,
+        on_event: mpsc::Sender<StreamEvent>,
     ) -> AppResult<()> {
         let api_url = if config.api_url.is_empty() {
             "https://api.openai.com/v1/chat/completions".to_string()
@@ -183,13 +313,8 @@ impl AIProvider for OpenAIClient {
             format!("{}/chat/completions", config.api_url.trim_end_matches('/'))
         };
 
-        let request = OpenAIRequest {
-            model: config.model.clone(),
-            messages: convert_messages(messages),
-            temperature: config.temperature,
-            max_tokens: config.max_tokens,
-            stream: Some(true),
-        };
+        logging::request("openai", &api_url, config, &messages);
+        let request = build_request(messages, config, true)?;
 
         let response = self
             .http_client
@@ -201,36 +326,15 @@ impl AIProvider for OpenAIClient {
             .await
             .map_err(|e| AppError::Network(e.to_string()))?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Api(format!("OpenAI API error: {}", error_text)));
-        }
-
-        let mut stream = response.bytes_stream();
-
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| AppError::Network(e.to_string()))?;
-            let text = String::from_utf8_lossy(&chunk);
-
-            for line in text.lines() {
-                if line.starts_with("data: ") {
-                    let data = &line[6..];
-                    if data == "[DONE]" {
-                        break;
-                    }
-
-                    if let Ok(response) = serde_json::from_str::<OpenAIStreamResponse>(data) {
-                        if let Some(choice) = response.choices.first() {
-                            if let Some(content) = &choice.delta.content {
-                                let _ = on_chunk.send(content.clone()).await;
-                            }
-                        }
-                    }
-                }
-            }
+            logging::response("openai", status, Some(&error_text), config);
+            return Err(api_status_error("OpenAI", status, error_text));
         }
+        logging::response("openai", status, None, config);
 
-        Ok(())
+        forward_stream(response, OpenAIStreamParser::default(), &on_event).await
     }
 
     async fn list_models(&self, config: &AIProviderConfig) -> AppResult<Vec<String>> {
@@ -274,3 +378,149 @@ impl AIProvider for OpenAIClient {
         Ok(models)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ai::tests::{image_message, parse_fixture, test_config};
+    use crate::core::ai::CallOverrides;
+
+    fn user_message() -> Vec<AIMessage> {
+        vec![AIMessage {
+            id: "1".to_string(),
+            role: "user".to_string(),
+            content: "hi".to_string(),
+            timestamp: 0,
+            attachments: None,
+            usage: None,
+        }]
+    }
+
+    /// Recorded stream: text, then a tool call split over several deltas
+    const TOOL_CALL_STREAM: &str = r#"data: {"id":"c1","choices":[{"index":0,"delta":{"role":"assistant","content":""}}]}
+
+data: {"id":"c1","choices":[{"index":0,"delta":{"content":"Let me check."}}]}
+
+data: {"id":"c1","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"get_weather","arguments":""}}]}}]}
+
+data: {"id":"c1","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"city\":"}}]}}]}
+
+data: {"id":"c1","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"Paris\"}"}}]}}]}
+
+data: {"id":"c1","choices":[{"index":0,"delta":{},"finish_reason":"tool_calls"}]}
+
+data: [DONE]
+
+"#;
+
+    #[test]
+    fn test_stream_emits_text_then_tool_call() {
+        let events = parse_fixture(&mut OpenAIStreamParser::default(), TOOL_CALL_STREAM);
+
+        assert_eq!(
+            events,
+            vec![
+                StreamEvent::Text("Let me check.".to_string()),
+                StreamEvent::ToolCall {
+                    name: "get_weather".to_string(),
+                    arguments: r#"{"city":"Paris"}"#.to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_response_usage_is_captured() {
+        let body = r#"{
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1718000000,
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Paris."},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 14, "completion_tokens": 2, "total_tokens": 16}
+        }"#;
+        let reply = parse_response(serde_json::from_str(body).unwrap()).unwrap();
+
+        assert_eq!(reply.content, "Paris.");
+        assert_eq!(reply.usage, Some(TokenUsage::new(14, 2)));
+    }
+
+    #[test]
+    fn test_stream_usage_chunk() {
+        let fixture = r#"data: {"choices":[{"delta":{"content":"Hi"},"finish_reason":"stop"}],"usage":null}
+
+data: {"choices":[],"usage":{"prompt_tokens":9,"completion_tokens":1,"total_tokens":10}}
+
+data: [DONE]
+"#;
+        let events = parse_fixture(&mut OpenAIStreamParser::default(), fixture);
+
+        assert_eq!(
+            events,
+            vec![StreamEvent::Text("Hi".to_string()), StreamEvent::Usage(TokenUsage::new(9, 1))]
+        );
+    }
+
+    #[test]
+    fn test_stream_text_only() {
+        let fixture = r#"data: {"choices":[{"delta":{"content":"Hel"}}]}
+
+data: {"choices":[{"delta":{"content":"lo"},"finish_reason":"stop"}]}
+
+data: [DONE]
+"#;
+        let events = parse_fixture(&mut OpenAIStreamParser::default(), fixture);
+
+        assert_eq!(events, vec![StreamEvent::Text("Hel".to_string()), StreamEvent::Text("lo".to_string())]);
+    }
+
+    #[test]
+    fn test_request_uses_config_defaults() {
+        let body = serde_json::to_value(build_request(user_message(), &test_config("openai"), false).unwrap()).unwrap();
+
+        assert_eq!(body["max_tokens"], 2000);
+        assert!(body.get("stop").is_none());
+        assert!(body.get("stream").is_none());
+        assert!(body.get("stream_options").is_none());
+    }
+
+    #[test]
+    fn test_request_includes_overrides() {
+        let config = test_config("openai")
+            .with_overrides(&CallOverrides {
+                max_tokens: Some(128),
+                temperature: Some(0.0),
+                stop: Some(vec!["END".to_string()]),
+            })
+            .unwrap();
+        let body = serde_json::to_value(build_request(user_message(), &config, true).unwrap()).unwrap();
+
+        assert_eq!(body["max_tokens"], 128);
+        assert_eq!(body["temperature"], 0.0);
+        assert_eq!(body["stop"], serde_json::json!(["END"]));
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["stream_options"]["include_usage"], true);
+    }
+
+    #[test]
+    fn test_request_includes_image_parts() {
+        let mut config = test_config("openai");
+        config.model = "gpt-4o".to_string();
+        let body = serde_json::to_value(build_request(vec![image_message()], &config, false).unwrap()).unwrap();
+
+        let parts = &body["messages"][0]["content"];
+        assert_eq!(parts[0], serde_json::json!({ "type": "text", "text": "What is this?" }));
+        assert_eq!(parts[1]["type"], "image_url");
+        assert_eq!(parts[1]["image_url"]["url"], "data:image/jpeg;base64,aGVsbG8=");
+
+        config.model = "gpt-3.5-turbo".to_string();
+        assert!(matches!(
+            build_request(vec![image_message()], &config, false),
+            Err(AppError::Config(_))
+        ));
+    }
+}
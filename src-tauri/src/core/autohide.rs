@@ -0,0 +1,112 @@
+// Launcher auto-hide switch and shortcut debounce timings
+//
+// The window-event and global-shortcut handlers run outside the async
+// runtime, so they read this copy of the `autohide` settings instead of
+// locking the config.
+use crate::app::config::AutohideConfig;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Overrides `autohide.on_blur` when set to a yes/no value, for debugging
+pub const AUTOHIDE_ENV: &str = "OMNIBOX_AUTOHIDE_ON_BLUR";
+
+static TIMINGS: Lazy<Mutex<AutohideTimings>> = Lazy::new(|| Mutex::new(AutohideTimings::default()));
+static ON_BLUR: AtomicBool = AtomicBool::new(true);
+
+/// [`AutohideConfig`] as durations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutohideTimings {
+    /// Blurs this soon after showing are ignored while the launcher has not
+    /// been focused yet
+    pub show_blur_grace: Duration,
+    /// Blurs this soon after the launcher got focus are ignored
+    pub focus_blur_grace: Duration,
+    pub main_shortcut_debounce: Duration,
+    pub clipboard_shortcut_debounce: Duration,
+    pub capture_shortcut_debounce: Duration,
+}
+
+impl Default for AutohideTimings {
+    fn default() -> Self {
+        Self::from(&AutohideConfig::default())
+    }
+}
+
+impl From<&AutohideConfig> for AutohideTimings {
+    fn from(config: &AutohideConfig) -> Self {
+        Self {
+            show_blur_grace: Duration::from_millis(config.show_blur_grace_ms),
+            focus_blur_grace: Duration::from_millis(config.focus_blur_grace_ms),
+            main_shortcut_debounce: Duration::from_millis(config.main_shortcut_debounce_ms),
+            clipboard_shortcut_debounce: Duration::from_millis(config.clipboard_shortcut_debounce_ms),
+            capture_shortcut_debounce: Duration::from_millis(config.capture_shortcut_debounce_ms),
+        }
+    }
+}
+
+/// Timings currently in effect
+pub fn timings() -> AutohideTimings {
+    TIMINGS.lock().map(|timings| *timings).unwrap_or_default()
+}
+
+/// Use `config` from the next focus change or shortcut press on
+pub fn apply(config: &AutohideConfig) {
+    ON_BLUR.store(config.on_blur, Ordering::Relaxed);
+    if let Ok(mut timings) = TIMINGS.lock() {
+        *timings = AutohideTimings::from(config);
+    }
+}
+
+/// Whether the launcher should hide when it loses focus
+pub fn on_blur_enabled() -> bool {
+    resolve_on_blur(std::env::var(AUTOHIDE_ENV).ok().as_deref(), ON_BLUR.load(Ordering::Relaxed))
+}
+
+/// A yes/no value of the environment variable wins over the setting;
+/// anything else leaves the setting in charge
+fn resolve_on_blur(env: Option<&str>, configured: bool) -> bool {
+    match env.map(|value| value.trim().to_ascii_lowercase()).as_deref() {
+        Some("0" | "false" | "no" | "off") => false,
+        Some("1" | "true" | "yes" | "on") => true,
+        _ => configured,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_to_durations() {
+        let defaults = AutohideTimings::default();
+        assert_eq!(defaults.show_blur_grace, Duration::from_millis(2000));
+        assert_eq!(defaults.focus_blur_grace, Duration::from_millis(250));
+        assert_eq!(defaults.main_shortcut_debounce, Duration::from_millis(350));
+        assert_eq!(defaults.clipboard_shortcut_debounce, Duration::from_millis(400));
+        assert_eq!(defaults.capture_shortcut_debounce, Duration::from_millis(500));
+
+        let instant = AutohideConfig {
+            show_blur_grace_ms: 0,
+            focus_blur_grace_ms: 0,
+            ..Default::default()
+        };
+        let timings = AutohideTimings::from(&instant);
+        assert_eq!(timings.show_blur_grace, Duration::ZERO);
+        assert_eq!(timings.focus_blur_grace, Duration::ZERO);
+        assert_eq!(timings.main_shortcut_debounce, defaults.main_shortcut_debounce);
+    }
+
+    #[test]
+    fn test_env_overrides_config() {
+        assert!(resolve_on_blur(None, AutohideConfig::default().on_blur));
+        assert!(!resolve_on_blur(None, false));
+        assert!(!resolve_on_blur(Some("0"), true));
+        assert!(!resolve_on_blur(Some(" FALSE "), true));
+        assert!(resolve_on_blur(Some("yes"), false));
+        // Empty or unknown values fall back to the setting
+        assert!(!resolve_on_blur(Some(""), false));
+        assert!(resolve_on_blur(Some("maybe"), true));
+    }
+}
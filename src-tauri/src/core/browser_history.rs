@@ -0,0 +1,640 @@
+//! Browser history search (Chromium-based browsers and Firefox).
+//!
+//! History databases are locked while the browser runs, so each one is
+//! copied to a temp file (with its WAL, if any) and read from there. The
+//! copy is reused until the browser writes to its database again.
+
+use crate::app::error::{AppError, AppResult};
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::sync::Mutex;
+
+/// Microseconds between 1601-01-01 (Chromium epoch) and 1970-01-01
+const CHROMIUM_EPOCH_OFFSET_MICROS: i64 = 11_644_473_600_000_000;
+
+/// Firefox's frecency buckets: visits within this many days weigh this much
+const FRECENCY_BUCKETS: &[(i64, i64)] = &[(4, 100), (14, 70), (31, 50), (90, 30)];
+
+/// Weight of visits older than every bucket
+const FRECENCY_OLD_WEIGHT: i64 = 10;
+
+/// Extra visits a typed (not clicked) visit counts for, after Firefox's
+/// typed-visit bonus
+const TYPED_VISIT_BONUS: i64 = 19;
+
+/// Temp copies by source database. Held across a query so a copy is never
+/// replaced while it is read.
+static COPIES: Lazy<Mutex<HashMap<PathBuf, CachedCopy>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+struct CachedCopy {
+    temp: PathBuf,
+    stamp: SourceStamp,
+}
+
+/// Modification time and size of a database and its WAL; when this changes
+/// the browser has written since the copy was made
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SourceStamp {
+    db: (SystemTime, u64),
+    wal: Option<(SystemTime, u64)>,
+}
+
+impl SourceStamp {
+    fn read(db_path: &Path) -> std::io::Result<Self> {
+        let stamp = |path: &Path| -> std::io::Result<(SystemTime, u64)> {
+            let metadata = std::fs::metadata(path)?;
+            Ok((metadata.modified()?, metadata.len()))
+        };
+        Ok(Self {
+            db: stamp(db_path)?,
+            wal: stamp(&wal_path(db_path)).ok(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryFormat {
+    /// `History` database with a `urls` table
+    Chromium,
+    /// `places.sqlite` with a `moz_places` table
+    Firefox,
+}
+
+/// One browser profile's history database
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryProfile {
+    /// Browser name, e.g. "Chrome"
+    pub browser: String,
+    /// Profile directory name, e.g. "Default" or "Profile 1"
+    pub name: String,
+    pub format: HistoryFormat,
+    pub db_path: PathBuf,
+}
+
+impl HistoryProfile {
+    /// Whether the profile is on the allowlist. Entries are either
+    /// "Browser/Profile" or just a profile name; an empty list allows all.
+    pub fn is_allowed(&self, allowlist: &[String]) -> bool {
+        allowlist.is_empty()
+            || allowlist.iter().any(|entry| {
+                let entry = entry.trim();
+                entry.eq_ignore_ascii_case(&self.name)
+                    || entry.eq_ignore_ascii_case(&format!("{}/{}", self.browser, self.name))
+            })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HistoryEntry {
+    pub title: String,
+    pub url: String,
+    pub visit_count: i64,
+    pub last_visit: DateTime<Utc>,
+    pub browser: String,
+    /// Ranking score; Firefox's own, or [`frecency`] for Chromium
+    pub frecency: i64,
+}
+
+/// Find history databases of installed browsers
+pub fn discover_profiles() -> Vec<HistoryProfile> {
+    let mut profiles = Vec::new();
+
+    for (browser, root) in chromium_roots() {
+        let Ok(entries) = std::fs::read_dir(&root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let db_path = entry.path().join("History");
+            if db_path.is_file() {
+                profiles.push(HistoryProfile {
+                    browser: browser.to_string(),
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    format: HistoryFormat::Chromium,
+                    db_path,
+                });
+            }
+        }
+    }
+
+    if let Some(root) = firefox_root() {
+        if let Ok(entries) = std::fs::read_dir(&root) {
+            for entry in entries.flatten() {
+                let db_path = entry.path().join("places.sqlite");
+                if db_path.is_file() {
+                    profiles.push(HistoryProfile {
+                        browser: "Firefox".to_string(),
+                        name: entry.file_name().to_string_lossy().to_string(),
+                        format: HistoryFormat::Firefox,
+                        db_path,
+                    });
+                }
+            }
+        }
+    }
+
+    profiles
+}
+
+fn chromium_roots() -> Vec<(&'static str, PathBuf)> {
+    let browsers: &[(&str, &str)] = if cfg!(target_os = "windows") {
+        &[
+            ("Chrome", r"Google\Chrome\User Data"),
+            ("Edge", r"Microsoft\Edge\User Data"),
+            ("Brave", r"BraveSoftware\Brave-Browser\User Data"),
+        ]
+    } else if cfg!(target_os = "macos") {
+        &[
+            ("Chrome", "Google/Chrome"),
+            ("Edge", "Microsoft Edge"),
+            ("Brave", "BraveSoftware/Brave-Browser"),
+        ]
+    } else {
+        &[
+            ("Chrome", "google-chrome"),
+            ("Chromium", "chromium"),
+            ("Edge", "microsoft-edge"),
+            ("Brave", "BraveSoftware/Brave-Browser"),
+        ]
+    };
+
+    let Some(base) = app_data_base(true) else {
+        return Vec::new();
+    };
+    browsers
+        .iter()
+        .map(|(browser, dir)| (*browser, base.join(dir)))
+        .collect()
+}
+
+fn firefox_root() -> Option<PathBuf> {
+    if cfg!(target_os = "linux") {
+        let home = std::env::var("HOME").ok()?;
+        return Some(Path::new(&home).join(".mozilla").join("firefox"));
+    }
+    let base = app_data_base(false)?;
+    if cfg!(target_os = "windows") {
+        Some(base.join("Mozilla").join("Firefox").join("Profiles"))
+    } else {
+        Some(base.join("Firefox").join("Profiles"))
+    }
+}
+
+/// Per-user application data folder. On Windows Chromium lives under
+/// LOCALAPPDATA and Firefox under APPDATA.
+fn app_data_base(local: bool) -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        let var = if local { "LOCALAPPDATA" } else { "APPDATA" };
+        std::env::var(var).ok().map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        let home = std::env::var("HOME").ok()?;
+        Some(Path::new(&home).join("Library").join("Application Support"))
+    } else {
+        let home = std::env::var("HOME").ok()?;
+        Some(Path::new(&home).join(".config"))
+    }
+}
+
+fn unix_micros_to_utc(micros: i64) -> DateTime<Utc> {
+    let secs = micros.div_euclid(1_000_000);
+    let nanos = (micros.rem_euclid(1_000_000) * 1_000) as u32;
+    Utc.timestamp_opt(secs, nanos).single().unwrap_or_default()
+}
+
+fn chromium_time_to_utc(micros: i64) -> DateTime<Utc> {
+    unix_micros_to_utc(micros - CHROMIUM_EPOCH_OFFSET_MICROS)
+}
+
+fn utc_to_chromium_time(time: DateTime<Utc>) -> i64 {
+    time.timestamp_micros() + CHROMIUM_EPOCH_OFFSET_MICROS
+}
+
+/// Escape `%`, `_` and `\` for a LIKE pattern using `ESCAPE '\'`
+fn like_pattern(query: &str) -> String {
+    let mut pattern = String::from("%");
+    for c in query.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+fn wal_path(db_path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}-wal", db_path.display()))
+}
+
+/// Copy a database (and its WAL) next to a fresh temp name
+fn copy_to_temp(db_path: &Path) -> std::io::Result<PathBuf> {
+    let temp = std::env::temp_dir().join(format!("omnibox_history_{}.sqlite", uuid::Uuid::new_v4()));
+    std::fs::copy(db_path, &temp)?;
+
+    let wal = wal_path(db_path);
+    if wal.is_file() {
+        let _ = std::fs::copy(&wal, format!("{}-wal", temp.display()));
+    }
+    Ok(temp)
+}
+
+fn remove_temp(temp: &Path) {
+    let _ = std::fs::remove_file(temp);
+    let _ = std::fs::remove_file(format!("{}-wal", temp.display()));
+    let _ = std::fs::remove_file(format!("{}-shm", temp.display()));
+}
+
+/// The cached copy of `db_path` in `copies`, copying it again when the
+/// source changed since
+fn fresh_copy(copies: &mut HashMap<PathBuf, CachedCopy>, db_path: &Path) -> std::io::Result<PathBuf> {
+    let stamp = SourceStamp::read(db_path)?;
+    if let Some(cached) = copies.get(db_path) {
+        if cached.stamp == stamp && cached.temp.is_file() {
+            return Ok(cached.temp.clone());
+        }
+    }
+
+    let temp = copy_to_temp(db_path)?;
+    let fresh = CachedCopy { temp: temp.clone(), stamp };
+    if let Some(stale) = copies.insert(db_path.to_path_buf(), fresh) {
+        remove_temp(&stale.temp);
+    }
+    Ok(temp)
+}
+
+/// Delete every temp copy; called when the app quits
+pub async fn remove_copies() {
+    for (_, copy) in COPIES.lock().await.drain() {
+        remove_temp(&copy.temp);
+    }
+}
+
+/// Query one profile's history for entries matching `query` visited since
+/// `cutoff`, best first
+pub async fn query_profile(
+    profile: &HistoryProfile,
+    query: &str,
+    cutoff: DateTime<Utc>,
+    limit: usize,
+) -> AppResult<Vec<HistoryEntry>> {
+    let mut copies = COPIES.lock().await;
+    let temp = fresh_copy(&mut copies, &profile.db_path).map_err(|e| {
+        AppError::Database(format!(
+            "Could not copy {} history ({}), it may be locked: {}",
+            profile.browser, profile.name, e
+        ))
+    })?;
+    let result = query_copy(profile, &temp, query, cutoff, Utc::now(), limit).await;
+    if result.is_err() {
+        // Don't keep serving a copy that can't be read
+        if let Some(broken) = copies.remove(&profile.db_path) {
+            remove_temp(&broken.temp);
+        }
+    }
+    result
+}
+
+async fn query_copy(
+    profile: &HistoryProfile,
+    db_path: &Path,
+    query: &str,
+    cutoff: DateTime<Utc>,
+    now: DateTime<Utc>,
+    limit: usize,
+) -> AppResult<Vec<HistoryEntry>> {
+    // Not read-only: SQLite needs write access to fold a copied WAL back in
+    let options = SqliteConnectOptions::new().filename(db_path);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await?;
+
+    // Firefox keeps its own frecency; for Chromium the same score is built
+    // from the bucket each row's last visit falls in
+    let rows: Result<Vec<(String, Option<String>, i64, i64, i64)>, _> = match profile.format {
+        HistoryFormat::Chromium => {
+            let bucket_case = FRECENCY_BUCKETS
+                .iter()
+                .filter_map(|(days, weight)| {
+                    let since = now.checked_sub_signed(TimeDelta::try_days(*days)?)?;
+                    Some(format!("WHEN last_visit_time >= {} THEN {}", utc_to_chromium_time(since), weight))
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            let sql = format!(
+                "SELECT url, title, visit_count, last_visit_time,
+                        (visit_count + {bonus} * typed_count)
+                          * CASE {bucket_case} ELSE {old} END AS frecency
+                 FROM urls
+                 WHERE (title LIKE ?1 ESCAPE '\\' OR url LIKE ?1 ESCAPE '\\')
+                   AND last_visit_time >= ?2 AND hidden = 0
+                 ORDER BY frecency DESC, last_visit_time DESC LIMIT ?3",
+                bonus = TYPED_VISIT_BONUS,
+                old = FRECENCY_OLD_WEIGHT,
+            );
+            sqlx::query_as(&sql)
+                .bind(like_pattern(query))
+                .bind(utc_to_chromium_time(cutoff))
+                .bind(limit as i64)
+                .fetch_all(&pool)
+                .await
+        }
+        HistoryFormat::Firefox => {
+            sqlx::query_as(
+                "SELECT url, title, visit_count, last_visit_date, MAX(frecency, 0) FROM moz_places
+                 WHERE (title LIKE ?1 ESCAPE '\\' OR url LIKE ?1 ESCAPE '\\')
+                   AND last_visit_date >= ?2 AND hidden = 0
+                 ORDER BY frecency DESC, last_visit_date DESC LIMIT ?3",
+            )
+            .bind(like_pattern(query))
+            .bind(cutoff.timestamp_micros())
+            .bind(limit as i64)
+            .fetch_all(&pool)
+            .await
+        }
+    };
+    pool.close().await;
+    let rows = rows?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(url, title, visit_count, last_visit, frecency)| HistoryEntry {
+            title: title.filter(|t| !t.is_empty()).unwrap_or_else(|| url.clone()),
+            url,
+            visit_count,
+            last_visit: match profile.format {
+                HistoryFormat::Chromium => chromium_time_to_utc(last_visit),
+                HistoryFormat::Firefox => unix_micros_to_utc(last_visit),
+            },
+            browser: profile.browser.clone(),
+            frecency,
+        })
+        .collect())
+}
+
+/// Firefox-style frecency from per-URL counters: every visit scores the
+/// weight of the bucket the last visit falls in, typed visits score extra
+pub fn frecency(visit_count: i64, typed_count: i64, last_visit: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    let age_days = (now - last_visit).num_days();
+    let weight = FRECENCY_BUCKETS
+        .iter()
+        .find(|(days, _)| age_days < *days)
+        .map_or(FRECENCY_OLD_WEIGHT, |(_, weight)| *weight);
+    let visits = visit_count.max(0).saturating_add(typed_count.max(0).saturating_mul(TYPED_VISIT_BONUS));
+    visits.saturating_mul(weight)
+}
+
+/// Merge duplicates (same URL across profiles) and sort best first
+pub fn rank(entries: Vec<HistoryEntry>) -> Vec<HistoryEntry> {
+    let mut by_url: HashMap<String, HistoryEntry> = HashMap::new();
+    for entry in entries {
+        match by_url.get_mut(&entry.url) {
+            Some(existing) => {
+                existing.visit_count += entry.visit_count;
+                existing.frecency = existing.frecency.saturating_add(entry.frecency);
+                if entry.last_visit > existing.last_visit {
+                    existing.last_visit = entry.last_visit;
+                }
+            }
+            None => {
+                by_url.insert(entry.url.clone(), entry);
+            }
+        }
+    }
+
+    let mut ranked: Vec<HistoryEntry> = by_url.into_values().collect();
+    ranked.sort_by(|a, b| {
+        b.frecency
+            .cmp(&a.frecency)
+            .then_with(|| b.last_visit.cmp(&a.last_visit))
+            .then_with(|| a.url.cmp(&b.url))
+    });
+    ranked
+}
+
+/// Search all allowed profiles. Profiles that can't be read (locked,
+/// corrupt, unknown schema) are skipped with a warning.
+pub async fn search(
+    profiles: &[HistoryProfile],
+    query: &str,
+    max_age_days: u32,
+    limit: usize,
+) -> Vec<HistoryEntry> {
+    let cutoff = TimeDelta::try_days(max_age_days as i64)
+        .and_then(|age| Utc::now().checked_sub_signed(age))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC);
+    let mut entries = Vec::new();
+
+    for profile in profiles {
+        match query_profile(profile, query, cutoff, limit).await {
+            Ok(found) => entries.extend(found),
+            Err(e) => tracing::warn!("Skipping {} history ({}): {}", profile.browser, profile.name, e),
+        }
+    }
+
+    let mut ranked = rank(entries);
+    ranked.truncate(limit);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn entry(url: &str, visit_count: i64, days_ago: i64, now: DateTime<Utc>) -> HistoryEntry {
+        let last_visit = now - Duration::days(days_ago);
+        HistoryEntry {
+            title: url.to_string(),
+            url: url.to_string(),
+            visit_count,
+            last_visit,
+            browser: "Chrome".to_string(),
+            frecency: frecency(visit_count, 0, last_visit, now),
+        }
+    }
+
+    /// Write a minimal Chromium `History` database
+    async fn sample_chromium_db(now: DateTime<Utc>) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("omnibox_history_test_{}", uuid::Uuid::new_v4()));
+        let options = SqliteConnectOptions::new().filename(&path).create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+
+        sqlx::query(
+            "CREATE TABLE urls (id INTEGER PRIMARY KEY, url LONGVARCHAR, title LONGVARCHAR,
+             visit_count INTEGER DEFAULT 0 NOT NULL, typed_count INTEGER DEFAULT 0 NOT NULL,
+             last_visit_time INTEGER NOT NULL, hidden INTEGER DEFAULT 0 NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let rows = [
+            ("https://docs.rs/tokio", "tokio - Rust", 3, 0, 1, 0),
+            ("https://tokio.rs/blog", "Tokio blog", 3, 0, 10, 0),
+            ("https://old.example/tokio", "Ancient tokio page", 50, 0, 400, 0),
+            ("https://github.com/tokio-rs/tokio", "", 1, 0, 2, 0),
+            ("https://crates.io/crates/tokio", "tokio - crates.io", 1, 1, 20, 0),
+            ("https://hidden.example/tokio", "Hidden redirect", 1, 0, 1, 1),
+            ("https://unrelated.example", "Something else", 9, 0, 1, 0),
+        ];
+        for (url, title, visits, typed, days_ago, hidden) in rows {
+            sqlx::query(
+                "INSERT INTO urls (url, title, visit_count, typed_count, last_visit_time, hidden)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(url)
+            .bind(title)
+            .bind(visits)
+            .bind(typed)
+            .bind(utc_to_chromium_time(now - Duration::days(days_ago)))
+            .bind(hidden)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        pool.close().await;
+        path
+    }
+
+    #[tokio::test]
+    async fn test_reads_chromium_history() {
+        let now = Utc::now();
+        let db_path = sample_chromium_db(now).await;
+        let profile = HistoryProfile {
+            browser: "Chrome".to_string(),
+            name: "Default".to_string(),
+            format: HistoryFormat::Chromium,
+            db_path: db_path.clone(),
+        };
+
+        let found = query_profile(&profile, "tokio", now - Duration::days(90), 10).await.unwrap();
+        let ranked = rank(found);
+        let urls: Vec<&str> = ranked.iter().map(|e| e.url.as_str()).collect();
+
+        // Hidden, unrelated and too-old entries are excluded; the typed
+        // visit outweighs more recent clicks
+        assert_eq!(
+            urls,
+            vec![
+                "https://crates.io/crates/tokio",
+                "https://docs.rs/tokio",
+                "https://tokio.rs/blog",
+                "https://github.com/tokio-rs/tokio",
+            ]
+        );
+        assert_eq!(ranked[1].frecency, frecency(3, 0, ranked[1].last_visit, now));
+        // Untitled pages fall back to the URL
+        assert_eq!(ranked[3].title, "https://github.com/tokio-rs/tokio");
+        assert_eq!(ranked[1].title, "tokio - Rust");
+        assert!((ranked[1].last_visit - (now - Duration::days(1))).num_seconds().abs() <= 1);
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[tokio::test]
+    async fn test_missing_database_is_an_error() {
+        let profile = HistoryProfile {
+            browser: "Chrome".to_string(),
+            name: "Default".to_string(),
+            format: HistoryFormat::Chromium,
+            db_path: PathBuf::from("/nonexistent/History"),
+        };
+        assert!(query_profile(&profile, "x", Utc::now(), 10).await.is_err());
+        assert!(search(&[profile.clone()], "x", 90, 10).await.is_empty());
+        // A huge age limit means no limit rather than an overflow
+        assert!(search(&[profile], "x", u32::MAX, 10).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_copy_is_reused_until_the_source_changes() {
+        let now = Utc::now();
+        let db_path = sample_chromium_db(now).await;
+        let mut copies = HashMap::new();
+
+        let first = fresh_copy(&mut copies, &db_path).unwrap();
+        assert_eq!(fresh_copy(&mut copies, &db_path).unwrap(), first);
+
+        // A browser write changes the size (and usually the mtime)
+        let mut data = std::fs::read(&db_path).unwrap();
+        data.extend_from_slice(&[0u8; 4096]);
+        std::fs::write(&db_path, data).unwrap();
+        let second = fresh_copy(&mut copies, &db_path).unwrap();
+        assert_ne!(second, first);
+        assert!(!first.exists());
+
+        remove_temp(&second);
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_frecency_buckets() {
+        let now = Utc::now();
+        let days_ago = |days| now - Duration::days(days);
+        assert_eq!(frecency(2, 0, days_ago(1), now), 200);
+        assert_eq!(frecency(2, 0, days_ago(20), now), 100);
+        assert_eq!(frecency(2, 0, days_ago(365), now), 20);
+        // A typed visit counts as many clicks
+        assert_eq!(frecency(1, 1, days_ago(1), now), 2000);
+        assert_eq!(frecency(i64::MAX, i64::MAX, days_ago(1), now), i64::MAX);
+    }
+
+    #[test]
+    fn test_rank_prefers_recent_visits() {
+        let now = Utc::now();
+        let ranked = rank(vec![
+            entry("https://a.example", 5, 30, now),
+            entry("https://b.example", 5, 1, now),
+            entry("https://c.example", 5, 7, now),
+        ]);
+        let urls: Vec<&str> = ranked.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://b.example", "https://c.example", "https://a.example"]);
+        let scores: Vec<i64> = ranked.iter().map(|e| e.frecency).collect();
+        assert_eq!(scores, vec![500, 350, 250]);
+    }
+
+    #[test]
+    fn test_rank_merges_profiles_and_weighs_frequency() {
+        let now = Utc::now();
+        let ranked = rank(vec![
+            entry("https://a.example", 1, 2, now),
+            entry("https://b.example", 20, 2, now),
+            entry("https://a.example", 2, 1, now),
+        ]);
+
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].url, "https://b.example");
+        assert_eq!(ranked[0].frecency, 2000);
+        assert_eq!(ranked[1].visit_count, 3);
+        assert_eq!(ranked[1].frecency, 300);
+        assert_eq!(ranked[1].last_visit, now - Duration::days(1));
+    }
+
+    #[test]
+    fn test_profile_allowlist() {
+        let profile = HistoryProfile {
+            browser: "Chrome".to_string(),
+            name: "Profile 1".to_string(),
+            format: HistoryFormat::Chromium,
+            db_path: PathBuf::new(),
+        };
+
+        assert!(profile.is_allowed(&[]));
+        assert!(profile.is_allowed(&["profile 1".to_string()]));
+        assert!(profile.is_allowed(&["Chrome/Profile 1".to_string()]));
+        assert!(!profile.is_allowed(&["Firefox/Profile 1".to_string(), "Default".to_string()]));
+    }
+
+    #[test]
+    fn test_like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern("50%_off"), "%50\\%\\_off%");
+    }
+}
@@ -0,0 +1,77 @@
+// Category filter: narrowing search results to the kinds the user picked
+/// A search result with a category ("Application", "File", ...) and a
+/// type ("app", "file", "folder", ...)
+pub trait Categorized {
+    fn category(&self) -> &str;
+    fn kind(&self) -> &str;
+}
+
+/// Keeps results whose category or type is one of `categories`, compared
+/// case-insensitively, in their existing order. An empty list keeps all.
+pub fn filter_categories<R: Categorized>(results: &mut Vec<R>, categories: &[String]) {
+    if categories.is_empty() {
+        return;
+    }
+    results.retain(|result| {
+        categories.iter().any(|wanted| {
+            wanted.eq_ignore_ascii_case(result.category()) || wanted.eq_ignore_ascii_case(result.kind())
+        })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item {
+        title: &'static str,
+        category: &'static str,
+        kind: &'static str,
+    }
+
+    impl Categorized for Item {
+        fn category(&self) -> &str {
+            self.category
+        }
+
+        fn kind(&self) -> &str {
+            self.kind
+        }
+    }
+
+    fn item(title: &'static str, category: &'static str, kind: &'static str) -> Item {
+        Item { title, category, kind }
+    }
+
+    fn results() -> Vec<Item> {
+        vec![
+            item("Code", "Application", "app"),
+            item("code.txt", "File", "file"),
+            item("Codex", "Application", "app"),
+            item("code", "File", "folder"),
+            item("Coder", "Application", "app"),
+        ]
+    }
+
+    fn titles(items: &[Item]) -> Vec<&str> {
+        items.iter().map(|item| item.title).collect()
+    }
+
+    #[test]
+    fn test_only_applications_keeps_app_order() {
+        let mut items = results();
+        filter_categories(&mut items, &["Application".to_string()]);
+        assert_eq!(titles(&items), vec!["Code", "Codex", "Coder"]);
+    }
+
+    #[test]
+    fn test_matches_types_case_insensitively() {
+        let mut items = results();
+        filter_categories(&mut items, &["FOLDER".to_string(), "application".to_string()]);
+        assert_eq!(titles(&items), vec!["Code", "Codex", "code", "Coder"]);
+
+        let mut all = results();
+        filter_categories(&mut all, &[]);
+        assert_eq!(all.len(), 5);
+    }
+}
@@ -0,0 +1,323 @@
+// At-rest encryption for clipboard history
+//
+// `plain_text` and `data` are sealed with AES-256-GCM under a random key that
+// the OS keeps for us: a DPAPI-protected file on Windows, the login Keychain
+// on macOS. Whether a row is sealed is recorded next to it by the storage;
+// the marker on sealed values only identifies the format. With encryption
+// on, `content_hash` is an HMAC of the plain hash, so the database does not
+// reveal which rows hold equal content or let guesses be confirmed.
+use crate::app::error::{AppError, AppResult};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::path::Path;
+
+/// Prefix of a sealed `data` blob, followed by nonce and ciphertext
+pub const BLOB_MAGIC: &[u8; 4] = b"OBX1";
+/// Prefix of a sealed `plain_text` value, followed by base64(nonce + ciphertext)
+pub const TEXT_PREFIX: &str = "obx1:";
+
+const NONCE_LEN: usize = 12;
+pub const KEY_LEN: usize = 32;
+
+/// Derives the content hash key from the encryption key
+const HASH_KEY_LABEL: &[u8] = b"omnibox clipboard content hash";
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Clone)]
+pub struct ClipboardCipher {
+    cipher: Aes256Gcm,
+    hash_key: [u8; KEY_LEN],
+}
+
+impl ClipboardCipher {
+    pub fn new(key: &[u8; KEY_LEN]) -> Self {
+        let mut hash_key = [0u8; KEY_LEN];
+        hash_key.copy_from_slice(&hmac_sha256(key, HASH_KEY_LABEL));
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+            hash_key,
+        }
+    }
+
+    /// `content_hash` as stored while encrypting: hex HMAC-SHA256 of the
+    /// plain hash under a key derived from the encryption key
+    pub fn keyed_hash(&self, content_hash: &str) -> String {
+        hmac_sha256(&self.hash_key, content_hash.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    pub fn generate_key() -> [u8; KEY_LEN] {
+        let mut key = [0u8; KEY_LEN];
+        key.copy_from_slice(&Aes256Gcm::generate_key(OsRng));
+        key
+    }
+
+    pub fn seal_blob(&self, data: &[u8]) -> AppResult<Vec<u8>> {
+        let mut sealed = BLOB_MAGIC.to_vec();
+        sealed.extend(self.seal(data)?);
+        Ok(sealed)
+    }
+
+    /// Decrypt a blob made by [`Self::seal_blob`]
+    pub fn open_blob(&self, stored: Vec<u8>) -> AppResult<Vec<u8>> {
+        let sealed = stored
+            .strip_prefix(BLOB_MAGIC.as_slice())
+            .ok_or_else(|| AppError::Parse("Encrypted clipboard data has an unknown format".to_string()))?;
+        self.open(sealed)
+    }
+
+    pub fn seal_text(&self, text: &str) -> AppResult<String> {
+        let sealed = self.seal(text.as_bytes())?;
+        Ok(format!(
+            "{}{}",
+            TEXT_PREFIX,
+            base64::engine::general_purpose::STANDARD.encode(sealed)
+        ))
+    }
+
+    /// Decrypt text made by [`Self::seal_text`]
+    pub fn open_text(&self, stored: String) -> AppResult<String> {
+        let encoded = stored
+            .strip_prefix(TEXT_PREFIX)
+            .ok_or_else(|| AppError::Parse("Encrypted clipboard text has an unknown format".to_string()))?;
+        let sealed = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| AppError::Parse(format!("Invalid encrypted clipboard text: {}", e)))?;
+        String::from_utf8(self.open(&sealed)?)
+            .map_err(|e| AppError::Parse(format!("Decrypted clipboard text is not UTF-8: {}", e)))
+    }
+
+    fn seal(&self, plaintext: &[u8]) -> AppResult<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| AppError::Unknown("Failed to encrypt clipboard item".to_string()))?;
+        let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend(ciphertext);
+        Ok(sealed)
+    }
+
+    fn open(&self, sealed: &[u8]) -> AppResult<Vec<u8>> {
+        if sealed.len() < NONCE_LEN {
+            return Err(AppError::Parse("Encrypted clipboard item is truncated".to_string()));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| AppError::Parse("Clipboard item could not be decrypted".to_string()))
+    }
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Load the clipboard key from the OS keychain. With `create`, a new key is
+/// generated and stored when there is none yet.
+pub fn load_key(data_dir: &Path, create: bool) -> AppResult<Option<[u8; KEY_LEN]>> {
+    if let Some(stored) = keystore::read(data_dir)? {
+        let key: [u8; KEY_LEN] = stored
+            .as_slice()
+            .try_into()
+            .map_err(|_| AppError::Config("Stored clipboard key has the wrong length".to_string()))?;
+        return Ok(Some(key));
+    }
+    if !create {
+        return Ok(None);
+    }
+    let key = ClipboardCipher::generate_key();
+    keystore::write(data_dir, &key)?;
+    tracing::info!("Created clipboard encryption key");
+    Ok(Some(key))
+}
+
+/// The key sits next to the database, encrypted with DPAPI for the current user
+#[cfg(target_os = "windows")]
+mod keystore {
+    use crate::app::error::{AppError, AppResult};
+    use std::path::Path;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{LocalFree, HLOCAL};
+    use windows::Win32::Security::Cryptography::{CryptProtectData, CryptUnprotectData, CRYPT_INTEGER_BLOB};
+
+    const KEY_FILE: &str = "clipboard.key";
+
+    pub fn read(data_dir: &Path) -> AppResult<Option<Vec<u8>>> {
+        let path = data_dir.join(KEY_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let protected = std::fs::read(path)?;
+        let input = blob(&protected);
+        let mut output = CRYPT_INTEGER_BLOB::default();
+        unsafe { CryptUnprotectData(&input, None, None, None, None, 0, &mut output) }
+            .map_err(|e| AppError::Config(format!("DPAPI could not unlock the clipboard key: {}", e)))?;
+        Ok(Some(take(output)))
+    }
+
+    pub fn write(data_dir: &Path, key: &[u8]) -> AppResult<()> {
+        let input = blob(key);
+        let mut output = CRYPT_INTEGER_BLOB::default();
+        unsafe { CryptProtectData(&input, PCWSTR::null(), None, None, None, 0, &mut output) }
+            .map_err(|e| AppError::Config(format!("DPAPI could not protect the clipboard key: {}", e)))?;
+        std::fs::write(data_dir.join(KEY_FILE), take(output))?;
+        Ok(())
+    }
+
+    fn blob(data: &[u8]) -> CRYPT_INTEGER_BLOB {
+        CRYPT_INTEGER_BLOB {
+            cbData: data.len() as u32,
+            pbData: data.as_ptr() as *mut u8,
+        }
+    }
+
+    /// Copy a DPAPI output blob and free the system allocation
+    fn take(output: CRYPT_INTEGER_BLOB) -> Vec<u8> {
+        let data = unsafe { std::slice::from_raw_parts(output.pbData, output.cbData as usize) }.to_vec();
+        unsafe {
+            let _ = LocalFree(HLOCAL(output.pbData as _));
+        }
+        data
+    }
+}
+
+/// The key is a generic password in the login Keychain, stored as hex
+#[cfg(target_os = "macos")]
+mod keystore {
+    use crate::app::error::{AppError, AppResult};
+    use std::path::Path;
+    use std::process::Command;
+
+    const SERVICE: &str = "OmniBox Clipboard";
+    const ACCOUNT: &str = "clipboard-history";
+    /// `security` exit code for a missing item
+    const ITEM_NOT_FOUND: i32 = 44;
+
+    pub fn read(_data_dir: &Path) -> AppResult<Option<Vec<u8>>> {
+        let output = Command::new("security")
+            .args(["find-generic-password", "-s", SERVICE, "-a", ACCOUNT, "-w"])
+            .output()?;
+        if output.status.code() == Some(ITEM_NOT_FOUND) {
+            return Ok(None);
+        }
+        if !output.status.success() {
+            return Err(AppError::Config(format!(
+                "Keychain could not read the clipboard key: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let hex = String::from_utf8_lossy(&output.stdout);
+        decode_hex(hex.trim())
+            .map(Some)
+            .ok_or_else(|| AppError::Config("Keychain holds an invalid clipboard key".to_string()))
+    }
+
+    pub fn write(_data_dir: &Path, key: &[u8]) -> AppResult<()> {
+        let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+        let output = Command::new("security")
+            .args(["add-generic-password", "-U", "-s", SERVICE, "-a", ACCOUNT, "-w", &hex])
+            .output()?;
+        if !output.status.success() {
+            return Err(AppError::Config(format!(
+                "Keychain could not store the clipboard key: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
+    fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+        if hex.len() % 2 != 0 {
+            return None;
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+            .collect()
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod keystore {
+    use crate::app::error::{AppError, AppResult};
+    use std::path::Path;
+
+    pub fn read(_data_dir: &Path) -> AppResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    pub fn write(_data_dir: &Path, _key: &[u8]) -> AppResult<()> {
+        Err(AppError::Config(
+            "Clipboard encryption needs the Windows or macOS keychain".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher() -> ClipboardCipher {
+        ClipboardCipher::new(&ClipboardCipher::generate_key())
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let cipher = cipher();
+        let sealed = cipher.seal_text("hunter2 and more").unwrap();
+        assert!(sealed.starts_with(TEXT_PREFIX));
+        assert!(!sealed.contains("hunter2"));
+        assert_eq!(cipher.open_text(sealed).unwrap(), "hunter2 and more");
+    }
+
+    #[test]
+    fn test_blob_round_trip() {
+        let cipher = cipher();
+        let data: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        let sealed = cipher.seal_blob(&data).unwrap();
+        assert!(sealed.starts_with(BLOB_MAGIC));
+        assert_ne!(&sealed[BLOB_MAGIC.len()..], data.as_slice());
+        assert_eq!(cipher.open_blob(sealed).unwrap(), data);
+    }
+
+    #[test]
+    fn test_same_plaintext_seals_differently() {
+        let cipher = cipher();
+        assert_ne!(cipher.seal_text("same").unwrap(), cipher.seal_text("same").unwrap());
+    }
+
+    #[test]
+    fn test_unsealed_values_are_rejected() {
+        let cipher = cipher();
+        assert!(matches!(cipher.open_text("plain".to_string()), Err(AppError::Parse(_))));
+        assert!(matches!(cipher.open_blob(vec![1, 2, 3]), Err(AppError::Parse(_))));
+    }
+
+    #[test]
+    fn test_keyed_hash() {
+        let key = ClipboardCipher::generate_key();
+        let hash = format!("{:x}", md5::compute("hunter2"));
+        let keyed = ClipboardCipher::new(&key).keyed_hash(&hash);
+        assert_eq!(keyed.len(), 64);
+        assert_ne!(keyed, hash);
+        // Stable for one key, so equal content still dedups
+        assert_eq!(ClipboardCipher::new(&key).keyed_hash(&hash), keyed);
+        assert_ne!(cipher().keyed_hash(&hash), keyed);
+    }
+
+    #[test]
+    fn test_wrong_key_fails() {
+        let sealed = cipher().seal_text("secret").unwrap();
+        assert!(matches!(cipher().open_text(sealed), Err(AppError::Parse(_))));
+    }
+}
@@ -4,12 +4,24 @@ pub mod storage;
 pub mod filter;
 pub mod monitor;
 pub mod window;
+pub mod transfer;
+pub mod crypto;
+pub mod thumbnail;
+pub mod paste;
+pub mod ring;
+pub mod transforms;
+pub mod sync;
 
 pub use types::{ClipboardContent, ImageFormat};
-pub use storage::{ClipboardStorage, ClipboardHistoryItem};
+pub use storage::{ClipboardStorage, ClipboardHistoryItem, ClipboardTypeFilter, CombinedText, PruneLimits, Upsert};
 pub use filter::ContentFilter;
 pub use monitor::ClipboardMonitor;
 pub use window::ClipboardWindowManager;
+pub use transfer::ImportSummary;
+pub use crypto::ClipboardCipher;
+pub use paste::{ClipboardSnapshot, RestoreQueue};
+pub use transforms::{Transform, Transformed};
+pub use sync::ClipboardSync;
 
 pub struct ClipboardManager;
 
@@ -1,23 +1,45 @@
 use crate::app::error::AppResult;
 use crate::core::clipboard::types::ClipboardContent;
-use crate::core::clipboard::storage::{ClipboardStorage, ClipboardHistoryItem};
+use crate::core::clipboard::storage::{ClipboardStorage, ClipboardHistoryItem, PruneLimits, Upsert};
 use crate::core::clipboard::filter::ContentFilter;
+use crate::core::clipboard::ring;
+use crate::core::clipboard::sync;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::collections::HashSet;
+use std::path::PathBuf;
 use tauri::AppHandle;
 use tokio::sync::RwLock;
 use tokio::time::sleep;
 use chrono::Utc;
 
+/// Inserts within this window share a single prune pass
+const PRUNE_DEBOUNCE: Duration = Duration::from_secs(5);
+
+/// Sensitive items expire by age, so history is also pruned this often when
+/// nothing new is copied
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Where a copy came from: the foreground app and its window title
+#[derive(Debug, Default)]
+struct CopySource {
+    app: Option<String>,
+    window: Option<String>,
+}
+
 /// Clipboard monitor that watches for clipboard changes
 pub struct ClipboardMonitor {
     app_handle: AppHandle,
     is_running: Arc<RwLock<bool>>,
     last_hash: Arc<RwLock<Option<String>>>,
     storage: Arc<RwLock<Option<Arc<ClipboardStorage>>>>,
-    content_filter: ContentFilter,
+    content_filter: Arc<RwLock<ContentFilter>>,
+    mask_sensitive: Arc<AtomicBool>,
     excluded_apps: Arc<RwLock<HashSet<String>>>,
+    prune_limits: Arc<RwLock<Option<PruneLimits>>>,
+    prune_pending: Arc<AtomicBool>,
+    sync_folder: Arc<RwLock<Option<PathBuf>>>,
 }
 
 impl ClipboardMonitor {
@@ -27,8 +49,12 @@ impl ClipboardMonitor {
             is_running: Arc::new(RwLock::new(false)),
             last_hash: Arc::new(RwLock::new(None)),
             storage: Arc::new(RwLock::new(None)),
-            content_filter: ContentFilter::new(),
+            content_filter: Arc::new(RwLock::new(ContentFilter::new())),
+            mask_sensitive: Arc::new(AtomicBool::new(true)),
             excluded_apps: Arc::new(RwLock::new(HashSet::new())),
+            prune_limits: Arc::new(RwLock::new(None)),
+            prune_pending: Arc::new(AtomicBool::new(false)),
+            sync_folder: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -47,6 +73,53 @@ impl ClipboardMonitor {
         }
     }
 
+    /// Set history limits; pruning is disabled until limits are set
+    pub async fn set_prune_limits(&self, limits: PruneLimits) {
+        *self.prune_limits.write().await = Some(limits);
+    }
+
+    /// Set the folder new text items are mirrored to; `None` stops mirroring
+    pub async fn set_sync_folder(&self, folder: Option<PathBuf>) {
+        *self.sync_folder.write().await = folder;
+    }
+
+    /// Prune history after [`PRUNE_DEBOUNCE`], coalescing repeated requests
+    fn schedule_prune(
+        storage: Arc<ClipboardStorage>,
+        limits: Arc<RwLock<Option<PruneLimits>>>,
+        pending: Arc<AtomicBool>,
+        delay: Duration,
+    ) {
+        if pending.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        tokio::spawn(async move {
+            sleep(delay).await;
+            pending.store(false, Ordering::SeqCst);
+
+            let Some(limits) = *limits.read().await else {
+                return;
+            };
+            if let Err(e) = storage
+                .prune(limits.max_items, limits.max_age_days, limits.max_total_bytes)
+                .await
+            {
+                tracing::warn!("Failed to prune clipboard history: {}", e);
+            }
+            if let Err(e) = storage.expire_sensitive(limits.sensitive_max_age_minutes).await {
+                tracing::warn!("Failed to expire sensitive clipboard items: {}", e);
+            }
+        });
+    }
+
+    /// Configure sensitive content handling: whether to store masked previews
+    /// and which source apps are always treated as sensitive
+    pub async fn set_sensitive_options(&self, mask: bool, sensitive_apps: &[String]) {
+        self.mask_sensitive.store(mask, Ordering::SeqCst);
+        *self.content_filter.write().await = ContentFilter::new().with_sensitive_apps(sensitive_apps);
+    }
+
     /// Check if an app is excluded
     async fn is_app_excluded(&self, app_name: &Option<String>) -> bool {
         if let Some(name) = app_name {
@@ -70,24 +143,53 @@ impl ClipboardMonitor {
         let app_handle = self.app_handle.clone();
         let storage = self.storage.clone();
         let content_filter = self.content_filter.clone();
+        let mask_sensitive = self.mask_sensitive.clone();
         let excluded_apps = self.excluded_apps.clone();
+        let prune_limits = self.prune_limits.clone();
+        let prune_pending = self.prune_pending.clone();
+        let sync_folder = self.sync_folder.clone();
+
+        // Enforce limits once at startup
+        if let Some(ref storage) = *storage.read().await {
+            Self::schedule_prune(
+                storage.clone(),
+                prune_limits.clone(),
+                prune_pending.clone(),
+                Duration::ZERO,
+            );
+        }
 
         tokio::spawn(async move {
             tracing::info!("Clipboard monitor started");
+            let mut last_prune = std::time::Instant::now();
             
             while *is_running.read().await {
+                if last_prune.elapsed() >= PRUNE_INTERVAL {
+                    last_prune = std::time::Instant::now();
+                    if let Some(ref storage) = *storage.read().await {
+                        Self::schedule_prune(
+                            storage.clone(),
+                            prune_limits.clone(),
+                            prune_pending.clone(),
+                            Duration::ZERO,
+                        );
+                    }
+                }
+
                 if let Ok(content) = Self::read_clipboard(&app_handle).await {
                     let content_hash = content.hash();
                     let mut last = last_hash.write().await;
                     
                     // Only process if content has changed and is not empty
                     if last.as_ref() != Some(&content_hash) && !content.is_empty() {
+                        // Ask for the foreground app before anything else,
+                        // so focus has the least time to move on. Off the
+                        // runtime: on macOS this runs osascript.
+                        let CopySource { app: source_app, window: source_window } =
+                            tokio::task::spawn_blocking(Self::active_source).await.unwrap_or_default();
                         *last = Some(content_hash.clone());
                         drop(last);
-                        
-                        // Get source app
-                        let source_app = Self::get_active_app();
-                        
+
                         // Check if app is excluded
                         if let Some(ref app_name) = source_app {
                             let excluded = excluded_apps.read().await;
@@ -101,34 +203,79 @@ impl ClipboardMonitor {
                         
                         // Check if content should be filtered (sensitive)
                         let plain_text = content.plain_text();
-                        let is_sensitive = content_filter.is_sensitive(&plain_text);
+                        let filter = content_filter.read().await.clone();
+                        let is_sensitive = filter.is_sensitive_from(&plain_text, source_app.as_deref());
+
+                        // Masked items keep only a redacted preview as text; the
+                        // original text moves to `data` so it can still be pasted.
+                        // Formatting is dropped: the HTML would show what the
+                        // preview hides.
+                        let masked = is_sensitive && mask_sensitive.load(Ordering::SeqCst);
+                        let (content, content_hash) = match content {
+                            ClipboardContent::Html { plain_text, .. } if masked => {
+                                let text = ClipboardContent::Text {
+                                    content: plain_text.clone(),
+                                    plain_text,
+                                };
+                                let hash = text.hash();
+                                (text, hash)
+                            }
+                            content => (content, content_hash),
+                        };
+                        let (plain_text, data) = if masked {
+                            let preview = filter.masked_preview(&plain_text, source_app.as_deref());
+                            let data = content.data().or_else(|| Some(plain_text.into_bytes()));
+                            (preview, data)
+                        } else {
+                            (plain_text, content.data())
+                        };
                         
                         // Save to storage if available
                         if let Some(ref storage) = *storage.read().await {
-                            // Check if this content already exists
-                            if !storage.exists_by_hash(&content_hash).await.unwrap_or(false) {
-                                let item = ClipboardHistoryItem {
-                                    id: uuid::Uuid::new_v4().to_string(),
-                                    content_type: content.content_type().to_string(),
-                                    content_hash,
-                                    plain_text: Some(plain_text),
-                                    data: content.data(),
-                                    source_app,
-                                    source_window: None,
-                                    is_favorite: false,
-                                    is_sensitive,
-                                    created_at: Utc::now(),
-                                    accessed_at: None,
-                                    access_count: 0,
-                                };
-                                
-                                if let Err(e) = storage.add_item(&item).await {
-                                    tracing::error!("Failed to save clipboard item: {}", e);
-                                } else {
-                                    tracing::debug!("Clipboard item saved: {}", item.id);
-                                    
+                            let item = ClipboardHistoryItem {
+                                id: uuid::Uuid::new_v4().to_string(),
+                                content_type: content.content_type().to_string(),
+                                content_hash,
+                                plain_text: Some(plain_text),
+                                data,
+                                thumbnail: None,
+                                source_app,
+                                source_window,
+                                is_favorite: false,
+                                is_sensitive,
+                                created_at: Utc::now(),
+                                accessed_at: None,
+                                access_count: 0,
+                            };
+
+                            // A re-copy moves the existing entry to the top
+                            match storage.upsert_item(&item).await {
+                                Err(e) => tracing::error!("Failed to save clipboard item: {}", e),
+                                Ok(saved) => {
+                                    tracing::debug!("Clipboard item saved: {:?}", saved);
+
                                     // Emit event to frontend
-                                    let _ = tauri::Emitter::emit(&app_handle, "clipboard-changed", &item.id);
+                                    let _ = tauri::Emitter::emit(&app_handle, "clipboard-changed", saved.id());
+
+                                    if matches!(saved, Upsert::Inserted(_)) {
+                                        // Pastes and restores only bump existing rows
+                                        ring::reset();
+                                        Self::schedule_prune(
+                                            storage.clone(),
+                                            prune_limits.clone(),
+                                            prune_pending.clone(),
+                                            PRUNE_DEBOUNCE,
+                                        );
+                                        if let Some(folder) = sync_folder.read().await.clone() {
+                                            if sync::should_sync(&item) {
+                                                tokio::spawn(async move {
+                                                    if let Err(e) = sync::mirror_item(&folder, &item).await {
+                                                        tracing::warn!("Failed to sync clipboard item: {}", e);
+                                                    }
+                                                });
+                                            }
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -164,6 +311,14 @@ impl ClipboardMonitor {
         // Try to read text (synchronous operation)
         if let Ok(text) = app_handle.clipboard().read_text() {
             if !text.is_empty() {
+                // Formatted text also comes as HTML; kept so a paste can
+                // restore the formatting
+                let html = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get().html());
+                if let Ok(html) = html.map(|html| html.trim().to_string()) {
+                    if !html.is_empty() {
+                        return Ok(ClipboardContent::Html { html, plain_text: text });
+                    }
+                }
                 return Ok(ClipboardContent::Text {
                     content: text.clone(),
                     plain_text: text,
@@ -182,26 +337,82 @@ impl ClipboardMonitor {
         })
     }
 
-    /// Get the name of the currently active application
-    #[cfg(target_os = "macos")]
-    fn get_active_app() -> Option<String> {
-        // Use AppleScript to get frontmost application
-        // This is a simple implementation - for production use cocoa bindings
-        None
+    /// The foreground app and window title
+    #[cfg(target_os = "windows")]
+    fn active_source() -> CopySource {
+        use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId};
+
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd.is_invalid() {
+                return CopySource::default();
+            }
+            let mut title = [0u16; 512];
+            let len = GetWindowTextW(hwnd, &mut title);
+            let title = String::from_utf16_lossy(&title[..len.max(0) as usize]);
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+
+            CopySource {
+                app: process_name(pid),
+                window: Some(title).filter(|title| !title.trim().is_empty()),
+            }
+        }
     }
 
-    #[cfg(target_os = "windows")]
-    fn get_active_app() -> Option<String> {
-        // Use Windows API to get foreground window process name
-        // This is a simple implementation - for production use winapi
-        None
+    /// The frontmost app and its front window title. The title needs
+    /// Accessibility access and is missing without it.
+    #[cfg(target_os = "macos")]
+    fn active_source() -> CopySource {
+        const SCRIPT: &str = r#"tell application "System Events"
+            set frontApp to first application process whose frontmost is true
+            set windowName to ""
+            try
+                set windowName to name of front window of frontApp
+            end try
+            return (name of frontApp) & linefeed & windowName
+        end tell"#;
+
+        let Ok(output) = std::process::Command::new("osascript").args(["-e", SCRIPT]).output() else {
+            return CopySource::default();
+        };
+        if !output.status.success() {
+            return CopySource::default();
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.trim_end_matches('\n').splitn(2, '\n');
+        let mut field = || lines.next().map(str::trim).filter(|s| !s.is_empty()).map(String::from);
+        CopySource {
+            app: field(),
+            window: field(),
+        }
     }
 
     #[cfg(target_os = "linux")]
-    fn get_active_app() -> Option<String> {
+    fn active_source() -> CopySource {
         // Use xdotool or similar to get active window
-        None
+        CopySource::default()
+    }
+}
+
+/// Name of process `pid`, without the `.exe` extension so it matches the
+/// app names used in the exclude and sensitive app lists
+#[cfg_attr(not(windows), allow(dead_code))]
+fn process_name(pid: u32) -> Option<String> {
+    if pid == 0 {
+        return None;
     }
+    let pid = sysinfo::Pid::from_u32(pid);
+    let mut system = sysinfo::System::new();
+    if !system.refresh_process(pid) {
+        return None;
+    }
+    let name = system.process(pid)?.name();
+    let name = match name.len().checked_sub(4) {
+        Some(stem) if name[stem..].eq_ignore_ascii_case(".exe") => &name[..stem],
+        _ => name,
+    };
+    Some(name.to_string())
 }
 
 #[cfg(test)]
@@ -213,4 +424,17 @@ mod tests {
         // This test would require a Tauri app handle, so we skip actual testing
         // Just verify the module compiles
     }
+
+    #[test]
+    fn test_process_name_of_pid() {
+        let exe = std::env::current_exe().unwrap();
+        let stem = exe.file_stem().unwrap().to_string_lossy();
+
+        let name = process_name(std::process::id()).unwrap();
+        // Linux reports at most 15 characters of the name
+        assert!(!name.is_empty() && stem.starts_with(&name), "{} vs {}", name, stem);
+        assert!(!name.to_lowercase().ends_with(".exe"));
+        assert_eq!(process_name(0), None);
+        assert_eq!(process_name(u32::MAX), None);
+    }
 }
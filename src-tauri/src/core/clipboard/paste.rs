@@ -0,0 +1,331 @@
+// Pasting through the system clipboard without losing what was on it
+use crate::app::error::{AppError, AppResult};
+use parking_lot::Mutex;
+use std::path::PathBuf;
+
+/// What was on the clipboard before a paste replaced it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardSnapshot {
+    Empty,
+    Text(String),
+    Image {
+        width: usize,
+        height: usize,
+        /// RGBA pixels
+        bytes: Vec<u8>,
+    },
+    /// Files copied in a file manager
+    Files(Vec<PathBuf>),
+}
+
+impl ClipboardSnapshot {
+    /// Reads the current clipboard: a file list first (Windows), then an
+    /// image, then text
+    pub fn capture() -> AppResult<Self> {
+        if let Some(paths) = read_file_list() {
+            return Ok(Self::Files(paths));
+        }
+        let mut clipboard = open()?;
+        if let Ok(image) = clipboard.get_image() {
+            return Ok(Self::Image {
+                width: image.width,
+                height: image.height,
+                bytes: image.bytes.into_owned(),
+            });
+        }
+        match clipboard.get_text() {
+            Ok(text) if !text.is_empty() => Ok(Self::Text(text)),
+            _ => Ok(Self::Empty),
+        }
+    }
+
+    /// Puts the snapshot back on the clipboard
+    pub fn restore(&self) -> AppResult<()> {
+        let mut clipboard = open()?;
+        let result = match self {
+            Self::Empty => clipboard.clear(),
+            Self::Text(text) => clipboard.set_text(text.clone()),
+            Self::Image { width, height, bytes } => clipboard.set_image(arboard::ImageData {
+                width: *width,
+                height: *height,
+                bytes: std::borrow::Cow::Borrowed(bytes),
+            }),
+            Self::Files(paths) => return write_file_list(paths),
+        };
+        result.map_err(|e| AppError::Clipboard(format!("Failed to restore the clipboard: {}", e)))
+    }
+}
+
+fn open() -> AppResult<arboard::Clipboard> {
+    arboard::Clipboard::new().map_err(|e| AppError::Clipboard(format!("Failed to open the clipboard: {}", e)))
+}
+
+/// Snapshots of the clipboard waiting to be restored after pastes.
+///
+/// Pastes in quick succession share the snapshot taken by the first, so a
+/// later paste never mistakes an earlier paste's item for the user's
+/// clipboard, and only the latest paste's restore runs.
+pub struct RestoreQueue<S> {
+    pending: Mutex<Pending<S>>,
+}
+
+struct Pending<S> {
+    /// Generation of the latest paste begun
+    latest: u64,
+    /// Pastes begun and not yet finished or abandoned, oldest first; the
+    /// newest of them restores the snapshot
+    live: Vec<u64>,
+    snapshot: Option<S>,
+}
+
+impl<S> Default for RestoreQueue<S> {
+    fn default() -> Self {
+        Self {
+            pending: Mutex::new(Pending {
+                latest: 0,
+                live: Vec::new(),
+                snapshot: None,
+            }),
+        }
+    }
+}
+
+impl<S> RestoreQueue<S> {
+    /// Starts a paste, taking a snapshot with `capture` unless an earlier
+    /// paste's restore is still pending. Returns the paste's generation.
+    pub fn begin(&self, capture: impl FnOnce() -> Option<S>) -> u64 {
+        let mut pending = self.pending.lock();
+        if pending.snapshot.is_none() {
+            pending.snapshot = capture();
+        }
+        pending.latest += 1;
+        let generation = pending.latest;
+        pending.live.push(generation);
+        generation
+    }
+
+    /// The snapshot to restore once paste `generation`'s delay is over;
+    /// `None` when a newer paste has taken over the restore
+    pub fn finish(&self, generation: u64) -> Option<S> {
+        let mut pending = self.pending.lock();
+        let newest = pending.live.last() == Some(&generation);
+        pending.live.retain(|&live| live != generation);
+        if newest {
+            pending.snapshot.take()
+        } else {
+            None
+        }
+    }
+
+    /// Ends paste `generation` without restoring. The restore passes back
+    /// to the newest earlier paste still waiting; with none, the snapshot
+    /// is dropped.
+    pub fn abandon(&self, generation: u64) {
+        let mut pending = self.pending.lock();
+        pending.live.retain(|&live| live != generation);
+        if pending.live.is_empty() {
+            pending.snapshot = None;
+        }
+    }
+}
+
+/// Sends the paste shortcut (Ctrl+V, or Cmd+V on macOS) to the focused window.
+/// On Windows, Shift, Alt and Win keys still held from a global shortcut are
+/// released first so the target sees a plain Ctrl+V.
+#[cfg(windows)]
+pub fn send_paste_keystroke() -> AppResult<()> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+        VIRTUAL_KEY, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT, VK_V,
+    };
+
+    let key = |vk: VIRTUAL_KEY, flags: KEYBD_EVENT_FLAGS| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: vk,
+                wScan: 0,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    };
+    // Ctrl goes down first so releasing Alt does not open the target's menu bar
+    let mut inputs = vec![key(VK_CONTROL, KEYBD_EVENT_FLAGS(0))];
+    for modifier in [VK_SHIFT, VK_MENU, VK_LWIN, VK_RWIN] {
+        if unsafe { GetAsyncKeyState(modifier.0 as i32) } < 0 {
+            inputs.push(key(modifier, KEYEVENTF_KEYUP));
+        }
+    }
+    inputs.extend([
+        key(VK_V, KEYBD_EVENT_FLAGS(0)),
+        key(VK_V, KEYEVENTF_KEYUP),
+        key(VK_CONTROL, KEYEVENTF_KEYUP),
+    ]);
+    let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize != inputs.len() {
+        return Err(AppError::Unknown("SendInput was blocked".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+pub fn send_paste_keystroke() -> AppResult<()> {
+    let status = std::process::Command::new("osascript")
+        .args(["-e", r#"tell application "System Events" to keystroke "v" using command down"#])
+        .status()?;
+    if !status.success() {
+        return Err(AppError::PermissionDenied(
+            "Pasting needs Accessibility access for OmniBox".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+pub fn send_paste_keystroke() -> AppResult<()> {
+    Err(AppError::Unknown(
+        "Pasting into other apps is not supported on this platform".to_string(),
+    ))
+}
+
+/// Paths on the clipboard as a CF_HDROP file list
+#[cfg(windows)]
+fn read_file_list() -> Option<Vec<PathBuf>> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::DataExchange::{CloseClipboard, GetClipboardData, IsClipboardFormatAvailable, OpenClipboard};
+    use windows::Win32::System::Ole::CF_HDROP;
+    use windows::Win32::UI::Shell::{DragQueryFileW, HDROP};
+
+    unsafe {
+        IsClipboardFormatAvailable(CF_HDROP.0 as u32).ok()?;
+        OpenClipboard(HWND::default()).ok()?;
+        let paths = GetClipboardData(CF_HDROP.0 as u32).ok().map(|handle| {
+            let hdrop = HDROP(handle.0);
+            (0..DragQueryFileW(hdrop, u32::MAX, None))
+                .map(|i| {
+                    let mut buf = vec![0u16; DragQueryFileW(hdrop, i, None) as usize + 1];
+                    let len = DragQueryFileW(hdrop, i, Some(&mut buf)) as usize;
+                    PathBuf::from(String::from_utf16_lossy(&buf[..len]))
+                })
+                .collect::<Vec<_>>()
+        });
+        let _ = CloseClipboard();
+        paths.filter(|paths| !paths.is_empty())
+    }
+}
+
+#[cfg(not(windows))]
+fn read_file_list() -> Option<Vec<PathBuf>> {
+    None
+}
+
+#[cfg(windows)]
+fn write_file_list(paths: &[PathBuf]) -> AppResult<()> {
+    use windows::Win32::Foundation::{HANDLE, HWND};
+    use windows::Win32::System::DataExchange::{CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData};
+    use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+    use windows::Win32::System::Ole::CF_HDROP;
+
+    let buffer = drop_files_buffer(paths);
+    let failed = |e: windows::core::Error| AppError::Clipboard(format!("Failed to restore copied files: {}", e));
+    unsafe {
+        let memory = GlobalAlloc(GMEM_MOVEABLE, buffer.len()).map_err(failed)?;
+        let target = GlobalLock(memory) as *mut u8;
+        if target.is_null() {
+            return Err(AppError::Clipboard("Failed to restore copied files".to_string()));
+        }
+        std::ptr::copy_nonoverlapping(buffer.as_ptr(), target, buffer.len());
+        let _ = GlobalUnlock(memory);
+
+        OpenClipboard(HWND::default()).map_err(failed)?;
+        // The clipboard owns the memory once SetClipboardData succeeds
+        let result = EmptyClipboard().and_then(|_| SetClipboardData(CF_HDROP.0 as u32, HANDLE(memory.0)));
+        let _ = CloseClipboard();
+        result.map(|_| ()).map_err(failed)
+    }
+}
+
+#[cfg(not(windows))]
+fn write_file_list(_paths: &[PathBuf]) -> AppResult<()> {
+    Err(AppError::Clipboard(
+        "Restoring copied files is only supported on Windows".to_string(),
+    ))
+}
+
+/// CF_HDROP data: a DROPFILES header (offset 20, wide chars) followed by the
+/// NUL-terminated UTF-16 paths and a final NUL
+#[cfg(any(windows, test))]
+fn drop_files_buffer(paths: &[PathBuf]) -> Vec<u8> {
+    const HEADER_LEN: u32 = 20;
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(&HEADER_LEN.to_le_bytes()); // pFiles
+    buffer.extend_from_slice(&[0; 12]); // pt, fNC
+    buffer.extend_from_slice(&1u32.to_le_bytes()); // fWide
+    for path in paths {
+        for unit in path.to_string_lossy().encode_utf16().chain([0]) {
+            buffer.extend_from_slice(&unit.to_le_bytes());
+        }
+    }
+    buffer.extend_from_slice(&[0, 0]);
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quick_pastes_keep_the_first_snapshot() {
+        let queue = RestoreQueue::default();
+
+        let first = queue.begin(|| Some("user text"));
+        // The second paste would read the first paste's item; it must not
+        let second = queue.begin(|| Some("first pasted item"));
+
+        assert_eq!(queue.finish(first), None);
+        assert_eq!(queue.finish(second), Some("user text"));
+        assert_eq!(queue.finish(second), None);
+    }
+
+    #[test]
+    fn test_abandoned_paste_leaves_earlier_restore_alone() {
+        let queue = RestoreQueue::default();
+        let first = queue.begin(|| Some("user text"));
+        let second = queue.begin(|| Some("first pasted item"));
+
+        // The second paste's keystroke failed; the first still restores
+        queue.abandon(second);
+        assert_eq!(queue.finish(first), Some("user text"));
+
+        // Abandoned on its own, nothing is left to restore
+        let third = queue.begin(|| Some("user text"));
+        queue.abandon(third);
+        let fourth = queue.begin(|| Some("newer text"));
+        assert_eq!(queue.finish(fourth), Some("newer text"));
+    }
+
+    #[test]
+    fn test_paste_after_restore_takes_new_snapshot() {
+        let queue = RestoreQueue::default();
+        let first = queue.begin(|| Some(1));
+        assert_eq!(queue.finish(first), Some(1));
+
+        let second = queue.begin(|| Some(2));
+        assert_eq!(queue.finish(second), Some(2));
+    }
+
+    #[test]
+    fn test_drop_files_buffer_layout() {
+        let buffer = drop_files_buffer(&[PathBuf::from("C:\\a"), PathBuf::from("b")]);
+
+        assert_eq!(&buffer[..4], &20u32.to_le_bytes());
+        assert_eq!(&buffer[16..20], &1u32.to_le_bytes());
+        let units: Vec<u16> = buffer[20..]
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        assert_eq!(String::from_utf16(&units).unwrap(), "C:\\a\0b\0\0");
+    }
+}
@@ -0,0 +1,92 @@
+// Clipboard ring: repeated ring pastes walk back through the history
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+/// The ring shared by the paste command and the monitor, which resets it
+/// when something new is copied
+static RING: Lazy<Mutex<ClipboardRing>> = Lazy::new(|| Mutex::new(ClipboardRing::default()));
+
+/// The history order when the ring started and the position of the next
+/// ring paste in it. Pasting bumps the pasted row to the top of the history,
+/// so the ring walks this snapshot rather than the live order.
+#[derive(Debug, Default)]
+pub struct ClipboardRing {
+    order: Vec<String>,
+    next: usize,
+}
+
+impl ClipboardRing {
+    /// Id of the item to paste, moving the ring on by one. Wraps to the
+    /// most recent item after the oldest; `None` before [`Self::start`].
+    pub fn advance(&mut self) -> Option<String> {
+        if self.order.is_empty() {
+            return None;
+        }
+        let index = if self.next < self.order.len() { self.next } else { 0 };
+        self.next = index + 1;
+        Some(self.order[index].clone())
+    }
+
+    /// Start over on `order`, the history ids most recent first, and
+    /// advance once
+    pub fn start(&mut self, order: Vec<String>) -> Option<String> {
+        self.order = order;
+        self.next = 0;
+        self.advance()
+    }
+
+    pub fn reset(&mut self) {
+        self.order.clear();
+        self.next = 0;
+    }
+}
+
+/// [`ClipboardRing::advance`] on the shared ring
+pub fn advance() -> Option<String> {
+    RING.lock().map_or(None, |mut ring| ring.advance())
+}
+
+/// [`ClipboardRing::start`] on the shared ring
+pub fn start(order: Vec<String>) -> Option<String> {
+    RING.lock().map_or(None, |mut ring| ring.start(order))
+}
+
+/// Start the shared ring over at the most recent item
+pub fn reset() {
+    if let Ok(mut ring) = RING.lock() {
+        ring.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_advance_walks_back_through_history() {
+        let mut ring = ClipboardRing::default();
+        assert_eq!(ring.advance(), None);
+
+        assert_eq!(ring.start(ids(&["e", "d", "c", "b", "a"])).as_deref(), Some("e"));
+        assert_eq!(ring.advance().as_deref(), Some("d"));
+        assert_eq!(ring.advance().as_deref(), Some("c"));
+        ring.reset();
+        assert_eq!(ring.advance(), None);
+        assert_eq!(ring.start(ids(&["c", "e", "d"])).as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn test_advance_wraps_around() {
+        let mut ring = ClipboardRing::default();
+
+        let mut pasted = vec![ring.start(ids(&["c", "b", "a"]))];
+        pasted.extend((0..6).map(|_| ring.advance()));
+        let expected = ["c", "b", "a", "c", "b", "a", "c"].map(|id| Some(id.to_string()));
+        assert_eq!(pasted, expected);
+        assert_eq!(ring.start(Vec::new()), None);
+    }
+}
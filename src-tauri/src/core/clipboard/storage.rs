@@ -1,8 +1,12 @@
 // Clipboard history storage using SQLite
-use crate::app::error::AppResult;
+use super::crypto::{ClipboardCipher, BLOB_MAGIC, TEXT_PREFIX};
+use super::types::{ClipboardContent, ImageFormat};
+use super::thumbnail::{png_thumbnail, THUMBNAIL_SIZE};
+use crate::app::error::{AppError, AppResult};
 use chrono::{DateTime, Utc};
+use futures_util::stream::{BoxStream, StreamExt};
 use serde::{Deserialize, Serialize};
-use sqlx::{sqlite::SqlitePool, Row};
+use sqlx::{sqlite::{SqlitePool, SqliteRow}, Row};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClipboardHistoryItem {
@@ -11,6 +15,9 @@ pub struct ClipboardHistoryItem {
     pub content_hash: String,
     pub plain_text: Option<String>,
     pub data: Option<Vec<u8>>,
+    /// Small PNG preview of an image item, made when the row is added
+    #[serde(default)]
+    pub thumbnail: Option<Vec<u8>>,
     pub source_app: Option<String>,
     pub source_window: Option<String>,
     pub is_favorite: bool,
@@ -20,13 +27,162 @@ pub struct ClipboardHistoryItem {
     pub access_count: i32,
 }
 
+impl ClipboardHistoryItem {
+    /// Text a paste of this item writes. Masked sensitive text keeps the
+    /// original in `data`; that is still plain text, so it is used instead
+    /// of the redacted preview.
+    pub fn pasteable_text(&self) -> Option<String> {
+        match &self.data {
+            Some(data) if self.is_sensitive && matches!(self.content_type.as_str(), "text" | "files") => {
+                String::from_utf8(data.clone()).ok()
+            }
+            _ => self.plain_text.clone(),
+        }
+    }
+
+    /// The formatted form of an HTML item, pasted unless plain text is
+    /// asked for. Masked items are stored as text, so this is never hidden.
+    pub fn html(&self) -> Option<String> {
+        match &self.data {
+            Some(data) if self.content_type == "html" => String::from_utf8(data.clone()).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Result of [`ClipboardStorage::combine_text`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CombinedText {
+    pub text: String,
+    /// `text` with each item's stored preview instead, so masked items stay
+    /// masked
+    #[serde(skip)]
+    pub preview: String,
+    /// Any joined item is sensitive
+    #[serde(skip)]
+    pub sensitive: bool,
+    /// Items joined into `text`
+    pub combined: usize,
+    /// Items left out because they are not text (images, files)
+    pub skipped: usize,
+    /// Ids with no item
+    pub missing: usize,
+}
+
+/// What [`ClipboardStorage::upsert_item`] did with an item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Upsert {
+    /// The content was new and a row was added
+    Inserted(String),
+    /// The content was already stored; that row moved to the top instead
+    Bumped(String),
+}
+
+impl Upsert {
+    /// Id of the row holding the content
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Inserted(id) | Self::Bumped(id) => id,
+        }
+    }
+}
+
+/// Limits enforced by [`ClipboardStorage::prune`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruneLimits {
+    pub max_items: usize,
+    pub max_age_days: usize,
+    pub max_total_bytes: u64,
+    /// For sensitive rows, which [`ClipboardStorage::prune`] leaves alone
+    pub sensitive_max_age_minutes: u64,
+}
+
+impl From<&crate::app::config::ClipboardConfig> for PruneLimits {
+    fn from(config: &crate::app::config::ClipboardConfig) -> Self {
+        Self {
+            max_items: config.history_limit,
+            max_age_days: config.retention_days,
+            max_total_bytes: config.max_total_bytes,
+            sensitive_max_age_minutes: config.sensitive_retention_minutes,
+        }
+    }
+}
+
+/// Content type filter for the clipboard window tabs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardTypeFilter {
+    Text,
+    Image,
+    Files,
+    /// Text that is a single URL
+    Link,
+}
+
+impl ClipboardTypeFilter {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "image" => Some(Self::Image),
+            "files" => Some(Self::Files),
+            "link" => Some(Self::Link),
+            _ => None,
+        }
+    }
+
+    /// SQL condition for this filter. Links are matched in SQL as the URL
+    /// pattern `^(https?://|www\.)\S+$` on the trimmed text.
+    fn sql_condition(&self) -> &'static str {
+        match self {
+            Self::Text => "content_type = 'text'",
+            Self::Image => "content_type = 'image'",
+            Self::Files => "content_type = 'files'",
+            Self::Link => {
+                "content_type = 'text' \
+                 AND (TRIM(plain_text) GLOB 'http://?*' OR TRIM(plain_text) GLOB 'https://?*' \
+                      OR TRIM(plain_text) GLOB 'www.?*') \
+                 AND TRIM(plain_text) NOT GLOB '*[ \t\r\n]*'"
+            }
+        }
+    }
+}
+
+/// Most rows returned by [`ClipboardStorage::search`]
+const SEARCH_LIMIT: usize = 50;
+
+/// Most recent matches [`ClipboardStorage::search`] ranks before keeping
+/// the best [`SEARCH_LIMIT`]
+const SEARCH_CANDIDATES: usize = 500;
+
 pub struct ClipboardStorage {
     pool: SqlitePool,
+    /// Decrypts sealed rows on read. Present whenever a key exists, even
+    /// with encryption turned off, so earlier sealed rows stay readable.
+    cipher: Option<ClipboardCipher>,
+    /// Seal `plain_text` and `data` on write
+    encrypt: bool,
 }
 
 impl ClipboardStorage {
     pub async fn new(pool: SqlitePool) -> AppResult<Self> {
-        let storage = Self { pool };
+        let storage = Self {
+            pool,
+            cipher: None,
+            encrypt: false,
+        };
+        storage.initialize_schema().await?;
+        Ok(storage)
+    }
+
+    /// Storage that reads sealed rows with `cipher` and, with `encrypt`,
+    /// seals new ones. Run [`Self::migrate_encryption`] afterwards to bring
+    /// existing rows in line.
+    pub async fn with_encryption(pool: SqlitePool, cipher: ClipboardCipher, encrypt: bool) -> AppResult<Self> {
+        let storage = Self {
+            pool,
+            cipher: Some(cipher),
+            encrypt,
+        };
         storage.initialize_schema().await?;
         Ok(storage)
     }
@@ -46,13 +202,50 @@ impl ClipboardStorage {
                 is_sensitive BOOLEAN DEFAULT FALSE,
                 created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
                 accessed_at TIMESTAMP,
-                access_count INTEGER DEFAULT 0
+                access_count INTEGER DEFAULT 0,
+                thumbnail BLOB,
+                sealed BOOLEAN DEFAULT FALSE
             )
             "#,
         )
         .execute(&self.pool)
         .await?;
 
+        // Databases created before thumbnails existed lack the column
+        let has_thumbnail: i32 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM pragma_table_info('clipboard_history') WHERE name = 'thumbnail'",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if has_thumbnail == 0 {
+            sqlx::query("ALTER TABLE clipboard_history ADD COLUMN thumbnail BLOB")
+                .execute(&self.pool)
+                .await?;
+        }
+
+        // Rows sealed before the column existed are told apart by the
+        // marker, once; afterwards only the column counts
+        let has_sealed: i32 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM pragma_table_info('clipboard_history') WHERE name = 'sealed'",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if has_sealed == 0 {
+            sqlx::query("ALTER TABLE clipboard_history ADD COLUMN sealed BOOLEAN DEFAULT FALSE")
+                .execute(&self.pool)
+                .await?;
+            sqlx::query(
+                "UPDATE clipboard_history SET sealed = TRUE \
+                 WHERE SUBSTR(plain_text, 1, ?) = ? OR SUBSTR(data, 1, ?) = ?",
+            )
+            .bind(TEXT_PREFIX.len() as i64)
+            .bind(TEXT_PREFIX)
+            .bind(BLOB_MAGIC.len() as i64)
+            .bind(BLOB_MAGIC.as_slice())
+            .execute(&self.pool)
+            .await?;
+        }
+
         // Create indexes for better query performance
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_created_at ON clipboard_history(created_at DESC)")
             .execute(&self.pool)
@@ -69,22 +262,37 @@ impl ClipboardStorage {
         Ok(())
     }
 
-    /// Add a new clipboard item to history
+    /// Add a new clipboard item to history. With encryption on, `plain_text`,
+    /// `data` and the thumbnail are stored sealed and `content_hash` is
+    /// stored keyed. Image items without a thumbnail get one made from `data`.
     pub async fn add_item(&self, item: &ClipboardHistoryItem) -> AppResult<()> {
+        let thumbnail = match (&item.thumbnail, &item.data) {
+            (Some(thumbnail), _) => Some(thumbnail.clone()),
+            (None, Some(data)) if item.content_type == "image" => {
+                let data = data.clone();
+                tokio::task::spawn_blocking(move || png_thumbnail(&data, THUMBNAIL_SIZE))
+                    .await
+                    .map_err(|e| AppError::Unknown(format!("Thumbnail task failed: {}", e)))?
+            }
+            _ => None,
+        };
+        let (plain_text, data) = self.seal(item.plain_text.as_deref(), item.data.as_deref())?;
+        let (_, thumbnail) = self.seal(None, thumbnail.as_deref())?;
         sqlx::query(
             r#"
             INSERT INTO clipboard_history (
-                id, content_type, content_hash, plain_text, data,
+                id, content_type, content_hash, plain_text, data, thumbnail,
                 source_app, source_window, is_favorite, is_sensitive,
-                created_at, accessed_at, access_count
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                created_at, accessed_at, access_count, sealed
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&item.id)
         .bind(&item.content_type)
-        .bind(&item.content_hash)
-        .bind(&item.plain_text)
-        .bind(&item.data)
+        .bind(self.stored_hash(&item.content_hash))
+        .bind(plain_text)
+        .bind(data)
+        .bind(thumbnail)
         .bind(&item.source_app)
         .bind(&item.source_window)
         .bind(item.is_favorite)
@@ -92,19 +300,120 @@ impl ClipboardStorage {
         .bind(item.created_at)
         .bind(item.accessed_at)
         .bind(item.access_count)
+        .bind(self.sealing())
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
+    /// Add an item, or bump the existing row with the same `content_hash`.
+    ///
+    /// A re-copy moves the original row to the top of the history: its
+    /// `created_at`/`accessed_at` become the new item's time and its
+    /// `access_count` goes up. Favorite status and source metadata stay.
+    pub async fn upsert_item(&self, item: &ClipboardHistoryItem) -> AppResult<Upsert> {
+        let existing: Option<String> = sqlx::query_scalar(
+            "SELECT id FROM clipboard_history WHERE content_hash = ? ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(self.stored_hash(&item.content_hash))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(id) = existing else {
+            self.add_item(item).await?;
+            return Ok(Upsert::Inserted(item.id.clone()));
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE clipboard_history
+            SET created_at = ?, accessed_at = ?, access_count = access_count + 1
+            WHERE id = ?
+            "#,
+        )
+        .bind(item.created_at)
+        .bind(item.created_at)
+        .bind(&id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(Upsert::Bumped(id))
+    }
+
+    /// Store an item read from an export file unless its content is
+    /// already in history. Returns `false` for such duplicates; the stored
+    /// row still becomes a favorite if the imported one is.
+    pub async fn import_item(&self, item: &ClipboardHistoryItem) -> AppResult<bool> {
+        let existing: Option<String> =
+            sqlx::query_scalar("SELECT id FROM clipboard_history WHERE content_hash = ? LIMIT 1")
+                .bind(self.stored_hash(&item.content_hash))
+                .fetch_optional(&self.pool)
+                .await?;
+
+        if let Some(id) = existing {
+            if item.is_favorite {
+                sqlx::query("UPDATE clipboard_history SET is_favorite = TRUE WHERE id = ?")
+                    .bind(&id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            return Ok(false);
+        }
+
+        let id_taken: i32 = sqlx::query_scalar("SELECT COUNT(*) FROM clipboard_history WHERE id = ?")
+            .bind(&item.id)
+            .fetch_one(&self.pool)
+            .await?;
+        if id_taken > 0 {
+            let renamed = ClipboardHistoryItem {
+                id: uuid::Uuid::new_v4().to_string(),
+                ..item.clone()
+            };
+            self.add_item(&renamed).await?;
+        } else {
+            self.add_item(item).await?;
+        }
+        Ok(true)
+    }
+
+    /// Every item, oldest first, read from the database one row at a time.
+    /// Sealed rows get their unkeyed hash back, so the items can be stored
+    /// under another key or none.
+    pub fn stream_all(&self) -> BoxStream<'_, AppResult<ClipboardHistoryItem>> {
+        sqlx::query(
+            r#"
+            SELECT id, content_type, content_hash, plain_text, data, thumbnail,
+                   source_app, source_window, is_favorite, is_sensitive,
+                   created_at, accessed_at, access_count, sealed
+            FROM clipboard_history
+            ORDER BY created_at ASC
+            "#,
+        )
+        .fetch(&self.pool)
+        .map(move |row| -> AppResult<ClipboardHistoryItem> {
+            let row = row?;
+            let mut item = self.decode(&row);
+            if row.get::<bool, _>("sealed") {
+                item.content_hash = plain_hash(
+                    &item.content_type,
+                    item.is_sensitive,
+                    item.plain_text.as_deref(),
+                    item.data.as_deref(),
+                );
+            }
+            Ok(item)
+        })
+        .boxed()
+    }
+
     /// Get clipboard history with pagination
     pub async fn get_history(&self, limit: i32, offset: i32) -> AppResult<Vec<ClipboardHistoryItem>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, content_type, content_hash, plain_text, data,
+            SELECT id, content_type, content_hash, plain_text, data, thumbnail,
                    source_app, source_window, is_favorite, is_sensitive,
-                   created_at, accessed_at, access_count
+                   created_at, accessed_at, access_count, sealed
             FROM clipboard_history
             ORDER BY created_at DESC
             LIMIT ? OFFSET ?
@@ -115,64 +424,140 @@ impl ClipboardStorage {
         .fetch_all(&self.pool)
         .await?;
 
-        let items = rows
-            .into_iter()
-            .map(|row| ClipboardHistoryItem {
-                id: row.get("id"),
-                content_type: row.get("content_type"),
-                content_hash: row.get("content_hash"),
-                plain_text: row.get("plain_text"),
-                data: row.get("data"),
-                source_app: row.get("source_app"),
-                source_window: row.get("source_window"),
-                is_favorite: row.get("is_favorite"),
-                is_sensitive: row.get("is_sensitive"),
-                created_at: row.get("created_at"),
-                accessed_at: row.get("accessed_at"),
-                access_count: row.get("access_count"),
-            })
-            .collect();
+        Ok(rows.iter().map(|row| self.decode(row)).collect())
+    }
+
+    /// Ids of every item, most recent first
+    pub async fn history_ids(&self) -> AppResult<Vec<String>> {
+        let ids = sqlx::query_scalar("SELECT id FROM clipboard_history ORDER BY created_at DESC")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(ids)
+    }
+
+    /// Get clipboard history filtered by content type and/or favorites, with
+    /// pagination applied after the filter.
+    ///
+    /// This is the list view: image rows carry their thumbnail but no `data`,
+    /// so the full image is only read by [`Self::get_by_id`].
+    pub async fn get_history_filtered(
+        &self,
+        content_type: Option<ClipboardTypeFilter>,
+        favorites_only: bool,
+        limit: i32,
+        offset: i32,
+    ) -> AppResult<Vec<ClipboardHistoryItem>> {
+        // SQL cannot see sealed text, so with a key present links are
+        // picked out in Rust after decrypting every text row
+        let links_in_rust = content_type == Some(ClipboardTypeFilter::Link) && self.cipher.is_some();
+
+        let mut conditions = Vec::new();
+        if links_in_rust {
+            conditions.push(ClipboardTypeFilter::Text.sql_condition());
+        } else if let Some(filter) = content_type {
+            conditions.push(filter.sql_condition());
+        }
+        if favorites_only {
+            conditions.push("is_favorite = TRUE");
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let sql = format!(
+            r#"
+            SELECT id, content_type, content_hash, plain_text,
+                   CASE WHEN content_type = 'image' THEN NULL ELSE data END AS data, thumbnail,
+                   source_app, source_window, is_favorite, is_sensitive,
+                   created_at, accessed_at, access_count, sealed
+            FROM clipboard_history
+            {}
+            ORDER BY created_at DESC
+            {}
+            "#,
+            where_clause,
+            if links_in_rust { "" } else { "LIMIT ? OFFSET ?" }
+        );
+
+        if links_in_rust {
+            let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
+            return Ok(rows
+                .iter()
+                .map(|row| self.decode(row))
+                .filter(|item| item.plain_text.as_deref().is_some_and(is_link))
+                .skip(offset.max(0) as usize)
+                .take(limit.max(0) as usize)
+                .collect());
+        }
+
+        let rows = sqlx::query(&sql)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
 
-        Ok(items)
+        Ok(rows.iter().map(|row| self.decode(row)).collect())
     }
 
-    /// Search clipboard history by text
+    /// Search clipboard history by text (sensitive items are never matched)
+    ///
+    /// With a key present the text may be sealed, so instead of SQL `LIKE`
+    /// every non-sensitive text row is decrypted and matched here, newest
+    /// first. That reads the whole history on a miss and is noticeably
+    /// slower than the SQL path on large histories.
     pub async fn search(&self, query: &str) -> AppResult<Vec<ClipboardHistoryItem>> {
+        let needle = query.to_lowercase();
+        if self.cipher.is_some() {
+            let mut rows = sqlx::query(
+                r#"
+                SELECT id, content_type, content_hash, plain_text, data, thumbnail,
+                       source_app, source_window, is_favorite, is_sensitive,
+                       created_at, accessed_at, access_count, sealed
+                FROM clipboard_history
+                WHERE plain_text IS NOT NULL AND is_sensitive = FALSE
+                ORDER BY created_at DESC
+                "#,
+            )
+            .fetch(&self.pool);
+
+            let mut items = Vec::new();
+            while let Some(row) = rows.next().await {
+                let item = self.decode(&row?);
+                let matches = item
+                    .plain_text
+                    .as_deref()
+                    .is_some_and(|text| text.to_lowercase().contains(&needle));
+                if matches {
+                    items.push(item);
+                    if items.len() == SEARCH_CANDIDATES {
+                        break;
+                    }
+                }
+            }
+            return Ok(rank_search_results(items, &needle));
+        }
+
         let search_pattern = format!("%{}%", query);
         let rows = sqlx::query(
             r#"
-            SELECT id, content_type, content_hash, plain_text, data,
+            SELECT id, content_type, content_hash, plain_text, data, thumbnail,
                    source_app, source_window, is_favorite, is_sensitive,
-                   created_at, accessed_at, access_count
+                   created_at, accessed_at, access_count, sealed
             FROM clipboard_history
-            WHERE plain_text LIKE ?
+            WHERE plain_text LIKE ? AND is_sensitive = FALSE
             ORDER BY created_at DESC
-            LIMIT 50
+            LIMIT ?
             "#,
         )
         .bind(&search_pattern)
+        .bind(SEARCH_CANDIDATES as i64)
         .fetch_all(&self.pool)
         .await?;
 
-        let items = rows
-            .into_iter()
-            .map(|row| ClipboardHistoryItem {
-                id: row.get("id"),
-                content_type: row.get("content_type"),
-                content_hash: row.get("content_hash"),
-                plain_text: row.get("plain_text"),
-                data: row.get("data"),
-                source_app: row.get("source_app"),
-                source_window: row.get("source_window"),
-                is_favorite: row.get("is_favorite"),
-                is_sensitive: row.get("is_sensitive"),
-                created_at: row.get("created_at"),
-                accessed_at: row.get("accessed_at"),
-                access_count: row.get("access_count"),
-            })
-            .collect();
-
-        Ok(items)
+        let items = rows.iter().map(|row| self.decode(row)).collect();
+        Ok(rank_search_results(items, &needle))
     }
 
     /// Toggle favorite status
@@ -231,7 +616,7 @@ impl ClipboardStorage {
     /// Check if content already exists in history
     pub async fn exists_by_hash(&self, content_hash: &str) -> AppResult<bool> {
         let count: i32 = sqlx::query_scalar("SELECT COUNT(*) FROM clipboard_history WHERE content_hash = ?")
-            .bind(content_hash)
+            .bind(self.stored_hash(content_hash))
             .fetch_one(&self.pool)
             .await?;
 
@@ -242,9 +627,9 @@ impl ClipboardStorage {
     pub async fn get_favorites(&self) -> AppResult<Vec<ClipboardHistoryItem>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, content_type, content_hash, plain_text, data,
+            SELECT id, content_type, content_hash, plain_text, data, thumbnail,
                    source_app, source_window, is_favorite, is_sensitive,
-                   created_at, accessed_at, access_count
+                   created_at, accessed_at, access_count, sealed
             FROM clipboard_history
             WHERE is_favorite = TRUE
             ORDER BY created_at DESC
@@ -253,34 +638,16 @@ impl ClipboardStorage {
         .fetch_all(&self.pool)
         .await?;
 
-        let items = rows
-            .into_iter()
-            .map(|row| ClipboardHistoryItem {
-                id: row.get("id"),
-                content_type: row.get("content_type"),
-                content_hash: row.get("content_hash"),
-                plain_text: row.get("plain_text"),
-                data: row.get("data"),
-                source_app: row.get("source_app"),
-                source_window: row.get("source_window"),
-                is_favorite: row.get("is_favorite"),
-                is_sensitive: row.get("is_sensitive"),
-                created_at: row.get("created_at"),
-                accessed_at: row.get("accessed_at"),
-                access_count: row.get("access_count"),
-            })
-            .collect();
-
-        Ok(items)
+        Ok(rows.iter().map(|row| self.decode(row)).collect())
     }
 
     /// Get a single item by ID
     pub async fn get_by_id(&self, id: &str) -> AppResult<Option<ClipboardHistoryItem>> {
         let row = sqlx::query(
             r#"
-            SELECT id, content_type, content_hash, plain_text, data,
+            SELECT id, content_type, content_hash, plain_text, data, thumbnail,
                    source_app, source_window, is_favorite, is_sensitive,
-                   created_at, accessed_at, access_count
+                   created_at, accessed_at, access_count, sealed
             FROM clipboard_history
             WHERE id = ?
             "#,
@@ -289,20 +656,37 @@ impl ClipboardStorage {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(row.map(|row| ClipboardHistoryItem {
-            id: row.get("id"),
-            content_type: row.get("content_type"),
-            content_hash: row.get("content_hash"),
-            plain_text: row.get("plain_text"),
-            data: row.get("data"),
-            source_app: row.get("source_app"),
-            source_window: row.get("source_window"),
-            is_favorite: row.get("is_favorite"),
-            is_sensitive: row.get("is_sensitive"),
-            created_at: row.get("created_at"),
-            accessed_at: row.get("accessed_at"),
-            access_count: row.get("access_count"),
-        }))
+        Ok(row.map(|row| self.decode(&row)))
+    }
+
+    /// Joins the text of the items `ids`, in that order, with `separator`
+    pub async fn combine_text(&self, ids: &[String], separator: &str) -> AppResult<CombinedText> {
+        let (mut parts, mut previews) = (Vec::new(), Vec::new());
+        let (mut skipped, mut missing, mut sensitive) = (0, 0, false);
+        for id in ids {
+            match self.get_by_id(id).await? {
+                None => missing += 1,
+                Some(item) if matches!(item.content_type.as_str(), "text" | "html") => {
+                    match item.pasteable_text() {
+                        Some(text) => {
+                            previews.push(item.plain_text.clone().unwrap_or_else(|| text.clone()));
+                            parts.push(text);
+                            sensitive |= item.is_sensitive;
+                        }
+                        None => skipped += 1,
+                    }
+                }
+                Some(_) => skipped += 1,
+            }
+        }
+        Ok(CombinedText {
+            combined: parts.len(),
+            text: parts.join(separator),
+            preview: previews.join(separator),
+            sensitive,
+            skipped,
+            missing,
+        })
     }
 
     /// Delete a clipboard item (alias for delete_item)
@@ -359,4 +743,797 @@ impl ClipboardStorage {
 
         Ok((deleted_by_age + deleted_by_limit) as usize)
     }
+
+    /// Enforce history limits, returning the number of deleted rows.
+    ///
+    /// Only non-favorite, non-sensitive rows are candidates (sensitive rows
+    /// go by [`Self::expire_sensitive`] instead). Rows older than
+    /// `max_age_days` go first, then the oldest rows beyond `max_items`. If the
+    /// stored `data` blobs still exceed `max_total_bytes`, the largest blobs are
+    /// dropped (oldest first among equal sizes) until the total fits.
+    pub async fn prune(
+        &self,
+        max_items: usize,
+        max_age_days: usize,
+        max_total_bytes: u64,
+    ) -> AppResult<usize> {
+        const PRUNABLE: &str = "is_favorite = FALSE AND is_sensitive = FALSE";
+
+        let cutoff = Utc::now() - chrono::Duration::days(max_age_days as i64);
+        let mut deleted = sqlx::query(&format!(
+            "DELETE FROM clipboard_history WHERE {} AND created_at < ?",
+            PRUNABLE
+        ))
+        .bind(cutoff)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        let prunable_count: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM clipboard_history WHERE {}",
+            PRUNABLE
+        ))
+        .fetch_one(&self.pool)
+        .await?;
+
+        if prunable_count as usize > max_items {
+            let excess = prunable_count - max_items as i64;
+            deleted += sqlx::query(&format!(
+                r#"
+                DELETE FROM clipboard_history
+                WHERE id IN (
+                    SELECT id FROM clipboard_history
+                    WHERE {}
+                    ORDER BY created_at ASC
+                    LIMIT ?
+                )
+                "#,
+                PRUNABLE
+            ))
+            .bind(excess)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        }
+
+        let total_bytes: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(LENGTH(data)), 0) FROM clipboard_history",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        if total_bytes as u64 > max_total_bytes {
+            let mut over = total_bytes as u64 - max_total_bytes;
+            let blobs: Vec<(String, i64)> = sqlx::query_as(&format!(
+                r#"
+                SELECT id, LENGTH(data) AS size FROM clipboard_history
+                WHERE {} AND data IS NOT NULL
+                ORDER BY size DESC, created_at ASC
+                "#,
+                PRUNABLE
+            ))
+            .fetch_all(&self.pool)
+            .await?;
+
+            for (id, size) in blobs {
+                if over == 0 {
+                    break;
+                }
+                self.delete_item(&id).await?;
+                deleted += 1;
+                over = over.saturating_sub(size as u64);
+            }
+        }
+
+        if deleted > 0 {
+            tracing::debug!("Pruned {} clipboard history rows", deleted);
+        }
+
+        Ok(deleted as usize)
+    }
+
+    /// Delete sensitive rows copied more than `max_age_minutes` ago, so the
+    /// original text kept for pasting does not stay on disk. Favorites are
+    /// kept. Returns the number of deleted rows.
+    pub async fn expire_sensitive(&self, max_age_minutes: u64) -> AppResult<usize> {
+        let max_age = i64::try_from(max_age_minutes)
+            .ok()
+            .and_then(chrono::Duration::try_minutes)
+            .unwrap_or(chrono::Duration::MAX);
+        let cutoff = Utc::now().checked_sub_signed(max_age).unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let deleted = sqlx::query(
+            "DELETE FROM clipboard_history WHERE is_sensitive = TRUE AND is_favorite = FALSE AND created_at < ?",
+        )
+        .bind(cutoff)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        if deleted > 0 {
+            tracing::debug!("Expired {} sensitive clipboard rows", deleted);
+        }
+        Ok(deleted as usize)
+    }
+
+    /// Rewrite rows whose `sealed` flag does not match the current setting:
+    /// seal plaintext rows and key their hash when encrypting, open sealed
+    /// rows and hash their content again when not. Rows already in the
+    /// right form are skipped, so this is a no-op after the first run.
+    /// Returns the number of rewritten rows.
+    pub async fn migrate_encryption(&self) -> AppResult<usize> {
+        let Some(cipher) = &self.cipher else {
+            return Ok(0);
+        };
+
+        // Collect ids first so no read cursor is open while rows are updated
+        let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM clipboard_history WHERE sealed <> ?")
+            .bind(self.encrypt)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut migrated = 0;
+        for id in &ids {
+            type Row = (String, String, bool, Option<String>, Option<Vec<u8>>, Option<Vec<u8>>);
+            let row: Option<Row> = sqlx::query_as(
+                "SELECT content_type, content_hash, is_sensitive, plain_text, data, thumbnail \
+                 FROM clipboard_history WHERE id = ?",
+            )
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+            let Some((content_type, content_hash, is_sensitive, plain_text, data, thumbnail)) = row else {
+                continue;
+            };
+            let sealed = !self.encrypt;
+            // Leave rows that cannot be opened alone rather than lose them
+            let opened = self.open(sealed, plain_text, data);
+            let (plain_text, data) = match opened {
+                Ok(opened) => opened,
+                Err(e) => {
+                    tracing::warn!("Skipping clipboard item {}: {}", id, e);
+                    continue;
+                }
+            };
+            // A thumbnail that cannot be opened is dropped; it is only a preview
+            let thumbnail = self.open(sealed, None, thumbnail).ok().and_then(|(_, thumbnail)| thumbnail);
+            // The keyed hash cannot be undone, so it is made again from the content
+            let content_hash = if self.encrypt {
+                cipher.keyed_hash(&content_hash)
+            } else {
+                plain_hash(&content_type, is_sensitive, plain_text.as_deref(), data.as_deref())
+            };
+            let (plain_text, data) = self.seal(plain_text.as_deref(), data.as_deref())?;
+            let (_, thumbnail) = self.seal(None, thumbnail.as_deref())?;
+            sqlx::query(
+                "UPDATE clipboard_history \
+                 SET content_hash = ?, plain_text = ?, data = ?, thumbnail = ?, sealed = ? WHERE id = ?",
+            )
+            .bind(content_hash)
+            .bind(plain_text)
+            .bind(data)
+            .bind(thumbnail)
+            .bind(self.encrypt)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+            migrated += 1;
+        }
+
+        if migrated > 0 {
+            tracing::info!(
+                "{} {} clipboard history rows",
+                if self.encrypt { "Encrypted" } else { "Decrypted" },
+                migrated
+            );
+        }
+        Ok(migrated)
+    }
+
+    /// Whether rows written now are sealed
+    fn sealing(&self) -> bool {
+        self.cipher.is_some() && self.encrypt
+    }
+
+    /// `content_hash` in the form it is stored
+    fn stored_hash(&self, content_hash: &str) -> String {
+        match &self.cipher {
+            Some(cipher) if self.encrypt => cipher.keyed_hash(content_hash),
+            _ => content_hash.to_string(),
+        }
+    }
+
+    /// Stored `plain_text` and `data`, opened if the row is `sealed`
+    fn open(
+        &self,
+        sealed: bool,
+        plain_text: Option<String>,
+        data: Option<Vec<u8>>,
+    ) -> AppResult<(Option<String>, Option<Vec<u8>>)> {
+        match &self.cipher {
+            Some(cipher) if sealed => Ok((
+                plain_text.map(|text| cipher.open_text(text)).transpose()?,
+                data.map(|data| cipher.open_blob(data)).transpose()?,
+            )),
+            _ => Ok((plain_text, data)),
+        }
+    }
+
+    /// `plain_text` and `data` in the form they are stored
+    fn seal(&self, plain_text: Option<&str>, data: Option<&[u8]>) -> AppResult<(Option<String>, Option<Vec<u8>>)> {
+        match &self.cipher {
+            Some(cipher) if self.encrypt => Ok((
+                plain_text.map(|text| cipher.seal_text(text)).transpose()?,
+                data.map(|data| cipher.seal_blob(data)).transpose()?,
+            )),
+            _ => Ok((plain_text.map(str::to_string), data.map(<[u8]>::to_vec))),
+        }
+    }
+
+    /// Row to item, opening sealed fields. A field that fails to decrypt
+    /// (e.g. the key was replaced) is dropped rather than failing the read.
+    fn decode(&self, row: &SqliteRow) -> ClipboardHistoryItem {
+        let mut item = row_to_item(row);
+        if !row.get::<bool, _>("sealed") {
+            return item;
+        }
+        if let Some(cipher) = &self.cipher {
+            item.plain_text = item.plain_text.and_then(|text| {
+                cipher
+                    .open_text(text)
+                    .map_err(|e| tracing::warn!("Clipboard item {}: {}", item.id, e))
+                    .ok()
+            });
+            item.data = item.data.and_then(|data| {
+                cipher
+                    .open_blob(data)
+                    .map_err(|e| tracing::warn!("Clipboard item {}: {}", item.id, e))
+                    .ok()
+            });
+            item.thumbnail = item.thumbnail.and_then(|thumbnail| cipher.open_blob(thumbnail).ok());
+        }
+        item
+    }
+}
+
+/// Unkeyed `content_hash` of a stored row, as [`ClipboardContent::hash`]
+/// made it. HTML rows keep the HTML in `data`; HTML rows without it (stored
+/// before it was kept) hash as text.
+fn plain_hash(content_type: &str, is_sensitive: bool, plain_text: Option<&str>, data: Option<&[u8]>) -> String {
+    let content = match (content_type, data) {
+        ("image", Some(data)) => ClipboardContent::Image {
+            data: data.to_vec(),
+            format: ImageFormat::PNG,
+            thumbnail: Vec::new(),
+        },
+        ("html", Some(data)) => ClipboardContent::Html {
+            html: String::from_utf8_lossy(data).into_owned(),
+            plain_text: plain_text.unwrap_or_default().to_string(),
+        },
+        ("files", _) => ClipboardContent::Files {
+            paths: plain_text.unwrap_or_default().lines().map(Into::into).collect(),
+        },
+        _ => {
+            // Masked items keep the original text in `data`
+            let text = match data {
+                Some(data) if is_sensitive => String::from_utf8_lossy(data).into_owned(),
+                _ => plain_text.unwrap_or_default().to_string(),
+            };
+            ClipboardContent::Text {
+                content: text.clone(),
+                plain_text: text,
+            }
+        }
+    };
+    content.hash()
+}
+
+/// [`ClipboardTypeFilter::Link`]'s SQL condition, for text SQL cannot read
+fn is_link(text: &str) -> bool {
+    let text = text.trim_matches(' ');
+    let rest = ["http://", "https://", "www."]
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix));
+    rest.is_some_and(|rest| !rest.is_empty()) && !text.contains([' ', '\t', '\r', '\n'])
+}
+
+fn row_to_item(row: &SqliteRow) -> ClipboardHistoryItem {
+    ClipboardHistoryItem {
+        id: row.get("id"),
+        content_type: row.get("content_type"),
+        content_hash: row.get("content_hash"),
+        plain_text: row.get("plain_text"),
+        data: row.get("data"),
+        thumbnail: row.get("thumbnail"),
+        source_app: row.get("source_app"),
+        source_window: row.get("source_window"),
+        is_favorite: row.get("is_favorite"),
+        is_sensitive: row.get("is_sensitive"),
+        created_at: row.get("created_at"),
+        accessed_at: row.get("accessed_at"),
+        access_count: row.get("access_count"),
+    }
+}
+
+/// Best [`SEARCH_LIMIT`] search hits, highest [`search_score`] first and
+/// newest first among equals
+fn rank_search_results(mut items: Vec<ClipboardHistoryItem>, needle: &str) -> Vec<ClipboardHistoryItem> {
+    items.sort_by_cached_key(|item| {
+        (
+            std::cmp::Reverse(search_score(item, needle)),
+            std::cmp::Reverse(item.created_at),
+        )
+    });
+    items.truncate(SEARCH_LIMIT);
+    items
+}
+
+/// Relevance of a hit for the lowercase `needle`: a match at the start of
+/// the text beats one inside it, then favorites win. Frequent use adds a
+/// small boost that never lifts an item past a better match.
+fn search_score(item: &ClipboardHistoryItem, needle: &str) -> i32 {
+    let text = item.plain_text.as_deref().unwrap_or_default().trim_start().to_lowercase();
+    let mut score = if text.starts_with(needle) {
+        100
+    } else if text.contains(needle) {
+        50
+    } else {
+        0
+    };
+    if item.is_favorite {
+        score += 20;
+    }
+    score + ((item.access_count.max(0) as f64).ln_1p() * 5.0).min(15.0) as i32
+}
+
+/// Storage and items for the clipboard module's tests
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    pub async fn memory_storage() -> ClipboardStorage {
+        // A single connection so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        ClipboardStorage::new(pool).await.unwrap()
+    }
+
+    pub fn image_item(id: &str, size: usize, minutes_ago: i64) -> ClipboardHistoryItem {
+        ClipboardHistoryItem {
+            id: id.to_string(),
+            content_type: "image".to_string(),
+            content_hash: format!("hash-{}", id),
+            plain_text: None,
+            data: Some(vec![0u8; size]),
+            thumbnail: None,
+            source_app: None,
+            source_window: None,
+            is_favorite: false,
+            is_sensitive: false,
+            created_at: Utc::now() - chrono::Duration::minutes(minutes_ago),
+            accessed_at: None,
+            access_count: 0,
+        }
+    }
+
+    pub fn text_item(id: &str, text: &str, minutes_ago: i64) -> ClipboardHistoryItem {
+        ClipboardHistoryItem {
+            content_type: "text".to_string(),
+            plain_text: Some(text.to_string()),
+            data: None,
+            ..image_item(id, 0, minutes_ago)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::{image_item, memory_storage, text_item};
+    use super::*;
+
+    async fn remaining_ids(storage: &ClipboardStorage) -> Vec<String> {
+        let mut ids: Vec<String> = storage
+            .get_history(100, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|item| item.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    async fn mixed_storage() -> ClipboardStorage {
+        let storage = memory_storage().await;
+        storage.add_item(&text_item("note", "buy milk", 6)).await.unwrap();
+        storage.add_item(&text_item("url", "https://example.com/a?b=1", 5)).await.unwrap();
+        storage.add_item(&text_item("www", "  www.rust-lang.org ", 4)).await.unwrap();
+        storage.add_item(&text_item("sentence", "see https://example.com for more", 3)).await.unwrap();
+        storage.add_item(&image_item("shot", 100, 2)).await.unwrap();
+        let mut files = text_item("files", "C:\\a.txt", 1);
+        files.content_type = "files".to_string();
+        files.is_favorite = true;
+        storage.add_item(&files).await.unwrap();
+        storage
+    }
+
+    fn ids(items: Vec<ClipboardHistoryItem>) -> Vec<String> {
+        items.into_iter().map(|item| item.id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_recopy_bumps_existing_row() {
+        let storage = memory_storage().await;
+        let mut first = text_item("first", "hello", 10);
+        first.content_hash = "hash-hello".to_string();
+        first.source_app = Some("Editor".to_string());
+        first.is_favorite = true;
+        assert_eq!(storage.upsert_item(&first).await.unwrap(), Upsert::Inserted("first".to_string()));
+        storage.add_item(&text_item("other", "world", 5)).await.unwrap();
+
+        let mut again = text_item("again", "hello", 0);
+        again.content_hash = "hash-hello".to_string();
+        assert_eq!(storage.upsert_item(&again).await.unwrap(), Upsert::Bumped("first".to_string()));
+
+        let history = storage.get_history(100, 0).await.unwrap();
+        assert_eq!(ids(history.clone()), vec!["first", "other"]);
+        let bumped = &history[0];
+        assert!(bumped.created_at > first.created_at);
+        assert_eq!(bumped.accessed_at, Some(bumped.created_at));
+        assert_eq!(bumped.access_count, 1);
+        assert!(bumped.is_favorite);
+        assert_eq!(bumped.source_app.as_deref(), Some("Editor"));
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_content_type() {
+        let storage = mixed_storage().await;
+
+        assert_eq!(storage.history_ids().await.unwrap().len(), 6);
+
+        let text = storage
+            .get_history_filtered(Some(ClipboardTypeFilter::Text), false, 100, 0)
+            .await
+            .unwrap();
+        assert_eq!(ids(text), vec!["sentence", "www", "url", "note"]);
+
+        let images = storage
+            .get_history_filtered(Some(ClipboardTypeFilter::Image), false, 100, 0)
+            .await
+            .unwrap();
+        assert_eq!(ids(images), vec!["shot"]);
+
+        let files = storage
+            .get_history_filtered(Some(ClipboardTypeFilter::Files), false, 100, 0)
+            .await
+            .unwrap();
+        assert_eq!(ids(files), vec!["files"]);
+    }
+
+    #[tokio::test]
+    async fn test_filter_links_only_matches_urls() {
+        let storage = mixed_storage().await;
+        let links = storage
+            .get_history_filtered(Some(ClipboardTypeFilter::Link), false, 100, 0)
+            .await
+            .unwrap();
+        assert_eq!(ids(links), vec!["www", "url"]);
+    }
+
+    #[tokio::test]
+    async fn test_filter_pagination_and_favorites() {
+        let storage = mixed_storage().await;
+
+        let page = storage
+            .get_history_filtered(Some(ClipboardTypeFilter::Text), false, 2, 1)
+            .await
+            .unwrap();
+        assert_eq!(ids(page), vec!["www", "url"]);
+
+        let favorites = storage.get_history_filtered(None, true, 100, 0).await.unwrap();
+        assert_eq!(ids(favorites), vec!["files"]);
+    }
+
+    #[tokio::test]
+    async fn test_prune_drops_largest_blobs_over_byte_budget() {
+        let storage = memory_storage().await;
+        storage.add_item(&image_item("small-old", 100, 30)).await.unwrap();
+        storage.add_item(&image_item("big-new", 5_000, 1)).await.unwrap();
+        storage.add_item(&image_item("medium", 2_000, 10)).await.unwrap();
+        storage.add_item(&image_item("small-new", 100, 0)).await.unwrap();
+
+        // 7_200 bytes stored; dropping the 5_000 byte blob gets under 4_000
+        let pruned = storage.prune(100, 30, 4_000).await.unwrap();
+
+        assert_eq!(pruned, 1);
+        assert_eq!(remaining_ids(&storage).await, vec!["medium", "small-new", "small-old"]);
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_favorites_and_sensitive_rows() {
+        let storage = memory_storage().await;
+        let mut favorite = image_item("favorite", 5_000, 5);
+        favorite.is_favorite = true;
+        let mut sensitive = image_item("sensitive", 5_000, 5);
+        sensitive.is_sensitive = true;
+        storage.add_item(&favorite).await.unwrap();
+        storage.add_item(&sensitive).await.unwrap();
+        storage.add_item(&image_item("a", 1_000, 3)).await.unwrap();
+        storage.add_item(&image_item("b", 1_000, 2)).await.unwrap();
+
+        // Budget can never be met, so every prunable row goes but nothing else
+        let pruned = storage.prune(100, 30, 1_000).await.unwrap();
+
+        assert_eq!(pruned, 2);
+        assert_eq!(remaining_ids(&storage).await, vec!["favorite", "sensitive"]);
+    }
+
+    #[tokio::test]
+    async fn test_sensitive_rows_expire() {
+        let storage = memory_storage().await;
+        let mut old_secret = text_item("old-secret", "••••••••", 90);
+        old_secret.is_sensitive = true;
+        old_secret.data = Some(b"hunter2!A9".to_vec());
+        let mut new_secret = old_secret.clone();
+        new_secret.id = "new-secret".to_string();
+        new_secret.content_hash = "new-secret".to_string();
+        new_secret.created_at = Utc::now() - chrono::Duration::minutes(10);
+        let mut kept_secret = old_secret.clone();
+        kept_secret.id = "favorite-secret".to_string();
+        kept_secret.content_hash = "favorite-secret".to_string();
+        kept_secret.is_favorite = true;
+        for item in [&old_secret, &new_secret, &kept_secret] {
+            storage.add_item(item).await.unwrap();
+        }
+        storage.add_item(&text_item("old-note", "buy milk", 90)).await.unwrap();
+
+        assert_eq!(storage.expire_sensitive(60).await.unwrap(), 1);
+        assert_eq!(remaining_ids(&storage).await, vec!["favorite-secret", "new-secret", "old-note"]);
+        // Out-of-range ages only keep everything
+        assert_eq!(storage.expire_sensitive(u64::MAX).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_prune_by_count_and_age() {
+        let storage = memory_storage().await;
+        storage.add_item(&image_item("ancient", 10, 60 * 24 * 40)).await.unwrap();
+        storage.add_item(&image_item("old", 10, 30)).await.unwrap();
+        storage.add_item(&image_item("mid", 10, 20)).await.unwrap();
+        storage.add_item(&image_item("new", 10, 10)).await.unwrap();
+
+        let pruned = storage.prune(2, 30, u64::MAX).await.unwrap();
+
+        assert_eq!(pruned, 2);
+        assert_eq!(remaining_ids(&storage).await, vec!["mid", "new"]);
+    }
+
+    async fn encrypted(storage: ClipboardStorage, encrypt: bool) -> ClipboardStorage {
+        let cipher = ClipboardCipher::new(&ClipboardCipher::generate_key());
+        ClipboardStorage::with_encryption(storage.pool, cipher, encrypt).await.unwrap()
+    }
+
+    async fn raw_hash(storage: &ClipboardStorage, id: &str) -> String {
+        sqlx::query_scalar("SELECT content_hash FROM clipboard_history WHERE id = ?")
+            .bind(id)
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap()
+    }
+
+    async fn raw_columns(storage: &ClipboardStorage, id: &str) -> (Option<String>, Option<Vec<u8>>) {
+        sqlx::query_as("SELECT plain_text, data FROM clipboard_history WHERE id = ?")
+            .bind(id)
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_rows_round_trip() {
+        let storage = encrypted(memory_storage().await, true).await;
+        let note = text_item("note", "buy milk", 1);
+        let shot = image_item("shot", 100, 0);
+        storage.add_item(&note).await.unwrap();
+        storage.add_item(&shot).await.unwrap();
+
+        let (text, _) = raw_columns(&storage, "note").await;
+        assert_ne!(text.as_deref(), Some("buy milk"));
+        assert!(!text.unwrap().contains("milk"));
+        let (_, data) = raw_columns(&storage, "shot").await;
+        assert_ne!(data, shot.data);
+
+        let history = storage.get_history(10, 0).await.unwrap();
+        assert_eq!(history[0].data, shot.data);
+        assert_eq!(history[1].plain_text.as_deref(), Some("buy milk"));
+        let by_id = storage.get_by_id("note").await.unwrap().unwrap();
+        assert_eq!(by_id.plain_text.as_deref(), Some("buy milk"));
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_search_and_link_filter() {
+        let plain = mixed_storage().await;
+        let storage = encrypted(plain, true).await;
+        assert_eq!(storage.migrate_encryption().await.unwrap(), 6);
+
+        assert_eq!(ids(storage.search("EXAMPLE.com").await.unwrap()), vec!["sentence", "url"]);
+        let links = storage
+            .get_history_filtered(Some(ClipboardTypeFilter::Link), false, 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(ids(links), vec!["url"]);
+    }
+
+    #[tokio::test]
+    async fn test_migration_seals_then_opens_existing_rows() {
+        let plain = memory_storage().await;
+        let mut note = text_item("note", "buy milk", 1);
+        note.content_hash = format!("{:x}", md5::compute("buy milk"));
+        plain.add_item(&note).await.unwrap();
+        plain.add_item(&image_item("shot", 100, 0)).await.unwrap();
+
+        let cipher = ClipboardCipher::new(&ClipboardCipher::generate_key());
+        let storage = ClipboardStorage::with_encryption(plain.pool, cipher.clone(), true).await.unwrap();
+        assert_eq!(storage.migrate_encryption().await.unwrap(), 2);
+        assert_eq!(storage.migrate_encryption().await.unwrap(), 0);
+        let (text, _) = raw_columns(&storage, "note").await;
+        assert!(text.unwrap().starts_with(TEXT_PREFIX));
+        let (_, data) = raw_columns(&storage, "shot").await;
+        assert!(data.unwrap().starts_with(BLOB_MAGIC));
+        assert_eq!(raw_hash(&storage, "note").await, cipher.keyed_hash(&note.content_hash));
+        assert!(storage.exists_by_hash(&note.content_hash).await.unwrap());
+
+        // Turning encryption off opens the rows and hashes them again
+        let storage = ClipboardStorage::with_encryption(storage.pool, cipher, false).await.unwrap();
+        assert_eq!(storage.migrate_encryption().await.unwrap(), 2);
+        assert_eq!(raw_columns(&storage, "note").await.0.as_deref(), Some("buy milk"));
+        assert_eq!(raw_columns(&storage, "shot").await.1, Some(vec![0u8; 100]));
+        assert_eq!(raw_hash(&storage, "note").await, note.content_hash);
+        assert_eq!(raw_hash(&storage, "shot").await, format!("{:x}", md5::compute([0u8; 100])));
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_that_looks_sealed_is_kept() {
+        // Encryption off, but a key is loaded for earlier sealed rows
+        let storage = encrypted(memory_storage().await, false).await;
+        let text = format!("{}not actually sealed", TEXT_PREFIX);
+        storage.add_item(&text_item("note", &text, 0)).await.unwrap();
+        assert_eq!(storage.migrate_encryption().await.unwrap(), 0);
+
+        let item = storage.get_by_id("note").await.unwrap().unwrap();
+        assert_eq!(item.plain_text.as_deref(), Some(text.as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_hashes_are_keyed_and_still_dedup() {
+        let storage = encrypted(memory_storage().await, true).await;
+        let mut first = text_item("first", "hello", 1);
+        first.content_hash = "hash-hello".to_string();
+        storage.upsert_item(&first).await.unwrap();
+        assert_ne!(raw_hash(&storage, "first").await, "hash-hello");
+
+        let mut again = text_item("again", "hello", 0);
+        again.content_hash = "hash-hello".to_string();
+        assert_eq!(storage.upsert_item(&again).await.unwrap(), Upsert::Bumped("first".to_string()));
+        assert!(storage.exists_by_hash("hash-hello").await.unwrap());
+    }
+
+    #[test]
+    fn test_html_items_keep_their_formatting() {
+        let content = ClipboardContent::Html {
+            html: "<b>bold</b>".to_string(),
+            plain_text: "bold".to_string(),
+        };
+        let mut item = text_item("html", "bold", 0);
+        item.content_type = "html".to_string();
+        item.data = content.data();
+
+        assert_eq!(item.html().as_deref(), Some("<b>bold</b>"));
+        assert_eq!(item.pasteable_text().as_deref(), Some("bold"));
+        assert_eq!(
+            plain_hash("html", false, item.plain_text.as_deref(), item.data.as_deref()),
+            content.hash()
+        );
+        // Sensitive but not masked: the text is the original
+        item.is_sensitive = true;
+        assert_eq!(item.pasteable_text().as_deref(), Some("bold"));
+    }
+
+    #[tokio::test]
+    async fn test_exported_hashes_are_unkeyed() {
+        let storage = encrypted(memory_storage().await, true).await;
+        let mut note = text_item("note", "buy milk", 1);
+        note.content_hash = format!("{:x}", md5::compute("buy milk"));
+        storage.add_item(&note).await.unwrap();
+
+        let exported: Vec<ClipboardHistoryItem> =
+            storage.stream_all().map(Result::unwrap).collect().await;
+        assert_eq!(exported[0].content_hash, note.content_hash);
+
+        // Another machine with its own key, and one without encryption
+        for other in [encrypted(memory_storage().await, true).await, memory_storage().await] {
+            assert!(other.import_item(&exported[0]).await.unwrap());
+            assert!(other.exists_by_hash(&note.content_hash).await.unwrap());
+            assert!(!other.import_item(&exported[0]).await.unwrap());
+        }
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut out = Vec::new();
+        image::DynamicImage::ImageRgba8(image::RgbaImage::new(width, height))
+            .write_to(&mut std::io::Cursor::new(&mut out), image::ImageOutputFormat::Png)
+            .unwrap();
+        out
+    }
+
+    #[tokio::test]
+    async fn test_history_list_returns_thumbnails_not_images() {
+        let storage = encrypted(memory_storage().await, true).await;
+        let mut shot = image_item("shot", 0, 1);
+        shot.data = Some(png(1920, 1080));
+        storage.add_item(&shot).await.unwrap();
+        // Not an image after all: stored without a thumbnail
+        storage.add_item(&image_item("broken", 100, 0)).await.unwrap();
+
+        let list = storage.get_history_filtered(None, false, 10, 0).await.unwrap();
+        assert_eq!(ids(list.clone()), vec!["broken", "shot"]);
+        assert!(list.iter().all(|item| item.data.is_none()));
+        assert_eq!(list[0].thumbnail, None);
+        let thumbnail = image::load_from_memory(list[1].thumbnail.as_ref().unwrap()).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (128, 72));
+
+        // The full image is still there for pasting
+        let full = storage.get_by_id("shot").await.unwrap().unwrap();
+        assert_eq!(full.data, shot.data);
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_prefix_matches_first() {
+        let storage = memory_storage().await;
+        storage.add_item(&text_item("prefix", "Hello world", 30)).await.unwrap();
+        storage.add_item(&text_item("prefix-old", "  hello again", 40)).await.unwrap();
+        let mut favorite = text_item("favorite", "well, hello", 20);
+        favorite.is_favorite = true;
+        storage.add_item(&favorite).await.unwrap();
+        storage.add_item(&text_item("substring", "say hello", 1)).await.unwrap();
+        let mut used = text_item("used", "oh hello", 10);
+        used.access_count = 50;
+        storage.add_item(&used).await.unwrap();
+        storage.add_item(&text_item("other", "goodbye", 0)).await.unwrap();
+
+        let results = storage.search("HELLO").await.unwrap();
+
+        assert_eq!(ids(results), vec!["prefix", "prefix-old", "favorite", "used", "substring"]);
+    }
+
+    #[test]
+    fn test_is_link_matches_sql_pattern() {
+        assert!(is_link("https://example.com/a?b=1"));
+        assert!(is_link("  www.rust-lang.org "));
+        assert!(!is_link("see https://example.com for more"));
+        assert!(!is_link("https://"));
+        assert!(!is_link("https://example.com\n"));
+    }
+
+    #[tokio::test]
+    async fn test_combine_text_joins_in_requested_order() {
+        let storage = mixed_storage().await;
+        let mut secret = text_item("secret", "pw: ******", 0);
+        secret.is_sensitive = true;
+        secret.data = Some(b"pw: hunter2".to_vec());
+        storage.add_item(&secret).await.unwrap();
+
+        let ids = ["url", "shot", "note", "gone", "secret", "files"].map(String::from);
+        let combined = storage.combine_text(&ids, " | ").await.unwrap();
+
+        assert_eq!(combined.text, "https://example.com/a?b=1 | buy milk | pw: hunter2");
+        assert_eq!(combined.preview, "https://example.com/a?b=1 | buy milk | pw: ******");
+        assert!(combined.sensitive);
+        assert_eq!((combined.combined, combined.skipped, combined.missing), (3, 2, 1));
+
+        let plain = storage.combine_text(&["url", "note"].map(String::from), "\n").await.unwrap();
+        assert!(!plain.sensitive);
+        assert_eq!(plain.preview, plain.text);
+    }
 }
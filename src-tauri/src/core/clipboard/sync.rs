@@ -0,0 +1,236 @@
+// Clipboard history sync through a shared folder (Syncthing, Dropbox, ...)
+//
+// Each new text item is written as an export file of its own, named
+// `<created at, ms>-<item id>.jsonl`: devices never write the same file, so
+// the sync tool has no conflicts to resolve. Files that appear in the folder
+// are imported unless their content is already in history, which also skips
+// the files this device wrote itself. Names carry nothing derived from the
+// content. Syncing is off while history is encrypted, since the files would
+// hold the text in the clear.
+use super::storage::{ClipboardHistoryItem, ClipboardStorage};
+use super::transfer::{export_item, import_history, ImportSummary};
+use crate::app::error::{AppError, AppResult};
+use crate::core::indexer::FileWatcher;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Extension of synced item files; anything else in the folder is ignored
+pub const SYNC_EXTENSION: &str = "jsonl";
+
+/// Whether `item` may leave this device: text only, never sensitive items
+pub fn should_sync(item: &ClipboardHistoryItem) -> bool {
+    item.content_type == "text" && !item.is_sensitive
+}
+
+/// Name of the file `item` is synced as
+pub fn sync_file_name(item: &ClipboardHistoryItem) -> String {
+    format!("{}-{}.{}", item.created_at.timestamp_millis(), item.id, SYNC_EXTENSION)
+}
+
+/// Whether `path` is named like a synced item file
+fn is_sync_file(path: &Path) -> bool {
+    if path.extension().map_or(true, |ext| ext != SYNC_EXTENSION) {
+        return false;
+    }
+    let Some((millis, id)) = path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.split_once('-')) else {
+        return false;
+    };
+    !millis.is_empty() && millis.bytes().all(|b| b.is_ascii_digit()) && !id.is_empty()
+}
+
+/// Write `item` to the sync folder. Returns `false` for items that are not
+/// synced. The file is renamed into place so other devices never import a
+/// partial one.
+pub async fn mirror_item(folder: &Path, item: &ClipboardHistoryItem) -> AppResult<bool> {
+    if !should_sync(item) {
+        return Ok(false);
+    }
+    let name = sync_file_name(item);
+    let temp = folder.join(format!(".{}.tmp", name));
+    export_item(item, &temp).await?;
+    tokio::fs::rename(&temp, folder.join(name)).await?;
+    Ok(true)
+}
+
+/// Import one synced file. Content already in history is counted as a
+/// duplicate; files that are not synced items, or are gone already, count
+/// as neither.
+pub async fn import_file(storage: &ClipboardStorage, path: &Path) -> AppResult<ImportSummary> {
+    if !is_sync_file(path) || !path.is_file() {
+        return Ok(ImportSummary::default());
+    }
+    import_history(storage, path, true).await
+}
+
+/// Import every synced file in `folder`, oldest first
+pub async fn import_folder(storage: &ClipboardStorage, folder: &Path) -> AppResult<ImportSummary> {
+    let mut paths = Vec::new();
+    let mut entries = tokio::fs::read_dir(folder).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        paths.push(entry.path());
+    }
+    // Names start with the creation time
+    paths.sort();
+
+    let mut summary = ImportSummary::default();
+    for path in paths {
+        match import_file(storage, &path).await {
+            Ok(found) => {
+                summary.imported += found.imported;
+                summary.duplicates += found.duplicates;
+            }
+            Err(e) => tracing::warn!("Skipping synced clipboard file {:?}: {}", path, e),
+        }
+    }
+    Ok(summary)
+}
+
+/// A sync folder being watched for items from other devices. Watching
+/// stops when this is dropped.
+pub struct ClipboardSync {
+    folder: PathBuf,
+    _watcher: FileWatcher,
+}
+
+impl ClipboardSync {
+    /// Import what is in `folder` already, then watch it. `on_import` runs
+    /// after a new file added items to history.
+    pub async fn start<F>(folder: PathBuf, storage: Arc<ClipboardStorage>, on_import: F) -> AppResult<Self>
+    where
+        F: Fn(ImportSummary) + Send + Sync + 'static,
+    {
+        tokio::fs::create_dir_all(&folder).await?;
+        let summary = import_folder(&storage, &folder).await?;
+        tracing::info!(
+            "Clipboard sync folder {:?}: {} imported, {} already in history",
+            folder,
+            summary.imported,
+            summary.duplicates
+        );
+        if summary.imported > 0 {
+            on_import(summary);
+        }
+
+        let mut watcher = FileWatcher::new();
+        watcher.add_path(folder.clone());
+        let on_import = Arc::new(on_import);
+        watcher
+            .start_watching(move |path| {
+                let storage = storage.clone();
+                let on_import = on_import.clone();
+                async move {
+                    match import_file(&storage, &path).await {
+                        Ok(summary) if summary.imported > 0 => on_import(summary),
+                        Ok(_) => {}
+                        Err(e) => tracing::warn!("Failed to import synced clipboard file {:?}: {}", path, e),
+                    }
+                }
+            })
+            .await
+            .map_err(|e| AppError::Unknown(format!("Failed to watch the clipboard sync folder: {}", e)))?;
+
+        Ok(Self {
+            folder,
+            _watcher: watcher,
+        })
+    }
+
+    pub fn folder(&self) -> &Path {
+        &self.folder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::clipboard::storage::test_support::memory_storage;
+
+    fn text_item(id: &str, text: &str) -> ClipboardHistoryItem {
+        crate::core::clipboard::storage::test_support::text_item(id, text, 0)
+    }
+
+    fn temp_folder() -> PathBuf {
+        let folder = std::env::temp_dir().join(format!("omnibox_clipboard_sync_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    #[tokio::test]
+    async fn test_import_skips_hashes_already_in_history() {
+        let folder = temp_folder();
+        let first = text_item("1", "from the laptop");
+        let second = text_item("2", "also from the laptop");
+        assert!(mirror_item(&folder, &first).await.unwrap());
+        assert!(mirror_item(&folder, &second).await.unwrap());
+
+        // This device copied the first text itself, under another id
+        let storage = memory_storage().await;
+        storage.add_item(&ClipboardHistoryItem { id: "local".to_string(), ..first.clone() }).await.unwrap();
+
+        let first_file = folder.join(sync_file_name(&first));
+        let second_file = folder.join(sync_file_name(&second));
+        assert_eq!(
+            import_file(&storage, &first_file).await.unwrap(),
+            ImportSummary { imported: 0, duplicates: 1 }
+        );
+        assert_eq!(
+            import_file(&storage, &second_file).await.unwrap(),
+            ImportSummary { imported: 1, duplicates: 0 }
+        );
+        // The watcher reports the same file again on later modify events
+        assert_eq!(
+            import_file(&storage, &second_file).await.unwrap(),
+            ImportSummary { imported: 0, duplicates: 1 }
+        );
+
+        let history = storage.get_history(10, 0).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().any(|item| item.plain_text.as_deref() == Some("also from the laptop")));
+
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_folder_ignores_other_files() {
+        let folder = temp_folder();
+        mirror_item(&folder, &text_item("1", "synced")).await.unwrap();
+        std::fs::write(folder.join("notes.txt"), "not an item").unwrap();
+        std::fs::write(folder.join(".123-9.jsonl.tmp"), "partial").unwrap();
+        std::fs::write(folder.join("draft-8.jsonl"), "not a synced name").unwrap();
+
+        let storage = memory_storage().await;
+        assert_eq!(
+            import_folder(&storage, &folder).await.unwrap(),
+            ImportSummary { imported: 1, duplicates: 0 }
+        );
+        assert_eq!(
+            import_folder(&storage, &folder).await.unwrap(),
+            ImportSummary { imported: 0, duplicates: 1 }
+        );
+
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn test_file_names_do_not_reveal_content() {
+        let item = text_item("1", "hunter2");
+        let name = sync_file_name(&item);
+        assert!(!name.contains(&item.content_hash));
+        assert_eq!(name, format!("{}-1.jsonl", item.created_at.timestamp_millis()));
+    }
+
+    #[tokio::test]
+    async fn test_sensitive_and_image_items_stay_local() {
+        let folder = temp_folder();
+        let mut secret = text_item("1", "hunter2");
+        secret.is_sensitive = true;
+        let mut image = text_item("2", "");
+        image.content_type = "image".to_string();
+
+        assert!(!mirror_item(&folder, &secret).await.unwrap());
+        assert!(!mirror_item(&folder, &image).await.unwrap());
+        assert_eq!(std::fs::read_dir(&folder).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+}
@@ -0,0 +1,62 @@
+// Small PNG previews of image clipboard items for the history list
+use image::ImageOutputFormat;
+use std::io::Cursor;
+
+/// Longest side of a stored thumbnail, in pixels
+pub const THUMBNAIL_SIZE: u32 = 128;
+
+/// Encode `data` as a PNG no larger than `max_side` on either side, keeping
+/// the aspect ratio. Smaller images are re-encoded at their own size.
+/// Returns `None` when `data` is not a decodable image.
+pub fn png_thumbnail(data: &[u8], max_side: u32) -> Option<Vec<u8>> {
+    let image = image::load_from_memory(data).ok()?;
+    let image = if image.width() > max_side || image.height() > max_side {
+        image.thumbnail(max_side, max_side)
+    } else {
+        image
+    };
+
+    let mut out = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut out), ImageOutputFormat::Png)
+        .map_err(|e| tracing::debug!("Failed to encode clipboard thumbnail: {}", e))
+        .ok()?;
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, RgbaImage};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut out = Vec::new();
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, image::Rgba([200, 30, 30, 255])))
+            .write_to(&mut Cursor::new(&mut out), ImageOutputFormat::Png)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn test_large_image_gets_bounded_thumbnail() {
+        let original = png(2000, 1000);
+        let thumbnail = png_thumbnail(&original, THUMBNAIL_SIZE).unwrap();
+
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (128, 64));
+        assert!(thumbnail.len() < original.len());
+    }
+
+    #[test]
+    fn test_small_image_keeps_its_size() {
+        let thumbnail = png_thumbnail(&png(40, 90), THUMBNAIL_SIZE).unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (40, 90));
+    }
+
+    #[test]
+    fn test_non_image_data_has_no_thumbnail() {
+        assert_eq!(png_thumbnail(b"definitely not an image", THUMBNAIL_SIZE), None);
+        assert_eq!(png_thumbnail(&[], THUMBNAIL_SIZE), None);
+    }
+}
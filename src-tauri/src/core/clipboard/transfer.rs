@@ -0,0 +1,322 @@
+// Clipboard history export/import
+//
+// The file is newline-delimited JSON: a header line followed by one item per
+// line, with binary `data` base64-encoded. Items are written and read one at
+// a time, so large images never sit in memory as a single document.
+use super::storage::{ClipboardHistoryItem, ClipboardStorage};
+use crate::app::error::{AppError, AppResult};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, BufWriter};
+
+/// `format` value in the header line
+pub const EXPORT_FORMAT: &str = "omnibox-clipboard";
+pub const EXPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+}
+
+/// One line of the export file
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportedItem {
+    id: String,
+    content_type: String,
+    content_hash: String,
+    #[serde(default)]
+    plain_text: Option<String>,
+    /// Base64 of `ClipboardHistoryItem::data`
+    #[serde(default)]
+    data: Option<String>,
+    #[serde(default)]
+    source_app: Option<String>,
+    #[serde(default)]
+    source_window: Option<String>,
+    #[serde(default)]
+    is_favorite: bool,
+    #[serde(default)]
+    is_sensitive: bool,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    accessed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    access_count: i32,
+}
+
+impl From<ClipboardHistoryItem> for ExportedItem {
+    fn from(item: ClipboardHistoryItem) -> Self {
+        Self {
+            id: item.id,
+            content_type: item.content_type,
+            content_hash: item.content_hash,
+            plain_text: item.plain_text,
+            data: item.data.map(|data| base64::engine::general_purpose::STANDARD.encode(data)),
+            source_app: item.source_app,
+            source_window: item.source_window,
+            is_favorite: item.is_favorite,
+            is_sensitive: item.is_sensitive,
+            created_at: item.created_at,
+            accessed_at: item.accessed_at,
+            access_count: item.access_count,
+        }
+    }
+}
+
+impl TryFrom<ExportedItem> for ClipboardHistoryItem {
+    type Error = AppError;
+
+    fn try_from(item: ExportedItem) -> AppResult<Self> {
+        let data = item
+            .data
+            .map(|data| base64::engine::general_purpose::STANDARD.decode(data))
+            .transpose()
+            .map_err(|e| AppError::Parse(format!("Invalid data in item {}: {}", item.id, e)))?;
+        Ok(Self {
+            id: item.id,
+            content_type: item.content_type,
+            content_hash: item.content_hash,
+            plain_text: item.plain_text,
+            data,
+            // Made again from `data` when the item is stored
+            thumbnail: None,
+            source_app: item.source_app,
+            source_window: item.source_window,
+            is_favorite: item.is_favorite,
+            is_sensitive: item.is_sensitive,
+            created_at: item.created_at,
+            accessed_at: item.accessed_at,
+            access_count: item.access_count,
+        })
+    }
+}
+
+/// Outcome of [`import_history`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    /// Items whose content was already in history
+    pub duplicates: usize,
+}
+
+/// Write every history item to `path`. Returns the number of items written.
+pub async fn export_history(storage: &ClipboardStorage, path: &Path) -> AppResult<usize> {
+    let mut writer = BufWriter::new(tokio::fs::File::create(path).await?);
+    write_header(&mut writer).await?;
+
+    let mut count = 0;
+    let mut items = storage.stream_all();
+    while let Some(item) = items.next().await {
+        write_line(&mut writer, &ExportedItem::from(item?)).await?;
+        count += 1;
+    }
+    writer.flush().await?;
+    Ok(count)
+}
+
+/// Write a single item to `path` as an export file of its own
+pub async fn export_item(item: &ClipboardHistoryItem, path: &Path) -> AppResult<()> {
+    let mut writer = BufWriter::new(tokio::fs::File::create(path).await?);
+    write_header(&mut writer).await?;
+    write_line(&mut writer, &ExportedItem::from(item.clone())).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+async fn write_header<W: AsyncWrite + Unpin>(writer: &mut W) -> AppResult<()> {
+    let header = Header {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+    };
+    write_line(writer, &header).await
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(writer: &mut W, value: &impl Serialize) -> AppResult<()> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
+
+/// Read an export file into history, skipping content that is already
+/// stored. Without `merge` the current history is cleared first
+/// (favorites are kept, like "Clear history"). The whole file is checked
+/// before anything is cleared or stored, so a bad file changes nothing.
+pub async fn import_history(storage: &ClipboardStorage, path: &Path, merge: bool) -> AppResult<ImportSummary> {
+    // A first pass only parses, one item at a time like the import itself
+    let mut items = read_items(path).await?;
+    while items.next_item().await?.is_some() {}
+
+    if !merge {
+        storage.clear_all().await?;
+    }
+
+    let mut summary = ImportSummary::default();
+    let mut items = read_items(path).await?;
+    while let Some(item) = items.next_item().await? {
+        if storage.import_item(&item).await? {
+            summary.imported += 1;
+        } else {
+            summary.duplicates += 1;
+        }
+    }
+    Ok(summary)
+}
+
+/// Items of an export file, after its header
+struct ExportReader {
+    lines: tokio::io::Lines<BufReader<tokio::fs::File>>,
+}
+
+/// Open an export file and check its header
+async fn read_items(path: &Path) -> AppResult<ExportReader> {
+    let mut lines = BufReader::new(tokio::fs::File::open(path).await?).lines();
+
+    let header: Header = match lines.next_line().await? {
+        Some(line) => serde_json::from_str(&line)
+            .map_err(|_| AppError::Parse("Not a clipboard history export".to_string()))?,
+        None => return Err(AppError::Parse("Clipboard history export is empty".to_string())),
+    };
+    if header.format != EXPORT_FORMAT || header.version > EXPORT_VERSION {
+        return Err(AppError::Parse(format!(
+            "Unsupported clipboard export: {} version {}",
+            header.format, header.version
+        )));
+    }
+    Ok(ExportReader { lines })
+}
+
+impl ExportReader {
+    async fn next_item(&mut self) -> AppResult<Option<ClipboardHistoryItem>> {
+        while let Some(line) = self.lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let exported: ExportedItem = serde_json::from_str(&line)?;
+            return ClipboardHistoryItem::try_from(exported).map(Some);
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::clipboard::storage::test_support::{image_item, memory_storage};
+
+    fn item(id: &str, content_type: &str, minutes_ago: i64) -> ClipboardHistoryItem {
+        ClipboardHistoryItem {
+            content_type: content_type.to_string(),
+            data: None,
+            source_app: Some("Editor".to_string()),
+            access_count: 2,
+            ..image_item(id, 0, minutes_ago)
+        }
+    }
+
+    fn temp_file() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("omnibox_clipboard_export_{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    async fn sample_storage() -> ClipboardStorage {
+        let storage = memory_storage().await;
+        let mut note = item("note", "text", 3);
+        note.plain_text = Some("first line\nsecond line".to_string());
+        note.is_favorite = true;
+        let mut password = item("password", "text", 2);
+        password.plain_text = Some("hunter2".to_string());
+        password.is_sensitive = true;
+        let mut shot = item("shot", "image", 1);
+        shot.data = Some((0..=255u8).cycle().take(4096).collect());
+        for item in [note, password, shot] {
+            storage.add_item(&item).await.unwrap();
+        }
+        storage
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_text_and_images() {
+        let source = sample_storage().await;
+        let path = temp_file();
+        assert_eq!(export_history(&source, &path).await.unwrap(), 3);
+
+        let target = memory_storage().await;
+        let summary = import_history(&target, &path, true).await.unwrap();
+        assert_eq!(summary, ImportSummary { imported: 3, duplicates: 0 });
+
+        let original = source.get_history(10, 0).await.unwrap();
+        let imported = target.get_history(10, 0).await.unwrap();
+        assert_eq!(imported.len(), original.len());
+        for (a, b) in original.iter().zip(&imported) {
+            assert_eq!(a.id, b.id);
+            assert_eq!(a.content_hash, b.content_hash);
+            assert_eq!(a.plain_text, b.plain_text);
+            assert_eq!(a.data, b.data);
+            assert_eq!(a.is_favorite, b.is_favorite);
+            assert_eq!(a.is_sensitive, b.is_sensitive);
+            assert_eq!(a.source_app, b.source_app);
+            assert_eq!(a.access_count, b.access_count);
+        }
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_skips_existing_content() {
+        let source = sample_storage().await;
+        let path = temp_file();
+        export_history(&source, &path).await.unwrap();
+
+        // The target already holds the screenshot under another id
+        let target = memory_storage().await;
+        let mut existing = item("local-shot", "image", 0);
+        existing.content_hash = "hash-shot".to_string();
+        target.add_item(&existing).await.unwrap();
+
+        let summary = import_history(&target, &path, true).await.unwrap();
+        assert_eq!(summary, ImportSummary { imported: 2, duplicates: 1 });
+
+        // Importing again adds nothing
+        let summary = import_history(&target, &path, true).await.unwrap();
+        assert_eq!(summary, ImportSummary { imported: 0, duplicates: 3 });
+        assert_eq!(target.get_history(10, 0).await.unwrap().len(), 3);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rejects_foreign_files() {
+        let path = temp_file();
+        std::fs::write(&path, "{\"hello\":\"world\"}\n").unwrap();
+
+        let storage = memory_storage().await;
+        assert!(matches!(
+            import_history(&storage, &path, true).await,
+            Err(AppError::Parse(_))
+        ));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bad_file_leaves_history_alone() {
+        let source = sample_storage().await;
+        let path = temp_file();
+        export_history(&source, &path).await.unwrap();
+        let mut text = std::fs::read_to_string(&path).unwrap();
+        text.push_str("{\"id\":\"broken\"\n");
+        std::fs::write(&path, text).unwrap();
+
+        let target = memory_storage().await;
+        target.add_item(&item("kept", "text", 0)).await.unwrap();
+        assert!(import_history(&target, &path, false).await.is_err());
+        let ids: Vec<String> = target.get_history(10, 0).await.unwrap().into_iter().map(|i| i.id).collect();
+        assert_eq!(ids, vec!["kept"]);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+// Text transforms applied to clipboard items as they are pasted
+use pulldown_cmark::{html, Parser};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// The text as is, without any rich formatting
+    Plain,
+    /// Markdown rendered to HTML, with the Markdown as the plain-text fallback
+    MarkdownToHtml,
+    /// Leading and trailing whitespace removed
+    Trim,
+    /// Lines trimmed and joined with single spaces; blank lines dropped
+    SingleLine,
+    Uppercase,
+    Lowercase,
+}
+
+impl Transform {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "plain" => Some(Self::Plain),
+            "markdown_to_html" => Some(Self::MarkdownToHtml),
+            "trim" => Some(Self::Trim),
+            "single_line" => Some(Self::SingleLine),
+            "uppercase" => Some(Self::Uppercase),
+            "lowercase" => Some(Self::Lowercase),
+            _ => None,
+        }
+    }
+
+    pub fn apply(self, text: &str) -> Transformed {
+        match self {
+            Self::Plain => Transformed::Text(text.to_string()),
+            Self::MarkdownToHtml => Transformed::Html {
+                html: markdown_to_html(text),
+                alt_text: text.to_string(),
+            },
+            Self::Trim => Transformed::Text(text.trim().to_string()),
+            Self::SingleLine => Transformed::Text(
+                text.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            Self::Uppercase => Transformed::Text(text.to_uppercase()),
+            Self::Lowercase => Transformed::Text(text.to_lowercase()),
+        }
+    }
+}
+
+/// The result of a transform, as it goes on the clipboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transformed {
+    Text(String),
+    /// Rich text; apps that cannot take HTML paste `alt_text`
+    Html { html: String, alt_text: String },
+}
+
+fn markdown_to_html(markdown: &str) -> String {
+    let mut out = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut out, Parser::new(markdown));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(transform: Transform, input: &str) -> String {
+        match transform.apply(input) {
+            Transformed::Text(text) => text,
+            other => panic!("expected text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_names() {
+        assert_eq!(Transform::parse("markdown_to_html"), Some(Transform::MarkdownToHtml));
+        assert_eq!(Transform::parse("single_line"), Some(Transform::SingleLine));
+        assert_eq!(Transform::parse("Uppercase"), None);
+        assert_eq!(Transform::parse("rot13"), None);
+    }
+
+    #[test]
+    fn test_plain_keeps_text() {
+        assert_eq!(text(Transform::Plain, "  a **b**\n"), "  a **b**\n");
+    }
+
+    #[test]
+    fn test_trim() {
+        assert_eq!(text(Transform::Trim, "\n\t  hello world \r\n"), "hello world");
+    }
+
+    #[test]
+    fn test_single_line() {
+        assert_eq!(
+            text(Transform::SingleLine, "  first line\r\n\n   second\tline  \nthird\n"),
+            "first line second\tline third"
+        );
+        assert_eq!(text(Transform::SingleLine, "\n\n"), "");
+    }
+
+    #[test]
+    fn test_case() {
+        assert_eq!(text(Transform::Uppercase, "Straße ok"), "STRASSE OK");
+        assert_eq!(text(Transform::Lowercase, "HeLLo ÄÖ"), "hello äö");
+    }
+
+    #[test]
+    fn test_markdown_to_html() {
+        let input = "Some **bold** and a [link](https://example.com).";
+
+        assert_eq!(
+            Transform::MarkdownToHtml.apply(input),
+            Transformed::Html {
+                html: "<p>Some <strong>bold</strong> and a <a href=\"https://example.com\">link</a>.</p>\n"
+                    .to_string(),
+                alt_text: input.to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_markdown_to_html_blocks_and_escaping() {
+        let Transformed::Html { html, .. } = Transform::MarkdownToHtml.apply("# Title\n\n- one\n- *two*\n\n1 < 2")
+        else {
+            panic!("expected html");
+        };
+
+        assert_eq!(
+            html,
+            "<h1>Title</h1>\n<ul>\n<li>one</li>\n<li><em>two</em></li>\n</ul>\n<p>1 &lt; 2</p>\n"
+        );
+    }
+}
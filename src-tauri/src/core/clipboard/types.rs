@@ -73,6 +73,7 @@ impl ClipboardContent {
     pub fn data(&self) -> Option<Vec<u8>> {
         match self {
             Self::Image { data, .. } => Some(data.clone()),
+            Self::Html { html, .. } => Some(html.clone().into_bytes()),
             _ => None,
         }
     }
@@ -0,0 +1,75 @@
+// Image files as data URLs, for the "copy as data URL" result action
+use crate::app::error::{AppError, AppResult};
+use base64::Engine;
+use std::path::Path;
+
+/// Largest file copied as a data URL; base64 grows it by a third
+pub const MAX_DATA_URL_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// MIME type of an image file, from its extension (case-insensitive)
+pub fn image_mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    let mime = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" | "jfif" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "tif" | "tiff" => "image/tiff",
+        "avif" => "image/avif",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+/// Reads an image into a `data:<mime>;base64,...` URL. Files that are not
+/// images or are larger than `max_bytes` are refused.
+pub fn image_data_url(path: &Path, max_bytes: u64) -> AppResult<String> {
+    let mime = image_mime_type(path).ok_or_else(|| {
+        AppError::Clipboard(format!("{} is not an image file", path.display()))
+    })?;
+    let size = std::fs::metadata(path)?.len();
+    if size > max_bytes {
+        return Err(AppError::Clipboard(format!(
+            "{} is {:.1} MB; only images up to {} MB can be copied as a data URL",
+            path.display(),
+            size as f64 / (1024.0 * 1024.0),
+            max_bytes / (1024 * 1024)
+        )));
+    }
+    let data = std::fs::read(path)?;
+    Ok(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(data)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_mime_type_by_extension() {
+        assert_eq!(image_mime_type(Path::new("shot.png")), Some("image/png"));
+        assert_eq!(image_mime_type(Path::new("Photo.JPG")), Some("image/jpeg"));
+        assert_eq!(image_mime_type(Path::new("a/b/logo.svg")), Some("image/svg+xml"));
+        assert_eq!(image_mime_type(Path::new("anim.webp")), Some("image/webp"));
+        assert_eq!(image_mime_type(Path::new("notes.txt")), None);
+        assert_eq!(image_mime_type(Path::new("png")), None);
+    }
+
+    #[test]
+    fn test_image_data_url_and_size_cap() {
+        let path = std::env::temp_dir().join(format!("omnibox_data_url_{}.png", uuid::Uuid::new_v4()));
+        std::fs::write(&path, [0x89, b'P', b'N', b'G']).unwrap();
+
+        assert_eq!(image_data_url(&path, 1024).unwrap(), "data:image/png;base64,iVBORw==");
+        let err = image_data_url(&path, 2).unwrap_err();
+        assert!(err.to_string().contains("only images up to"));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,117 @@
+// Startup health checks, reported per check so failures are visible
+// outside the logs
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// Does not apply on this platform
+    Skipped,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckResult {
+    /// Short name shown in the diagnostics panel, e.g. "Database"
+    pub name: String,
+    pub status: CheckStatus,
+    /// What was found, or why the check failed
+    pub detail: String,
+}
+
+impl CheckResult {
+    /// Pass with `Ok`'s detail, fail with `Err`'s
+    pub fn from_outcome(name: &str, outcome: Result<String, String>) -> Self {
+        let (status, detail) = match outcome {
+            Ok(detail) => (CheckStatus::Pass, detail),
+            Err(detail) => (CheckStatus::Fail, detail),
+        };
+        Self {
+            name: name.to_string(),
+            status,
+            detail,
+        }
+    }
+
+    pub fn skipped(name: &str, detail: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Skipped,
+            detail: detail.to_string(),
+        }
+    }
+}
+
+/// Every check, in the order run, with the totals
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiagnosticsReport {
+    pub checks: Vec<CheckResult>,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// No check failed; skipped checks do not count against it
+    pub healthy: bool,
+    /// Unix timestamp of the run
+    pub ran_at: i64,
+}
+
+impl DiagnosticsReport {
+    pub fn new(checks: Vec<CheckResult>, ran_at: i64) -> Self {
+        let count = |status| checks.iter().filter(|check| check.status == status).count();
+        let (passed, failed, skipped) = (count(CheckStatus::Pass), count(CheckStatus::Fail), count(CheckStatus::Skipped));
+        Self {
+            checks,
+            passed,
+            failed,
+            skipped,
+            healthy: failed == 0,
+            ran_at,
+        }
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.checks.iter().filter(|check| check.status == CheckStatus::Fail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixed_outcomes() {
+        let report = DiagnosticsReport::new(
+            vec![
+                CheckResult::from_outcome("Database", Ok("reachable".to_string())),
+                CheckResult::from_outcome("Icon cache", Err("permission denied".to_string())),
+                CheckResult::skipped("Everything", "Windows only"),
+                CheckResult::from_outcome("Monitors", Ok("2 monitors".to_string())),
+            ],
+            1_700_000_000,
+        );
+        assert_eq!((report.passed, report.failed, report.skipped), (2, 1, 1));
+        assert!(!report.healthy);
+        let failures: Vec<&str> = report.failures().map(|check| check.name.as_str()).collect();
+        assert_eq!(failures, ["Icon cache"]);
+        assert_eq!(report.checks[1].detail, "permission denied");
+    }
+
+    #[test]
+    fn test_skipped_checks_stay_healthy() {
+        let report = DiagnosticsReport::new(
+            vec![
+                CheckResult::from_outcome("Config", Ok("using defaults".to_string())),
+                CheckResult::skipped("OCR", "Windows only"),
+            ],
+            0,
+        );
+        assert!(report.healthy);
+        assert_eq!((report.passed, report.failed, report.skipped), (1, 0, 1));
+
+        let empty = DiagnosticsReport::new(Vec::new(), 0);
+        assert!(empty.healthy);
+        assert_eq!(empty.failures().count(), 0);
+    }
+}
@@ -0,0 +1,124 @@
+//! Launching apps through ShellExecute, elevated ("Run as administrator")
+//! or as a plain shell open (shortcuts).
+//!
+//! Building the ShellExecute request and matching the always-elevate list are
+//! kept platform independent; the actual launch lives in `platform::windows`.
+
+/// ShellExecute verb that triggers the UAC prompt
+pub const RUNAS_VERB: &str = "runas";
+/// ShellExecute verb for a normal launch
+pub const OPEN_VERB: &str = "open";
+
+/// Arguments for a ShellExecute call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellExecuteRequest {
+    pub verb: String,
+    pub file: String,
+    pub parameters: Option<String>,
+    /// Working directory; the target's folder so relative resources resolve
+    pub directory: Option<String>,
+}
+
+/// Build the request that launches `path` elevated
+pub fn elevated_launch(path: &str) -> ShellExecuteRequest {
+    launch_request(RUNAS_VERB, path)
+}
+
+/// Build the request that opens `path` like a double-click in Explorer,
+/// e.g. to follow a `.lnk` shortcut
+pub fn shell_open(path: &str) -> ShellExecuteRequest {
+    launch_request(OPEN_VERB, path)
+}
+
+fn launch_request(verb: &str, path: &str) -> ShellExecuteRequest {
+    // Split on either separator so Windows paths parse the same everywhere
+    let directory = path
+        .rfind(['\\', '/'])
+        .map(|i| path[..i].to_string())
+        .filter(|p| !p.is_empty());
+
+    ShellExecuteRequest {
+        verb: verb.to_string(),
+        file: path.to_string(),
+        parameters: None,
+        directory,
+    }
+}
+
+/// Normalize a Windows path for comparison: backslashes, lowercase, no
+/// trailing separator
+fn normalize(path: &str) -> String {
+    path.trim()
+        .replace('/', "\\")
+        .trim_end_matches('\\')
+        .to_lowercase()
+}
+
+/// Whether `path` is on the always-elevate list.
+///
+/// Entries are full paths, or bare file names (e.g. `regedit.exe`) matching
+/// that file in any folder. Matching is case-insensitive and ignores
+/// separator style.
+pub fn should_always_elevate(always_elevate: &[String], path: &str) -> bool {
+    let path = normalize(path);
+    if path.is_empty() {
+        return false;
+    }
+    let file_name = path.rsplit('\\').next().unwrap_or(&path);
+
+    always_elevate.iter().map(|entry| normalize(entry)).any(|entry| {
+        if entry.contains('\\') {
+            entry == path
+        } else {
+            !entry.is_empty() && entry == file_name
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elevated_launch_uses_runas_in_target_folder() {
+        let request = elevated_launch(r"C:\Program Files\Tool\tool.exe");
+
+        assert_eq!(request.verb, "runas");
+        assert_eq!(request.file, r"C:\Program Files\Tool\tool.exe");
+        assert_eq!(request.parameters, None);
+        assert_eq!(request.directory.as_deref(), Some(r"C:\Program Files\Tool"));
+    }
+
+    #[test]
+    fn test_shell_open_uses_open_verb() {
+        let request = shell_open(r"C:\Users\me\Desktop\Editor.lnk");
+
+        assert_eq!(request.verb, "open");
+        assert_eq!(request.directory.as_deref(), Some(r"C:\Users\me\Desktop"));
+    }
+
+    #[test]
+    fn test_elevated_launch_without_folder() {
+        assert_eq!(elevated_launch("cmd.exe").directory, None);
+    }
+
+    #[test]
+    fn test_always_elevate_matches_full_paths() {
+        let list = vec![r"C:\Tools\Admin Tool.exe".to_string()];
+
+        assert!(should_always_elevate(&list, r"c:\tools\admin tool.exe"));
+        assert!(should_always_elevate(&list, "C:/Tools/Admin Tool.exe"));
+        assert!(!should_always_elevate(&list, r"C:\Other\Admin Tool.exe"));
+        assert!(!should_always_elevate(&list, r"C:\Tools\Admin Tool.exe.bak"));
+    }
+
+    #[test]
+    fn test_always_elevate_matches_file_names() {
+        let list = vec!["regedit.exe".to_string(), String::new()];
+
+        assert!(should_always_elevate(&list, r"C:\Windows\regedit.exe"));
+        assert!(should_always_elevate(&list, "RegEdit.EXE"));
+        assert!(!should_always_elevate(&list, r"C:\Windows\notepad.exe"));
+        assert!(!should_always_elevate(&list, ""));
+    }
+}
@@ -0,0 +1,367 @@
+// File-content (full-text) index over text-like files
+use super::filter::SearchFilter;
+use super::scanner::FileEntry;
+use super::trigram::TrigramIndex;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Characters of context kept on each side of the first match
+const SNIPPET_CONTEXT: usize = 40;
+
+#[derive(Debug, Clone)]
+pub struct ContentOptions {
+    /// Extensions of files to read (case-insensitive, without the dot)
+    pub extensions: Vec<String>,
+    /// Larger files are skipped
+    pub max_file_size: u64,
+    /// Additional restrictions on which files participate
+    pub filter: SearchFilter,
+}
+
+impl ContentOptions {
+    /// Whether a scanned file should be indexed by content
+    pub fn accepts(&self, entry: &FileEntry) -> bool {
+        self.accepts_file(&entry.path, entry.size, entry.modified)
+    }
+
+    fn accepts_file(&self, path: &Path, size: u64, modified: Option<SystemTime>) -> bool {
+        if size > self.max_file_size {
+            return false;
+        }
+        let allowed = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+            .unwrap_or(false);
+        if !allowed {
+            return false;
+        }
+
+        let modified = modified.map(DateTime::<Utc>::from).unwrap_or_else(Utc::now);
+        self.filter.matches(path, size, modified)
+    }
+}
+
+/// A file whose content matched every query term
+#[derive(Debug, Clone, Serialize)]
+pub struct ContentMatch {
+    pub path: PathBuf,
+    /// Text around the first match, on one line
+    pub snippet: String,
+    pub score: f64,
+}
+
+/// Text extracted from a file, with the size and modification time of the
+/// file when it was read
+#[derive(Debug, Clone)]
+pub struct IndexedText {
+    text: Arc<str>,
+    stamp: Option<(SystemTime, u64)>,
+}
+
+impl IndexedText {
+    /// Read `path` as text, or `None` if it is too large or looks binary
+    pub fn read(path: &Path, max_file_size: u64) -> Option<Self> {
+        let stamp = file_stamp(path);
+        let text = read_text(path, max_file_size)?;
+        Some(Self {
+            text: text.into(),
+            stamp,
+        })
+    }
+
+    /// Compare with the file at `path` now, reading it again if it changed
+    pub fn current(&self, path: &Path, max_file_size: u64) -> Freshness {
+        if self.stamp.is_some() && file_stamp(path) == self.stamp {
+            return Freshness::Unchanged;
+        }
+        match Self::read(path, max_file_size) {
+            Some(text) => Freshness::Changed(text),
+            None => Freshness::Unreadable,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// What [`IndexedText::current`] found on disk
+pub enum Freshness {
+    Unchanged,
+    Changed(IndexedText),
+    /// Gone, grown past the limit or binary now
+    Unreadable,
+}
+
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// A file that may contain the query, with its text from indexing time
+pub struct ContentCandidate {
+    pub path: PathBuf,
+    pub score: f64,
+    pub text: IndexedText,
+}
+
+/// Trigram index over file contents, kept apart from the file-name index.
+/// The extracted text is kept too, so searches only re-read files whose
+/// modification time changed.
+#[derive(Default)]
+pub struct ContentIndex {
+    trigram: TrigramIndex,
+    paths: HashMap<usize, PathBuf>,
+    ids: HashMap<PathBuf, usize>,
+    texts: HashMap<usize, IndexedText>,
+    next_id: usize,
+    /// Directories and options the index was built from; changed files
+    /// elsewhere are not added
+    directories: Vec<PathBuf>,
+    options: Option<ContentOptions>,
+}
+
+impl ContentIndex {
+    pub fn new(directories: Vec<PathBuf>, options: ContentOptions) -> Self {
+        Self {
+            directories,
+            options: Some(options),
+            ..Default::default()
+        }
+    }
+
+    /// Limit the files were read with, reused when re-reading them
+    pub fn max_file_size(&self) -> u64 {
+        self.options.as_ref().map_or(0, |options| options.max_file_size)
+    }
+
+    /// Index `path` with its text, replacing its entry if it has one
+    pub fn add(&mut self, path: PathBuf, text: IndexedText) {
+        self.remove(&path);
+        let id = self.next_id;
+        self.next_id += 1;
+        self.trigram.add_file(text.text(), id);
+        self.texts.insert(id, text);
+        self.ids.insert(path.clone(), id);
+        self.paths.insert(id, path);
+    }
+
+    /// Drop `path`. Returns false if it was not indexed.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let Some(id) = self.ids.remove(path) else {
+            return false;
+        };
+        self.trigram.remove_file(id);
+        self.texts.remove(&id);
+        self.paths.remove(&id);
+        true
+    }
+
+    /// Drop `path` and any indexed files under it
+    pub fn remove_under(&mut self, path: &Path) {
+        let gone: Vec<PathBuf> = self.ids.keys().filter(|p| p.starts_with(path)).cloned().collect();
+        for removed in gone {
+            self.remove(&removed);
+        }
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.ids.contains_key(path)
+    }
+
+    /// The full-text directory holding `path` when a file with this size
+    /// and modification time belongs in the index
+    pub fn directory_for(&self, path: &Path, size: u64, modified: Option<SystemTime>) -> Option<&Path> {
+        let options = self.options.as_ref()?;
+        let dir = self.directories.iter().find(|dir| path.starts_with(dir))?;
+        options.accepts_file(path, size, modified).then_some(dir.as_path())
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Files that may contain `query`, best trigram overlap first.
+    /// Candidates still need [`match_text`] to confirm the terms.
+    pub fn candidates(&self, query: &str) -> Vec<ContentCandidate> {
+        self.trigram
+            .search(query)
+            .into_iter()
+            .filter_map(|(id, score)| {
+                Some(ContentCandidate {
+                    path: self.paths.get(&id)?.clone(),
+                    score,
+                    text: self.texts.get(&id)?.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Lowercased, whitespace-separated query terms
+pub fn query_terms(query: &str) -> Vec<String> {
+    query.split_whitespace().map(|term| term.to_lowercase()).collect()
+}
+
+/// Read a file as text, or `None` if it is too large or looks binary
+pub fn read_text(path: &Path, max_file_size: u64) -> Option<String> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut bytes = Vec::new();
+    // Read one byte past the limit to notice files that grew since scanning
+    file.take(max_file_size + 1).read_to_end(&mut bytes).ok()?;
+    if bytes.len() as u64 > max_file_size || bytes.contains(&0) {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Snippet around the first term if `text` contains every term
+pub fn match_text(text: &str, terms: &[String]) -> Option<String> {
+    if terms.is_empty() {
+        return None;
+    }
+    let lower = text.to_lowercase();
+    if !terms.iter().all(|term| lower.contains(term.as_str())) {
+        return None;
+    }
+
+    // Lowercasing can shift byte offsets; cut from the lowercased text then
+    let aligned = lower.len() == text.len()
+        && text.char_indices().zip(lower.char_indices()).all(|(a, b)| a.0 == b.0);
+    let source = if aligned { text } else { lower.as_str() };
+    let start = lower.find(terms[0].as_str())?;
+    let end = start + terms[0].len();
+
+    let before: Vec<(usize, char)> = source[..start].char_indices().collect();
+    let from = before
+        .len()
+        .checked_sub(SNIPPET_CONTEXT)
+        .map(|i| before[i].0)
+        .unwrap_or(0);
+    let to = source[end..]
+        .char_indices()
+        .nth(SNIPPET_CONTEXT * 2)
+        .map(|(i, _)| end + i)
+        .unwrap_or(source.len());
+
+    let mut snippet = source[from..to].split_whitespace().collect::<Vec<_>>().join(" ");
+    if from > 0 {
+        snippet.insert_str(0, "…");
+    }
+    if to < source.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_text_requires_every_term() {
+        let text = "Meeting notes\nThe quarterly Budget was approved.";
+        let terms = query_terms("budget approved");
+
+        let snippet = match_text(text, &terms).unwrap();
+        assert!(snippet.contains("quarterly Budget was approved."));
+        assert!(!snippet.contains('\n'));
+        assert!(match_text(text, &query_terms("budget rejected")).is_none());
+    }
+
+    #[test]
+    fn test_snippet_is_trimmed_around_match() {
+        let text = format!("{} needle {}", "a".repeat(200), "b".repeat(200));
+        let snippet = match_text(&text, &query_terms("needle")).unwrap();
+
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.chars().count() <= SNIPPET_CONTEXT * 3 + "needle".len() + 2);
+    }
+
+    #[tokio::test]
+    async fn test_term_inside_txt_file_surfaces_it() {
+        use crate::core::indexer::{Indexer, ScanConfig};
+
+        let dir = std::env::temp_dir().join(format!("omnibox_fulltext_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("node_modules")).unwrap();
+        std::fs::write(dir.join("notes.txt"), "Agenda: review the quarterly budget").unwrap();
+        std::fs::write(dir.join("other.txt"), "Nothing to see here").unwrap();
+        std::fs::write(dir.join("photo.png"), "quarterly budget").unwrap();
+        std::fs::write(dir.join("node_modules").join("dep.txt"), "quarterly budget").unwrap();
+        std::fs::write(dir.join("huge.txt"), format!("quarterly budget {}", "x".repeat(2048))).unwrap();
+
+        let indexer = Indexer::new(ScanConfig::default());
+        let options = ContentOptions {
+            extensions: vec!["txt".to_string()],
+            max_file_size: 1024,
+            filter: SearchFilter::default(),
+        };
+        assert_eq!(indexer.index_contents(std::slice::from_ref(&dir), &options).await, 2);
+
+        let matches = indexer.search_contents("Quarterly budget", 10).await;
+        let paths: Vec<&PathBuf> = matches.iter().map(|m| &m.path).collect();
+        assert_eq!(paths, vec![&dir.join("notes.txt")]);
+        assert!(matches[0].snippet.contains("review the quarterly budget"));
+
+        assert!(indexer.search_contents("budget deficit", 10).await.is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_files_are_not_read_again() {
+        use crate::core::indexer::{Indexer, ScanConfig};
+
+        let dir = std::env::temp_dir().join(format!("omnibox_content_cache_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("notes.txt");
+        std::fs::write(&file, "alpha budget").unwrap();
+
+        let indexer = Indexer::new(ScanConfig::default());
+        let options = ContentOptions {
+            extensions: vec!["txt".to_string()],
+            max_file_size: 1024,
+            filter: SearchFilter::default(),
+        };
+        indexer.index_contents(std::slice::from_ref(&dir), &options).await;
+
+        // Same size and modification time: the indexed text is used
+        let modified = std::fs::metadata(&file).unwrap().modified().unwrap();
+        std::fs::write(&file, "gamma budget").unwrap();
+        std::fs::File::options().write(true).open(&file).unwrap().set_modified(modified).unwrap();
+        let matches = indexer.search_contents("alpha budget", 10).await;
+        assert_eq!(matches.len(), 1);
+
+        // A newer modification time makes the search read the file again
+        let later = modified + std::time::Duration::from_secs(5);
+        std::fs::File::options().write(true).open(&file).unwrap().set_modified(later).unwrap();
+        assert!(indexer.search_contents("alpha budget", 10).await.is_empty());
+        assert!(indexer.search_contents("budget", 10).await[0].snippet.contains("gamma"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_text_skips_binary_and_large_files() {
+        let dir = std::env::temp_dir().join(format!("omnibox_content_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("text.txt"), "plain text").unwrap();
+        std::fs::write(dir.join("binary.txt"), b"abc\0def").unwrap();
+
+        assert_eq!(read_text(&dir.join("text.txt"), 1024).as_deref(), Some("plain text"));
+        assert!(read_text(&dir.join("text.txt"), 4).is_none());
+        assert!(read_text(&dir.join("binary.txt"), 1024).is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
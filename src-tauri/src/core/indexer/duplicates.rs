@@ -0,0 +1,221 @@
+// Duplicate file detection over indexed files
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::task::JoinSet;
+
+#[derive(Debug, Clone)]
+pub struct DuplicateOptions {
+    /// Files smaller than this are ignored (empty files are never duplicates)
+    pub min_size: u64,
+    /// Stop hashing once this many bytes have been read
+    pub max_hashed_bytes: u64,
+    /// Maximum number of files hashed at the same time
+    pub concurrency: usize,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self {
+            min_size: 1,
+            max_hashed_bytes: 8 * 1024 * 1024 * 1024,
+            concurrency: 4,
+        }
+    }
+}
+
+/// Files with identical size and content
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DuplicateGroup {
+    pub size: u64,
+    pub hash: String,
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateReport {
+    /// Largest files first
+    pub groups: Vec<DuplicateGroup>,
+    /// False if the scan was cancelled or hit the work cap
+    pub complete: bool,
+}
+
+/// MD5 of a file's contents, read in chunks
+pub fn hash_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut context = md5::Context::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        context.consume(&buf[..n]);
+    }
+    Ok(format!("{:x}", context.compute()))
+}
+
+/// Find duplicates among `(path, size)` candidates.
+///
+/// Files are grouped by size first; only files sharing a size are hashed.
+/// Hashing runs on the blocking pool with at most `concurrency` files in
+/// flight, and stops early when `cancel` is set or the byte budget runs out.
+pub async fn find_duplicates(
+    candidates: Vec<(PathBuf, u64)>,
+    options: &DuplicateOptions,
+    cancel: &AtomicBool,
+) -> DuplicateReport {
+    let mut by_size: BTreeMap<u64, Vec<PathBuf>> = BTreeMap::new();
+    for (path, size) in candidates {
+        if size >= options.min_size.max(1) {
+            by_size.entry(size).or_default().push(path);
+        }
+    }
+
+    // Largest sizes first, so the budget goes where the savings are
+    let mut jobs = Vec::new();
+    let mut budget = options.max_hashed_bytes;
+    let mut complete = true;
+    for (size, paths) in by_size.into_iter().rev() {
+        if paths.len() < 2 {
+            continue;
+        }
+        let group_bytes = size.saturating_mul(paths.len() as u64);
+        if group_bytes > budget {
+            complete = false;
+            continue;
+        }
+        budget -= group_bytes;
+        jobs.extend(paths.into_iter().map(|path| (path, size)));
+    }
+
+    let concurrency = options.concurrency.max(1);
+    let mut jobs = jobs.into_iter();
+    let mut in_flight = JoinSet::new();
+    let mut by_hash: HashMap<(u64, String), Vec<PathBuf>> = HashMap::new();
+
+    loop {
+        if cancel.load(Ordering::SeqCst) {
+            in_flight.abort_all();
+            complete = false;
+            break;
+        }
+
+        while in_flight.len() < concurrency {
+            let Some((path, size)) = jobs.next() else {
+                break;
+            };
+            in_flight.spawn_blocking(move || {
+                let hash = hash_file(&path);
+                (path, size, hash)
+            });
+        }
+
+        let Some(joined) = in_flight.join_next().await else {
+            break;
+        };
+        match joined {
+            Ok((path, size, Ok(hash))) => by_hash.entry((size, hash)).or_default().push(path),
+            Ok((path, _, Err(e))) => tracing::debug!("Failed to hash {:?}: {}", path, e),
+            Err(e) => tracing::warn!("Duplicate hashing task failed: {}", e),
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((size, hash), mut paths)| {
+            paths.sort();
+            DuplicateGroup { size, hash, paths }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.paths.cmp(&b.paths)));
+
+    DuplicateReport { groups, complete }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Temp tree with two duplicate pairs, a same-size unique file and an
+    /// unrelated file
+    fn temp_tree() -> (PathBuf, Vec<(PathBuf, u64)>) {
+        let root = std::env::temp_dir().join(format!("omnibox_dupes_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+
+        let files = [
+            ("a.txt", "hello world"),
+            ("sub/a-copy.txt", "hello world"),
+            ("same-size.txt", "hello there"),
+            ("b.bin", "0123456789abcdef"),
+            ("sub/b.bin", "0123456789abcdef"),
+            ("unique.txt", "nothing like it"),
+            ("empty-1", ""),
+            ("empty-2", ""),
+        ];
+        let candidates = files
+            .iter()
+            .map(|(name, content)| {
+                let path = root.join(name);
+                std::fs::write(&path, content).unwrap();
+                (path, content.len() as u64)
+            })
+            .collect();
+        (root, candidates)
+    }
+
+    #[tokio::test]
+    async fn test_clusters_identical_files() {
+        let (root, candidates) = temp_tree();
+        let report = find_duplicates(candidates, &DuplicateOptions::default(), &AtomicBool::new(false)).await;
+
+        assert!(report.complete);
+        let groups: Vec<Vec<PathBuf>> = report.groups.iter().map(|g| g.paths.clone()).collect();
+        assert_eq!(
+            groups,
+            vec![
+                vec![root.join("b.bin"), root.join("sub/b.bin")],
+                vec![root.join("a.txt"), root.join("sub/a-copy.txt")],
+            ]
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unique_files_are_not_grouped() {
+        let (root, candidates) = temp_tree();
+        let report = find_duplicates(candidates, &DuplicateOptions::default(), &AtomicBool::new(false)).await;
+
+        let grouped: Vec<&PathBuf> = report.groups.iter().flat_map(|g| &g.paths).collect();
+        for name in ["same-size.txt", "unique.txt", "empty-1", "empty-2"] {
+            assert!(!grouped.contains(&&root.join(name)), "{} was grouped", name);
+        }
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_budget_and_cancellation_mark_incomplete() {
+        let (root, candidates) = temp_tree();
+
+        let options = DuplicateOptions {
+            max_hashed_bytes: 40,
+            ..Default::default()
+        };
+        let report = find_duplicates(candidates.clone(), &options, &AtomicBool::new(false)).await;
+        assert!(!report.complete);
+        // Only the 16-byte pair fits in the budget
+        assert_eq!(report.groups.len(), 1);
+        assert_eq!(report.groups[0].size, 16);
+
+        let report = find_duplicates(candidates, &DuplicateOptions::default(), &AtomicBool::new(true)).await;
+        assert!(!report.complete);
+        assert!(report.groups.is_empty());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -9,6 +9,15 @@ pub struct SearchFilter {
     pub extensions: Option<Vec<String>>,
     /// Filter by path patterns (glob-like patterns)
     pub path_patterns: Option<Vec<String>>,
+    /// Reject paths containing any of these (case-insensitive)
+    #[serde(default)]
+    pub exclude_paths: Option<Vec<String>>,
+    /// Keep only paths matching one of these globs (see `glob_match`)
+    #[serde(default)]
+    pub include_globs: Option<Vec<String>>,
+    /// Reject paths matching any of these globs
+    #[serde(default)]
+    pub exclude_globs: Option<Vec<String>>,
     /// Minimum file size in bytes
     pub min_size: Option<u64>,
     /// Maximum file size in bytes
@@ -24,6 +33,9 @@ impl SearchFilter {
         Self {
             extensions: None,
             path_patterns: None,
+            exclude_paths: None,
+            include_globs: None,
+            exclude_globs: None,
             min_size: None,
             max_size: None,
             modified_after: None,
@@ -33,6 +45,41 @@ impl SearchFilter {
 
     /// Check if a file matches the filter criteria
     pub fn matches(&self, path: &Path, size: u64, modified: DateTime<Utc>) -> bool {
+        if !self.matches_path(path) {
+            return false;
+        }
+
+        // Check size filters
+        if let Some(min) = self.min_size {
+            if size < min {
+                return false;
+            }
+        }
+
+        if let Some(max) = self.max_size {
+            if size > max {
+                return false;
+            }
+        }
+
+        // Check modified time filters
+        if let Some(after) = self.modified_after {
+            if modified < after {
+                return false;
+            }
+        }
+
+        if let Some(before) = self.modified_before {
+            if modified > before {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Check only the criteria that depend on the path itself
+    pub fn matches_path(&self, path: &Path) -> bool {
         // Check extension filter
         if let Some(ref exts) = self.extensions {
             if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
@@ -57,28 +104,26 @@ impl SearchFilter {
             }
         }
 
-        // Check size filters
-        if let Some(min) = self.min_size {
-            if size < min {
+        // Check exclusions
+        if let Some(ref excluded) = self.exclude_paths {
+            let path_str = path.to_string_lossy().to_lowercase();
+            if excluded
+                .iter()
+                .any(|pattern| !pattern.is_empty() && path_str.contains(&pattern.to_lowercase()))
+            {
                 return false;
             }
         }
 
-        if let Some(max) = self.max_size {
-            if size > max {
+        // Check globs
+        let path_str = path.to_string_lossy();
+        if let Some(ref globs) = self.include_globs {
+            if !globs.is_empty() && !globs.iter().any(|glob| glob_match(glob, &path_str)) {
                 return false;
             }
         }
-
-        // Check modified time filters
-        if let Some(after) = self.modified_after {
-            if modified < after {
-                return false;
-            }
-        }
-
-        if let Some(before) = self.modified_before {
-            if modified > before {
+        if let Some(ref globs) = self.exclude_globs {
+            if globs.iter().any(|glob| glob_match(glob, &path_str)) {
                 return false;
             }
         }
@@ -141,6 +186,50 @@ impl Default for SearchFilter {
     }
 }
 
+/// Match a path against a glob, ignoring case and treating `\` and `/` alike.
+///
+/// `*` and `?` stay within one path segment, `**` spans any number of
+/// segments. A pattern without wildcards names a folder and matches
+/// everything under it, so `D:\Projects` works as an include root.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<char> = normalize_glob_path(pattern).chars().collect();
+    let path: Vec<char> = normalize_glob_path(path).chars().collect();
+    if pattern.is_empty() {
+        return false;
+    }
+
+    if !pattern.iter().any(|c| matches!(c, '*' | '?')) {
+        let root = pattern.strip_suffix(&['/']).unwrap_or(&pattern);
+        return path.starts_with(root) && matches!(path.get(root.len()), None | Some('/'));
+    }
+    glob_match_chars(&pattern, &path)
+}
+
+fn normalize_glob_path(path: &str) -> String {
+    path.replace('\\', "/").to_lowercase()
+}
+
+fn glob_match_chars(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // "**/" may also match no folders at all
+            let skip_slash = match rest {
+                ['/', after @ ..] => (0..=path.len())
+                    .filter(|&i| i == 0 || path[i - 1] == '/')
+                    .any(|i| glob_match_chars(after, &path[i..])),
+                _ => false,
+            };
+            skip_slash || (0..=path.len()).any(|i| glob_match_chars(rest, &path[i..]))
+        }
+        ['*', rest @ ..] => (0..=path.len())
+            .take_while(|&i| i == 0 || path[i - 1] != '/')
+            .any(|i| glob_match_chars(rest, &path[i..])),
+        ['?', rest @ ..] => matches!(path.first(), Some(c) if *c != '/') && glob_match_chars(rest, &path[1..]),
+        [c, rest @ ..] => path.first() == Some(c) && glob_match_chars(rest, &path[1..]),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,6 +266,61 @@ mod tests {
         assert!(!filter.matches(&path, 2000, now));
     }
 
+    #[test]
+    fn test_exclude_paths() {
+        let filter = SearchFilter {
+            exclude_paths: Some(vec!["/Private/".to_string(), String::new()]),
+            ..Default::default()
+        };
+        let now = Utc::now();
+
+        assert!(filter.matches(&PathBuf::from("/home/me/notes.txt"), 100, now));
+        assert!(!filter.matches(&PathBuf::from("/home/me/private/diary.txt"), 100, now));
+    }
+
+    #[test]
+    fn test_glob_match_windows_paths() {
+        let path = r"D:\Projects\OmniBox\src\main.rs";
+        assert!(glob_match(r"D:\Projects", path));
+        assert!(glob_match(r"d:\projects\", path));
+        assert!(glob_match("d:/PROJECTS/**", path));
+        assert!(glob_match(r"D:\Projects\**\*.RS", path));
+        assert!(glob_match("**/src/*.rs", path));
+        assert!(glob_match("**/m?in.rs", path));
+        assert!(!glob_match(r"D:\Proj", path));
+        assert!(!glob_match(r"D:\Projects\*.rs", path));
+        assert!(!glob_match(r"C:\**", path));
+        assert!(!glob_match("", path));
+    }
+
+    #[test]
+    fn test_glob_match_double_star_edges() {
+        assert!(glob_match("**/*uninstall*", r"C:\Program Files\App\Uninstall.exe"));
+        assert!(glob_match("**/*uninstall*", "uninstall.exe"));
+        assert!(!glob_match("**/*uninstall*", r"C:\Uninstallers\notes.txt"));
+        assert!(glob_match("**/$recycle.bin/**", r"C:\$Recycle.Bin\S-1-5\a.txt"));
+    }
+
+    #[test]
+    fn test_include_and_exclude_globs() {
+        let filter = SearchFilter {
+            include_globs: Some(vec![r"D:\Projects".to_string(), r"E:\Music\**\*.mp3".to_string()]),
+            exclude_globs: Some(vec!["**/node_modules/**".to_string()]),
+            ..Default::default()
+        };
+
+        assert!(filter.matches_path(Path::new(r"D:\Projects\app\readme.md")));
+        assert!(filter.matches_path(Path::new(r"e:\music\live\song.MP3")));
+        assert!(!filter.matches_path(Path::new(r"E:\Music\cover.jpg")));
+        assert!(!filter.matches_path(Path::new(r"C:\Users\me\readme.md")));
+        assert!(!filter.matches_path(Path::new(r"D:\Projects\app\node_modules\x\index.js")));
+        assert!(SearchFilter {
+            include_globs: Some(vec![]),
+            ..Default::default()
+        }
+        .matches_path(Path::new(r"C:\any.txt")));
+    }
+
     #[test]
     fn test_preset_filters() {
         let doc_filter = SearchFilter::documents_only();
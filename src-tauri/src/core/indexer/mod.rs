@@ -5,19 +5,63 @@ mod scanner;
 mod ranker;
 mod watcher;
 mod filter;
+mod duplicates;
+mod content;
+mod snapshot;
 
 pub use scanner::{FileScanner, ScanConfig, FileEntry};
 pub use ranker::{Ranker, FileScore};
 pub use watcher::FileWatcher;
-pub use filter::SearchFilter;
+pub use filter::{glob_match, SearchFilter};
+pub use duplicates::{hash_file, DuplicateGroup, DuplicateOptions, DuplicateReport};
+pub use content::{ContentMatch, ContentOptions};
+pub use snapshot::{IndexSnapshot, SNAPSHOT_VERSION};
 
+use content::{ContentIndex, Freshness, IndexedText};
 use trie::Trie;
 use trigram::TrigramIndex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+#[derive(Debug, thiserror::Error)]
+pub enum IndexerError {
+    #[error("{} does not exist", .0.display())]
+    PathNotFound(PathBuf),
+
+    #[error("{} is already indexed", .0.display())]
+    AlreadyIndexed(PathBuf),
+
+    /// A new root lies inside `root`, which is indexed already
+    #[error("{} is already indexed as part of {}", .path.display(), .root.display())]
+    InsideRoot { path: PathBuf, root: PathBuf },
+
+    #[error("{} is not an indexed root", .0.display())]
+    NotARoot(PathBuf),
+
+    #[error("{} is not a directory", .0.display())]
+    NotADirectory(PathBuf),
+
+    #[error("{} cannot be resolved (symlink loop?): {source}", .path.display())]
+    Unresolvable { path: PathBuf, source: std::io::Error },
+
+    #[error("Cannot read metadata of {}: {source}", .path.display())]
+    Metadata { path: PathBuf, source: std::io::Error },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("File watcher error: {0}")]
+    Watch(String),
+
+    /// The saved index could not be read or written
+    #[error("Index snapshot error: {0}")]
+    Snapshot(String),
+}
+
 /// Check if a character is CJK (Chinese, Japanese, Korean)
 fn is_cjk(c: char) -> bool {
     matches!(c,
@@ -32,6 +76,127 @@ fn is_cjk(c: char) -> bool {
     )
 }
 
+/// Every trie key of a file: its full name, the words of the name and, for
+/// localized apps, the display name with its words and CJK characters.
+/// Insertion and removal both use this, so removing an entry leaves no keys
+/// behind.
+fn trie_keys(name: &str, display_name: Option<&str>) -> Vec<String> {
+    let mut keys = vec![name.to_lowercase()];
+    keys.extend(
+        name.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase),
+    );
+    if let Some(display) = display_name {
+        keys.push(display.to_lowercase());
+        keys.extend(
+            display
+                .split(|c: char| !c.is_alphanumeric() && !is_cjk(c))
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase),
+        );
+        keys.extend(display.chars().filter(|&c| is_cjk(c)).map(String::from));
+    }
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+fn index_entry(trie: &mut Trie, trigram: &mut TrigramIndex, entry: &FileEntry) {
+    for key in trie_keys(&entry.name, entry.display_name.as_deref()) {
+        trie.insert(&key, entry.id);
+    }
+    if let Some(display) = &entry.display_name {
+        trigram.add_file(display, entry.id);
+    }
+    trigram.add_file(&entry.name, entry.id);
+}
+
+fn unindex_entry(trie: &mut Trie, trigram: &mut TrigramIndex, entry: &FileEntry) {
+    for key in trie_keys(&entry.name, entry.display_name.as_deref()) {
+        trie.remove(&key, entry.id);
+    }
+    trigram.remove_file(entry.id);
+}
+
+/// What [`Indexer::reconcile`] found on disk
+#[derive(Default)]
+struct DiskChanges {
+    removed: Vec<PathBuf>,
+    /// Known files with their current size and mtime
+    updated: Vec<(PathBuf, u64, Option<SystemTime>)>,
+    /// New files; ids are assigned when they are indexed
+    added: Vec<FileEntry>,
+    /// New directories, to be scanned whole
+    new_dirs: Vec<PathBuf>,
+}
+
+/// Compare the `known` files with the directories modified since `saved`.
+/// Known files outside the roots (added one by one) are only checked for
+/// existence.
+fn changes_since(scanner: &FileScanner, roots: &[PathBuf], known: Vec<PathBuf>, saved: SystemTime) -> DiskChanges {
+    let mut changes = DiskChanges::default();
+    let in_roots = |path: &Path| roots.iter().any(|root| path.starts_with(root));
+
+    // Directories the index knows: the roots and every parent up to them
+    let mut dirs: HashSet<PathBuf> = roots.iter().cloned().collect();
+    let mut files_by_dir: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    for path in known {
+        if !in_roots(&path) {
+            if !path.exists() {
+                changes.removed.push(path);
+            }
+            continue;
+        }
+        let Some(parent) = path.parent().map(Path::to_path_buf) else {
+            continue;
+        };
+        let mut dir = Some(parent.as_path());
+        while let Some(current) = dir {
+            if !dirs.insert(current.to_path_buf()) {
+                break;
+            }
+            dir = current.parent();
+        }
+        files_by_dir.entry(parent).or_default().push(path);
+    }
+
+    for dir in &dirs {
+        let modified = std::fs::metadata(dir).and_then(|m| m.modified()).ok();
+        // Equal times count as changed: mtimes can be coarser than the save
+        if modified.is_some_and(|modified| modified < saved) {
+            continue;
+        }
+        let mut missing: HashSet<PathBuf> = files_by_dir.remove(dir).unwrap_or_default().into_iter().collect();
+        for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if !dirs.contains(&path) && !scanner.excludes(&path, true) {
+                    changes.new_dirs.push(path);
+                }
+            } else if metadata.is_file() {
+                if missing.remove(&path) {
+                    changes.updated.push((path, metadata.len(), metadata.modified().ok()));
+                } else if !scanner.excludes(&path, false) {
+                    changes.added.push(FileEntry {
+                        id: 0,
+                        name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                        display_name: None,
+                        size: metadata.len(),
+                        modified: metadata.modified().ok(),
+                        path,
+                    });
+                }
+            }
+        }
+        changes.removed.extend(missing);
+    }
+    changes
+}
+
 /// Main file indexer that combines Trie and Trigram indexing
 pub struct Indexer {
     /// Trie for prefix matching
@@ -42,14 +207,20 @@ pub struct Indexer {
     files: Arc<RwLock<HashMap<usize, FileEntry>>>,
     /// Path to ID mapping for quick lookup
     path_to_id: Arc<RwLock<HashMap<PathBuf, usize>>>,
-    /// File scanner
-    scanner: FileScanner,
+    /// File scanner; its config holds the indexed roots
+    scanner: Arc<RwLock<FileScanner>>,
     /// Ranking algorithm
     ranker: Arc<RwLock<Ranker>>,
     /// File watcher for incremental updates
     watcher: Arc<RwLock<Option<FileWatcher>>>,
     /// Next available ID
     next_id: Arc<RwLock<usize>>,
+    /// Bumped on every change to the indexed files
+    generation: Arc<AtomicU64>,
+    /// Unix time (seconds) of the last directory scan, 0 before the first
+    last_refresh: AtomicI64,
+    /// File contents of the full-text directories
+    content: Arc<RwLock<ContentIndex>>,
 }
 
 impl Indexer {
@@ -59,62 +230,64 @@ impl Indexer {
             trigram: Arc::new(RwLock::new(TrigramIndex::new())),
             files: Arc::new(RwLock::new(HashMap::new())),
             path_to_id: Arc::new(RwLock::new(HashMap::new())),
-            scanner: FileScanner::new(config),
+            scanner: Arc::new(RwLock::new(FileScanner::new(config))),
             ranker: Arc::new(RwLock::new(Ranker::new())),
             watcher: Arc::new(RwLock::new(None)),
             next_id: Arc::new(RwLock::new(1)),
+            generation: Arc::new(AtomicU64::new(0)),
+            last_refresh: AtomicI64::new(0),
+            content: Arc::new(RwLock::new(ContentIndex::default())),
         }
     }
 
-    /// Index a directory
-    pub async fn index_directory(&self, path: &Path) -> Result<usize, String> {
-        let entries = self.scanner.scan_directory(path).await;
-        let count = entries.len();
+    /// Index a directory. Returns the number of newly indexed files; files
+    /// that are already indexed keep their entry.
+    pub async fn index_directory(&self, path: &Path) -> Result<usize, IndexerError> {
+        let scanner = self.scanner.read().await.clone();
+        let entries = scanner.scan_directory(path).await;
+        let mut count = 0;
 
         let mut trie = self.trie.write().await;
         let mut trigram = self.trigram.write().await;
         let mut files = self.files.write().await;
         let mut path_to_id = self.path_to_id.write().await;
+        let mut next_id = self.next_id.write().await;
 
-        for entry in entries {
-            let file_id = entry.id;
-            let file_name = entry.name.clone();
-            let file_path = entry.path.clone();
-
-            // Add to trie (word-by-word)
-            for word in file_name.split(|c: char| !c.is_alphanumeric()) {
-                if !word.is_empty() {
-                    trie.insert(word, file_id);
-                }
+        for mut entry in entries {
+            if path_to_id.contains_key(&entry.path) {
+                continue;
             }
+            // Scanner IDs restart at 0 for every scan
+            entry.id = *next_id;
+            *next_id += 1;
+            count += 1;
 
-            // Add to trigram index
-            trigram.add_file(&file_name, file_id);
-
-            // Store file entry and path mapping
-            path_to_id.insert(file_path, file_id);
-            files.insert(file_id, entry);
+            index_entry(&mut trie, &mut trigram, &entry);
+            path_to_id.insert(entry.path.clone(), entry.id);
+            files.insert(entry.id, entry);
         }
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.last_refresh.store(chrono::Utc::now().timestamp(), Ordering::SeqCst);
 
         Ok(count)
     }
 
     /// Add a single file to the index
-    pub async fn add_file(&self, path: &Path) -> Result<usize, String> {
+    pub async fn add_file(&self, path: &Path) -> Result<usize, IndexerError> {
         self.add_file_with_display_name(path, None).await
     }
     
     /// Add a single file to the index with an optional display name
-    pub async fn add_file_with_display_name(&self, path: &Path, display_name: Option<String>) -> Result<usize, String> {
+    pub async fn add_file_with_display_name(&self, path: &Path, display_name: Option<String>) -> Result<usize, IndexerError> {
         if !path.exists() {
-            return Err("Path does not exist".to_string());
+            return Err(IndexerError::PathNotFound(path.to_path_buf()));
         }
 
         // Check if already indexed
         {
             let path_to_id = self.path_to_id.read().await;
             if path_to_id.contains_key(path) {
-                return Err("File already indexed".to_string());
+                return Err(IndexerError::AlreadyIndexed(path.to_path_buf()));
             }
         }
 
@@ -138,7 +311,7 @@ impl Indexer {
         };
 
         let metadata = tokio::fs::metadata(path).await
-            .map_err(|e| e.to_string())?;
+            .map_err(|source| IndexerError::Metadata { path: path.to_path_buf(), source })?;
 
         let entry = FileEntry {
             id: file_id,
@@ -155,56 +328,19 @@ impl Indexer {
         let mut files = self.files.write().await;
         let mut path_to_id = self.path_to_id.write().await;
 
-        // Add to trie - index both full name and individual words
-        // For apps like "Google Chrome", we want to match "goo", "chr", "chrome", etc.
-        let name_lower = file_name.to_lowercase();
-        
-        // Add full name
-        trie.insert(&name_lower, file_id);
-        
-        // Add individual words from file name
-        for word in file_name.split(|c: char| !c.is_alphanumeric()) {
-            if !word.is_empty() && word.len() >= 2 {
-                trie.insert(&word.to_lowercase(), file_id);
-            }
-        }
-        
-        // Also index the display name (for Chinese/localized names)
-        if let Some(ref disp_name) = display_name {
-            let disp_lower = disp_name.to_lowercase();
-            trie.insert(&disp_lower, file_id);
-            
-            // Add individual words/characters from display name
-            for word in disp_name.split(|c: char| !c.is_alphanumeric() && !is_cjk(c)) {
-                if !word.is_empty() {
-                    trie.insert(&word.to_lowercase(), file_id);
-                }
-            }
-            
-            // For CJK text, index each character
-            for ch in disp_name.chars() {
-                if is_cjk(ch) {
-                    trie.insert(&ch.to_string(), file_id);
-                }
-            }
-            
-            // Add display name to trigram index
-            trigram.add_file(disp_name, file_id);
-        }
-
-        // Add to trigram index
-        trigram.add_file(&file_name, file_id);
+        index_entry(&mut trie, &mut trigram, &entry);
 
         // Store entry
         path_to_id.insert(path.to_path_buf(), file_id);
         files.insert(file_id, entry);
+        self.generation.fetch_add(1, Ordering::SeqCst);
 
         tracing::debug!("Added to index: {:?} as '{}' (display: {:?})", path, file_name, display_name);
         Ok(file_id)
     }
 
     /// Remove a file from the index
-    pub async fn remove_file(&self, path: &Path) -> Result<(), String> {
+    pub async fn remove_file(&self, path: &Path) -> Result<(), IndexerError> {
         let file_id = {
             let path_to_id = self.path_to_id.read().await;
             match path_to_id.get(path) {
@@ -218,31 +354,18 @@ impl Indexer {
         let mut files = self.files.write().await;
         let mut path_to_id = self.path_to_id.write().await;
 
-        // Get file name for removal from trie
-        if let Some(entry) = files.get(&file_id) {
-            let file_name = entry.name.clone();
-            
-            // Remove from trie
-            for word in file_name.split(|c: char| !c.is_alphanumeric()) {
-                if !word.is_empty() {
-                    trie.remove(word, file_id);
-                }
-            }
-
-            // Remove from trigram index
-            trigram.remove_file(file_id);
+        if let Some(entry) = files.remove(&file_id) {
+            unindex_entry(&mut trie, &mut trigram, &entry);
         }
-
-        // Remove from storage
         path_to_id.remove(path);
-        files.remove(&file_id);
+        self.generation.fetch_add(1, Ordering::SeqCst);
 
         tracing::debug!("Removed file from index: {:?}", path);
         Ok(())
     }
 
     /// Update a file in the index (re-index)
-    pub async fn update_file(&self, path: &Path) -> Result<(), String> {
+    pub async fn update_file(&self, path: &Path) -> Result<(), IndexerError> {
         self.remove_file(path).await?;
         if path.exists() {
             self.add_file(path).await?;
@@ -250,119 +373,102 @@ impl Indexer {
         Ok(())
     }
 
+    /// Directories registered with `add_root`
+    pub async fn roots(&self) -> Vec<PathBuf> {
+        self.scanner.read().await.config().roots.clone()
+    }
+
+    /// Register and index a directory root. Roots nested in the new one are
+    /// folded into it. With `watch`, the root is also watched for changes.
+    /// Returns the canonical root.
+    pub async fn add_root(&self, path: &Path, watch: bool) -> Result<PathBuf, IndexerError> {
+        let root = resolve_root(path)?;
+        let nested = {
+            let mut scanner = self.scanner.write().await;
+            let roots = &mut scanner.config_mut().roots;
+            if let Some(parent) = roots.iter().find(|r| root.starts_with(r)) {
+                return Err(IndexerError::InsideRoot { path: root.clone(), root: parent.clone() });
+            }
+            let nested: Vec<PathBuf> = roots.iter().filter(|r| r.starts_with(&root)).cloned().collect();
+            roots.retain(|r| !r.starts_with(&root));
+            roots.push(root.clone());
+            nested
+        };
+
+        let count = self.index_directory(&root).await?;
+        tracing::info!("Indexed root {:?}: {} new files", root, count);
+
+        if watch {
+            let mut watcher = self.watcher.write().await;
+            if let Some(watcher) = watcher.as_mut() {
+                for path in &nested {
+                    watcher.unwatch(path);
+                }
+                watcher.watch(root.clone()).map_err(|e| IndexerError::Watch(e.to_string()))?;
+            } else {
+                drop(watcher);
+                self.start_watching(vec![root.clone()]).await?;
+            }
+        }
+        Ok(root)
+    }
+
+    /// Unregister a root, drop its files from the index and stop watching it.
+    /// The watcher is shut down once no roots are left.
+    pub async fn remove_root(&self, path: &Path) -> Result<PathBuf, IndexerError> {
+        // The directory may be gone already, so also match the path as given
+        let resolved = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let (root, remaining) = {
+            let mut scanner = self.scanner.write().await;
+            let roots = &mut scanner.config_mut().roots;
+            let index = roots
+                .iter()
+                .position(|r| r == &resolved || r == path)
+                .ok_or_else(|| IndexerError::NotARoot(path.to_path_buf()))?;
+            (roots.remove(index), roots.clone())
+        };
+
+        let under_root: Vec<PathBuf> = {
+            let files = self.files.read().await;
+            files
+                .values()
+                .map(|entry| &entry.path)
+                .filter(|p| p.starts_with(&root) && !remaining.iter().any(|r| p.starts_with(r)))
+                .cloned()
+                .collect()
+        };
+        for file in &under_root {
+            self.remove_file(file).await?;
+        }
+        tracing::info!("Removed root {:?}: {} files", root, under_root.len());
+
+        let mut watcher = self.watcher.write().await;
+        if let Some(active) = watcher.as_mut() {
+            active.unwatch(&root);
+            if active.paths().is_empty() {
+                *watcher = None;
+                tracing::info!("File watcher stopped");
+            }
+        }
+        Ok(root)
+    }
+
     /// Start watching directories for changes
-    pub async fn start_watching(&self, paths: Vec<PathBuf>) -> Result<(), String> {
+    pub async fn start_watching(&self, paths: Vec<PathBuf>) -> Result<(), IndexerError> {
         let mut watcher = FileWatcher::new();
         
         for path in &paths {
             watcher.add_path(path.clone());
         }
 
-        // Clone Arcs for the callback
-        let trie = self.trie.clone();
-        let trigram = self.trigram.clone();
-        let files = self.files.clone();
-        let path_to_id = self.path_to_id.clone();
-        let next_id = self.next_id.clone();
-
-        watcher.start_watching(move |changed_path| {
-            let trie = trie.clone();
-            let trigram = trigram.clone();
-            let files = files.clone();
-            let path_to_id = path_to_id.clone();
-            let next_id = next_id.clone();
-
-            // Spawn async task to handle the change
-            tokio::spawn(async move {
-                if changed_path.exists() {
-                    // File was created or modified
-                    let file_id = {
-                        let path_to_id_read = path_to_id.read().await;
-                        path_to_id_read.get(&changed_path).copied()
-                    };
-
-                    if let Some(file_id) = file_id {
-                        // Update existing file
-                        tracing::debug!("File modified: {:?}", changed_path);
-                        // For simplicity, just update the entry
-                        if let Ok(metadata) = tokio::fs::metadata(&changed_path).await {
-                            let mut files_write = files.write().await;
-                            if let Some(entry) = files_write.get_mut(&file_id) {
-                                entry.size = metadata.len();
-                                entry.modified = metadata.modified().ok();
-                            }
-                        }
-                    } else if changed_path.is_file() {
-                        // New file
-                        tracing::debug!("New file detected: {:?}", changed_path);
-                        
-                        let file_id = {
-                            let mut next = next_id.write().await;
-                            let id = *next;
-                            *next += 1;
-                            id
-                        };
-
-                        let file_name = changed_path.file_name()
-                            .map(|n| n.to_string_lossy().to_string())
-                            .unwrap_or_default();
-
-                        if let Ok(metadata) = tokio::fs::metadata(&changed_path).await {
-                            let entry = FileEntry {
-                                id: file_id,
-                                name: file_name.clone(),
-                                display_name: None,
-                                path: changed_path.clone(),
-                                size: metadata.len(),
-                                modified: metadata.modified().ok(),
-                            };
-
-                            let mut trie_write = trie.write().await;
-                            let mut trigram_write = trigram.write().await;
-                            let mut files_write = files.write().await;
-                            let mut path_to_id_write = path_to_id.write().await;
-
-                            // Add to indexes
-                            for word in file_name.split(|c: char| !c.is_alphanumeric()) {
-                                if !word.is_empty() {
-                                    trie_write.insert(word, file_id);
-                                }
-                            }
-                            trigram_write.add_file(&file_name, file_id);
-                            
-                            path_to_id_write.insert(changed_path, file_id);
-                            files_write.insert(file_id, entry);
-                        }
-                    }
-                } else {
-                    // File was deleted
-                    tracing::debug!("File deleted: {:?}", changed_path);
-                    
-                    let file_id = {
-                        let mut path_to_id_write = path_to_id.write().await;
-                        path_to_id_write.remove(&changed_path)
-                    };
-
-                    if let Some(file_id) = file_id {
-                        let mut files_write = files.write().await;
-                        if let Some(entry) = files_write.remove(&file_id) {
-                            let mut trie_write = trie.write().await;
-                            let mut trigram_write = trigram.write().await;
-
-                            // Remove from trie
-                            for word in entry.name.split(|c: char| !c.is_alphanumeric()) {
-                                if !word.is_empty() {
-                                    trie_write.remove(word, file_id);
-                                }
-                            }
-                            
-                            // Remove from trigram
-                            trigram_write.remove_file(file_id);
-                        }
-                    }
-                }
-            });
-        }).await.map_err(|e| e.to_string())?;
+        let target = self.watch_target();
+        watcher
+            .start_watching(move |changed_path| {
+                let target = target.clone();
+                async move { target.refresh_path(&changed_path).await }
+            })
+            .await
+            .map_err(|e| IndexerError::Watch(e.to_string()))?;
 
         // Store the watcher
         let mut watcher_lock = self.watcher.write().await;
@@ -372,8 +478,109 @@ impl Indexer {
         Ok(())
     }
 
-    /// Search for files matching the query
-    pub async fn search(&self, query: &str) -> Vec<FileEntry> {
+    /// Save the roots and indexed files to `path`
+    pub async fn save_snapshot(&self, path: &Path) -> Result<(), IndexerError> {
+        let snapshot = IndexSnapshot {
+            version: SNAPSHOT_VERSION,
+            roots: self.roots().await,
+            next_id: *self.next_id.read().await,
+            files: self.files.read().await.values().cloned().collect(),
+        };
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || snapshot.write(&path))
+            .await
+            .map_err(|e| IndexerError::Snapshot(format!("Save task failed: {e}")))?
+    }
+
+    /// Replace the index with the snapshot saved at `path`, roots included.
+    /// Entries may be stale; see [`Self::reconcile`]. Returns the number of
+    /// files restored.
+    pub async fn load_snapshot(&self, path: &Path) -> Result<usize, IndexerError> {
+        let path = path.to_path_buf();
+        let snapshot = tokio::task::spawn_blocking(move || IndexSnapshot::read(&path))
+            .await
+            .map_err(|e| IndexerError::Snapshot(format!("Load task failed: {e}")))??;
+
+        let mut trie = Trie::new();
+        let mut trigram = TrigramIndex::new();
+        let mut files = HashMap::with_capacity(snapshot.files.len());
+        let mut path_to_id = HashMap::with_capacity(snapshot.files.len());
+        let mut next_id = snapshot.next_id;
+        for entry in snapshot.files {
+            index_entry(&mut trie, &mut trigram, &entry);
+            next_id = next_id.max(entry.id + 1);
+            path_to_id.insert(entry.path.clone(), entry.id);
+            files.insert(entry.id, entry);
+        }
+        let count = files.len();
+
+        self.scanner.write().await.config_mut().roots = snapshot.roots;
+        *self.trie.write().await = trie;
+        *self.trigram.write().await = trigram;
+        *self.files.write().await = files;
+        *self.path_to_id.write().await = path_to_id;
+        *self.next_id.write().await = next_id;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        Ok(count)
+    }
+
+    /// Bring a restored index in line with the disk, given the time the
+    /// snapshot was saved. Adding or deleting a file changes its directory's
+    /// mtime, so only directories modified since `saved` are read again:
+    /// files gone from them are dropped, new ones added, and new
+    /// subdirectories scanned. Files edited in place keep their saved size
+    /// and mtime until the watcher sees them. Returns (dropped, added).
+    pub async fn reconcile(&self, saved: SystemTime) -> (usize, usize) {
+        let roots = self.roots().await;
+        let known: Vec<PathBuf> = self.path_to_id.read().await.keys().cloned().collect();
+        let scanner = self.scanner.read().await.clone();
+        let changes = tokio::task::spawn_blocking(move || changes_since(&scanner, &roots, known, saved))
+            .await
+            .unwrap_or_default();
+
+        let dropped = changes.removed.len();
+        let mut added = changes.added.len();
+        {
+            let mut trie = self.trie.write().await;
+            let mut trigram = self.trigram.write().await;
+            let mut files = self.files.write().await;
+            let mut path_to_id = self.path_to_id.write().await;
+            let mut next_id = self.next_id.write().await;
+
+            for path in &changes.removed {
+                if let Some(entry) = path_to_id.remove(path).and_then(|id| files.remove(&id)) {
+                    unindex_entry(&mut trie, &mut trigram, &entry);
+                }
+            }
+            for (path, size, modified) in changes.updated {
+                if let Some(entry) = path_to_id.get(&path).and_then(|id| files.get_mut(id)) {
+                    entry.size = size;
+                    entry.modified = modified;
+                }
+            }
+            for mut entry in changes.added {
+                entry.id = *next_id;
+                *next_id += 1;
+                index_entry(&mut trie, &mut trigram, &entry);
+                path_to_id.insert(entry.path.clone(), entry.id);
+                files.insert(entry.id, entry);
+            }
+        }
+        self.generation.fetch_add(1, Ordering::SeqCst);
+
+        for dir in changes.new_dirs {
+            match self.index_directory(&dir).await {
+                Ok(count) => added += count,
+                Err(e) => tracing::warn!("Failed to scan {:?}: {}", dir, e),
+            }
+        }
+        (dropped, added)
+    }
+
+    /// Search for files matching the query, returning `limit` results after
+    /// skipping `offset`. The ranking is a total order, so consecutive pages
+    /// of the same query neither overlap nor skip results.
+    pub async fn search(&self, query: &str, offset: usize, limit: usize) -> Vec<FileEntry> {
         if query.is_empty() {
             return Vec::new();
         }
@@ -403,10 +610,11 @@ impl Indexer {
         // Rank results
         let ranked = ranker.rank_results(candidate_ids.into_iter().collect(), query, &file_names);
 
-        // Return top results
+        // Return the requested page
         ranked
             .into_iter()
-            .take(20)
+            .skip(offset)
+            .take(limit)
             .filter_map(|score| files.get(&score.file_id).cloned())
             .collect()
     }
@@ -417,17 +625,330 @@ impl Indexer {
         ranker.record_access(file_id);
     }
 
+    /// Record an access by path. Paths outside the index, such as apps and
+    /// Everything results, are tracked by path.
+    pub async fn record_access_path(&self, path: &Path) {
+        let file_id = self.path_to_id.read().await.get(path).copied();
+        let mut ranker = self.ranker.write().await;
+        match file_id {
+            Some(file_id) => ranker.record_access(file_id),
+            None => ranker.record_path_access(path),
+        }
+    }
+
+    /// Ranking bonus earned by past accesses to `path`, for results ranked
+    /// outside the indexer
+    pub async fn access_bonus(&self, path: &Path) -> f64 {
+        let file_id = self.path_to_id.read().await.get(path).copied();
+        let ranker = self.ranker.read().await;
+        match file_id {
+            Some(file_id) => ranker.access_bonus(file_id),
+            None => ranker.path_access_bonus(path),
+        }
+    }
+
     /// Get file entry by ID
     pub async fn get_file(&self, file_id: usize) -> Option<FileEntry> {
         let files = self.files.read().await;
         files.get(&file_id).cloned()
     }
 
+    /// Find duplicate files among indexed files, optionally under `root`
+    pub async fn find_duplicates(
+        &self,
+        root: Option<&Path>,
+        options: &DuplicateOptions,
+        cancel: &AtomicBool,
+    ) -> DuplicateReport {
+        let candidates: Vec<(PathBuf, u64)> = {
+            let files = self.files.read().await;
+            files
+                .values()
+                .filter(|entry| root.map_or(true, |root| entry.path.starts_with(root)))
+                .map(|entry| (entry.path.clone(), entry.size))
+                .collect()
+        };
+
+        duplicates::find_duplicates(candidates, options, cancel).await
+    }
+
+    /// Rebuild the full-text index from the text-like files under
+    /// `directories`. Scan exclusions apply as for file names.
+    pub async fn index_contents(&self, directories: &[PathBuf], options: &ContentOptions) -> usize {
+        let scanner = self.scanner.read().await.clone();
+        let mut entries = Vec::new();
+        for dir in directories {
+            entries.extend(
+                scanner
+                    .scan_directory(dir)
+                    .await
+                    .into_iter()
+                    .filter(|entry| options.accepts(entry)),
+            );
+        }
+
+        let max_file_size = options.max_file_size;
+        let (directories, options) = (directories.to_vec(), options.clone());
+        let empty = ContentIndex::new(directories.clone(), options.clone());
+        let index = tokio::task::spawn_blocking(move || {
+            let mut index = ContentIndex::new(directories, options);
+            for entry in entries {
+                if let Some(text) = IndexedText::read(&entry.path, max_file_size) {
+                    index.add(entry.path, text);
+                }
+            }
+            index
+        })
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Content indexing task failed: {}", e);
+            empty
+        });
+
+        let count = index.len();
+        *self.content.write().await = index;
+        count
+    }
+
+    /// Files under the full-text directories containing every query term
+    pub async fn search_contents(&self, query: &str, limit: usize) -> Vec<ContentMatch> {
+        let terms = content::query_terms(query);
+        let (candidates, max_file_size) = {
+            let content = self.content.read().await;
+            (content.candidates(query), content.max_file_size())
+        };
+        if terms.is_empty() || candidates.is_empty() {
+            return Vec::new();
+        }
+
+        // Candidates only share trigrams with the query; confirm against
+        // the text, re-reading only files changed since they were indexed
+        let (matches, changed) = tokio::task::spawn_blocking(move || {
+            let mut matches = Vec::new();
+            let mut changed = Vec::new();
+            for candidate in candidates {
+                if matches.len() >= limit {
+                    break;
+                }
+                let text = match candidate.text.current(&candidate.path, max_file_size) {
+                    Freshness::Unchanged => candidate.text,
+                    Freshness::Changed(text) => {
+                        changed.push((candidate.path.clone(), Some(text.clone())));
+                        text
+                    }
+                    Freshness::Unreadable => {
+                        changed.push((candidate.path, None));
+                        continue;
+                    }
+                };
+                if let Some(snippet) = content::match_text(text.text(), &terms) {
+                    matches.push(ContentMatch {
+                        path: candidate.path,
+                        snippet,
+                        score: candidate.score,
+                    });
+                }
+            }
+            (matches, changed)
+        })
+        .await
+        .unwrap_or_default();
+
+        if !changed.is_empty() {
+            let mut content = self.content.write().await;
+            for (path, text) in changed {
+                match text {
+                    Some(text) => content.add(path, text),
+                    None => {
+                        content.remove(&path);
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    /// Counter that changes whenever the indexed files change
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
     /// Get total number of indexed files
     pub async fn file_count(&self) -> usize {
         let files = self.files.read().await;
         files.len()
     }
+
+    /// Unix time (seconds) of the last directory scan
+    pub fn last_refresh(&self) -> Option<i64> {
+        Some(self.last_refresh.load(Ordering::SeqCst)).filter(|&t| t > 0)
+    }
+
+    /// Whether a file watcher is running
+    pub async fn is_watching(&self) -> bool {
+        self.watcher.read().await.is_some()
+    }
+
+    /// Name of some indexed file, usable as a query that must find something
+    pub async fn sample_name(&self) -> Option<String> {
+        let files = self.files.read().await;
+        files.values().map(|entry| entry.name.clone()).min()
+    }
+}
+
+/// The parts of the index the watcher updates, shared with the [`Indexer`]
+#[derive(Clone)]
+struct WatchTarget {
+    trie: Arc<RwLock<Trie>>,
+    trigram: Arc<RwLock<TrigramIndex>>,
+    files: Arc<RwLock<HashMap<usize, FileEntry>>>,
+    path_to_id: Arc<RwLock<HashMap<PathBuf, usize>>>,
+    scanner: Arc<RwLock<FileScanner>>,
+    next_id: Arc<RwLock<usize>>,
+    generation: Arc<AtomicU64>,
+    content: Arc<RwLock<ContentIndex>>,
+}
+
+impl Indexer {
+    fn watch_target(&self) -> WatchTarget {
+        WatchTarget {
+            trie: self.trie.clone(),
+            trigram: self.trigram.clone(),
+            files: self.files.clone(),
+            path_to_id: self.path_to_id.clone(),
+            scanner: self.scanner.clone(),
+            next_id: self.next_id.clone(),
+            generation: self.generation.clone(),
+            content: self.content.clone(),
+        }
+    }
+}
+
+impl WatchTarget {
+    /// Bring the index in line with what is at `path` now: a file is indexed
+    /// or re-indexed, a directory new to the index has its files indexed, and
+    /// a missing path is dropped with everything indexed under it. A rename
+    /// is the old path going missing and the new one appearing.
+    async fn refresh_path(&self, path: &Path) {
+        match tokio::fs::metadata(path).await {
+            Err(_) => {
+                self.remove_under(path).await;
+                self.content.write().await.remove_under(path);
+            }
+            Ok(metadata) if metadata.is_dir() => {
+                // Directories report changes to their children; only scan
+                // ones that arrived whole, e.g. by being moved in
+                let known = self.path_to_id.read().await.keys().any(|p| p.starts_with(path));
+                if known {
+                    return;
+                }
+                let scanner = self.scanner.read().await.clone();
+                for entry in scanner.scan_directory(path).await {
+                    self.refresh_content(&entry.path, entry.size, entry.modified).await;
+                    self.upsert(entry.path, entry.size, entry.modified).await;
+                }
+            }
+            Ok(metadata) => {
+                let modified = metadata.modified().ok();
+                self.refresh_content(path, metadata.len(), modified).await;
+                self.upsert(path.to_path_buf(), metadata.len(), modified).await
+            }
+        }
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Re-read the file at `path` into the full-text index if it is indexed
+    /// there or now belongs there
+    async fn refresh_content(&self, path: &Path, size: u64, modified: Option<SystemTime>) {
+        let max_file_size = {
+            let content = self.content.read().await;
+            let belongs = match content.directory_for(path, size, modified) {
+                Some(dir) => {
+                    let scanner = self.scanner.read().await;
+                    // Excluded folders between the full-text directory and the file
+                    let excluded = path
+                        .ancestors()
+                        .skip(1)
+                        .take_while(|ancestor| *ancestor != dir)
+                        .any(|ancestor| scanner.excludes(ancestor, true));
+                    !excluded && !scanner.excludes(path, false)
+                }
+                None => false,
+            };
+            if !belongs && !content.contains(path) {
+                return;
+            }
+            belongs.then_some(content.max_file_size())
+        };
+
+        let read_path = path.to_path_buf();
+        let text = match max_file_size {
+            Some(max_file_size) => {
+                tokio::task::spawn_blocking(move || IndexedText::read(&read_path, max_file_size))
+                    .await
+                    .ok()
+                    .flatten()
+            }
+            None => None,
+        };
+        let mut content = self.content.write().await;
+        match text {
+            Some(text) => content.add(path.to_path_buf(), text),
+            None => {
+                content.remove(path);
+            }
+        }
+    }
+
+    /// Index the file at `path`, replacing its entry if it has one
+    async fn upsert(&self, path: PathBuf, size: u64, modified: Option<std::time::SystemTime>) {
+        let mut trie = self.trie.write().await;
+        let mut trigram = self.trigram.write().await;
+        let mut files = self.files.write().await;
+        let mut path_to_id = self.path_to_id.write().await;
+
+        let previous = path_to_id.get(&path).and_then(|id| files.remove(id));
+        let entry = match previous {
+            Some(previous) => {
+                unindex_entry(&mut trie, &mut trigram, &previous);
+                FileEntry { size, modified, ..previous }
+            }
+            None => {
+                let mut next_id = self.next_id.write().await;
+                let id = *next_id;
+                *next_id += 1;
+                tracing::debug!("New file detected: {:?}", path);
+                FileEntry {
+                    id,
+                    name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                    display_name: None,
+                    path: path.clone(),
+                    size,
+                    modified,
+                }
+            }
+        };
+
+        index_entry(&mut trie, &mut trigram, &entry);
+        path_to_id.insert(path, entry.id);
+        files.insert(entry.id, entry);
+    }
+
+    /// Drop `path` and any indexed files under it
+    async fn remove_under(&self, path: &Path) {
+        let mut trie = self.trie.write().await;
+        let mut trigram = self.trigram.write().await;
+        let mut files = self.files.write().await;
+        let mut path_to_id = self.path_to_id.write().await;
+
+        let gone: Vec<PathBuf> = path_to_id.keys().filter(|p| p.starts_with(path)).cloned().collect();
+        for removed in gone {
+            if let Some(entry) = path_to_id.remove(&removed).and_then(|id| files.remove(&id)) {
+                unindex_entry(&mut trie, &mut trigram, &entry);
+            }
+        }
+        tracing::debug!("Removed from index: {:?}", path);
+    }
 }
 
 impl Default for Indexer {
@@ -435,3 +956,307 @@ impl Default for Indexer {
         Self::new(ScanConfig::default())
     }
 }
+
+/// Validate a directory to index and return its canonical path. Resolving
+/// the path rejects symlink loops; the scan itself does not follow links.
+pub fn resolve_root(path: &Path) -> Result<PathBuf, IndexerError> {
+    // `exists` follows links, so a looping link would be reported as missing
+    if std::fs::symlink_metadata(path).is_err() {
+        return Err(IndexerError::PathNotFound(path.to_path_buf()));
+    }
+    let root = path.canonicalize().map_err(|source| IndexerError::Unresolvable {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    if !root.is_dir() {
+        return Err(IndexerError::NotADirectory(path.to_path_buf()));
+    }
+    Ok(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_tree() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("omnibox_roots_{}", uuid::Uuid::new_v4()));
+        for dir in ["a", "b", "a/nested"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in ["a/one.txt", "a/nested/two.txt", "b/three.txt"] {
+            fs::write(root.join(file), file).unwrap();
+        }
+        root.canonicalize().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_accented_names_match_folded_queries() {
+        let root = temp_tree();
+        for name in ["Café.txt", "cafe.txt", "Mañana.md", "Straße.pdf"] {
+            fs::write(root.join("b").join(name), name).unwrap();
+        }
+        let indexer = Indexer::default();
+        indexer.add_root(&root.join("b"), false).await.unwrap();
+
+        let names = |found: Vec<FileEntry>| found.into_iter().map(|entry| entry.name).collect::<Vec<_>>();
+        assert_eq!(names(indexer.search("manana", 0, 20).await), vec!["Mañana.md"]);
+        assert_eq!(names(indexer.search("MAÑ", 0, 20).await), vec!["Mañana.md"]);
+        assert_eq!(names(indexer.search("strasse", 0, 20).await), vec!["Straße.pdf"]);
+        assert_eq!(names(indexer.search("straße", 0, 20).await), vec!["Straße.pdf"]);
+
+        // Both spellings match either query; the exact one ranks first
+        assert_eq!(names(indexer.search("cafe.txt", 0, 2).await), vec!["cafe.txt", "Café.txt"]);
+        assert_eq!(names(indexer.search("café.txt", 0, 2).await), vec!["Café.txt", "cafe.txt"]);
+
+        // Removal finds the folded keys again
+        indexer.remove_file(&root.join("b/Straße.pdf")).await.unwrap();
+        assert!(indexer.trie.read().await.search_prefix("strasse").is_empty());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let root = temp_tree();
+        let snapshot = root.join("index.json");
+        let indexer = Indexer::default();
+        indexer.add_root(&root.join("a"), false).await.unwrap();
+        indexer.save_snapshot(&snapshot).await.unwrap();
+
+        let restored = Indexer::default();
+        assert_eq!(restored.load_snapshot(&snapshot).await.unwrap(), 2);
+        assert_eq!(restored.roots().await, vec![root.join("a")]);
+        let found = restored.search("two", 0, 20).await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, root.join("a/nested/two.txt"));
+        assert_eq!(found[0].id, indexer.search("two", 0, 20).await[0].id);
+
+        // New files get IDs past the restored ones
+        fs::write(root.join("a/four.txt"), "four").unwrap();
+        let id = restored.add_file(&root.join("a/four.txt")).await.unwrap();
+        assert!(restored.files.read().await.values().all(|entry| entry.id <= id));
+
+        // Snapshots of another format version are refused
+        let data = fs::read_to_string(&snapshot).unwrap();
+        fs::write(&snapshot, data.replacen(&format!("\"version\":{}", SNAPSHOT_VERSION), "\"version\":999", 1)).unwrap();
+        assert!(matches!(Indexer::default().load_snapshot(&snapshot).await, Err(IndexerError::Snapshot(_))));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_drops_deleted_and_adds_new_files() {
+        let root = temp_tree();
+        let snapshot = root.join("index.json");
+        let indexer = Indexer::default();
+        indexer.add_root(&root.join("a"), false).await.unwrap();
+        indexer.save_snapshot(&snapshot).await.unwrap();
+
+        // Changes made while the app was not running
+        fs::remove_file(root.join("a/one.txt")).unwrap();
+        fs::write(root.join("a/nested/five.txt"), "five").unwrap();
+
+        fs::create_dir(root.join("a/added")).unwrap();
+        fs::write(root.join("a/added/six.txt"), "six").unwrap();
+
+        let saved = fs::metadata(&snapshot).unwrap().modified().unwrap();
+        let restored = Indexer::default();
+        restored.load_snapshot(&snapshot).await.unwrap();
+        assert_eq!(restored.reconcile(saved).await, (1, 2));
+        assert_eq!(restored.file_count().await, 3);
+        assert!(restored.search("one", 0, 20).await.is_empty());
+        assert!(restored.trie.read().await.search_prefix("one").is_empty());
+        assert_eq!(restored.search("five", 0, 20).await.len(), 1);
+        assert_eq!(restored.search("six", 0, 20).await.len(), 1);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_skips_directories_unchanged_since_the_save() {
+        let root = temp_tree();
+        let snapshot = root.join("index.json");
+        let indexer = Indexer::default();
+        indexer.add_root(&root.join("a"), false).await.unwrap();
+        indexer.save_snapshot(&snapshot).await.unwrap();
+        fs::write(root.join("a/nested/five.txt"), "five").unwrap();
+
+        // As if the snapshot were newer than every directory
+        let restored = Indexer::default();
+        restored.load_snapshot(&snapshot).await.unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(3600);
+        assert_eq!(restored.reconcile(later).await, (0, 0));
+        assert!(restored.search("five", 0, 20).await.is_empty());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_root_rejects_invalid_paths() {
+        let root = temp_tree();
+        assert!(matches!(resolve_root(&root.join("missing")), Err(IndexerError::PathNotFound(_))));
+        assert!(matches!(resolve_root(&root.join("a/one.txt")), Err(IndexerError::NotADirectory(_))));
+        assert_eq!(resolve_root(&root.join("a/../b")).unwrap(), root.join("b"));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_root_rejects_symlink_loop() {
+        let root = temp_tree();
+        std::os::unix::fs::symlink(root.join("loop2"), root.join("loop1")).unwrap();
+        std::os::unix::fs::symlink(root.join("loop1"), root.join("loop2")).unwrap();
+        assert!(matches!(resolve_root(&root.join("loop1")), Err(IndexerError::Unresolvable { .. })));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_add_file_errors() {
+        let root = temp_tree();
+        let indexer = Indexer::default();
+        let file = root.join("a/one.txt");
+
+        let missing = indexer.add_file_with_display_name(&root.join("missing.txt"), None).await;
+        assert!(matches!(missing, Err(IndexerError::PathNotFound(path)) if path == root.join("missing.txt")));
+
+        indexer.add_file_with_display_name(&file, Some("First".into())).await.unwrap();
+        let again = indexer.add_file_with_display_name(&file, None).await;
+        assert!(matches!(again, Err(IndexerError::AlreadyIndexed(path)) if path == file));
+        assert_eq!(indexer.file_count().await, 1);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_watcher_changes_leave_consistent_index() {
+        let root = temp_tree();
+        let indexer = Indexer::default();
+        indexer.add_root(&root, false).await.unwrap();
+        let target = indexer.watch_target();
+        let old = root.join("b/three.txt");
+        let new = root.join("b/renamed.txt");
+
+        // Modify events for one file, then a rename, delivered in a burst
+        for _ in 0..5 {
+            target.refresh_path(&old).await;
+        }
+        fs::rename(&old, &new).unwrap();
+        for path in [&old, &new, &old, &new] {
+            target.refresh_path(path).await;
+        }
+
+        assert_eq!(indexer.file_count().await, 3);
+        assert!(indexer.search("three", 0, 20).await.is_empty());
+        assert!(indexer.trie.read().await.search_prefix("three").is_empty());
+        let found = indexer.search("renamed", 0, 20).await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, new);
+
+        // A directory moved away takes its files along; moved back, they return
+        let moved = root.join("moved");
+        fs::rename(root.join("a"), &moved).unwrap();
+        target.refresh_path(&root.join("a")).await;
+        target.refresh_path(&moved).await;
+        assert_eq!(indexer.file_count().await, 3);
+        let found = indexer.search("one", 0, 20).await;
+        assert!(found.iter().any(|entry| entry.path == moved.join("one.txt")));
+        assert!(found.iter().all(|entry| !entry.path.starts_with(root.join("a"))));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_watcher_changes_update_contents() {
+        let root = temp_tree();
+        let indexer = Indexer::default();
+        let options = ContentOptions {
+            extensions: vec!["txt".to_string()],
+            max_file_size: 1024,
+            filter: SearchFilter::default(),
+        };
+        indexer.index_contents(std::slice::from_ref(&root), &options).await;
+        let target = indexer.watch_target();
+
+        let added = root.join("b/added.txt");
+        fs::write(&added, "the launch checklist").unwrap();
+        target.refresh_path(&added).await;
+        let found = indexer.search_contents("checklist", 10).await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, added);
+
+        fs::remove_file(&added).unwrap();
+        target.refresh_path(&added).await;
+        assert!(!indexer.content.read().await.contains(&added));
+        assert!(indexer.search_contents("checklist", 10).await.is_empty());
+
+        // Files the full-text options don't take stay out
+        let image = root.join("b/photo.png");
+        fs::write(&image, "checklist").unwrap();
+        target.refresh_path(&image).await;
+        assert!(!indexer.content.read().await.contains(&image));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_add_and_remove_roots() {
+        let root = temp_tree();
+        let indexer = Indexer::default();
+
+        indexer.add_root(&root.join("a/nested"), false).await.unwrap();
+        indexer.add_root(&root.join("b"), false).await.unwrap();
+        assert_eq!(indexer.file_count().await, 2);
+
+        // The parent absorbs the nested root without duplicating its files
+        indexer.add_root(&root.join("a"), false).await.unwrap();
+        assert_eq!(indexer.roots().await, vec![root.join("b"), root.join("a")]);
+        assert_eq!(indexer.file_count().await, 3);
+        assert!(matches!(
+            indexer.add_root(&root.join("a/nested"), false).await,
+            Err(IndexerError::InsideRoot { .. })
+        ));
+
+        // IDs stay unique across scans
+        let ids: std::collections::HashSet<usize> =
+            indexer.files.read().await.keys().copied().collect();
+        assert_eq!(ids.len(), 3);
+
+        indexer.remove_root(&root.join("a")).await.unwrap();
+        assert_eq!(indexer.file_count().await, 1);
+        assert!(indexer.search("one", 0, 20).await.is_empty());
+        assert_eq!(indexer.search("three", 0, 20).await.len(), 1);
+        assert!(matches!(indexer.remove_root(&root.join("a")).await, Err(IndexerError::NotARoot(_))));
+        assert!(indexer.last_refresh().is_some());
+        assert_eq!(indexer.sample_name().await.as_deref(), Some("three.txt"));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pages_follow_one_stable_order() {
+        let root = std::env::temp_dir().join(format!("omnibox_pages_{}", uuid::Uuid::new_v4()));
+        // Same-named files tie on score; they must still come in one order
+        for dir in ["a", "b", "c", "d", "e", "f", "g"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join("report.txt"), dir).unwrap();
+        }
+        fs::write(root.join("report-final.txt"), "final").unwrap();
+        let indexer = Indexer::default();
+        indexer.add_root(&root, false).await.unwrap();
+
+        let paths = |entries: Vec<FileEntry>| entries.into_iter().map(|e| e.path).collect::<Vec<_>>();
+        let all = paths(indexer.search("report", 0, 100).await);
+        assert_eq!(all.len(), 8);
+
+        let mut paged = Vec::new();
+        for offset in (0..all.len()).step_by(3) {
+            paged.extend(paths(indexer.search("report", offset, 3).await));
+        }
+        assert_eq!(paged, all);
+        assert_eq!(paths(indexer.search("report", 0, 100).await), all);
+        assert!(indexer.search("report", 8, 3).await.is_empty());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
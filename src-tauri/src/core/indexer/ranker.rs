@@ -1,5 +1,7 @@
 // Ranking algorithm for search results
+use crate::utils::text::fold_for_search;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 #[derive(Debug, Clone)]
@@ -13,6 +15,9 @@ pub struct Ranker {
     access_count: HashMap<usize, u32>,
     /// Track last access time
     last_access: HashMap<usize, SystemTime>,
+    /// Accesses to paths outside the index (apps, Everything results)
+    path_access_count: HashMap<PathBuf, u32>,
+    path_last_access: HashMap<PathBuf, SystemTime>,
 }
 
 impl Ranker {
@@ -20,6 +25,8 @@ impl Ranker {
         Self {
             access_count: HashMap::new(),
             last_access: HashMap::new(),
+            path_access_count: HashMap::new(),
+            path_last_access: HashMap::new(),
         }
     }
 
@@ -29,6 +36,23 @@ impl Ranker {
         self.last_access.insert(file_id, SystemTime::now());
     }
 
+    /// Record an access to a path that has no file id
+    pub fn record_path_access(&mut self, path: &Path) {
+        *self.path_access_count.entry(path.to_path_buf()).or_insert(0) += 1;
+        self.path_last_access.insert(path.to_path_buf(), SystemTime::now());
+    }
+
+    /// Frequency and recency bonus of an indexed file
+    pub fn access_bonus(&self, file_id: usize) -> f64 {
+        access_bonus(self.access_count.get(&file_id), self.last_access.get(&file_id))
+    }
+
+    /// Frequency and recency bonus of a path recorded with
+    /// [`Self::record_path_access`]
+    pub fn path_access_bonus(&self, path: &Path) -> f64 {
+        access_bonus(self.path_access_count.get(path), self.path_last_access.get(path))
+    }
+
     /// Rank search results based on various factors
     pub fn rank_results(
         &self,
@@ -44,8 +68,14 @@ impl Ranker {
             })
             .collect();
 
-        // Sort by score descending
-        scores.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        // Score descending; ties by name, then id, so the order is total
+        // and pages of the same query line up
+        scores.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| file_names.get(&a.file_id).cmp(&file_names.get(&b.file_id)))
+                .then_with(|| a.file_id.cmp(&b.file_id))
+        });
 
         scores
     }
@@ -61,15 +91,19 @@ impl Ranker {
             None => return 0.0,
         };
 
-        let query_lower = query.to_lowercase();
-        let name_lower = file_name.to_lowercase();
+        // Matching ignores case and diacritics; only the exact-match bonus
+        // tells "résumé" and "resume" apart
+        let query_lower = fold_for_search(query);
+        let name_lower = fold_for_search(file_name);
 
         // Base score: match quality
         let mut score = 0.0;
 
         // Exact match bonus
-        if name_lower == query_lower {
+        if file_name.to_lowercase() == query.to_lowercase() {
             score += 100.0;
+        } else if name_lower == query_lower {
+            score += 90.0;
         }
         // Starts with query bonus
         else if name_lower.starts_with(&query_lower) {
@@ -85,20 +119,7 @@ impl Ranker {
             score += 20.0;
         }
 
-        // Frequency bonus (logarithmic scale)
-        if let Some(&count) = self.access_count.get(&file_id) {
-            score += (count as f64).ln() * 5.0;
-        }
-
-        // Recency bonus
-        if let Some(&last_access) = self.last_access.get(&file_id) {
-            if let Ok(duration) = SystemTime::now().duration_since(last_access) {
-                let days_ago = duration.as_secs() as f64 / 86400.0;
-                // Decay factor: recent files get higher scores
-                let recency_score = (-days_ago / 30.0).exp() * 10.0;
-                score += recency_score;
-            }
-        }
+        score += self.access_bonus(file_id);
 
         // Shorter file names get slight bonus (prefer concise matches)
         let length_penalty = (file_name.len() as f64 / 100.0).min(5.0);
@@ -114,6 +135,26 @@ impl Default for Ranker {
     }
 }
 
+fn access_bonus(count: Option<&u32>, last_access: Option<&SystemTime>) -> f64 {
+    let mut bonus = 0.0;
+
+    // Frequency bonus (logarithmic scale)
+    if let Some(&count) = count {
+        bonus += (count as f64).ln() * 5.0;
+    }
+
+    // Recency bonus
+    if let Some(&last_access) = last_access {
+        if let Ok(duration) = SystemTime::now().duration_since(last_access) {
+            let days_ago = duration.as_secs() as f64 / 86400.0;
+            // Decay factor: recent files get higher scores
+            bonus += (-days_ago / 30.0).exp() * 10.0;
+        }
+    }
+
+    bonus
+}
+
 /// Check if query matches at word boundaries
 fn is_word_boundary_match(text: &str, query: &str) -> bool {
     let words: Vec<&str> = text.split(|c: char| !c.is_alphanumeric()).collect();
@@ -147,4 +188,35 @@ mod tests {
         assert!(score1 > score2);
         assert!(score1 > score3);
     }
+
+    #[test]
+    fn test_path_accesses_earn_a_bonus() {
+        let mut ranker = Ranker::new();
+        let app = Path::new("C:\\Program Files\\App\\app.exe");
+        assert_eq!(ranker.path_access_bonus(app), 0.0);
+
+        ranker.record_path_access(app);
+        let once = ranker.path_access_bonus(app);
+        ranker.record_path_access(app);
+        assert!(once > 0.0);
+        assert!(ranker.path_access_bonus(app) > once);
+        // Ids and paths are tracked apart
+        assert_eq!(ranker.access_bonus(1), 0.0);
+    }
+
+    #[test]
+    fn test_accented_names_match_folded_queries() {
+        let ranker = Ranker::new();
+        let mut file_names = HashMap::new();
+        file_names.insert(1, "Café.txt".to_string());
+        file_names.insert(2, "cafe.txt".to_string());
+        file_names.insert(3, "la-cafeteria.txt".to_string());
+
+        // The folded query ranks the accented name like a plain one
+        let accented = ranker.calculate_score(1, "cafe", &file_names);
+        assert!(accented > ranker.calculate_score(3, "cafe", &file_names));
+        // Exact spelling still wins over the folded match
+        assert!(ranker.calculate_score(2, "cafe.txt", &file_names) > ranker.calculate_score(1, "cafe.txt", &file_names));
+        assert!(ranker.calculate_score(1, "café.txt", &file_names) > ranker.calculate_score(2, "café.txt", &file_names));
+    }
 }
@@ -1,21 +1,69 @@
 // File scanner for indexing
+use crate::app::config::ScanPreset;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::fs;
-use tokio::task;
+use tokio::task::JoinSet;
 
 #[derive(Debug, Clone)]
 pub struct ScanConfig {
+    /// Directories the indexer keeps indexed
+    pub roots: Vec<PathBuf>,
     /// Maximum depth to scan
     pub max_depth: Option<usize>,
     /// Paths to exclude (glob patterns)
     pub exclude_patterns: Vec<String>,
     /// Extensions to exclude
     pub exclude_extensions: Vec<String>,
+    /// Maximum number of directories read at the same time
+    pub concurrency: usize,
+    /// Optional pause between batches of entries to keep background I/O light
+    pub throttle: Option<ScanThrottle>,
+}
+
+/// Sleep for `pause` after every `batch_size` directory entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanThrottle {
+    pub batch_size: usize,
+    pub pause: Duration,
+}
+
+impl ScanConfig {
+    /// Apply the concurrency/throttle settings of a preset
+    pub fn with_preset(mut self, preset: ScanPreset) -> Self {
+        match preset {
+            ScanPreset::Low => {
+                self.concurrency = 1;
+                self.throttle = Some(ScanThrottle {
+                    batch_size: 200,
+                    pause: Duration::from_millis(20),
+                });
+            }
+            ScanPreset::Normal => {
+                self.concurrency = 4;
+                self.throttle = Some(ScanThrottle {
+                    batch_size: 1000,
+                    pause: Duration::from_millis(5),
+                });
+            }
+            ScanPreset::Aggressive => {
+                self.concurrency = std::thread::available_parallelism()
+                    .map(|n| n.get() * 2)
+                    .unwrap_or(8);
+                self.throttle = None;
+            }
+        }
+        self
+    }
 }
 
 impl Default for ScanConfig {
     fn default() -> Self {
         Self {
+            roots: vec![],
             max_depth: Some(10),
             exclude_patterns: vec![
                 "**/node_modules/**".to_string(),
@@ -28,11 +76,14 @@ impl Default for ScanConfig {
                 "**/AppData/**".to_string(), // Windows
             ],
             exclude_extensions: vec![],
+            concurrency: 1,
+            throttle: None,
         }
+        .with_preset(ScanPreset::default())
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileEntry {
     pub id: usize,
     pub path: PathBuf,
@@ -42,6 +93,42 @@ pub struct FileEntry {
     pub modified: Option<std::time::SystemTime>,
 }
 
+/// A directory child as returned by a directory reader
+#[derive(Debug, Clone)]
+struct RawEntry {
+    path: PathBuf,
+    is_dir: bool,
+    is_file: bool,
+    size: u64,
+    modified: Option<std::time::SystemTime>,
+}
+
+/// Read one directory level; unreadable directories/entries are skipped
+async fn read_dir_entries(path: PathBuf) -> Vec<RawEntry> {
+    let mut out = Vec::new();
+    let mut read_dir = match fs::read_dir(&path).await {
+        Ok(rd) => rd,
+        Err(_) => return out,
+    };
+
+    while let Ok(Some(entry)) = read_dir.next_entry().await {
+        let metadata = match entry.metadata().await {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        out.push(RawEntry {
+            path: entry.path(),
+            is_dir: metadata.is_dir(),
+            is_file: metadata.is_file(),
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+        });
+    }
+
+    out
+}
+
+#[derive(Clone)]
 pub struct FileScanner {
     config: ScanConfig,
 }
@@ -51,85 +138,130 @@ impl FileScanner {
         Self { config }
     }
 
+    pub fn config(&self) -> &ScanConfig {
+        &self.config
+    }
+
+    pub fn config_mut(&mut self) -> &mut ScanConfig {
+        &mut self.config
+    }
+
     /// Scan a directory recursively
     pub async fn scan_directory(&self, path: &Path) -> Vec<FileEntry> {
-        let mut entries = Vec::new();
-        self.scan_recursive(path, 0, &mut entries, 0).await;
-        entries
+        self.walk(path, read_dir_entries).await
     }
 
-    fn scan_recursive<'a>(
-        &'a self,
-        path: &'a Path,
-        depth: usize,
-        entries: &'a mut Vec<FileEntry>,
-        next_id: usize,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = usize> + Send + 'a>> {
-        Box::pin(async move {
-            let mut current_id = next_id;
-
-            // Check max depth
-            if let Some(max_depth) = self.config.max_depth {
-                if depth > max_depth {
-                    return current_id;
-                }
-            }
+    /// Walk the tree with at most `concurrency` directory reads in flight,
+    /// pausing per the throttle between batches of entries
+    async fn walk<R, Fut>(&self, root: &Path, read_dir: R) -> Vec<FileEntry>
+    where
+        R: Fn(PathBuf) -> Fut,
+        Fut: Future<Output = Vec<RawEntry>> + Send + 'static,
+    {
+        let concurrency = self.config.concurrency.max(1);
+        let mut pending: VecDeque<(PathBuf, usize)> = VecDeque::new();
+        let mut in_flight = JoinSet::new();
+        let mut entries = Vec::new();
+        let mut since_pause = 0usize;
+
+        self.enqueue_dir(root.to_path_buf(), 0, &mut pending);
 
-            // Check if path should be excluded
-            if self.should_exclude(path) {
-                return current_id;
+        loop {
+            while in_flight.len() < concurrency {
+                let Some((dir, depth)) = pending.pop_front() else {
+                    break;
+                };
+                let read = read_dir(dir);
+                in_flight.spawn(async move { (depth, read.await) });
             }
 
-            // Read directory
-            let mut read_dir = match fs::read_dir(path).await {
-                Ok(rd) => rd,
-                Err(_) => return current_id,
+            let Some(joined) = in_flight.join_next().await else {
+                break;
             };
-
-            while let Ok(Some(entry)) = read_dir.next_entry().await {
-                let entry_path = entry.path();
-
-                // Skip hidden files (starting with .)
-                if let Some(file_name) = entry_path.file_name() {
-                    if file_name.to_string_lossy().starts_with('.') {
-                        continue;
-                    }
+            let (depth, children) = match joined {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::warn!("Directory scan task failed: {}", e);
+                    continue;
                 }
+            };
+            let child_count = children.len();
 
-                let metadata = match entry.metadata().await {
-                    Ok(m) => m,
-                    Err(_) => continue,
+            for child in children {
+                // Skip hidden files (starting with .)
+                let name = match child.path.file_name() {
+                    Some(name) => name.to_string_lossy().to_string(),
+                    None => continue,
                 };
+                if name.starts_with('.') {
+                    continue;
+                }
 
-                if metadata.is_dir() {
-                    // Recursively scan subdirectory
-                    current_id = self.scan_recursive(&entry_path, depth + 1, entries, current_id).await;
-                } else if metadata.is_file() {
+                if child.is_dir {
+                    self.enqueue_dir(child.path, depth + 1, &mut pending);
+                } else if child.is_file {
                     // Check extension exclusion
-                    if let Some(ext) = entry_path.extension() {
+                    if let Some(ext) = child.path.extension() {
                         let ext_str = ext.to_string_lossy().to_string();
                         if self.config.exclude_extensions.contains(&ext_str) {
                             continue;
                         }
                     }
 
-                    // Add file entry
-                    if let Some(name) = entry_path.file_name() {
-                        entries.push(FileEntry {
-                            id: current_id,
-                            path: entry_path.clone(),
-                            name: name.to_string_lossy().to_string(),
-                            display_name: None,
-                            size: metadata.len(),
-                            modified: metadata.modified().ok(),
-                        });
-                        current_id += 1;
-                    }
+                    entries.push(FileEntry {
+                        id: entries.len(),
+                        path: child.path,
+                        name,
+                        display_name: None,
+                        size: child.size,
+                        modified: child.modified,
+                    });
                 }
             }
 
-            current_id
-        })
+            if let Some(throttle) = self.config.throttle {
+                since_pause += child_count;
+                if since_pause >= throttle.batch_size {
+                    since_pause = 0;
+                    tokio::time::sleep(throttle.pause).await;
+                }
+            }
+        }
+
+        entries
+    }
+
+    /// Whether a scan leaves out `path`, judged like one directory level of
+    /// [`Self::scan_directory`]: hidden names, excluded directories and
+    /// excluded file extensions. The depth limit is not applied.
+    pub fn excludes(&self, path: &Path, is_dir: bool) -> bool {
+        let hidden = path
+            .file_name()
+            .map_or(true, |name| name.to_string_lossy().starts_with('.'));
+        if hidden {
+            return true;
+        }
+        if is_dir {
+            return self.should_exclude(path);
+        }
+        path.extension()
+            .is_some_and(|ext| self.config.exclude_extensions.contains(&ext.to_string_lossy().to_string()))
+    }
+
+    fn enqueue_dir(&self, path: PathBuf, depth: usize, pending: &mut VecDeque<(PathBuf, usize)>) {
+        // Check max depth
+        if let Some(max_depth) = self.config.max_depth {
+            if depth > max_depth {
+                return;
+            }
+        }
+
+        // Check if path should be excluded
+        if self.should_exclude(&path) {
+            return;
+        }
+
+        pending.push_back((path, depth));
     }
 
     fn should_exclude(&self, path: &Path) -> bool {
@@ -160,11 +292,93 @@ impl FileScanner {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
 
     #[test]
     fn test_default_config() {
         let config = ScanConfig::default();
         assert!(config.max_depth.is_some());
         assert!(!config.exclude_patterns.is_empty());
+        assert!(config.concurrency >= 1);
+    }
+
+    /// Synthetic tree: `/root` holds `dirs` subdirectories with `files` files each
+    fn fake_tree(dirs: usize, files: usize) -> impl Fn(PathBuf) -> Vec<RawEntry> + Clone {
+        move |path: PathBuf| {
+            let raw = |path: PathBuf, is_dir: bool| RawEntry {
+                path,
+                is_dir,
+                is_file: !is_dir,
+                size: 1,
+                modified: None,
+            };
+            if path == Path::new("/root") {
+                (0..dirs).map(|d| raw(path.join(format!("d{}", d)), true)).collect()
+            } else {
+                (0..files).map(|f| raw(path.join(format!("f{}.txt", f)), false)).collect()
+            }
+        }
+    }
+
+    fn scanner(concurrency: usize, throttle: Option<ScanThrottle>) -> FileScanner {
+        FileScanner::new(ScanConfig {
+            concurrency,
+            throttle,
+            ..ScanConfig::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_cap_is_respected() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_seen = Arc::new(AtomicUsize::new(0));
+        let tree = fake_tree(8, 3);
+
+        let reader = {
+            let in_flight = in_flight.clone();
+            let max_seen = max_seen.clone();
+            move |path: PathBuf| {
+                let in_flight = in_flight.clone();
+                let max_seen = max_seen.clone();
+                let tree = tree.clone();
+                async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_seen.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    tree(path)
+                }
+            }
+        };
+
+        let entries = scanner(2, None).walk(Path::new("/root"), reader).await;
+
+        assert_eq!(entries.len(), 24);
+        assert_eq!(max_seen.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_slow_io_yields_to_throttle() {
+        let tree = fake_tree(4, 10);
+        let slow_reader = move |path: PathBuf| {
+            let tree = tree.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(2)).await;
+                tree(path)
+            }
+        };
+        let throttle = ScanThrottle {
+            batch_size: 10,
+            pause: Duration::from_millis(25),
+        };
+
+        let started = Instant::now();
+        let entries = scanner(1, Some(throttle)).walk(Path::new("/root"), slow_reader).await;
+
+        assert_eq!(entries.len(), 40);
+        // Each of the four 10-file directories fills a batch and triggers a pause
+        assert!(started.elapsed() >= throttle.pause * 4);
     }
 }
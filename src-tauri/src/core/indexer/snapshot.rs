@@ -0,0 +1,52 @@
+// On-disk copy of the file index, so startup can search right away and
+// only reconcile with the disk in the background
+use super::{FileEntry, IndexerError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the snapshot layout changes; older snapshots are ignored
+/// and the index is rebuilt by scanning
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// The indexed roots and files. Trie and trigram indexes are rebuilt from
+/// the files on load.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexSnapshot {
+    pub version: u32,
+    pub roots: Vec<PathBuf>,
+    pub next_id: usize,
+    pub files: Vec<FileEntry>,
+}
+
+impl IndexSnapshot {
+    pub fn read(path: &Path) -> Result<Self, IndexerError> {
+        let data = std::fs::read(path)?;
+        // Check the version before the layout it decides
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+        let Version { version } =
+            serde_json::from_slice(&data).map_err(|e| IndexerError::Snapshot(e.to_string()))?;
+        if version != SNAPSHOT_VERSION {
+            return Err(IndexerError::Snapshot(format!(
+                "version {} is not supported (expected {})",
+                version, SNAPSHOT_VERSION
+            )));
+        }
+        serde_json::from_slice(&data).map_err(|e| IndexerError::Snapshot(e.to_string()))
+    }
+
+    /// Writes through a temporary file, so a crash mid-write cannot leave a
+    /// truncated snapshot behind
+    pub fn write(&self, path: &Path) -> Result<(), IndexerError> {
+        let data = serde_json::to_vec(self).map_err(|e| IndexerError::Snapshot(e.to_string()))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, data)?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    }
+}
@@ -1,11 +1,12 @@
 // Trie data structure for prefix matching
-use std::collections::HashMap;
+use crate::utils::text::fold_for_search;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone)]
 pub struct TrieNode {
     pub children: HashMap<char, TrieNode>,
     pub is_end: bool,
-    pub file_ids: Vec<usize>,
+    pub file_ids: HashSet<usize>,
 }
 
 impl TrieNode {
@@ -13,7 +14,7 @@ impl TrieNode {
         Self {
             children: HashMap::new(),
             is_end: false,
-            file_ids: Vec::new(),
+            file_ids: HashSet::new(),
         }
     }
 }
@@ -36,9 +37,10 @@ impl Trie {
         }
     }
 
-    /// Insert a word into the trie with associated file ID
+    /// Insert a word into the trie with associated file ID. Words are stored
+    /// case- and diacritic-folded, as queries are looked up.
     pub fn insert(&mut self, word: &str, file_id: usize) {
-        let word_lower = word.to_lowercase();
+        let word_lower = fold_for_search(word);
         let mut node = &mut self.root;
 
         for ch in word_lower.chars() {
@@ -46,40 +48,42 @@ impl Trie {
         }
 
         node.is_end = true;
-        if !node.file_ids.contains(&file_id) {
-            node.file_ids.push(file_id);
-        }
+        node.file_ids.insert(file_id);
     }
 
-    /// Remove a word's file ID from the trie
+    /// Remove a word's file ID from the trie.
+    ///
+    /// Only the terminal node of `word` loses `file_id`; nodes on the path are
+    /// pruned bottom-up while they have no children and no file IDs, so words
+    /// sharing a prefix are unaffected.
     pub fn remove(&mut self, word: &str, file_id: usize) {
-        let word_lower = word.to_lowercase();
-        let chars: Vec<char> = word_lower.chars().collect();
-        
-        // Navigate to the node containing this word
-        let mut node = &mut self.root;
-        for ch in &chars {
-            match node.children.get_mut(ch) {
-                Some(child) => node = child,
-                None => return, // Word not found
+        let chars: Vec<char> = fold_for_search(word).chars().collect();
+        Self::remove_from(&mut self.root, &chars, file_id);
+    }
+
+    /// Returns true if `node` is now empty and can be detached from its parent
+    fn remove_from(node: &mut TrieNode, chars: &[char], file_id: usize) -> bool {
+        match chars.split_first() {
+            None => {
+                node.file_ids.remove(&file_id);
+                node.is_end = !node.file_ids.is_empty();
+            }
+            Some((ch, rest)) => {
+                let Some(child) = node.children.get_mut(ch) else {
+                    return false; // Word not found
+                };
+                if Self::remove_from(child, rest, file_id) {
+                    node.children.remove(ch);
+                }
             }
         }
-        
-        // Remove file_id from this node
-        node.file_ids.retain(|&id| id != file_id);
-        
-        // If no more file_ids, mark as not end
-        if node.file_ids.is_empty() {
-            node.is_end = false;
-        }
-        
-        // Note: We don't clean up empty nodes for simplicity
-        // A full implementation would traverse back and remove empty nodes
+
+        node.children.is_empty() && node.file_ids.is_empty()
     }
 
     /// Search for words with given prefix
     pub fn search_prefix(&self, prefix: &str) -> Vec<usize> {
-        let prefix_lower = prefix.to_lowercase();
+        let prefix_lower = fold_for_search(prefix);
         let mut node = &self.root;
 
         // Navigate to the prefix node
@@ -96,7 +100,7 @@ impl Trie {
 
     /// Fuzzy search - find words similar to the query
     pub fn fuzzy_search(&self, query: &str, max_distance: usize) -> Vec<usize> {
-        let query_lower = query.to_lowercase();
+        let query_lower = fold_for_search(query);
         let mut results = Vec::new();
         self.fuzzy_search_helper(&self.root, &query_lower, "", max_distance, &mut results);
         results
@@ -114,7 +118,7 @@ impl Trie {
         let distance = levenshtein_distance(current, query);
 
         if distance <= max_distance && node.is_end {
-            results.extend_from_slice(&node.file_ids);
+            results.extend(node.file_ids.iter().copied());
         }
 
         // Continue searching if we haven't exceeded max distance
@@ -131,7 +135,7 @@ impl Trie {
         let mut file_ids = Vec::new();
 
         if node.is_end {
-            file_ids.extend_from_slice(&node.file_ids);
+            file_ids.extend(node.file_ids.iter().copied());
         }
 
         for child in node.children.values() {
@@ -202,6 +206,55 @@ mod tests {
         assert!(!results.contains(&2));
     }
 
+    #[test]
+    fn test_remove_keeps_words_sharing_a_prefix() {
+        let mut trie = Trie::new();
+        trie.insert("chrome", 1);
+        trie.insert("chromium", 2);
+        trie.insert("chr", 3);
+
+        trie.remove("chrome", 1);
+
+        let mut results = trie.search_prefix("chr");
+        results.sort();
+        assert_eq!(results, vec![2, 3]);
+        assert_eq!(trie.search_prefix("chromi"), vec![2]);
+        assert!(trie.search_prefix("chrome").is_empty());
+        // The "chrom" prefix is still needed by "chromium"
+        assert_eq!(trie.search_prefix("chrom"), vec![2]);
+    }
+
+    #[test]
+    fn test_remove_only_detaches_given_id() {
+        let mut trie = Trie::new();
+        trie.insert("chrome", 1);
+        trie.insert("chrome", 2);
+        trie.insert("chrome", 2);
+
+        trie.remove("chrome", 2);
+        assert_eq!(trie.search_prefix("chrome"), vec![1]);
+
+        // Unknown words and ids are no-ops
+        trie.remove("chromebook", 1);
+        trie.remove("chrome", 7);
+        assert_eq!(trie.search_prefix("chrome"), vec![1]);
+    }
+
+    #[test]
+    fn test_remove_prunes_empty_nodes() {
+        let mut trie = Trie::new();
+        trie.insert("chr", 3);
+        trie.insert("chrome", 1);
+
+        trie.remove("chrome", 1);
+        let chr = &trie.root.children[&'c'].children[&'h'].children[&'r'];
+        assert!(chr.children.is_empty());
+        assert!(chr.is_end);
+
+        trie.remove("chr", 3);
+        assert!(trie.root.children.is_empty());
+    }
+
     #[test]
     fn test_levenshtein_distance() {
         assert_eq!(levenshtein_distance("hello", "hello"), 0);
@@ -1,4 +1,6 @@
 // Trigram indexing for fuzzy search
+use super::is_cjk;
+use crate::utils::text::fold_for_search;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
@@ -6,30 +8,37 @@ use std::collections::{HashMap, HashSet};
 pub struct TrigramIndex {
     /// Map from trigram to set of file IDs
     index: HashMap<String, HashSet<usize>>,
+    /// Reverse map from file ID to the trigrams it contributed, so removal
+    /// only touches those buckets. Rebuilt from `index` when deserializing.
+    #[serde(skip)]
+    file_trigrams: HashMap<usize, HashSet<String>>,
 }
 
 impl TrigramIndex {
     pub fn new() -> Self {
         Self {
             index: HashMap::new(),
+            file_trigrams: HashMap::new(),
         }
     }
 
     /// Add a file to the trigram index
     pub fn add_file(&mut self, text: &str, file_id: usize) {
-        let trigrams = extract_trigrams(&text.to_lowercase());
+        let trigrams = extract_trigrams(text);
+        let contributed = self.file_trigrams.entry(file_id).or_default();
 
         for trigram in trigrams {
             self.index
-                .entry(trigram)
+                .entry(trigram.clone())
                 .or_insert_with(HashSet::new)
                 .insert(file_id);
+            contributed.insert(trigram);
         }
     }
 
     /// Search for files matching the query using trigrams
     pub fn search(&self, query: &str) -> Vec<(usize, f64)> {
-        let query_trigrams = extract_trigrams(&query.to_lowercase());
+        let query_trigrams = extract_trigrams(query);
 
         if query_trigrams.is_empty() {
             return Vec::new();
@@ -62,16 +71,30 @@ impl TrigramIndex {
         results
     }
 
-    /// Remove a file from the index
-    pub fn remove_file(&mut self, file_id: usize) {
-        for file_ids in self.index.values_mut() {
-            file_ids.remove(&file_id);
+    /// Remove a file from the index, touching only the buckets of its own
+    /// trigrams. Buckets left empty are dropped. Returns the number of
+    /// buckets touched.
+    pub fn remove_file(&mut self, file_id: usize) -> usize {
+        let Some(trigrams) = self.file_trigrams.remove(&file_id) else {
+            return 0;
+        };
+
+        let touched = trigrams.len();
+        for trigram in trigrams {
+            if let Some(file_ids) = self.index.get_mut(&trigram) {
+                file_ids.remove(&file_id);
+                if file_ids.is_empty() {
+                    self.index.remove(&trigram);
+                }
+            }
         }
+        touched
     }
 
     /// Clear the entire index
     pub fn clear(&mut self) {
         self.index.clear();
+        self.file_trigrams.clear();
     }
 
     /// Get the number of trigrams in the index
@@ -86,7 +109,22 @@ impl TrigramIndex {
 
     /// Deserialize the index from bytes
     pub fn deserialize(data: &[u8]) -> Result<Self, String> {
-        serde_json::from_slice(data).map_err(|e| format!("Failed to deserialize trigram index: {}", e))
+        let mut index: Self = serde_json::from_slice(data)
+            .map_err(|e| format!("Failed to deserialize trigram index: {}", e))?;
+        index.rebuild_file_trigrams();
+        Ok(index)
+    }
+
+    fn rebuild_file_trigrams(&mut self) {
+        self.file_trigrams.clear();
+        for (trigram, file_ids) in &self.index {
+            for &file_id in file_ids {
+                self.file_trigrams
+                    .entry(file_id)
+                    .or_default()
+                    .insert(trigram.clone());
+            }
+        }
     }
 }
 
@@ -96,22 +134,59 @@ impl Default for TrigramIndex {
     }
 }
 
-/// Extract trigrams from text
+/// Extract the grams of normalized text: padded trigrams of each non-CJK
+/// run, and character bigrams of each CJK run. CJK words are short and
+/// carry meaning in every pair of characters, so bigrams let a query match
+/// the middle of a name ("记录" in "会议记录"); a lone CJK character is kept
+/// as a unigram.
 fn extract_trigrams(text: &str) -> Vec<String> {
-    let text = format!("  {}  ", text); // Add padding
-    let chars: Vec<char> = text.chars().collect();
+    let text = normalize(text);
+    let mut grams = Vec::new();
+    let mut run = String::new();
+    let mut run_is_cjk = false;
+    for c in text.chars() {
+        if is_cjk(c) != run_is_cjk && !run.is_empty() {
+            push_run_grams(&run, run_is_cjk, &mut grams);
+            run.clear();
+        }
+        run_is_cjk = is_cjk(c);
+        run.push(c);
+    }
+    push_run_grams(&run, run_is_cjk, &mut grams);
+    grams
+}
 
-    if chars.len() < 3 {
-        return Vec::new();
+fn push_run_grams(run: &str, cjk: bool, grams: &mut Vec<String>) {
+    if cjk {
+        let chars: Vec<char> = run.chars().collect();
+        if chars.len() == 1 {
+            grams.push(run.to_string());
+        }
+        grams.extend(chars.windows(2).map(|pair| pair.iter().collect::<String>()));
+        return;
     }
 
-    let mut trigrams = Vec::new();
-    for i in 0..chars.len() - 2 {
-        let trigram: String = chars[i..i + 3].iter().collect();
-        trigrams.push(trigram);
+    let run = run.trim();
+    if run.is_empty() {
+        return;
     }
+    let chars: Vec<char> = format!("  {}  ", run).chars().collect(); // Add padding
+    grams.extend(chars.windows(3).map(|window| window.iter().collect::<String>()));
+}
 
-    trigrams
+/// Lowercase and without diacritics, with full-width ASCII and the
+/// ideographic space folded to their ASCII forms so "ＲＥＡＤＭＥ" and
+/// "readme" share grams
+fn normalize(text: &str) -> String {
+    let narrow: String = text
+        .chars()
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            '\u{3000}' => ' ',
+            _ => c,
+        })
+        .collect();
+    fold_for_search(&narrow)
 }
 
 #[cfg(test)]
@@ -130,6 +205,68 @@ mod tests {
         assert!(trigrams.contains(&"o  ".to_string()));
     }
 
+    #[test]
+    fn test_cjk_runs_become_bigrams() {
+        assert_eq!(extract_trigrams("会议记录"), vec!["会议", "议记", "记录"]);
+        assert_eq!(extract_trigrams("表"), vec!["表"]);
+
+        let grams = extract_trigrams("Q3会议.docx");
+        assert!(grams.contains(&" q3".to_string()));
+        assert!(grams.contains(&"会议".to_string()));
+        assert!(grams.contains(&"doc".to_string()));
+        // No gram spans the script boundary
+        assert!(!grams.iter().any(|gram| gram.contains('3') && gram.contains('会')));
+    }
+
+    #[test]
+    fn test_width_and_case_are_normalized() {
+        assert_eq!(extract_trigrams("ＲＥＡＤＭＥ"), extract_trigrams("readme"));
+        assert_eq!(extract_trigrams("会议\u{3000}记录"), extract_trigrams("会议 记录"));
+
+        let mut index = TrigramIndex::new();
+        index.add_file("ＲＥＡＤＭＥ.md", 1);
+        assert_eq!(sorted_ids(index.search("Readme")), vec![1]);
+    }
+
+    #[test]
+    fn test_partial_chinese_queries() {
+        /// Padded character trigrams over the whole text, as indexed before
+        fn window_trigrams(text: &str) -> Vec<String> {
+            let chars: Vec<char> = format!("  {}  ", text.to_lowercase()).chars().collect();
+            chars.windows(3).map(|window| window.iter().collect()).collect()
+        }
+        /// Whether `query` reaches the search threshold against `name`
+        fn window_recall(name: &str, query: &str) -> bool {
+            let indexed = window_trigrams(name);
+            let grams = window_trigrams(query);
+            let matched = grams.iter().filter(|gram| indexed.contains(gram)).count();
+            matched as f64 / grams.len() as f64 > 0.3
+        }
+
+        let names = ["会议记录.docx", "项目周报.xlsx", "年度会议纪要.pdf"];
+        let mut index = TrigramIndex::new();
+        for (id, name) in names.iter().enumerate() {
+            index.add_file(name, id);
+        }
+
+        for (query, expected) in [
+            ("会议", vec![0, 2]),
+            ("记录", vec![0]),
+            ("议记", vec![0]),
+            ("周报", vec![1]),
+            ("议纪要", vec![2]),
+            ("纪要", vec![2]),
+        ] {
+            assert_eq!(sorted_ids(index.search(query)), expected, "{}", query);
+        }
+
+        // Queries from the middle or end of a name found nothing before
+        for (name, query) in [(names[0], "记录"), (names[0], "议记"), (names[1], "周报"), (names[2], "纪要")] {
+            assert!(!window_recall(name, query), "{} / {}", name, query);
+        }
+        assert!(index.search("天气").is_empty());
+    }
+
     #[test]
     fn test_trigram_search() {
         let mut index = TrigramIndex::new();
@@ -142,4 +279,92 @@ mod tests {
         assert!(results.iter().any(|(id, _)| *id == 1));
         assert!(results.iter().any(|(id, _)| *id == 2));
     }
+
+    fn file_name(i: usize) -> String {
+        format!("report_{}_{}.txt", i, ["draft", "final", "notes", "summary"][i % 4])
+    }
+
+    fn sorted_ids(results: Vec<(usize, f64)>) -> Vec<usize> {
+        let mut ids: Vec<usize> = results.into_iter().map(|(id, _)| id).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_remove_drops_only_that_file() {
+        let mut index = TrigramIndex::new();
+        index.add_file("hello world", 1);
+        index.add_file("hello there", 2);
+
+        index.remove_file(1);
+        index.remove_file(42); // Unknown ids are ignored
+
+        assert_eq!(sorted_ids(index.search("hello")), vec![2]);
+        assert!(index.search("world").is_empty());
+        // Buckets only "hello world" used are gone
+        assert!(!index.index.contains_key("wor"));
+        assert!(index.index.contains_key("hel"));
+    }
+
+    #[test]
+    fn test_search_unchanged_after_add_remove_cycles() {
+        let mut churned = TrigramIndex::new();
+        for i in 0..200 {
+            churned.add_file(&file_name(i), i);
+        }
+        // Simulate the watcher re-indexing modified files many times
+        for round in 0..20 {
+            for i in (round % 5..200).step_by(5) {
+                churned.remove_file(i);
+                churned.add_file(&file_name(i), i);
+            }
+        }
+        for i in 100..200 {
+            churned.remove_file(i);
+        }
+
+        let mut fresh = TrigramIndex::new();
+        for i in 0..100 {
+            fresh.add_file(&file_name(i), i);
+        }
+
+        for query in ["report", "final", "notes_", "summary", "report_4"] {
+            assert_eq!(sorted_ids(churned.search(query)), sorted_ids(fresh.search(query)), "{}", query);
+        }
+        assert_eq!(churned.trigram_count(), fresh.trigram_count());
+    }
+
+    #[test]
+    fn test_deserialized_index_supports_removal() {
+        let mut index = TrigramIndex::new();
+        index.add_file("hello world", 1);
+        index.add_file("goodbye", 2);
+
+        let mut restored = TrigramIndex::deserialize(&index.serialize().unwrap()).unwrap();
+        restored.remove_file(1);
+
+        assert!(restored.search("hello").is_empty());
+        assert_eq!(sorted_ids(restored.search("goodbye")), vec![2]);
+    }
+
+    #[test]
+    fn test_removal_cost_is_independent_of_index_size() {
+        /// Buckets touched removing the first `removed` files from an index of `total`
+        fn removal_cost(total: usize, removed: usize) -> usize {
+            let mut index = TrigramIndex::new();
+            for i in 0..total {
+                index.add_file(&file_name(i), i);
+            }
+            (0..removed).map(|i| index.remove_file(i)).sum()
+        }
+
+        // Each removal touches only its own file's trigrams
+        let small = removal_cost(1_000, 1_000);
+        assert_eq!(removal_cost(20_000, 1_000), small);
+        let own: usize = (0..1_000)
+            .map(|i| extract_trigrams(&file_name(i)).into_iter().collect::<HashSet<_>>().len())
+            .sum();
+        assert_eq!(small, own);
+        assert_eq!(TrigramIndex::new().remove_file(7), 0);
+    }
 }
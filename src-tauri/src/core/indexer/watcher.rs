@@ -1,18 +1,32 @@
 // File system watcher for incremental indexing
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as NotifyWatcher};
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::Mutex;
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::time::sleep;
+use tokio::time::Instant;
+
+/// How long a path must go without events before its change is handled
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Raw events waiting for the debounce task; when full, the notify thread
+/// waits instead of events piling up
+const EVENT_QUEUE_SIZE: usize = 1024;
 
 pub struct FileWatcher {
     paths: Vec<PathBuf>,
+    /// The running notify watcher; dropping it stops the event stream
+    watcher: Mutex<Option<RecommendedWatcher>>,
 }
 
 impl FileWatcher {
     pub fn new() -> Self {
-        Self { paths: Vec::new() }
+        Self {
+            paths: Vec::new(),
+            watcher: Mutex::new(None),
+        }
     }
 
     /// Add a path to watch
@@ -22,21 +36,57 @@ impl FileWatcher {
         }
     }
 
-    /// Start watching for file changes
-    pub async fn start_watching<F>(
+    /// Paths currently watched
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Add a path, watching it right away if the watcher is running
+    pub fn watch(&mut self, path: PathBuf) -> Result<(), notify::Error> {
+        if self.paths.contains(&path) {
+            return Ok(());
+        }
+        if let Some(watcher) = self.watcher.get_mut().unwrap_or_else(|e| e.into_inner()) {
+            watcher.watch(&path, RecursiveMode::Recursive)?;
+        }
+        self.paths.push(path);
+        Ok(())
+    }
+
+    /// Stop watching a path. Returns false if it was not watched.
+    pub fn unwatch(&mut self, path: &Path) -> bool {
+        let Some(index) = self.paths.iter().position(|p| p == path) else {
+            return false;
+        };
+        self.paths.remove(index);
+        if let Some(watcher) = self.watcher.get_mut().unwrap_or_else(|e| e.into_inner()) {
+            if let Err(e) = watcher.unwatch(path) {
+                tracing::debug!("Failed to unwatch {:?}: {}", path, e);
+            }
+        }
+        true
+    }
+
+    /// Start watching for file changes. `on_change` runs once per changed
+    /// path after [`DEBOUNCE`] without further events for it, one change at
+    /// a time; it should look at the path's current state rather than assume
+    /// what happened to it.
+    pub async fn start_watching<F, Fut>(
         &self,
         on_change: F,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
     where
-        F: Fn(PathBuf) + Send + 'static,
+        F: FnMut(PathBuf) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
     {
-        let (tx, mut rx) = mpsc::channel::<PathBuf>(100);
+        let (tx, rx) = mpsc::channel::<PathBuf>(EVENT_QUEUE_SIZE);
 
         // Create watcher
         let mut watcher = RecommendedWatcher::new(
             move |res: Result<Event, notify::Error>| {
                 if let Ok(event) = res {
                     match event.kind {
+                        // Renames arrive as Modify(Name) with the old and/or new path
                         EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
                             for path in event.paths {
                                 let _ = tx.blocking_send(path);
@@ -56,33 +106,51 @@ impl FileWatcher {
             watcher.watch(path, RecursiveMode::Recursive)?;
         }
 
-        // Keep watcher alive and process events
-        tokio::spawn(async move {
-            let mut debounce_map: std::collections::HashMap<PathBuf, tokio::time::Instant> =
-                std::collections::HashMap::new();
-            let debounce_duration = Duration::from_secs(2);
-
-            while let Some(path) = rx.recv().await {
-                // Debounce: only process if enough time has passed since last event
-                let now = tokio::time::Instant::now();
-                if let Some(&last_time) = debounce_map.get(&path) {
-                    if now.duration_since(last_time) < debounce_duration {
-                        continue;
-                    }
-                }
-                debounce_map.insert(path.clone(), now);
-
-                // Process the change
-                on_change(path);
-            }
+        // Keep the watcher alive for as long as this FileWatcher exists;
+        // the event task ends once it is dropped and the channel closes
+        *self.watcher.lock().unwrap_or_else(|e| e.into_inner()) = Some(watcher);
 
-            // Watcher will be dropped automatically when this task ends
-        });
+        tokio::spawn(debounce(rx, DEBOUNCE, on_change));
 
         Ok(())
     }
 }
 
+/// Calls `on_change` for each path received once `delay` has passed without
+/// another event for it, in the order they became quiet. Runs until `rx`
+/// closes; paths still waiting then are dropped along with the watcher.
+pub(crate) async fn debounce<F, Fut>(mut rx: mpsc::Receiver<PathBuf>, delay: Duration, mut on_change: F)
+where
+    F: FnMut(PathBuf) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    loop {
+        let next_due = pending.values().min().copied();
+        tokio::select! {
+            received = rx.recv() => match received {
+                Some(path) => {
+                    pending.insert(path, Instant::now() + delay);
+                }
+                None => break,
+            },
+            _ = tokio::time::sleep_until(next_due.unwrap_or_else(Instant::now)), if next_due.is_some() => {
+                let now = Instant::now();
+                let mut due: Vec<(PathBuf, Instant)> = pending
+                    .iter()
+                    .filter(|(_, &at)| at <= now)
+                    .map(|(path, &at)| (path.clone(), at))
+                    .collect();
+                due.sort_by_key(|&(_, at)| at);
+                for (path, _) in due {
+                    pending.remove(&path);
+                    on_change(path).await;
+                }
+            }
+        }
+    }
+}
+
 impl Default for FileWatcher {
     fn default() -> Self {
         Self::new()
@@ -101,4 +169,44 @@ mod tests {
         assert_eq!(watcher.paths.len(), 1);
         assert_eq!(watcher.paths[0], path);
     }
+
+    #[tokio::test]
+    async fn test_debounce_coalesces_bursts() {
+        let (tx, rx) = mpsc::channel(EVENT_QUEUE_SIZE);
+        let seen = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let task = tokio::spawn(debounce(rx, Duration::from_millis(50), move |path| {
+            recorded.lock().unwrap().push(path);
+            async {}
+        }));
+
+        for _ in 0..20 {
+            tx.send(PathBuf::from("/busy")).await.unwrap();
+        }
+        tx.send(PathBuf::from("/quiet")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        // A later event for a handled path is handled again
+        tx.send(PathBuf::from("/busy")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        drop(tx);
+        task.await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(
+            *seen,
+            vec![PathBuf::from("/busy"), PathBuf::from("/quiet"), PathBuf::from("/busy")]
+        );
+    }
+
+    #[test]
+    fn test_watch_and_unwatch_before_start() {
+        let mut watcher = FileWatcher::new();
+        let path = PathBuf::from("/test/path");
+        watcher.watch(path.clone()).unwrap();
+        watcher.watch(path.clone()).unwrap();
+        assert_eq!(watcher.paths(), &[path.clone()]);
+        assert!(watcher.unwatch(&path));
+        assert!(!watcher.unwatch(&path));
+        assert!(watcher.paths().is_empty());
+    }
 }
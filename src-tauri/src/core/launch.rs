@@ -0,0 +1,64 @@
+//! Launching search results.
+//!
+//! Deciding how a path is opened is platform independent; the launch itself
+//! goes through `platform::launch_app` for applications and the default
+//! handler for everything else.
+
+use std::path::Path;
+
+/// How a path should be opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchKind {
+    /// Shortcut, executable or app bundle, started with `platform::launch_app`
+    App,
+    /// Shown in Explorer/Finder
+    Folder,
+    /// Opened with the default handler for its type
+    Document,
+}
+
+/// Extensions started as applications (case-insensitive)
+const APP_EXTENSIONS: &[&str] = &["lnk", "exe", "app"];
+
+/// Classify `path`. `.app` bundles are directories but launch as apps.
+pub fn classify(path: &Path) -> LaunchKind {
+    let is_app = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| APP_EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+        .unwrap_or(false);
+
+    if is_app {
+        LaunchKind::App
+    } else if path.is_dir() {
+        LaunchKind::Folder
+    } else {
+        LaunchKind::Document
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_apps_by_extension() {
+        assert_eq!(classify(Path::new(r"C:\Apps\Notepad++.lnk")), LaunchKind::App);
+        assert_eq!(classify(Path::new(r"C:\Windows\notepad.EXE")), LaunchKind::App);
+        assert_eq!(classify(Path::new("/Applications/Safari.app")), LaunchKind::App);
+    }
+
+    #[test]
+    fn test_classify_folders_and_documents() {
+        let dir = std::env::temp_dir().join(format!("omnibox_launch_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("Tool.app")).unwrap();
+        std::fs::write(dir.join("report.pdf"), b"%PDF").unwrap();
+
+        assert_eq!(classify(&dir), LaunchKind::Folder);
+        assert_eq!(classify(&dir.join("Tool.app")), LaunchKind::App);
+        assert_eq!(classify(&dir.join("report.pdf")), LaunchKind::Document);
+        assert_eq!(classify(&dir.join("missing.txt")), LaunchKind::Document);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
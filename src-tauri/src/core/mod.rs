@@ -1,7 +1,27 @@
+pub mod actions;
 pub mod ai;
+pub mod autohide;
+pub mod browser_history;
+pub mod category_filter;
 pub mod clipboard;
+pub mod data_url;
+pub mod diagnostics;
+pub mod elevation;
 pub mod indexer;
+pub mod launch;
+pub mod open_with;
+pub mod palette;
 pub mod parser;
+pub mod pinned;
 pub mod plugin;
+pub mod process;
+pub mod query_generation;
+pub mod quick_note;
+pub mod recent;
+pub mod recovery;
+pub mod refine;
 pub mod screenshot;
+pub mod search_cache;
+pub mod shell;
+pub mod snippet;
 pub mod workflow;
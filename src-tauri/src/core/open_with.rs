@@ -0,0 +1,136 @@
+//! "Open with…": opening a file with an app other than its default handler.
+//!
+//! Building the launch arguments and reading the app list are platform
+//! independent; the launch and the association lookup live in
+//! `platform::windows` and `platform::macos`.
+
+use super::elevation::{shell_open, ShellExecuteRequest};
+use serde::Serialize;
+
+/// An app that can open a file type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OpenWithApp {
+    /// Name shown in the menu, e.g. "Notepad"
+    pub name: String,
+    /// Executable or app bundle passed back to `open_with`
+    pub path: String,
+}
+
+/// Build the ShellExecute request that starts `app_path` with `path` as its
+/// only argument
+pub fn windows_launch(path: &str, app_path: &str) -> ShellExecuteRequest {
+    ShellExecuteRequest {
+        parameters: Some(quote_windows_arg(path)),
+        ..shell_open(app_path)
+    }
+}
+
+/// Arguments for `open` that open `path` in the app bundle `app_path`. Each
+/// is a separate argv entry, so spaces need no quoting.
+pub fn macos_open_args(path: &str, app_path: &str) -> Vec<String> {
+    vec!["-a".to_string(), app_path.to_string(), path.to_string()]
+}
+
+/// Quote `arg` for a Windows command line so that `CommandLineToArgvW` and
+/// the C runtime read it back unchanged: wrapped in quotes when it has
+/// spaces, tabs or quotes, with quotes and the backslashes before them
+/// escaped
+pub fn quote_windows_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        if c == '\\' {
+            backslashes += 1;
+            continue;
+        }
+        let escapes = if c == '"' { backslashes * 2 + 1 } else { backslashes };
+        quoted.extend(std::iter::repeat('\\').take(escapes));
+        quoted.push(c);
+        backslashes = 0;
+    }
+    // Backslashes before the closing quote would escape it
+    quoted.extend(std::iter::repeat('\\').take(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Parse the `name<TAB>path` lines printed by the macOS app lookup. Lines
+/// without a path are skipped; the first entry for a path wins.
+pub fn parse_app_list(output: &str) -> Vec<OpenWithApp> {
+    let mut apps: Vec<OpenWithApp> = Vec::new();
+    for line in output.lines() {
+        let Some((name, path)) = line.split_once('\t') else {
+            continue;
+        };
+        let path = path.trim();
+        if path.is_empty() || apps.iter().any(|app| app.path == path) {
+            continue;
+        }
+        apps.push(OpenWithApp {
+            name: name.trim().to_string(),
+            path: path.to_string(),
+        });
+    }
+    apps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_windows_arg() {
+        assert_eq!(quote_windows_arg(r"C:\notes.txt"), r"C:\notes.txt");
+        assert_eq!(quote_windows_arg(r"C:\My Documents\notes.txt"), r#""C:\My Documents\notes.txt""#);
+        assert_eq!(quote_windows_arg(""), r#""""#);
+        // Backslashes only double before a quote, including the closing one
+        assert_eq!(quote_windows_arg(r"C:\My Folder\"), r#""C:\My Folder\\""#);
+        assert_eq!(quote_windows_arg(r#"say "hi"\"#), r#""say \"hi\"\\""#);
+        assert_eq!(quote_windows_arg(r#"a\"b"#), r#""a\\\"b""#);
+    }
+
+    #[test]
+    fn test_windows_launch_passes_quoted_path() {
+        let request = windows_launch(r"D:\Work Files\plan v2.md", r"C:\Program Files\Editor\editor.exe");
+
+        assert_eq!(request.verb, "open");
+        assert_eq!(request.file, r"C:\Program Files\Editor\editor.exe");
+        assert_eq!(request.parameters.as_deref(), Some(r#""D:\Work Files\plan v2.md""#));
+        assert_eq!(request.directory.as_deref(), Some(r"C:\Program Files\Editor"));
+    }
+
+    #[test]
+    fn test_macos_open_args_keep_spaces_in_single_args() {
+        assert_eq!(
+            macos_open_args("/Users/me/My Notes/todo list.md", "/Applications/Visual Studio Code.app"),
+            vec!["-a", "/Applications/Visual Studio Code.app", "/Users/me/My Notes/todo list.md"]
+        );
+    }
+
+    #[test]
+    fn test_parse_app_list() {
+        let output = "TextEdit\t/System/Applications/TextEdit.app\n\
+                      Visual Studio Code\t/Applications/Visual Studio Code.app\n\
+                      no path\n\
+                      TextEdit\t/System/Applications/TextEdit.app\n";
+
+        assert_eq!(
+            parse_app_list(output),
+            vec![
+                OpenWithApp {
+                    name: "TextEdit".to_string(),
+                    path: "/System/Applications/TextEdit.app".to_string(),
+                },
+                OpenWithApp {
+                    name: "Visual Studio Code".to_string(),
+                    path: "/Applications/Visual Studio Code.app".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,240 @@
+// Command palette: app actions run by name from "cmd <name>"
+use crate::core::plugin::PluginAction;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Score added for each past use, up to [`MAX_USE_BOOST`] uses
+const USE_BOOST: i64 = 4;
+const MAX_USE_BOOST: i64 = 10;
+/// Score added for a command used within the last day
+const RECENT_BOOST: i64 = 30;
+const RECENT_SECS: i64 = 24 * 60 * 60;
+
+/// An action listed in the palette
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaletteCommand {
+    /// Unique in the registry; plugin commands are prefixed with the plugin id
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub description: String,
+    pub handler: PaletteHandler,
+    /// Ask before running; for actions that delete data
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// What selecting a command runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PaletteHandler {
+    /// A Tauri command, invoked by the launcher with `args`
+    Invoke {
+        command: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        args: Option<serde_json::Value>,
+    },
+    /// An action of an enabled plugin, run through `execute_plugin_action`
+    Plugin { plugin_id: String, action: PluginAction },
+}
+
+/// How often and when a command was last run from the palette
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PaletteUsage {
+    pub use_count: i64,
+    /// Unix timestamp
+    pub last_used: i64,
+}
+
+/// The commands the palette searches: the built-ins, then whatever plugins
+/// register
+#[derive(Debug, Clone)]
+pub struct PaletteRegistry {
+    commands: Vec<PaletteCommand>,
+}
+
+impl PaletteRegistry {
+    /// Registry holding only [`builtin_commands`]
+    pub fn new() -> Self {
+        Self {
+            commands: builtin_commands(),
+        }
+    }
+
+    /// Add `command`, replacing any command with the same id
+    pub fn register(&mut self, command: PaletteCommand) {
+        match self.commands.iter_mut().find(|existing| existing.id == command.id) {
+            Some(existing) => *existing = command,
+            None => self.commands.push(command),
+        }
+    }
+
+    pub fn commands(&self) -> &[PaletteCommand] {
+        &self.commands
+    }
+
+    /// Commands matching `query`, best first. Commands run often or
+    /// recently rank higher; an empty query lists every command that way.
+    pub fn search(&self, query: &str, usage: &HashMap<String, PaletteUsage>, now: i64) -> Vec<&PaletteCommand> {
+        let query = query.trim().to_lowercase();
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(i64, &PaletteCommand)> = self
+            .commands
+            .iter()
+            .filter_map(|command| {
+                let score = if query.is_empty() { Some(0) } else { match_score(&matcher, command, &query) }?;
+                Some((score + history_boost(usage.get(&command.id), now), command))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.label.cmp(&b.1.label)));
+        scored.into_iter().map(|(_, command)| command).collect()
+    }
+}
+
+impl Default for PaletteRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The label decides; the description only matches when the label does not
+fn match_score(matcher: &SkimMatcherV2, command: &PaletteCommand, query: &str) -> Option<i64> {
+    let label = command.label.to_lowercase();
+    if label == query {
+        return Some(1000);
+    }
+    matcher
+        .fuzzy_match(&label, query)
+        .or_else(|| matcher.fuzzy_match(&command.description.to_lowercase(), query).map(|score| score / 2))
+}
+
+fn history_boost(usage: Option<&PaletteUsage>, now: i64) -> i64 {
+    let Some(usage) = usage else {
+        return 0;
+    };
+    let recent = if now - usage.last_used < RECENT_SECS { RECENT_BOOST } else { 0 };
+    usage.use_count.min(MAX_USE_BOOST) * USE_BOOST + recent
+}
+
+/// Actions the app itself offers
+pub fn builtin_commands() -> Vec<PaletteCommand> {
+    let invoke = |id: &str, label: &str, description: &str, command: &str, args: Option<serde_json::Value>| {
+        PaletteCommand {
+            id: id.to_string(),
+            label: label.to_string(),
+            description: description.to_string(),
+            handler: PaletteHandler::Invoke {
+                command: command.to_string(),
+                args,
+            },
+            confirm: false,
+        }
+    };
+    vec![
+        invoke(
+            "open-settings",
+            "Open Settings",
+            "Show the settings window",
+            "show_window",
+            Some(serde_json::json!({ "label": "settings" })),
+        ),
+        invoke(
+            "show-clipboard-history",
+            "Show Clipboard History",
+            "Open the clipboard history window",
+            "show_clipboard_window",
+            None,
+        ),
+        PaletteCommand {
+            confirm: true,
+            ..invoke(
+                "clear-clipboard-history",
+                "Clear Clipboard History",
+                "Delete every clipboard item except favorites",
+                "clear_clipboard_history",
+                None,
+            )
+        },
+        invoke("capture-screen", "Capture Screen", "Select a region to capture", "init_capture", None),
+        invoke(
+            "copy-last-capture",
+            "Copy Last Capture",
+            "Put the latest screenshot on the clipboard",
+            "copy_last_capture",
+            None,
+        ),
+        invoke(
+            "refresh-index",
+            "Refresh File Index",
+            "Rescan indexed folders for added and removed files",
+            "refresh_index",
+            None,
+        ),
+        invoke("clear-icon-cache", "Clear Icon Cache", "Delete cached file and app icons", "clear_icon_cache", None),
+        invoke(
+            "reopen-closed",
+            "Reopen Recently Closed",
+            "Bring back the last closed pin or panel",
+            "reopen_recently_closed",
+            None,
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels<'a>(commands: &[&'a PaletteCommand]) -> Vec<&'a str> {
+        commands.iter().map(|command| command.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_fuzzy_abbreviation() {
+        let registry = PaletteRegistry::new();
+        let found = registry.search("clr", &HashMap::new(), 0);
+        assert_eq!(labels(&found)[0], "Clear Clipboard History");
+        assert!(labels(&found).contains(&"Clear Icon Cache"));
+        assert!(!labels(&found).contains(&"Open Settings"));
+    }
+
+    #[test]
+    fn test_history_ranks_used_commands_first() {
+        let registry = PaletteRegistry::new();
+        let now = 1_700_000_000;
+        let usage = HashMap::from([(
+            "clear-icon-cache".to_string(),
+            PaletteUsage { use_count: 3, last_used: now - 60 },
+        )]);
+        assert_eq!(labels(&registry.search("clr", &usage, now))[0], "Clear Icon Cache");
+        assert_eq!(labels(&registry.search("", &usage, now))[0], "Clear Icon Cache");
+    }
+
+    #[test]
+    fn test_register_replaces_by_id() {
+        let mut registry = PaletteRegistry::new();
+        let count = registry.commands().len();
+        let command = PaletteCommand {
+            id: "todo:add".to_string(),
+            label: "Add Todo".to_string(),
+            description: String::new(),
+            handler: PaletteHandler::Plugin {
+                plugin_id: "todo".to_string(),
+                action: PluginAction {
+                    action_type: "add".to_string(),
+                    payload: None,
+                },
+            },
+            confirm: false,
+        };
+        registry.register(command.clone());
+        registry.register(PaletteCommand {
+            label: "New Todo".to_string(),
+            ..command
+        });
+        assert_eq!(registry.commands().len(), count + 1);
+        assert_eq!(labels(&registry.search("todo", &HashMap::new(), 0)), ["New Todo"]);
+    }
+}
@@ -1,168 +1,319 @@
 // Calculator with unit conversion support
+use super::date_math;
+use chrono::Local;
+use once_cell::sync::Lazy;
 use std::collections::HashMap;
 
-pub struct Calculator {
-    conversion_rates: HashMap<String, HashMap<String, f64>>,
+/// Unit categories. Conversions are only allowed within one category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitCategory {
+    Length,
+    Mass,
+    Temperature,
+    Data,
+    Time,
 }
 
-impl Calculator {
-    pub fn new() -> Self {
-        let mut calc = Self {
-            conversion_rates: HashMap::new(),
-        };
-        calc.init_conversions();
-        calc
-    }
-
-    /// Initialize conversion rates for different unit types
-    fn init_conversions(&mut self) {
-        // Length conversions (base unit: meter)
-        let mut length = HashMap::new();
-        length.insert("km".to_string(), 1000.0);
-        length.insert("m".to_string(), 1.0);
-        length.insert("cm".to_string(), 0.01);
-        length.insert("mm".to_string(), 0.001);
-        length.insert("mi".to_string(), 1609.344);
-        length.insert("ft".to_string(), 0.3048);
-        length.insert("in".to_string(), 0.0254);
-        self.conversion_rates.insert("length".to_string(), length);
-
-        // Weight conversions (base unit: gram)
-        let mut weight = HashMap::new();
-        weight.insert("kg".to_string(), 1000.0);
-        weight.insert("g".to_string(), 1.0);
-        weight.insert("mg".to_string(), 0.001);
-        weight.insert("lb".to_string(), 453.592);
-        weight.insert("oz".to_string(), 28.3495);
-        self.conversion_rates.insert("weight".to_string(), weight);
-
-        // Data conversions (base unit: byte)
-        let mut data = HashMap::new();
-        data.insert("TB".to_string(), 1024.0 * 1024.0 * 1024.0 * 1024.0);
-        data.insert("GB".to_string(), 1024.0 * 1024.0 * 1024.0);
-        data.insert("MB".to_string(), 1024.0 * 1024.0);
-        data.insert("KB".to_string(), 1024.0);
-        data.insert("B".to_string(), 1.0);
-        self.conversion_rates.insert("data".to_string(), data);
-
-        // Time conversions (base unit: second)
-        let mut time = HashMap::new();
-        time.insert("d".to_string(), 86400.0);
-        time.insert("h".to_string(), 3600.0);
-        time.insert("min".to_string(), 60.0);
-        time.insert("s".to_string(), 1.0);
-        time.insert("ms".to_string(), 0.001);
-        self.conversion_rates.insert("time".to_string(), time);
+impl UnitCategory {
+    fn name(&self) -> &'static str {
+        match self {
+            UnitCategory::Length => "length",
+            UnitCategory::Mass => "mass",
+            UnitCategory::Temperature => "temperature",
+            UnitCategory::Data => "data size",
+            UnitCategory::Time => "time",
+        }
     }
+}
 
-    /// Evaluate a mathematical expression
-    pub fn evaluate(&self, expression: &str) -> Result<f64, String> {
-        // Check if it's a unit conversion
-        if let Some(result) = self.try_unit_conversion(expression) {
-            return result;
-        }
+/// A unit expressed relative to its category's base unit:
+/// `base = value * factor + offset`.
+///
+/// `offset` is only non-zero for temperatures (base unit: kelvin).
+#[derive(Debug, Clone, Copy)]
+struct Unit {
+    category: UnitCategory,
+    symbol: &'static str,
+    factor: f64,
+    offset: f64,
+}
 
-        // Check if it's a temperature conversion
-        if let Some(result) = self.try_temperature_conversion(expression) {
-            return result;
+/// Static unit table keyed by lowercase alias
+static UNITS: Lazy<HashMap<&'static str, Unit>> = Lazy::new(|| {
+    let mut units = HashMap::new();
+    let mut add = |aliases: &[&'static str], category, symbol, factor, offset| {
+        for alias in aliases {
+            units.insert(*alias, Unit { category, symbol, factor, offset });
         }
+    };
+
+    // Length (base unit: meter)
+    add(&["km", "kilometer", "kilometers", "kilometre", "kilometres"], UnitCategory::Length, "km", 1000.0, 0.0);
+    add(&["m", "meter", "meters", "metre", "metres"], UnitCategory::Length, "m", 1.0, 0.0);
+    add(&["cm", "centimeter", "centimeters"], UnitCategory::Length, "cm", 0.01, 0.0);
+    add(&["mm", "millimeter", "millimeters"], UnitCategory::Length, "mm", 0.001, 0.0);
+    add(&["mi", "mile", "miles"], UnitCategory::Length, "mi", 1609.344, 0.0);
+    add(&["yd", "yard", "yards"], UnitCategory::Length, "yd", 0.9144, 0.0);
+    add(&["ft", "foot", "feet"], UnitCategory::Length, "ft", 0.3048, 0.0);
+    add(&["in", "inch", "inches"], UnitCategory::Length, "in", 0.0254, 0.0);
+
+    // Mass (base unit: gram)
+    add(&["t", "tonne", "tonnes"], UnitCategory::Mass, "t", 1_000_000.0, 0.0);
+    add(&["kg", "kilogram", "kilograms"], UnitCategory::Mass, "kg", 1000.0, 0.0);
+    add(&["g", "gram", "grams"], UnitCategory::Mass, "g", 1.0, 0.0);
+    add(&["mg", "milligram", "milligrams"], UnitCategory::Mass, "mg", 0.001, 0.0);
+    add(&["lb", "lbs", "pound", "pounds"], UnitCategory::Mass, "lb", 453.59237, 0.0);
+    add(&["oz", "ounce", "ounces"], UnitCategory::Mass, "oz", 28.349523125, 0.0);
+
+    // Temperature (base unit: kelvin)
+    add(&["c", "°c", "celsius"], UnitCategory::Temperature, "°C", 1.0, 273.15);
+    add(&["f", "°f", "fahrenheit"], UnitCategory::Temperature, "°F", 5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0);
+    add(&["k", "kelvin"], UnitCategory::Temperature, "K", 1.0, 0.0);
+
+    // Data size (base unit: byte, binary multiples)
+    add(&["b", "byte", "bytes"], UnitCategory::Data, "B", 1.0, 0.0);
+    add(&["kb", "kib", "kilobyte", "kilobytes"], UnitCategory::Data, "KB", 1024.0, 0.0);
+    add(&["mb", "mib", "megabyte", "megabytes"], UnitCategory::Data, "MB", 1024.0 * 1024.0, 0.0);
+    add(&["gb", "gib", "gigabyte", "gigabytes"], UnitCategory::Data, "GB", 1024.0 * 1024.0 * 1024.0, 0.0);
+    add(&["tb", "tib", "terabyte", "terabytes"], UnitCategory::Data, "TB", 1024.0 * 1024.0 * 1024.0 * 1024.0, 0.0);
+
+    // Time (base unit: second)
+    add(&["d", "day", "days"], UnitCategory::Time, "d", 86400.0, 0.0);
+    add(&["h", "hr", "hour", "hours"], UnitCategory::Time, "h", 3600.0, 0.0);
+    add(&["min", "minute", "minutes"], UnitCategory::Time, "min", 60.0, 0.0);
+    add(&["s", "sec", "second", "seconds"], UnitCategory::Time, "s", 1.0, 0.0);
+    add(&["ms", "millisecond", "milliseconds"], UnitCategory::Time, "ms", 0.001, 0.0);
+
+    units
+});
+
+/// ISO 4217 codes recognized as currencies even before rates are loaded
+const KNOWN_CURRENCIES: &[&str] = &[
+    "USD", "EUR", "CNY", "RMB", "JPY", "GBP", "HKD", "TWD", "KRW", "SGD", "AUD", "CAD", "CHF",
+    "NZD", "SEK", "NOK", "DKK", "RUB", "INR", "BRL", "MXN", "THB", "MYR", "IDR", "PHP", "VND",
+    "ZAR", "TRY", "PLN", "AED",
+];
+
+/// A parsed `<value> <unit> to|in <unit>` query
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionQuery {
+    pub value: f64,
+    pub from: String,
+    pub to: String,
+}
 
-        // Otherwise, evaluate as math expression
-        self.evaluate_math(expression)
+/// Parse `<value> <unit> to|in <unit>`, e.g. "10 km in miles" or "100usd to eur".
+///
+/// Units are not validated here; see [`is_unit_conversion`].
+pub fn parse_conversion(expression: &str) -> Option<ConversionQuery> {
+    let parts: Vec<&str> = expression.split_whitespace().collect();
+    if parts.len() < 3 {
+        return None;
     }
 
-    /// Try to parse and convert units
-    fn try_unit_conversion(&self, expression: &str) -> Option<Result<f64, String>> {
-        // Pattern: "100 km to mi" or "100km to mi"
-        let parts: Vec<&str> = expression.split_whitespace().collect();
+    let keyword = parts[parts.len() - 2].to_lowercase();
+    if keyword != "to" && keyword != "in" {
+        return None;
+    }
+    let to = parts[parts.len() - 1].to_string();
 
-        if parts.len() < 3 || parts[parts.len() - 2].to_lowercase() != "to" {
-            return None;
+    // Value and source unit: "100km" or "100 km" (multi-word units are not supported)
+    let (value, from) = match &parts[..parts.len() - 2] {
+        [joined] => {
+            let (value, unit) = split_value_and_unit(joined)?;
+            (value, unit.to_string())
         }
+        [value, unit] => (value.parse().ok()?, unit.to_string()),
+        _ => return None,
+    };
 
-        // Parse value and from_unit
-        let (value, from_unit) = self.parse_value_and_unit(parts[0])?;
-        let to_unit = parts[parts.len() - 1];
-
-        // Find which category these units belong to
-        for (_category, rates) in &self.conversion_rates {
-            if rates.contains_key(from_unit) && rates.contains_key(to_unit) {
-                let from_rate = rates[from_unit];
-                let to_rate = rates[to_unit];
-                let result = value * from_rate / to_rate;
-                return Some(Ok(result));
-            }
-        }
+    Some(ConversionQuery { value, from, to })
+}
+
+/// Whether the input is a conversion between units the calculator knows about
+pub fn is_unit_conversion(expression: &str) -> bool {
+    parse_conversion(expression)
+        .map(|q| is_known_unit(&q.from) && is_known_unit(&q.to))
+        .unwrap_or(false)
+}
+
+/// Whether the input converts to or from a currency (and thus needs rates)
+pub fn is_currency_conversion(expression: &str) -> bool {
+    parse_conversion(expression)
+        .map(|q| is_currency_code(&q.from) || is_currency_code(&q.to))
+        .unwrap_or(false)
+}
+
+fn is_known_unit(unit: &str) -> bool {
+    UNITS.contains_key(unit.to_lowercase().as_str()) || is_currency_code(unit)
+}
+
+fn is_currency_code(unit: &str) -> bool {
+    KNOWN_CURRENCIES.contains(&unit.to_uppercase().as_str())
+}
 
-        Some(Err(format!(
-            "Cannot convert from {} to {}",
-            from_unit, to_unit
-        )))
+/// Normalize currency aliases to the ISO code used by rate providers
+fn currency_code(unit: &str) -> String {
+    match unit.to_uppercase().as_str() {
+        "RMB" => "CNY".to_string(),
+        code => code.to_string(),
     }
+}
 
-    /// Try to parse temperature conversion
-    fn try_temperature_conversion(&self, expression: &str) -> Option<Result<f64, String>> {
-        let parts: Vec<&str> = expression.split_whitespace().collect();
+/// Split "100km" into (100.0, "km")
+fn split_value_and_unit(s: &str) -> Option<(f64, &str)> {
+    let s = s.trim();
 
-        if parts.len() < 3 || parts[parts.len() - 2].to_lowercase() != "to" {
-            return None;
+    // Try to find where the number ends
+    let mut num_end = 0;
+    for (i, c) in s.char_indices() {
+        if c.is_ascii_digit() || c == '.' || (c == '-' && i == 0) {
+            num_end = i + 1;
+        } else {
+            break;
         }
+    }
 
-        let value: f64 = parts[0].parse().ok()?;
-        let from_unit = parts[1];
-        let to_unit = parts[parts.len() - 1];
-
-        // Temperature conversions
-        let result = match (from_unit, to_unit) {
-            ("°C" | "C", "°F" | "F") => value * 9.0 / 5.0 + 32.0,
-            ("°F" | "F", "°C" | "C") => (value - 32.0) * 5.0 / 9.0,
-            ("°C" | "C", "K") => value + 273.15,
-            ("K", "°C" | "C") => value - 273.15,
-            ("°F" | "F", "K") => (value - 32.0) * 5.0 / 9.0 + 273.15,
-            ("K", "°F" | "F") => (value - 273.15) * 9.0 / 5.0 + 32.0,
-            _ => return None,
-        };
+    if num_end == 0 {
+        return None;
+    }
+
+    let value: f64 = s[..num_end].parse().ok()?;
+    let unit = s[num_end..].trim();
+
+    if unit.is_empty() {
+        return None;
+    }
+
+    Some((value, unit))
+}
+
+/// Result of an evaluation, carrying the target unit for conversions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub value: f64,
+    pub unit: Option<String>,
+}
 
-        Some(Ok(result))
+pub struct Calculator {
+    /// Currency rates relative to an arbitrary base (1 base = rate units)
+    currency_rates: Option<HashMap<String, f64>>,
+}
+
+impl Calculator {
+    pub fn new() -> Self {
+        Self { currency_rates: None }
+    }
+
+    /// Create a calculator that can convert currencies with the given rates
+    pub fn with_currency_rates(rates: HashMap<String, f64>) -> Self {
+        Self {
+            currency_rates: Some(rates),
+        }
     }
 
-    /// Parse a value and unit string like "100km" or "100 km"
-    fn parse_value_and_unit<'a>(&self, s: &'a str) -> Option<(f64, &'a str)> {
-        let s = s.trim();
+    /// Evaluate a mathematical expression
+    pub fn evaluate(&self, expression: &str) -> Result<f64, String> {
+        self.evaluate_with_unit(expression).map(|e| e.value)
+    }
 
-        // Try to find where the number ends
-        let mut num_end = 0;
-        for (i, c) in s.char_indices() {
-            if c.is_ascii_digit() || c == '.' || c == '-' {
-                num_end = i + 1;
-            } else {
-                break;
+    /// Evaluate an expression, returning the target unit for conversions
+    pub fn evaluate_with_unit(&self, expression: &str) -> Result<Evaluation, String> {
+        if let Some(query) = parse_conversion(expression) {
+            if is_known_unit(&query.from) || is_known_unit(&query.to) {
+                return self.convert(&query);
             }
         }
 
-        if num_end == 0 {
-            return None;
+        // Otherwise, evaluate as math expression
+        self.evaluate_math(expression).map(|value| Evaluation { value, unit: None })
+    }
+
+    /// Convert between two units of the same category
+    fn convert(&self, query: &ConversionQuery) -> Result<Evaluation, String> {
+        if is_currency_code(&query.from) || is_currency_code(&query.to) {
+            return self.convert_currency(query);
+        }
+
+        let from = lookup_unit(&query.from)?;
+        let to = lookup_unit(&query.to)?;
+
+        if from.category != to.category {
+            return Err(format!(
+                "Cannot convert {} to {}: incompatible units ({} vs {})",
+                query.from,
+                query.to,
+                from.category.name(),
+                to.category.name()
+            ));
         }
 
-        let value: f64 = s[..num_end].parse().ok()?;
-        let unit = s[num_end..].trim();
+        let base = query.value * from.factor + from.offset;
+        let value = (base - to.offset) / to.factor;
+
+        Ok(Evaluation {
+            value,
+            unit: Some(to.symbol.to_string()),
+        })
+    }
 
-        if unit.is_empty() {
-            return None;
+    fn convert_currency(&self, query: &ConversionQuery) -> Result<Evaluation, String> {
+        if !is_currency_code(&query.from) || !is_currency_code(&query.to) {
+            let other = if is_currency_code(&query.from) { &query.to } else { &query.from };
+            let category = lookup_unit(other).map(|u| u.category.name()).unwrap_or("unknown");
+            return Err(format!(
+                "Cannot convert {} to {}: incompatible units (currency vs {})",
+                query.from, query.to, category
+            ));
         }
 
-        Some((value, unit))
+        let rates = self
+            .currency_rates
+            .as_ref()
+            .ok_or_else(|| "Currency rates are not available".to_string())?;
+
+        let from = currency_code(&query.from);
+        let to = currency_code(&query.to);
+        let from_rate = rates
+            .get(&from)
+            .ok_or_else(|| format!("No exchange rate for {}", from))?;
+        let to_rate = rates
+            .get(&to)
+            .ok_or_else(|| format!("No exchange rate for {}", to))?;
+
+        Ok(Evaluation {
+            value: query.value / from_rate * to_rate,
+            unit: Some(to),
+        })
     }
 
-    /// Evaluate a mathematical expression using meval
+    /// Evaluate date/time math ("now + 3 days", "2024-01-01 to today") in the local zone
+    pub fn evaluate_date(&self, expression: &str, language: &str) -> Result<String, String> {
+        let value = date_math::evaluate_in(expression, Local::now())?;
+        Ok(date_math::format_value(&value, language))
+    }
+
+    /// Evaluate a mathematical expression using meval.
+    ///
+    /// Overly long or deeply nested input is rejected up front since meval
+    /// parses recursively, and any panic inside meval becomes an error.
     fn evaluate_math(&self, expression: &str) -> Result<f64, String> {
-        meval::eval_str(expression).map_err(|e| format!("Math error: {}", e))
+        if expression.len() > MAX_EXPRESSION_LEN {
+            return Err("Math error: expression is too long".to_string());
+        }
+        if nesting_depth(expression) > MAX_NESTING_DEPTH {
+            return Err("Math error: expression is nested too deeply".to_string());
+        }
+        std::panic::catch_unwind(|| meval::eval_str(expression))
+            .map_err(|_| "Math error: invalid expression".to_string())?
+            .map_err(|e| format!("Math error: {}", e))
     }
 
-    /// Format result with appropriate precision
-    pub fn format_result(&self, result: f64) -> String {
-        // If result is close to an integer, show as integer
-        if (result - result.round()).abs() < 0.0001 {
+    /// Format result with appropriate precision, appending the unit if any
+    pub fn format_result(&self, result: f64, unit: Option<&str>) -> String {
+        // If result is close to an integer, show as integer (while it still
+        // fits an i64 exactly)
+        let number = if result.abs() < 1e15 && (result - result.round()).abs() < 0.0001 {
             format!("{}", result.round() as i64)
         } else if result.abs() > 1000.0 || result.abs() < 0.001 {
             // Use scientific notation for very large or small numbers
@@ -170,10 +321,43 @@ impl Calculator {
         } else {
             // Otherwise show up to 6 decimal places, trimming trailing zeros
             format!("{:.6}", result).trim_end_matches('0').trim_end_matches('.').to_string()
+        };
+
+        match unit {
+            Some(unit) => format!("{} {}", number, unit),
+            None => number,
         }
     }
 }
 
+/// Longest expression handed to meval
+const MAX_EXPRESSION_LEN: usize = 1000;
+/// Deepest parenthesis nesting handed to meval
+const MAX_NESTING_DEPTH: usize = 64;
+
+fn nesting_depth(expression: &str) -> usize {
+    let mut depth = 0usize;
+    let mut max = 0;
+    for c in expression.chars() {
+        match c {
+            '(' => {
+                depth += 1;
+                max = max.max(depth);
+            }
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    max
+}
+
+fn lookup_unit(unit: &str) -> Result<Unit, String> {
+    UNITS
+        .get(unit.to_lowercase().as_str())
+        .copied()
+        .ok_or_else(|| format!("Unknown unit: {}", unit))
+}
+
 impl Default for Calculator {
     fn default() -> Self {
         Self::new()
@@ -200,6 +384,12 @@ mod tests {
 
         let result = calc.evaluate("100 cm to m").unwrap();
         assert!((result - 1.0).abs() < 0.01);
+
+        let result = calc.evaluate("10 km in miles").unwrap();
+        assert!((result - 6.2137).abs() < 0.001);
+
+        let result = calc.evaluate("12in to cm").unwrap();
+        assert!((result - 30.48).abs() < 0.001);
     }
 
     #[test]
@@ -212,6 +402,17 @@ mod tests {
         assert!(result.abs() < 0.01);
     }
 
+    #[test]
+    fn test_temperature_offsets() {
+        let calc = Calculator::new();
+        // Offsets make these non-proportional: 2x °C is not 2x °F
+        assert!((calc.evaluate("100 c to f").unwrap() - 212.0).abs() < 1e-9);
+        assert!((calc.evaluate("-40 celsius in fahrenheit").unwrap() + 40.0).abs() < 1e-9);
+        assert!((calc.evaluate("98.6 F to C").unwrap() - 37.0).abs() < 1e-9);
+        assert!((calc.evaluate("0 K to °C").unwrap() + 273.15).abs() < 1e-9);
+        assert!((calc.evaluate("212 f to kelvin").unwrap() - 373.15).abs() < 1e-9);
+    }
+
     #[test]
     fn test_data_conversion() {
         let calc = Calculator::new();
@@ -226,11 +427,77 @@ mod tests {
         assert!((result - 1000.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_incompatible_units() {
+        let calc = Calculator::new();
+        let err = calc.evaluate("5 kg to meters").unwrap_err();
+        assert!(err.contains("incompatible"), "{}", err);
+
+        let err = calc.evaluate("5 usd to km").unwrap_err();
+        assert!(err.contains("incompatible"), "{}", err);
+    }
+
+    #[test]
+    fn test_currency_conversion() {
+        let calc = Calculator::new();
+        assert!(calc.evaluate("100 usd to eur").is_err());
+
+        let mut rates = HashMap::new();
+        rates.insert("USD".to_string(), 1.0);
+        rates.insert("EUR".to_string(), 0.5);
+        rates.insert("CNY".to_string(), 7.0);
+        let calc = Calculator::with_currency_rates(rates);
+
+        let eval = calc.evaluate_with_unit("100 usd to eur").unwrap();
+        assert!((eval.value - 50.0).abs() < 1e-9);
+        assert_eq!(eval.unit.as_deref(), Some("EUR"));
+        assert!((calc.evaluate("10 EUR in rmb").unwrap() - 140.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_is_unit_conversion() {
+        assert!(is_unit_conversion("10 km in miles"));
+        assert!(is_unit_conversion("100 usd to eur"));
+        assert!(is_unit_conversion("5 kg to meters"));
+        assert!(!is_unit_conversion("3 songs in folder"));
+        assert!(!is_unit_conversion("how to cook"));
+    }
+
     #[test]
     fn test_format_result() {
         let calc = Calculator::new();
-        assert_eq!(calc.format_result(42.0), "42");
-        assert_eq!(calc.format_result(3.14159), "3.14159");
-        assert_eq!(calc.format_result(1000000.0), "1.0000e6");
+        assert_eq!(calc.format_result(42.0, None), "42");
+        assert_eq!(calc.format_result(3.14159, None), "3.14159");
+        assert_eq!(calc.format_result(1000000.0, None), "1.0000e6");
+        assert_eq!(calc.format_result(212.0, Some("°F")), "212 °F");
+    }
+
+    #[test]
+    fn test_malformed_input_never_panics() {
+        let calc = Calculator::new();
+        let fragments = [
+            "", " ", "(", ")", "((", "))", "+", "-", "*", "/", "^", "%", ".", "..", "1", "0", "9e999",
+            "-1", "sqrt", "sin(", "abs()", "log(0)", "1/0", "0/0", "to", "in", "km", "usd", "°c", "1e",
+            "𝟙", "é", "\u{0}", ",", "max(1,", "2^^2", "--5", "1..2", "1 km to", "to km", "NaN", "inf",
+        ];
+        for a in fragments {
+            for b in fragments {
+                for input in [format!("{}{}", a, b), format!("{} {}", a, b), format!("{} to {}", a, b)] {
+                    let _ = calc.evaluate(&input);
+                }
+            }
+        }
+
+        let deep = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert!(calc.evaluate(&deep).is_err());
+        assert!(calc.evaluate(&"1+".repeat(5_000)).is_err());
+    }
+
+    #[test]
+    fn test_format_huge_and_non_finite_results() {
+        let calc = Calculator::new();
+        assert_eq!(calc.format_result(1e300, None), "1.0000e300");
+        assert_eq!(calc.format_result(f64::INFINITY, None), "inf");
+        assert_eq!(calc.format_result(f64::NAN, None), "NaN");
     }
 }
@@ -0,0 +1,77 @@
+// Currency exchange rates with a 12 hour in-memory cache
+use crate::app::error::{AppError, AppResult};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long fetched rates stay valid
+pub const RATES_TTL: Duration = Duration::from_secs(12 * 60 * 60);
+
+struct CachedRates {
+    endpoint: String,
+    fetched_at: Instant,
+    rates: HashMap<String, f64>,
+}
+
+static RATES_CACHE: Lazy<Mutex<Option<CachedRates>>> = Lazy::new(|| Mutex::new(None));
+
+/// Expected response shape: `{ "rates": { "USD": 1.0, "EUR": 0.92, ... } }`
+/// (compatible with open.er-api.com, exchangerate.host and frankfurter.app)
+#[derive(Debug, Deserialize)]
+struct RatesResponse {
+    #[serde(default, alias = "base_code")]
+    base: Option<String>,
+    rates: HashMap<String, f64>,
+}
+
+/// Get exchange rates from `endpoint`, reusing cached rates for [`RATES_TTL`]
+pub async fn get_rates(endpoint: &str) -> AppResult<HashMap<String, f64>> {
+    {
+        let cache = RATES_CACHE.lock();
+        if let Some(cached) = cache.as_ref() {
+            if cached.endpoint == endpoint && cached.fetched_at.elapsed() < RATES_TTL {
+                return Ok(cached.rates.clone());
+            }
+        }
+    }
+
+    let response = reqwest::Client::new()
+        .get(endpoint)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to fetch exchange rates: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(AppError::Api(format!(
+            "Exchange rate API error: {}",
+            response.status()
+        )));
+    }
+
+    let body: RatesResponse = response
+        .json()
+        .await
+        .map_err(|e| AppError::Parse(format!("Invalid exchange rate response: {}", e)))?;
+
+    // Keys are normalized so lookups by ISO code work regardless of provider casing
+    let mut rates: HashMap<String, f64> = body
+        .rates
+        .into_iter()
+        .map(|(code, rate)| (code.to_uppercase(), rate))
+        .collect();
+    // Some providers omit the base currency from `rates`
+    if let Some(base) = body.base {
+        rates.entry(base.to_uppercase()).or_insert(1.0);
+    }
+
+    *RATES_CACHE.lock() = Some(CachedRates {
+        endpoint: endpoint.to_string(),
+        fetched_at: Instant::now(),
+        rates: rates.clone(),
+    });
+
+    Ok(rates)
+}
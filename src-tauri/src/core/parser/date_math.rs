@@ -0,0 +1,566 @@
+// Date/time arithmetic: "now + 3 days", "2024-01-01 to today", "today - 2h30m"
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Weekday,
+};
+
+/// Starting point of a date expression
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BaseDate {
+    Now,
+    Today,
+    Tomorrow,
+    Yesterday,
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl BaseDate {
+    fn is_date_only(&self) -> bool {
+        !matches!(self, BaseDate::Now | BaseDate::DateTime(_))
+    }
+}
+
+/// A duration split into calendar parts (months, days) and exact seconds.
+///
+/// Calendar parts are applied to the wall clock so "+1 day" keeps the time of
+/// day across DST changes, while "+24h" is always exactly 24 hours.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct DateDuration {
+    months: u32,
+    days: i64,
+    seconds: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DateExpr {
+    Offset {
+        base: BaseDate,
+        negative: bool,
+        duration: DateDuration,
+    },
+    Between {
+        from: BaseDate,
+        to: BaseDate,
+    },
+}
+
+/// Evaluated date expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum DateValue<Tz: TimeZone> {
+    /// A point in time; `date_only` when no time of day is involved
+    Instant { at: DateTime<Tz>, date_only: bool },
+    /// The span between two dates
+    Span { duration: Duration, date_only: bool },
+}
+
+/// Whether the input looks like date math
+pub fn is_date_expression(input: &str) -> bool {
+    parse_expression(input).is_some()
+}
+
+/// Evaluate a date expression relative to `now`, in `now`'s time zone
+pub fn evaluate_in<Tz: TimeZone>(input: &str, now: DateTime<Tz>) -> Result<DateValue<Tz>, String> {
+    let expr = parse_expression(input).ok_or_else(|| format!("Not a date expression: {}", input))?;
+
+    match expr {
+        DateExpr::Offset {
+            base,
+            negative,
+            duration,
+        } => {
+            let start = resolve_base(base, &now)?;
+            let at = apply_duration(start, duration, negative)?;
+            Ok(DateValue::Instant {
+                at,
+                date_only: base.is_date_only() && duration.seconds == 0,
+            })
+        }
+        DateExpr::Between { from, to } => {
+            let date_only = from.is_date_only() && to.is_date_only();
+            let start = resolve_base(from, &now)?;
+            let end = resolve_base(to, &now)?;
+            let duration = if date_only {
+                // Count calendar days so DST days still count as one day
+                Duration::days((end.date_naive() - start.date_naive()).num_days())
+            } else {
+                end - start
+            };
+            Ok(DateValue::Span { duration, date_only })
+        }
+    }
+}
+
+/// Format a value for display. `language` selects Chinese ("zh*") or English output.
+pub fn format_value<Tz: TimeZone>(value: &DateValue<Tz>, language: &str) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let zh = language.to_lowercase().starts_with("zh");
+
+    match value {
+        DateValue::Instant { at, date_only } => {
+            let weekday = weekday_name(at.weekday(), zh);
+            match (zh, date_only) {
+                (true, true) => format!("{} {}", at.format("%Y年%m月%d日"), weekday),
+                (true, false) => format!("{} {} {}", at.format("%Y年%m月%d日"), weekday, at.format("%H:%M")),
+                (false, true) => format!("{} ({})", at.format("%Y-%m-%d"), weekday),
+                (false, false) => format!("{} ({})", at.format("%Y-%m-%d %H:%M"), weekday),
+            }
+        }
+        DateValue::Span { duration, .. } => format_span(*duration, zh),
+    }
+}
+
+fn weekday_name(day: Weekday, zh: bool) -> &'static str {
+    if zh {
+        match day {
+            Weekday::Mon => "星期一",
+            Weekday::Tue => "星期二",
+            Weekday::Wed => "星期三",
+            Weekday::Thu => "星期四",
+            Weekday::Fri => "星期五",
+            Weekday::Sat => "星期六",
+            Weekday::Sun => "星期日",
+        }
+    } else {
+        match day {
+            Weekday::Mon => "Mon",
+            Weekday::Tue => "Tue",
+            Weekday::Wed => "Wed",
+            Weekday::Thu => "Thu",
+            Weekday::Fri => "Fri",
+            Weekday::Sat => "Sat",
+            Weekday::Sun => "Sun",
+        }
+    }
+}
+
+fn format_span(duration: Duration, zh: bool) -> String {
+    let total = duration.num_seconds();
+    let sign = if total < 0 { "-" } else { "" };
+    let total = total.abs();
+
+    let parts = [
+        (total / 86400, "天", "day"),
+        (total % 86400 / 3600, "小时", "hour"),
+        (total % 3600 / 60, "分钟", "minute"),
+        (total % 60, "秒", "second"),
+    ];
+
+    let mut out: Vec<String> = parts
+        .iter()
+        .filter(|(n, _, _)| *n > 0)
+        .map(|(n, zh_unit, en_unit)| {
+            if zh {
+                format!("{}{}", n, zh_unit)
+            } else if *n == 1 {
+                format!("{} {}", n, en_unit)
+            } else {
+                format!("{} {}s", n, en_unit)
+            }
+        })
+        .collect();
+
+    if out.is_empty() {
+        out.push(if zh { "0天".to_string() } else { "0 days".to_string() });
+    }
+
+    let sep = if zh { "" } else { " " };
+    format!("{}{}", sign, out.join(sep))
+}
+
+fn parse_expression(input: &str) -> Option<DateExpr> {
+    let s = input.trim().to_lowercase();
+    if s.is_empty() {
+        return None;
+    }
+
+    // "<date> to <date>" / "<date> until <date>"
+    for keyword in [" to ", " until "] {
+        if let Some((a, b)) = s.split_once(keyword) {
+            return Some(DateExpr::Between {
+                from: parse_base(a)?,
+                to: parse_base(b)?,
+            });
+        }
+    }
+
+    // "<date> + <duration>"
+    if let Some((a, b)) = s.split_once('+') {
+        return Some(DateExpr::Offset {
+            base: parse_base(a)?,
+            negative: false,
+            duration: parse_duration(b)?,
+        });
+    }
+
+    // "<date> - <duration>" or "<date> - <date>" (spaces required: dates contain '-')
+    if let Some((a, b)) = s.split_once(" - ") {
+        let base = parse_base(a)?;
+        if let Some(duration) = parse_duration(b) {
+            return Some(DateExpr::Offset {
+                base,
+                negative: true,
+                duration,
+            });
+        }
+        return Some(DateExpr::Between {
+            from: parse_base(b)?,
+            to: base,
+        });
+    }
+
+    None
+}
+
+fn parse_base(s: &str) -> Option<BaseDate> {
+    let s = s.trim();
+    match s {
+        "now" => return Some(BaseDate::Now),
+        "today" => return Some(BaseDate::Today),
+        "tomorrow" => return Some(BaseDate::Tomorrow),
+        "yesterday" => return Some(BaseDate::Yesterday),
+        _ => {}
+    }
+
+    for fmt in ["%Y-%m-%d", "%Y/%m/%d"] {
+        if let Ok(date) = NaiveDate::parse_from_str(s, fmt) {
+            return Some(BaseDate::Date(date));
+        }
+    }
+
+    for fmt in [
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dt%H:%M",
+        "%Y-%m-%dt%H:%M:%S",
+        "%Y/%m/%d %H:%M",
+        "%Y/%m/%d %H:%M:%S",
+    ] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, fmt) {
+            return Some(BaseDate::DateTime(dt));
+        }
+    }
+
+    None
+}
+
+/// Parse durations like "3 days", "2h30m", "1 week 2 days", "1y 6mo"
+fn parse_duration(s: &str) -> Option<DateDuration> {
+    let mut duration = DateDuration::default();
+    let mut chars = s.trim().chars().peekable();
+    let mut parsed_any = false;
+
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace() || *c == ',') {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut number = String::new();
+        while let Some(c) = chars.peek().copied().filter(|c| c.is_ascii_digit()) {
+            number.push(c);
+            chars.next();
+        }
+        let amount: i64 = number.parse().ok()?;
+
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        let mut unit = String::new();
+        while let Some(c) = chars.peek().copied().filter(|c| c.is_alphabetic()) {
+            unit.push(c);
+            chars.next();
+        }
+
+        match unit.as_str() {
+            "y" | "yr" | "yrs" | "year" | "years" => {
+                duration.months = duration.months.checked_add(u32::try_from(amount.checked_mul(12)?).ok()?)?
+            }
+            "mo" | "mon" | "month" | "months" => {
+                duration.months = duration.months.checked_add(u32::try_from(amount).ok()?)?
+            }
+            "w" | "wk" | "wks" | "week" | "weeks" => {
+                duration.days = duration.days.checked_add(amount.checked_mul(7)?)?
+            }
+            "d" | "day" | "days" => duration.days = duration.days.checked_add(amount)?,
+            "h" | "hr" | "hrs" | "hour" | "hours" => {
+                duration.seconds = duration.seconds.checked_add(amount.checked_mul(3600)?)?
+            }
+            "m" | "min" | "mins" | "minute" | "minutes" => {
+                duration.seconds = duration.seconds.checked_add(amount.checked_mul(60)?)?
+            }
+            "s" | "sec" | "secs" | "second" | "seconds" => duration.seconds = duration.seconds.checked_add(amount)?,
+            _ => return None,
+        }
+        parsed_any = true;
+    }
+
+    // Both parts must fit a `Duration` for `apply_duration`
+    Duration::try_days(duration.days)?;
+    Duration::try_seconds(duration.seconds)?;
+    parsed_any.then_some(duration)
+}
+
+fn resolve_base<Tz: TimeZone>(base: BaseDate, now: &DateTime<Tz>) -> Result<DateTime<Tz>, String> {
+    let tz = now.timezone();
+    let today = now.date_naive();
+
+    let naive = match base {
+        BaseDate::Now => return Ok(now.clone()),
+        BaseDate::Today => today.and_time(NaiveTime::MIN),
+        BaseDate::Tomorrow => today.succ_opt().ok_or("Date out of range")?.and_time(NaiveTime::MIN),
+        BaseDate::Yesterday => today.pred_opt().ok_or("Date out of range")?.and_time(NaiveTime::MIN),
+        BaseDate::Date(date) => date.and_time(NaiveTime::MIN),
+        BaseDate::DateTime(dt) => dt,
+    };
+
+    resolve_local(&tz, naive)
+}
+
+/// Map a wall-clock time to the zone. Times skipped by a DST jump move forward
+/// by the gap; repeated times pick the earlier occurrence.
+fn resolve_local<Tz: TimeZone>(tz: &Tz, naive: NaiveDateTime) -> Result<DateTime<Tz>, String> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => Ok(dt),
+        LocalResult::Ambiguous(earliest, _) => Ok(earliest),
+        LocalResult::None => match tz.from_local_datetime(&(naive + Duration::hours(1))) {
+            LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => Ok(dt),
+            LocalResult::None => Err(format!("Invalid local time: {}", naive)),
+        },
+    }
+}
+
+fn apply_duration<Tz: TimeZone>(
+    start: DateTime<Tz>,
+    duration: DateDuration,
+    negative: bool,
+) -> Result<DateTime<Tz>, String> {
+    let overflow = || "Date out of range".to_string();
+    let tz = start.timezone();
+    let mut naive = start.naive_local();
+
+    // Calendar parts on the wall clock; month arithmetic clamps to month end
+    if duration.months > 0 {
+        let months = Months::new(duration.months);
+        naive = if negative {
+            naive.checked_sub_months(months)
+        } else {
+            naive.checked_add_months(months)
+        }
+        .ok_or_else(overflow)?;
+    }
+    let days = Duration::try_days(duration.days).ok_or_else(overflow)?;
+    naive = if negative {
+        naive.checked_sub_signed(days)
+    } else {
+        naive.checked_add_signed(days)
+    }
+    .ok_or_else(overflow)?;
+
+    let at = if duration.months > 0 || duration.days != 0 {
+        resolve_local(&tz, naive)?
+    } else {
+        start
+    };
+
+    // Exact parts on the timeline
+    let seconds = Duration::try_seconds(duration.seconds).ok_or_else(overflow)?;
+    if negative {
+        at.checked_sub_signed(seconds)
+    } else {
+        at.checked_add_signed(seconds)
+    }
+    .ok_or_else(overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Timelike, Utc};
+
+    /// US Eastern time for 2024: DST from 2024-03-10 02:00 to 2024-11-03 02:00 local
+    #[derive(Debug, Clone, Copy)]
+    struct Eastern2024;
+
+    fn est() -> FixedOffset {
+        FixedOffset::west_opt(5 * 3600).unwrap()
+    }
+
+    fn edt() -> FixedOffset {
+        FixedOffset::west_opt(4 * 3600).unwrap()
+    }
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap()
+    }
+
+    impl TimeZone for Eastern2024 {
+        type Offset = FixedOffset;
+
+        fn from_offset(_offset: &FixedOffset) -> Self {
+            Eastern2024
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(&self, dt: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let spring = local(2024, 3, 10, 2, 0);
+            let fall = local(2024, 11, 3, 1, 0);
+            if *dt < spring {
+                LocalResult::Single(est())
+            } else if *dt < spring + Duration::hours(1) {
+                LocalResult::None
+            } else if *dt < fall {
+                LocalResult::Single(edt())
+            } else if *dt < fall + Duration::hours(1) {
+                LocalResult::Ambiguous(edt(), est())
+            } else {
+                LocalResult::Single(est())
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            if *utc >= local(2024, 3, 10, 7, 0) && *utc < local(2024, 11, 3, 6, 0) {
+                edt()
+            } else {
+                est()
+            }
+        }
+    }
+
+    fn eastern_now() -> DateTime<Eastern2024> {
+        Eastern2024.from_local_datetime(&local(2024, 3, 1, 9, 0)).unwrap()
+    }
+
+    fn instant(expr: &str) -> DateTime<Eastern2024> {
+        match evaluate_in(expr, eastern_now()).unwrap() {
+            DateValue::Instant { at, .. } => at,
+            other => panic!("expected instant for {}, got {:?}", expr, other),
+        }
+    }
+
+    fn span(expr: &str) -> Duration {
+        match evaluate_in(expr, eastern_now()).unwrap() {
+            DateValue::Span { duration, .. } => duration,
+            other => panic!("expected span for {}, got {:?}", expr, other),
+        }
+    }
+
+    #[test]
+    fn test_detection() {
+        assert!(is_date_expression("now + 3 days"));
+        assert!(is_date_expression("2024-01-01 to today"));
+        assert!(is_date_expression("today - 2h30m"));
+        assert!(is_date_expression("2024-12-25 - 2024-01-01"));
+        assert!(!is_date_expression("10 km to miles"));
+        assert!(!is_date_expression("2 + 3"));
+        assert!(!is_date_expression("now"));
+    }
+
+    #[test]
+    fn test_duration_parsing() {
+        assert_eq!(
+            parse_duration("2h30m"),
+            Some(DateDuration { months: 0, days: 0, seconds: 9000 })
+        );
+        assert_eq!(
+            parse_duration("3 weeks"),
+            Some(DateDuration { months: 0, days: 21, seconds: 0 })
+        );
+        assert_eq!(
+            parse_duration("1y 2mo, 1 day"),
+            Some(DateDuration { months: 14, days: 1, seconds: 0 })
+        );
+        assert_eq!(parse_duration("3 parsecs"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn test_large_durations_do_not_overflow() {
+        // Too big for a `Duration` at all
+        assert_eq!(parse_duration("999999999999999 days"), None);
+        assert_eq!(parse_duration("9223372036854775807 days 1 day"), None);
+        assert_eq!(parse_duration("9223372036854775807 seconds"), None);
+        // Valid durations that leave the supported date range
+        assert!(evaluate_in("now + 99999999999 days", eastern_now()).is_err());
+        assert!(evaluate_in("now - 9999999999 weeks", eastern_now()).is_err());
+        assert!(evaluate_in("now + 9999999999999 seconds", eastern_now()).is_err());
+        assert!(evaluate_in("now + 4000000000 months", eastern_now()).is_err());
+    }
+
+    #[test]
+    fn test_relative_to_now() {
+        assert_eq!(instant("now + 3 days").naive_local(), local(2024, 3, 4, 9, 0));
+        assert_eq!(instant("today - 1 week").naive_local(), local(2024, 2, 23, 0, 0));
+        assert_eq!(instant("tomorrow + 2h30m").naive_local(), local(2024, 3, 2, 2, 30));
+    }
+
+    #[test]
+    fn test_dst_spring_forward() {
+        // Calendar day keeps the wall clock; 24h is exact and shifts by the lost hour
+        assert_eq!(instant("2024-03-09 12:00 + 1 day").naive_local(), local(2024, 3, 10, 12, 0));
+        assert_eq!(instant("2024-03-09 12:00 + 24h").naive_local(), local(2024, 3, 10, 13, 0));
+        assert_eq!(instant("2024-03-10 01:30 + 1h").naive_local(), local(2024, 3, 10, 3, 30));
+
+        // Landing in the skipped hour moves forward
+        let at = instant("2024-03-09 02:30 + 1 day");
+        assert_eq!(at.naive_local(), local(2024, 3, 10, 3, 30));
+        assert_eq!(at.hour(), 3);
+
+        // The DST day is 23 hours long but still one calendar day
+        assert_eq!(span("2024-03-10 00:00 to 2024-03-11 00:00"), Duration::hours(23));
+        assert_eq!(span("2024-03-10 to 2024-03-11"), Duration::days(1));
+    }
+
+    #[test]
+    fn test_dst_fall_back() {
+        // Repeated hour resolves to the first (daylight) occurrence
+        let at = instant("2024-11-02 01:30 + 1 day");
+        assert_eq!(at.naive_local(), local(2024, 11, 3, 1, 30));
+        assert_eq!(at.offset(), &edt());
+
+        assert_eq!(span("2024-11-03 00:00 to 2024-11-04 00:00"), Duration::hours(25));
+        assert_eq!(
+            instant("2024-11-03 00:00 + 24h").with_timezone(&Utc).naive_utc(),
+            local(2024, 11, 4, 4, 0)
+        );
+    }
+
+    #[test]
+    fn test_month_length_edges() {
+        assert_eq!(instant("2024-01-31 + 1 month").date_naive(), NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(instant("2023-01-31 + 1 month").date_naive(), NaiveDate::from_ymd_opt(2023, 2, 28).unwrap());
+        assert_eq!(instant("2024-03-31 - 1 month").date_naive(), NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(instant("2024-02-29 + 1 year").date_naive(), NaiveDate::from_ymd_opt(2025, 2, 28).unwrap());
+        assert_eq!(instant("2024-02-28 + 2 days").date_naive(), NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+    }
+
+    #[test]
+    fn test_between_dates() {
+        assert_eq!(span("2024-01-01 to 2024-12-31"), Duration::days(365));
+        assert_eq!(span("2024-12-25 - 2024-01-01"), Duration::days(359));
+        assert_eq!(span("2024-03-01 to 2024-02-01"), Duration::days(-29));
+    }
+
+    #[test]
+    fn test_format_value() {
+        let value = evaluate_in("2024-01-31 + 1 month", eastern_now()).unwrap();
+        assert_eq!(format_value(&value, "en"), "2024-02-29 (Thu)");
+        assert_eq!(format_value(&value, "zh-CN"), "2024年02月29日 星期四");
+
+        let value = evaluate_in("2024-03-01 09:00 to 2024-03-02 11:30", eastern_now()).unwrap();
+        assert_eq!(format_value(&value, "en"), "1 day 2 hours 30 minutes");
+        assert_eq!(format_value(&value, "zh"), "1天2小时30分钟");
+    }
+}
@@ -0,0 +1,224 @@
+// Word definitions from a dictionary API, cached in memory for a day
+use crate::app::error::{AppError, AppResult};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long a lookup, including "not found", stays cached
+pub const DEFINITIONS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Cached lookups kept before expired ones are dropped
+const CACHE_CAPACITY: usize = 256;
+/// Definitions shown for one word
+pub const MAX_DEFINITIONS: usize = 8;
+
+/// One sense of a word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    /// "noun", "verb", ...; empty when the API gives none
+    pub part_of_speech: String,
+    pub text: String,
+    pub example: Option<String>,
+}
+
+impl Definition {
+    /// Secondary line of a result: the part of speech and the example
+    pub fn detail(&self) -> String {
+        match (&self.part_of_speech, &self.example) {
+            (pos, Some(example)) if !pos.is_empty() => format!("{} · “{}”", pos, example),
+            (_, Some(example)) => format!("“{}”", example),
+            (pos, None) => pos.clone(),
+        }
+    }
+}
+
+/// Response shape of dictionaryapi.dev and compatible APIs:
+/// `[{ "word": "...", "meanings": [{ "partOfSpeech": "noun", "definitions": [{ "definition": "...", "example": "..." }] }] }]`
+#[derive(Debug, Deserialize)]
+struct Entry {
+    #[serde(default)]
+    meanings: Vec<Meaning>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Meaning {
+    #[serde(default)]
+    part_of_speech: String,
+    #[serde(default)]
+    definitions: Vec<Sense>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Sense {
+    definition: String,
+    #[serde(default)]
+    example: Option<String>,
+}
+
+struct CachedLookup {
+    fetched_at: Instant,
+    definitions: Vec<Definition>,
+}
+
+/// Lookups by request URL
+static DEFINITIONS_CACHE: Lazy<Mutex<HashMap<String, CachedLookup>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Trim and collapse inner whitespace, so "  ice   cream " and "ice cream"
+/// share a cache entry
+pub fn normalize_word(input: &str) -> String {
+    input.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// The lookup URL: `url_template` with `{word}` replaced by the encoded word
+pub fn lookup_url(url_template: &str, word: &str) -> String {
+    url_template.replace("{word}", &urlencoding::encode(word))
+}
+
+/// Definitions from a response body, in the API's order, at most
+/// [`MAX_DEFINITIONS`]
+pub fn parse_definitions(body: &str) -> AppResult<Vec<Definition>> {
+    let entries: Vec<Entry> =
+        serde_json::from_str(body).map_err(|e| AppError::Parse(format!("Invalid dictionary response: {}", e)))?;
+    Ok(entries
+        .into_iter()
+        .flat_map(|entry| entry.meanings)
+        .flat_map(|meaning| {
+            let part_of_speech = meaning.part_of_speech;
+            meaning.definitions.into_iter().map(move |sense| Definition {
+                part_of_speech: part_of_speech.clone(),
+                text: sense.definition.trim().to_string(),
+                example: sense.example.map(|e| e.trim().to_string()).filter(|e| !e.is_empty()),
+            })
+        })
+        .filter(|definition| !definition.text.is_empty())
+        .take(MAX_DEFINITIONS)
+        .collect())
+}
+
+/// Look up `word` (a word or phrase) with the API at `url_template`. A word
+/// the API does not know gives an empty list, not an error.
+pub async fn define(url_template: &str, word: &str) -> AppResult<Vec<Definition>> {
+    let url = lookup_url(url_template, &normalize_word(word));
+    {
+        let cache = DEFINITIONS_CACHE.lock();
+        if let Some(cached) = cache.get(&url) {
+            if cached.fetched_at.elapsed() < DEFINITIONS_TTL {
+                return Ok(cached.definitions.clone());
+            }
+        }
+    }
+
+    let response = reqwest::Client::new()
+        .get(&url)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to reach the dictionary: {}", e)))?;
+
+    let definitions = if response.status() == reqwest::StatusCode::NOT_FOUND {
+        Vec::new()
+    } else if response.status().is_success() {
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AppError::Network(format!("Failed to read the dictionary response: {}", e)))?;
+        parse_definitions(&body)?
+    } else {
+        return Err(AppError::Api(format!("Dictionary API error: {}", response.status())));
+    };
+
+    let mut cache = DEFINITIONS_CACHE.lock();
+    if cache.len() >= CACHE_CAPACITY {
+        cache.retain(|_, cached| cached.fetched_at.elapsed() < DEFINITIONS_TTL);
+        if cache.len() >= CACHE_CAPACITY {
+            cache.clear();
+        }
+    }
+    cache.insert(
+        url,
+        CachedLookup {
+            fetched_at: Instant::now(),
+            definitions: definitions.clone(),
+        },
+    );
+    Ok(definitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERENDIPITY: &str = r#"[
+        {
+            "word": "serendipity",
+            "phonetic": "/ˌsɛɹ.ənˈdɪp.ɪ.ti/",
+            "meanings": [
+                {
+                    "partOfSpeech": "noun",
+                    "definitions": [
+                        {
+                            "definition": "An unsought, unintended, and/or unexpected, but fortunate, discovery.",
+                            "example": "Finding the book was pure serendipity.",
+                            "synonyms": []
+                        },
+                        { "definition": "  The faculty of making such discoveries. " }
+                    ]
+                }
+            ]
+        },
+        {
+            "word": "serendipity",
+            "meanings": [{ "definitions": [{ "definition": "A happy accident.", "example": "" }] }]
+        }
+    ]"#;
+
+    #[test]
+    fn test_parse_and_render_definitions() {
+        let definitions = parse_definitions(SERENDIPITY).unwrap();
+
+        let lines: Vec<(&str, String)> = definitions
+            .iter()
+            .map(|definition| (definition.text.as_str(), definition.detail()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (
+                    "An unsought, unintended, and/or unexpected, but fortunate, discovery.",
+                    "noun · “Finding the book was pure serendipity.”".to_string()
+                ),
+                ("The faculty of making such discoveries.", "noun".to_string()),
+                ("A happy accident.", String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_definitions_are_capped() {
+        let senses: Vec<String> = (0..20).map(|i| format!(r#"{{ "definition": "sense {}" }}"#, i)).collect();
+        let body = format!(r#"[{{ "meanings": [{{ "partOfSpeech": "verb", "definitions": [{}] }}] }}]"#, senses.join(","));
+
+        let definitions = parse_definitions(&body).unwrap();
+        assert_eq!(definitions.len(), MAX_DEFINITIONS);
+        assert_eq!(definitions[0].text, "sense 0");
+    }
+
+    #[test]
+    fn test_invalid_response() {
+        assert!(matches!(
+            parse_definitions(r#"{ "title": "No Definitions Found" }"#),
+            Err(AppError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_phrases_are_normalized_and_encoded() {
+        assert_eq!(normalize_word("  Ice   Cream "), "ice cream");
+        assert_eq!(
+            lookup_url("https://api.dictionaryapi.dev/api/v2/entries/en/{word}", "ice cream"),
+            "https://api.dictionaryapi.dev/api/v2/entries/en/ice%20cream"
+        );
+    }
+}
@@ -0,0 +1,430 @@
+// Bundled emoji and symbol table, compiled into the binary.
+//
+// Names follow the Unicode CLDR short names; shortcodes are the common
+// GitHub/Slack ones. `tone!` marks emoji that take skin-tone modifiers.
+use super::Emoji;
+
+macro_rules! emoji {
+    ($glyph:expr, $name:expr, [$($code:expr),*], [$($keyword:expr),*]) => {
+        Emoji { glyph: $glyph, name: $name, shortcodes: &[$($code),*], keywords: &[$($keyword),*], skin_tones: false }
+    };
+}
+
+macro_rules! tone {
+    ($glyph:expr, $name:expr, [$($code:expr),*], [$($keyword:expr),*]) => {
+        Emoji { glyph: $glyph, name: $name, shortcodes: &[$($code),*], keywords: &[$($keyword),*], skin_tones: true }
+    };
+}
+
+pub static EMOJI: &[Emoji] = &[
+    // Smileys
+    emoji!("😀", "grinning face", ["grinning"], ["smile", "happy"]),
+    emoji!("😃", "grinning face with big eyes", ["smiley"], ["smile", "happy"]),
+    emoji!("😄", "grinning face with smiling eyes", ["smile"], ["happy", "joy"]),
+    emoji!("😁", "beaming face with smiling eyes", ["grin"], ["happy"]),
+    emoji!("😆", "grinning squinting face", ["laughing", "satisfied"], ["laugh", "happy"]),
+    emoji!("😅", "grinning face with sweat", ["sweat_smile"], ["relief", "nervous"]),
+    emoji!("🤣", "rolling on the floor laughing", ["rofl"], ["laugh", "lol"]),
+    emoji!("😂", "face with tears of joy", ["joy"], ["laugh", "lol", "cry"]),
+    emoji!("🙂", "slightly smiling face", ["slightly_smiling_face"], ["smile"]),
+    emoji!("🙃", "upside-down face", ["upside_down_face"], ["sarcasm", "silly"]),
+    emoji!("😉", "winking face", ["wink"], ["flirt"]),
+    emoji!("😊", "smiling face with smiling eyes", ["blush"], ["happy", "proud"]),
+    emoji!("😇", "smiling face with halo", ["innocent"], ["angel"]),
+    emoji!("🥰", "smiling face with hearts", ["smiling_face_with_three_hearts"], ["love", "crush"]),
+    emoji!("😍", "smiling face with heart-eyes", ["heart_eyes"], ["love", "crush"]),
+    emoji!("🤩", "star-struck", ["star_struck"], ["excited", "wow"]),
+    emoji!("😘", "face blowing a kiss", ["kissing_heart"], ["kiss", "love"]),
+    emoji!("😋", "face savoring food", ["yum"], ["delicious", "tasty"]),
+    emoji!("😛", "face with tongue", ["stuck_out_tongue"], ["silly"]),
+    emoji!("😜", "winking face with tongue", ["stuck_out_tongue_winking_eye"], ["silly", "joke"]),
+    emoji!("🤪", "zany face", ["zany_face"], ["crazy", "silly"]),
+    emoji!("🤑", "money-mouth face", ["money_mouth_face"], ["rich", "money"]),
+    emoji!("🤗", "smiling face with open hands", ["hugs"], ["hug"]),
+    emoji!("🤭", "face with hand over mouth", ["hand_over_mouth"], ["oops", "giggle"]),
+    emoji!("🤫", "shushing face", ["shushing_face"], ["quiet", "secret"]),
+    emoji!("🤔", "thinking face", ["thinking"], ["hmm", "wonder"]),
+    emoji!("🤐", "zipper-mouth face", ["zipper_mouth_face"], ["secret", "quiet"]),
+    emoji!("🤨", "face with raised eyebrow", ["raised_eyebrow"], ["suspicious", "skeptical"]),
+    emoji!("😐", "neutral face", ["neutral_face"], ["meh"]),
+    emoji!("😑", "expressionless face", ["expressionless"], ["meh", "blank"]),
+    emoji!("😶", "face without mouth", ["no_mouth"], ["silent"]),
+    emoji!("😏", "smirking face", ["smirk"], ["smug"]),
+    emoji!("😒", "unamused face", ["unamused"], ["meh", "annoyed"]),
+    emoji!("🙄", "face with rolling eyes", ["roll_eyes"], ["eyeroll", "annoyed"]),
+    emoji!("😬", "grimacing face", ["grimacing"], ["awkward", "nervous"]),
+    emoji!("😌", "relieved face", ["relieved"], ["calm"]),
+    emoji!("😔", "pensive face", ["pensive"], ["sad"]),
+    emoji!("😪", "sleepy face", ["sleepy"], ["tired"]),
+    emoji!("😴", "sleeping face", ["sleeping"], ["zzz", "tired"]),
+    emoji!("😷", "face with medical mask", ["mask"], ["sick", "ill"]),
+    emoji!("🤒", "face with thermometer", ["face_with_thermometer"], ["sick", "fever"]),
+    emoji!("🤢", "nauseated face", ["nauseated_face"], ["sick", "gross"]),
+    emoji!("🤮", "face vomiting", ["vomiting_face"], ["sick", "gross"]),
+    emoji!("🥵", "hot face", ["hot_face"], ["heat", "sweat"]),
+    emoji!("🥶", "cold face", ["cold_face"], ["freezing", "ice"]),
+    emoji!("🤯", "exploding head", ["exploding_head"], ["mind blown", "shocked"]),
+    emoji!("🥳", "partying face", ["partying_face"], ["party", "celebration", "birthday"]),
+    emoji!("😎", "smiling face with sunglasses", ["sunglasses"], ["cool"]),
+    emoji!("🤓", "nerd face", ["nerd_face"], ["geek"]),
+    emoji!("😕", "confused face", ["confused"], ["unsure"]),
+    emoji!("😟", "worried face", ["worried"], ["nervous"]),
+    emoji!("🙁", "slightly frowning face", ["slightly_frowning_face"], ["sad"]),
+    emoji!("😮", "face with open mouth", ["open_mouth"], ["surprise", "wow"]),
+    emoji!("😲", "astonished face", ["astonished"], ["shocked", "surprise"]),
+    emoji!("😳", "flushed face", ["flushed"], ["embarrassed", "blush"]),
+    emoji!("🥺", "pleading face", ["pleading_face"], ["puppy eyes", "please"]),
+    emoji!("😢", "crying face", ["cry"], ["sad", "tear"]),
+    emoji!("😭", "loudly crying face", ["sob"], ["sad", "cry"]),
+    emoji!("😱", "face screaming in fear", ["scream"], ["scared", "horror"]),
+    emoji!("😩", "weary face", ["weary"], ["tired", "frustrated"]),
+    emoji!("😤", "face with steam from nose", ["triumph"], ["angry", "proud"]),
+    emoji!("😡", "enraged face", ["rage", "pout"], ["angry", "mad"]),
+    emoji!("😠", "angry face", ["angry"], ["mad"]),
+    emoji!("🤬", "face with symbols on mouth", ["cursing_face"], ["swearing", "angry"]),
+    emoji!("😈", "smiling face with horns", ["smiling_imp"], ["devil", "evil"]),
+    emoji!("💀", "skull", ["skull"], ["dead", "death"]),
+    emoji!("💩", "pile of poo", ["poop", "hankey"], ["poo"]),
+    emoji!("🤡", "clown face", ["clown_face"], ["clown"]),
+    emoji!("👻", "ghost", ["ghost"], ["halloween", "boo"]),
+    emoji!("👽", "alien", ["alien"], ["ufo", "space"]),
+    emoji!("🤖", "robot", ["robot"], ["bot", "machine"]),
+    emoji!("😺", "grinning cat", ["smiley_cat"], ["cat"]),
+    emoji!("🙈", "see-no-evil monkey", ["see_no_evil"], ["monkey", "shy"]),
+    emoji!("🙉", "hear-no-evil monkey", ["hear_no_evil"], ["monkey"]),
+    emoji!("🙊", "speak-no-evil monkey", ["speak_no_evil"], ["monkey"]),
+    // Hearts
+    emoji!("❤️", "red heart", ["heart"], ["love"]),
+    emoji!("🧡", "orange heart", ["orange_heart"], ["love"]),
+    emoji!("💛", "yellow heart", ["yellow_heart"], ["love"]),
+    emoji!("💚", "green heart", ["green_heart"], ["love"]),
+    emoji!("💙", "blue heart", ["blue_heart"], ["love"]),
+    emoji!("💜", "purple heart", ["purple_heart"], ["love"]),
+    emoji!("🖤", "black heart", ["black_heart"], ["love"]),
+    emoji!("🤍", "white heart", ["white_heart"], ["love"]),
+    emoji!("🤎", "brown heart", ["brown_heart"], ["love"]),
+    emoji!("💔", "broken heart", ["broken_heart"], ["sad", "breakup"]),
+    emoji!("❣️", "heart exclamation", ["heavy_heart_exclamation"], ["love"]),
+    emoji!("💕", "two hearts", ["two_hearts"], ["love"]),
+    emoji!("💖", "sparkling heart", ["sparkling_heart"], ["love"]),
+    emoji!("💘", "heart with arrow", ["cupid"], ["love", "valentine"]),
+    emoji!("💯", "hundred points", ["100"], ["perfect", "score"]),
+    emoji!("💥", "collision", ["boom", "collision"], ["explosion"]),
+    emoji!("💤", "zzz", ["zzz"], ["sleep"]),
+    emoji!("💬", "speech balloon", ["speech_balloon"], ["comment", "chat"]),
+    // People and gestures
+    tone!("👋", "waving hand", ["wave"], ["hello", "bye", "hi"]),
+    tone!("🤚", "raised back of hand", ["raised_back_of_hand"], ["stop"]),
+    tone!("✋", "raised hand", ["hand", "raised_hand"], ["stop", "high five"]),
+    tone!("🖖", "vulcan salute", ["vulcan_salute"], ["spock", "star trek"]),
+    tone!("👌", "OK hand", ["ok_hand"], ["okay", "perfect"]),
+    tone!("🤌", "pinched fingers", ["pinched_fingers"], ["italian"]),
+    tone!("✌️", "victory hand", ["v"], ["peace"]),
+    tone!("🤞", "crossed fingers", ["crossed_fingers"], ["luck", "hope"]),
+    tone!("🤟", "love-you gesture", ["love_you_gesture"], ["ily"]),
+    tone!("🤘", "sign of the horns", ["metal"], ["rock"]),
+    tone!("🤙", "call me hand", ["call_me_hand"], ["shaka"]),
+    tone!("👈", "backhand index pointing left", ["point_left"], ["left"]),
+    tone!("👉", "backhand index pointing right", ["point_right"], ["right"]),
+    tone!("👆", "backhand index pointing up", ["point_up_2"], ["up"]),
+    tone!("👇", "backhand index pointing down", ["point_down"], ["down"]),
+    tone!("☝️", "index pointing up", ["point_up"], ["up", "one"]),
+    tone!("👍", "thumbs up", ["+1", "thumbsup"], ["like", "yes", "approve"]),
+    tone!("👎", "thumbs down", ["-1", "thumbsdown"], ["dislike", "no"]),
+    tone!("✊", "raised fist", ["fist"], ["power"]),
+    tone!("👊", "oncoming fist", ["punch", "fist_oncoming"], ["bump"]),
+    tone!("👏", "clapping hands", ["clap"], ["applause", "congrats"]),
+    tone!("🙌", "raising hands", ["raised_hands"], ["hooray", "celebrate"]),
+    tone!("👐", "open hands", ["open_hands"], ["hug"]),
+    tone!("🤲", "palms up together", ["palms_up_together"], ["prayer"]),
+    emoji!("🤝", "handshake", ["handshake"], ["deal", "agreement"]),
+    tone!("🙏", "folded hands", ["pray"], ["please", "thanks", "hope"]),
+    tone!("✍️", "writing hand", ["writing_hand"], ["write"]),
+    tone!("💪", "flexed biceps", ["muscle"], ["strong", "flex"]),
+    emoji!("🧠", "brain", ["brain"], ["smart", "think"]),
+    emoji!("👀", "eyes", ["eyes"], ["look", "see"]),
+    emoji!("👁️", "eye", ["eye"], ["look"]),
+    tone!("👶", "baby", ["baby"], ["child"]),
+    tone!("🧒", "child", ["child"], ["kid"]),
+    tone!("🧑", "person", ["adult"], ["human"]),
+    tone!("👨", "man", ["man"], ["male"]),
+    tone!("👩", "woman", ["woman"], ["female"]),
+    tone!("🧓", "older person", ["older_adult"], ["old"]),
+    tone!("🙋", "person raising hand", ["raising_hand"], ["question", "me"]),
+    tone!("🤷", "person shrugging", ["shrug"], ["dunno", "whatever"]),
+    tone!("🤦", "person facepalming", ["facepalm"], ["ugh", "disbelief"]),
+    tone!("🙇", "person bowing", ["bow"], ["sorry", "respect"]),
+    tone!("🏃", "person running", ["running", "runner"], ["run", "exercise"]),
+    tone!("🚶", "person walking", ["walking"], ["walk"]),
+    tone!("💃", "woman dancing", ["dancer"], ["dance"]),
+    tone!("🕺", "man dancing", ["man_dancing"], ["dance"]),
+    tone!("🧘", "person in lotus position", ["lotus_position"], ["yoga", "meditate"]),
+    emoji!("👥", "busts in silhouette", ["busts_in_silhouette"], ["users", "people"]),
+    // Animals and nature
+    emoji!("🐶", "dog face", ["dog"], ["puppy", "pet"]),
+    emoji!("🐱", "cat face", ["cat"], ["kitten", "pet"]),
+    emoji!("🐭", "mouse face", ["mouse"], ["animal"]),
+    emoji!("🐰", "rabbit face", ["rabbit"], ["bunny"]),
+    emoji!("🦊", "fox", ["fox_face"], ["animal"]),
+    emoji!("🐻", "bear", ["bear"], ["animal"]),
+    emoji!("🐼", "panda", ["panda_face"], ["animal"]),
+    emoji!("🐨", "koala", ["koala"], ["animal"]),
+    emoji!("🐯", "tiger face", ["tiger"], ["animal"]),
+    emoji!("🦁", "lion", ["lion"], ["animal"]),
+    emoji!("🐮", "cow face", ["cow"], ["animal"]),
+    emoji!("🐷", "pig face", ["pig"], ["animal"]),
+    emoji!("🐸", "frog", ["frog"], ["animal"]),
+    emoji!("🐵", "monkey face", ["monkey_face"], ["animal"]),
+    emoji!("🐔", "chicken", ["chicken"], ["animal"]),
+    emoji!("🐧", "penguin", ["penguin"], ["animal", "linux"]),
+    emoji!("🐦", "bird", ["bird"], ["animal"]),
+    emoji!("🦆", "duck", ["duck"], ["animal"]),
+    emoji!("🦉", "owl", ["owl"], ["animal", "night"]),
+    emoji!("🐝", "honeybee", ["bee", "honeybee"], ["insect"]),
+    emoji!("🐛", "bug", ["bug"], ["insect", "error"]),
+    emoji!("🦋", "butterfly", ["butterfly"], ["insect"]),
+    emoji!("🐢", "turtle", ["turtle"], ["slow"]),
+    emoji!("🐍", "snake", ["snake"], ["python"]),
+    emoji!("🐙", "octopus", ["octopus"], ["sea"]),
+    emoji!("🐳", "spouting whale", ["whale"], ["sea", "docker"]),
+    emoji!("🐬", "dolphin", ["dolphin"], ["sea"]),
+    emoji!("🐟", "fish", ["fish"], ["sea"]),
+    emoji!("🦀", "crab", ["crab"], ["rust", "ferris"]),
+    emoji!("🦄", "unicorn", ["unicorn"], ["magic"]),
+    emoji!("🌸", "cherry blossom", ["cherry_blossom"], ["flower", "spring"]),
+    emoji!("🌹", "rose", ["rose"], ["flower", "love"]),
+    emoji!("🌻", "sunflower", ["sunflower"], ["flower"]),
+    emoji!("🌷", "tulip", ["tulip"], ["flower"]),
+    emoji!("🌱", "seedling", ["seedling"], ["plant", "grow"]),
+    emoji!("🌲", "evergreen tree", ["evergreen_tree"], ["tree", "forest"]),
+    emoji!("🌴", "palm tree", ["palm_tree"], ["beach", "vacation"]),
+    emoji!("🌵", "cactus", ["cactus"], ["desert"]),
+    emoji!("🍀", "four leaf clover", ["four_leaf_clover"], ["luck"]),
+    emoji!("🍁", "maple leaf", ["maple_leaf"], ["autumn", "canada"]),
+    // Weather and sky
+    emoji!("☀️", "sun", ["sunny"], ["weather", "bright"]),
+    emoji!("🌤️", "sun behind small cloud", ["mostly_sunny"], ["weather"]),
+    emoji!("☁️", "cloud", ["cloud"], ["weather"]),
+    emoji!("🌧️", "cloud with rain", ["cloud_with_rain"], ["weather", "rain"]),
+    emoji!("⛈️", "cloud with lightning and rain", ["cloud_with_lightning_and_rain"], ["storm", "thunder"]),
+    emoji!("❄️", "snowflake", ["snowflake"], ["snow", "winter", "cold"]),
+    emoji!("☃️", "snowman", ["snowman_with_snow"], ["winter"]),
+    emoji!("🌈", "rainbow", ["rainbow"], ["pride"]),
+    emoji!("⚡", "high voltage", ["zap"], ["lightning", "electric", "fast"]),
+    emoji!("🔥", "fire", ["fire"], ["hot", "lit", "flame"]),
+    emoji!("💧", "droplet", ["droplet"], ["water"]),
+    emoji!("🌊", "water wave", ["ocean"], ["wave", "sea"]),
+    emoji!("🌙", "crescent moon", ["crescent_moon"], ["night"]),
+    emoji!("⭐", "star", ["star"], ["favorite"]),
+    emoji!("🌟", "glowing star", ["star2"], ["shine"]),
+    emoji!("✨", "sparkles", ["sparkles"], ["shiny", "magic", "new"]),
+    emoji!("🌍", "globe showing Europe-Africa", ["earth_africa"], ["world", "globe"]),
+    emoji!("🌎", "globe showing Americas", ["earth_americas"], ["world", "globe"]),
+    emoji!("🌏", "globe showing Asia-Australia", ["earth_asia"], ["world", "globe"]),
+    // Food and drink
+    emoji!("🍎", "red apple", ["apple"], ["fruit"]),
+    emoji!("🍌", "banana", ["banana"], ["fruit"]),
+    emoji!("🍇", "grapes", ["grapes"], ["fruit"]),
+    emoji!("🍓", "strawberry", ["strawberry"], ["fruit"]),
+    emoji!("🍉", "watermelon", ["watermelon"], ["fruit", "summer"]),
+    emoji!("🍋", "lemon", ["lemon"], ["fruit"]),
+    emoji!("🍑", "peach", ["peach"], ["fruit"]),
+    emoji!("🥑", "avocado", ["avocado"], ["fruit"]),
+    emoji!("🍅", "tomato", ["tomato"], ["vegetable"]),
+    emoji!("🥕", "carrot", ["carrot"], ["vegetable"]),
+    emoji!("🌶️", "hot pepper", ["hot_pepper"], ["spicy", "chili"]),
+    emoji!("🍞", "bread", ["bread"], ["toast"]),
+    emoji!("🧀", "cheese wedge", ["cheese"], ["food"]),
+    emoji!("🍔", "hamburger", ["hamburger"], ["burger", "food"]),
+    emoji!("🍟", "french fries", ["fries"], ["food"]),
+    emoji!("🍕", "pizza", ["pizza"], ["food"]),
+    emoji!("🌮", "taco", ["taco"], ["food", "mexican"]),
+    emoji!("🍣", "sushi", ["sushi"], ["food", "japanese"]),
+    emoji!("🍜", "steaming bowl", ["ramen"], ["noodles", "soup"]),
+    emoji!("🍚", "cooked rice", ["rice"], ["food"]),
+    emoji!("🥟", "dumpling", ["dumpling"], ["food", "jiaozi"]),
+    emoji!("🍩", "doughnut", ["doughnut"], ["donut", "dessert"]),
+    emoji!("🍪", "cookie", ["cookie"], ["dessert"]),
+    emoji!("🎂", "birthday cake", ["birthday"], ["cake", "party"]),
+    emoji!("🍰", "shortcake", ["cake"], ["dessert"]),
+    emoji!("🍫", "chocolate bar", ["chocolate_bar"], ["dessert"]),
+    emoji!("🍿", "popcorn", ["popcorn"], ["movie"]),
+    emoji!("☕", "hot beverage", ["coffee"], ["tea", "cafe"]),
+    emoji!("🍵", "teacup without handle", ["tea"], ["green tea"]),
+    emoji!("🧋", "bubble tea", ["bubble_tea"], ["boba"]),
+    emoji!("🍺", "beer mug", ["beer"], ["drink", "pub"]),
+    emoji!("🍻", "clinking beer mugs", ["beers"], ["cheers", "drink"]),
+    emoji!("🍷", "wine glass", ["wine_glass"], ["drink"]),
+    emoji!("🥂", "clinking glasses", ["clinking_glasses"], ["cheers", "toast", "celebrate"]),
+    // Activities and objects
+    emoji!("🎉", "party popper", ["tada"], ["party", "celebrate", "congrats"]),
+    emoji!("🎊", "confetti ball", ["confetti_ball"], ["party"]),
+    emoji!("🎈", "balloon", ["balloon"], ["party", "birthday"]),
+    emoji!("🎁", "wrapped gift", ["gift"], ["present", "birthday"]),
+    emoji!("🎄", "Christmas tree", ["christmas_tree"], ["xmas"]),
+    emoji!("🎃", "jack-o-lantern", ["jack_o_lantern"], ["halloween", "pumpkin"]),
+    emoji!("🏆", "trophy", ["trophy"], ["win", "award"]),
+    emoji!("🥇", "1st place medal", ["1st_place_medal"], ["gold", "first"]),
+    emoji!("⚽", "soccer ball", ["soccer"], ["football", "sport"]),
+    emoji!("🏀", "basketball", ["basketball"], ["sport"]),
+    emoji!("🎮", "video game", ["video_game"], ["gaming", "controller"]),
+    emoji!("🎲", "game die", ["game_die"], ["dice", "random"]),
+    emoji!("🎯", "bullseye", ["dart"], ["target", "goal"]),
+    emoji!("🎵", "musical note", ["musical_note"], ["music"]),
+    emoji!("🎶", "musical notes", ["notes"], ["music"]),
+    emoji!("🎧", "headphone", ["headphones"], ["music"]),
+    emoji!("🎨", "artist palette", ["art"], ["paint", "design"]),
+    emoji!("🎬", "clapper board", ["clapper"], ["movie", "film"]),
+    emoji!("📷", "camera", ["camera"], ["photo"]),
+    emoji!("💻", "laptop", ["computer"], ["pc", "work"]),
+    emoji!("🖥️", "desktop computer", ["desktop_computer"], ["pc", "monitor"]),
+    emoji!("⌨️", "keyboard", ["keyboard"], ["type"]),
+    emoji!("🖱️", "computer mouse", ["computer_mouse"], ["click"]),
+    emoji!("📱", "mobile phone", ["iphone"], ["phone", "smartphone"]),
+    emoji!("☎️", "telephone", ["phone", "telephone"], ["call"]),
+    emoji!("🔋", "battery", ["battery"], ["power"]),
+    emoji!("🔌", "electric plug", ["electric_plug"], ["power"]),
+    emoji!("💡", "light bulb", ["bulb"], ["idea"]),
+    emoji!("🔦", "flashlight", ["flashlight"], ["torch"]),
+    emoji!("📚", "books", ["books"], ["library", "read"]),
+    emoji!("📖", "open book", ["book", "open_book"], ["read"]),
+    emoji!("📝", "memo", ["memo", "pencil"], ["note", "write"]),
+    emoji!("✏️", "pencil", ["pencil2"], ["write", "edit"]),
+    emoji!("📎", "paperclip", ["paperclip"], ["attachment"]),
+    emoji!("📌", "pushpin", ["pushpin"], ["pin", "location"]),
+    emoji!("📍", "round pushpin", ["round_pushpin"], ["pin", "location"]),
+    emoji!("✂️", "scissors", ["scissors"], ["cut"]),
+    emoji!("📁", "file folder", ["file_folder"], ["directory"]),
+    emoji!("📂", "open file folder", ["open_file_folder"], ["directory"]),
+    emoji!("📄", "page facing up", ["page_facing_up"], ["document", "file"]),
+    emoji!("📅", "calendar", ["date"], ["calendar", "schedule"]),
+    emoji!("📈", "chart increasing", ["chart_with_upwards_trend"], ["growth", "graph"]),
+    emoji!("📉", "chart decreasing", ["chart_with_downwards_trend"], ["decline", "graph"]),
+    emoji!("📊", "bar chart", ["bar_chart"], ["graph", "stats"]),
+    emoji!("📦", "package", ["package"], ["box", "shipping"]),
+    emoji!("📧", "e-mail", ["email", "e-mail"], ["mail"]),
+    emoji!("✉️", "envelope", ["envelope"], ["letter", "mail"]),
+    emoji!("📣", "megaphone", ["mega"], ["announcement"]),
+    emoji!("🔔", "bell", ["bell"], ["notification"]),
+    emoji!("🔕", "bell with slash", ["no_bell"], ["mute", "silent"]),
+    emoji!("🔒", "locked", ["lock"], ["secure", "private"]),
+    emoji!("🔓", "unlocked", ["unlock"], ["open"]),
+    emoji!("🔑", "key", ["key"], ["password", "lock"]),
+    emoji!("🔨", "hammer", ["hammer"], ["tool", "build"]),
+    emoji!("🔧", "wrench", ["wrench"], ["tool", "fix"]),
+    emoji!("⚙️", "gear", ["gear"], ["settings", "cog"]),
+    emoji!("🧪", "test tube", ["test_tube"], ["science", "experiment"]),
+    emoji!("🔬", "microscope", ["microscope"], ["science"]),
+    emoji!("💊", "pill", ["pill"], ["medicine"]),
+    emoji!("💰", "money bag", ["moneybag"], ["money", "rich"]),
+    emoji!("💵", "dollar banknote", ["dollar"], ["money", "cash"]),
+    emoji!("💳", "credit card", ["credit_card"], ["money", "pay"]),
+    emoji!("💎", "gem stone", ["gem"], ["diamond", "jewel"]),
+    emoji!("⏰", "alarm clock", ["alarm_clock"], ["time", "wake"]),
+    emoji!("⌛", "hourglass done", ["hourglass"], ["time", "wait"]),
+    emoji!("⏳", "hourglass not done", ["hourglass_flowing_sand"], ["time", "wait", "loading"]),
+    emoji!("🚀", "rocket", ["rocket"], ["launch", "ship", "space"]),
+    emoji!("✈️", "airplane", ["airplane"], ["flight", "travel"]),
+    emoji!("🚗", "automobile", ["car", "red_car"], ["drive"]),
+    emoji!("🚲", "bicycle", ["bike"], ["cycle"]),
+    emoji!("🚌", "bus", ["bus"], ["transit"]),
+    emoji!("🚆", "train", ["train2"], ["rail", "transit"]),
+    emoji!("🏠", "house", ["house"], ["home"]),
+    emoji!("🏢", "office building", ["office"], ["work"]),
+    emoji!("🏥", "hospital", ["hospital"], ["health"]),
+    emoji!("🏫", "school", ["school"], ["education"]),
+    emoji!("🗺️", "world map", ["world_map"], ["travel"]),
+    emoji!("🚧", "construction", ["construction"], ["wip", "work in progress"]),
+    emoji!("🚨", "police car light", ["rotating_light"], ["alert", "siren", "emergency"]),
+    emoji!("🏁", "chequered flag", ["checkered_flag"], ["finish", "race"]),
+    emoji!("🚩", "triangular flag", ["triangular_flag_on_post"], ["red flag"]),
+    emoji!("🏳️‍🌈", "rainbow flag", ["rainbow_flag"], ["pride"]),
+    // Symbols
+    emoji!("✅", "check mark button", ["white_check_mark"], ["done", "yes", "ok"]),
+    emoji!("✔️", "check mark", ["heavy_check_mark"], ["done", "yes", "tick"]),
+    emoji!("☑️", "check box with check", ["ballot_box_with_check"], ["done"]),
+    emoji!("❌", "cross mark", ["x"], ["no", "wrong", "delete"]),
+    emoji!("❎", "cross mark button", ["negative_squared_cross_mark"], ["no"]),
+    emoji!("➕", "plus", ["heavy_plus_sign"], ["add"]),
+    emoji!("➖", "minus", ["heavy_minus_sign"], ["subtract"]),
+    emoji!("❓", "red question mark", ["question"], ["help"]),
+    emoji!("❗", "red exclamation mark", ["exclamation", "heavy_exclamation_mark"], ["important"]),
+    emoji!("⚠️", "warning", ["warning"], ["caution", "alert"]),
+    emoji!("⛔", "no entry", ["no_entry"], ["forbidden", "stop"]),
+    emoji!("🚫", "prohibited", ["no_entry_sign"], ["forbidden", "ban"]),
+    emoji!("♻️", "recycling symbol", ["recycle"], ["environment"]),
+    emoji!("🔴", "red circle", ["red_circle"], ["dot"]),
+    emoji!("🟢", "green circle", ["green_circle"], ["dot", "online"]),
+    emoji!("🟡", "yellow circle", ["yellow_circle"], ["dot"]),
+    emoji!("🔵", "blue circle", ["large_blue_circle"], ["dot"]),
+    emoji!("⚫", "black circle", ["black_circle"], ["dot"]),
+    emoji!("⚪", "white circle", ["white_circle"], ["dot"]),
+    emoji!("🔗", "link", ["link"], ["url", "chain"]),
+    emoji!("🔍", "magnifying glass tilted left", ["mag"], ["search", "find", "zoom"]),
+    emoji!("🆕", "NEW button", ["new"], ["fresh"]),
+    emoji!("🆗", "OK button", ["ok"], ["okay"]),
+    emoji!("🆒", "COOL button", ["cool"], ["nice"]),
+    emoji!("🆘", "SOS button", ["sos"], ["help", "emergency"]),
+    emoji!("ℹ️", "information", ["information_source"], ["info"]),
+    emoji!("🔄", "counterclockwise arrows button", ["arrows_counterclockwise"], ["refresh", "sync", "reload"]),
+    emoji!("🔁", "repeat button", ["repeat"], ["loop"]),
+    emoji!("▶️", "play button", ["arrow_forward"], ["play"]),
+    emoji!("⏸️", "pause button", ["pause_button"], ["pause"]),
+    emoji!("⏹️", "stop button", ["stop_button"], ["stop"]),
+    emoji!("⬆️", "up arrow", ["arrow_up"], ["up"]),
+    emoji!("⬇️", "down arrow", ["arrow_down"], ["down"]),
+    emoji!("⬅️", "left arrow", ["arrow_left"], ["left"]),
+    emoji!("➡️", "right arrow", ["arrow_right"], ["right"]),
+    emoji!("♠️", "spade suit", ["spades"], ["cards"]),
+    emoji!("♥️", "heart suit", ["hearts"], ["cards"]),
+    emoji!("♦️", "diamond suit", ["diamonds"], ["cards"]),
+    emoji!("♣️", "club suit", ["clubs"], ["cards"]),
+    // Typographic and math symbols
+    emoji!("→", "rightwards arrow", ["arrow"], ["right", "to"]),
+    emoji!("←", "leftwards arrow", [], ["left", "from"]),
+    emoji!("↑", "upwards arrow", [], ["up"]),
+    emoji!("↓", "downwards arrow", [], ["down"]),
+    emoji!("↔", "left right arrow", [], ["both"]),
+    emoji!("⇒", "rightwards double arrow", ["implies"], ["then"]),
+    emoji!("↵", "downwards arrow with corner leftwards", ["return"], ["enter"]),
+    emoji!("⌘", "place of interest sign", ["command", "cmd"], ["mac"]),
+    emoji!("⌥", "option key", ["option", "alt"], ["mac"]),
+    emoji!("⇧", "upwards white arrow", ["shift"], ["key"]),
+    emoji!("⌫", "erase to the left", ["backspace"], ["delete", "key"]),
+    emoji!("©", "copyright sign", ["copyright"], ["legal"]),
+    emoji!("®", "registered sign", ["registered"], ["trademark"]),
+    emoji!("™", "trade mark sign", ["tm"], ["trademark"]),
+    emoji!("°", "degree sign", ["degree"], ["temperature", "angle"]),
+    emoji!("±", "plus-minus sign", ["plusminus"], ["tolerance"]),
+    emoji!("×", "multiplication sign", ["times"], ["multiply"]),
+    emoji!("÷", "division sign", ["divide"], ["division"]),
+    emoji!("≈", "almost equal to", ["approx"], ["about"]),
+    emoji!("≠", "not equal to", ["ne"], ["unequal"]),
+    emoji!("≤", "less-than or equal to", ["le"], ["lte"]),
+    emoji!("≥", "greater-than or equal to", ["ge"], ["gte"]),
+    emoji!("∞", "infinity", ["infinity"], ["forever"]),
+    emoji!("√", "square root", ["sqrt"], ["root"]),
+    emoji!("∑", "n-ary summation", ["sum"], ["sigma"]),
+    emoji!("π", "greek small letter pi", ["pi"], ["math"]),
+    emoji!("µ", "micro sign", ["micro"], ["mu"]),
+    emoji!("Δ", "greek capital letter delta", ["delta"], ["change"]),
+    emoji!("€", "euro sign", ["euro"], ["currency", "money"]),
+    emoji!("£", "pound sign", ["pound"], ["currency", "money"]),
+    emoji!("¥", "yen sign", ["yen", "yuan"], ["currency", "money"]),
+    emoji!("₿", "bitcoin sign", ["bitcoin"], ["currency", "crypto"]),
+    emoji!("§", "section sign", ["section"], ["legal"]),
+    emoji!("¶", "pilcrow sign", ["pilcrow", "paragraph"], ["text"]),
+    emoji!("•", "bullet", ["bullet"], ["dot", "list"]),
+    emoji!("…", "horizontal ellipsis", ["ellipsis"], ["dots"]),
+    emoji!("–", "en dash", ["endash"], ["dash", "range"]),
+    emoji!("—", "em dash", ["emdash"], ["dash"]),
+    emoji!("“", "left double quotation mark", ["ldquo"], ["quote"]),
+    emoji!("”", "right double quotation mark", ["rdquo"], ["quote"]),
+    emoji!("✓", "check mark", ["check"], ["tick", "done"]),
+    emoji!("✗", "ballot x", ["ballot_x"], ["cross", "no"]),
+    emoji!("★", "black star", ["black_star"], ["rating"]),
+    emoji!("☆", "white star", ["white_star"], ["rating"]),
+    emoji!("♪", "eighth note", ["eighth_note"], ["music"]),
+    emoji!("☺", "white smiling face", ["relaxed"], ["smile"]),
+    emoji!("¯\\_(ツ)_/¯", "shrug kaomoji", ["shruggie"], ["shrug", "dunno", "whatever"]),
+];
@@ -0,0 +1,194 @@
+// Emoji and symbol search ("e heart", ":thumbsup:")
+mod data;
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// One entry of the bundled table
+#[derive(Debug)]
+pub struct Emoji {
+    pub glyph: &'static str,
+    pub name: &'static str,
+    /// Without the surrounding colons
+    pub shortcodes: &'static [&'static str],
+    pub keywords: &'static [&'static str],
+    /// Takes the Fitzpatrick skin-tone modifiers
+    pub skin_tones: bool,
+}
+
+/// Skin-tone names accepted as the last query word, with their modifier
+const SKIN_TONES: &[(&str, &str, char)] = &[
+    ("light", "tone1", '\u{1F3FB}'),
+    ("medium-light", "tone2", '\u{1F3FC}'),
+    ("medium", "tone3", '\u{1F3FD}'),
+    ("medium-dark", "tone4", '\u{1F3FE}'),
+    ("dark", "tone5", '\u{1F3FF}'),
+];
+
+/// A search hit, with any skin tone applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmojiMatch {
+    /// The text to copy
+    pub glyph: String,
+    pub name: String,
+    /// Primary shortcode, e.g. `:thumbsup:`
+    pub shortcode: Option<String>,
+}
+
+/// The whole bundled table
+pub fn all() -> &'static [Emoji] {
+    data::EMOJI
+}
+
+/// Emoji matching `query` by name, shortcode or keyword, best first.
+///
+/// Exact shortcodes rank above exact names, which rank above exact keywords;
+/// everything else is ordered by fuzzy score. A trailing skin-tone word
+/// ("wave dark", "thumbsup tone2") applies that tone; without one, the best
+/// match's tone variants follow it.
+pub fn search(query: &str, limit: usize) -> Vec<EmojiMatch> {
+    let (query, tone) = split_tone(&normalize(query));
+    if query.is_empty() {
+        return all().iter().take(limit).map(|emoji| to_match(emoji, tone)).collect();
+    }
+
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<(i64, &Emoji)> = all()
+        .iter()
+        .filter_map(|emoji| score(&matcher, emoji, &query).map(|score| (score, emoji)))
+        .collect();
+    // Shorter names first on equal scores; the sort is stable, so the
+    // table order decides the rest
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.name.len().cmp(&b.1.name.len())));
+
+    let mut matches = Vec::new();
+    for (idx, (_, emoji)) in scored.into_iter().enumerate() {
+        matches.push(to_match(emoji, tone));
+        if idx == 0 && tone.is_none() && emoji.skin_tones {
+            matches.extend(SKIN_TONES.iter().map(|&(_, _, modifier)| to_match(emoji, Some(modifier))));
+        }
+    }
+    matches.truncate(limit);
+    matches
+}
+
+/// Lowercase, without shortcode colons
+fn normalize(query: &str) -> String {
+    query.trim().trim_matches(':').trim().to_lowercase()
+}
+
+/// Split a trailing skin-tone word off the query
+fn split_tone(query: &str) -> (String, Option<char>) {
+    if let Some((rest, last)) = query.rsplit_once(|c: char| c.is_whitespace() || c == ':') {
+        let last = last.replace('_', "-");
+        let found = SKIN_TONES.iter().find(|(name, code, _)| *name == last || *code == last);
+        if let Some(&(_, _, modifier)) = found {
+            return (rest.trim().trim_end_matches(':').to_string(), Some(modifier));
+        }
+    }
+    (query.to_string(), None)
+}
+
+fn score(matcher: &SkimMatcherV2, emoji: &Emoji, query: &str) -> Option<i64> {
+    let as_shortcode = query.replace(' ', "_");
+    if emoji.shortcodes.iter().any(|code| *code == query || *code == as_shortcode) {
+        return Some(3000);
+    }
+    let name = emoji.name.to_lowercase();
+    if name == query {
+        return Some(2500);
+    }
+    if emoji.keywords.contains(&query) {
+        return Some(1500);
+    }
+
+    std::iter::once(name.as_str())
+        .chain(emoji.shortcodes.iter().copied())
+        .chain(emoji.keywords.iter().copied())
+        .filter_map(|text| matcher.fuzzy_match(text, query))
+        .max()
+}
+
+fn to_match(emoji: &Emoji, tone: Option<char>) -> EmojiMatch {
+    let (glyph, name) = match tone.filter(|_| emoji.skin_tones) {
+        Some(modifier) => (
+            with_skin_tone(emoji.glyph, modifier),
+            format!("{}: {} skin tone", emoji.name, tone_name(modifier)),
+        ),
+        None => (emoji.glyph.to_string(), emoji.name.to_string()),
+    };
+    EmojiMatch {
+        glyph,
+        name,
+        shortcode: emoji.shortcodes.first().map(|code| format!(":{}:", code)),
+    }
+}
+
+/// The modifier goes right after the base character, replacing the emoji
+/// presentation selector if there is one
+fn with_skin_tone(glyph: &str, modifier: char) -> String {
+    let mut chars = glyph.chars();
+    let mut toned: String = chars.next().into_iter().collect();
+    toned.push(modifier);
+    toned.extend(chars.skip_while(|&c| c == '\u{FE0F}'));
+    toned
+}
+
+fn tone_name(modifier: char) -> &'static str {
+    SKIN_TONES
+        .iter()
+        .find(|(_, _, m)| *m == modifier)
+        .map(|(name, _, _)| *name)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heart_finds_red_heart_first() {
+        let matches = search("heart", 30);
+        assert_eq!(matches[0].glyph, "\u{2764}\u{FE0F}");
+        assert_eq!(matches[0].name, "red heart");
+        assert_eq!(matches[0].shortcode.as_deref(), Some(":heart:"));
+        // Other hearts follow
+        assert!(matches.iter().any(|m| m.name == "broken heart"));
+    }
+
+    #[test]
+    fn test_shortcodes_and_fuzzy_names() {
+        assert_eq!(search(":thumbsup:", 1)[0].name, "thumbs up");
+        assert_eq!(search("+1", 1)[0].name, "thumbs up");
+        assert_eq!(search("thumbs up", 1)[0].name, "thumbs up");
+        assert_eq!(search("tears joy", 1)[0].glyph, "😂");
+        assert_eq!(search("copyright", 1)[0].glyph, "©");
+        assert!(search("zzzzqqq", 10).is_empty());
+    }
+
+    #[test]
+    fn test_skin_tones() {
+        assert_eq!(search("wave dark", 1)[0].glyph, "👋\u{1F3FF}");
+        assert_eq!(search(":thumbsup::tone2:", 1)[0].glyph, "👍\u{1F3FC}");
+        assert_eq!(search("wave medium-light", 1)[0].name, "waving hand: medium-light skin tone");
+        // The presentation selector is replaced by the modifier
+        assert_eq!(with_skin_tone("✌️", '\u{1F3FD}'), "✌\u{1F3FD}");
+
+        // Without a tone, the best match is followed by its variants
+        let matches = search("wave", 10);
+        assert_eq!(matches[0].glyph, "👋");
+        assert_eq!(matches[1].glyph, "👋\u{1F3FB}");
+        assert_eq!(matches[5].glyph, "👋\u{1F3FF}");
+
+        // Emoji without tones ignore the tone word
+        assert_eq!(search("heart dark", 1)[0].glyph, "\u{2764}\u{FE0F}");
+    }
+
+    #[test]
+    fn test_table_is_well_formed() {
+        for emoji in all() {
+            assert!(!emoji.glyph.is_empty() && !emoji.name.is_empty());
+            assert!(emoji.shortcodes.iter().all(|code| !code.contains(':') && !code.contains(' ')));
+        }
+    }
+}
@@ -1,8 +1,14 @@
 // Input parser module
 mod calculator;
+pub mod currency;
+mod date_math;
+pub mod dictionary;
+pub mod emoji;
+pub mod text_case;
 pub mod web_search;
 
-pub use calculator::Calculator;
+pub use calculator::{Calculator, Evaluation, is_currency_conversion, is_unit_conversion, parse_conversion};
+pub use date_math::is_date_expression;
 pub use web_search::{SearchEngine, builtin_engines, parse_search_trigger, validate_url_template};
 pub use web_search::is_url as is_web_url;
 
@@ -14,87 +20,59 @@ pub struct Parser {
     web_engines: HashMap<String, WebSearchEngine>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A keyword-triggered web search engine, persisted in `AppConfig::web_search`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WebSearchEngine {
     pub name: String,
+    pub keyword: String,
+    /// URL with a `{query}` placeholder (stored as `url` in the config file)
+    #[serde(rename = "url", alias = "url_template")]
     pub url_template: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+impl WebSearchEngine {
+    fn new(name: &str, keyword: &str, url_template: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            keyword: keyword.to_string(),
+            url_template: url_template.to_string(),
+            icon: None,
+        }
+    }
+}
+
+/// Engines seeded into the config on first run
+pub fn default_web_engines() -> Vec<WebSearchEngine> {
+    vec![
+        WebSearchEngine::new("Google", "gg", "https://www.google.com/search?q={query}"),
+        WebSearchEngine::new("Baidu", "bd", "https://www.baidu.com/s?wd={query}"),
+        WebSearchEngine::new("Bing", "bi", "https://www.bing.com/search?q={query}"),
+        WebSearchEngine::new("DuckDuckGo", "ddg", "https://duckduckgo.com/?q={query}"),
+        WebSearchEngine::new("GitHub", "gh", "https://github.com/search?q={query}"),
+        WebSearchEngine::new("Stack Overflow", "so", "https://stackoverflow.com/search?q={query}"),
+        WebSearchEngine::new("YouTube", "yt", "https://www.youtube.com/results?search_query={query}"),
+        WebSearchEngine::new("Twitter", "tw", "https://twitter.com/search?q={query}"),
+        WebSearchEngine::new("NPM", "npm", "https://www.npmjs.com/search?q={query}"),
+        WebSearchEngine::new("Crates.io", "crate", "https://crates.io/search?q={query}"),
+    ]
 }
 
 impl Parser {
+    /// Parser with the default engines; prefer [`Parser::with_engines`] with
+    /// the user's configured engines
     pub fn new() -> Self {
-        let mut web_engines = HashMap::new();
-
-        // Initialize built-in search engines
-        web_engines.insert(
-            "gg".to_string(),
-            WebSearchEngine {
-                name: "Google".to_string(),
-                url_template: "https://www.google.com/search?q={query}".to_string(),
-            },
-        );
-        web_engines.insert(
-            "bd".to_string(),
-            WebSearchEngine {
-                name: "Baidu".to_string(),
-                url_template: "https://www.baidu.com/s?wd={query}".to_string(),
-            },
-        );
-        web_engines.insert(
-            "bi".to_string(),
-            WebSearchEngine {
-                name: "Bing".to_string(),
-                url_template: "https://www.bing.com/search?q={query}".to_string(),
-            },
-        );
-        web_engines.insert(
-            "ddg".to_string(),
-            WebSearchEngine {
-                name: "DuckDuckGo".to_string(),
-                url_template: "https://duckduckgo.com/?q={query}".to_string(),
-            },
-        );
-        web_engines.insert(
-            "gh".to_string(),
-            WebSearchEngine {
-                name: "GitHub".to_string(),
-                url_template: "https://github.com/search?q={query}".to_string(),
-            },
-        );
-        web_engines.insert(
-            "so".to_string(),
-            WebSearchEngine {
-                name: "Stack Overflow".to_string(),
-                url_template: "https://stackoverflow.com/search?q={query}".to_string(),
-            },
-        );
-        web_engines.insert(
-            "yt".to_string(),
-            WebSearchEngine {
-                name: "YouTube".to_string(),
-                url_template: "https://www.youtube.com/results?search_query={query}".to_string(),
-            },
-        );
-        web_engines.insert(
-            "tw".to_string(),
-            WebSearchEngine {
-                name: "Twitter".to_string(),
-                url_template: "https://twitter.com/search?q={query}".to_string(),
-            },
-        );
-        web_engines.insert(
-            "npm".to_string(),
-            WebSearchEngine {
-                name: "NPM".to_string(),
-                url_template: "https://www.npmjs.com/search?q={query}".to_string(),
-            },
-        );
-        web_engines.insert(
-            "crate".to_string(),
-            WebSearchEngine {
-                name: "Crates.io".to_string(),
-                url_template: "https://crates.io/search?q={query}".to_string(),
-            },
-        );
+        Self::with_engines(&default_web_engines())
+    }
+
+    /// Parser using the given web search engines (keywords are case-insensitive)
+    pub fn with_engines(engines: &[WebSearchEngine]) -> Self {
+        let web_engines = engines
+            .iter()
+            .filter(|e| !e.keyword.trim().is_empty())
+            .map(|e| (e.keyword.trim().to_lowercase(), e.clone()))
+            .collect();
 
         Self { web_engines }
     }
@@ -111,8 +89,24 @@ impl Parser {
             return ParseResult::Calculator(trimmed[1..].trim().to_string());
         }
 
-        // Check if it's a math expression
-        if is_math_expression(trimmed) {
+        // Full-text search; before math so "ft 2024-01-01" searches file contents
+        if let Some(rest) = trimmed.strip_prefix("ft ") {
+            return ParseResult::FullText(rest.trim().to_string());
+        }
+
+        // Text case conversion ("case fooBar"; bare "case" uses the clipboard).
+        // Checked before math since identifiers like "cosine" contain function names.
+        if let Some(text) = text_case::parse_case_query(trimmed) {
+            return ParseResult::TextCase(text);
+        }
+
+        // Date math before plain math: "2024-12-25 - 2024-01-01" would otherwise subtract numbers
+        if is_date_expression(trimmed) {
+            return ParseResult::DateMath(trimmed.to_string());
+        }
+
+        // Check if it's a math expression or unit conversion ("10 km in miles")
+        if is_calculation(trimmed) {
             return ParseResult::Calculator(trimmed.to_string());
         }
 
@@ -131,16 +125,48 @@ impl Parser {
             return ParseResult::Bookmark(trimmed[3..].trim().to_string());
         }
 
+        // Word definitions ("def ice cream")
+        if let Some(rest) = trimmed.strip_prefix("def ") {
+            return ParseResult::Define(rest.trim().to_string());
+        }
+
+        // Snippets to paste ("snip sig")
+        if let Some(rest) = trimmed.strip_prefix("snip ") {
+            return ParseResult::Snippet(rest.trim().to_string());
+        }
+
+        // App actions by name ("cmd settings"); a bare "cmd" lists them all
+        if trimmed == "cmd" {
+            return ParseResult::Palette(String::new());
+        }
+        if let Some(rest) = trimmed.strip_prefix("cmd ") {
+            return ParseResult::Palette(rest.trim().to_string());
+        }
+
+        // Emoji and symbols ("e heart", ":thumbsup")
+        if let Some(rest) = trimmed.strip_prefix("e ").or_else(|| trimmed.strip_prefix(':')) {
+            return ParseResult::Emoji(rest.trim().to_string());
+        }
+
+        // Force-quit a running process ("kill chrome")
+        if let Some(rest) = trimmed.strip_prefix("kill ") {
+            return ParseResult::KillProcess(rest.trim().to_string());
+        }
+
+        // Switch to an open window ("w chrome")
+        if let Some(rest) = trimmed.strip_prefix("w ") {
+            return ParseResult::WindowSwitch(rest.trim().to_string());
+        }
+
         // Check for system command
         if trimmed.starts_with("> ") {
             return ParseResult::Command(trimmed[2..].trim().to_string());
         }
 
         // Check for web search with keyword
-        for (keyword, engine) in &self.web_engines {
-            let prefix = format!("{} ", keyword);
-            if trimmed.starts_with(&prefix) {
-                let query = trimmed[prefix.len()..].trim().to_string();
+        if let Some((keyword, rest)) = trimmed.split_once(' ') {
+            if let Some(engine) = self.web_engines.get(&keyword.to_lowercase()) {
+                let query = rest.trim().to_string();
                 let url = engine.url_template.replace("{query}", &urlencoding::encode(&query));
                 return ParseResult::WebSearch {
                     engine: engine.name.clone(),
@@ -172,6 +198,7 @@ pub enum ParseResult {
     Empty,
     FileOrApp(String),
     Calculator(String),
+    DateMath(String),
     WebSearch {
         engine: String,
         query: String,
@@ -181,11 +208,40 @@ pub enum ParseResult {
     AI(String),
     Clipboard(String),
     Bookmark(String),
+    TextCase(String),
     Command(String),
+    /// File-content search ("ft budget")
+    FullText(String),
+    /// Running processes to force-quit ("kill chrome")
+    KillProcess(String),
+    /// Open windows to switch to ("w chrome")
+    WindowSwitch(String),
+    /// Dictionary definitions of a word or phrase ("def serendipity")
+    Define(String),
+    /// Emoji and symbols to copy ("e heart", ":thumbsup:")
+    Emoji(String),
+    /// Saved snippets by keyword ("snip sig")
+    Snippet(String),
+    /// App actions from the command palette ("cmd settings")
+    Palette(String),
+}
+
+/// Functions recognized as the start of a math expression, e.g. "sqrt(16)"
+const MATH_FUNCTIONS: &[&str] = &["sqrt", "sin", "cos", "tan", "log", "ln", "abs", "exp", "floor", "ceil", "round"];
+
+/// Whether the calculator should handle the input: a math expression or a
+/// unit conversion
+pub fn is_calculation(input: &str) -> bool {
+    is_math_expression(input) || is_unit_conversion(input)
 }
 
 /// Check if input looks like a math expression
 fn is_math_expression(input: &str) -> bool {
+    // File names like "my-notes" or "cosmos-db" are words joined by a dash
+    if is_dashed_identifier(input) {
+        return false;
+    }
+
     // Simple heuristic: contains mostly numbers, operators, and math functions
     let math_chars = input.chars().all(|c| {
         c.is_ascii_digit()
@@ -201,47 +257,93 @@ fn is_math_expression(input: &str) -> bool {
         || input.contains('^')
         || input.contains('%');
 
-    // Check for math functions
-    let has_function = input.contains("sin")
-        || input.contains("cos")
-        || input.contains("tan")
-        || input.contains("sqrt")
-        || input.contains("log")
-        || input.contains("ln")
-        || input.contains("abs");
+    // A lone "-" or "()" is not a calculation
+    let has_digit = input.chars().any(|c| c.is_ascii_digit());
+
+    (math_chars && has_operator && has_digit) || has_function_call(input)
+}
 
-    (math_chars && has_operator) || has_function
+/// A math function applied to something: "sqrt(16)", "sin 30". Names inside
+/// words ("login", "cosmos") do not count.
+fn has_function_call(input: &str) -> bool {
+    let lower = input.to_lowercase();
+    MATH_FUNCTIONS.iter().any(|name| {
+        lower.match_indices(name).any(|(i, _)| {
+            let word_start = !matches!(
+                lower[..i].chars().next_back(),
+                Some(c) if c.is_alphanumeric() || c == '_'
+            );
+            let rest = lower[i + name.len()..].trim_start();
+            word_start && (rest.starts_with('(') || rest.starts_with(|c: char| c.is_ascii_digit()))
+        })
+    })
 }
 
+/// Two identifier-like words joined by a single dash ("a-b", "build-tools")
+fn is_dashed_identifier(input: &str) -> bool {
+    let mut parts = input.split('-');
+    let (Some(first), Some(second), None) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    let is_word = |part: &str| {
+        !part.is_empty()
+            && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+            && part.chars().any(|c| c.is_alphabetic())
+    };
+    is_word(first) && is_word(second)
+}
+
+/// TLDs that make a bare "name.tld" input a URL
+const URL_TLDS: &[&str] = &[
+    "com", "org", "net", "io", "dev", "cn", "co", "app", "me", "tv", "ai", "edu", "gov",
+    "info", "xyz", "uk", "de", "jp", "fr", "us", "ca", "au", "ru", "tech", "site",
+];
+
+/// Extensions of common files. An input ending in one is a file name even
+/// when the extension is also a TLD ("logo.ai", "Safari.app"); such sites
+/// still work with a scheme or "www." prefix.
+const FILE_EXTENSIONS: &[&str] = &[
+    "txt", "md", "json", "toml", "yaml", "yml", "xml", "ini", "cfg", "conf", "log", "csv", "lock",
+    "rs", "py", "js", "ts", "tsx", "jsx", "go", "java", "kt", "rb", "php", "c", "h", "cpp", "cs",
+    "sh", "bat", "ps1", "sql", "html", "css", "db",
+    "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "png", "jpg", "jpeg", "gif", "svg", "psd",
+    "ai", "mp3", "mp4", "mov", "zip", "tar", "gz", "7z", "rar", "iso",
+    "exe", "dll", "msi", "app", "dmg", "pkg",
+];
+
 /// Check if input is a URL
-/// Must be strict to avoid matching filenames like "file.txt" or "pkg_bsaml.pck"
+/// Must be strict to avoid matching filenames like "file.txt" or "pkg_bsaml.pck":
+/// without a scheme or "www." the host must end in a known TLD that is not
+/// also a common file extension.
 fn is_url(input: &str) -> bool {
+    let input = input.trim();
+    if input.is_empty() || input.contains(char::is_whitespace) {
+        return false;
+    }
+
     // Explicit protocols
-    if input.starts_with("http://") || input.starts_with("https://") || input.starts_with("www.") {
+    let lower = input.to_ascii_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") || lower.starts_with("www.") {
         return true;
     }
-    
-    // Check for domain-like patterns
-    // Must not contain spaces and look like a domain with common TLD
-    if input.contains(' ') || input.contains('\\') || input.contains('/') {
+
+    // Windows paths are never URLs
+    if input.contains('\\') {
         return false;
     }
-    
-    // Common TLDs that indicate a URL
-    let common_tlds = ["com", "org", "net", "io", "dev", "cn", "co", "app", "me", "tv", "ai", "edu", "gov"];
-    
-    // Check if ends with a common TLD
-    let parts: Vec<&str> = input.split('.').collect();
-    if parts.len() >= 2 {
-        let tld = parts.last().unwrap().to_lowercase();
-        if common_tlds.contains(&tld.as_str()) {
-            // Additional check: TLD should be at most 3-4 chars for common ones
-            // This helps distinguish "google.com" from "file.docx"
-            return tld.len() <= 4;
-        }
+
+    // "github.com/rust-lang" is judged by its host
+    let host = lower.split('/').next().unwrap_or_default();
+    let labels: Vec<&str> = host.split('.').collect();
+    let valid_labels = labels
+        .iter()
+        .all(|label| !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+    if labels.len() < 2 || !valid_labels {
+        return false;
     }
-    
-    false
+
+    let tld = labels[labels.len() - 1];
+    URL_TLDS.contains(&tld) && !FILE_EXTENSIONS.contains(&tld)
 }
 
 /// Normalize URL by adding protocol if missing
@@ -254,3 +356,167 @@ fn normalize_url(input: &str) -> String {
         format!("https://{}", input)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_engine_resolves() {
+        match Parser::new().parse("gg rust lifetimes") {
+            ParseResult::WebSearch { engine, query, url } => {
+                assert_eq!(engine, "Google");
+                assert_eq!(query, "rust lifetimes");
+                assert_eq!(url, "https://www.google.com/search?q=rust%20lifetimes");
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_user_added_engine_resolves() {
+        let mut engines = default_web_engines();
+        engines.push(WebSearchEngine::new(
+            "YouTube Music",
+            "yt2",
+            "https://music.youtube.com/search?q={query}",
+        ));
+        let parser = Parser::with_engines(&engines);
+
+        match parser.parse("yt2 lofi beats") {
+            ParseResult::WebSearch { engine, url, .. } => {
+                assert_eq!(engine, "YouTube Music");
+                assert_eq!(url, "https://music.youtube.com/search?q=lofi%20beats");
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+        // The shorter keyword still resolves to its own engine
+        assert!(matches!(
+            parser.parse("yt lofi"),
+            ParseResult::WebSearch { ref engine, .. } if engine == "YouTube"
+        ));
+    }
+
+    #[test]
+    fn test_case_query_before_math() {
+        assert!(matches!(
+            Parser::new().parse("case cosine_value"),
+            ParseResult::TextCase(ref t) if t == "cosine_value"
+        ));
+    }
+
+    #[test]
+    fn test_full_text_prefix() {
+        let parser = Parser::new();
+        assert!(matches!(
+            parser.parse("ft quarterly budget"),
+            ParseResult::FullText(ref q) if q == "quarterly budget"
+        ));
+        assert!(matches!(parser.parse("ft 2024-01-01"), ParseResult::FullText(_)));
+        assert!(matches!(parser.parse("ftp client"), ParseResult::FileOrApp(_)));
+    }
+
+    #[test]
+    fn test_kill_prefix() {
+        let parser = Parser::new();
+        assert!(matches!(
+            parser.parse("kill  Chrome "),
+            ParseResult::KillProcess(ref q) if q == "Chrome"
+        ));
+        assert!(matches!(parser.parse("killall"), ParseResult::FileOrApp(_)));
+    }
+
+    #[test]
+    fn test_define_prefix() {
+        let parser = Parser::new();
+        assert!(matches!(
+            parser.parse("def  ice cream "),
+            ParseResult::Define(ref q) if q == "ice cream"
+        ));
+        assert!(matches!(parser.parse("default.json"), ParseResult::FileOrApp(_)));
+    }
+
+    #[test]
+    fn test_palette_prefix() {
+        let parser = Parser::new();
+        assert!(matches!(parser.parse("cmd  clr"), ParseResult::Palette(ref q) if q == "clr"));
+        assert!(matches!(parser.parse("  cmd "), ParseResult::Palette(ref q) if q.is_empty()));
+        assert!(matches!(parser.parse("cmdlet"), ParseResult::FileOrApp(_)));
+    }
+
+    #[test]
+    fn test_emoji_prefix() {
+        let parser = Parser::new();
+        assert!(matches!(parser.parse("e  heart"), ParseResult::Emoji(ref q) if q == "heart"));
+        assert!(matches!(parser.parse(":thumbsup:"), ParseResult::Emoji(ref q) if q == "thumbsup:"));
+        assert!(matches!(parser.parse("email"), ParseResult::FileOrApp(_)));
+    }
+
+    #[test]
+    fn test_snippet_prefix() {
+        let parser = Parser::new();
+        assert!(matches!(parser.parse("snip  sig "), ParseResult::Snippet(ref q) if q == "sig"));
+        assert!(matches!(parser.parse("snippets.md"), ParseResult::FileOrApp(_)));
+    }
+
+    #[test]
+    fn test_window_switch_prefix() {
+        let parser = Parser::new();
+        assert!(matches!(
+            parser.parse("w  chrome"),
+            ParseResult::WindowSwitch(ref q) if q == "chrome"
+        ));
+        assert!(matches!(parser.parse("word"), ParseResult::FileOrApp(_)));
+    }
+
+    #[test]
+    fn test_math_expressions() {
+        for input in ["2 + 2", "(1+2)*3", "10 % 3", "2^10", "sqrt(16)", "sin 30", "3 * abs(-2)"] {
+            assert!(is_math_expression(input), "{} should be math", input);
+        }
+    }
+
+    #[test]
+    fn test_dashed_names_are_not_math() {
+        for input in ["a-b", "my-notes", "cosmos-db", "login-form", "ln-tools", "sin-cos", "report_v2-final"] {
+            assert!(!is_math_expression(input), "{} should not be math", input);
+            assert!(matches!(Parser::new().parse(input), ParseResult::FileOrApp(_)), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_function_names_inside_words_are_not_math() {
+        for input in ["login", "absolute", "cosine", "logs 2024", "-", "()", "+"] {
+            assert!(!is_math_expression(input), "{} should not be math", input);
+        }
+    }
+
+    #[test]
+    fn test_file_names_are_not_urls() {
+        for input in ["config.json", "app.py", "readme.md", "main.rs", "logo.ai", "Safari.app", "pkg_bsaml.pck", "1.5", "C:\\a.com"] {
+            assert!(!is_url(input), "{} should not be a URL", input);
+        }
+        assert!(matches!(Parser::new().parse("config.json"), ParseResult::FileOrApp(_)));
+    }
+
+    #[test]
+    fn test_domains_are_urls() {
+        for input in ["example.com", "github.com", "GitHub.com/rust-lang/rust", "docs.rs.io", "www.character.ai", "https://x.ai"] {
+            assert!(is_url(input), "{} should be a URL", input);
+        }
+        assert!(matches!(
+            Parser::new().parse("github.com"),
+            ParseResult::Url(ref url) if url == "https://github.com"
+        ));
+    }
+
+    #[test]
+    fn test_removed_engine_is_not_matched() {
+        let engines: Vec<_> = default_web_engines()
+            .into_iter()
+            .filter(|e| e.keyword != "gg")
+            .collect();
+        let parser = Parser::with_engines(&engines);
+        assert!(matches!(parser.parse("gg rust"), ParseResult::FileOrApp(_)));
+    }
+}
@@ -0,0 +1,203 @@
+// Text case conversion (camelCase, snake_case, kebab-case, ...)
+use serde::{Deserialize, Serialize};
+
+/// Query prefix that triggers case conversion ("case myVariableName")
+pub const CASE_PREFIX: &str = "case";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStyle {
+    Camel,
+    Snake,
+    Kebab,
+    Pascal,
+    Constant,
+    Title,
+}
+
+impl CaseStyle {
+    /// All styles, in the order they are offered as results
+    pub const ALL: [CaseStyle; 6] = [
+        CaseStyle::Camel,
+        CaseStyle::Snake,
+        CaseStyle::Kebab,
+        CaseStyle::Pascal,
+        CaseStyle::Constant,
+        CaseStyle::Title,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CaseStyle::Camel => "camelCase",
+            CaseStyle::Snake => "snake_case",
+            CaseStyle::Kebab => "kebab-case",
+            CaseStyle::Pascal => "PascalCase",
+            CaseStyle::Constant => "CONSTANT_CASE",
+            CaseStyle::Title => "Title Case",
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            CaseStyle::Camel => "camel",
+            CaseStyle::Snake => "snake",
+            CaseStyle::Kebab => "kebab",
+            CaseStyle::Pascal => "pascal",
+            CaseStyle::Constant => "constant",
+            CaseStyle::Title => "title",
+        }
+    }
+}
+
+/// Text after the `case` prefix, or `None` if the input isn't a case query.
+/// A bare `case` yields an empty string, meaning "use the clipboard".
+pub fn parse_case_query(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    let rest = trimmed.strip_prefix(CASE_PREFIX)?;
+    if rest.is_empty() {
+        return Some(String::new());
+    }
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.trim().to_string())
+}
+
+/// Split an identifier or phrase into words.
+///
+/// Splits on any non-alphanumeric separator and on case boundaries:
+/// lower/digit -> upper (`myVar` -> `my|Var`) and the end of an acronym
+/// (`HTTPServer` -> `HTTP|Server`). Digits stay attached to the preceding
+/// word (`utf8String` -> `utf8|String`).
+pub fn tokenize(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+
+    for chunk in input.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<char> = chunk.chars().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let prev = chars[i - 1];
+            let cur = chars[i];
+            let next = chars.get(i + 1).copied();
+
+            let lower_to_upper = cur.is_uppercase() && (prev.is_lowercase() || prev.is_ascii_digit());
+            let acronym_end = cur.is_uppercase()
+                && prev.is_uppercase()
+                && next.is_some_and(|n| n.is_lowercase());
+
+            if lower_to_upper || acronym_end {
+                words.push(chars[start..i].iter().collect());
+                start = i;
+            }
+        }
+        if start < chars.len() {
+            words.push(chars[start..].iter().collect());
+        }
+    }
+
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let lower = word.to_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Acronyms ("HTTP", "URL") keep their capitals in Title Case
+fn is_acronym(word: &str) -> bool {
+    word.chars().filter(|c| c.is_alphabetic()).count() > 1
+        && !word.chars().any(|c| c.is_lowercase())
+}
+
+/// Convert `input` to the given style
+pub fn convert(input: &str, style: CaseStyle) -> String {
+    let words = tokenize(input);
+    let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+
+    match style {
+        CaseStyle::Snake => lower.join("_"),
+        CaseStyle::Kebab => lower.join("-"),
+        CaseStyle::Constant => words
+            .iter()
+            .map(|w| w.to_uppercase())
+            .collect::<Vec<_>>()
+            .join("_"),
+        CaseStyle::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+        CaseStyle::Camel => words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if i == 0 { w.to_lowercase() } else { capitalize(w) })
+            .collect(),
+        CaseStyle::Title => words
+            .iter()
+            .map(|w| if is_acronym(w) { w.clone() } else { capitalize(w) })
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+/// Every conversion of `input`, in [`CaseStyle::ALL`] order
+pub fn convert_all(input: &str) -> Vec<(CaseStyle, String)> {
+    CaseStyle::ALL
+        .iter()
+        .map(|style| (*style, convert(input, *style)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("HTTPServerURL"), vec!["HTTP", "Server", "URL"]);
+        assert_eq!(tokenize("my-variable_name"), vec!["my", "variable", "name"]);
+        assert_eq!(tokenize("parseJSONResponse"), vec!["parse", "JSON", "Response"]);
+        assert_eq!(tokenize("utf8String"), vec!["utf8", "String"]);
+        assert_eq!(tokenize("  hello   world  "), vec!["hello", "world"]);
+        assert_eq!(tokenize("already_snake_case"), vec!["already", "snake", "case"]);
+        assert!(tokenize("--").is_empty());
+    }
+
+    #[test]
+    fn test_acronym_input() {
+        let input = "HTTPServerURL";
+        assert_eq!(convert(input, CaseStyle::Camel), "httpServerUrl");
+        assert_eq!(convert(input, CaseStyle::Snake), "http_server_url");
+        assert_eq!(convert(input, CaseStyle::Kebab), "http-server-url");
+        assert_eq!(convert(input, CaseStyle::Pascal), "HttpServerUrl");
+        assert_eq!(convert(input, CaseStyle::Constant), "HTTP_SERVER_URL");
+        assert_eq!(convert(input, CaseStyle::Title), "HTTP Server URL");
+    }
+
+    #[test]
+    fn test_mixed_separators() {
+        let input = "my-variable_name";
+        assert_eq!(convert(input, CaseStyle::Camel), "myVariableName");
+        assert_eq!(convert(input, CaseStyle::Snake), "my_variable_name");
+        assert_eq!(convert(input, CaseStyle::Kebab), "my-variable-name");
+        assert_eq!(convert(input, CaseStyle::Pascal), "MyVariableName");
+        assert_eq!(convert(input, CaseStyle::Constant), "MY_VARIABLE_NAME");
+        assert_eq!(convert(input, CaseStyle::Title), "My Variable Name");
+    }
+
+    #[test]
+    fn test_round_trip_between_styles() {
+        for (_, converted) in convert_all("someAPIKey value") {
+            assert_eq!(convert(&converted, CaseStyle::Snake), "some_api_key_value");
+        }
+    }
+
+    #[test]
+    fn test_parse_case_query() {
+        assert_eq!(parse_case_query("case fooBar"), Some("fooBar".to_string()));
+        assert_eq!(parse_case_query("case"), Some(String::new()));
+        assert_eq!(parse_case_query("  case   a b "), Some("a b".to_string()));
+        assert_eq!(parse_case_query("cases"), None);
+        assert_eq!(parse_case_query("notepad"), None);
+    }
+}
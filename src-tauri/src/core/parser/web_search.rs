@@ -17,28 +17,9 @@ impl SearchEngine {
     }
 }
 
-/// Check if input is a URL
+/// Check if input is a URL (same rules as the launcher's URL detection)
 pub fn is_url(input: &str) -> bool {
-    let input = input.trim();
-    
-    // Check for common URL patterns
-    if input.starts_with("http://") || input.starts_with("https://") {
-        return true;
-    }
-    
-    // Check for domain-like pattern (e.g., "example.com")
-    if input.contains('.') {
-        let parts: Vec<&str> = input.split('.').collect();
-        if parts.len() >= 2 {
-            let last_part = parts.last().unwrap();
-            // Common TLDs
-            if last_part.len() >= 2 && last_part.chars().all(|c| c.is_ascii_alphabetic()) {
-                return true;
-            }
-        }
-    }
-    
-    false
+    super::is_url(input)
 }
 
 /// Parse web search trigger from input
@@ -137,6 +118,7 @@ mod tests {
         assert!(is_url("www.example.com"));
         assert!(!is_url("just text"));
         assert!(!is_url("single"));
+        assert!(!is_url("notes.txt"));
     }
 
     #[test]
@@ -0,0 +1,189 @@
+// Pinned items: paths the user keeps at the top of search results
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Score of the first pinned match; above anything search computes
+pub const PINNED_SCORE: i32 = 1_000_000;
+/// How long a pinned path's file system check is trusted
+const KIND_TTL: Duration = Duration::from_secs(30);
+
+/// What a pinned path points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinKind {
+    File,
+    Folder,
+}
+
+/// Checked pinned paths; `None` for one that did not exist
+static KINDS: Lazy<Mutex<HashMap<String, (Instant, Option<PinKind>)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// What `path` points at, or `None` when it does not exist. Remembered for
+/// [`KIND_TTL`], so typing a query does not stat the pins on every keystroke.
+pub fn kind(path: &str) -> Option<PinKind> {
+    let mut kinds = KINDS.lock();
+    if let Some((checked_at, kind)) = kinds.get(path) {
+        if checked_at.elapsed() < KIND_TTL {
+            return *kind;
+        }
+    }
+    let kind = std::fs::metadata(path)
+        .ok()
+        .map(|meta| if meta.is_dir() { PinKind::Folder } else { PinKind::File });
+    kinds.insert(path.to_string(), (Instant::now(), kind));
+    kind
+}
+
+/// A search result that pins can move to the top
+pub trait Pinnable: Sized {
+    fn path(&self) -> Option<&str>;
+    fn score(&self) -> i32;
+    fn set_score(&mut self, score: i32);
+    /// Result for a pinned path the search did not return; `None` when the
+    /// path no longer exists
+    fn from_pinned_path(path: &str) -> Option<Self>;
+}
+
+/// Whether `path` matches `query` closely enough to be shown pinned: the
+/// query starts its file name or one of the words in it
+pub fn matches_query(path: &str, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return false;
+    }
+    let name = Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    name.starts_with(&query)
+        || name
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| word.starts_with(&query))
+}
+
+/// Moves pinned paths matching `query` to the top of `results`, in pinned
+/// order, adding those the search missed. Paths compare case-insensitively.
+/// Leaves `results` sorted by score.
+pub fn apply_pins<R: Pinnable>(results: &mut Vec<R>, pins: &[String], query: &str) {
+    let mut rank = 0;
+    for pin in pins.iter().filter(|pin| matches_query(pin, query)) {
+        let pin_key = pin.to_lowercase();
+        let score = PINNED_SCORE - rank;
+        match results
+            .iter_mut()
+            .find(|r| r.path().is_some_and(|p| p.to_lowercase() == pin_key))
+        {
+            Some(result) => result.set_score(score),
+            None => match R::from_pinned_path(pin) {
+                Some(mut result) => {
+                    result.set_score(score);
+                    results.push(result);
+                }
+                None => continue,
+            },
+        }
+        rank += 1;
+    }
+    if rank > 0 {
+        results.sort_by(|a, b| b.score().cmp(&a.score()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Item {
+        path: String,
+        score: i32,
+    }
+
+    impl Pinnable for Item {
+        fn path(&self) -> Option<&str> {
+            Some(&self.path)
+        }
+
+        fn score(&self) -> i32 {
+            self.score
+        }
+
+        fn set_score(&mut self, score: i32) {
+            self.score = score;
+        }
+
+        fn from_pinned_path(path: &str) -> Option<Self> {
+            (!path.contains("missing")).then(|| Item {
+                path: path.to_string(),
+                score: 0,
+            })
+        }
+    }
+
+    fn item(path: &str, score: i32) -> Item {
+        Item {
+            path: path.to_string(),
+            score,
+        }
+    }
+
+    fn paths(items: &[Item]) -> Vec<&str> {
+        items.iter().map(|item| item.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_pinned_app_outranks_higher_score() {
+        let mut results = vec![
+            item(r"C:\Apps\Code.exe", 1999),
+            item(r"C:\Apps\Code Insiders.exe", 1998),
+            item(r"C:\Docs\code-notes.txt", 1000),
+        ];
+        let pins = vec![r"c:\apps\code insiders.exe".to_string()];
+
+        apply_pins(&mut results, &pins, "co");
+
+        assert_eq!(paths(&results)[0], r"C:\Apps\Code Insiders.exe");
+        assert_eq!(results[0].score, PINNED_SCORE);
+        assert_eq!(results.len(), 3);
+    }
+
+    #[test]
+    fn test_pins_keep_their_order_and_fill_in_missed_paths() {
+        let mut results = vec![item("/usr/bin/firefox", 1500)];
+        let pins = vec![
+            "/opt/Foobar/foobar".to_string(),
+            "/opt/missing/fork".to_string(),
+            "/usr/bin/firefox".to_string(),
+            "/usr/bin/gimp".to_string(),
+        ];
+
+        apply_pins(&mut results, &pins, "f");
+
+        assert_eq!(paths(&results), vec!["/opt/Foobar/foobar", "/usr/bin/firefox"]);
+        assert_eq!(results[1].score, PINNED_SCORE - 1);
+    }
+
+    #[test]
+    fn test_kind_is_remembered() {
+        let dir = std::env::temp_dir().join(format!("omnibox_pin_kind_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("notes.txt");
+        std::fs::write(&file, "pinned").unwrap();
+
+        assert_eq!(kind(&dir.to_string_lossy()), Some(PinKind::Folder));
+        assert_eq!(kind(&file.to_string_lossy()), Some(PinKind::File));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(kind(&file.to_string_lossy()), Some(PinKind::File));
+    }
+
+    #[test]
+    fn test_matches_query() {
+        assert!(matches_query(r"C:\Apps\Visual Studio Code.lnk", "stu"));
+        assert!(matches_query("/Applications/Slack.app", "SL"));
+        assert!(!matches_query("/Applications/Slack.app", "ack"));
+        assert!(!matches_query("/Applications/Slack.app", "  "));
+    }
+}
@@ -0,0 +1,159 @@
+//! Plugin Config
+//! 插件配置校验 - 按 manifest 中声明的 `config_schema` 检查用户配置
+//!
+//! 支持 JSON Schema 的常用子集：顶层 `properties`、`required`、
+//! `additionalProperties: false`，以及每个属性的 `type`、`enum`、
+//! `minimum`、`maximum`。
+
+use super::PluginError;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 按 `schema` 校验配置，失败时列出所有出错的键
+pub fn validate_config(schema: &Value, config: &HashMap<String, Value>) -> Result<(), PluginError> {
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+    let mut problems = Vec::new();
+
+    let required = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str);
+    for key in required {
+        if matches!(config.get(key), None | Some(Value::Null)) {
+            problems.push(format!("{}: required", key));
+        }
+    }
+
+    let mut keys: Vec<&String> = config.keys().collect();
+    keys.sort();
+    for key in keys {
+        let value = &config[key];
+        match properties.and_then(|p| p.get(key)) {
+            Some(property) => {
+                if let Err(why) = check_value(property, value) {
+                    problems.push(format!("{}: {}", key, why));
+                }
+            }
+            None if closed => problems.push(format!("{}: unknown key", key)),
+            None => {}
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(PluginError::InvalidConfig(problems))
+    }
+}
+
+/// 检查单个值是否符合属性定义
+fn check_value(property: &Value, value: &Value) -> Result<(), String> {
+    if let Some(expected) = property.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            return Err(format!("expected {}, got {}", types.join(" or "), type_name(value)));
+        }
+    }
+
+    if let Some(allowed) = property.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let names: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            return Err(format!("{} is not one of {}", value, names.join(", ")));
+        }
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = property.get("minimum").and_then(Value::as_f64) {
+            if n < min {
+                return Err(format!("{} is less than the minimum {}", n, min));
+            }
+        }
+        if let Some(max) = property.get("maximum").and_then(Value::as_f64) {
+            if n > max {
+                return Err(format!("{} is greater than the maximum {}", n, max));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        other => type_name(value) == other,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "api_token": { "type": "string" },
+                "max_results": { "type": "integer", "minimum": 1, "maximum": 50 },
+                "region": { "type": "string", "enum": ["us", "eu"] }
+            },
+            "required": ["api_token"],
+            "additionalProperties": false
+        })
+    }
+
+    fn config(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let config = config(json!({ "api_token": "secret", "max_results": 10, "region": "eu" }));
+        assert!(validate_config(&schema(), &config).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_config_lists_offending_keys() {
+        let config = config(json!({ "max_results": 0, "region": "asia", "colour": "red" }));
+
+        match validate_config(&schema(), &config) {
+            Err(PluginError::InvalidConfig(problems)) => {
+                let keys: Vec<&str> = problems
+                    .iter()
+                    .map(|p| p.split(':').next().unwrap())
+                    .collect();
+                assert_eq!(keys, vec!["api_token", "colour", "max_results", "region"]);
+                assert!(problems[0].contains("required"));
+                assert!(problems[1].contains("unknown key"));
+            }
+            other => panic!("expected InvalidConfig, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_wrong_type() {
+        let config = config(json!({ "api_token": 42 }));
+        let err = validate_config(&schema(), &config).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid plugin config: api_token: expected string, got number");
+    }
+}
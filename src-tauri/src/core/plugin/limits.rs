@@ -0,0 +1,184 @@
+//! Plugin Limits
+//! 插件资源限制 - 单次调用的超时、输出大小和进程内存上限
+
+#[cfg(windows)]
+use super::PluginError;
+use crate::app::config::PluginSandboxConfig;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// 插件进程的资源限制，超出任一限制时进程被杀掉
+#[derive(Debug, Clone, Copy)]
+pub struct SandboxLimits {
+    /// 单次调用的超时
+    pub call_timeout: Duration,
+    /// 单次调用从 stdout 读取的最大字节数
+    pub max_output_bytes: usize,
+    /// 进程内存上限；Windows 用 Job Object，Unix 用 `RLIMIT_DATA`，`None` 为不限制
+    pub max_memory_bytes: Option<u64>,
+}
+
+impl Default for SandboxLimits {
+    fn default() -> Self {
+        Self::from(&PluginSandboxConfig::default())
+    }
+}
+
+impl From<&PluginSandboxConfig> for SandboxLimits {
+    fn from(config: &PluginSandboxConfig) -> Self {
+        Self {
+            call_timeout: Duration::from_millis(config.timeout_ms),
+            max_output_bytes: config.max_output_bytes,
+            max_memory_bytes: (config.max_memory_bytes > 0).then_some(config.max_memory_bytes),
+        }
+    }
+}
+
+/// 运行时分配失败时打印到 stderr 的消息片段（Python、V8、Rust、C++、libc）
+///
+/// 内存上限只会让分配失败，进程随后自行退出；没有这类消息的崩溃不算超限。
+const ALLOCATION_FAILURE_MARKERS: &[&str] = &[
+    "MemoryError",
+    "out of memory",
+    "memory allocation of",
+    "bad_alloc",
+    "Cannot allocate memory",
+];
+
+/// stderr 的一行是否报告了分配失败
+pub(crate) fn reports_allocation_failure(line: &str) -> bool {
+    ALLOCATION_FAILURE_MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+/// 在子进程 exec 前设置数据段上限
+///
+/// 用 `RLIMIT_DATA` 而不是 `RLIMIT_AS`：V8 等运行时会预留大量不提交的地址空间，
+/// 按地址空间限制会让它们无法启动。
+#[cfg(unix)]
+pub(crate) fn limit_memory(command: &mut Command, max_bytes: u64) {
+    let limit = libc::rlimit {
+        rlim_cur: max_bytes as libc::rlim_t,
+        rlim_max: max_bytes as libc::rlim_t,
+    };
+    // SAFETY: setrlimit is async-signal-safe and only touches the child
+    unsafe {
+        command.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_DATA, &limit) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Windows 上由 `MemoryJob` 限制
+#[cfg(not(unix))]
+pub(crate) fn limit_memory(_command: &mut Command, _max_bytes: u64) {}
+
+/// 限制插件进程内存的 Job Object；句柄关闭时进程随之结束
+///
+/// 进程启动后才能加入 Job，启动瞬间的分配不受限制。
+#[cfg(windows)]
+pub(crate) struct MemoryJob {
+    /// Job 句柄（以整数保存，使调用 future 保持 `Send`）
+    handle: isize,
+}
+
+#[cfg(windows)]
+impl MemoryJob {
+    /// 创建带内存上限的 Job 并把 `child` 放进去
+    pub(crate) fn assign(child: &tokio::process::Child, max_bytes: u64) -> Result<Self, PluginError> {
+        use windows::core::PCWSTR;
+        use windows::Win32::Foundation::HANDLE;
+        use windows::Win32::System::JobObjects::{
+            AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+            SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+        };
+
+        let failed = |e: windows::core::Error| {
+            PluginError::SandboxError(format!("Failed to apply the memory limit: {}", e))
+        };
+        let process = child
+            .raw_handle()
+            .ok_or_else(|| PluginError::SandboxError("Plugin process already exited".to_string()))?;
+
+        unsafe {
+            let job = CreateJobObjectW(None, PCWSTR::null()).map_err(failed)?;
+            // Owns the handle from here on, so every error path closes it
+            let guard = Self { handle: job.0 as isize };
+
+            let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+            info.BasicLimitInformation.LimitFlags =
+                JOB_OBJECT_LIMIT_PROCESS_MEMORY | JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            info.ProcessMemoryLimit = max_bytes as usize;
+            SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const core::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            )
+            .map_err(failed)?;
+            AssignProcessToJobObject(job, HANDLE(process)).map_err(failed)?;
+            Ok(guard)
+        }
+    }
+
+    /// 进程内存用量峰值（字节）
+    pub(crate) fn peak_memory(&self) -> Option<u64> {
+        use windows::Win32::Foundation::HANDLE;
+        use windows::Win32::System::JobObjects::{
+            JobObjectExtendedLimitInformation, QueryInformationJobObject,
+            JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        };
+
+        let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+        unsafe {
+            QueryInformationJobObject(
+                HANDLE(self.handle as *mut core::ffi::c_void),
+                JobObjectExtendedLimitInformation,
+                &mut info as *mut _ as *mut core::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                None,
+            )
+            .ok()?;
+        }
+        Some(info.PeakProcessMemoryUsed as u64)
+    }
+}
+
+#[cfg(windows)]
+impl Drop for MemoryJob {
+    fn drop(&mut self) {
+        use windows::Win32::Foundation::{CloseHandle, HANDLE};
+        unsafe {
+            let _ = CloseHandle(HANDLE(self.handle as *mut core::ffi::c_void));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_memory_means_unlimited() {
+        let config = PluginSandboxConfig {
+            max_memory_bytes: 0,
+            ..Default::default()
+        };
+        let limits = SandboxLimits::from(&config);
+        assert_eq!(limits.max_memory_bytes, None);
+        assert_eq!(limits.call_timeout, Duration::from_millis(config.timeout_ms));
+        assert!(SandboxLimits::default().max_memory_bytes.is_some());
+    }
+
+    #[test]
+    fn test_allocation_failure_messages() {
+        assert!(reports_allocation_failure("memory allocation of 1048576 bytes failed"));
+        assert!(reports_allocation_failure("FATAL ERROR: Reached heap limit Allocation failed - JavaScript heap out of memory"));
+        assert!(reports_allocation_failure("MemoryError"));
+        assert!(!reports_allocation_failure("thread 'main' panicked at src/main.rs:3:5"));
+        assert!(!reports_allocation_failure("Segmentation fault"));
+    }
+}
@@ -1,9 +1,12 @@
 //! Plugin Loader
 //! 插件加载器 - 负责从文件系统加载插件
 
-use super::{InstalledPlugin, PluginMetadata, PluginStatus, PluginPermission, PluginError};
-use std::path::PathBuf;
-use chrono::Utc;
+use super::{InstalledPlugin, PluginCategory, PluginMetadata, PluginRuntime, PluginStatus, PluginPermission, PluginError, WorkflowNodeDefinition, PaletteCommandDefinition};
+use std::path::{Component, Path, PathBuf};
+use chrono::{DateTime, Utc};
+
+/// 当前应用版本，用于检查 `min_app_version`
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// 插件加载器
 pub struct PluginLoader;
@@ -14,7 +17,7 @@ impl PluginLoader {
         Self
     }
 
-    /// 从路径加载插件
+    /// 从路径加载插件，只读取 manifest；保存的状态由 `PluginManager` 合并
     pub async fn load(&self, path: &PathBuf) -> Result<InstalledPlugin, PluginError> {
         // 读取 manifest.json
         let manifest_path = path.join("manifest.json");
@@ -27,23 +30,7 @@ impl PluginLoader {
         let manifest_content = std::fs::read_to_string(&manifest_path)
             .map_err(|e| PluginError::IoError(e.to_string()))?;
 
-        let manifest: PluginManifest = serde_json::from_str(&manifest_content)
-            .map_err(|e| PluginError::InvalidManifest(e.to_string()))?;
-
-        // 读取状态文件（如果存在）
-        let state_path = path.parent()
-            .map(|p| p.join(format!("{}.state.json", manifest.id)))
-            .unwrap_or_default();
-
-        let (status, granted_permissions, config) = if state_path.exists() {
-            let state_content = std::fs::read_to_string(&state_path)
-                .map_err(|e| PluginError::IoError(e.to_string()))?;
-            let state: PluginState = serde_json::from_str(&state_content)
-                .unwrap_or_default();
-            (state.status, state.granted_permissions, state.config)
-        } else {
-            (PluginStatus::Installed, vec![], std::collections::HashMap::new())
-        };
+        let manifest = parse_manifest(&manifest_content, APP_VERSION)?;
 
         Ok(InstalledPlugin {
             metadata: PluginMetadata {
@@ -60,14 +47,19 @@ impl PluginLoader {
                 category: manifest.category.unwrap_or_default(),
                 min_app_version: manifest.min_app_version,
             },
-            status,
+            status: PluginStatus::Installed,
             permissions: manifest.permissions.unwrap_or_default(),
-            granted_permissions,
+            granted_permissions: vec![],
             installed_at: Utc::now(),
             updated_at: Utc::now(),
-            config,
+            config: std::collections::HashMap::new(),
             error: None,
             path: Some(path.clone()),
+            entrypoint: manifest.main.unwrap_or_else(super::default_entrypoint),
+            runtime: manifest.runtime.unwrap_or_default(),
+            config_schema: manifest.config_schema,
+            workflow_nodes: manifest.workflow_nodes.unwrap_or_default(),
+            palette_commands: manifest.palette_commands.unwrap_or_default(),
         })
     }
 
@@ -112,8 +104,9 @@ impl PluginLoader {
         let manifest_entry = archive.by_name("manifest.json")
             .map_err(|_| PluginError::InvalidManifest("manifest.json not found in archive".to_string()))?;
         
-        let manifest: PluginManifest = serde_json::from_reader(manifest_entry)
-            .map_err(|e| PluginError::InvalidManifest(e.to_string()))?;
+        let manifest_content = std::io::read_to_string(manifest_entry)
+            .map_err(|e| PluginError::IoError(e.to_string()))?;
+        let manifest = parse_manifest(&manifest_content, APP_VERSION)?;
 
         // 目标目录
         let plugin_dir = plugins_dir.join(&manifest.id);
@@ -170,19 +163,7 @@ impl PluginLoader {
         let manifest_content = std::fs::read_to_string(&manifest_path)
             .map_err(|e| PluginError::IoError(e.to_string()))?;
 
-        let manifest: PluginManifest = serde_json::from_str(&manifest_content)
-            .map_err(|e| PluginError::InvalidManifest(e.to_string()))?;
-
-        // 验证必要字段
-        if manifest.id.is_empty() {
-            return Err(PluginError::InvalidManifest("Plugin ID is required".to_string()));
-        }
-        if manifest.name.is_empty() {
-            return Err(PluginError::InvalidManifest("Plugin name is required".to_string()));
-        }
-        if manifest.version.is_empty() {
-            return Err(PluginError::InvalidManifest("Plugin version is required".to_string()));
-        }
+        let manifest = parse_manifest(&manifest_content, APP_VERSION)?;
 
         // 验证主入口文件
         let main_file = path.join(&manifest.main.unwrap_or_else(|| "index.js".to_string()));
@@ -196,13 +177,25 @@ impl PluginLoader {
     }
 }
 
-/// 插件清单文件结构
+/// 插件清单文件结构 (manifest.json)
+///
+/// Required: `id` (lowercase letters, digits, `-`, `_`, `.`), `name` and
+/// `version` (semver, e.g. `1.2.0`). Optional: `description`, `author`,
+/// `category` (search/action/workflow/theme/integration/utility/other),
+/// `permissions` (e.g. `["clipboard:read", "network"]`), `entrypoint` (alias
+/// `main`, relative to the plugin directory, default `index.js`), `runtime`
+/// (node/deno/python/binary, default node), `min_app_version` (semver),
+/// `config_schema` (JSON Schema for the plugin's settings),
+/// `workflow_nodes` (node types the plugin provides to workflows) and
+/// `palette_commands` (actions listed in the "cmd " command palette).
 #[derive(Debug, serde::Deserialize)]
 struct PluginManifest {
     id: String,
     name: String,
     version: String,
+    #[serde(default)]
     description: String,
+    #[serde(default)]
     author: String,
     #[serde(default)]
     homepage: Option<String>,
@@ -215,22 +208,402 @@ struct PluginManifest {
     #[serde(default)]
     keywords: Option<Vec<String>>,
     #[serde(default)]
-    category: Option<super::PluginCategory>,
+    category: Option<PluginCategory>,
     #[serde(default)]
     min_app_version: Option<String>,
-    #[serde(default)]
+    /// Entry file relative to the plugin directory (default `index.js`);
+    /// `entrypoint` is accepted as an alias
+    #[serde(default, alias = "entrypoint")]
     main: Option<String>,
+    /// How the entry file is run: node (default), deno, python or binary
+    #[serde(default)]
+    runtime: Option<PluginRuntime>,
     #[serde(default)]
     permissions: Option<Vec<PluginPermission>>,
+    /// JSON Schema for the plugin's settings
+    #[serde(default)]
+    config_schema: Option<serde_json::Value>,
+    /// Node types this plugin runs in workflows
+    #[serde(default)]
+    workflow_nodes: Option<Vec<WorkflowNodeDefinition>>,
+    /// Actions run by name from the command palette
+    #[serde(default)]
+    palette_commands: Option<Vec<PaletteCommandDefinition>>,
+}
+
+fn invalid(field: &str, why: impl Into<String>) -> PluginError {
+    PluginError::InvalidManifest(format!("{}: {}", field, why.into()))
+}
+
+fn required_string<'a>(
+    object: &'a serde_json::Map<String, serde_json::Value>,
+    field: &str,
+) -> Result<&'a str, PluginError> {
+    match object.get(field) {
+        None | Some(serde_json::Value::Null) => Err(invalid(field, "required field is missing")),
+        Some(serde_json::Value::String(value)) if value.trim().is_empty() => {
+            Err(invalid(field, "must not be empty"))
+        }
+        Some(serde_json::Value::String(value)) => Ok(value),
+        Some(_) => Err(invalid(field, "must be a string")),
+    }
+}
+
+/// Names accepted for a serde enum, e.g. all permission strings
+fn known_names<T: serde::Serialize>(values: &[T]) -> String {
+    values
+        .iter()
+        .filter_map(|value| serde_json::to_value(value).ok())
+        .filter_map(|value| value.as_str().map(str::to_string))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
-/// 插件状态文件结构
-#[derive(Debug, Default, serde::Deserialize)]
-struct PluginState {
+/// 解析并校验 manifest.json，错误信息指明出错的字段及原因
+fn parse_manifest(content: &str, app_version: &str) -> Result<PluginManifest, PluginError> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| invalid("manifest.json", format!("not valid JSON ({})", e)))?;
+    let object = value
+        .as_object()
+        .ok_or_else(|| invalid("manifest.json", "must be a JSON object"))?;
+
+    let id = required_string(object, "id")?;
+    let valid_id = !id.starts_with('.')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'));
+    if !valid_id {
+        return Err(invalid(
+            "id",
+            format!("'{}' may only contain lowercase letters, digits, '-', '_' and '.'", id),
+        ));
+    }
+    required_string(object, "name")?;
+    let version = required_string(object, "version")?;
+    semver::Version::parse(version).map_err(|e| {
+        invalid("version", format!("'{}' is not a valid semantic version ({})", version, e))
+    })?;
+
+    if let Some(category) = object.get("category") {
+        if serde_json::from_value::<PluginCategory>(category.clone()).is_err() {
+            let all = [
+                PluginCategory::Search,
+                PluginCategory::Action,
+                PluginCategory::Workflow,
+                PluginCategory::Theme,
+                PluginCategory::Integration,
+                PluginCategory::Utility,
+                PluginCategory::Other,
+            ];
+            return Err(invalid(
+                "category",
+                format!("unknown category {} (expected one of {})", category, known_names(&all)),
+            ));
+        }
+    }
+
+    if let Some(permissions) = object.get("permissions") {
+        let permissions = permissions
+            .as_array()
+            .ok_or_else(|| invalid("permissions", "must be an array of permission names"))?;
+        for (i, permission) in permissions.iter().enumerate() {
+            if serde_json::from_value::<PluginPermission>(permission.clone()).is_err() {
+                return Err(invalid(
+                    &format!("permissions[{}]", i),
+                    format!(
+                        "unknown permission {} (expected one of {})",
+                        permission,
+                        known_names(&PluginPermission::ALL)
+                    ),
+                ));
+            }
+        }
+    }
+
+    if let Some(runtime) = object.get("runtime") {
+        if serde_json::from_value::<PluginRuntime>(runtime.clone()).is_err() {
+            let all = [PluginRuntime::Node, PluginRuntime::Deno, PluginRuntime::Python, PluginRuntime::Binary];
+            return Err(invalid(
+                "runtime",
+                format!("unknown runtime {} (expected one of {})", runtime, known_names(&all)),
+            ));
+        }
+    }
+
+    if let Some(schema) = object.get("config_schema") {
+        if !schema.is_object() {
+            return Err(invalid("config_schema", "must be a JSON Schema object"));
+        }
+    }
+
+    if let Some(nodes) = object.get("workflow_nodes") {
+        let nodes = nodes
+            .as_array()
+            .ok_or_else(|| invalid("workflow_nodes", "must be an array"))?;
+        let mut seen = std::collections::HashSet::new();
+        for (i, node) in nodes.iter().enumerate() {
+            let field = format!("workflow_nodes[{}]", i);
+            let node: WorkflowNodeDefinition =
+                serde_json::from_value(node.clone()).map_err(|e| invalid(&field, e.to_string()))?;
+            if !seen.insert(node.node_type.clone()) {
+                return Err(invalid(&field, format!("duplicate node type '{}'", node.node_type)));
+            }
+        }
+    }
+
+    if let Some(commands) = object.get("palette_commands") {
+        let commands = commands
+            .as_array()
+            .ok_or_else(|| invalid("palette_commands", "must be an array"))?;
+        let mut seen = std::collections::HashSet::new();
+        for (i, command) in commands.iter().enumerate() {
+            let field = format!("palette_commands[{}]", i);
+            let command: PaletteCommandDefinition =
+                serde_json::from_value(command.clone()).map_err(|e| invalid(&field, e.to_string()))?;
+            if command.id.trim().is_empty() || command.label.trim().is_empty() {
+                return Err(invalid(&field, "id and label must not be empty"));
+            }
+            if !seen.insert(command.id.clone()) {
+                return Err(invalid(&field, format!("duplicate command id '{}'", command.id)));
+            }
+        }
+    }
+
+    for field in ["entrypoint", "main"] {
+        let Some(entry) = object.get(field) else {
+            continue;
+        };
+        let entry = entry
+            .as_str()
+            .filter(|entry| !entry.trim().is_empty())
+            .ok_or_else(|| invalid(field, "must be a non-empty relative path"))?;
+        let path = Path::new(entry);
+        if path.is_absolute() || path.components().any(|c| matches!(c, Component::ParentDir)) {
+            return Err(invalid(field, format!("'{}' must stay inside the plugin directory", entry)));
+        }
+    }
+
+    let manifest: PluginManifest = serde_json::from_value(value.clone())
+        .map_err(|e| invalid("manifest.json", e.to_string()))?;
+
+    if let Some(min) = &manifest.min_app_version {
+        let required = semver::Version::parse(min).map_err(|e| {
+            invalid("min_app_version", format!("'{}' is not a valid semantic version ({})", min, e))
+        })?;
+        let running = semver::Version::parse(app_version).map_err(|e| {
+            PluginError::VersionMismatch(format!("Invalid app version '{}': {}", app_version, e))
+        })?;
+        if required > running {
+            return Err(PluginError::VersionMismatch(format!(
+                "Plugin '{}' requires app version {} or newer, running {}",
+                manifest.id, min, app_version
+            )));
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// 插件状态文件结构（`save_plugin_state` 写入的 `InstalledPlugin` 中需要保留的部分）
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct PluginState {
     #[serde(default)]
     status: PluginStatus,
     #[serde(default)]
     granted_permissions: Vec<PluginPermission>,
     #[serde(default)]
     config: std::collections::HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    installed_at: Option<DateTime<Utc>>,
+}
+
+impl PluginState {
+    /// 读取状态文件；文件不存在或已损坏时返回 `None`（损坏时记录警告）
+    pub(crate) fn read(path: &Path) -> Option<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                tracing::warn!("Failed to read plugin state {:?}: {}", path, e);
+                return None;
+            }
+        };
+        match serde_json::from_str(&content) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                tracing::warn!("Ignoring corrupt plugin state {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    /// 把保存的状态合并到刚从 manifest 加载的插件上
+    ///
+    /// 只保留 manifest 仍然声明的权限；中断的更新按禁用处理。
+    pub(crate) fn apply(self, plugin: &mut InstalledPlugin) {
+        plugin.status = match self.status {
+            PluginStatus::Updating => PluginStatus::Disabled,
+            status => status,
+        };
+        plugin.granted_permissions = self
+            .granted_permissions
+            .into_iter()
+            .filter(|p| plugin.permissions.contains(p))
+            .collect();
+        plugin.config = self.config;
+        if let Some(installed_at) = self.installed_at {
+            plugin.installed_at = installed_at;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> serde_json::Value {
+        serde_json::json!({
+            "id": "color-picker",
+            "name": "Color Picker",
+            "version": "1.2.0",
+            "category": "utility",
+            "permissions": ["clipboard:write"],
+            "entrypoint": "dist/main.js",
+            "runtime": "deno",
+            "min_app_version": "0.1.0"
+        })
+    }
+
+    fn parse(value: &serde_json::Value) -> Result<PluginManifest, PluginError> {
+        parse_manifest(&value.to_string(), "0.1.0")
+    }
+
+    fn invalid_message(value: &serde_json::Value) -> String {
+        match parse(value) {
+            Err(PluginError::InvalidManifest(message)) => message,
+            other => panic!("expected InvalidManifest, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_valid_manifest() {
+        let manifest = parse(&manifest()).unwrap();
+        assert_eq!(manifest.id, "color-picker");
+        assert_eq!(manifest.main.as_deref(), Some("dist/main.js"));
+        assert_eq!(manifest.runtime, Some(PluginRuntime::Deno));
+        assert_eq!(manifest.permissions, Some(vec![PluginPermission::ClipboardWrite]));
+    }
+
+    #[test]
+    fn test_missing_id() {
+        let mut value = manifest();
+        value.as_object_mut().unwrap().remove("id");
+        assert_eq!(invalid_message(&value), "id: required field is missing");
+
+        value["id"] = serde_json::json!("Color Picker");
+        assert!(invalid_message(&value).starts_with("id: 'Color Picker' may only contain"));
+    }
+
+    #[test]
+    fn test_bad_semver() {
+        let mut value = manifest();
+        value["version"] = serde_json::json!("1.0");
+        assert!(invalid_message(&value).starts_with("version: '1.0' is not a valid semantic version"));
+
+        value["version"] = serde_json::json!(1);
+        assert_eq!(invalid_message(&value), "version: must be a string");
+    }
+
+    #[test]
+    fn test_unknown_permission() {
+        let mut value = manifest();
+        value["permissions"] = serde_json::json!(["clipboard:read", "camera"]);
+        assert!(invalid_message(&value).starts_with(
+            "permissions[1]: unknown permission \"camera\" (expected one of clipboard:read, clipboard:write,"
+        ));
+    }
+
+    #[test]
+    fn test_unknown_category_and_runtime() {
+        let mut value = manifest();
+        value["category"] = serde_json::json!("games");
+        assert!(invalid_message(&value).starts_with("category: unknown category \"games\""));
+
+        let mut value = manifest();
+        value["runtime"] = serde_json::json!("ruby");
+        assert!(invalid_message(&value).starts_with("runtime: unknown runtime \"ruby\""));
+    }
+
+    #[test]
+    fn test_entrypoint_must_stay_in_plugin_dir() {
+        let mut value = manifest();
+        value["entrypoint"] = serde_json::json!("../../evil.js");
+        assert_eq!(
+            invalid_message(&value),
+            "entrypoint: '../../evil.js' must stay inside the plugin directory"
+        );
+    }
+
+    #[test]
+    fn test_workflow_nodes() {
+        let node = serde_json::json!({
+            "type": "uppercase",
+            "name": "Uppercase",
+            "description": "Uppercases text",
+            "inputs": [{ "name": "text", "type": "string", "required": true }],
+            "outputs": [{ "name": "text", "type": "string" }]
+        });
+        let mut value = manifest();
+        value["workflow_nodes"] = serde_json::json!([node]);
+        let nodes = parse(&value).unwrap().workflow_nodes.unwrap();
+        assert_eq!(nodes[0].node_type, "uppercase");
+        assert!(nodes[0].inputs[0].required);
+
+        value["workflow_nodes"] = serde_json::json!([node, node]);
+        assert_eq!(invalid_message(&value), "workflow_nodes[1]: duplicate node type 'uppercase'");
+    }
+
+    #[test]
+    fn test_palette_commands() {
+        let command = serde_json::json!({
+            "id": "add",
+            "label": "Add Todo",
+            "action": { "type": "add" }
+        });
+        let mut value = manifest();
+        value["palette_commands"] = serde_json::json!([command]);
+        let commands = parse(&value).unwrap().palette_commands.unwrap();
+        assert_eq!(commands[0].label, "Add Todo");
+        assert!(!commands[0].confirm);
+
+        value["palette_commands"] = serde_json::json!([command, command]);
+        assert_eq!(invalid_message(&value), "palette_commands[1]: duplicate command id 'add'");
+    }
+
+    #[test]
+    fn test_min_app_version_newer_than_app() {
+        let mut value = manifest();
+        value["min_app_version"] = serde_json::json!("2.0.0");
+        assert!(matches!(
+            parse(&value),
+            Err(PluginError::VersionMismatch(ref message)) if message.contains("requires app version 2.0.0")
+        ));
+
+        value["min_app_version"] = serde_json::json!("latest");
+        assert!(invalid_message(&value).starts_with("min_app_version: 'latest' is not a valid semantic version"));
+    }
+
+    #[tokio::test]
+    async fn test_load_rejects_invalid_manifest() {
+        let dir = std::env::temp_dir().join(format!("omnibox_plugin_manifest_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut value = manifest();
+        value["version"] = serde_json::json!("v1");
+        std::fs::write(dir.join("manifest.json"), value.to_string()).unwrap();
+
+        let result = PluginLoader::new().load(&dir).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(result, Err(PluginError::InvalidManifest(ref m)) if m.starts_with("version:")));
+    }
 }
@@ -7,15 +7,22 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
+use crate::core::palette::{PaletteCommand, PaletteHandler};
 
+pub mod config;
+pub mod limits;
 pub mod loader;
 pub mod sandbox;
 pub mod registry;
+pub mod rpc;
+pub mod search;
 
 // Re-exports
 pub use loader::PluginLoader;
+pub use limits::SandboxLimits;
 pub use sandbox::PluginSandbox;
 pub use registry::PluginRegistry;
+pub use search::{search_plugins, SearchPluginSource};
 
 /// 插件分类
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -58,6 +65,40 @@ pub enum PluginPermission {
     System,
 }
 
+impl PluginPermission {
+    /// 所有权限，用于校验 manifest 与提示
+    pub const ALL: [PluginPermission; 8] = [
+        Self::ClipboardRead,
+        Self::ClipboardWrite,
+        Self::FsRead,
+        Self::FsWrite,
+        Self::Network,
+        Self::Shell,
+        Self::Notification,
+        Self::System,
+    ];
+}
+
+/// 插件运行时 - 决定如何启动 entrypoint
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginRuntime {
+    /// `node <entrypoint>`; requires the `shell` permission
+    Node,
+    /// `deno run <entrypoint>`, with Deno permissions derived from granted permissions
+    Deno,
+    /// `python3 <entrypoint>` (`python` on Windows); requires the `shell` permission
+    Python,
+    /// The entrypoint is a native executable; requires the `shell` permission
+    Binary,
+}
+
+impl Default for PluginRuntime {
+    fn default() -> Self {
+        Self::Node
+    }
+}
+
 /// 插件状态
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -115,6 +156,25 @@ pub struct InstalledPlugin {
     /// 插件安装路径
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<PathBuf>,
+    /// 入口文件（相对插件目录）
+    #[serde(default = "default_entrypoint")]
+    pub entrypoint: String,
+    /// 入口文件的运行时
+    #[serde(default)]
+    pub runtime: PluginRuntime,
+    /// manifest 声明的配置 JSON Schema，`set_config` 按它校验
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_schema: Option<serde_json::Value>,
+    /// manifest 声明的工作流节点
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workflow_nodes: Vec<WorkflowNodeDefinition>,
+    /// manifest 声明的命令面板命令
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub palette_commands: Vec<PaletteCommandDefinition>,
+}
+
+pub(crate) fn default_entrypoint() -> String {
+    "index.js".to_string()
 }
 
 /// 插件市场项目
@@ -238,6 +298,21 @@ pub struct WorkflowNodeDefinition {
     pub config_schema: Option<serde_json::Value>,
 }
 
+/// 命令面板命令定义 - 在 "cmd " 中按名称运行插件动作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaletteCommandDefinition {
+    /// 插件内唯一，面板中的 id 为 `<插件 id>:<id>`
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub description: String,
+    /// 选中时交给插件执行的动作
+    pub action: PluginAction,
+    /// 运行前是否需要确认
+    #[serde(default)]
+    pub confirm: bool,
+}
+
 /// 插件管理器
 pub struct PluginManager {
     /// 已安装的插件
@@ -255,10 +330,15 @@ pub struct PluginManager {
 impl PluginManager {
     /// 创建新的插件管理器
     pub fn new(plugins_dir: PathBuf) -> Self {
+        Self::with_limits(plugins_dir, SandboxLimits::default())
+    }
+
+    /// 使用自定义沙箱资源限制创建插件管理器
+    pub fn with_limits(plugins_dir: PathBuf, limits: SandboxLimits) -> Self {
         Self {
             plugins: Arc::new(RwLock::new(HashMap::new())),
             loader: PluginLoader::new(),
-            sandbox: PluginSandbox::new(),
+            sandbox: PluginSandbox::with_limits(limits),
             registry: PluginRegistry::new(),
             plugins_dir,
         }
@@ -287,7 +367,12 @@ impl PluginManager {
             let entry = entry.map_err(|e| PluginError::IoError(e.to_string()))?;
             let path = entry.path();
             
-            if path.is_dir() {
+            // 跳过更新时的暂存/备份目录
+            let hidden = path
+                .file_name()
+                .map(|name| name.to_string_lossy().starts_with('.'))
+                .unwrap_or(false);
+            if path.is_dir() && !hidden {
                 if let Err(e) = self.load_plugin_from_path(&path).await {
                     tracing::warn!("Failed to load plugin from {:?}: {}", path, e);
                 }
@@ -297,9 +382,14 @@ impl PluginManager {
         Ok(())
     }
 
-    /// 从路径加载插件
+    /// 从路径加载插件，并合并 `save_plugin_state` 保存的状态、权限和配置
+    ///
+    /// 没有状态文件（或文件损坏）时为刚安装、未授权、无配置的插件。
     async fn load_plugin_from_path(&self, path: &PathBuf) -> Result<InstalledPlugin, PluginError> {
-        let plugin = self.loader.load(path).await?;
+        let mut plugin = self.loader.load(path).await?;
+        if let Some(state) = loader::PluginState::read(&self.state_path(&plugin.metadata.id)) {
+            state.apply(&mut plugin);
+        }
         
         let mut plugins = self.plugins.write().await;
         plugins.insert(plugin.metadata.id.clone(), plugin.clone());
@@ -360,6 +450,12 @@ impl PluginManager {
                 std::fs::remove_dir_all(path)
                     .map_err(|e| PluginError::IoError(e.to_string()))?;
             }
+            // 删除状态文件，重新安装时不沿用旧的权限和配置
+            let state_file = self.state_path(plugin_id);
+            if state_file.exists() {
+                std::fs::remove_file(&state_file)
+                    .map_err(|e| PluginError::IoError(e.to_string()))?;
+            }
         } else {
             return Err(PluginError::NotFound(plugin_id.to_string()));
         }
@@ -394,19 +490,115 @@ impl PluginManager {
     }
 
     /// 更新插件
+    ///
+    /// 新版本先下载到 `plugins_dir` 下的暂存目录并校验，成功后才替换旧版本；
+    /// 任何一步失败都会保留（或恢复）旧版本，并在 `UpdateFailed` 中返回它。
     pub async fn update_plugin(&self, plugin_id: &str) -> Result<InstalledPlugin, PluginError> {
         // 获取当前插件
         let current = self.get_plugin(plugin_id).await
             .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?;
-        
-        // 保存当前权限
-        let permissions = current.granted_permissions.clone();
-        
-        // 卸载旧版本
-        self.uninstall_plugin(plugin_id).await?;
-        
-        // 安装新版本
-        self.install_plugin(plugin_id, None, permissions).await
+
+        let staging_root = self.plugins_dir.join(format!(".staging-{}", uuid::Uuid::new_v4()));
+        let staged = async {
+            let download_url = self.registry.get_download_url(plugin_id, None).await?;
+            self.loader.download_and_extract(&download_url, &staging_root).await
+        }
+        .await;
+
+        let result = match staged {
+            Ok(staged) => self.update_from_staged(&current, &staged).await,
+            Err(e) => Err(PluginError::UpdateFailed {
+                restored: Box::new(current.clone()),
+                reason: e.to_string(),
+            }),
+        };
+        let _ = std::fs::remove_dir_all(&staging_root);
+        result
+    }
+
+    /// 用暂存目录中的新版本替换 `current`，保留已授予的权限、配置和状态
+    async fn update_from_staged(
+        &self,
+        current: &InstalledPlugin,
+        staged: &std::path::Path,
+    ) -> Result<InstalledPlugin, PluginError> {
+        let failed = |e: PluginError| PluginError::UpdateFailed {
+            restored: Box::new(current.clone()),
+            reason: e.to_string(),
+        };
+
+        // 替换前校验新版本
+        let staged_plugin = self.loader.load(&staged.to_path_buf()).await.map_err(failed)?;
+        if staged_plugin.metadata.id != current.metadata.id {
+            return Err(failed(PluginError::InvalidManifest(format!(
+                "id: update is for '{}', expected '{}'",
+                staged_plugin.metadata.id, current.metadata.id
+            ))));
+        }
+
+        let target = current
+            .path
+            .clone()
+            .unwrap_or_else(|| self.plugins_dir.join(&current.metadata.id));
+        let backup = self
+            .plugins_dir
+            .join(format!(".backup-{}-{}", current.metadata.id, uuid::Uuid::new_v4()));
+        let had_previous = target.exists();
+        if had_previous {
+            std::fs::rename(&target, &backup)
+                .map_err(|e| failed(PluginError::IoError(e.to_string())))?;
+        }
+
+        match self.swap_in(current, staged, &target).await {
+            Ok(plugin) => {
+                if had_previous {
+                    let _ = std::fs::remove_dir_all(&backup);
+                }
+                Ok(plugin)
+            }
+            Err(e) => {
+                // 恢复旧版本
+                let _ = std::fs::remove_dir_all(&target);
+                if had_previous {
+                    if let Err(restore_error) = std::fs::rename(&backup, &target) {
+                        tracing::error!(
+                            "Failed to restore plugin '{}' from {:?}: {}",
+                            current.metadata.id,
+                            backup,
+                            restore_error
+                        );
+                    }
+                }
+                self.plugins
+                    .write()
+                    .await
+                    .insert(current.metadata.id.clone(), current.clone());
+                Err(failed(e))
+            }
+        }
+    }
+
+    /// 把暂存的新版本移到 `target` 并加载，沿用旧版本的权限、配置和状态
+    async fn swap_in(
+        &self,
+        current: &InstalledPlugin,
+        staged: &std::path::Path,
+        target: &PathBuf,
+    ) -> Result<InstalledPlugin, PluginError> {
+        std::fs::rename(staged, target).map_err(|e| PluginError::IoError(e.to_string()))?;
+
+        let mut plugin = self.loader.load(target).await?;
+        plugin.granted_permissions = current.granted_permissions.clone();
+        plugin.config = current.config.clone();
+        plugin.status = current.status.clone();
+        plugin.installed_at = current.installed_at;
+
+        self.save_plugin_state(&plugin).await?;
+        self.plugins
+            .write()
+            .await
+            .insert(plugin.metadata.id.clone(), plugin.clone());
+        Ok(plugin)
     }
 
     /// 检查插件更新
@@ -468,7 +660,7 @@ impl PluginManager {
 
     /// 保存插件状态
     async fn save_plugin_state(&self, plugin: &InstalledPlugin) -> Result<(), PluginError> {
-        let state_file = self.plugins_dir.join(format!("{}.state.json", plugin.metadata.id));
+        let state_file = self.state_path(&plugin.metadata.id);
         let content = serde_json::to_string_pretty(plugin)
             .map_err(|e| PluginError::SerializationError(e.to_string()))?;
         
@@ -478,6 +670,41 @@ impl PluginManager {
         Ok(())
     }
 
+    /// 获取插件配置
+    pub async fn get_config(
+        &self,
+        plugin_id: &str,
+    ) -> Result<HashMap<String, serde_json::Value>, PluginError> {
+        let plugins = self.plugins.read().await;
+        plugins
+            .get(plugin_id)
+            .map(|plugin| plugin.config.clone())
+            .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))
+    }
+
+    /// 替换插件配置，先按插件声明的 schema 校验再保存
+    pub async fn set_config(
+        &self,
+        plugin_id: &str,
+        config: HashMap<String, serde_json::Value>,
+    ) -> Result<(), PluginError> {
+        let mut plugins = self.plugins.write().await;
+
+        let plugin = plugins.get_mut(plugin_id)
+            .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?;
+
+        if let Some(schema) = &plugin.config_schema {
+            config::validate_config(schema, &config)?;
+        }
+        plugin.config = config;
+        self.save_plugin_state(plugin).await
+    }
+
+    /// 插件状态文件路径
+    fn state_path(&self, plugin_id: &str) -> PathBuf {
+        self.plugins_dir.join(format!("{}.state.json", plugin_id))
+    }
+
     /// 检查插件是否有权限
     pub async fn has_permission(&self, plugin_id: &str, permission: &PluginPermission) -> bool {
         let plugins = self.plugins.read().await;
@@ -509,12 +736,12 @@ impl PluginManager {
         self.sandbox.execute_search(plugin, query, limit).await
     }
 
-    /// 执行插件动作
+    /// 执行插件动作，返回插件的 JSON-RPC 结果
     pub async fn execute_action(
         &self,
         plugin_id: &str,
         action: &PluginAction,
-    ) -> Result<(), PluginError> {
+    ) -> Result<serde_json::Value, PluginError> {
         let plugins = self.plugins.read().await;
         
         let plugin = plugins.get(plugin_id)
@@ -527,6 +754,65 @@ impl PluginManager {
         // 在沙箱中执行动作
         self.sandbox.execute_action(plugin, action).await
     }
+
+    /// 查找插件声明的工作流节点定义
+    pub async fn workflow_node_definition(
+        &self,
+        plugin_id: &str,
+        node_type: &str,
+    ) -> Option<WorkflowNodeDefinition> {
+        let plugins = self.plugins.read().await;
+        plugins
+            .get(plugin_id)?
+            .workflow_nodes
+            .iter()
+            .find(|node| node.node_type == node_type)
+            .cloned()
+    }
+
+    /// 已启用插件注册的命令面板命令
+    pub async fn palette_commands(&self) -> Vec<PaletteCommand> {
+        let plugins = self.plugins.read().await;
+        let mut commands: Vec<PaletteCommand> = plugins
+            .values()
+            .filter(|plugin| plugin.status == PluginStatus::Enabled)
+            .flat_map(|plugin| {
+                plugin.palette_commands.iter().map(|command| PaletteCommand {
+                    id: format!("{}:{}", plugin.metadata.id, command.id),
+                    label: command.label.clone(),
+                    description: command.description.clone(),
+                    handler: PaletteHandler::Plugin {
+                        plugin_id: plugin.metadata.id.clone(),
+                        action: command.action.clone(),
+                    },
+                    confirm: command.confirm,
+                })
+            })
+            .collect();
+        // HashMap 顺序不固定，按 id 排序让结果稳定
+        commands.sort_by(|a, b| a.id.cmp(&b.id));
+        commands
+    }
+
+    /// 执行插件提供的工作流节点，`inputs` 按输入端口名组织
+    pub async fn execute_workflow_node(
+        &self,
+        plugin_id: &str,
+        node_type: &str,
+        inputs: serde_json::Value,
+        config: serde_json::Value,
+    ) -> Result<serde_json::Value, PluginError> {
+        let plugins = self.plugins.read().await;
+
+        let plugin = plugins.get(plugin_id)
+            .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?;
+
+        if plugin.status != PluginStatus::Enabled {
+            return Err(PluginError::PluginDisabled(plugin_id.to_string()));
+        }
+
+        self.sandbox.execute_workflow_node(plugin, node_type, inputs, config).await
+    }
 }
 
 /// 插件错误类型
@@ -558,4 +844,221 @@ pub enum PluginError {
     
     #[error("Version mismatch: {0}")]
     VersionMismatch(String),
+
+    /// 配置不符合插件的 schema，每项为 `键: 原因`
+    #[error("Invalid plugin config: {}", .0.join("; "))]
+    InvalidConfig(Vec<String>),
+
+    /// 更新失败，`restored` 为保留下来的旧版本
+    #[error("Update failed, kept version {}: {reason}", .restored.metadata.version)]
+    UpdateFailed {
+        restored: Box<InstalledPlugin>,
+        reason: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_plugin(dir: &std::path::Path, version: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        let manifest = serde_json::json!({
+            "id": "demo",
+            "name": "Demo",
+            "version": version,
+            "permissions": ["clipboard:read"],
+        });
+        std::fs::write(dir.join("manifest.json"), manifest.to_string()).unwrap();
+        std::fs::write(dir.join("index.js"), format!("// {}", version)).unwrap();
+    }
+
+    /// Manager with "demo" 1.0.0 installed, enabled, granted and configured
+    async fn manager_with_plugin() -> (PluginManager, PathBuf) {
+        let plugins_dir = std::env::temp_dir().join(format!("omnibox_plugins_{}", uuid::Uuid::new_v4()));
+        write_plugin(&plugins_dir.join("demo"), "1.0.0");
+
+        let manager = PluginManager::new(plugins_dir.clone());
+        manager.init().await.unwrap();
+        manager.enable_plugin("demo").await.unwrap();
+        manager.grant_permission("demo", PluginPermission::ClipboardRead).await.unwrap();
+        manager
+            .plugins
+            .write()
+            .await
+            .get_mut("demo")
+            .unwrap()
+            .config
+            .insert("theme".to_string(), serde_json::json!("dark"));
+        (manager, plugins_dir)
+    }
+
+    #[tokio::test]
+    async fn test_failed_update_keeps_original() {
+        let (manager, plugins_dir) = manager_with_plugin().await;
+        let current = manager.get_plugin("demo").await.unwrap();
+        let staged = plugins_dir.join(".staging-test").join("demo");
+        write_plugin(&staged, "2.0");
+
+        let result = manager.update_from_staged(&current, &staged).await;
+
+        match result {
+            Err(PluginError::UpdateFailed { restored, reason }) => {
+                assert_eq!(restored.metadata.version, "1.0.0");
+                assert!(reason.contains("version"), "{}", reason);
+            }
+            other => panic!("expected UpdateFailed, got {:?}", other),
+        }
+        let plugin = manager.get_plugin("demo").await.unwrap();
+        assert_eq!(plugin.metadata.version, "1.0.0");
+        assert_eq!(plugin.status, PluginStatus::Enabled);
+        let manifest = std::fs::read_to_string(plugins_dir.join("demo").join("manifest.json")).unwrap();
+        assert!(manifest.contains("1.0.0"));
+
+        std::fs::remove_dir_all(plugins_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_failing_after_the_swap_restores_the_original() {
+        let (manager, plugins_dir) = manager_with_plugin().await;
+        let current = manager.get_plugin("demo").await.unwrap();
+        let staged = plugins_dir.join(".staging-test").join("demo");
+        write_plugin(&staged, "1.1.0");
+        // The new version installs, but its state cannot be saved
+        let state_file = manager.state_path("demo");
+        let _ = std::fs::remove_file(&state_file);
+        std::fs::create_dir_all(&state_file).unwrap();
+
+        let result = manager.update_from_staged(&current, &staged).await;
+
+        assert!(matches!(result, Err(PluginError::UpdateFailed { ref restored, .. }) if restored.metadata.version == "1.0.0"));
+        let plugin = manager.get_plugin("demo").await.unwrap();
+        assert_eq!(plugin.metadata.version, "1.0.0");
+        assert_eq!(plugin.status, PluginStatus::Enabled);
+        assert_eq!(plugin.granted_permissions, vec![PluginPermission::ClipboardRead]);
+        let entry = std::fs::read_to_string(plugins_dir.join("demo").join("index.js")).unwrap();
+        assert_eq!(entry, "// 1.0.0");
+        let leftovers: Vec<_> = std::fs::read_dir(&plugins_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with(".backup"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        std::fs::remove_dir_all(plugins_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_preserves_permissions_and_config() {
+        let (manager, plugins_dir) = manager_with_plugin().await;
+        let current = manager.get_plugin("demo").await.unwrap();
+        let staged = plugins_dir.join(".staging-test").join("demo");
+        write_plugin(&staged, "1.1.0");
+
+        let updated = manager.update_from_staged(&current, &staged).await.unwrap();
+
+        assert_eq!(updated.metadata.version, "1.1.0");
+        assert_eq!(updated.status, PluginStatus::Enabled);
+        assert_eq!(updated.granted_permissions, vec![PluginPermission::ClipboardRead]);
+        assert_eq!(updated.config.get("theme"), Some(&serde_json::json!("dark")));
+        assert_eq!(manager.get_plugin("demo").await.unwrap().metadata.version, "1.1.0");
+        let entry = std::fs::read_to_string(plugins_dir.join("demo").join("index.js")).unwrap();
+        assert_eq!(entry, "// 1.1.0");
+        // No backup left behind
+        let leftovers: Vec<_> = std::fs::read_dir(&plugins_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with(".backup"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        std::fs::remove_dir_all(plugins_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_for_other_plugin_is_rejected() {
+        let (manager, plugins_dir) = manager_with_plugin().await;
+        let current = manager.get_plugin("demo").await.unwrap();
+        let staged = plugins_dir.join(".staging-test").join("other");
+        write_plugin(&staged, "1.1.0");
+        let manifest = std::fs::read_to_string(staged.join("manifest.json")).unwrap();
+        std::fs::write(staged.join("manifest.json"), manifest.replace("\"demo\"", "\"other\"")).unwrap();
+
+        let result = manager.update_from_staged(&current, &staged).await;
+
+        assert!(matches!(result, Err(PluginError::UpdateFailed { ref reason, .. }) if reason.contains("expected 'demo'")));
+        assert_eq!(manager.get_plugin("demo").await.unwrap().metadata.version, "1.0.0");
+
+        std::fs::remove_dir_all(plugins_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_set_config_validates_against_schema() {
+        let (manager, plugins_dir) = manager_with_plugin().await;
+        manager.plugins.write().await.get_mut("demo").unwrap().config_schema = Some(serde_json::json!({
+            "properties": { "api_token": { "type": "string" } },
+            "required": ["api_token"],
+        }));
+
+        let bad = HashMap::from([("api_token".to_string(), serde_json::json!(1))]);
+        let result = manager.set_config("demo", bad).await;
+        assert!(matches!(result, Err(PluginError::InvalidConfig(ref keys)) if keys[0].starts_with("api_token")));
+        assert_eq!(manager.get_config("demo").await.unwrap().get("theme"), Some(&serde_json::json!("dark")));
+
+        let good = HashMap::from([("api_token".to_string(), serde_json::json!("secret"))]);
+        manager.set_config("demo", good.clone()).await.unwrap();
+        assert_eq!(manager.get_config("demo").await.unwrap(), good);
+        let state = std::fs::read_to_string(plugins_dir.join("demo.state.json")).unwrap();
+        assert!(state.contains("secret"));
+
+        std::fs::remove_dir_all(plugins_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_saved_state_survives_reload() {
+        let (manager, plugins_dir) = manager_with_plugin().await;
+        manager.disable_plugin("demo").await.unwrap();
+        let installed_at = manager.get_plugin("demo").await.unwrap().installed_at;
+
+        let reloaded = PluginManager::new(plugins_dir.clone());
+        reloaded.init().await.unwrap();
+
+        let plugin = reloaded.get_plugin("demo").await.unwrap();
+        assert_eq!(plugin.status, PluginStatus::Disabled);
+        assert_eq!(plugin.granted_permissions, vec![PluginPermission::ClipboardRead]);
+        assert_eq!(plugin.installed_at, installed_at);
+
+        std::fs::remove_dir_all(plugins_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload_merges_state_file_over_manifest() {
+        let plugins_dir = std::env::temp_dir().join(format!("omnibox_plugins_{}", uuid::Uuid::new_v4()));
+        write_plugin(&plugins_dir.join("demo"), "1.0.0");
+        let state = serde_json::json!({
+            "status": "disabled",
+            // "network" is not declared by the manifest
+            "granted_permissions": ["clipboard:read", "network"],
+            "config": { "theme": "dark" },
+        });
+        std::fs::write(plugins_dir.join("demo.state.json"), state.to_string()).unwrap();
+
+        let manager = PluginManager::new(plugins_dir.clone());
+        manager.init().await.unwrap();
+
+        let plugin = manager.get_plugin("demo").await.unwrap();
+        assert_eq!(plugin.status, PluginStatus::Disabled);
+        assert_eq!(plugin.granted_permissions, vec![PluginPermission::ClipboardRead]);
+        assert_eq!(plugin.config.get("theme"), Some(&serde_json::json!("dark")));
+
+        // Without a usable state file the plugin starts as freshly installed
+        std::fs::write(plugins_dir.join("demo.state.json"), "{ not json").unwrap();
+        let manager = PluginManager::new(plugins_dir.clone());
+        manager.init().await.unwrap();
+        let plugin = manager.get_plugin("demo").await.unwrap();
+        assert_eq!(plugin.status, PluginStatus::Installed);
+        assert!(plugin.granted_permissions.is_empty());
+
+        std::fs::remove_dir_all(plugins_dir).unwrap();
+    }
 }
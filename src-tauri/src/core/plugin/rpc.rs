@@ -0,0 +1,140 @@
+//! Plugin RPC Protocol
+//! 插件进程通信协议 - JSON-RPC 2.0 over stdio
+//!
+//! The sandbox starts the plugin's entrypoint once per call, writes a single
+//! request line to its stdin and closes it, then reads stdout line by line
+//! until it finds the response with the same `id`. Other stdout lines are
+//! treated as plugin logs; stderr is logged too.
+//!
+//! Request:  `{"jsonrpc":"2.0","id":1,"method":"action","params":{...}}`
+//! Response: `{"jsonrpc":"2.0","id":1,"result":...}` or
+//!           `{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"..."}}`
+//!
+//! Methods:
+//! - `search`: params `{ "query": "...", "limit": 10, "config": {...} }`,
+//!   result is an array of `PluginSearchResult`
+//! - `action`: params `{ "action": PluginAction, "config": {...} }`
+//! - `workflow_node`: params `{ "node_type": "...", "inputs": {...}, "config": {...} }`,
+//!   result is an object keyed by output port name
+
+use super::PluginError;
+use serde::{Deserialize, Serialize};
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// Method called for `PluginManager::execute_search`
+pub const METHOD_SEARCH: &str = "search";
+
+/// Method called for `PluginManager::execute_action`
+pub const METHOD_ACTION: &str = "action";
+
+/// Method called for `PluginManager::execute_workflow_node`
+pub const METHOD_WORKFLOW_NODE: &str = "workflow_node";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    pub id: u64,
+    pub method: String,
+    pub params: serde_json::Value,
+}
+
+impl RpcRequest {
+    pub fn new(id: u64, method: &str, params: serde_json::Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    /// Single line sent to the plugin's stdin
+    pub fn to_line(&self) -> Result<String, PluginError> {
+        let mut line = serde_json::to_string(self)
+            .map_err(|e| PluginError::SerializationError(e.to_string()))?;
+        line.push('\n');
+        Ok(line)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    pub id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+/// Parse one stdout line. `None` if the line is not the response to
+/// `expected_id` (plugin logging, blank lines, other ids).
+pub fn parse_response_line(
+    line: &str,
+    expected_id: u64,
+) -> Option<Result<serde_json::Value, PluginError>> {
+    let response: RpcResponse = serde_json::from_str(line.trim()).ok()?;
+    if response.id != expected_id || response.jsonrpc != JSONRPC_VERSION {
+        return None;
+    }
+
+    Some(match (response.result, response.error) {
+        (_, Some(error)) => Err(PluginError::SandboxError(format!(
+            "Plugin returned error {}: {}",
+            error.code, error.message
+        ))),
+        (result, None) => Ok(result.unwrap_or(serde_json::Value::Null)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_is_one_line() {
+        let request = RpcRequest::new(7, METHOD_ACTION, serde_json::json!({ "text": "a\nb" }));
+        let line = request.to_line().unwrap();
+
+        assert!(line.ends_with('\n'));
+        assert_eq!(line.matches('\n').count(), 1);
+        let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed["jsonrpc"], "2.0");
+        assert_eq!(parsed["id"], 7);
+        assert_eq!(parsed["method"], "action");
+    }
+
+    #[test]
+    fn test_parse_response_line() {
+        let ok = parse_response_line(r#"{"jsonrpc":"2.0","id":3,"result":{"done":true}}"#, 3);
+        assert_eq!(ok.unwrap().unwrap(), serde_json::json!({ "done": true }));
+
+        let empty = parse_response_line(r#"{"jsonrpc":"2.0","id":3}"#, 3);
+        assert_eq!(empty.unwrap().unwrap(), serde_json::Value::Null);
+
+        let failed = parse_response_line(
+            r#"{"jsonrpc":"2.0","id":3,"error":{"code":-32601,"message":"unknown method"}}"#,
+            3,
+        );
+        assert!(matches!(
+            failed.unwrap(),
+            Err(PluginError::SandboxError(ref msg)) if msg.contains("unknown method")
+        ));
+    }
+
+    #[test]
+    fn test_unrelated_lines_are_skipped() {
+        assert!(parse_response_line("starting up...", 1).is_none());
+        assert!(parse_response_line(r#"{"jsonrpc":"2.0","id":2,"result":1}"#, 1).is_none());
+        assert!(parse_response_line(r#"{"id":1,"result":1}"#, 1).is_none());
+    }
+}
@@ -1,23 +1,54 @@
 //! Plugin Sandbox
 //! 插件沙箱 - 在隔离环境中执行插件代码
 
-use super::{InstalledPlugin, PluginAction, PluginSearchResult, PluginError, PluginPermission};
+use super::limits::{self, SandboxLimits};
+use super::rpc::{self, RpcRequest};
+use super::{InstalledPlugin, PluginAction, PluginSearchResult, PluginError, PluginPermission, PluginRuntime};
+use std::path::{Component, Path};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
 
 /// 插件沙箱
 pub struct PluginSandbox {
-    // JavaScript 运行时（使用 deno_core 或类似方案）
-    // runtime: Option<JsRuntime>,
+    /// 单次调用的资源限制，超出后插件进程被杀掉
+    limits: SandboxLimits,
+    next_request_id: AtomicU64,
+}
+
+/// 一次调用没有拿到结果的原因
+enum CallFailure {
+    Error(PluginError),
+    OutputLimit,
+    NoResponse,
 }
 
 impl PluginSandbox {
     /// 创建新的沙箱
     pub fn new() -> Self {
+        Self::with_limits(SandboxLimits::default())
+    }
+
+    /// 使用自定义调用超时创建沙箱
+    pub fn with_timeout(call_timeout: Duration) -> Self {
+        Self::with_limits(SandboxLimits {
+            call_timeout,
+            ..SandboxLimits::default()
+        })
+    }
+
+    /// 使用自定义资源限制创建沙箱
+    pub fn with_limits(limits: SandboxLimits) -> Self {
         Self {
-            // runtime: None,
+            limits,
+            next_request_id: AtomicU64::new(0),
         }
     }
 
-    /// 在沙箱中执行搜索
+    /// 在沙箱中执行搜索：通过 JSON-RPC `search` 请求传入查询，结果超过 `limit` 时截断
     pub async fn execute_search(
         &self,
         plugin: &InstalledPlugin,
@@ -27,49 +58,32 @@ impl PluginSandbox {
         // 检查权限
         self.check_permissions(plugin)?;
 
-        // 获取插件主文件路径
-        let plugin_path = plugin.path.as_ref()
-            .ok_or_else(|| PluginError::InvalidManifest("Plugin path not found".to_string()))?;
-        
-        let main_file = plugin_path.join("index.js");
-        if !main_file.exists() {
-            return Err(PluginError::InvalidManifest("Main entry file not found".to_string()));
-        }
-
-        // TODO: 实现 JavaScript 运行时执行
-        // 目前返回模拟数据
-        tracing::info!(
+        tracing::debug!(
             "Executing search in plugin '{}' with query '{}' (limit: {})",
             plugin.metadata.id,
             query,
             limit
         );
 
-        // 模拟搜索结果
-        Ok(vec![
-            PluginSearchResult {
-                id: format!("{}-result-1", plugin.metadata.id),
-                title: format!("Result from {} for '{}'", plugin.metadata.name, query),
-                subtitle: Some(format!("Plugin: {}", plugin.metadata.id)),
-                icon: plugin.metadata.icon.clone(),
-                action: PluginAction {
-                    action_type: "plugin".to_string(),
-                    payload: Some(serde_json::json!({
-                        "plugin_id": plugin.metadata.id,
-                        "result_id": "result-1"
-                    })),
-                },
-                score: 0.8,
-            }
-        ])
+        let params = serde_json::json!({
+            "query": query,
+            "limit": limit,
+            "config": plugin.config,
+        });
+        let result = self.call(plugin, rpc::METHOD_SEARCH, params).await?;
+        let mut results: Vec<PluginSearchResult> = serde_json::from_value(result).map_err(|e| {
+            PluginError::SerializationError(format!("Invalid search results from '{}': {}", plugin.metadata.id, e))
+        })?;
+        results.truncate(limit);
+        Ok(results)
     }
 
-    /// 在沙箱中执行动作
+    /// 在沙箱中执行动作：启动插件进程，通过 stdio 发送 JSON-RPC `action` 请求
     pub async fn execute_action(
         &self,
         plugin: &InstalledPlugin,
         action: &PluginAction,
-    ) -> Result<(), PluginError> {
+    ) -> Result<serde_json::Value, PluginError> {
         // 检查权限
         self.check_permissions(plugin)?;
 
@@ -79,18 +93,223 @@ impl PluginSandbox {
             plugin.metadata.id
         );
 
-        // TODO: 实现 JavaScript 运行时执行
-        // 目前仅记录日志
+        let params = serde_json::json!({
+            "action": action,
+            "config": plugin.config,
+        });
+        self.call(plugin, rpc::METHOD_ACTION, params).await
+    }
+
+    /// 启动插件入口并完成一次 JSON-RPC 调用；超时、输出过大或内存超限时杀掉进程，
+    /// 错误信息说明触发的是哪一项限制
+    async fn call(
+        &self,
+        plugin: &InstalledPlugin,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, PluginError> {
+        let plugin_id = plugin.metadata.id.clone();
+        let mut command = self.command(plugin)?;
+        if let Some(max) = self.limits.max_memory_bytes {
+            limits::limit_memory(&mut command, max);
+        }
 
-        Ok(())
+        let id = self.next_request_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let request = RpcRequest::new(id, method, params).to_line()?;
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| PluginError::SandboxError(format!("Failed to start plugin '{}': {}", plugin_id, e)))?;
+
+        #[cfg(windows)]
+        let job = match self.limits.max_memory_bytes {
+            Some(max) => Some(limits::MemoryJob::assign(&child, max)?),
+            None => None,
+        };
+
+        let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(PluginError::SandboxError("Plugin stdio not available".to_string()));
+        };
+        // 记录运行时是否报告过分配失败，用来判断进程是否死于内存上限
+        let allocation_failed = Arc::new(AtomicBool::new(false));
+        let stderr_task = child.stderr.take().map(|stderr| {
+            let plugin_id = plugin_id.clone();
+            let allocation_failed = allocation_failed.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if limits::reports_allocation_failure(&line) {
+                        allocation_failed.store(true, Ordering::Relaxed);
+                    }
+                    tracing::debug!("[plugin {}] {}", plugin_id, line);
+                }
+            })
+        });
+
+        let log_id = plugin_id.clone();
+        let max_output = self.limits.max_output_bytes;
+        let exchange = async move {
+            stdin
+                .write_all(request.as_bytes())
+                .await
+                .map_err(|e| CallFailure::Error(PluginError::SandboxError(format!("Failed to send request: {}", e))))?;
+            // Closing stdin tells the plugin no more requests follow
+            drop(stdin);
+
+            // Reading one byte past the limit is enough to know it was exceeded
+            let mut reader = BufReader::new(stdout.take(max_output as u64 + 1));
+            let mut line = String::new();
+            let mut total = 0;
+            loop {
+                line.clear();
+                let read = reader.read_line(&mut line).await.map_err(|e| {
+                    CallFailure::Error(PluginError::SandboxError(format!("Failed to read response: {}", e)))
+                })?;
+                if read == 0 {
+                    return Err(CallFailure::NoResponse);
+                }
+                total += read;
+                if total > max_output {
+                    return Err(CallFailure::OutputLimit);
+                }
+                let line = line.trim_end_matches(['\r', '\n']);
+                match rpc::parse_response_line(line, id) {
+                    Some(result) => return result.map_err(CallFailure::Error),
+                    None => tracing::debug!("[plugin {}] {}", log_id, line),
+                }
+            }
+        };
+
+        let failure = match tokio::time::timeout(self.limits.call_timeout, exchange).await {
+            Ok(Ok(result)) => return Ok(result),
+            Ok(Err(failure)) => failure,
+            Err(_) => {
+                let _ = child.kill().await;
+                return Err(PluginError::SandboxError(format!(
+                    "Plugin '{}' timed out after {:?} and was stopped",
+                    plugin_id, self.limits.call_timeout
+                )));
+            }
+        };
+
+        match failure {
+            CallFailure::Error(e) => {
+                let _ = child.kill().await;
+                Err(e)
+            }
+            CallFailure::OutputLimit => {
+                let _ = child.kill().await;
+                Err(PluginError::SandboxError(format!(
+                    "Plugin '{}' exceeded the output limit of {} bytes and was stopped",
+                    plugin_id, max_output
+                )))
+            }
+            CallFailure::NoResponse => {
+                let status = match tokio::time::timeout(Duration::from_secs(1), child.wait()).await {
+                    Ok(Ok(status)) => Some(status),
+                    _ => {
+                        let _ = child.kill().await;
+                        None
+                    }
+                };
+
+                // stderr 在进程退出后才读完
+                if let Some(task) = stderr_task {
+                    let _ = tokio::time::timeout(Duration::from_secs(1), task).await;
+                }
+                // 上限只让分配失败，崩溃前运行时会报告失败的分配
+                let allocation_failed = self.limits.max_memory_bytes.is_some()
+                    && status.is_some_and(|s| !s.success())
+                    && allocation_failed.load(Ordering::Relaxed);
+
+                // Allocations fail at the cap, so the peak stays just below it
+                #[cfg(windows)]
+                let memory_hit = allocation_failed
+                    || job.as_ref().and_then(|job| job.peak_memory()).is_some_and(|peak| {
+                        self.limits.max_memory_bytes.is_some_and(|max| peak >= max / 10 * 9)
+                    });
+                #[cfg(not(windows))]
+                let memory_hit = allocation_failed;
+
+                match (memory_hit, self.limits.max_memory_bytes) {
+                    (true, Some(max)) => Err(PluginError::SandboxError(format!(
+                        "Plugin '{}' exited ({}) without a response, likely after hitting the memory limit of {} bytes",
+                        plugin_id,
+                        status.map(|s| s.to_string()).unwrap_or_else(|| "stopped".to_string()),
+                        max
+                    ))),
+                    _ => Err(PluginError::SandboxError("Plugin exited without a response".to_string())),
+                }
+            }
+        }
+    }
+
+    /// 构建插件进程命令；在启动前检查入口路径和运行时所需权限
+    fn command(&self, plugin: &InstalledPlugin) -> Result<Command, PluginError> {
+        let plugin_dir = plugin.path.as_ref()
+            .ok_or_else(|| PluginError::InvalidManifest("Plugin path not found".to_string()))?;
+
+        let entry = Path::new(&plugin.entrypoint);
+        if entry.is_absolute() || entry.components().any(|c| matches!(c, Component::ParentDir)) {
+            return Err(PluginError::InvalidManifest(format!(
+                "entrypoint must stay inside the plugin directory: {}",
+                plugin.entrypoint
+            )));
+        }
+        let entry = plugin_dir.join(entry);
+        if !entry.is_file() {
+            return Err(PluginError::InvalidManifest(format!("entrypoint not found: {:?}", entry)));
+        }
+
+        // Node、Python 和原生程序可以随意启动子进程，等同于 shell 命令
+        if runs_unrestricted(plugin.runtime) && !self.has_permission(plugin, &PluginPermission::Shell) {
+            return Err(PluginError::PermissionDenied(format!(
+                "Plugin '{}' runs on {:?}, which can start processes, and needs the shell permission",
+                plugin.metadata.id, plugin.runtime
+            )));
+        }
+
+        let mut command = match plugin.runtime {
+            PluginRuntime::Node => {
+                let mut command = Command::new("node");
+                command.arg(&entry);
+                command
+            }
+            PluginRuntime::Deno => {
+                let mut command = Command::new("deno");
+                command
+                    .args(["run", "--quiet"])
+                    .args(deno_permission_flags(&plugin.granted_permissions))
+                    .arg(&entry);
+                command
+            }
+            PluginRuntime::Python => {
+                let mut command = Command::new(if cfg!(windows) { "python" } else { "python3" });
+                command.arg(&entry);
+                command
+            }
+            PluginRuntime::Binary => Command::new(&entry),
+        };
+
+        let granted: Vec<String> = plugin.granted_permissions.iter().map(permission_name).collect();
+        command
+            .current_dir(plugin_dir)
+            .env("OMNIBOX_PLUGIN_ID", &plugin.metadata.id)
+            .env("OMNIBOX_PLUGIN_PERMISSIONS", granted.join(","));
+        Ok(command)
     }
 
-    /// 在沙箱中执行工作流节点
+    /// 在沙箱中执行工作流节点：通过 JSON-RPC `workflow_node` 请求传入各输入端口的值，
+    /// 返回按输出端口名组织的对象
     pub async fn execute_workflow_node(
         &self,
         plugin: &InstalledPlugin,
         node_type: &str,
-        input: serde_json::Value,
+        inputs: serde_json::Value,
         config: serde_json::Value,
     ) -> Result<serde_json::Value, PluginError> {
         // 检查权限
@@ -102,10 +321,12 @@ impl PluginSandbox {
             plugin.metadata.id
         );
 
-        // TODO: 实现 JavaScript 运行时执行
-        // 目前返回输入
-
-        Ok(input)
+        let params = serde_json::json!({
+            "node_type": node_type,
+            "inputs": inputs,
+            "config": config,
+        });
+        self.call(plugin, rpc::METHOD_WORKFLOW_NODE, params).await
     }
 
     /// 检查插件权限
@@ -135,6 +356,34 @@ impl PluginSandbox {
     }
 }
 
+/// 权限在 manifest 中的名称，如 `clipboard:read`
+fn permission_name(permission: &PluginPermission) -> String {
+    serde_json::to_value(permission)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// 运行时本身不限制启动子进程；Deno 只有授予 shell 权限才会带上 `--allow-run`
+fn runs_unrestricted(runtime: PluginRuntime) -> bool {
+    !matches!(runtime, PluginRuntime::Deno)
+}
+
+/// Deno 默认拒绝一切访问，只放开已授予的权限
+fn deno_permission_flags(granted: &[PluginPermission]) -> Vec<&'static str> {
+    granted
+        .iter()
+        .filter_map(|permission| match permission {
+            PluginPermission::FsRead => Some("--allow-read"),
+            PluginPermission::FsWrite => Some("--allow-write"),
+            PluginPermission::Network => Some("--allow-net"),
+            PluginPermission::Shell => Some("--allow-run"),
+            PluginPermission::System => Some("--allow-sys"),
+            _ => None,
+        })
+        .collect()
+}
+
 /// 沙箱 API 上下文
 struct SandboxApiContext {
     plugin_id: String,
@@ -202,4 +451,222 @@ mod tests {
         assert!(ctx.can_access_network());
         assert!(!ctx.can_execute_shell());
     }
+
+    #[test]
+    fn test_deno_flags_follow_granted_permissions() {
+        let flags = deno_permission_flags(&[PluginPermission::Network, PluginPermission::ClipboardRead]);
+        assert_eq!(flags, vec!["--allow-net"]);
+        assert_eq!(permission_name(&PluginPermission::ClipboardRead), "clipboard:read");
+    }
+
+    #[cfg(unix)]
+    mod process {
+        use super::super::*;
+        use crate::core::plugin::{PluginMetadata, PluginStatus};
+        use std::os::unix::fs::PermissionsExt;
+        use std::path::PathBuf;
+
+        /// Plugin directory with `script` as its executable entrypoint
+        fn binary_plugin(script: &str, granted: Vec<PluginPermission>) -> InstalledPlugin {
+            let dir = std::env::temp_dir().join(format!("omnibox_plugin_rpc_{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let entry = dir.join("plugin.sh");
+            std::fs::write(&entry, script).unwrap();
+            std::fs::set_permissions(&entry, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+            InstalledPlugin {
+                metadata: PluginMetadata {
+                    id: "test-plugin".to_string(),
+                    name: "Test Plugin".to_string(),
+                    version: "1.0.0".to_string(),
+                    description: String::new(),
+                    author: String::new(),
+                    homepage: None,
+                    repository: None,
+                    license: None,
+                    icon: None,
+                    keywords: vec![],
+                    category: Default::default(),
+                    min_app_version: None,
+                },
+                status: PluginStatus::Enabled,
+                permissions: vec![PluginPermission::Shell],
+                granted_permissions: granted,
+                installed_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                config: Default::default(),
+                error: None,
+                path: Some(dir),
+                entrypoint: "plugin.sh".to_string(),
+                runtime: PluginRuntime::Binary,
+                config_schema: None,
+                workflow_nodes: Vec::new(),
+                palette_commands: Vec::new(),
+            }
+        }
+
+        fn action() -> PluginAction {
+            PluginAction {
+                action_type: "copy".to_string(),
+                payload: Some(serde_json::json!("hello")),
+            }
+        }
+
+        fn cleanup(plugin: &InstalledPlugin) -> PathBuf {
+            let dir = plugin.path.clone().unwrap();
+            let _ = std::fs::remove_dir_all(&dir);
+            dir
+        }
+
+        #[tokio::test]
+        async fn test_action_round_trip() {
+            // Logs a line, then echoes the request back as the result
+            let plugin = binary_plugin(
+                r#"#!/bin/sh
+read request
+echo "starting"
+id=$(echo "$request" | sed 's/.*"id":\([0-9]*\).*/\1/')
+echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":$request}"
+"#,
+                vec![PluginPermission::Shell],
+            );
+
+            let result = PluginSandbox::new().execute_action(&plugin, &action()).await;
+            cleanup(&plugin);
+
+            let result = result.unwrap();
+            assert_eq!(result["method"], "action");
+            assert_eq!(result["params"]["action"]["type"], "copy");
+            assert_eq!(result["params"]["action"]["payload"], "hello");
+        }
+
+        #[tokio::test]
+        async fn test_search_results_come_from_the_plugin() {
+            // Answers with three results whatever the query
+            let plugin = binary_plugin(
+                r#"#!/bin/sh
+read request
+id=$(echo "$request" | sed 's/.*"id":\([0-9]*\).*/\1/')
+r='{"id":"a","title":"A","action":{"type":"open"},"score":0.5}'
+echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":[$r,$r,$r]}"
+"#,
+                vec![PluginPermission::Shell],
+            );
+
+            let result = PluginSandbox::new().execute_search(&plugin, "query", 2).await;
+            cleanup(&plugin);
+
+            let results = result.unwrap();
+            assert_eq!(results.len(), 2);
+            assert_eq!(results[0].title, "A");
+            assert_eq!(results[0].action.action_type, "open");
+        }
+
+        #[tokio::test]
+        async fn test_binary_without_shell_permission_is_not_started() {
+            let mut plugin = binary_plugin("#!/bin/sh\ntouch started\n", vec![]);
+            plugin.permissions = vec![];
+
+            let result = PluginSandbox::new().execute_action(&plugin, &action()).await;
+            let dir = plugin.path.clone().unwrap();
+            let started = dir.join("started").exists();
+            cleanup(&plugin);
+
+            assert!(matches!(result, Err(PluginError::PermissionDenied(_))));
+            assert!(!started);
+        }
+
+        #[tokio::test]
+        async fn test_script_runtimes_without_shell_permission_are_not_started() {
+            for runtime in [PluginRuntime::Node, PluginRuntime::Python] {
+                let mut plugin = binary_plugin("require('child_process').execSync('touch started')\n", vec![]);
+                plugin.permissions = vec![];
+                plugin.runtime = runtime;
+
+                let result = PluginSandbox::new().execute_action(&plugin, &action()).await;
+                let started = plugin.path.clone().unwrap().join("started").exists();
+                cleanup(&plugin);
+
+                assert!(matches!(result, Err(PluginError::PermissionDenied(ref msg)) if msg.contains("shell")));
+                assert!(!started);
+            }
+        }
+
+        #[tokio::test]
+        async fn test_hung_plugin_is_killed() {
+            // Records its pid, then becomes the sleeping process
+            let plugin = binary_plugin("#!/bin/sh\necho $$ > pid\nexec sleep 30\n", vec![PluginPermission::Shell]);
+
+            let started = std::time::Instant::now();
+            let result = PluginSandbox::with_timeout(Duration::from_millis(300))
+                .execute_action(&plugin, &action())
+                .await;
+            let dir = plugin.path.clone().unwrap();
+            let pid = std::fs::read_to_string(dir.join("pid")).unwrap();
+            cleanup(&plugin);
+
+            assert!(matches!(result, Err(PluginError::SandboxError(ref msg)) if msg.contains("timed out")));
+            assert!(started.elapsed() < Duration::from_secs(5));
+            let alive = std::process::Command::new("kill")
+                .args(["-0", pid.trim()])
+                .status()
+                .unwrap()
+                .success();
+            assert!(!alive, "plugin process {} still running", pid.trim());
+        }
+
+        #[tokio::test]
+        async fn test_output_past_limit_stops_plugin() {
+            let plugin = binary_plugin(
+                "#!/bin/sh\nread request\nhead -c 100000 /dev/zero | tr '\\0' 'a'\nsleep 30\n",
+                vec![PluginPermission::Shell],
+            );
+            let limits = SandboxLimits {
+                max_output_bytes: 1024,
+                ..SandboxLimits::default()
+            };
+
+            let started = std::time::Instant::now();
+            let result = PluginSandbox::with_limits(limits).execute_action(&plugin, &action()).await;
+            cleanup(&plugin);
+
+            assert!(matches!(result, Err(PluginError::SandboxError(ref msg)) if msg.contains("output limit")));
+            assert!(started.elapsed() < Duration::from_secs(5));
+        }
+
+        #[tokio::test]
+        async fn test_crash_is_not_reported_as_memory_limit() {
+            let plugin = binary_plugin(
+                "#!/bin/sh\nread request\necho 'Segmentation fault' >&2\nexit 139\n",
+                vec![PluginPermission::Shell],
+            );
+            let result = PluginSandbox::new().execute_action(&plugin, &action()).await;
+            cleanup(&plugin);
+
+            assert!(matches!(result, Err(PluginError::SandboxError(ref msg)) if !msg.contains("memory")));
+        }
+
+        #[tokio::test]
+        async fn test_failed_allocation_is_reported_as_memory_limit() {
+            let plugin = binary_plugin(
+                "#!/bin/sh\nread request\necho 'memory allocation of 4096 bytes failed' >&2\nexit 134\n",
+                vec![PluginPermission::Shell],
+            );
+            let result = PluginSandbox::new().execute_action(&plugin, &action()).await;
+            cleanup(&plugin);
+
+            assert!(matches!(result, Err(PluginError::SandboxError(ref msg)) if msg.contains("memory limit")));
+        }
+
+        #[tokio::test]
+        async fn test_entrypoint_outside_plugin_dir_is_rejected() {
+            let mut plugin = binary_plugin("#!/bin/sh\n", vec![PluginPermission::Shell]);
+            plugin.entrypoint = "../plugin.sh".to_string();
+
+            let result = PluginSandbox::new().execute_action(&plugin, &action()).await;
+            cleanup(&plugin);
+
+            assert!(matches!(result, Err(PluginError::InvalidManifest(_))));
+        }
+    }
 }
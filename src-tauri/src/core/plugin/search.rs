@@ -0,0 +1,135 @@
+//! Plugin Search
+//! 插件搜索 - 并发查询所有已启用的搜索类插件，为主搜索提供结果
+
+use super::{PluginCategory, PluginError, PluginManager, PluginSearchResult, PluginStatus};
+use futures_util::future::join_all;
+use std::time::Duration;
+
+/// 搜索类插件的来源，便于在测试中替换插件管理器
+#[async_trait::async_trait]
+pub trait SearchPluginSource: Send + Sync {
+    /// 已启用的搜索类插件 ID
+    async fn search_plugin_ids(&self) -> Vec<String>;
+
+    /// 在单个插件中执行搜索
+    async fn execute_search(
+        &self,
+        plugin_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<PluginSearchResult>, PluginError>;
+}
+
+#[async_trait::async_trait]
+impl SearchPluginSource for PluginManager {
+    async fn search_plugin_ids(&self) -> Vec<String> {
+        let plugins = self.plugins.read().await;
+        let mut ids: Vec<String> = plugins
+            .values()
+            .filter(|p| p.status == PluginStatus::Enabled)
+            .filter(|p| p.metadata.category == PluginCategory::Search)
+            .map(|p| p.metadata.id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    async fn execute_search(
+        &self,
+        plugin_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<PluginSearchResult>, PluginError> {
+        PluginManager::execute_search(self, plugin_id, query, limit).await
+    }
+}
+
+/// 并发查询所有搜索类插件，返回 `(插件 ID, 结果)`
+///
+/// 每个插件最多等待 `timeout`；超时或出错的插件只记录日志并跳过，
+/// 不会拖慢或打断主搜索。结果按插件 ID 排列，同一插件内保持原顺序。
+pub async fn search_plugins(
+    source: &dyn SearchPluginSource,
+    query: &str,
+    limit: usize,
+    timeout: Duration,
+) -> Vec<(String, PluginSearchResult)> {
+    let ids = source.search_plugin_ids().await;
+    let searches = ids.iter().map(|id| async move {
+        match tokio::time::timeout(timeout, source.execute_search(id, query, limit)).await {
+            Ok(Ok(results)) => results
+                .into_iter()
+                .take(limit)
+                .map(|r| (id.clone(), r))
+                .collect(),
+            Ok(Err(e)) => {
+                tracing::warn!("Search plugin {} failed: {}", id, e);
+                Vec::new()
+            }
+            Err(_) => {
+                tracing::warn!("Search plugin {} timed out after {:?}", id, timeout);
+                Vec::new()
+            }
+        }
+    });
+    join_all(searches).await.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::plugin::PluginAction;
+
+    /// 返回固定结果的模拟插件管理器："fast" 正常，"slow" 超时，"broken" 出错
+    struct MockSource;
+
+    fn canned(id: &str, score: f32) -> PluginSearchResult {
+        PluginSearchResult {
+            id: id.to_string(),
+            title: format!("Result {}", id),
+            subtitle: None,
+            icon: None,
+            action: PluginAction {
+                action_type: "open".to_string(),
+                payload: Some(serde_json::json!({ "id": id })),
+            },
+            score,
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SearchPluginSource for MockSource {
+        async fn search_plugin_ids(&self) -> Vec<String> {
+            vec!["broken".to_string(), "fast".to_string(), "slow".to_string()]
+        }
+
+        async fn execute_search(
+            &self,
+            plugin_id: &str,
+            _query: &str,
+            _limit: usize,
+        ) -> Result<Vec<PluginSearchResult>, PluginError> {
+            match plugin_id {
+                "fast" => Ok(vec![canned("a", 0.9), canned("b", 0.5), canned("c", 0.1)]),
+                "slow" => {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    Ok(vec![canned("late", 1.0)])
+                }
+                _ => Err(PluginError::SandboxError("crashed".to_string())),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_plugins_skips_slow_and_failing() {
+        let started = std::time::Instant::now();
+        let results = search_plugins(&MockSource, "q", 2, Duration::from_millis(50)).await;
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        let ids: Vec<(&str, &str)> = results
+            .iter()
+            .map(|(plugin, r)| (plugin.as_str(), r.id.as_str()))
+            .collect();
+        assert_eq!(ids, vec![("fast", "a"), ("fast", "b")]);
+    }
+}
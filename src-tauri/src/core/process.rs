@@ -0,0 +1,177 @@
+// Running processes for the "kill " launcher trigger
+use crate::app::error::{AppError, AppResult};
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, RefreshKind, System};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    /// Resident memory in bytes
+    pub memory: u64,
+}
+
+/// Processes whose name contains `query` (case-insensitive), largest first.
+/// An empty query lists every process.
+pub fn list_processes(query: &str, limit: usize) -> Vec<ProcessInfo> {
+    let system = System::new_with_specifics(
+        RefreshKind::new().with_processes(ProcessRefreshKind::new().with_memory()),
+    );
+    let query = query.trim().to_lowercase();
+
+    let mut processes: Vec<ProcessInfo> = system
+        .processes()
+        .iter()
+        .filter(|(_, process)| process.name().to_lowercase().contains(&query))
+        .map(|(pid, process)| ProcessInfo {
+            pid: pid.as_u32(),
+            name: process.name().to_string(),
+            memory: process.memory(),
+        })
+        .collect();
+    sort_processes(&mut processes);
+    processes.truncate(limit);
+    processes
+}
+
+/// Memory for display, e.g. "312.4 MB"
+pub fn format_memory(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let mb = bytes as f64 / MB;
+    if mb >= 1024.0 {
+        format!("{:.1} GB", mb / 1024.0)
+    } else {
+        format!("{:.1} MB", mb)
+    }
+}
+
+fn sort_processes(processes: &mut [ProcessInfo]) {
+    processes.sort_by(|a, b| b.memory.cmp(&a.memory).then_with(|| a.name.cmp(&b.name)));
+}
+
+/// Force-quit a process. Killing ourselves is refused.
+pub fn kill_process(pid: u32) -> AppResult<()> {
+    if pid == std::process::id() {
+        return Err(AppError::Unknown("Refusing to kill OmniBox itself".to_string()));
+    }
+
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_process_specifics(pid, ProcessRefreshKind::new());
+    let process = system
+        .process(pid)
+        .ok_or_else(|| AppError::NotFound(format!("No process with PID {}", pid)))?;
+    let name = process.name().to_string();
+
+    // Called directly rather than through sysinfo, whose `kill` only
+    // reports success, so the OS error says why it failed
+    terminate(pid.as_u32()).map_err(|e| kill_error(&name, pid.as_u32(), e))?;
+    tracing::info!("Killed process {} ({})", name, pid);
+    Ok(())
+}
+
+#[cfg(unix)]
+fn terminate(pid: u32) -> std::io::Result<()> {
+    // SAFETY: kill has no memory effects; errno is read right after it
+    if unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(windows)]
+fn terminate(pid: u32) -> std::io::Result<()> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE};
+
+    // SAFETY: the handle is checked by OpenProcess and closed below
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, false, pid).map_err(win32_error)?;
+        let result = TerminateProcess(handle, 1);
+        let _ = CloseHandle(handle);
+        result.map_err(win32_error)?;
+    }
+    Ok(())
+}
+
+/// The Win32 error inside a `windows` error. Its own conversion keeps the
+/// HRESULT (0x80070005, not 5), which `ErrorKind` does not recognize.
+#[cfg(windows)]
+fn win32_error(error: windows::core::Error) -> std::io::Error {
+    const FACILITY_WIN32: u32 = 0x8007_0000;
+    let code = error.code().0 as u32;
+    if code & 0xFFFF_0000 == FACILITY_WIN32 {
+        std::io::Error::from_raw_os_error((code & 0xFFFF) as i32)
+    } else {
+        std::io::Error::other(error)
+    }
+}
+
+fn kill_error(name: &str, pid: u32, error: std::io::Error) -> AppError {
+    match error.kind() {
+        std::io::ErrorKind::PermissionDenied => AppError::PermissionDenied(format!(
+            "{} ({}) runs with higher privileges; run OmniBox as administrator to quit it",
+            name, pid
+        )),
+        _ => AppError::Unknown(format!("Failed to kill {} ({}): {}", name, pid, error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(name: &str, memory: u64) -> ProcessInfo {
+        ProcessInfo {
+            pid: 1,
+            name: name.to_string(),
+            memory,
+        }
+    }
+
+    #[test]
+    fn test_sort_by_memory_then_name() {
+        let mut processes = vec![info("b", 10), info("a", 10), info("c", 50)];
+        sort_processes(&mut processes);
+        let names: Vec<_> = processes.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_format_memory() {
+        assert_eq!(format_memory(0), "0.0 MB");
+        assert_eq!(format_memory(512 * 1024 * 1024), "512.0 MB");
+        assert_eq!(format_memory(3 * 1024 * 1024 * 1024 / 2), "1.5 GB");
+    }
+
+    #[test]
+    fn test_list_includes_current_process() {
+        let own_pid = std::process::id();
+        assert!(list_processes("", usize::MAX).iter().any(|p| p.pid == own_pid));
+    }
+
+    #[test]
+    fn test_kill_refuses_self_and_unknown_pid() {
+        assert!(kill_process(std::process::id()).is_err());
+        assert!(matches!(kill_process(u32::MAX - 1), Err(AppError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_permission_denied_is_reported_clearly() {
+        let error = kill_error("svc", 4, std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert!(matches!(error, AppError::PermissionDenied(ref msg) if msg.contains("administrator")));
+        let error = kill_error("svc", 4, std::io::Error::from(std::io::ErrorKind::Other));
+        assert!(matches!(error, AppError::Unknown(_)));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_access_denied_from_windows_is_permission_denied() {
+        use windows::Win32::Foundation::E_ACCESSDENIED;
+
+        let error = win32_error(windows::core::Error::from(E_ACCESSDENIED));
+        assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(matches!(kill_error("svc", 4, error), AppError::PermissionDenied(_)));
+    }
+}
@@ -0,0 +1,108 @@
+//! Ordering of overlapping search calls.
+//!
+//! Every search takes a new generation number. Starting a newer search makes
+//! all older ones stale: they stop waiting on their backends and report
+//! nothing, so results for a query the user has already typed past can never
+//! overwrite newer ones.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::Notify;
+
+#[derive(Default)]
+pub struct QueryGeneration {
+    current: AtomicU64,
+    changed: Notify,
+}
+
+impl QueryGeneration {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new query, superseding every earlier one
+    pub fn begin(&self) -> u64 {
+        let generation = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+        self.changed.notify_waiters();
+        generation
+    }
+
+    /// Whether no newer query has started since `generation`
+    pub fn is_current(&self, generation: u64) -> bool {
+        self.current.load(Ordering::SeqCst) == generation
+    }
+
+    /// Resolves once a query newer than `generation` starts
+    pub async fn superseded(&self, generation: u64) {
+        loop {
+            // Register before checking so a `begin` in between is not missed
+            let notified = self.changed.notified();
+            if !self.is_current(generation) {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Run `search` for `generation`; `None` if a newer query started first
+    pub async fn run<F: Future>(&self, generation: u64, search: F) -> Option<F::Output> {
+        tokio::select! {
+            biased;
+            _ = self.superseded(generation) => None,
+            output = search => self.is_current(generation).then_some(output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_rapid_queries_supersede_older_ones() {
+        let generations = Arc::new(QueryGeneration::new());
+
+        let first_generation = generations.begin();
+        let first = tokio::spawn({
+            let generations = generations.clone();
+            async move {
+                generations
+                    .run(first_generation, async {
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                        "chr"
+                    })
+                    .await
+            }
+        });
+
+        // The user keeps typing before the first search finished
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let second_generation = generations.begin();
+        let second = generations
+            .run(second_generation, async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                "chrome"
+            })
+            .await;
+
+        assert_eq!(second, Some("chrome"));
+        let first = tokio::time::timeout(Duration::from_millis(100), first)
+            .await
+            .expect("stale query kept waiting on its search")
+            .unwrap();
+        assert_eq!(first, None);
+    }
+
+    #[tokio::test]
+    async fn test_finished_search_is_stale_once_superseded() {
+        let generations = QueryGeneration::new();
+        let generation = generations.begin();
+
+        assert_eq!(generations.run(generation, async { 1 }).await, Some(1));
+        generations.begin();
+        assert!(!generations.is_current(generation));
+        assert_eq!(generations.run(generation, async { 1 }).await, None);
+    }
+}
@@ -0,0 +1,82 @@
+// Quick notes: text appended to a notes file under a timestamp header
+use crate::app::error::{AppError, AppResult};
+use chrono::{DateTime, Local};
+use std::io::Write;
+use std::path::Path;
+
+/// Appends `text` to the notes file at `path` under a `## <time>` header,
+/// creating the file and its directories if needed
+pub fn append_note(path: &Path, text: &str, time: DateTime<Local>) -> AppResult<()> {
+    let text = text.trim_end();
+    if text.trim().is_empty() {
+        return Err(AppError::InvalidInput("Nothing to save: the note is empty".to_string()));
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    // Keep a blank line between notes, even if the file was edited by hand
+    let separator = match std::fs::metadata(path)?.len() {
+        0 => "",
+        _ => "\n",
+    };
+    write!(
+        file,
+        "{}## {}\n\n{}\n",
+        separator,
+        time.format("%Y-%m-%d %H:%M:%S"),
+        text
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn temp_dir() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("omnibox_notes_{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_appends_notes_in_order() {
+        let dir = temp_dir();
+        let path = dir.join("quick-notes.md");
+        let first = Local.with_ymd_and_hms(2024, 5, 1, 9, 30, 0).unwrap();
+        let second = Local.with_ymd_and_hms(2024, 5, 1, 10, 0, 5).unwrap();
+
+        append_note(&path, "buy milk\n", first).unwrap();
+        append_note(&path, "call Sam", second).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "## 2024-05-01 09:30:00\n\nbuy milk\n\n## 2024-05-01 10:00:05\n\ncall Sam\n"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_creates_missing_directories() {
+        let dir = temp_dir();
+        let path = dir.join("nested").join("notes").join("inbox.md");
+
+        append_note(&path, "idea", Local::now()).unwrap();
+
+        assert!(path.is_file());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_empty_note_is_refused() {
+        let dir = temp_dir();
+        let path = dir.join("quick-notes.md");
+
+        assert!(matches!(
+            append_note(&path, "  \n", Local::now()),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,114 @@
+//! Recently opened files, folders and apps, shown for an empty query.
+//!
+//! Items come from the launch history in the database and, on Windows, the
+//! shell's Recent folder. The list is deduplicated and paths that no longer
+//! exist are dropped.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tokio::sync::Semaphore;
+
+/// Paths checked for existence at once
+const STAT_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentItem {
+    pub path: PathBuf,
+    pub name: String,
+    /// Unix timestamp of the last open
+    pub last_used: i64,
+}
+
+/// Newest first, one entry per path (case-insensitive), only paths that
+/// still exist, at most `limit` items.
+pub async fn existing_recent_items(mut items: Vec<RecentItem>, limit: usize) -> Vec<RecentItem> {
+    items.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+    let mut seen = HashSet::new();
+    items.retain(|item| seen.insert(item.path.to_string_lossy().to_lowercase()));
+
+    let semaphore = Semaphore::new(STAT_CONCURRENCY);
+    let exists = futures_util::future::join_all(items.iter().map(|item| async {
+        let _permit = semaphore.acquire().await;
+        tokio::fs::try_exists(&item.path).await.unwrap_or(false)
+    }))
+    .await;
+
+    items
+        .into_iter()
+        .zip(exists)
+        .filter_map(|(item, exists)| exists.then_some(item))
+        .take(limit)
+        .collect()
+}
+
+/// Shortcuts in the shell's Recent folder (`%APPDATA%\Microsoft\Windows\Recent`)
+#[cfg(windows)]
+pub fn shell_recent_items(limit: usize) -> Vec<RecentItem> {
+    let Some(dir) = std::env::var_os("APPDATA")
+        .map(|appdata| PathBuf::from(appdata).join("Microsoft").join("Windows").join("Recent"))
+    else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut items: Vec<RecentItem> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if !path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("lnk")) {
+                return None;
+            }
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            let last_used = modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs() as i64;
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            Some(RecentItem { path, name, last_used })
+        })
+        .collect();
+    items.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+    items.truncate(limit);
+    items
+}
+
+#[cfg(not(windows))]
+pub fn shell_recent_items(_limit: usize) -> Vec<RecentItem> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &std::path::Path, last_used: i64) -> RecentItem {
+        RecentItem {
+            path: path.to_path_buf(),
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            last_used,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dedups_and_drops_missing_paths() {
+        let dir = std::env::temp_dir().join(format!("omnibox_recent_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let report = dir.join("report.pdf");
+        let notes = dir.join("notes.txt");
+        std::fs::write(&report, b"%PDF").unwrap();
+        std::fs::write(&notes, b"notes").unwrap();
+
+        let items = vec![
+            item(&report, 10),
+            item(&dir.join("deleted.docx"), 50),
+            item(&notes, 20),
+            // Opened again later: only the newest entry is kept
+            item(&report, 30),
+        ];
+        let recent = existing_recent_items(items, 10).await;
+
+        assert_eq!(recent, vec![item(&report, 30), item(&notes, 20)]);
+        assert_eq!(existing_recent_items(recent, 1).await.len(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,181 @@
+//! Recently closed pins and AI conversations, so they can be reopened.
+//!
+//! Pin payloads are retained while their window is open and move onto the
+//! stack when the window closes. The stack is capped; the oldest entries are
+//! dropped first.
+
+use std::collections::{HashMap, VecDeque};
+
+/// How many closed items are remembered
+pub const RECOVERY_CAPACITY: usize = 10;
+
+/// Something that was closed and can be restored
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClosedItem<P> {
+    /// A pin window, with everything needed to recreate it
+    Pin(P),
+    /// The AI panel, showing this conversation
+    Conversation(String),
+}
+
+pub struct RecoveryStack<P> {
+    /// Payloads of pin windows that are currently open, by window label
+    open_pins: HashMap<String, P>,
+    /// Conversation shown in the AI panel, if any
+    active_conversation: Option<String>,
+    /// Most recently closed last
+    closed: VecDeque<ClosedItem<P>>,
+    capacity: usize,
+}
+
+impl<P> RecoveryStack<P> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            open_pins: HashMap::new(),
+            active_conversation: None,
+            closed: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Retain a pin's payload while its window is open
+    pub fn pin_opened(&mut self, label: impl Into<String>, pin: P) {
+        self.open_pins.insert(label.into(), pin);
+    }
+
+    /// Move a closed pin's retained payload onto the stack.
+    /// Returns false for labels that were never retained.
+    pub fn pin_closed(&mut self, label: &str) -> bool {
+        match self.open_pins.remove(label) {
+            Some(pin) => {
+                self.push(ClosedItem::Pin(pin));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Track the conversation currently shown in the AI panel
+    pub fn set_active_conversation(&mut self, id: Option<String>) {
+        self.active_conversation = id;
+    }
+
+    /// Record that the AI panel was closed. Returns false if no conversation
+    /// was open.
+    pub fn conversation_closed(&mut self) -> bool {
+        match self.active_conversation.take() {
+            Some(id) => {
+                self.push(ClosedItem::Conversation(id));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget a conversation (e.g. it was deleted)
+    pub fn forget_conversation(&mut self, id: &str) {
+        self.closed
+            .retain(|item| !matches!(item, ClosedItem::Conversation(c) if c == id));
+        if self.active_conversation.as_deref() == Some(id) {
+            self.active_conversation = None;
+        }
+    }
+
+    /// Take the most recently closed item
+    pub fn pop(&mut self) -> Option<ClosedItem<P>> {
+        self.closed.pop_back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.closed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.closed.is_empty()
+    }
+
+    fn push(&mut self, item: ClosedItem<P>) {
+        if self.closed.len() == self.capacity {
+            self.closed.pop_front();
+        }
+        self.closed.push_back(item);
+    }
+}
+
+impl<P> Default for RecoveryStack<P> {
+    fn default() -> Self {
+        Self::new(RECOVERY_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Pin {
+        data: String,
+    }
+
+    fn pin(data: &str) -> Pin {
+        Pin {
+            data: data.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_closed_pin_reopens_from_retained_payload() {
+        let mut stack = RecoveryStack::default();
+        stack.pin_opened("pin_0", pin("iVBORw0KGgo="));
+
+        assert!(stack.is_empty());
+        assert!(stack.pin_closed("pin_0"));
+
+        assert_eq!(stack.pop(), Some(ClosedItem::Pin(pin("iVBORw0KGgo="))));
+        assert!(stack.pop().is_none());
+        // The payload is only restored once
+        assert!(!stack.pin_closed("pin_0"));
+    }
+
+    #[test]
+    fn test_most_recent_first() {
+        let mut stack = RecoveryStack::default();
+        stack.pin_opened("pin_0", pin("a"));
+        stack.pin_opened("pin_1", pin("b"));
+        stack.set_active_conversation(Some("conv-1".to_string()));
+
+        stack.pin_closed("pin_1");
+        stack.conversation_closed();
+        stack.pin_closed("pin_0");
+
+        assert_eq!(stack.pop(), Some(ClosedItem::Pin(pin("a"))));
+        assert_eq!(stack.pop(), Some(ClosedItem::Conversation("conv-1".to_string())));
+        assert_eq!(stack.pop(), Some(ClosedItem::Pin(pin("b"))));
+    }
+
+    #[test]
+    fn test_capacity_drops_oldest() {
+        let mut stack = RecoveryStack::new(2);
+        for i in 0..3 {
+            let label = format!("pin_{}", i);
+            stack.pin_opened(label.clone(), pin(&i.to_string()));
+            stack.pin_closed(&label);
+        }
+
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.pop(), Some(ClosedItem::Pin(pin("2"))));
+        assert_eq!(stack.pop(), Some(ClosedItem::Pin(pin("1"))));
+        assert!(stack.pop().is_none());
+    }
+
+    #[test]
+    fn test_conversation_requires_active() {
+        let mut stack: RecoveryStack<Pin> = RecoveryStack::default();
+        assert!(!stack.conversation_closed());
+
+        stack.set_active_conversation(Some("conv-1".to_string()));
+        stack.conversation_closed();
+        stack.forget_conversation("conv-1");
+        assert!(stack.is_empty());
+    }
+}
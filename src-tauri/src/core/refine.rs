@@ -0,0 +1,83 @@
+// Refine filter: narrowing produced search results by extra words
+use crate::utils::text::fold_for_search;
+
+/// A search result with the text a refine filter looks at
+pub trait Refinable {
+    fn title(&self) -> &str;
+    fn subtitle(&self) -> Option<&str>;
+}
+
+/// Keeps results whose title or subtitle contains every whitespace-separated
+/// token of `refine`, ignoring case and diacritics, in their existing order.
+/// A blank `refine` keeps all.
+pub fn refine_results<R: Refinable>(results: &mut Vec<R>, refine: &str) {
+    let tokens: Vec<String> = refine.split_whitespace().map(fold_for_search).collect();
+    if tokens.is_empty() {
+        return;
+    }
+    results.retain(|result| {
+        let mut text = fold_for_search(result.title());
+        if let Some(subtitle) = result.subtitle() {
+            text.push('\n');
+            text.push_str(&fold_for_search(subtitle));
+        }
+        tokens.iter().all(|token| text.contains(token.as_str()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item {
+        title: &'static str,
+        subtitle: Option<&'static str>,
+    }
+
+    impl Refinable for Item {
+        fn title(&self) -> &str {
+            self.title
+        }
+
+        fn subtitle(&self) -> Option<&str> {
+            self.subtitle
+        }
+    }
+
+    fn results() -> Vec<Item> {
+        vec![
+            Item { title: "report.docx", subtitle: Some("C:\\Work\\Q3") },
+            Item { title: "Résumé.pdf", subtitle: Some("C:\\Users\\me\\Documents") },
+            Item { title: "report-final.pdf", subtitle: Some("C:\\Work\\Q4") },
+            Item { title: "Notes", subtitle: None },
+            Item { title: "q3 report.xlsx", subtitle: Some("D:\\Archive") },
+        ]
+    }
+
+    fn titles(items: &[Item]) -> Vec<&str> {
+        items.iter().map(|item| item.title).collect()
+    }
+
+    #[test]
+    fn test_refine_drops_rows_and_keeps_order() {
+        let mut items = results();
+        refine_results(&mut items, "REPORT q3");
+        // Tokens may match in the title or the subtitle
+        assert_eq!(titles(&items), vec!["report.docx", "q3 report.xlsx"]);
+
+        let mut items = results();
+        refine_results(&mut items, "pdf");
+        assert_eq!(titles(&items), vec!["Résumé.pdf", "report-final.pdf"]);
+    }
+
+    #[test]
+    fn test_refine_ignores_diacritics_and_blank_input() {
+        let mut items = results();
+        refine_results(&mut items, "resume documents");
+        assert_eq!(titles(&items), vec!["Résumé.pdf"]);
+
+        let mut all = results();
+        refine_results(&mut all, "  ");
+        assert_eq!(all.len(), 5);
+    }
+}
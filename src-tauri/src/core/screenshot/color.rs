@@ -0,0 +1,141 @@
+//! Pixel color conversions for the eyedropper
+
+use serde::Serialize;
+
+/// A screen pixel's color, with the string forms shown to users
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PickedColor {
+    /// Physical screen coordinates the color was read at
+    pub x: i32,
+    pub y: i32,
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+    pub hex: String,
+    pub rgb: String,
+    pub hsl: String,
+}
+
+impl PickedColor {
+    pub fn from_rgba(rgba: [u8; 4], x: i32, y: i32) -> Self {
+        let [r, g, b, a] = rgba;
+        Self {
+            x,
+            y,
+            r,
+            g,
+            b,
+            a,
+            hex: to_hex(rgba),
+            rgb: to_rgb_string(rgba),
+            hsl: to_hsl_string(rgba),
+        }
+    }
+}
+
+/// The RGBA pixel at `(x, y)` of a row-major RGBA buffer `width` pixels wide
+pub fn pixel_at(raw: &[u8], width: u32, x: u32, y: u32) -> Option<[u8; 4]> {
+    if x >= width {
+        return None;
+    }
+    let offset = (y as usize * width as usize + x as usize) * 4;
+    let pixel = raw.get(offset..offset + 4)?;
+    Some([pixel[0], pixel[1], pixel[2], pixel[3]])
+}
+
+/// `#rrggbb`, or `#rrggbbaa` when the pixel is not opaque
+pub fn to_hex([r, g, b, a]: [u8; 4]) -> String {
+    if a == u8::MAX {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+}
+
+/// `rgb(r, g, b)`, or `rgba(r, g, b, alpha)` when the pixel is not opaque
+pub fn to_rgb_string([r, g, b, a]: [u8; 4]) -> String {
+    if a == u8::MAX {
+        format!("rgb({}, {}, {})", r, g, b)
+    } else {
+        format!("rgba({}, {}, {}, {})", r, g, b, alpha(a))
+    }
+}
+
+/// `hsl(h, s%, l%)`, or `hsla(...)` when the pixel is not opaque
+pub fn to_hsl_string(rgba: [u8; 4]) -> String {
+    let (h, s, l) = rgb_to_hsl(rgba[0], rgba[1], rgba[2]);
+    if rgba[3] == u8::MAX {
+        format!("hsl({}, {}%, {}%)", h, s, l)
+    } else {
+        format!("hsla({}, {}%, {}%, {})", h, s, l, alpha(rgba[3]))
+    }
+}
+
+/// Hue in degrees (0-359), saturation and lightness in percent, rounded
+pub fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (u16, u8, u8) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0, 0, (lightness * 100.0).round() as u8);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    (
+        hue.round() as u16 % 360,
+        (saturation * 100.0).round() as u8,
+        (lightness * 100.0).round() as u8,
+    )
+}
+
+/// Alpha as a 0-1 fraction with at most two decimals
+fn alpha(a: u8) -> String {
+    let text = format!("{:.2}", a as f64 / 255.0);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions_from_rgba_buffer() {
+        // 2x2 image: orange, teal / white, half-transparent red
+        let raw = [
+            255, 128, 0, 255, 0, 128, 128, 255, //
+            255, 255, 255, 255, 255, 0, 0, 128,
+        ];
+
+        let orange = PickedColor::from_rgba(pixel_at(&raw, 2, 0, 0).unwrap(), 10, 20);
+        assert_eq!(orange.hex, "#ff8000");
+        assert_eq!(orange.rgb, "rgb(255, 128, 0)");
+        assert_eq!(orange.hsl, "hsl(30, 100%, 50%)");
+        assert_eq!((orange.x, orange.y), (10, 20));
+
+        let teal = pixel_at(&raw, 2, 1, 0).unwrap();
+        assert_eq!(to_hex(teal), "#008080");
+        assert_eq!(to_hsl_string(teal), "hsl(180, 100%, 25%)");
+
+        let white = pixel_at(&raw, 2, 0, 1).unwrap();
+        assert_eq!(to_hsl_string(white), "hsl(0, 0%, 100%)");
+
+        let red = pixel_at(&raw, 2, 1, 1).unwrap();
+        assert_eq!(to_hex(red), "#ff000080");
+        assert_eq!(to_rgb_string(red), "rgba(255, 0, 0, 0.5)");
+        assert_eq!(to_hsl_string(red), "hsla(0, 100%, 50%, 0.5)");
+
+        assert_eq!(pixel_at(&raw, 2, 2, 0), None);
+        assert_eq!(pixel_at(&raw, 2, 0, 2), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! Keeping OmniBox's own windows out of captures
+
+/// A window that is hidden while the screen is captured
+pub trait CaptureWindow {
+    fn label(&self) -> String;
+    fn is_visible(&self) -> bool;
+    fn hide(&self);
+    fn show(&self);
+}
+
+impl CaptureWindow for tauri::WebviewWindow {
+    fn label(&self) -> String {
+        tauri::WebviewWindow::label(self).to_string()
+    }
+
+    fn is_visible(&self) -> bool {
+        tauri::WebviewWindow::is_visible(self).unwrap_or(false)
+    }
+
+    fn hide(&self) {
+        if let Err(e) = tauri::WebviewWindow::hide(self) {
+            tracing::warn!("Failed to hide window '{}' for capture: {}", self.label(), e);
+        }
+    }
+
+    fn show(&self) {
+        if let Err(e) = tauri::WebviewWindow::show(self) {
+            tracing::warn!("Failed to show window '{}' after capture: {}", self.label(), e);
+        }
+    }
+}
+
+/// Labels of the windows hidden for a capture, in the order they were hidden
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HiddenWindows(Vec<String>);
+
+impl HiddenWindows {
+    /// Hide every visible window whose label is not in `keep`, remembering
+    /// which were hidden. Windows that were already hidden stay untracked so
+    /// restoring never shows them.
+    pub fn hide_all<W: CaptureWindow>(windows: &[W], keep: &[&str]) -> Self {
+        let mut hidden = Vec::new();
+        for window in windows {
+            let label = window.label();
+            if keep.contains(&label.as_str()) || !window.is_visible() {
+                continue;
+            }
+            window.hide();
+            hidden.push(label);
+        }
+        Self(hidden)
+    }
+
+    /// Add windows hidden by a later capture, before this one was restored
+    pub fn merge(&mut self, other: HiddenWindows) {
+        for label in other.0 {
+            if !self.0.contains(&label) {
+                self.0.push(label);
+            }
+        }
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Show the tracked windows that still exist, in the order they were
+    /// hidden
+    pub fn restore<W: CaptureWindow>(self, windows: &[W]) {
+        for label in &self.0 {
+            if let Some(window) = windows.iter().find(|window| window.label() == *label) {
+                window.show();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct FakeWindow {
+        label: &'static str,
+        visible: Cell<bool>,
+    }
+
+    fn window(label: &'static str, visible: bool) -> FakeWindow {
+        FakeWindow {
+            label,
+            visible: Cell::new(visible),
+        }
+    }
+
+    impl CaptureWindow for FakeWindow {
+        fn label(&self) -> String {
+            self.label.to_string()
+        }
+
+        fn is_visible(&self) -> bool {
+            self.visible.get()
+        }
+
+        fn hide(&self) {
+            self.visible.set(false);
+        }
+
+        fn show(&self) {
+            self.visible.set(true);
+        }
+    }
+
+    fn visible(windows: &[FakeWindow]) -> Vec<&'static str> {
+        windows.iter().filter(|w| w.visible.get()).map(|w| w.label).collect()
+    }
+
+    #[test]
+    fn test_hides_visible_windows_and_restores_only_those() {
+        let windows = [
+            window("main", true),
+            window("clipboard", false),
+            window("settings", true),
+            window("pin_1", true),
+            window("capture", true),
+        ];
+
+        let hidden = HiddenWindows::hide_all(&windows, &["capture"]);
+        assert_eq!(hidden.labels(), ["main", "settings", "pin_1"]);
+        assert_eq!(visible(&windows), ["capture"]);
+
+        // The overlay closes, then the windows come back; the clipboard
+        // window was hidden before the capture and stays hidden
+        windows[4].hide();
+        hidden.restore(&windows);
+        assert_eq!(visible(&windows), ["main", "settings", "pin_1"]);
+    }
+
+    #[test]
+    fn test_restore_skips_closed_windows_and_merges_captures() {
+        let before = [window("main", true), window("pin_1", true)];
+        let mut hidden = HiddenWindows::hide_all(&before, &["capture"]);
+
+        // A second capture before the first was restored; the pin was closed
+        let after = [window("main", false), window("settings", true)];
+        hidden.merge(HiddenWindows::hide_all(&after, &["capture"]));
+        assert_eq!(hidden.labels(), ["main", "pin_1", "settings"]);
+
+        hidden.restore(&after);
+        assert_eq!(visible(&after), ["main", "settings"]);
+        assert!(HiddenWindows::hide_all(&[window("capture", true)], &["capture"]).is_empty());
+    }
+}
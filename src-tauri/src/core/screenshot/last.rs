@@ -0,0 +1,87 @@
+//! The most recent capture, kept for cropping, pinning and re-opening
+
+use crate::app::error::{AppError, AppResult};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// A full capture with the monitor geometry needed to map selections back
+/// to the screen
+#[derive(Debug, Clone)]
+pub struct CaptureFrame {
+    pub id: u64,
+    pub png_bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub monitor_x: i32,
+    pub monitor_y: i32,
+    /// Physical pixels per CSS pixel on the captured monitor
+    pub scale_factor: f64,
+    /// Copy in the capture cache folder, when one was written
+    pub file_path: Option<PathBuf>,
+}
+
+/// The last capture as returned to the frontend
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LastCapture {
+    /// Base64 PNG
+    pub data: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+static LAST_CAPTURE: Lazy<Mutex<Option<CaptureFrame>>> = Lazy::new(|| Mutex::new(None));
+
+/// Replace the last capture
+pub fn store(frame: CaptureFrame) {
+    *LAST_CAPTURE.lock() = Some(frame);
+}
+
+/// A copy of the last capture, if there was one
+pub fn latest() -> Option<CaptureFrame> {
+    LAST_CAPTURE.lock().clone()
+}
+
+/// The last capture as base64 PNG
+pub fn last_capture() -> AppResult<LastCapture> {
+    let frame = latest().ok_or_else(|| AppError::NotFound("No screenshot has been taken yet".into()))?;
+    Ok(LastCapture {
+        data: BASE64.encode(&frame.png_bytes),
+        width: frame.width,
+        height: frame.height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::screenshot::{encode_image, SaveFormat};
+
+    #[test]
+    fn test_last_capture_after_store() {
+        assert!(matches!(last_capture(), Err(AppError::NotFound(_))));
+
+        let pixels = [255, 0, 0, 255, 0, 0, 255, 255];
+        let png = encode_image(&pixels, 2, 1, SaveFormat::Png, 100).unwrap();
+        store(CaptureFrame {
+            id: 1,
+            png_bytes: png.clone(),
+            width: 2,
+            height: 1,
+            monitor_x: 0,
+            monitor_y: 0,
+            scale_factor: 1.0,
+            file_path: None,
+        });
+
+        let last = last_capture().unwrap();
+        assert_eq!((last.width, last.height), (2, 1));
+        let decoded = BASE64.decode(&last.data).unwrap();
+        assert_eq!(decoded, png);
+        let image = image::load_from_memory(&decoded).unwrap().to_rgba8();
+        assert_eq!(image.as_raw(), &pixels);
+    }
+}
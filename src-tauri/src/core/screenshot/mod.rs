@@ -6,12 +6,24 @@
 //! - Fast PNG encoding with minimal compression
 //! - Memory-efficient buffer management
 
+use crate::app::config::CaptureMode;
 use crate::app::error::{AppError, AppResult};
+use image::codecs::jpeg::JpegEncoder;
 use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::codecs::webp::WebPEncoder;
 use image::{ColorType, ImageEncoder, RgbaImage};
 use std::sync::Arc;
 use parking_lot::RwLock;
 
+pub mod color;
+pub mod hide;
+pub mod last;
+pub mod save;
+pub mod scroll;
+
+pub use color::PickedColor;
+pub use save::SaveFormat;
+
 /// Monitor information for multi-screen support
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct MonitorInfo {
@@ -34,34 +46,281 @@ pub struct CaptureResult {
     pub monitor: MonitorInfo,
 }
 
-/// Screenshot engine with caching and optimization
-pub struct ScreenshotEngine {
-    /// Cached monitor list (refreshed on demand)
-    monitors_cache: Arc<RwLock<Option<Vec<MonitorInfo>>>>,
-    /// Pre-allocated buffer for PNG encoding (reduces allocations)
-    encode_buffer: Arc<RwLock<Vec<u8>>>,
+/// Rectangle in physical screen coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ScreenRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
 }
 
-impl Default for ScreenshotEngine {
-    fn default() -> Self {
-        Self::new()
+impl ScreenRect {
+    fn center(&self) -> (i32, i32) {
+        (self.x + (self.width / 2) as i32, self.y + (self.height / 2) as i32)
+    }
+
+    fn contains(&self, px: i32, py: i32) -> bool {
+        px >= self.x
+            && py >= self.y
+            && (px as i64) < self.x as i64 + self.width as i64
+            && (py as i64) < self.y as i64 + self.height as i64
+    }
+
+    /// Intersect with `bounds`, returning the result relative to `bounds`' origin
+    fn clip_relative_to(&self, bounds: &ScreenRect) -> Option<(u32, u32, u32, u32)> {
+        let left = self.x.max(bounds.x) as i64;
+        let top = self.y.max(bounds.y) as i64;
+        let right = (self.x as i64 + self.width as i64).min(bounds.x as i64 + bounds.width as i64);
+        let bottom = (self.y as i64 + self.height as i64).min(bounds.y as i64 + bounds.height as i64);
+
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        Some((
+            (left - bounds.x as i64) as u32,
+            (top - bounds.y as i64) as u32,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        ))
     }
 }
 
-impl ScreenshotEngine {
-    pub fn new() -> Self {
+impl From<&MonitorInfo> for ScreenRect {
+    fn from(m: &MonitorInfo) -> Self {
         Self {
-            monitors_cache: Arc::new(RwLock::new(None)),
-            encode_buffer: Arc::new(RwLock::new(Vec::with_capacity(8 * 1024 * 1024))), // 8MB pre-alloc
+            x: m.x,
+            y: m.y,
+            width: m.width,
+            height: m.height,
         }
     }
+}
 
-    /// Refresh the monitor cache
-    pub fn refresh_monitors(&self) -> AppResult<Vec<MonitorInfo>> {
+/// What a capture hotkey press should do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapturePlan {
+    /// Show the interactive region selection overlay
+    Overlay,
+    /// Capture the monitor under the cursor
+    Fullscreen,
+    /// Capture a fixed screen rectangle
+    Region(ScreenRect),
+}
+
+/// Decide what the capture hotkey does for the configured mode.
+///
+/// Modes that need a rectangle fall back to the overlay when none is available
+/// (no previous selection, or the foreground window could not be queried).
+/// `active_window` is only called for [`CaptureMode::ActiveWindow`].
+pub fn plan_capture(
+    mode: CaptureMode,
+    last_region: Option<ScreenRect>,
+    active_window: impl FnOnce() -> Option<ScreenRect>,
+) -> CapturePlan {
+    let region = match mode {
+        CaptureMode::Region => return CapturePlan::Overlay,
+        CaptureMode::Fullscreen => return CapturePlan::Fullscreen,
+        CaptureMode::LastRegion => last_region,
+        CaptureMode::ActiveWindow => active_window(),
+    };
+
+    match region {
+        Some(rect) if rect.width > 0 && rect.height > 0 => CapturePlan::Region(rect),
+        _ => CapturePlan::Overlay,
+    }
+}
+
+/// How a capture is finished once the region is known
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureFinish {
+    /// Leave the overlay in editing mode with the copy/save/pin toolbar
+    Toolbar,
+    /// Copy the image to the clipboard
+    Clipboard,
+    /// Open a pin window with the image
+    Pin,
+}
+
+/// Decide how a capture made with `plan` ends for the configured follow-up
+pub fn finish_capture(after: AfterCapture, plan: CapturePlan) -> CaptureFinish {
+    match (after, plan) {
+        (AfterCapture::Pin, _) => CaptureFinish::Pin,
+        (AfterCapture::Edit, CapturePlan::Overlay) => CaptureFinish::Toolbar,
+        (AfterCapture::Edit, _) => CaptureFinish::Clipboard,
+    }
+}
+
+/// Maps a selection in capture-overlay CSS pixels to a crop rect
+/// `(x, y, width, height)` in image pixels.
+///
+/// CSS pixels are scaled by the capture monitor's `scale_factor`; when that
+/// is unknown (not positive) the overlay is assumed to span the image and the
+/// viewport ratio is used instead. Edges are rounded separately, so the crop
+/// covers exactly the selected physical pixels, and the rect is clamped to
+/// the image.
+pub fn selection_to_image_rect(
+    selection: (i32, i32, u32, u32),
+    scale_factor: f64,
+    viewport: (u32, u32),
+    image: (u32, u32),
+) -> (u32, u32, u32, u32) {
+    let (x, y, width, height) = selection;
+    let (img_w, img_h) = (image.0 as i64, image.1 as i64);
+    let (scale_x, scale_y) = if scale_factor > 0.0 {
+        (scale_factor, scale_factor)
+    } else {
+        (
+            img_w as f64 / viewport.0.max(1) as f64,
+            img_h as f64 / viewport.1.max(1) as f64,
+        )
+    };
+
+    let edge = |css: f64, scale: f64| (css * scale).round() as i64;
+    let left = edge(x as f64, scale_x).clamp(0, img_w);
+    let top = edge(y as f64, scale_y).clamp(0, img_h);
+    let right = edge(x as f64 + width as f64, scale_x).max(left + 1).min(img_w);
+    let bottom = edge(y as f64 + height as f64, scale_y).max(top + 1).min(img_h);
+
+    (
+        left as u32,
+        top as u32,
+        (right - left) as u32,
+        (bottom - top) as u32,
+    )
+}
+
+/// Encode raw RGBA pixels as `format`. `quality` (1-100) applies to JPEG,
+/// which also drops the alpha channel; PNG favors speed and WebP is lossless.
+pub fn encode_image(raw: &[u8], width: u32, height: u32, format: SaveFormat, quality: u8) -> AppResult<Vec<u8>> {
+    let mut out = Vec::new();
+    let result = match format {
+        SaveFormat::Png => PngEncoder::new_with_quality(&mut out, CompressionType::Fast, FilterType::NoFilter)
+            .write_image(raw, width, height, ColorType::Rgba8),
+        SaveFormat::Jpeg => {
+            let rgb: Vec<u8> = raw.chunks_exact(4).flat_map(|px| [px[0], px[1], px[2]]).collect();
+            JpegEncoder::new_with_quality(&mut out, quality.clamp(1, 100))
+                .write_image(&rgb, width, height, ColorType::Rgb8)
+        }
+        SaveFormat::WebP => WebPEncoder::new_lossless(&mut out).write_image(raw, width, height, ColorType::Rgba8),
+    };
+    result.map_err(|e| AppError::Unknown(format!("{} encoding failed: {e}", format.extension().to_uppercase())))?;
+    Ok(out)
+}
+
+/// Bounds of the current foreground window
+#[cfg(windows)]
+pub fn foreground_window_rect() -> Option<ScreenRect> {
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS};
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowRect};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0.is_null() {
+            return None;
+        }
+
+        // Extended frame bounds exclude the invisible resize border/shadow that
+        // GetWindowRect includes on Windows 10+.
+        let mut rc = RECT::default();
+        let dwm_ok = DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut rc as *mut RECT as *mut _,
+            std::mem::size_of::<RECT>() as u32,
+        )
+        .is_ok();
+        if !dwm_ok {
+            GetWindowRect(hwnd, &mut rc).ok()?;
+        }
+
+        let width = (rc.right - rc.left).max(0) as u32;
+        let height = (rc.bottom - rc.top).max(0) as u32;
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        Some(ScreenRect {
+            x: rc.left,
+            y: rc.top,
+            width,
+            height,
+        })
+    }
+}
+
+#[cfg(not(windows))]
+pub fn foreground_window_rect() -> Option<ScreenRect> {
+    None
+}
+
+/// Cursor position in physical screen coordinates
+#[cfg(windows)]
+pub fn cursor_position() -> Option<(i32, i32)> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
+
+    let mut pos = POINT::default();
+    unsafe { GetCursorPos(&mut pos).ok()? };
+    Some((pos.x, pos.y))
+}
+
+#[cfg(not(windows))]
+pub fn cursor_position() -> Option<(i32, i32)> {
+    None
+}
+
+/// Part of a `monitor` capture of size `image` showing screen rect `rect`,
+/// as (x, y, width, height) in image pixels. The rect is clamped to the
+/// monitor first; `None` when nothing of it is on the monitor.
+fn crop_rect(rect: &ScreenRect, monitor: &MonitorInfo, image: (u32, u32)) -> Option<(u32, u32, u32, u32)> {
+    let (x, y, width, height) = rect.clip_relative_to(&ScreenRect::from(monitor))?;
+    let scale = |value: u32, size: u32, image_size: u32| {
+        (value as u64 * image_size as u64 / size as u64) as u32
+    };
+    let left = scale(x, monitor.width, image.0);
+    let top = scale(y, monitor.height, image.1);
+    let right = scale(x + width, monitor.width, image.0);
+    let bottom = scale(y + height, monitor.height, image.1);
+    if right <= left || bottom <= top {
+        return None;
+    }
+    Some((left, top, right - left, bottom - top))
+}
+
+/// Offset of screen point `(x, y)` from `monitor`'s top-left corner, in
+/// the monitor's units; `None` when the point is not on it
+fn point_in_monitor(x: i32, y: i32, monitor: &MonitorInfo) -> Option<(u32, u32)> {
+    if !ScreenRect::from(monitor).contains(x, y) {
+        return None;
+    }
+    Some(((x - monitor.x) as u32, (y - monitor.y) as u32))
+}
+
+/// Where monitors are listed and captured; tests stand in a fake display setup
+pub(crate) trait MonitorSource: Send + Sync {
+    fn list(&self) -> AppResult<Vec<MonitorInfo>>;
+
+    /// Captures the monitor at `monitor`'s position; `NotFound` when no
+    /// monitor is there any more
+    fn capture(&self, monitor: &MonitorInfo) -> AppResult<RgbaImage>;
+
+    /// Captures the `width`×`height` area at offset `(x, y)` into the
+    /// monitor, in the monitor's units. HiDPI captures come back larger.
+    fn capture_area(&self, monitor: &MonitorInfo, x: u32, y: u32, width: u32, height: u32) -> AppResult<RgbaImage>;
+}
+
+/// The real displays, through xcap
+struct XcapMonitors;
+
+impl MonitorSource for XcapMonitors {
+    fn list(&self) -> AppResult<Vec<MonitorInfo>> {
         let monitors = xcap::Monitor::all()
             .map_err(|e| AppError::Unknown(format!("Failed to enumerate monitors: {e}")))?;
 
-        let info: Vec<MonitorInfo> = monitors
+        Ok(monitors
             .into_iter()
             .enumerate()
             .map(|(idx, m)| MonitorInfo {
@@ -74,8 +333,75 @@ impl ScreenshotEngine {
                 scale_factor: m.scale_factor().unwrap_or(1.0) as f64,
                 is_primary: m.is_primary().unwrap_or(false),
             })
-            .collect();
+            .collect())
+    }
+
+    fn capture(&self, monitor_info: &MonitorInfo) -> AppResult<RgbaImage> {
+        xcap_monitor(monitor_info)?
+            .capture_image()
+            .map_err(|e| AppError::Unknown(format!("Failed to capture screen: {e}")))
+    }
+
+    fn capture_area(&self, monitor_info: &MonitorInfo, x: u32, y: u32, width: u32, height: u32) -> AppResult<RgbaImage> {
+        xcap_monitor(monitor_info)?
+            .capture_region(x, y, width, height)
+            .map_err(|e| AppError::Unknown(format!("Failed to capture screen: {e}")))
+    }
+}
+
+/// The xcap monitor at `monitor_info`'s position
+fn xcap_monitor(monitor_info: &MonitorInfo) -> AppResult<xcap::Monitor> {
+    let monitors = xcap::Monitor::all()
+        .map_err(|e| AppError::Unknown(format!("Failed to list monitors: {e}")))?;
+
+    monitors
+        .into_iter()
+        .find(|m| m.x().unwrap_or(0) == monitor_info.x && m.y().unwrap_or(0) == monitor_info.y)
+        .ok_or_else(|| AppError::NotFound("Target monitor not found".into()))
+}
+
+/// The monitor in a fresh list that `stale` refers to after a display
+/// change: the one at the same position, else the one with the same name
+fn find_same_monitor(monitors: &[MonitorInfo], stale: &MonitorInfo) -> Option<MonitorInfo> {
+    monitors
+        .iter()
+        .find(|m| m.x == stale.x && m.y == stale.y)
+        .or_else(|| monitors.iter().find(|m| m.name == stale.name))
+        .cloned()
+}
+
+/// Screenshot engine with caching and optimization
+pub struct ScreenshotEngine {
+    source: Box<dyn MonitorSource>,
+    /// Cached monitor list; refreshed on demand and whenever a capture's
+    /// monitor is no longer where the cache says (display plugged/unplugged)
+    monitors_cache: Arc<RwLock<Option<Vec<MonitorInfo>>>>,
+    /// Pre-allocated buffer for PNG encoding (reduces allocations)
+    encode_buffer: Arc<RwLock<Vec<u8>>>,
+}
+
+impl Default for ScreenshotEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
+impl ScreenshotEngine {
+    pub fn new() -> Self {
+        Self::with_source(Box::new(XcapMonitors))
+    }
+
+    pub(crate) fn with_source(source: Box<dyn MonitorSource>) -> Self {
+        Self {
+            source,
+            monitors_cache: Arc::new(RwLock::new(None)),
+            encode_buffer: Arc::new(RwLock::new(Vec::with_capacity(8 * 1024 * 1024))), // 8MB pre-alloc
+        }
+    }
+
+    /// Refresh the monitor cache
+    pub fn refresh_monitors(&self) -> AppResult<Vec<MonitorInfo>> {
+        let info = self.source.list()?;
         *self.monitors_cache.write() = Some(info.clone());
         Ok(info)
     }
@@ -110,12 +436,16 @@ impl ScreenshotEngine {
 
             let rc = info.monitorInfo.rcMonitor;
 
-            // Find matching monitor in our cache
-            let monitors = self.get_monitors()?;
-            monitors
-                .into_iter()
-                .find(|m| m.x == rc.left && m.y == rc.top)
-                .ok_or_else(|| AppError::NotFound("Monitor not found".into()))
+            // Find matching monitor in our cache; a miss means the displays
+            // changed since it was filled
+            let at_cursor = |monitors: Vec<MonitorInfo>| {
+                monitors.into_iter().find(|m| m.x == rc.left && m.y == rc.top)
+            };
+            match at_cursor(self.get_monitors()?) {
+                Some(monitor) => Ok(monitor),
+                None => at_cursor(self.refresh_monitors()?)
+                    .ok_or_else(|| AppError::NotFound("Monitor not found".into())),
+            }
         }
     }
 
@@ -129,21 +459,48 @@ impl ScreenshotEngine {
             .ok_or_else(|| AppError::NotFound("No monitor found".into()))
     }
 
-    /// Capture a specific monitor
-    pub fn capture_monitor(&self, monitor_info: &MonitorInfo) -> AppResult<CaptureResult> {
-        let monitors = xcap::Monitor::all()
-            .map_err(|e| AppError::Unknown(format!("Failed to list monitors: {e}")))?;
+    /// The monitor containing screen point `(x, y)`, refreshing the cache
+    /// when no cached monitor does
+    fn monitor_at(&self, x: i32, y: i32) -> AppResult<MonitorInfo> {
+        let containing = |monitors: Vec<MonitorInfo>| {
+            monitors
+                .into_iter()
+                .find(|m| ScreenRect::from(m).contains(x, y))
+        };
+        match containing(self.get_monitors()?) {
+            Some(monitor) => Ok(monitor),
+            None => containing(self.refresh_monitors()?)
+                .ok_or_else(|| AppError::NotFound(format!("No monitor at ({x}, {y})"))),
+        }
+    }
 
-        let monitor = monitors
-            .into_iter()
-            .find(|m| {
-                m.x().unwrap_or(0) == monitor_info.x && m.y().unwrap_or(0) == monitor_info.y
-            })
-            .ok_or_else(|| AppError::NotFound("Target monitor not found".into()))?;
+    /// Capture a specific monitor as raw RGBA, along with its current info.
+    /// If the monitor is not where `monitor_info` says, the monitor list is
+    /// refreshed and the capture retried once.
+    fn capture_monitor_image(&self, monitor_info: &MonitorInfo) -> AppResult<(RgbaImage, MonitorInfo)> {
+        match self.source.capture(monitor_info) {
+            Ok(img) => Ok((img, monitor_info.clone())),
+            Err(AppError::NotFound(_)) => {
+                tracing::info!(
+                    "Monitor '{}' is no longer at ({}, {}); refreshing monitors",
+                    monitor_info.name,
+                    monitor_info.x,
+                    monitor_info.y
+                );
+                let monitors = self.refresh_monitors()?;
+                let current = find_same_monitor(&monitors, monitor_info).ok_or_else(|| {
+                    AppError::NotFound(format!("Monitor '{}' is no longer connected", monitor_info.name))
+                })?;
+                let img = self.source.capture(&current)?;
+                Ok((img, current))
+            }
+            Err(e) => Err(e),
+        }
+    }
 
-        let img = monitor
-            .capture_image()
-            .map_err(|e| AppError::Unknown(format!("Failed to capture screen: {e}")))?;
+    /// Capture a specific monitor
+    pub fn capture_monitor(&self, monitor_info: &MonitorInfo) -> AppResult<CaptureResult> {
+        let (img, monitor) = self.capture_monitor_image(monitor_info)?;
 
         let width = img.width();
         let height = img.height();
@@ -156,7 +513,7 @@ impl ScreenshotEngine {
             png_bytes,
             width,
             height,
-            monitor: monitor_info.clone(),
+            monitor,
         })
     }
 
@@ -179,6 +536,65 @@ impl ScreenshotEngine {
         self.capture_monitor(&monitor)
     }
 
+    /// Capture a screen rectangle, clipped to the monitor containing its center
+    pub fn capture_region(&self, rect: &ScreenRect) -> AppResult<CaptureResult> {
+        let (cx, cy) = rect.center();
+        let monitors = self.refresh_monitors()?;
+        let monitor = monitors
+            .iter()
+            .find(|m| ScreenRect::from(*m).contains(cx, cy))
+            .or_else(|| monitors.iter().find(|m| m.is_primary))
+            .cloned()
+            .ok_or_else(|| AppError::NotFound("No monitor found".into()))?;
+
+        self.capture_clipped(&monitor, rect)
+    }
+
+    /// Capture a UI element's bounds, clipped to the monitor containing
+    /// screen point `(x, y)`, the point the element was picked at. Parts of
+    /// the element off that monitor are left out.
+    pub fn capture_element(&self, rect: &ScreenRect, x: i32, y: i32) -> AppResult<CaptureResult> {
+        let monitor = self.monitor_at(x, y)?;
+        self.capture_clipped(&monitor, rect)
+    }
+
+    /// Capture `monitor` and crop it to the part of `rect` on it
+    fn capture_clipped(&self, monitor: &MonitorInfo, rect: &ScreenRect) -> AppResult<CaptureResult> {
+        let (img, monitor) = self.capture_monitor_image(monitor)?;
+        let (x, y, width, height) = crop_rect(rect, &monitor, img.dimensions())
+            .ok_or_else(|| AppError::Unknown("Capture region is off-screen".into()))?;
+
+        let (full_width, full_height) = img.dimensions();
+        let png_bytes = self.crop_and_encode(img.as_raw(), full_width, full_height, x, y, width, height)?;
+
+        Ok(CaptureResult {
+            png_bytes,
+            width,
+            height,
+            monitor,
+        })
+    }
+
+    /// Color of the screen pixel at physical screen coordinates `(x, y)`, on
+    /// whichever monitor contains it. Only that pixel is captured.
+    pub fn pick_color_at(&self, x: i32, y: i32) -> AppResult<PickedColor> {
+        let not_found = || AppError::NotFound(format!("No monitor at ({x}, {y})"));
+        let monitor = self.monitor_at(x, y)?;
+        let (dx, dy) = point_in_monitor(x, y, &monitor).ok_or_else(not_found)?;
+        let img = match self.source.capture_area(&monitor, dx, dy, 1, 1) {
+            Err(AppError::NotFound(_)) => {
+                // The monitor moved since it was listed
+                let monitor = self.monitor_at(x, y)?;
+                let (dx, dy) = point_in_monitor(x, y, &monitor).ok_or_else(not_found)?;
+                self.source.capture_area(&monitor, dx, dy, 1, 1)?
+            }
+            result => result?,
+        };
+        // A HiDPI capture of the point is several pixels; they share the color
+        let pixel = img.get_pixel_checked(0, 0).ok_or_else(not_found)?;
+        Ok(PickedColor::from_rgba(pixel.0, x, y))
+    }
+
     /// Fast PNG encoding optimized for speed over compression ratio
     fn encode_png_fast(&self, raw: &[u8], width: u32, height: u32) -> AppResult<Vec<u8>> {
         let mut buffer = self.encode_buffer.write();
@@ -241,4 +657,263 @@ mod tests {
         let engine = ScreenshotEngine::new();
         assert!(engine.monitors_cache.read().is_none());
     }
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> ScreenRect {
+        ScreenRect { x, y, width, height }
+    }
+
+    #[test]
+    fn test_plan_capture_modes() {
+        let last = Some(rect(10, 20, 300, 200));
+        let window = rect(100, 100, 800, 600);
+
+        assert_eq!(plan_capture(CaptureMode::Region, last, || Some(window)), CapturePlan::Overlay);
+        assert_eq!(plan_capture(CaptureMode::Fullscreen, last, || Some(window)), CapturePlan::Fullscreen);
+        assert_eq!(
+            plan_capture(CaptureMode::LastRegion, last, || Some(window)),
+            CapturePlan::Region(last.unwrap())
+        );
+        assert_eq!(
+            plan_capture(CaptureMode::ActiveWindow, last, || Some(window)),
+            CapturePlan::Region(window)
+        );
+    }
+
+    #[test]
+    fn test_plan_capture_falls_back_to_overlay() {
+        assert_eq!(plan_capture(CaptureMode::LastRegion, None, || None), CapturePlan::Overlay);
+        assert_eq!(plan_capture(CaptureMode::ActiveWindow, None, || None), CapturePlan::Overlay);
+        assert_eq!(
+            plan_capture(CaptureMode::ActiveWindow, None, || Some(rect(0, 0, 0, 10))),
+            CapturePlan::Overlay
+        );
+    }
+
+    #[test]
+    fn test_plan_capture_only_queries_window_when_needed() {
+        for mode in [CaptureMode::Region, CaptureMode::Fullscreen, CaptureMode::LastRegion] {
+            plan_capture(mode, None, || panic!("active window queried for {:?}", mode));
+        }
+    }
+
+    #[test]
+    fn test_finish_capture_pin_skips_toolbar() {
+        let plans = [CapturePlan::Overlay, CapturePlan::Fullscreen, CapturePlan::Region(rect(0, 0, 10, 10))];
+        for plan in plans {
+            assert_eq!(finish_capture(AfterCapture::Pin, plan), CaptureFinish::Pin);
+        }
+    }
+
+    #[test]
+    fn test_finish_capture_edit_keeps_normal_path() {
+        assert_eq!(finish_capture(AfterCapture::Edit, CapturePlan::Overlay), CaptureFinish::Toolbar);
+        assert_eq!(finish_capture(AfterCapture::Edit, CapturePlan::Fullscreen), CaptureFinish::Clipboard);
+        assert_eq!(
+            finish_capture(AfterCapture::Edit, CapturePlan::Region(rect(0, 0, 10, 10))),
+            CaptureFinish::Clipboard
+        );
+    }
+
+    #[test]
+    fn test_clip_relative_to_monitor() {
+        let monitor = rect(1920, 0, 1920, 1080);
+        // Window straddling the left edge of the second monitor
+        assert_eq!(rect(1800, 100, 400, 300).clip_relative_to(&monitor), Some((0, 100, 280, 300)));
+        assert_eq!(rect(0, 0, 100, 100).clip_relative_to(&monitor), None);
+    }
+
+    /// Displays that can be rearranged between calls
+    #[derive(Default)]
+    struct FakeMonitors {
+        monitors: parking_lot::Mutex<Vec<MonitorInfo>>,
+        lists: std::sync::atomic::AtomicUsize,
+        /// Whole-monitor captures
+        captures: std::sync::atomic::AtomicUsize,
+    }
+
+    impl MonitorSource for Arc<FakeMonitors> {
+        fn list(&self) -> AppResult<Vec<MonitorInfo>> {
+            self.lists.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(self.monitors.lock().clone())
+        }
+
+        /// Each display is a solid red whose shade tells displays apart
+        fn capture(&self, monitor: &MonitorInfo) -> AppResult<RgbaImage> {
+            self.captures.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.monitors
+                .lock()
+                .iter()
+                .find(|m| m.x == monitor.x && m.y == monitor.y)
+                .map(|m| {
+                    RgbaImage::from_pixel(m.width, m.height, image::Rgba([(m.width / 4) as u8, 0, 0, 255]))
+                })
+                .ok_or_else(|| AppError::NotFound("Target monitor not found".into()))
+        }
+
+        fn capture_area(&self, monitor: &MonitorInfo, x: u32, y: u32, width: u32, height: u32) -> AppResult<RgbaImage> {
+            let m = self
+                .monitors
+                .lock()
+                .iter()
+                .find(|m| m.x == monitor.x && m.y == monitor.y)
+                .cloned()
+                .ok_or_else(|| AppError::NotFound("Target monitor not found".into()))?;
+            if x + width > m.width || y + height > m.height {
+                return Err(AppError::Unknown("Area is off the monitor".into()));
+            }
+            Ok(RgbaImage::from_pixel(width, height, image::Rgba([(m.width / 4) as u8, 0, 0, 255])))
+        }
+    }
+
+    fn monitor(name: &str, x: i32, width: u32) -> MonitorInfo {
+        MonitorInfo {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            x,
+            y: 0,
+            width,
+            height: 100,
+            scale_factor: 1.0,
+            is_primary: x == 0,
+        }
+    }
+
+    #[test]
+    fn test_moved_monitor_refreshes_cache_and_retries() {
+        let displays = Arc::new(FakeMonitors::default());
+        *displays.monitors.lock() = vec![monitor("Laptop", 0, 200), monitor("Dell", 200, 400)];
+        let engine = ScreenshotEngine::with_source(Box::new(displays.clone()));
+        let cached_dell = engine.get_monitors().unwrap()[1].clone();
+
+        // The laptop display is unplugged; the Dell becomes the only one, at 0
+        *displays.monitors.lock() = vec![monitor("Dell", 0, 400)];
+        let result = engine.capture_monitor(&cached_dell).unwrap();
+
+        assert_eq!(result.monitor.x, 0);
+        assert_eq!(result.width, 400);
+        assert_eq!(displays.lists.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_eq!(engine.get_monitors().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_unplugged_monitor_fails_after_one_refresh() {
+        let displays = Arc::new(FakeMonitors::default());
+        *displays.monitors.lock() = vec![monitor("Laptop", 0, 200), monitor("Dell", 200, 400)];
+        let engine = ScreenshotEngine::with_source(Box::new(displays.clone()));
+        let cached_dell = engine.get_monitors().unwrap()[1].clone();
+
+        *displays.monitors.lock() = vec![monitor("Laptop", 0, 200)];
+        let result = engine.capture_monitor(&cached_dell);
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+        assert_eq!(displays.lists.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_selection_to_image_rect_uses_scale_factor() {
+        let selection = (100, 50, 200, 100);
+        assert_eq!(
+            selection_to_image_rect(selection, 1.0, (1920, 1080), (1920, 1080)),
+            (100, 50, 200, 100)
+        );
+        assert_eq!(
+            selection_to_image_rect(selection, 1.5, (1280, 720), (1920, 1080)),
+            (150, 75, 300, 150)
+        );
+        assert_eq!(
+            selection_to_image_rect(selection, 2.0, (1440, 900), (2880, 1800)),
+            (200, 100, 400, 200)
+        );
+        // Fractional edges round independently so adjacent selections tile
+        assert_eq!(
+            selection_to_image_rect((1, 1, 1, 1), 1.25, (1536, 864), (1920, 1080)),
+            (1, 1, 2, 2)
+        );
+    }
+
+    #[test]
+    fn test_selection_to_image_rect_falls_back_to_viewport_and_clamps() {
+        // Unknown scale factor: ratio of image to viewport
+        assert_eq!(
+            selection_to_image_rect((10, 10, 20, 20), 0.0, (960, 540), (1920, 1080)),
+            (20, 20, 40, 40)
+        );
+        // Selection running past the image edge is clipped
+        assert_eq!(
+            selection_to_image_rect((-10, 600, 300, 300), 1.5, (1280, 720), (1920, 1080)),
+            (0, 900, 435, 180)
+        );
+    }
+
+    #[test]
+    fn test_point_in_monitor_is_relative_to_its_corner() {
+        // Secondary display left of and above the primary
+        let left = MonitorInfo {
+            x: -1920,
+            y: -200,
+            width: 1920,
+            height: 1080,
+            ..monitor("Left", -1920, 1920)
+        };
+        assert_eq!(point_in_monitor(-1920, -200, &left), Some((0, 0)));
+        assert_eq!(point_in_monitor(-1, 879, &left), Some((1919, 1079)));
+        assert_eq!(point_in_monitor(-960, 340, &left), Some((960, 540)));
+        assert_eq!(point_in_monitor(0, 0, &left), None);
+    }
+
+    #[test]
+    fn test_pick_color_uses_monitor_under_point() {
+        let displays = Arc::new(FakeMonitors::default());
+        *displays.monitors.lock() = vec![monitor("Laptop", 0, 200), monitor("Dell", 200, 400)];
+        let engine = ScreenshotEngine::with_source(Box::new(displays.clone()));
+
+        let laptop = engine.pick_color_at(199, 50).unwrap();
+        assert_eq!(laptop.hex, "#320000");
+        let dell = engine.pick_color_at(200, 50).unwrap();
+        assert_eq!((dell.x, dell.y, dell.r), (200, 50, 100));
+        assert_eq!(dell.hex, "#640000");
+
+        assert!(matches!(engine.pick_color_at(600, 50), Err(AppError::NotFound(_))));
+        // Only the picked pixel is captured, never a whole monitor
+        assert_eq!(displays.captures.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_encode_image_formats_decode() {
+        let mut img = RgbaImage::from_pixel(5, 4, image::Rgba([30, 144, 255, 255]));
+        img.put_pixel(0, 0, image::Rgba([255, 0, 0, 128]));
+
+        for (format, expected) in [
+            (SaveFormat::Png, image::ImageFormat::Png),
+            (SaveFormat::Jpeg, image::ImageFormat::Jpeg),
+            (SaveFormat::WebP, image::ImageFormat::WebP),
+        ] {
+            let bytes = encode_image(img.as_raw(), 5, 4, format, 80).unwrap();
+            assert_eq!(image::guess_format(&bytes).unwrap(), expected);
+            let decoded = image::load_from_memory(&bytes).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (5, 4));
+        }
+
+        // Lossless formats keep the exact pixels, alpha included
+        let webp = encode_image(img.as_raw(), 5, 4, SaveFormat::WebP, 80).unwrap();
+        assert_eq!(image::load_from_memory(&webp).unwrap().to_rgba8(), img);
+    }
+
+    #[test]
+    fn test_crop_rect_clamps_element_to_monitor() {
+        let secondary = MonitorInfo {
+            y: 0,
+            height: 1080,
+            ..monitor("Dell", 1920, 1920)
+        };
+        // Panel hanging off the right and bottom edges of the secondary monitor
+        let element = rect(3700, 1000, 400, 200);
+
+        assert_eq!(crop_rect(&element, &secondary, (1920, 1080)), Some((1780, 1000, 140, 80)));
+        // Same element on a capture taken at 2x
+        assert_eq!(crop_rect(&element, &secondary, (3840, 2160)), Some((3560, 2000, 280, 160)));
+        // Element spanning into the primary monitor keeps only its part on the secondary
+        assert_eq!(crop_rect(&rect(1800, 10, 200, 50), &secondary, (1920, 1080)), Some((0, 10, 80, 50)));
+        assert_eq!(crop_rect(&rect(0, 0, 100, 100), &secondary, (1920, 1080)), None);
+    }
 }
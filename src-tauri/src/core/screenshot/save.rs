@@ -0,0 +1,165 @@
+//! Quick-save of captures: templated file names in the configured format
+
+use crate::app::error::{AppError, AppResult};
+use chrono::{DateTime, Local};
+use super::encode_image;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// File name template used when none is configured
+pub const DEFAULT_FILE_NAME_TEMPLATE: &str = "Screenshot {date} {time}";
+
+/// Characters replaced in rendered file names; invalid on Windows or a path separator
+const INVALID_FILE_NAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Output format of saved captures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveFormat {
+    Png,
+    Jpeg,
+    /// Lossless; `quality` does not apply
+    WebP,
+}
+
+impl SaveFormat {
+    /// The format named in `screenshot.format`
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.to_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::WebP),
+            _ => None,
+        }
+    }
+
+    /// The format a file is named for, from its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::parse(&path.extension()?.to_string_lossy())
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::WebP => "webp",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::WebP => "image/webp",
+        }
+    }
+}
+
+/// Fills `{date}` (2024-05-01), `{time}` (09-30-00) and `{timestamp}`
+/// (Unix seconds) in `template`. Characters not allowed in file names are
+/// replaced with `_`.
+pub fn render_file_name(template: &str, time: DateTime<Local>) -> String {
+    let name = template
+        .replace("{date}", &time.format("%Y-%m-%d").to_string())
+        .replace("{time}", &time.format("%H-%M-%S").to_string())
+        .replace("{timestamp}", &time.timestamp().to_string());
+    let name = name
+        .replace(INVALID_FILE_NAME_CHARS, "_")
+        .trim()
+        .to_string();
+    if name.is_empty() {
+        "Screenshot".to_string()
+    } else {
+        name
+    }
+}
+
+/// Re-encodes PNG capture bytes as `format`; PNG is passed through as is
+pub fn encode(png_bytes: &[u8], format: SaveFormat, quality: u8) -> AppResult<Vec<u8>> {
+    if format == SaveFormat::Png {
+        return Ok(png_bytes.to_vec());
+    }
+    let img = image::load_from_memory(png_bytes)
+        .map_err(|e| AppError::Unknown(format!("Failed to decode PNG: {e}")))?
+        .to_rgba8();
+    encode_image(img.as_raw(), img.width(), img.height(), format, quality)
+}
+
+/// Writes `bytes` to `dir/<name>.<extension>`, adding ` (2)`, ` (3)`, ...
+/// before the extension while the name is taken. Returns the path written.
+pub fn write_unique(dir: &Path, name: &str, extension: &str, bytes: &[u8]) -> AppResult<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    for n in 1u32.. {
+        let file_name = match n {
+            1 => format!("{}.{}", name, extension),
+            _ => format!("{} ({}).{}", name, n, extension),
+        };
+        let path = dir.join(file_name);
+        // create_new so a file appearing between check and write is not overwritten
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(bytes)?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    unreachable!("ran out of file name suffixes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render_file_name() {
+        let time = Local.with_ymd_and_hms(2024, 5, 1, 9, 30, 0).unwrap();
+
+        assert_eq!(
+            render_file_name(DEFAULT_FILE_NAME_TEMPLATE, time),
+            "Screenshot 2024-05-01 09-30-00"
+        );
+        assert_eq!(
+            render_file_name("shot_{timestamp}", time),
+            format!("shot_{}", time.timestamp())
+        );
+        assert_eq!(render_file_name("a/b:{date}", time), "a_b_2024-05-01");
+        assert_eq!(render_file_name("  ", time), "Screenshot");
+    }
+
+    #[test]
+    fn test_write_unique_adds_suffixes() {
+        let dir = std::env::temp_dir().join(format!("omnibox_captures_{}", uuid::Uuid::new_v4()));
+
+        let first = write_unique(&dir, "Screenshot", "png", b"one").unwrap();
+        let second = write_unique(&dir, "Screenshot", "png", b"two").unwrap();
+        let third = write_unique(&dir, "Screenshot", "png", b"three").unwrap();
+        let other = write_unique(&dir, "Screenshot", "jpg", b"four").unwrap();
+
+        assert_eq!(first, dir.join("Screenshot.png"));
+        assert_eq!(second, dir.join("Screenshot (2).png"));
+        assert_eq!(third, dir.join("Screenshot (3).png"));
+        assert_eq!(other, dir.join("Screenshot.jpg"));
+        assert_eq!(std::fs::read(&first).unwrap(), b"one");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_encode_passes_png_through() {
+        let img = image::RgbaImage::from_pixel(4, 3, image::Rgba([255, 0, 0, 255]));
+        let png = encode_image(img.as_raw(), 4, 3, SaveFormat::Png, 90).unwrap();
+
+        assert_eq!(encode(&png, SaveFormat::Png, 90).unwrap(), png);
+        let jpeg = encode(&png, SaveFormat::Jpeg, 90).unwrap();
+        assert_eq!(image::guess_format(&jpeg).unwrap(), image::ImageFormat::Jpeg);
+    }
+
+    #[test]
+    fn test_format_names() {
+        assert_eq!(SaveFormat::parse("JPEG"), Some(SaveFormat::Jpeg));
+        assert_eq!(SaveFormat::parse("gif"), None);
+        assert_eq!(SaveFormat::from_path(Path::new("shot.webp")), Some(SaveFormat::WebP));
+        assert_eq!(SaveFormat::from_path(Path::new("shot")), None);
+    }
+}
@@ -0,0 +1,206 @@
+//! Scrolling capture: stitching successive frames of a scrolled view into
+//! one long image
+
+use crate::app::error::{AppError, AppResult};
+use image::RgbaImage;
+
+/// How far, in pixels, a frame may sit from where its scroll offset puts
+/// it; covers smooth-scroll overshoot and rounding of the reported offset
+pub const SEAM_SEARCH_RADIUS: i64 = 48;
+
+/// Rows compared per candidate position; evenly spaced through the overlap
+const SAMPLED_ROWS: usize = 64;
+
+/// Stitches `frames` vertically. Each frame comes with the scroll offset it
+/// was captured at; frames are placed by offset, nudged to where their
+/// overlap with the image so far matches best, and joined at the row where
+/// the two agree most. All frames must have the same width.
+pub fn assemble(mut frames: Vec<(RgbaImage, i32)>) -> AppResult<RgbaImage> {
+    frames.sort_by_key(|(_, offset)| *offset);
+    let mut frames = frames.into_iter();
+    let (first, base_offset) = frames
+        .next()
+        .ok_or_else(|| AppError::NotFound("No frames to assemble".into()))?;
+    let width = first.width();
+
+    let mut canvas = first;
+    for (frame, offset) in frames {
+        if frame.width() != width {
+            return Err(AppError::Unknown(format!(
+                "Frame widths differ ({} and {})",
+                width,
+                frame.width()
+            )));
+        }
+        let expected = offset as i64 - base_offset as i64;
+        let position = best_position(&canvas, &frame, expected);
+        // A shorter frame that lies within the canvas adds nothing
+        if position + frame.height() as i64 <= canvas.height() as i64 {
+            continue;
+        }
+        let seam = best_seam(&canvas, &frame, position);
+        canvas = place(canvas, &frame, position, seam);
+    }
+    Ok(canvas)
+}
+
+/// Sum of per-channel differences between row `ay` of `a` and row `by` of `b`
+fn row_diff(a: &RgbaImage, ay: u32, b: &RgbaImage, by: u32) -> u64 {
+    let row_len = a.width() as usize * 4;
+    let a_row = &a.as_raw()[ay as usize * row_len..][..row_len];
+    let b_row = &b.as_raw()[by as usize * row_len..][..row_len];
+    a_row
+        .iter()
+        .zip(b_row)
+        .map(|(x, y)| x.abs_diff(*y) as u64)
+        .sum()
+}
+
+/// Canvas row the frame's top row lands on: the candidate near `expected`
+/// whose overlap differs least per row, preferring the one closest to
+/// `expected` on ties. Frames the offset puts past the end are trusted.
+fn best_position(canvas: &RgbaImage, frame: &RgbaImage, expected: i64) -> i64 {
+    let canvas_h = canvas.height() as i64;
+    let frame_h = frame.height() as i64;
+    // At least one row of overlap, and the frame must extend the canvas
+    let lowest = (canvas_h - frame_h + 1).max(0);
+    let candidates = (expected - SEAM_SEARCH_RADIUS).max(lowest)..=(expected + SEAM_SEARCH_RADIUS).min(canvas_h - 1);
+    if expected >= canvas_h || candidates.is_empty() {
+        return expected;
+    }
+
+    candidates
+        .map(|position| {
+            let overlap = (canvas_h - position) as usize;
+            let step = overlap.div_ceil(SAMPLED_ROWS);
+            let (total, rows) = (0..overlap).step_by(step).fold((0u64, 0u64), |(total, rows), row| {
+                let diff = row_diff(canvas, (position as usize + row) as u32, frame, row as u32);
+                (total + diff, rows + 1)
+            });
+            (total / rows, (position - expected).abs(), position)
+        })
+        .min()
+        .map(|(_, _, position)| position)
+        .unwrap_or(expected)
+}
+
+/// Canvas row from which the frame replaces the canvas: within the overlap,
+/// the row where canvas and frame differ least (the lowest such row on ties).
+/// Without overlap the frame starts at the canvas end.
+fn best_seam(canvas: &RgbaImage, frame: &RgbaImage, position: i64) -> i64 {
+    let end = canvas.height() as i64;
+    let overlap_end = end.min(position + frame.height() as i64);
+    (position.max(0)..overlap_end)
+        .map(|y| (row_diff(canvas, y as u32, frame, (y - position) as u32), y))
+        .min()
+        .map(|(_, y)| y)
+        .unwrap_or(end)
+}
+
+/// Copies `canvas` rows above `seam` and `frame` rows from `seam` down, with
+/// the frame's top at `position`. Rows in a gap between them stay transparent.
+fn place(canvas: RgbaImage, frame: &RgbaImage, position: i64, seam: i64) -> RgbaImage {
+    let width = canvas.width();
+    let row_len = width as usize * 4;
+    let height = (position + frame.height() as i64).max(canvas.height() as i64) as u32;
+    let seam = seam.clamp(0, canvas.height() as i64) as usize;
+
+    let mut raw = canvas.into_raw();
+    raw.truncate(seam * row_len);
+    raw.resize(height as usize * row_len, 0);
+    let first_row = (seam as i64 - position).max(0) as usize;
+    let dest = (position.max(0) as usize + first_row) * row_len;
+    let src = &frame.as_raw()[first_row * row_len..];
+    raw[dest..dest + src.len()].copy_from_slice(src);
+
+    RgbaImage::from_raw(width, height, raw).expect("buffer sized for the image")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A page whose rows are all distinct
+    fn page(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, y| {
+            image::Rgba([(y * 7 % 256) as u8, ((y * 13 + x) % 256) as u8, (y / 256) as u8, 255])
+        })
+    }
+
+    fn frame(page: &RgbaImage, top: u32, height: u32) -> RgbaImage {
+        image::imageops::crop_imm(page, 0, top, page.width(), height).to_image()
+    }
+
+    #[test]
+    fn test_stitches_frames_with_known_overlap() {
+        let page = page(16, 300);
+        // 100px frames scrolled 70px at a time: 30 rows of overlap
+        let frames = vec![
+            (frame(&page, 140, 100), 140),
+            (frame(&page, 0, 100), 0),
+            (frame(&page, 70, 100), 70),
+            (frame(&page, 200, 100), 200),
+        ];
+
+        let result = assemble(frames).unwrap();
+
+        assert_eq!(result, page);
+    }
+
+    #[test]
+    fn test_corrects_inexact_offsets() {
+        let page = page(16, 240);
+        // The second frame was really captured at 90, the third at 140
+        let frames = vec![
+            (frame(&page, 0, 100), 0),
+            (frame(&page, 90, 100), 80),
+            (frame(&page, 140, 100), 155),
+        ];
+
+        assert_eq!(assemble(frames).unwrap(), page);
+    }
+
+    #[test]
+    fn test_seam_avoids_rows_that_changed() {
+        let page = page(8, 160);
+        let first = frame(&page, 0, 100);
+        // A blinking cursor changed rows 60-63 between shots
+        let mut second = frame(&page, 60, 100);
+        for y in 0..4 {
+            for x in 0..8 {
+                second.put_pixel(x, y, image::Rgba([0, 0, 0, 255]));
+            }
+        }
+
+        let result = assemble(vec![(first, 0), (second, 60)]).unwrap();
+
+        assert_eq!(result.height(), 160);
+        assert_eq!(result, page);
+    }
+
+    #[test]
+    fn test_frames_of_different_heights() {
+        let page = page(16, 1000);
+        // A short frame inside the first one, then one reaching past it
+        let frames = vec![
+            (frame(&page, 0, 800), 0),
+            (frame(&page, 500, 100), 500),
+            (frame(&page, 750, 250), 750),
+        ];
+        assert_eq!(assemble(frames).unwrap(), page);
+
+        let frames = vec![(page.clone(), 0), (frame(&page, 500, 100), 500)];
+        assert_eq!(assemble(frames).unwrap(), page);
+
+        // A tall frame after a short one
+        let frames = vec![(frame(&page, 0, 100), 0), (frame(&page, 50, 950), 50)];
+        assert_eq!(assemble(frames).unwrap(), page);
+    }
+
+    #[test]
+    fn test_rejects_mismatched_widths_and_empty_input() {
+        let frames = vec![(RgbaImage::new(10, 10), 0), (RgbaImage::new(12, 10), 5)];
+        assert!(assemble(frames).is_err());
+        assert!(matches!(assemble(Vec::new()), Err(AppError::NotFound(_))));
+    }
+}
@@ -0,0 +1,214 @@
+//! Short-lived LRU cache of search results, keyed by normalized query.
+//!
+//! Entries expire after a TTL (Everything results can change under us) and
+//! are ignored once the index generation moves on. Only exact queries are
+//! served: app matching is fuzzy and pinyin-aware, so filtering a shorter
+//! prefix's results locally could drop matches the full search would find.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+struct CacheEntry<T> {
+    results: Vec<T>,
+    inserted: Instant,
+    generation: u64,
+}
+
+/// Hit/miss counters, for logging the hit rate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+pub struct SearchCache<T> {
+    entries: HashMap<String, CacheEntry<T>>,
+    /// Keys from least to most recently used
+    order: VecDeque<String>,
+    capacity: usize,
+    ttl: Duration,
+    stats: CacheStats,
+}
+
+impl<T: Clone> SearchCache<T> {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+            ttl,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Cache key for a query: trimmed and lowercased
+    pub fn key(query: &str) -> String {
+        query.trim().to_lowercase()
+    }
+
+    /// Change limits, evicting entries beyond the new capacity
+    pub fn configure(&mut self, capacity: usize, ttl: Duration) {
+        self.capacity = capacity;
+        self.ttl = ttl;
+        self.evict_to(capacity);
+    }
+
+    /// Cached results for `query`, if fresh and built at `generation`
+    pub fn get(&mut self, query: &str, generation: u64, now: Instant) -> Option<Vec<T>> {
+        let key = Self::key(query);
+        let fresh = self.entries.get(&key).map(|entry| {
+            entry.generation == generation && now.saturating_duration_since(entry.inserted) < self.ttl
+        });
+
+        match fresh {
+            Some(true) => {
+                self.stats.hits += 1;
+                self.touch(&key);
+                self.entries.get(&key).map(|entry| entry.results.clone())
+            }
+            Some(false) => {
+                self.stats.misses += 1;
+                self.remove(&key);
+                None
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, query: &str, results: Vec<T>, generation: u64, now: Instant) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = Self::key(query);
+        self.remove(&key);
+        self.evict_to(self.capacity - 1);
+        self.order.push_back(key.clone());
+        self.entries.insert(
+            key,
+            CacheEntry {
+                results,
+                inserted: now,
+                generation,
+            },
+        );
+    }
+
+    /// Drop everything, e.g. after the index was rebuilt
+    pub fn invalidate(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    fn touch(&mut self, key: &str) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            if let Some(k) = self.order.remove(pos) {
+                self.order.push_back(k);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if self.entries.remove(key).is_some() {
+            self.order.retain(|k| k != key);
+        }
+    }
+
+    fn evict_to(&mut self, size: usize) {
+        while self.entries.len() > size {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(capacity: usize) -> SearchCache<u32> {
+        SearchCache::new(capacity, Duration::from_secs(3))
+    }
+
+    #[test]
+    fn test_hit_on_normalized_query() {
+        let mut cache = cache(4);
+        let now = Instant::now();
+        cache.insert("Chrome ", vec![1, 2], 0, now);
+
+        assert_eq!(cache.get("chrome", 0, now), Some(vec![1, 2]));
+        assert_eq!(cache.get("chro", 0, now), None);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let mut cache = cache(4);
+        let now = Instant::now();
+        cache.insert("chrome", vec![1], 0, now);
+
+        assert!(cache.get("chrome", 0, now + Duration::from_secs(2)).is_some());
+        assert!(cache.get("chrome", 0, now + Duration::from_secs(3)).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_index_refresh_invalidates() {
+        let mut cache = cache(4);
+        let now = Instant::now();
+        cache.insert("chrome", vec![1], 7, now);
+
+        assert!(cache.get("chrome", 8, now).is_none());
+
+        cache.insert("chrome", vec![1], 8, now);
+        cache.invalidate();
+        assert!(cache.get("chrome", 8, now).is_none());
+    }
+
+    #[test]
+    fn test_least_recently_used_is_evicted() {
+        let mut cache = cache(2);
+        let now = Instant::now();
+        cache.insert("a", vec![1], 0, now);
+        cache.insert("b", vec![2], 0, now);
+        // Using "a" makes "b" the eviction candidate
+        cache.get("a", 0, now);
+        cache.insert("c", vec![3], 0, now);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b", 0, now).is_none());
+        assert!(cache.get("a", 0, now).is_some());
+        assert!(cache.get("c", 0, now).is_some());
+
+        cache.configure(1, Duration::from_secs(3));
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get("c", 0, now).is_some());
+    }
+}
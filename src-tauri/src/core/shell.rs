@@ -0,0 +1,282 @@
+// Shell commands for the "> " launcher trigger
+use crate::app::error::{AppError, AppResult};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// How long a command may run before it is killed
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` when the process was ended by a signal
+    pub exit_code: Option<i32>,
+}
+
+/// Commands that wipe a disk or the whole file system, refused outright.
+/// This only catches the obvious cases; it is no sandbox.
+static DESTRUCTIVE_PATTERNS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    vec![
+        (
+            Regex::new(r"\brm\s+(?:-\S+\s+)*(?:/|/\*|~/?|~/\*|\$HOME/?)(?:\s|;|&|\||$)").unwrap(),
+            "deleting the root or home directory",
+        ),
+        (Regex::new(r"\bmkfs(?:\.\w+)?\b").unwrap(), "formatting a file system"),
+        (Regex::new(r"\bdd\b.*\bof=/dev/").unwrap(), "writing to a raw device"),
+        (Regex::new(r">\s*/dev/(?:sd|hd|nvme|disk|mmcblk)").unwrap(), "writing to a raw device"),
+        (Regex::new(r":\(\)\s*\{\s*:\s*\|\s*:\s*&\s*\}\s*;\s*:").unwrap(), "a fork bomb"),
+        (Regex::new(r"(?i)\bformat(?:\.com)?\s+[a-z]:").unwrap(), "formatting a drive"),
+        (
+            Regex::new(r"(?i)\b(?:rd|rmdir|del|erase)\s+(?:/\w\s+)*[a-z]:\\?\*?(?:\s|$)").unwrap(),
+            "deleting a drive root",
+        ),
+    ]
+});
+
+/// Why `cmd` is refused, if it matches a destructive pattern
+pub fn destructive_reason(cmd: &str) -> Option<&'static str> {
+    DESTRUCTIVE_PATTERNS
+        .iter()
+        .find(|(pattern, _)| pattern.is_match(cmd))
+        .map(|(_, reason)| *reason)
+}
+
+/// Runs `cmd` through the platform shell and captures its output.
+///
+/// Refused unless shell commands are enabled in settings. A command still
+/// running after `timeout` is killed together with anything it started.
+pub async fn run_command(cmd: &str, enabled: bool, timeout: Duration) -> AppResult<CommandOutput> {
+    if !enabled {
+        return Err(AppError::PermissionDenied(
+            "Shell commands are disabled; enable them in settings first".to_string(),
+        ));
+    }
+    let cmd = cmd.trim();
+    if cmd.is_empty() {
+        return Err(AppError::Shell("Empty command".to_string()));
+    }
+    if let Some(reason) = destructive_reason(cmd) {
+        tracing::warn!("Refused shell command {:?}: {}", cmd, reason);
+        return Err(AppError::PermissionDenied(format!("Refusing to run a command that looks like {}", reason)));
+    }
+
+    tracing::info!("Running shell command: {:?}", cmd);
+    let mut command = shell_command(cmd);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let child = command
+        .spawn()
+        .map_err(|e| AppError::Shell(format!("Failed to start shell: {}", e)))?;
+    let group = ProcessGroup::of(&child);
+
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(output) => {
+            group.release();
+            let output = output?;
+            tracing::info!("Shell command {:?} exited with {:?}", cmd, output.status.code());
+            Ok(CommandOutput {
+                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+                exit_code: output.status.code(),
+            })
+        }
+        Err(_) => {
+            // The shell itself was killed when the child was dropped
+            group.kill();
+            tracing::warn!("Shell command {:?} timed out after {:?}", cmd, timeout);
+            Err(AppError::Shell(format!("Command timed out after {} s", timeout.as_secs_f32())))
+        }
+    }
+}
+
+#[cfg(unix)]
+fn shell_command(cmd: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(cmd);
+    // Own process group, so a timeout also ends what the shell started
+    command.process_group(0);
+    command
+}
+
+#[cfg(windows)]
+fn shell_command(cmd: &str) -> Command {
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let mut command = Command::new("cmd");
+    command.arg("/C").raw_arg(cmd).creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+/// The shell and everything it starts: its process group on Unix, a Job
+/// Object on Windows
+#[cfg(unix)]
+struct ProcessGroup {
+    pid: Option<u32>,
+}
+
+#[cfg(unix)]
+impl ProcessGroup {
+    fn of(child: &tokio::process::Child) -> Self {
+        Self { pid: child.id() }
+    }
+
+    fn kill(self) {
+        if let Some(pid) = self.pid {
+            // SAFETY: signals only the group created for this command
+            unsafe {
+                libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+            }
+        }
+    }
+
+    /// The command finished; whatever it left running keeps running
+    fn release(self) {}
+}
+
+/// The Job is created with kill-on-close, so the command's processes also
+/// end if OmniBox exits while it runs. Processes the shell started before
+/// it was assigned are not in the Job.
+#[cfg(windows)]
+struct ProcessGroup {
+    /// Job handle, kept as an integer so the future stays `Send`
+    job: Option<isize>,
+}
+
+#[cfg(windows)]
+impl ProcessGroup {
+    fn of(child: &tokio::process::Child) -> Self {
+        let job = Self::create_job(child)
+            .map_err(|e| tracing::warn!("Shell command runs without a Job Object: {}", e))
+            .ok();
+        Self { job }
+    }
+
+    fn create_job(child: &tokio::process::Child) -> windows::core::Result<isize> {
+        use windows::core::PCWSTR;
+        use windows::Win32::Foundation::{CloseHandle, HANDLE};
+        use windows::Win32::System::JobObjects::{AssignProcessToJobObject, CreateJobObjectW};
+
+        let Some(process) = child.raw_handle() else {
+            return Err(windows::core::Error::empty());
+        };
+        unsafe {
+            let job = CreateJobObjectW(None, PCWSTR::null())?;
+            let assigned = Self::set_kill_on_close(job, true)
+                .and_then(|()| AssignProcessToJobObject(job, HANDLE(process)));
+            if let Err(e) = assigned {
+                let _ = CloseHandle(job);
+                return Err(e);
+            }
+            Ok(job.0 as isize)
+        }
+    }
+
+    unsafe fn set_kill_on_close(job: windows::Win32::Foundation::HANDLE, kill: bool) -> windows::core::Result<()> {
+        use windows::Win32::System::JobObjects::{
+            JobObjectExtendedLimitInformation, SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        };
+
+        let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+        if kill {
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        }
+        SetInformationJobObject(
+            job,
+            JobObjectExtendedLimitInformation,
+            &info as *const _ as *const core::ffi::c_void,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        )
+    }
+
+    fn handle(&self) -> Option<windows::Win32::Foundation::HANDLE> {
+        self.job
+            .map(|job| windows::Win32::Foundation::HANDLE(job as *mut core::ffi::c_void))
+    }
+
+    /// Closing the Job ends every process in it
+    fn kill(self) {}
+
+    /// The command finished; whatever it left running (e.g. `start notepad`)
+    /// keeps running
+    fn release(self) {
+        if let Some(job) = self.handle() {
+            // SAFETY: the Job was created by `of` and is still open
+            if let Err(e) = unsafe { Self::set_kill_on_close(job, false) } {
+                tracing::warn!("Processes left by the shell command will be ended: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        if let Some(job) = self.handle() {
+            unsafe {
+                let _ = windows::Win32::Foundation::CloseHandle(job);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_disabled_by_config() {
+        let result = run_command("echo hello", false, COMMAND_TIMEOUT).await;
+        assert!(matches!(result, Err(AppError::PermissionDenied(_))));
+    }
+
+    #[tokio::test]
+    async fn test_captures_output() {
+        let output = run_command("echo hello && echo oops 1>&2", true, COMMAND_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(output.stdout.trim(), "hello");
+        assert_eq!(output.stderr.trim(), "oops");
+        assert_eq!(output.exit_code, Some(0));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout() {
+        let started = Instant::now();
+        let result = run_command("sleep 5; echo done", true, Duration::from_millis(200)).await;
+
+        match result {
+            Err(AppError::Shell(message)) => assert!(message.contains("timed out")),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_destructive_patterns() {
+        for cmd in [
+            "rm -rf /",
+            "rm -rf --no-preserve-root / ",
+            "sudo rm -fr ~",
+            "mkfs.ext4 /dev/sda1",
+            "dd if=/dev/zero of=/dev/sda",
+            ":(){ :|:& };:",
+            "format C: /q",
+            "rd /s /q C:\\",
+        ] {
+            assert!(destructive_reason(cmd).is_some(), "{} was allowed", cmd);
+        }
+        for cmd in ["rm -rf ./build", "ls /", "dd if=a.img of=b.img", "git status", "del notes.txt"] {
+            assert!(destructive_reason(cmd).is_none(), "{} was refused", cmd);
+        }
+    }
+}
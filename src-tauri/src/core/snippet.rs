@@ -0,0 +1,158 @@
+// Text snippets: a keyword expanded into stored text with placeholders
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// A stored snippet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Snippet {
+    pub id: i64,
+    /// Word typed after "snip " to find the snippet
+    pub keyword: String,
+    /// Text pasted on selection; see [`expand`] for placeholders
+    pub expansion: String,
+    pub created_at: i64,
+}
+
+/// Keyword as stored: trimmed and lowercased. `None` when it is empty or
+/// contains whitespace.
+pub fn normalize_keyword(keyword: &str) -> Option<String> {
+    let keyword = keyword.trim().to_lowercase();
+    (!keyword.is_empty() && !keyword.contains(char::is_whitespace)).then_some(keyword)
+}
+
+/// Whether expanding `template` needs the clipboard text
+pub fn uses_clipboard(template: &str) -> bool {
+    template.contains("{clipboard}")
+}
+
+/// Fill the placeholders in `template`: `{date}` (2024-05-01), `{time}`
+/// (09:30:00) and `{clipboard}`. Inserted text is not expanded again, so
+/// clipboard text containing `{date}` is pasted as is. Unknown placeholders
+/// are kept.
+pub fn expand(template: &str, now: DateTime<Local>, clipboard: &str) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let name = &rest[1..end];
+        if name.contains('{') {
+            // "{{date}": only the inner brace can open a placeholder
+            out.push('{');
+            rest = &rest[1..];
+            continue;
+        }
+        match name {
+            "date" => out.push_str(&now.format("%Y-%m-%d").to_string()),
+            "time" => out.push_str(&now.format("%H:%M:%S").to_string()),
+            "clipboard" => out.push_str(clipboard),
+            _ => out.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Snippets for `query`, best first: keywords starting with it, then
+/// keywords containing it, then snippets whose text contains it. An empty
+/// query keeps every snippet.
+pub fn matching(snippets: Vec<Snippet>, query: &str) -> Vec<Snippet> {
+    let query = query.trim().to_lowercase();
+    let mut ranked: Vec<(u8, Snippet)> = snippets
+        .into_iter()
+        .filter_map(|snippet| {
+            let rank = if snippet.keyword.starts_with(&query) {
+                0
+            } else if snippet.keyword.contains(&query) {
+                1
+            } else if snippet.expansion.to_lowercase().contains(&query) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, snippet))
+        })
+        .collect();
+    ranked.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.keyword.cmp(&b.1.keyword)));
+    ranked.into_iter().map(|(_, snippet)| snippet).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 1, 9, 30, 0).unwrap()
+    }
+
+    fn snippet(keyword: &str, expansion: &str) -> Snippet {
+        Snippet {
+            id: 0,
+            keyword: keyword.to_string(),
+            expansion: expansion.to_string(),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_expands_placeholders() {
+        assert_eq!(
+            expand("Updated {date} at {time}: {clipboard}", at(), "v2.1"),
+            "Updated 2024-05-01 at 09:30:00: v2.1"
+        );
+        assert_eq!(expand("{clipboard} / {clipboard}", at(), "x"), "x / x");
+        assert_eq!(expand("no placeholders", at(), "x"), "no placeholders");
+    }
+
+    #[test]
+    fn test_clipboard_text_is_not_expanded_again() {
+        // Clipboard text that itself looks like placeholders stays literal
+        assert_eq!(
+            expand("> {clipboard} ({date})", at(), "{date} {clipboard}"),
+            "> {date} {clipboard} (2024-05-01)"
+        );
+        assert_eq!(expand("{{clipboard}}", at(), "a"), "{a}");
+    }
+
+    #[test]
+    fn test_unknown_and_unclosed_braces_are_kept() {
+        assert_eq!(expand("fn main() { {name} }", at(), ""), "fn main() { {name} }");
+        assert_eq!(expand("{date", at(), ""), "{date");
+        assert_eq!(expand("{}{date}", at(), ""), "{}2024-05-01");
+    }
+
+    #[test]
+    fn test_keywords() {
+        assert_eq!(normalize_keyword("  Sig "), Some("sig".to_string()));
+        assert_eq!(normalize_keyword("my sig"), None);
+        assert_eq!(normalize_keyword(" "), None);
+        assert!(uses_clipboard("see {clipboard}"));
+        assert!(!uses_clipboard("see {date}"));
+    }
+
+    #[test]
+    fn test_matching_ranks_keyword_prefixes_first() {
+        let snippets = vec![
+            snippet("addr", "221B Baker Street"),
+            snippet("email-sig", "Best regards"),
+            snippet("sig", "Cheers"),
+            snippet("phone", "+1 555 0100"),
+        ];
+        let keywords = |query| {
+            matching(snippets.clone(), query)
+                .into_iter()
+                .map(|s| s.keyword)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(keywords("sig"), ["sig", "email-sig"]);
+        assert_eq!(keywords("BAKER"), ["addr"]);
+        assert_eq!(keywords(""), ["addr", "email-sig", "phone", "sig"]);
+        assert!(keywords("zzz").is_empty());
+    }
+}
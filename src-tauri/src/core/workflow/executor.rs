@@ -0,0 +1,385 @@
+// Checks a workflow graph and runs its nodes in dependency order
+use super::{Workflow, WorkflowError, WorkflowNode, ANY_PORT_TYPE};
+use crate::core::plugin::{PluginError, PluginManager, WorkflowNodeDefinition, WorkflowPort};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Where node definitions come from and how nodes run, so tests can stand
+/// in for the plugin manager
+#[async_trait::async_trait]
+pub trait NodeRunner: Send + Sync {
+    /// Definition of `node_type` as declared by plugin `plugin_id`
+    async fn definition(&self, plugin_id: &str, node_type: &str) -> Option<WorkflowNodeDefinition>;
+
+    /// Runs one node; `inputs` and the result are keyed by port name
+    async fn run_node(
+        &self,
+        node: &WorkflowNode,
+        inputs: Map<String, Value>,
+    ) -> Result<Map<String, Value>, PluginError>;
+}
+
+#[async_trait::async_trait]
+impl NodeRunner for PluginManager {
+    async fn definition(&self, plugin_id: &str, node_type: &str) -> Option<WorkflowNodeDefinition> {
+        self.workflow_node_definition(plugin_id, node_type).await
+    }
+
+    async fn run_node(
+        &self,
+        node: &WorkflowNode,
+        inputs: Map<String, Value>,
+    ) -> Result<Map<String, Value>, PluginError> {
+        let result = self
+            .execute_workflow_node(&node.plugin_id, &node.node_type, Value::Object(inputs), node.config.clone())
+            .await?;
+        match result {
+            Value::Object(outputs) => Ok(outputs),
+            other => Err(PluginError::SandboxError(format!(
+                "Workflow node returned {} instead of an object of outputs",
+                other
+            ))),
+        }
+    }
+}
+
+/// Outputs of every node that ran
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowRun {
+    /// Node ids in the order they ran
+    pub order: Vec<String>,
+    /// Outputs by node id, then by port name
+    pub outputs: HashMap<String, Map<String, Value>>,
+}
+
+/// Checks nodes, edges, port types and required inputs, and returns the
+/// indices of `workflow.nodes` in an order where every node comes after the
+/// nodes it takes input from
+pub async fn validate_workflow(
+    workflow: &Workflow,
+    runner: &dyn NodeRunner,
+) -> Result<Vec<usize>, WorkflowError> {
+    let mut index = HashMap::new();
+    let mut definitions = Vec::with_capacity(workflow.nodes.len());
+    for (i, node) in workflow.nodes.iter().enumerate() {
+        if index.insert(node.id.as_str(), i).is_some() {
+            return Err(WorkflowError::DuplicateNode(node.id.clone()));
+        }
+        let definition = runner
+            .definition(&node.plugin_id, &node.node_type)
+            .await
+            .ok_or_else(|| WorkflowError::UnknownNodeType {
+                node: node.id.clone(),
+                plugin_id: node.plugin_id.clone(),
+                node_type: node.node_type.clone(),
+            })?;
+        definitions.push(definition);
+    }
+
+    let mut connected = HashSet::new();
+    for edge in &workflow.edges {
+        let from = *index
+            .get(edge.from_node.as_str())
+            .ok_or_else(|| WorkflowError::UnknownNode(edge.from_node.clone()))?;
+        let to = *index
+            .get(edge.to_node.as_str())
+            .ok_or_else(|| WorkflowError::UnknownNode(edge.to_node.clone()))?;
+        let output = find_port(&definitions[from].outputs, &edge.from_node, &edge.from_port, "output")?;
+        let input = find_port(&definitions[to].inputs, &edge.to_node, &edge.to_port, "input")?;
+
+        if !types_match(&output.port_type, &input.port_type) {
+            return Err(WorkflowError::TypeMismatch {
+                from_node: edge.from_node.clone(),
+                from_port: edge.from_port.clone(),
+                from_type: output.port_type.clone(),
+                to_node: edge.to_node.clone(),
+                to_port: edge.to_port.clone(),
+                to_type: input.port_type.clone(),
+            });
+        }
+        if !connected.insert((to, edge.to_port.as_str())) {
+            return Err(WorkflowError::InputConnectedTwice {
+                node: edge.to_node.clone(),
+                port: edge.to_port.clone(),
+            });
+        }
+    }
+
+    for (i, definition) in definitions.iter().enumerate() {
+        if let Some(port) = definition
+            .inputs
+            .iter()
+            .find(|port| port.required && !connected.contains(&(i, port.name.as_str())))
+        {
+            return Err(WorkflowError::MissingInput {
+                node: workflow.nodes[i].id.clone(),
+                port: port.name.clone(),
+            });
+        }
+    }
+
+    topological_order(workflow, &index)
+}
+
+/// Validates `workflow`, then runs each node once its inputs are ready.
+/// Stops at the first node that fails.
+pub async fn run_workflow(workflow: &Workflow, runner: &dyn NodeRunner) -> Result<WorkflowRun, WorkflowError> {
+    let order = validate_workflow(workflow, runner).await?;
+    let mut run = WorkflowRun {
+        order: Vec::with_capacity(order.len()),
+        outputs: HashMap::new(),
+    };
+
+    for i in order {
+        let node = &workflow.nodes[i];
+        let mut inputs = Map::new();
+        for edge in workflow.edges.iter().filter(|edge| edge.to_node == node.id) {
+            let value = run
+                .outputs
+                .get(&edge.from_node)
+                .and_then(|outputs| outputs.get(&edge.from_port))
+                .ok_or_else(|| WorkflowError::MissingOutput {
+                    node: edge.from_node.clone(),
+                    port: edge.from_port.clone(),
+                })?;
+            inputs.insert(edge.to_port.clone(), value.clone());
+        }
+
+        tracing::info!("Running workflow '{}' node '{}'", workflow.id, node.id);
+        let outputs = runner
+            .run_node(node, inputs)
+            .await
+            .map_err(|source| WorkflowError::NodeFailed {
+                node: node.id.clone(),
+                source,
+            })?;
+        run.order.push(node.id.clone());
+        run.outputs.insert(node.id.clone(), outputs);
+    }
+    Ok(run)
+}
+
+fn find_port<'a>(
+    ports: &'a [WorkflowPort],
+    node: &str,
+    port: &str,
+    direction: &'static str,
+) -> Result<&'a WorkflowPort, WorkflowError> {
+    ports
+        .iter()
+        .find(|p| p.name == port)
+        .ok_or_else(|| WorkflowError::UnknownPort {
+            node: node.to_string(),
+            port: port.to_string(),
+            direction,
+        })
+}
+
+fn types_match(output: &str, input: &str) -> bool {
+    output == input || output == ANY_PORT_TYPE || input == ANY_PORT_TYPE
+}
+
+/// Kahn's algorithm; nodes without a dependency between them keep the order
+/// they were listed in
+fn topological_order(workflow: &Workflow, index: &HashMap<&str, usize>) -> Result<Vec<usize>, WorkflowError> {
+    let count = workflow.nodes.len();
+    let mut incoming = vec![0usize; count];
+    let mut successors = vec![Vec::new(); count];
+    for edge in &workflow.edges {
+        let (from, to) = (index[edge.from_node.as_str()], index[edge.to_node.as_str()]);
+        successors[from].push(to);
+        incoming[to] += 1;
+    }
+
+    let mut ready: VecDeque<usize> = (0..count).filter(|&i| incoming[i] == 0).collect();
+    let mut order = Vec::with_capacity(count);
+    while let Some(i) = ready.pop_front() {
+        order.push(i);
+        for &next in &successors[i] {
+            incoming[next] -= 1;
+            if incoming[next] == 0 {
+                ready.push_back(next);
+            }
+        }
+    }
+
+    if order.len() < count {
+        return Err(WorkflowError::Cycle(find_cycle(workflow, index, &incoming)));
+    }
+    Ok(order)
+}
+
+/// Node ids of one cycle among the nodes left with unmet inputs. Each of
+/// them has a predecessor that is also left, so walking predecessors from
+/// any of them must revisit a node.
+fn find_cycle(workflow: &Workflow, index: &HashMap<&str, usize>, incoming: &[usize]) -> Vec<String> {
+    let left = |id: &str| incoming[index[id]] > 0;
+    let Some(start) = workflow.nodes.iter().find(|node| left(&node.id)) else {
+        return Vec::new();
+    };
+
+    let mut path = vec![start.id.as_str()];
+    loop {
+        let current = path[path.len() - 1];
+        let Some(previous) = workflow
+            .edges
+            .iter()
+            .find(|edge| edge.to_node == current && left(&edge.from_node))
+            .map(|edge| edge.from_node.as_str())
+        else {
+            return path.iter().map(|id| id.to_string()).collect();
+        };
+        if let Some(pos) = path.iter().position(|&id| id == previous) {
+            // `path` runs against the edges; flip it and close the loop
+            let mut cycle: Vec<String> = path[pos..].iter().rev().map(|id| id.to_string()).collect();
+            cycle.push(cycle[0].clone());
+            return cycle;
+        }
+        path.push(previous);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::workflow::WorkflowEdge;
+    use parking_lot::Mutex;
+    use serde_json::json;
+
+    fn port(name: &str, port_type: &str, required: bool) -> WorkflowPort {
+        WorkflowPort {
+            name: name.to_string(),
+            port_type: port_type.to_string(),
+            required,
+            description: None,
+        }
+    }
+
+    /// "text" plugin: `source` emits its configured text, `uppercase` and
+    /// `count` take text in; records which nodes ran
+    #[derive(Default)]
+    struct MockRunner {
+        ran: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl NodeRunner for MockRunner {
+        async fn definition(&self, plugin_id: &str, node_type: &str) -> Option<WorkflowNodeDefinition> {
+            let (inputs, outputs) = match (plugin_id, node_type) {
+                ("text", "source") => (vec![], vec![port("text", "string", false)]),
+                ("text", "uppercase") => (vec![port("text", "string", true)], vec![port("text", "string", false)]),
+                ("text", "count") => (vec![port("text", "string", true)], vec![port("length", "number", false)]),
+                _ => return None,
+            };
+            Some(WorkflowNodeDefinition {
+                node_type: node_type.to_string(),
+                name: node_type.to_string(),
+                description: String::new(),
+                icon: None,
+                inputs,
+                outputs,
+                config_schema: None,
+            })
+        }
+
+        async fn run_node(
+            &self,
+            node: &WorkflowNode,
+            inputs: Map<String, Value>,
+        ) -> Result<Map<String, Value>, PluginError> {
+            self.ran.lock().push(node.id.clone());
+            let text = inputs.get("text").and_then(Value::as_str).unwrap_or_default();
+            let output = match node.node_type.as_str() {
+                "source" => json!({ "text": node.config["text"] }),
+                "uppercase" => json!({ "text": text.to_uppercase() }),
+                _ => json!({ "length": text.len() }),
+            };
+            Ok(output.as_object().unwrap().clone())
+        }
+    }
+
+    fn node(id: &str, node_type: &str) -> WorkflowNode {
+        WorkflowNode {
+            id: id.to_string(),
+            plugin_id: "text".to_string(),
+            node_type: node_type.to_string(),
+            config: json!({ "text": "hello" }),
+        }
+    }
+
+    fn edge(from: &str, from_port: &str, to: &str, to_port: &str) -> WorkflowEdge {
+        WorkflowEdge {
+            from_node: from.to_string(),
+            from_port: from_port.to_string(),
+            to_node: to.to_string(),
+            to_port: to_port.to_string(),
+        }
+    }
+
+    fn workflow(nodes: Vec<WorkflowNode>, edges: Vec<WorkflowEdge>) -> Workflow {
+        Workflow {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            nodes,
+            edges,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_two_node_pipeline() {
+        // Listed downstream first; the run order still follows the edge
+        let workflow = workflow(
+            vec![node("upper", "uppercase"), node("input", "source")],
+            vec![edge("input", "text", "upper", "text")],
+        );
+        let runner = MockRunner::default();
+
+        let run = run_workflow(&workflow, &runner).await.unwrap();
+
+        assert_eq!(run.order, vec!["input", "upper"]);
+        assert_eq!(run.outputs["upper"]["text"], "HELLO");
+        assert_eq!(*runner.ran.lock(), vec!["input", "upper"]);
+    }
+
+    #[tokio::test]
+    async fn test_cycle_is_detected() {
+        let workflow = workflow(
+            vec![node("a", "uppercase"), node("b", "uppercase")],
+            vec![edge("a", "text", "b", "text"), edge("b", "text", "a", "text")],
+        );
+        let runner = MockRunner::default();
+
+        match run_workflow(&workflow, &runner).await {
+            Err(WorkflowError::Cycle(cycle)) => {
+                assert_eq!(cycle, vec!["b", "a", "b"]);
+            }
+            other => panic!("expected a cycle, got {:?}", other.map(|run| run.order)),
+        }
+        assert!(runner.ran.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_type_mismatch() {
+        let workflow = workflow(
+            vec![node("input", "source"), node("count", "count"), node("upper", "uppercase")],
+            vec![edge("input", "text", "count", "text"), edge("count", "length", "upper", "text")],
+        );
+
+        let err = run_workflow(&workflow, &MockRunner::default()).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot connect count.length (number) to upper.text (string)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_required_input_fails_before_running() {
+        let workflow = workflow(vec![node("input", "source"), node("upper", "uppercase")], vec![]);
+        let runner = MockRunner::default();
+
+        let err = run_workflow(&workflow, &runner).await.unwrap_err();
+        assert!(matches!(err, WorkflowError::MissingInput { ref node, ref port } if node == "upper" && port == "text"));
+        assert!(runner.ran.lock().is_empty());
+    }
+}
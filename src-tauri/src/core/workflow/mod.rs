@@ -1,10 +1,178 @@
-// Workflow engine module
-// TODO: Implement workflow logic
+// Workflows: plugin-provided nodes wired into a graph
+use crate::core::plugin::PluginError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
-pub struct WorkflowEngine;
+pub mod executor;
 
-impl WorkflowEngine {
-    pub fn new() -> Self {
-        Self
+pub use executor::{run_workflow, validate_workflow, NodeRunner, WorkflowRun};
+
+/// Port type that connects to a port of any type
+pub const ANY_PORT_TYPE: &str = "any";
+
+/// A graph of node instances; edges carry outputs to inputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workflow {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub nodes: Vec<WorkflowNode>,
+    #[serde(default)]
+    pub edges: Vec<WorkflowEdge>,
+}
+
+/// One use of a node type declared by a plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowNode {
+    /// Unique within the workflow
+    pub id: String,
+    pub plugin_id: String,
+    pub node_type: String,
+    /// Passed to the plugin along with the inputs
+    #[serde(default)]
+    pub config: serde_json::Value,
+}
+
+/// Connects an output port of one node to an input port of another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowEdge {
+    pub from_node: String,
+    pub from_port: String,
+    pub to_node: String,
+    pub to_port: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkflowError {
+    #[error("Node id '{0}' is used more than once")]
+    DuplicateNode(String),
+
+    #[error("Unknown node '{0}'")]
+    UnknownNode(String),
+
+    #[error("Node '{node}': plugin '{plugin_id}' has no node type '{node_type}'")]
+    UnknownNodeType {
+        node: String,
+        plugin_id: String,
+        node_type: String,
+    },
+
+    /// `direction` is "input" or "output"
+    #[error("Node '{node}' has no {direction} port '{port}'")]
+    UnknownPort {
+        node: String,
+        port: String,
+        direction: &'static str,
+    },
+
+    #[error("Cannot connect {from_node}.{from_port} ({from_type}) to {to_node}.{to_port} ({to_type})")]
+    TypeMismatch {
+        from_node: String,
+        from_port: String,
+        from_type: String,
+        to_node: String,
+        to_port: String,
+        to_type: String,
+    },
+
+    #[error("Input {node}.{port} is connected more than once")]
+    InputConnectedTwice { node: String, port: String },
+
+    #[error("Required input {node}.{port} is not connected")]
+    MissingInput { node: String, port: String },
+
+    /// Node ids along the cycle, ending where it started
+    #[error("Workflow contains a cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+
+    #[error("Node '{node}' failed: {source}")]
+    NodeFailed { node: String, source: PluginError },
+
+    #[error("Node '{node}' did not produce output '{port}'")]
+    MissingOutput { node: String, port: String },
+
+    #[error("Invalid workflow id '{0}'")]
+    InvalidId(String),
+
+    #[error("Workflow not found: {0}")]
+    NotFound(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// Saved workflows, one JSON file each
+pub struct WorkflowStore {
+    dir: PathBuf,
+}
+
+impl WorkflowStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Saves `workflow`, replacing any with the same id
+    pub fn save(&self, workflow: &Workflow) -> Result<(), WorkflowError> {
+        let path = self.path(&workflow.id)?;
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(path, serde_json::to_string_pretty(workflow)?)?;
+        Ok(())
+    }
+
+    pub fn load(&self, id: &str) -> Result<Workflow, WorkflowError> {
+        let content = match std::fs::read_to_string(self.path(id)?) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(WorkflowError::NotFound(id.to_string()))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Ids become file names, so only plain names are accepted
+    fn path(&self, id: &str) -> Result<PathBuf, WorkflowError> {
+        let valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(WorkflowError::InvalidId(id.to_string()));
+        }
+        Ok(self.dir.join(format!("{}.json", id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("omnibox_workflows_{}", uuid::Uuid::new_v4()));
+        let store = WorkflowStore::new(dir.clone());
+        let workflow = Workflow {
+            id: "daily-report".to_string(),
+            name: "Daily report".to_string(),
+            nodes: vec![WorkflowNode {
+                id: "fetch".to_string(),
+                plugin_id: "http".to_string(),
+                node_type: "get".to_string(),
+                config: serde_json::json!({ "url": "https://example.com" }),
+            }],
+            edges: Vec::new(),
+        };
+
+        store.save(&workflow).unwrap();
+        let loaded = store.load("daily-report").unwrap();
+        assert_eq!(loaded.nodes[0].config["url"], "https://example.com");
+
+        assert!(matches!(store.load("missing"), Err(WorkflowError::NotFound(_))));
+        assert!(matches!(store.load("../config"), Err(WorkflowError::InvalidId(_))));
+
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
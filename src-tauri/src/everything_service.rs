@@ -8,7 +8,10 @@
 use std::path::PathBuf;
 use std::os::raw::{c_int, c_uint};
 use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 
+use crate::app::config::FileSortOrder;
+use crate::core::indexer::SearchFilter;
 use libloading::Library;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
@@ -45,7 +48,13 @@ const EVERYTHING_REQUEST_DATE_MODIFIED: c_uint = 0x00000040;
 
 // Sort options
 const EVERYTHING_SORT_NAME_ASCENDING: c_uint = 1;
-const EVERYTHING_SORT_DATE_MODIFIED_DESCENDING: c_uint = 12;
+const EVERYTHING_SORT_NAME_DESCENDING: c_uint = 2;
+const EVERYTHING_SORT_PATH_ASCENDING: c_uint = 3;
+const EVERYTHING_SORT_SIZE_ASCENDING: c_uint = 5;
+const EVERYTHING_SORT_SIZE_DESCENDING: c_uint = 6;
+const EVERYTHING_SORT_DATE_MODIFIED_ASCENDING: c_uint = 13;
+const EVERYTHING_SORT_DATE_MODIFIED_DESCENDING: c_uint = 14;
+const EVERYTHING_SORT_RUN_COUNT_DESCENDING: c_uint = 20;
 
 // Error codes
 const EVERYTHING_OK: c_uint = 0;
@@ -170,7 +179,7 @@ impl EverythingLib {
         }
     }
     
-    fn search(&self, query: &str, max_results: u32) -> Result<Vec<FileSearchResult>, String> {
+    fn search(&self, query: &str, max_results: u32, options: &SearchOptions) -> Result<Vec<FileSearchResult>, String> {
         unsafe {
             // Reset state
             (self.reset)();
@@ -181,8 +190,8 @@ impl EverythingLib {
             // Configure search options
             (self.set_match_case)(0);        // Case insensitive
             (self.set_match_whole_word)(0);  // Partial match
-            (self.set_match_path)(0);        // Match filename only, not full path
-            (self.set_sort)(EVERYTHING_SORT_DATE_MODIFIED_DESCENDING); // Recent files first
+            (self.set_match_path)(options.match_path as c_int);
+            (self.set_sort)(sort_constant(options.sort));
             
             // Set search parameters
             (self.set_search_w)(query_wide.as_ptr());
@@ -261,19 +270,64 @@ impl EverythingLib {
         }
     }
     
-    fn error_to_string(&self, error: c_uint) -> String {
-        match error {
-            EVERYTHING_OK => "OK".to_string(),
-            EVERYTHING_ERROR_MEMORY => "Memory allocation error".to_string(),
-            EVERYTHING_ERROR_IPC => "Everything IPC error - is Everything running?".to_string(),
-            EVERYTHING_ERROR_REGISTERCLASSEX => "Failed to register window class".to_string(),
-            EVERYTHING_ERROR_CREATEWINDOW => "Failed to create window".to_string(),
-            EVERYTHING_ERROR_CREATETHREAD => "Failed to create thread".to_string(),
-            EVERYTHING_ERROR_INVALIDINDEX => "Invalid index".to_string(),
-            EVERYTHING_ERROR_INVALIDCALL => "Invalid call".to_string(),
-            _ => format!("Unknown error: {}", error),
+    /// Run a query for at most one result. An IPC error means the DLL is
+    /// loaded but Everything.exe is not running.
+    fn probe(&self) -> EverythingStatus {
+        unsafe {
+            (self.reset)();
+            let query_wide: Vec<u16> = PROBE_QUERY.encode_utf16().chain(std::iter::once(0)).collect();
+            (self.set_search_w)(query_wide.as_ptr());
+            (self.set_request_flags)(EVERYTHING_REQUEST_FULL_PATH_AND_FILE_NAME);
+            (self.set_max)(1);
+            let error = if (self.query_w)(1) != 0 {
+                EVERYTHING_OK
+            } else {
+                (self.get_last_error)()
+            };
+            status_for_error(error)
         }
     }
+    
+    fn error_to_string(&self, error: c_uint) -> String {
+        error_message(error)
+    }
+}
+
+fn error_message(error: c_uint) -> String {
+    match error {
+        EVERYTHING_OK => "OK".to_string(),
+        EVERYTHING_ERROR_MEMORY => "Memory allocation error".to_string(),
+        EVERYTHING_ERROR_IPC => "Everything IPC error - is Everything running?".to_string(),
+        EVERYTHING_ERROR_REGISTERCLASSEX => "Failed to register window class".to_string(),
+        EVERYTHING_ERROR_CREATEWINDOW => "Failed to create window".to_string(),
+        EVERYTHING_ERROR_CREATETHREAD => "Failed to create thread".to_string(),
+        EVERYTHING_ERROR_INVALIDINDEX => "Invalid index".to_string(),
+        EVERYTHING_ERROR_INVALIDCALL => "Invalid call".to_string(),
+        _ => format!("Unknown error: {}", error),
+    }
+}
+
+/// `Everything_SetSort` value for a sort order
+fn sort_constant(sort: FileSortOrder) -> c_uint {
+    match sort {
+        FileSortOrder::NameAscending => EVERYTHING_SORT_NAME_ASCENDING,
+        FileSortOrder::NameDescending => EVERYTHING_SORT_NAME_DESCENDING,
+        FileSortOrder::PathAscending => EVERYTHING_SORT_PATH_ASCENDING,
+        FileSortOrder::SizeDescending => EVERYTHING_SORT_SIZE_DESCENDING,
+        FileSortOrder::SizeAscending => EVERYTHING_SORT_SIZE_ASCENDING,
+        FileSortOrder::DateModifiedDescending => EVERYTHING_SORT_DATE_MODIFIED_DESCENDING,
+        FileSortOrder::DateModifiedAscending => EVERYTHING_SORT_DATE_MODIFIED_ASCENDING,
+        FileSortOrder::RunCountDescending => EVERYTHING_SORT_RUN_COUNT_DESCENDING,
+    }
+}
+
+/// Map an Everything SDK error code to a service status
+fn status_for_error(error: c_uint) -> EverythingStatus {
+    match error {
+        EVERYTHING_OK => EverythingStatus::Running,
+        EVERYTHING_ERROR_IPC => EverythingStatus::NotRunning,
+        error => EverythingStatus::Error(error_message(error)),
+    }
 }
 
 impl Drop for EverythingLib {
@@ -290,10 +344,29 @@ impl Drop for EverythingLib {
 
 static EVERYTHING: OnceLock<Result<EverythingLib, String>> = OnceLock::new();
 
+/// Last service probe; search runs on every keystroke, so probes are reused briefly
+static SERVICE_STATUS: Lazy<Mutex<Option<(Instant, EverythingStatus)>>> = Lazy::new(|| Mutex::new(None));
+
+/// How long a probe result is trusted
+const STATUS_TTL: Duration = Duration::from_secs(3);
+
+/// Matches nothing real; the probe only cares whether IPC succeeds
+const PROBE_QUERY: &str = "omnibox-everything-probe";
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Public API
 // ═══════════════════════════════════════════════════════════════════════════════
 
+/// How `search_files` queries Everything and filters its results
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    pub sort: FileSortOrder,
+    /// Let the query match full paths (`Everything_SetMatchPath`)
+    pub match_path: bool,
+    /// Applied to the returned paths, after the query
+    pub filter: SearchFilter,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct FileSearchResult {
     pub path: String,
@@ -558,7 +631,11 @@ fn build_smart_query(input: &str) -> String {
 /// Search files using Everything
 /// 
 /// Includes retry logic for IPC errors which can occur transiently.
-pub async fn search_files(query: String, max_results: Option<u32>) -> Result<Vec<FileSearchResult>, String> {
+pub async fn search_files(
+    query: String,
+    max_results: Option<u32>,
+    options: SearchOptions,
+) -> Result<Vec<FileSearchResult>, String> {
     let max = max_results.unwrap_or(50);
     
     // Build smart query with wildcards
@@ -583,12 +660,13 @@ pub async fn search_files(query: String, max_results: Option<u32>) -> Result<Vec
         }
         
         let query_clone = smart_query.clone();
+        let options_clone = options.clone();
         
         // Run search in blocking thread with timeout (Everything API is synchronous)
         let search_future = tokio::task::spawn_blocking(move || {
             let _guard = EVERYTHING_QUERY_LOCK.lock();
             match EVERYTHING.get() {
-                Some(Ok(lib)) => lib.search(&query_clone, max),
+                Some(Ok(lib)) => lib.search(&query_clone, max, &options_clone),
                 Some(Err(e)) => Err(e.clone()),
                 None => Err("Everything not initialized".to_string()),
             }
@@ -612,19 +690,12 @@ pub async fn search_files(query: String, max_results: Option<u32>) -> Result<Vec
         match result {
             Ok(results) => {
                 tracing::debug!("Everything returned {} raw results", results.len());
+                record_status(EverythingStatus::Running);
                 
-                // Filter out undesirable results
+                // Apply the configured include/exclude globs
                 let filtered: Vec<FileSearchResult> = results
                     .into_iter()
-                    .filter(|r| {
-                        let name_lower = r.filename.to_lowercase();
-                        // Skip uninstallers
-                        !name_lower.contains("uninstall") 
-                            && !name_lower.contains("卸载")
-                            // Skip system/temp files
-                            && !r.path.contains("$Recycle.Bin")
-                            && !r.path.contains("System Volume Information")
-                    })
+                    .filter(|r| options.filter.matches_path(std::path::Path::new(&r.path)))
                     .collect();
                 
                 tracing::debug!("Everything returning {} filtered results", filtered.len());
@@ -634,6 +705,7 @@ pub async fn search_files(query: String, max_results: Option<u32>) -> Result<Vec
                 // IPC error is retriable
                 if e.contains("IPC") {
                     tracing::warn!("Everything IPC error (attempt {}): {}", attempt, e);
+                    record_status(EverythingStatus::NotRunning);
                     last_error = Some(e);
                     continue;
                 }
@@ -653,6 +725,48 @@ pub fn is_available() -> bool {
     matches!(EVERYTHING.get(), Some(Ok(_)))
 }
 
+/// State of the Everything integration, for diagnostics
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "state", content = "message", rename_all = "snake_case")]
+pub enum EverythingStatus {
+    /// Everything64.dll could not be loaded
+    DllMissing(String),
+    /// The DLL is loaded but Everything.exe does not answer IPC
+    NotRunning,
+    Running,
+    Error(String),
+}
+
+/// Status of the Everything service, probed at most every few seconds.
+/// Blocks on the SDK, so call it from a blocking context.
+pub fn status() -> EverythingStatus {
+    if let Some((at, status)) = SERVICE_STATUS.lock().as_ref() {
+        if at.elapsed() < STATUS_TTL {
+            return status.clone();
+        }
+    }
+
+    let status = {
+        let _guard = EVERYTHING_QUERY_LOCK.lock();
+        match EVERYTHING.get() {
+            Some(Ok(lib)) => lib.probe(),
+            Some(Err(e)) => EverythingStatus::DllMissing(e.clone()),
+            None => EverythingStatus::DllMissing("Everything not initialized".to_string()),
+        }
+    };
+    record_status(status.clone());
+    status
+}
+
+/// Whether Everything.exe answers queries, as opposed to only the DLL being loaded
+pub fn is_everything_service_running() -> bool {
+    status() == EverythingStatus::Running
+}
+
+fn record_status(status: EverythingStatus) {
+    *SERVICE_STATUS.lock() = Some((Instant::now(), status));
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Tests
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -705,4 +819,29 @@ mod tests {
         assert_eq!(classify_file("C:\\docs\\file.pdf", "pdf"), "File");
         assert_eq!(classify_file("C:\\data\\file.pck", "pck"), "File");
     }
+    #[test]
+    fn test_sort_constant() {
+        assert_eq!(sort_constant(FileSortOrder::default()), 14);
+        assert_eq!(sort_constant(FileSortOrder::NameAscending), 1);
+        assert_eq!(sort_constant(FileSortOrder::NameDescending), 2);
+        assert_eq!(sort_constant(FileSortOrder::PathAscending), 3);
+        assert_eq!(sort_constant(FileSortOrder::SizeAscending), 5);
+        assert_eq!(sort_constant(FileSortOrder::SizeDescending), 6);
+        assert_eq!(sort_constant(FileSortOrder::DateModifiedAscending), 13);
+        assert_eq!(sort_constant(FileSortOrder::RunCountDescending), 20);
+    }
+
+    #[test]
+    fn test_status_for_error() {
+        assert_eq!(status_for_error(EVERYTHING_OK), EverythingStatus::Running);
+        assert_eq!(status_for_error(EVERYTHING_ERROR_IPC), EverythingStatus::NotRunning);
+        assert_eq!(
+            status_for_error(EVERYTHING_ERROR_MEMORY),
+            EverythingStatus::Error("Memory allocation error".to_string())
+        );
+        assert_eq!(
+            status_for_error(99),
+            EverythingStatus::Error("Unknown error: 99".to_string())
+        );
+    }
 }
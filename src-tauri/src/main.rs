@@ -13,6 +13,7 @@ use omnibox::ocr;
 #[cfg(windows)]
 use omnibox::everything_service;
 
+use app::startup::{self, StartupStatus};
 use app::state::AppState;
 use commands::*;
 use commands::ai::AIState;
@@ -36,37 +37,21 @@ struct MainShowState {
     focused_at: Option<Instant>,
 }
 
+// Blur graces and shortcut debounces come from `autohide` in the settings
 static MAIN_SHOW_STATE: Lazy<Mutex<MainShowState>> = Lazy::new(|| Mutex::new(MainShowState::default()));
-const MAIN_SHOW_BLUR_GRACE_NO_FOCUS: Duration = Duration::from_millis(2000);
-const MAIN_FOCUS_TO_BLUR_GRACE: Duration = Duration::from_millis(250);
 
 static LAST_MAIN_SHORTCUT_AT: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
-const MAIN_SHORTCUT_DEBOUNCE: Duration = Duration::from_millis(350);
 
 // Clipboard shortcut debounce to prevent double-trigger
 static LAST_CLIPBOARD_SHORTCUT_AT: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
-const CLIPBOARD_SHORTCUT_DEBOUNCE: Duration = Duration::from_millis(400);
 
 // Capture shortcut debounce - more aggressive due to rapid repeats observed
 static LAST_CAPTURE_SHORTCUT_AT: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
-const CAPTURE_SHORTCUT_DEBOUNCE: Duration = Duration::from_millis(500);
 
 fn main() {
     // Initialize logger
     utils::logger::init_simple_logger();
 
-    fn launcher_autohide_enabled() -> bool {
-        // Default ON (both dev & release) to match launcher UX.
-        // Disable via: OMNIBOX_AUTOHIDE_ON_BLUR=0
-        let v = std::env::var("OMNIBOX_AUTOHIDE_ON_BLUR").ok();
-        let explicit = v.as_deref().map(|s| s.trim()).filter(|s| !s.is_empty());
-
-        match explicit {
-            Some("0") | Some("false") | Some("FALSE") | Some("no") | Some("NO") => false,
-            _ => true,
-        }
-    }
-
     tauri::Builder::default()
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
@@ -84,13 +69,21 @@ fn main() {
                     let _ = window.hide();
                     return;
                 }
+
+                // Keep the AI panel alive so its conversation can be reopened
+                if window.label() == "ai" {
+                    api.prevent_close();
+                    let _ = window.hide();
+                    system::note_ai_panel_closed();
+                    return;
+                }
             }
 
             // Auto-hide launcher (main window only) when it loses focus.
             // DO NOT apply to clipboard or other windows!
             if let tauri::WindowEvent::Focused(focused) = event {
                 let label = window.label();
-                if label != "main" || !launcher_autohide_enabled() {
+                if label != "main" || !core::autohide::on_blur_enabled() {
                     return;
                 }
 
@@ -105,14 +98,15 @@ fn main() {
                 // On Windows (especially with transparent windows), focus can flap during show or
                 // during programmatic resize. We only auto-hide once the window has actually been
                 // focused at least once since it was shown.
+                let timings = core::autohide::timings();
                 if let Ok(st) = MAIN_SHOW_STATE.lock() {
                     if let Some(shown_at) = st.shown_at {
-                        if st.focused_at.is_none() && shown_at.elapsed() < MAIN_SHOW_BLUR_GRACE_NO_FOCUS {
+                        if st.focused_at.is_none() && shown_at.elapsed() < timings.show_blur_grace {
                             // Never actually focused: ignore this transient blur.
                             return;
                         }
                         if let Some(focused_at) = st.focused_at {
-                            if focused_at.elapsed() < MAIN_FOCUS_TO_BLUR_GRACE {
+                            if focused_at.elapsed() < timings.focus_blur_grace {
                                 // Just focused then immediately blurred: likely transient.
                                 return;
                             }
@@ -169,32 +163,44 @@ fn main() {
             // ═══════════════════════════════════════════════════════════════════
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                match AppState::new(app_handle.clone()).await {
-                    Ok(state) => {
-                        // Start background indexing task
-                        let state_clone = state.clone();
-                        tauri::async_runtime::spawn(async move {
-                            if let Err(e) = state_clone.initialize_indexing().await {
-                                tracing::error!("Failed to initialize indexing: {}", e);
-                            }
-                        });
-                        
-                        // Start clipboard monitoring
-                        let state_for_clipboard = state.clone();
-                        tauri::async_runtime::spawn(async move {
-                            if let Ok(monitor) = state_for_clipboard.clipboard_monitor().await {
-                                if let Err(e) = monitor.start().await {
-                                    tracing::error!("Failed to start clipboard monitor: {}", e);
-                                }
+                // On failure the launcher shows the published error
+                if let Some(state) = init_app_state(&app_handle).await {
+                    // Start background indexing task
+                    let state_clone = state.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = state_clone.initialize_indexing().await {
+                            tracing::error!("Failed to initialize indexing: {}", e);
+                        }
+                    });
+                
+                    // Start clipboard monitoring
+                    let state_for_clipboard = state.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Ok(monitor) = state_for_clipboard.clipboard_monitor().await {
+                            if let Err(e) = monitor.start().await {
+                                tracing::error!("Failed to start clipboard monitor: {}", e);
                             }
-                        });
-                        
-                        app_handle.manage(state);
-                        tracing::info!("AppState initialized successfully");
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to initialize AppState: {}", e);
-                    }
+                        }
+                    });
+                
+                    // Log failed checks so startup problems show up in bug reports
+                    let state_for_diagnostics = state.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let report = system::diagnostics_report(&state_for_diagnostics).await;
+                        for check in report.failures() {
+                            tracing::warn!("Startup check failed: {}: {}", check.name, check.detail);
+                        }
+                        tracing::info!(
+                            "Startup checks: {} passed, {} failed, {} skipped",
+                            report.passed, report.failed, report.skipped
+                        );
+                    });
+
+                    let shortcuts = state.get_config().await.shortcuts;
+                    app_handle.manage(state);
+                    startup::publish(&app_handle, StartupStatus::Ready);
+                    tracing::info!("AppState initialized successfully");
+                    app::shortcuts::register(&app_handle, &shortcuts);
                 }
             });
             
@@ -207,11 +213,39 @@ fn main() {
             // Search commands (uses hybrid search: AppIndexer + Everything)
             search::search,
             search::calculate,
+            search::quick_eval,
+            search::define_word,
+            search::execute_action,
+            search::search_browser_history,
+            search::set_full_text_directories,
+            search::get_index_roots,
+            search::add_index_root,
+            search::remove_index_root,
+            search::refresh_index,
+            search::index_stats,
+            search::index_self_test,
+            search::extract_icons_batch,
+            search::get_recent_items,
+            search::pin_result,
+            search::unpin_result,
+            search::find_duplicates,
+            search::cancel_find_duplicates,
+            search::delete_duplicate,
+            search::record_palette_use,
             // Clipboard commands
             clipboard::get_clipboard_history,
             clipboard::paste_clipboard_item,
+            clipboard::paste_as_plain_text,
+            clipboard::paste_clipboard_item_transformed,
+            clipboard::clipboard_ring_reset,
+            clipboard::clipboard_ring_paste_next,
+            clipboard::combine_clipboard_items,
+            clipboard::ocr_clipboard_item,
             clipboard::toggle_clipboard_favorite,
             clipboard::delete_clipboard_item,
+            clipboard::clear_clipboard_history,
+            clipboard::export_clipboard_history,
+            clipboard::import_clipboard_history,
             clipboard::show_clipboard_window,
             clipboard::hide_clipboard_window,
             // AI commands
@@ -223,10 +257,12 @@ fn main() {
             ai::ai_chat,
             ai::ai_chat_stream,
             ai::ai_save_response,
+            ai::ai_get_usage,
             ai::ai_get_presets,
             ai::ai_add_preset,
             ai::ai_delete_preset,
             ai::ai_get_models,
+            ai::ai_test_provider,
             ai::get_ai_conversations,
             ai::ai_quick_query,
             ai::ai_quick_stop,
@@ -243,39 +279,84 @@ fn main() {
             plugin::get_featured_plugins,
             plugin::grant_plugin_permission,
             plugin::revoke_plugin_permission,
+            plugin::execute_plugin_action,
+            plugin::get_plugin_config,
+            plugin::set_plugin_config,
             // Settings commands
             settings::get_config,
             settings::update_config,
             settings::reset_config,
             settings::export_config,
             settings::import_config,
+            settings::list_search_engines,
+            settings::add_search_engine,
+            settings::remove_search_engine,
+            settings::clear_icon_cache,
+            settings::update_autohide,
+            // Workflow commands
+            workflow::save_workflow,
+            workflow::load_workflow,
+            workflow::run_workflow,
+            // Snippet commands
+            snippet::add_snippet,
+            snippet::delete_snippet,
+            snippet::list_snippets,
+            snippet::paste_snippet,
             // System commands
             system::open_path,
+            system::open_with,
+            system::get_open_with_candidates,
+            system::launch_path,
+            system::reveal_in_file_manager,
+            system::copy_path_to_clipboard,
+            system::copy_file_as_data_url,
+            system::quick_note,
+            system::quick_note_from_clipboard,
+            system::launch_elevated,
             system::open_url,
             system::show_window,
             system::hide_window,
             system::toggle_main_window,
             system::app_ready,
+            system::set_active_conversation,
+            system::reopen_recently_closed,
+            system::list_processes,
+            system::kill_process,
+            system::execute_system_command,
+            system::run_diagnostics,
+            system::get_app_state_status,
             // Capture commands
             capture::init_capture,
             capture::capture_frontend_ready,
             capture::is_capture_ready,
             capture::save_capture,
             capture::save_capture_file,
+            capture::save_capture_auto,
+            capture::get_last_capture,
+            capture::copy_last_capture,
+            capture::assemble_scroll_capture,
+            capture::capture_element_at,
             capture::copy_capture_base64,
             capture::hide_capture_window,
             capture::create_pin_window,
             capture::create_pin_window_from_selection,
             capture::close_pin_window,
             capture::get_pin_payload,
+            capture::remember_capture_region,
+            capture::pick_color_at,
+            capture::pick_color_at_cursor,
 
             // OCR (Windows native via WinRT)
             ocr::recognize_text,
+            ocr::ocr_available_languages,
+            ocr::ocr_image_file,
 
             // UI Automation
             automation::get_element_rect_at,
             automation::get_element_info_at,
             automation::get_element_rects_batch,
+            automation::list_windows,
+            automation::focus_window,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
@@ -390,6 +471,11 @@ fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
                 }
                 "quit" => {
                     tracing::info!("Tray menu: Quit clicked - exiting application");
+                    // What the watcher indexed since the last save
+                    if let Some(state) = app_handle_for_menu.try_state::<AppState>() {
+                        tauri::async_runtime::block_on(state.save_index_snapshot_if_changed());
+                    }
+                    tauri::async_runtime::block_on(core::browser_history::remove_copies());
                     // Force exit - this MUST work
                     std::process::exit(0);
                 }
@@ -437,7 +523,7 @@ fn register_global_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error:
         // The launcher UX expects: hotkey always SHOWS (tray can toggle).
         if let Ok(mut last) = LAST_MAIN_SHORTCUT_AT.lock() {
             if let Some(t0) = *last {
-                if t0.elapsed() < MAIN_SHORTCUT_DEBOUNCE {
+                if t0.elapsed() < core::autohide::timings().main_shortcut_debounce {
                     return;
                 }
             }
@@ -453,7 +539,7 @@ fn register_global_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error:
         // Debounce: Windows hotkey can fire twice rapidly
         if let Ok(mut last) = LAST_CLIPBOARD_SHORTCUT_AT.lock() {
             if let Some(t0) = *last {
-                if t0.elapsed() < CLIPBOARD_SHORTCUT_DEBOUNCE {
+                if t0.elapsed() < core::autohide::timings().clipboard_shortcut_debounce {
                     tracing::debug!("Clipboard shortcut debounced");
                     return;
                 }
@@ -488,7 +574,7 @@ fn register_global_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error:
             // Aggressive debounce: Windows can fire global hotkeys many times per second
             if let Ok(mut last) = LAST_CAPTURE_SHORTCUT_AT.lock() {
                 if let Some(t0) = *last {
-                    if t0.elapsed() < CAPTURE_SHORTCUT_DEBOUNCE {
+                    if t0.elapsed() < core::autohide::timings().capture_shortcut_debounce {
                         tracing::trace!("Capture shortcut debounced ({})", label_owned);
                         return;
                     }
@@ -499,8 +585,8 @@ fn register_global_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error:
             tracing::info!("Capture shortcut triggered ({})", label_owned);
             let app_handle_capture = app_handle_capture.clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = capture::init_capture(app_handle_capture).await {
-                    tracing::error!("Capture init failed: {e}");
+                if let Err(e) = capture::capture_with_configured_mode(app_handle_capture).await {
+                    tracing::error!("Capture failed: {e}");
                 }
             });
         }) {
@@ -529,6 +615,36 @@ fn register_global_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error:
     Ok(())
 }
 
+/// `AppState::new`, tried again with backoff while it fails in a way that
+/// may pass, such as a locked database. Each attempt and the final failure
+/// are published through `app::startup`.
+async fn init_app_state(app_handle: &tauri::AppHandle) -> Option<AppState> {
+    let mut attempt = 1;
+    loop {
+        startup::publish(app_handle, StartupStatus::Starting { attempt });
+        match AppState::new(app_handle.clone()).await {
+            Ok(state) => return Some(state),
+            Err(e) if attempt < startup::MAX_INIT_ATTEMPTS && startup::is_transient(&e) => {
+                let delay = startup::retry_delay(attempt);
+                tracing::warn!("Failed to initialize AppState (attempt {}), retrying in {:?}: {}", attempt, delay, e);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                tracing::error!("Failed to initialize AppState: {}", e);
+                startup::publish(
+                    app_handle,
+                    StartupStatus::Failed {
+                        error: e.to_string(),
+                        attempts: attempt,
+                    },
+                );
+                return None;
+            }
+        }
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // WINDOW MANAGEMENT HELPERS
 // ═══════════════════════════════════════════════════════════════════════════════
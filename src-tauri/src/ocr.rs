@@ -1,32 +1,120 @@
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
+use serde::Serialize;
+
+/// Error returned when Windows has no OCR language pack installed at all.
+///
+/// Language packs are installed via Settings > Time & Language > Language
+/// (the "Optical character recognition" optional feature).
+pub const NO_LANGUAGE_PACK_ERROR: &str =
+    "No OCR language pack is installed. Add one in Windows Settings > Time & Language > Language";
+
+/// OCR output together with the recognizer language that produced it.
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrResult {
+    pub text: String,
+    /// BCP-47 tag of the OCR engine used (e.g. `en-US`, `zh-Hans-CN`).
+    pub language: Option<String>,
+}
 
 /// Windows 10/11 native OCR (WinRT).
 ///
 /// Accepts either raw base64 or a full data URL (`data:image/png;base64,...`).
+///
+/// `language` is an optional BCP-47 tag. When omitted, every installed OCR
+/// language matching the user's preferred languages is tried and the result
+/// with the highest score wins.
+///
+/// Errors with [`NO_LANGUAGE_PACK_ERROR`] when no OCR language pack is
+/// installed, or when the requested `language` is not installed.
 #[tauri::command]
-pub async fn recognize_text(base64_image: String) -> Result<String, String> {
-    recognize_text_impl(base64_image).await
+pub async fn recognize_text(base64_image: String, language: Option<String>) -> Result<OcrResult, String> {
+    recognize_text_impl(base64_image, language).await
+}
+
+/// List BCP-47 tags of installed OCR language packs.
+#[tauri::command]
+pub async fn ocr_available_languages() -> Result<Vec<String>, String> {
+    available_languages_impl().await
+}
+
+/// OCR an image file (PNG, JPEG or BMP), e.g. a screenshot in search results.
+#[tauri::command]
+pub async fn ocr_image_file(path: String, language: Option<String>) -> Result<OcrResult, String> {
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read {path}: {e}"))?;
+    recognize_image_bytes(bytes, language).await
+}
+
+/// OCR encoded image bytes. The image is decoded here and handed to the
+/// engine as an RGBA PNG, whatever format it was stored in.
+pub async fn recognize_image_bytes(bytes: Vec<u8>, language: Option<String>) -> Result<OcrResult, String> {
+    let png = tauri::async_runtime::spawn_blocking(move || normalize_image(&bytes))
+        .await
+        .map_err(|e| format!("OCR task join failed: {e}"))??;
+    recognize_text_impl(BASE64.encode(png), language).await
+}
+
+/// Decode `bytes` with the `image` crate and re-encode them as an RGBA PNG
+fn normalize_image(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory(bytes).map_err(|e| format!("Not a supported image: {e}"))?;
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgba8(image.to_rgba8())
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+        .map_err(|e| format!("Failed to encode image for OCR: {e}"))?;
+    Ok(png)
+}
+
+/// Find an installed tag for `wanted`, either exactly or by primary-subtag prefix
+/// (`zh-Hans` matches `zh-Hans-CN`). Comparison is case-insensitive.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn match_language_tag(available: &[String], wanted: &str) -> Option<String> {
+    let w = wanted.trim().to_ascii_lowercase();
+    if w.is_empty() {
+        return None;
+    }
+    let prefix = format!("{w}-");
+    available
+        .iter()
+        .find(|t| t.to_ascii_lowercase() == w)
+        .or_else(|| available.iter().find(|t| t.to_ascii_lowercase().starts_with(&prefix)))
+        .cloned()
+}
+
+/// Score used to compare results from different language engines.
+///
+/// WinRT `OcrResult` exposes no per-word confidence, so the number of
+/// recognized letters/digits serves as a proxy: an engine for the wrong
+/// script tends to drop characters or emit punctuation noise.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn score_text(text: &str) -> usize {
+    text.chars().filter(|c| c.is_alphanumeric()).count()
 }
 
 #[cfg(windows)]
-async fn recognize_text_impl(base64_image: String) -> Result<String, String> {
+async fn recognize_text_impl(base64_image: String, language: Option<String>) -> Result<OcrResult, String> {
     // WinRT async ops in windows 0.58 are easiest to run synchronously via .get().
     // Wrap in spawn_blocking to avoid blocking the async runtime thread.
-    tauri::async_runtime::spawn_blocking(move || recognize_text_sync(base64_image))
+    tauri::async_runtime::spawn_blocking(move || recognize_text_sync(base64_image, language))
         .await
         .map_err(|e| format!("OCR task join failed: {e}"))?
 }
 
 #[cfg(windows)]
-fn recognize_text_sync(base64_image: String) -> Result<String, String> {
-    use windows::Graphics::Imaging::{BitmapAlphaMode, BitmapDecoder, BitmapPixelFormat, SoftwareBitmap};
-    use windows::Media::Ocr::OcrEngine;
-    use windows::Globalization::{ApplicationLanguages, Language};
-    use windows::Storage::Streams::{DataWriter, InMemoryRandomAccessStream};
+async fn available_languages_impl() -> Result<Vec<String>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        init_com()?;
+        available_language_tags()
+    })
+    .await
+    .map_err(|e| format!("OCR task join failed: {e}"))?
+}
+
+#[cfg(windows)]
+fn init_com() -> Result<(), String> {
     use windows::Win32::Foundation::RPC_E_CHANGED_MODE;
     use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
-    use windows::core::HSTRING;
 
     // Best-effort COM initialization for WinRT usage.
     // If the process is already initialized with a different apartment model, ignore.
@@ -36,6 +124,39 @@ fn recognize_text_sync(base64_image: String) -> Result<String, String> {
             return Err(format!("CoInitializeEx failed: {hr:?}"));
         }
     }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn available_language_tags() -> Result<Vec<String>, String> {
+    use windows::Media::Ocr::OcrEngine;
+
+    let langs = OcrEngine::AvailableRecognizerLanguages()
+        .map_err(|e| format!("AvailableRecognizerLanguages failed: {e:?}"))?;
+    let size = langs
+        .Size()
+        .map_err(|e| format!("AvailableRecognizerLanguages.Size failed: {e:?}"))?;
+
+    let mut tags = Vec::with_capacity(size as usize);
+    for i in 0..size {
+        if let Ok(lang) = langs.GetAt(i) {
+            if let Ok(tag) = lang.LanguageTag() {
+                tags.push(tag.to_string());
+            }
+        }
+    }
+    Ok(tags)
+}
+
+#[cfg(windows)]
+fn recognize_text_sync(base64_image: String, language: Option<String>) -> Result<OcrResult, String> {
+    use windows::Graphics::Imaging::{BitmapAlphaMode, BitmapDecoder, BitmapPixelFormat, SoftwareBitmap};
+    use windows::Media::Ocr::OcrEngine;
+    use windows::Globalization::{ApplicationLanguages, Language};
+    use windows::Storage::Streams::{DataWriter, InMemoryRandomAccessStream};
+    use windows::core::HSTRING;
+
+    init_com()?;
 
     // 1) Decode base64
     let b64 = base64_image
@@ -98,7 +219,12 @@ fn recognize_text_sync(base64_image: String) -> Result<String, String> {
             .map_err(|e| format!("SoftwareBitmap::ConvertWithAlpha failed: {e:?}"))?;
     }
 
-    let run_with_engine = |engine: &OcrEngine| -> Result<String, String> {
+    let run_with_tag = |tag: &str| -> Result<String, String> {
+        let lang = Language::CreateLanguage(&HSTRING::from(tag))
+            .map_err(|e| format!("CreateLanguage({tag}) failed: {e:?}"))?;
+        let engine = OcrEngine::TryCreateFromLanguage(&lang)
+            .map_err(|e| format!("TryCreateFromLanguage({tag}) failed: {e:?}"))?;
+
         let result = engine
             .RecognizeAsync(&bitmap)
             .map_err(|e| format!("RecognizeAsync failed: {e:?}"))?
@@ -130,74 +256,161 @@ fn recognize_text_sync(base64_image: String) -> Result<String, String> {
         Ok(out.trim().to_string())
     };
 
-    // 3) Recognize
-    // Strategy:
-    // - First: user profile language engine
-    // - If empty: try common languages (English/Chinese) if available
-    // - Then: try user preferred language tags (ApplicationLanguages)
-    let engine = OcrEngine::TryCreateFromUserProfileLanguages()
-        .map_err(|e| format!("TryCreateFromUserProfileLanguages failed: {e:?}"))?;
-
-    let first = run_with_engine(&engine)?;
-    if !first.is_empty() {
-        return Ok(first);
+    let available_tags = available_language_tags()?;
+    if available_tags.is_empty() {
+        return Err(NO_LANGUAGE_PACK_ERROR.to_string());
     }
 
-    let mut available_tags: Vec<String> = Vec::new();
-    if let Ok(langs) = OcrEngine::AvailableRecognizerLanguages() {
-        if let Ok(size) = langs.Size() {
-            for i in 0..size {
-                if let Ok(lang) = langs.GetAt(i) {
-                    if let Ok(tag) = lang.LanguageTag() {
-                        available_tags.push(tag.to_string());
-                    }
-                }
-            }
-        }
+    // 3a) Explicit language: use it or fail loudly.
+    if let Some(wanted) = language.as_deref().filter(|l| !l.trim().is_empty()) {
+        let actual = match_language_tag(&available_tags, wanted).ok_or_else(|| {
+            format!(
+                "OCR language pack for '{wanted}' is not installed (available: {})",
+                available_tags.join(", ")
+            )
+        })?;
+        let text = run_with_tag(&actual)?;
+        return Ok(OcrResult { text, language: Some(actual) });
     }
 
-    let find_available_tag = |wanted: &str| -> Option<String> {
-        let w = wanted.to_ascii_lowercase();
-        available_tags
-            .iter()
-            .find(|t| t.to_ascii_lowercase() == w)
-            .or_else(|| available_tags.iter().find(|t| t.to_ascii_lowercase().starts_with(&(w.clone() + "-"))))
-            .map(|t| t.to_string())
-    };
-
+    // 3b) Auto-detect: try the user's preferred languages in order and keep the
+    // best-scoring result. Earlier preferences win ties; a language that fails
+    // is skipped, and only an all-round failure is reported.
     let mut candidates: Vec<String> = Vec::new();
-    // Common targets
-    for t in ["en-US", "en", "zh-Hans", "zh-CN", "zh"] {
-        candidates.push(t.to_string());
-    }
-    // Also include user preferred tags
     if let Ok(tags) = ApplicationLanguages::Languages() {
         if let Ok(size) = tags.Size() {
             for i in 0..size {
-                if let Ok(tag) = tags.GetAt(i) {
-                    candidates.push(tag.to_string());
+                let Ok(tag) = tags.GetAt(i) else { continue };
+                if let Some(actual) = match_language_tag(&available_tags, &tag.to_string()) {
+                    if !candidates.contains(&actual) {
+                        candidates.push(actual);
+                    }
                 }
             }
         }
     }
+    if candidates.is_empty() {
+        candidates = available_tags;
+    }
 
-    // Try candidates
-    for wanted in candidates {
-        let Some(actual) = find_available_tag(&wanted) else { continue };
-        let lang = Language::CreateLanguage(&HSTRING::from(actual.clone()))
-            .map_err(|e| format!("CreateLanguage({actual}) failed: {e:?}"))?;
-        let eng = OcrEngine::TryCreateFromLanguage(&lang)
-            .map_err(|e| format!("TryCreateFromLanguage({actual}) failed: {e:?}"))?;
-        let text = run_with_engine(&eng)?;
-        if !text.is_empty() {
-            return Ok(text);
+    let mut best: Option<(usize, OcrResult)> = None;
+    let mut errors: Vec<String> = Vec::new();
+    for tag in candidates {
+        let text = match run_with_tag(&tag) {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::warn!("OCR with language {tag} failed: {e}");
+                errors.push(e);
+                continue;
+            }
+        };
+        let score = score_text(&text);
+        let better = match &best {
+            Some((best_score, _)) => score > *best_score,
+            None => true,
+        };
+        if better {
+            best = Some((score, OcrResult { text, language: Some(tag) }));
         }
     }
 
-    Ok(String::new())
+    best.map(|(_, r)| r).ok_or_else(|| errors.join("; "))
 }
 
 #[cfg(not(windows))]
-async fn recognize_text_impl(_base64_image: String) -> Result<String, String> {
+async fn recognize_text_impl(_base64_image: String, _language: Option<String>) -> Result<OcrResult, String> {
     Err("OCR is only supported on Windows".to_string())
 }
+
+#[cfg(not(windows))]
+async fn available_languages_impl() -> Result<Vec<String>, String> {
+    Ok(Vec::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_match_language_tag() {
+        let available = tags(&["en-US", "zh-Hans-CN"]);
+        assert_eq!(match_language_tag(&available, "en-us"), Some("en-US".to_string()));
+        assert_eq!(match_language_tag(&available, "zh-Hans"), Some("zh-Hans-CN".to_string()));
+        assert_eq!(match_language_tag(&available, "zh"), Some("zh-Hans-CN".to_string()));
+        assert_eq!(match_language_tag(&available, "ja"), None);
+        assert_eq!(match_language_tag(&available, ""), None);
+    }
+
+    /// Black text on white, drawn with the letter-tile bitmap font
+    fn rendered_text(text: &str) -> Vec<u8> {
+        const SCALE: u32 = 6;
+        let width = (text.len() as u32 * 6 + 4) * SCALE;
+        let mut img = image::RgbImage::from_pixel(width, 11 * SCALE, image::Rgb([255, 255, 255]));
+        for (i, c) in text.chars().enumerate() {
+            if c == ' ' {
+                continue;
+            }
+            let rows = crate::storage::icon_tile::glyph_rows(c);
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..5u32 {
+                    if bits & (1 << (4 - col)) == 0 {
+                        continue;
+                    }
+                    let x0 = (2 + i as u32 * 6 + col) * SCALE;
+                    let y0 = (2 + row as u32) * SCALE;
+                    for dy in 0..SCALE {
+                        for dx in 0..SCALE {
+                            img.put_pixel(x0 + dx, y0 + dy, image::Rgb([0, 0, 0]));
+                        }
+                    }
+                }
+            }
+        }
+        let mut out = Vec::new();
+        image::DynamicImage::ImageRgb8(img)
+            .write_to(&mut std::io::Cursor::new(&mut out), image::ImageOutputFormat::Bmp)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn test_normalize_image_converts_to_rgba_png() {
+        let bmp = rendered_text("HELLO");
+        let png = normalize_image(&bmp).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+        let decoded = image::load_from_memory(&png).unwrap();
+        assert_eq!(decoded.color(), image::ColorType::Rgba8);
+        assert_eq!(decoded.width(), 34 * 6);
+    }
+
+    #[test]
+    fn test_non_image_bytes_are_rejected() {
+        let err = normalize_image(b"plain clipboard text").unwrap_err();
+        assert!(err.starts_with("Not a supported image"), "{err}");
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_recognize_image_bytes_unsupported_off_windows() {
+        let err = recognize_image_bytes(rendered_text("HELLO"), None).await.unwrap_err();
+        assert_eq!(err, "OCR is only supported on Windows");
+    }
+
+    #[cfg(windows)]
+    #[tokio::test]
+    #[ignore = "needs an installed OCR language pack"]
+    async fn test_recognize_rendered_text() {
+        let result = recognize_image_bytes(rendered_text("HELLO 42"), Some("en".to_string())).await.unwrap();
+        assert!(result.text.to_uppercase().contains("HELLO"), "{}", result.text);
+    }
+
+    #[test]
+    fn test_score_text_prefers_recognized_characters() {
+        assert!(score_text("你好 world") > score_text("?? world"));
+        assert_eq!(score_text("  ,.;  "), 0);
+    }
+}
@@ -201,6 +201,43 @@ pub async fn launch_app(app_path: &std::path::Path) -> Result<(), String> {
     }
 }
 
+/// Apps Launch Services offers for opening `path`, for an "Open with…" menu
+pub async fn open_with_candidates(path: &Path) -> Vec<crate::core::open_with::OpenWithApp> {
+    // Prints one "name<TAB>bundle path" line per app
+    const SCRIPT: &str = r#"
+ObjC.import('AppKit');
+function run(argv) {
+  const url = $.NSURL.fileURLWithPath(argv[0]);
+  const apps = $.NSWorkspace.sharedWorkspace.URLsForApplicationsToOpenURL(url);
+  const lines = [];
+  for (let i = 0; i < apps.count; i++) {
+    const path = apps.objectAtIndex(i).path;
+    const name = $.NSFileManager.defaultManager.displayNameAtPath(path);
+    lines.push(ObjC.unwrap(name) + '\t' + ObjC.unwrap(path));
+  }
+  return lines.join('\n');
+}"#;
+
+    match Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", SCRIPT])
+        .arg(path)
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => {
+            crate::core::open_with::parse_app_list(&String::from_utf8_lossy(&output.stdout))
+        }
+        Ok(output) => {
+            tracing::warn!("Listing apps for {:?} failed: {}", path, String::from_utf8_lossy(&output.stderr));
+            Vec::new()
+        }
+        Err(e) => {
+            tracing::warn!("Listing apps for {:?} failed: {}", path, e);
+            Vec::new()
+        }
+    }
+}
+
 /// Application info with localized display name
 #[derive(Debug, Clone)]
 pub struct AppDisplayInfo {
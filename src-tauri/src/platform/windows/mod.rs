@@ -3,6 +3,50 @@ pub mod apps;
 
 pub use apps::{AppScanner, AppInfo};
 
+use crate::app::error::{AppError, AppResult};
+use crate::core::elevation::{ShellExecuteRequest, RUNAS_VERB};
+
+/// Run a ShellExecute request. A declined UAC prompt maps to
+/// [`AppError::Cancelled`].
+pub fn shell_execute(request: &ShellExecuteRequest) -> AppResult<()> {
+    use std::ffi::OsStr;
+    use std::iter;
+    use std::mem::size_of;
+    use std::os::windows::ffi::OsStrExt;
+
+    use windows::core::{HRESULT, PCWSTR};
+    use windows::Win32::Foundation::ERROR_CANCELLED;
+    use windows::Win32::UI::Shell::{ShellExecuteExW, SEE_MASK_NOASYNC, SHELLEXECUTEINFOW};
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let wide = |s: &str| -> Vec<u16> { OsStr::new(s).encode_wide().chain(iter::once(0)).collect() };
+    let verb = wide(&request.verb);
+    let file = wide(&request.file);
+    let parameters = request.parameters.as_deref().map(wide);
+    let directory = request.directory.as_deref().map(wide);
+    let ptr = |s: &Option<Vec<u16>>| s.as_ref().map_or(PCWSTR::null(), |s| PCWSTR(s.as_ptr()));
+
+    let mut info = SHELLEXECUTEINFOW {
+        cbSize: size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: SEE_MASK_NOASYNC,
+        lpVerb: PCWSTR(verb.as_ptr()),
+        lpFile: PCWSTR(file.as_ptr()),
+        lpParameters: ptr(&parameters),
+        lpDirectory: ptr(&directory),
+        nShow: SW_SHOWNORMAL.0,
+        ..Default::default()
+    };
+
+    match unsafe { ShellExecuteExW(&mut info) } {
+        Ok(()) => Ok(()),
+        Err(e) if e.code() == HRESULT::from_win32(ERROR_CANCELLED.0) => {
+            let what = if request.verb == RUNAS_VERB { "Elevation" } else { "Launch" };
+            Err(AppError::Cancelled(format!("{} of {} was cancelled", what, request.file)))
+        }
+        Err(e) => Err(AppError::Shell(format!("Failed to launch {}: {}", request.file, e))),
+    }
+}
+
 /// Extract icon from Windows executable or shortcut
 pub async fn extract_app_icon(app_path: &std::path::Path) -> Option<Vec<u8>> {
     let path = app_path.to_path_buf();
@@ -27,26 +71,25 @@ pub async fn extract_file_icon(path: &std::path::Path) -> Option<Vec<u8>> {
         .flatten()
 }
 
-fn extract_file_icon_sync(path: &std::path::Path) -> Option<Vec<u8>> {
-    use std::ffi::OsStr;
-    use std::iter;
-    use std::mem::size_of;
-    use std::os::windows::ffi::OsStrExt;
+/// The generic icon Explorer shows for files with extension `ext`, i.e. the
+/// icon of a file that has no icon of its own.
+pub async fn extract_extension_icon(ext: &str) -> Option<Vec<u8>> {
+    let name = std::path::PathBuf::from(format!("file.{}", ext));
+
+    tokio::task::spawn_blocking(move || {
+        use windows::Win32::Storage::FileSystem::{FILE_ATTRIBUTE_NORMAL, FILE_FLAGS_AND_ATTRIBUTES};
+        // SHGFI_USEFILEATTRIBUTES: the file need not exist, only its extension counts
+        shell_file_icon(&name, FILE_FLAGS_AND_ATTRIBUTES(FILE_ATTRIBUTE_NORMAL.0), true)
+    })
+    .await
+    .ok()
+    .flatten()
+}
 
-    use windows::core::PCWSTR;
+fn extract_file_icon_sync(path: &std::path::Path) -> Option<Vec<u8>> {
     use windows::Win32::Storage::FileSystem::{
         FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_NORMAL, FILE_FLAGS_AND_ATTRIBUTES,
     };
-    use windows::Win32::UI::Shell::{
-        SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON, SHGFI_SMALLICON,
-        SHGFI_USEFILEATTRIBUTES,
-    };
-    use windows::Win32::UI::WindowsAndMessaging::DestroyIcon;
-
-    let wide: Vec<u16> = OsStr::new(path.as_os_str())
-        .encode_wide()
-        .chain(iter::once(0))
-        .collect();
 
     // If the path exists, let Shell decide attributes.
     // If it doesn't (rare for Everything results), fall back to using attributes.
@@ -69,6 +112,32 @@ fn extract_file_icon_sync(path: &std::path::Path) -> Option<Vec<u8>> {
         }
     };
 
+    shell_file_icon(path, attr, use_attrs)
+}
+
+/// SHGetFileInfo icon for `path` as PNG, large size with a small-size fallback
+fn shell_file_icon(
+    path: &std::path::Path,
+    attr: windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES,
+    use_attrs: bool,
+) -> Option<Vec<u8>> {
+    use std::ffi::OsStr;
+    use std::iter;
+    use std::mem::size_of;
+    use std::os::windows::ffi::OsStrExt;
+
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::Shell::{
+        SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON, SHGFI_SMALLICON,
+        SHGFI_USEFILEATTRIBUTES,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::DestroyIcon;
+
+    let wide: Vec<u16> = OsStr::new(path.as_os_str())
+        .encode_wide()
+        .chain(iter::once(0))
+        .collect();
+
     unsafe {
         let mut info = SHFILEINFOW::default();
         let mut flags_large = SHGFI_ICON | SHGFI_LARGEICON;
@@ -354,35 +423,144 @@ unsafe fn hicon_to_png(hicon: windows::Win32::UI::WindowsAndMessaging::HICON) ->
     Some(out)
 }
 
-/// Launch an application
+/// Launch an application.
+///
+/// Resolves as soon as the process has been created: a successful spawn is
+/// a successful launch, and the app keeps running on its own.
 pub async fn launch_app(app_path: &std::path::Path) -> Result<(), String> {
-    use tokio::process::Command;
-
-    // For .lnk files, use cmd /c start
-    // For .exe files, can run directly
-    if let Some(ext) = app_path.extension() {
-        if ext == "lnk" {
-            let output = Command::new("cmd")
-                .args(&["/c", "start", "", app_path.to_string_lossy().as_ref()])
-                .output()
+    let ext = app_path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        // Shortcuts go through the shell so their target, arguments and
+        // working directory apply; unlike `cmd /c start` no console flashes
+        "lnk" => {
+            let request = crate::core::elevation::shell_open(&app_path.to_string_lossy());
+            tokio::task::spawn_blocking(move || shell_execute(&request))
                 .await
-                .map_err(|e| format!("Failed to launch app: {}", e))?;
-
-            if output.status.success() {
-                return Ok(());
+                .map_err(|e| format!("Failed to launch app: {}", e))?
+                .map_err(|e| e.to_string())
+        }
+        "exe" => {
+            let mut command = std::process::Command::new(app_path);
+            if let Some(dir) = app_path.parent() {
+                command.current_dir(dir);
             }
-        } else if ext == "exe" {
-            let output = Command::new(app_path)
-                .output()
-                .await
-                .map_err(|e| format!("Failed to launch app: {}", e))?;
+            spawn_detached(command)
+        }
+        _ => Err("Unsupported file type".to_string()),
+    }
+}
+
+/// Apps Windows recommends for the file type of `path`, for an "Open with…"
+/// menu. Files without an extension have none.
+pub async fn open_with_candidates(path: &std::path::Path) -> Vec<crate::core::open_with::OpenWithApp> {
+    let Some(ext) = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())) else {
+        return Vec::new();
+    };
+
+    tokio::task::spawn_blocking(move || {
+        use std::ffi::OsStr;
+        use std::os::windows::ffi::OsStrExt;
 
-            if output.status.success() {
-                return Ok(());
+        use windows::core::{PCWSTR, PWSTR};
+        use windows::Win32::System::Com::CoTaskMemFree;
+        use windows::Win32::UI::Shell::{SHAssocEnumHandlers, ASSOC_FILTER_RECOMMENDED};
+
+        use crate::core::open_with::OpenWithApp;
+
+        // Strings from the handler are the caller's to free
+        let take = |text: PWSTR| -> String {
+            let owned = unsafe { text.to_string() }.unwrap_or_default();
+            unsafe { CoTaskMemFree(Some(text.0 as *const _)) };
+            owned
+        };
+        let ext: Vec<u16> = OsStr::new(&ext).encode_wide().chain(std::iter::once(0)).collect();
+
+        let mut apps: Vec<OpenWithApp> = Vec::new();
+        // Declared first so the handlers are released before COM is
+        let _com = ComGuard::init();
+        unsafe {
+            let Ok(handlers) = SHAssocEnumHandlers(PCWSTR(ext.as_ptr()), ASSOC_FILTER_RECOMMENDED) else {
+                return apps;
+            };
+            loop {
+                let mut slot = [None];
+                let mut fetched = 0;
+                if handlers.Next(&mut slot, Some(&mut fetched as *mut u32)).is_err() || fetched == 0 {
+                    break;
+                }
+                let Some(handler) = slot[0].take() else {
+                    break;
+                };
+                let (Ok(path), Ok(name)) = (handler.GetName(), handler.GetUIName()) else {
+                    continue;
+                };
+                let (path, name) = (take(path), take(name));
+                if !path.is_empty() && !apps.iter().any(|app| app.path.eq_ignore_ascii_case(&path)) {
+                    apps.push(OpenWithApp { name, path });
+                }
             }
         }
+        apps
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Pairs a successful `CoInitializeEx` with `CoUninitialize`, so pool
+/// threads do not keep COM initialized after the task that needed it
+struct ComGuard;
+
+impl ComGuard {
+    /// `None` when COM is already initialized differently on this thread;
+    /// it can still be used then, but must not be uninitialized
+    fn init() -> Option<Self> {
+        use windows::Win32::System::Com::{CoInitializeEx, COINIT_APARTMENTTHREADED};
+        unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) }.is_ok().then_some(ComGuard)
     }
+}
 
-    Err("Unsupported file type".to_string())
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        unsafe { windows::Win32::System::Com::CoUninitialize() };
+    }
+}
+
+/// Start `command` with no inherited stdio, without waiting for it to exit
+fn spawn_detached(mut command: std::process::Command) -> Result<(), String> {
+    use std::process::Stdio;
+
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to launch app: {}", e))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_spawn_detached_returns_before_exit() {
+        // Runs for about five seconds
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/c", "ping -n 6 127.0.0.1 > nul"]);
+
+        let started = Instant::now();
+        spawn_detached(command).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_launch_app_rejects_unknown_types() {
+        let result = launch_app(std::path::Path::new(r"C:\Users\me\notes.txt")).await;
+        assert_eq!(result, Err("Unsupported file type".to_string()));
+    }
+}
@@ -1,12 +1,81 @@
 // Icon cache module
 use crate::app::error::{AppError, AppResult};
+use crate::core::search_cache::CacheStats;
 use base64::Engine;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::fs;
+use tokio::sync::Semaphore;
+
+/// Icons extracted at once by [`IconCache::warm`]
+const WARM_CONCURRENCY: usize = 4;
+
+/// Icons extracted at once by [`IconCache::data_urls`]. Each extraction
+/// holds a few GDI handles on Windows, so keep this small.
+const BATCH_CONCURRENCY: usize = 8;
+
+/// How often [`IconCache::start_eviction`] enforces the size cap
+const EVICTION_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Sidecar file recording when each icon was last read
+const ACCESS_INDEX_FILE: &str = "access.json";
+
+/// Sidecar file listing extensions seen with a custom icon
+const CUSTOM_EXTENSIONS_FILE: &str = "custom_extensions.json";
+
+/// Extensions whose files usually carry their own icon; these are always
+/// cached per path
+const CUSTOM_ICON_EXTENSIONS: &[&str] = &["exe", "lnk", "ico", "url", "cur", "ani", "scr", "msc", "appref-ms"];
 
 /// Icon cache manager
 pub struct IconCache {
     cache_dir: PathBuf,
+    /// File icon lookups served from / missing from the cache
+    hits: AtomicU64,
+    misses: AtomicU64,
+    /// Extensions seen with a file whose icon differs from the generic one;
+    /// their files are no longer served the extension-keyed icon
+    custom_extensions: Mutex<HashSet<String>>,
+    /// Cache directory budget in bytes; 0 means unlimited
+    max_bytes: AtomicU64,
+    access: Mutex<AccessIndex>,
+    /// Icon files being written right now, which eviction must not touch
+    writing: Mutex<HashSet<PathBuf>>,
+}
+
+/// Least-recently-read order of icon files. A logical clock instead of
+/// wall time keeps the order exact for reads in quick succession.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AccessIndex {
+    clock: u64,
+    /// Icon file name -> clock value of its last read or write
+    entries: HashMap<String, u64>,
+}
+
+impl AccessIndex {
+    fn touch(&mut self, file: &Path) {
+        if let Some(name) = file.file_name().and_then(|n| n.to_str()) {
+            self.clock += 1;
+            self.entries.insert(name.to_string(), self.clock);
+        }
+    }
+}
+
+/// Removes a path from [`IconCache::writing`] when the write ends, even on error
+struct WriteGuard<'a> {
+    writing: &'a Mutex<HashSet<PathBuf>>,
+    path: PathBuf,
+}
+
+impl Drop for WriteGuard<'_> {
+    fn drop(&mut self) {
+        self.writing.lock().remove(&self.path);
+    }
 }
 
 impl IconCache {
@@ -15,16 +84,308 @@ impl IconCache {
         // Ensure cache directory exists
         fs::create_dir_all(&cache_dir).await?;
 
-        Ok(Self { cache_dir })
+        let access = match fs::read(cache_dir.join(ACCESS_INDEX_FILE)).await {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_default(),
+            Err(_) => AccessIndex::default(),
+        };
+        let custom_extensions = match fs::read(cache_dir.join(CUSTOM_EXTENSIONS_FILE)).await {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_default(),
+            Err(_) => HashSet::new(),
+        };
+
+        Ok(Self {
+            cache_dir,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            custom_extensions: Mutex::new(custom_extensions),
+            max_bytes: AtomicU64::new(0),
+            access: Mutex::new(access),
+            writing: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Set the cache directory budget enforced by [`Self::evict`]; 0 disables it
+    pub fn set_max_bytes(&self, max_bytes: u64) {
+        self.max_bytes.store(max_bytes, Ordering::Relaxed);
+    }
+
+    /// Evict once now, then every [`EVICTION_INTERVAL`]
+    pub fn start_eviction(cache: Arc<Self>) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(EVICTION_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = cache.evict().await {
+                    tracing::warn!("Icon cache eviction failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Delete the least recently read icons until the directory fits the
+    /// budget. Icons being written are skipped. Returns the number deleted.
+    pub async fn evict(&self) -> AppResult<usize> {
+        let max_bytes = self.max_bytes.load(Ordering::Relaxed);
+        if max_bytes == 0 {
+            return Ok(0);
+        }
+
+        let mut icons = self.icon_files().await?;
+        let mut total: u64 = icons.iter().map(|(_, size)| size).sum();
+        if total <= max_bytes {
+            return Ok(0);
+        }
+
+        // Icons never read since the index existed sort first
+        {
+            let access = self.access.lock();
+            icons.sort_by_key(|(path, _)| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(|n| access.entries.get(n).copied())
+                    .unwrap_or(0)
+            });
+        }
+
+        let mut deleted = 0;
+        for (path, size) in icons {
+            if total <= max_bytes {
+                break;
+            }
+            if self.writing.lock().contains(&path) {
+                continue;
+            }
+            if fs::remove_file(&path).await.is_ok() {
+                self.forget(&path);
+                total = total.saturating_sub(size);
+                deleted += 1;
+            }
+        }
+
+        self.save_access_index().await?;
+        tracing::debug!("Evicted {} icons, cache now {} bytes", deleted, total);
+        Ok(deleted)
+    }
+
+    /// Delete every cached icon except ones being written. Returns the
+    /// number deleted.
+    pub async fn clear(&self) -> AppResult<usize> {
+        let mut deleted = 0;
+        for (path, _) in self.icon_files().await? {
+            if self.writing.lock().contains(&path) {
+                continue;
+            }
+            if fs::remove_file(&path).await.is_ok() {
+                self.forget(&path);
+                deleted += 1;
+            }
+        }
+        self.save_access_index().await?;
+        Ok(deleted)
+    }
+
+    /// Write and remove a probe file, failing when icons could not be saved
+    pub async fn check_writable(&self) -> AppResult<()> {
+        let probe = self.cache_dir.join(".write-test");
+        fs::write(&probe, b"ok").await?;
+        fs::remove_file(&probe).await?;
+        Ok(())
+    }
+
+    /// Cached icon files with their sizes
+    async fn icon_files(&self) -> AppResult<Vec<(PathBuf, u64)>> {
+        let mut icons = Vec::new();
+        let mut entries = fs::read_dir(&self.cache_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("png") {
+                continue;
+            }
+            if let Ok(metadata) = entry.metadata().await {
+                icons.push((path, metadata.len()));
+            }
+        }
+        Ok(icons)
+    }
+
+    fn forget(&self, path: &Path) {
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            self.access.lock().entries.remove(name);
+        }
+    }
+
+    async fn save_access_index(&self) -> AppResult<()> {
+        let data = serde_json::to_vec(&*self.access.lock())?;
+        fs::write(self.cache_dir.join(ACCESS_INDEX_FILE), data).await?;
+        Ok(())
+    }
+
+    async fn save_custom_extensions(&self) -> AppResult<()> {
+        let data = serde_json::to_vec(&*self.custom_extensions.lock())?;
+        fs::write(self.cache_dir.join(CUSTOM_EXTENSIONS_FILE), data).await?;
+        Ok(())
+    }
+
+    /// Hit/miss counts for [`Self::file_icon`]
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Get (or extract and cache) the Explorer icon for a file or folder as
+    /// Base64 PNG.
+    ///
+    /// A file showing the generic icon of its extension is cached once under
+    /// the extension, so every `.txt` file shares one entry. Files with an
+    /// icon of their own are cached per path.
+    pub async fn file_icon(&self, path: &Path) -> Option<String> {
+        let extension = self.shared_extension(path);
+        let mut keys = vec![path.to_path_buf()];
+        keys.extend(extension.as_deref().map(extension_key));
+        for key in &keys {
+            if let Some(cached) = self.get_icon(key).await {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Some(cached);
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let icon_data = extract_file_icon(path).await?;
+        let generic = match extension.as_deref() {
+            Some(ext) => self.extension_icon(ext).await,
+            None => None,
+        };
+        if let Err(e) = self
+            .store_file_icon(path, extension.as_deref(), &icon_data, generic.as_deref())
+            .await
+        {
+            tracing::debug!("Failed to cache icon for {:?}: {}", path, e);
+        }
+        Some(base64::engine::general_purpose::STANDARD.encode(&icon_data))
+    }
+
+    /// Cache a freshly extracted file icon: under the extension when it is
+    /// that extension's generic icon, otherwise under the path
+    async fn store_file_icon(
+        &self,
+        path: &Path,
+        extension: Option<&str>,
+        icon_data: &[u8],
+        generic: Option<&[u8]>,
+    ) -> AppResult<()> {
+        match extension {
+            Some(ext) if generic == Some(icon_data) => self.cache_icon(&extension_key(ext), icon_data).await,
+            Some(ext) => {
+                let added = self.custom_extensions.lock().insert(ext.to_string());
+                if added {
+                    self.save_custom_extensions().await?;
+                }
+                self.cache_icon(path, icon_data).await
+            }
+            None => self.cache_icon(path, icon_data).await,
+        }
+    }
+
+    /// The generic icon for `ext`, extracted once and kept under its key
+    async fn extension_icon(&self, ext: &str) -> Option<Vec<u8>> {
+        let key = extension_key(ext);
+        if let Some(data) = self.read_icon(&key).await {
+            return Some(data);
+        }
+        let data = extract_extension_icon(ext).await?;
+        let _ = self.cache_icon(&key, &data).await;
+        Some(data)
+    }
+
+    /// Lowercase extension of `path` when its icon may be the shared
+    /// extension icon; `None` for folders and files that carry their own
+    fn shared_extension(&self, path: &Path) -> Option<String> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        if CUSTOM_ICON_EXTENSIONS.contains(&ext.as_str())
+            || self.custom_extensions.lock().contains(&ext)
+            || path.is_dir()
+        {
+            return None;
+        }
+        Some(ext)
+    }
+
+    /// Extract and cache file icons for `paths` ahead of display, a few at a
+    /// time. Paths that share an extension icon are extracted once.
+    pub async fn warm(&self, paths: Vec<PathBuf>) {
+        let started = Instant::now();
+        let mut seen = HashSet::new();
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .filter(|path| {
+                let key = match self.shared_extension(path) {
+                    Some(ext) => extension_key(&ext),
+                    None => path.to_path_buf(),
+                };
+                seen.insert(key)
+            })
+            .collect();
+
+        let semaphore = Semaphore::new(WARM_CONCURRENCY);
+        futures_util::future::join_all(paths.iter().map(|path| async {
+            let _permit = semaphore.acquire().await;
+            self.file_icon(path).await;
+        }))
+        .await;
+
+        tracing::debug!(
+            "Warmed {} icons in {:?} (hit rate {:.0}%)",
+            paths.len(),
+            started.elapsed(),
+            self.stats().hit_rate() * 100.0
+        );
+    }
+
+    /// File icons for `paths` as `data:` URLs, keyed by the path as given.
+    /// Missing paths and icons that cannot be extracted map to `None`.
+    /// Cached icons are served directly; the rest are extracted a few at a
+    /// time.
+    pub async fn data_urls(&self, paths: Vec<String>) -> HashMap<String, Option<String>> {
+        let started = Instant::now();
+        let semaphore = Semaphore::new(BATCH_CONCURRENCY);
+        let icons: HashMap<String, Option<String>> =
+            futures_util::future::join_all(paths.into_iter().collect::<HashSet<_>>().into_iter().map(|path| {
+                let semaphore = &semaphore;
+                async move {
+                    let file = Path::new(&path);
+                    let icon = if fs::try_exists(file).await.unwrap_or(false) {
+                        let _permit = semaphore.acquire().await;
+                        self.file_icon(file).await.map(|data| format!("data:image/png;base64,{}", data))
+                    } else {
+                        None
+                    };
+                    (path, icon)
+                }
+            }))
+            .await
+            .into_iter()
+            .collect();
+
+        tracing::debug!("Resolved {} icons in {:?}", icons.len(), started.elapsed());
+        icons
     }
 
     /// Get cached icon as Base64 string
     pub async fn get_icon(&self, app_path: &Path) -> Option<String> {
+        self.read_icon(app_path)
+            .await
+            .map(|data| base64::engine::general_purpose::STANDARD.encode(&data))
+    }
+
+    async fn read_icon(&self, app_path: &Path) -> Option<Vec<u8>> {
         let cache_path = self.get_cache_path(app_path);
 
         if cache_path.exists() {
             if let Ok(data) = fs::read(&cache_path).await {
-                return Some(base64::engine::general_purpose::STANDARD.encode(&data));
+                self.access.lock().touch(&cache_path);
+                return Some(data);
             }
         }
 
@@ -40,8 +401,18 @@ impl IconCache {
             fs::create_dir_all(parent).await?;
         }
 
-        // Write icon data to cache
-        fs::write(&cache_path, icon_data).await?;
+        // Write icon data to cache. Readers never see a partial file: the
+        // data goes to a temporary name first, and eviction skips the path
+        // until the write is done.
+        self.writing.lock().insert(cache_path.clone());
+        let _guard = WriteGuard {
+            writing: &self.writing,
+            path: cache_path.clone(),
+        };
+        let temp_path = cache_path.with_extension("png.tmp");
+        fs::write(&temp_path, icon_data).await?;
+        fs::rename(&temp_path, &cache_path).await?;
+        self.access.lock().touch(&cache_path);
 
         Ok(())
     }
@@ -80,6 +451,20 @@ impl IconCache {
         Ok(cleared)
     }
 
+    /// Get (or render and cache) a letter tile for `name` as Base64 PNG
+    pub async fn letter_tile(&self, name: &str) -> AppResult<String> {
+        // Tiles depend only on the name, so key them in their own namespace
+        let key = PathBuf::from(format!("letter-tile:{}", name.trim().to_lowercase()));
+
+        if let Some(cached) = self.get_icon(&key).await {
+            return Ok(cached);
+        }
+
+        let png = super::icon_tile::render_tile(name)?;
+        self.cache_icon(&key, &png).await?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(&png))
+    }
+
     /// Get cache path for an app
     fn get_cache_path(&self, app_path: &Path) -> PathBuf {
         // Create a hash of the app path for the cache filename
@@ -132,4 +517,187 @@ impl IconCache {
     }
 }
 
-// Re-export base64 encode/decode (we need to add base64 and md5 to Cargo.toml)
+/// Cache key of the generic icon for extension `ext`
+fn extension_key(ext: &str) -> PathBuf {
+    PathBuf::from(format!("file-ext:{}", ext))
+}
+
+#[cfg(target_os = "windows")]
+async fn extract_file_icon(path: &Path) -> Option<Vec<u8>> {
+    // Runs the Win32 extraction on a blocking thread
+    crate::platform::windows::extract_file_icon(path).await
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn extract_file_icon(_path: &Path) -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(target_os = "windows")]
+async fn extract_extension_icon(ext: &str) -> Option<Vec<u8>> {
+    crate::platform::windows::extract_extension_icon(ext).await
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn extract_extension_icon(_ext: &str) -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn temp_cache() -> (IconCache, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("omnibox_icon_cache_{}", uuid::Uuid::new_v4()));
+        (IconCache::new(dir.clone()).await.unwrap(), dir)
+    }
+
+    fn cached_files(dir: &Path) -> usize {
+        std::fs::read_dir(dir).unwrap().count()
+    }
+
+    #[tokio::test]
+    async fn test_generic_txt_files_share_one_blob() {
+        let (cache, dir) = temp_cache().await;
+        let generic = b"generic txt png".to_vec();
+        let first = Path::new("C:\\notes\\one.txt");
+        cache.store_file_icon(first, Some("txt"), &generic, Some(&generic)).await.unwrap();
+
+        let second = Path::new("D:\\other\\two.TXT");
+        let expected = base64::engine::general_purpose::STANDARD.encode(&generic);
+        assert_eq!(cache.file_icon(first).await, Some(expected.clone()));
+        assert_eq!(cache.file_icon(second).await, Some(expected));
+        assert_eq!(cached_files(&dir), 1);
+        assert_eq!(cache.stats().hits, 2);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_custom_icons_stay_per_path() {
+        let (cache, dir) = temp_cache().await;
+        let custom = Path::new("C:\\Tools\\setup.cpl");
+        cache
+            .store_file_icon(custom, Some("cpl"), b"custom png", Some(b"generic png"))
+            .await
+            .unwrap();
+
+        assert!(cache.file_icon(custom).await.is_some());
+        // The extension now counts as custom, so other .cpl files are keyed
+        // by their own path
+        assert_eq!(cache.shared_extension(Path::new("other.cpl")), None);
+
+        // and stays custom after a restart
+        let reopened = IconCache::new(dir.clone()).await.unwrap();
+        assert_eq!(reopened.shared_extension(Path::new("other.cpl")), None);
+        assert_eq!(reopened.shared_extension(Path::new("notes.txt")).as_deref(), Some("txt"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_executables_are_never_shared() {
+        let (cache, dir) = temp_cache().await;
+        assert_eq!(cache.shared_extension(Path::new("C:\\Tools\\tool.exe")), None);
+        assert_eq!(cache.shared_extension(Path::new("C:\\Tools\\Makefile")), None);
+        assert_eq!(cache.shared_extension(Path::new("a.PDF")).as_deref(), Some("pdf"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_evict_removes_least_recently_read_icons() {
+        let (cache, dir) = temp_cache().await;
+        for i in 0..6 {
+            cache.cache_icon(Path::new(&format!("icon-{}", i)), &[0u8; 100]).await.unwrap();
+        }
+        // Reading icon-0 makes it the most recently used
+        assert!(cache.get_icon(Path::new("icon-0")).await.is_some());
+
+        cache.set_max_bytes(350);
+        assert_eq!(cache.evict().await.unwrap(), 3);
+
+        for gone in ["icon-1", "icon-2", "icon-3"] {
+            assert!(cache.get_icon(Path::new(gone)).await.is_none(), "{} survived", gone);
+        }
+        for kept in ["icon-0", "icon-4", "icon-5"] {
+            assert!(cache.get_icon(Path::new(kept)).await.is_some(), "{} was evicted", kept);
+        }
+
+        // The order survives a restart through the sidecar index
+        let reopened = IconCache::new(dir.clone()).await.unwrap();
+        reopened.set_max_bytes(150);
+        assert!(reopened.get_icon(Path::new("icon-5")).await.is_some());
+        assert_eq!(reopened.evict().await.unwrap(), 2);
+        assert!(reopened.get_icon(Path::new("icon-5")).await.is_some());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_evict_skips_icons_being_written() {
+        let (cache, dir) = temp_cache().await;
+        cache.cache_icon(Path::new("old"), &[0u8; 100]).await.unwrap();
+        cache.cache_icon(Path::new("new"), &[0u8; 100]).await.unwrap();
+        cache.writing.lock().insert(cache.get_cache_path(Path::new("old")));
+
+        cache.set_max_bytes(100);
+        assert_eq!(cache.evict().await.unwrap(), 1);
+        assert!(cache.get_icon(Path::new("old")).await.is_some());
+        assert!(cache.get_icon(Path::new("new")).await.is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_clear_deletes_every_icon() {
+        let (cache, dir) = temp_cache().await;
+        cache.cache_icon(Path::new("a"), b"png").await.unwrap();
+        cache.cache_icon(Path::new("b"), b"png").await.unwrap();
+        assert_eq!(cache.clear().await.unwrap(), 2);
+        assert!(cache.get_icon(Path::new("a")).await.is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_warm_counts_each_extension_once() {
+        let (cache, dir) = temp_cache().await;
+        cache.cache_icon(&extension_key("txt"), b"png").await.unwrap();
+
+        cache
+            .warm(vec![PathBuf::from("a.txt"), PathBuf::from("b.txt"), PathBuf::from("c.TXT")])
+            .await;
+
+        assert_eq!(cache.stats().hits, 1);
+        assert_eq!(cache.stats().misses, 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_data_urls_mixes_existing_and_missing_paths() {
+        let (cache, dir) = temp_cache().await;
+        cache.cache_icon(&extension_key("txt"), b"png").await.unwrap();
+        let files = dir.join("files");
+        std::fs::create_dir_all(&files).unwrap();
+        let existing = files.join("notes.txt");
+        std::fs::write(&existing, "hello").unwrap();
+        let existing = existing.to_string_lossy().to_string();
+        let missing = files.join("gone.txt").to_string_lossy().to_string();
+
+        let icons = cache.data_urls(vec![existing.clone(), missing.clone(), existing.clone()]).await;
+
+        assert_eq!(icons.len(), 2);
+        let expected = format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(b"png"));
+        assert_eq!(icons[&existing], Some(expected));
+        // Never extracted, even though the extension icon is cached
+        assert_eq!(icons[&missing], None);
+        assert_eq!(cache.stats().hits, 1);
+
+        // A second batch is served from the cache
+        let again = cache.data_urls(vec![existing.clone()]).await;
+        assert!(again[&existing].is_some());
+        assert_eq!(cache.stats().hits, 2);
+        assert_eq!(cache.stats().misses, 0);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,6 +1,9 @@
 // Database module for SQLite operations
 use crate::app::error::{AppError, AppResult};
+use crate::core::palette::PaletteUsage;
+use crate::core::snippet::Snippet;
 use chrono::Utc;
+use std::collections::HashMap;
 use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
 use std::path::Path;
 
@@ -33,6 +36,15 @@ impl Database {
         Ok(db)
     }
 
+    /// Run a trivial query, failing when the database cannot be reached
+    pub async fn ping(&self) -> AppResult<()> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
     /// Run database migrations to create tables
     async fn run_migrations(&self) -> AppResult<()> {
         // File index table
@@ -139,6 +151,35 @@ impl Database {
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+        // Snippets table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS snippets (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                keyword TEXT NOT NULL UNIQUE,
+                expansion TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // Command palette history
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS palette_usage (
+                command_id TEXT PRIMARY KEY,
+                use_count INTEGER NOT NULL,
+                last_used INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
         Ok(())
     }
 
@@ -264,6 +305,24 @@ impl Database {
         Ok(())
     }
 
+    /// Most recently launched paths, newest first
+    pub async fn recent_launches(&self, limit: i64) -> AppResult<Vec<AppUsage>> {
+        let entries = sqlx::query_as::<_, AppUsage>(
+            r#"
+            SELECT app_path, app_name, launch_count, last_launched_at
+            FROM app_usage
+            WHERE last_launched_at IS NOT NULL
+            ORDER BY last_launched_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(entries)
+    }
+
     /// Add search history entry
     pub async fn add_search_history(
         &self,
@@ -287,6 +346,91 @@ impl Database {
         .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
+
+    /// Add a snippet, replacing the text of an existing one with the same keyword
+    pub async fn add_snippet(&self, keyword: &str, expansion: &str) -> AppResult<Snippet> {
+        let now = Utc::now().timestamp();
+        let snippet = sqlx::query_as::<_, Snippet>(
+            r#"
+            INSERT INTO snippets (keyword, expansion, created_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(keyword) DO UPDATE SET expansion = excluded.expansion
+            RETURNING id, keyword, expansion, created_at
+            "#,
+        )
+        .bind(keyword)
+        .bind(expansion)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(snippet)
+    }
+
+    /// Delete a snippet. Returns false if there was none with `id`.
+    pub async fn delete_snippet(&self, id: i64) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM snippets WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// All snippets, by keyword
+    pub async fn list_snippets(&self) -> AppResult<Vec<Snippet>> {
+        let snippets = sqlx::query_as::<_, Snippet>(
+            "SELECT id, keyword, expansion, created_at FROM snippets ORDER BY keyword",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(snippets)
+    }
+
+    /// Snippet with `id`
+    pub async fn get_snippet(&self, id: i64) -> AppResult<Option<Snippet>> {
+        let snippet = sqlx::query_as::<_, Snippet>(
+            "SELECT id, keyword, expansion, created_at FROM snippets WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(snippet)
+    }
+
+    /// Count a run of the palette command `command_id`
+    pub async fn record_palette_use(&self, command_id: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO palette_usage (command_id, use_count, last_used)
+            VALUES (?, 1, ?)
+            ON CONFLICT(command_id) DO UPDATE SET
+                use_count = use_count + 1,
+                last_used = excluded.last_used
+            "#,
+        )
+        .bind(command_id)
+        .bind(Utc::now().timestamp())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Palette history by command id
+    pub async fn palette_usage(&self) -> AppResult<HashMap<String, PaletteUsage>> {
+        let rows: Vec<(String, i64, i64)> =
+            sqlx::query_as("SELECT command_id, use_count, last_used FROM palette_usage")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, use_count, last_used)| (id, PaletteUsage { use_count, last_used }))
+            .collect())
+    }
 }
 
 /// Clipboard history entry
@@ -300,3 +444,12 @@ pub struct ClipboardEntry {
     pub is_favorite: i64,
     pub created_at: i64,
 }
+
+/// Launch statistics of one path
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AppUsage {
+    pub app_path: String,
+    pub app_name: String,
+    pub launch_count: i64,
+    pub last_launched_at: Option<i64>,
+}
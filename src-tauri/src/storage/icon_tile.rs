@@ -0,0 +1,177 @@
+// Letter tile icons: a colored rounded square with the name's leading letter,
+// used when no real icon can be extracted.
+use crate::app::error::{AppError, AppResult};
+use image::{ImageOutputFormat, Rgba, RgbaImage};
+use pinyin::ToPinyin;
+use std::io::Cursor;
+
+/// Tile edge length in pixels
+pub const TILE_SIZE: u32 = 64;
+
+const CORNER_RADIUS: f32 = 12.0;
+const GLYPH_SCALE: u32 = 6;
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+
+/// 5x7 bitmap font, one byte per row, low 5 bits used (MSB = left)
+pub(crate) fn glyph_rows(c: char) -> [u8; 7] {
+    match c {
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        _ => [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010], // '#'
+    }
+}
+
+/// Letter shown on the tile: first ASCII letter/digit, or the pinyin initial of
+/// a leading Chinese character ("微信" -> 'W'). Anything else maps to '#'.
+pub fn tile_letter(name: &str) -> char {
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            return c.to_ascii_uppercase();
+        }
+        if let Some(first) = c.to_pinyin().and_then(|p| p.plain().chars().next()) {
+            return first.to_ascii_uppercase();
+        }
+        if c.is_alphanumeric() {
+            break;
+        }
+    }
+    '#'
+}
+
+/// Deterministic background color derived from the (case-insensitive) name
+pub fn tile_color(name: &str) -> [u8; 3] {
+    let digest = md5::compute(name.trim().to_lowercase().as_bytes());
+    let hue = u16::from_le_bytes([digest[0], digest[1]]) as f32 % 360.0;
+    hsl_to_rgb(hue, 0.55, 0.48)
+}
+
+fn hsl_to_rgb(h: f32, s: f32, l: f32) -> [u8; 3] {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = l - c / 2.0;
+    let (r, g, b) = match h as u32 {
+        0..=59 => (c, x, 0.0),
+        60..=119 => (x, c, 0.0),
+        120..=179 => (0.0, c, x),
+        180..=239 => (0.0, x, c),
+        240..=299 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let to_u8 = |v: f32| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    [to_u8(r), to_u8(g), to_u8(b)]
+}
+
+/// Render the tile as PNG bytes
+pub fn render_tile(name: &str) -> AppResult<Vec<u8>> {
+    let [r, g, b] = tile_color(name);
+    let background = Rgba([r, g, b, 255]);
+    let foreground = Rgba([255, 255, 255, 255]);
+
+    let mut img = RgbaImage::from_pixel(TILE_SIZE, TILE_SIZE, Rgba([0, 0, 0, 0]));
+
+    // Rounded square
+    let max = (TILE_SIZE - 1) as f32;
+    for (x, y, px) in img.enumerate_pixels_mut() {
+        let (fx, fy) = (x as f32, y as f32);
+        let cx = fx.clamp(CORNER_RADIUS, max - CORNER_RADIUS);
+        let cy = fy.clamp(CORNER_RADIUS, max - CORNER_RADIUS);
+        if (fx - cx).powi(2) + (fy - cy).powi(2) <= CORNER_RADIUS * CORNER_RADIUS {
+            *px = background;
+        }
+    }
+
+    // Centered, scaled glyph
+    let rows = glyph_rows(tile_letter(name));
+    let left = (TILE_SIZE - GLYPH_WIDTH * GLYPH_SCALE) / 2;
+    let top = (TILE_SIZE - GLYPH_HEIGHT * GLYPH_SCALE) / 2;
+    for (row, bits) in rows.iter().enumerate() {
+        for col in 0..GLYPH_WIDTH {
+            if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                continue;
+            }
+            let x0 = left + col * GLYPH_SCALE;
+            let y0 = top + row as u32 * GLYPH_SCALE;
+            for dy in 0..GLYPH_SCALE {
+                for dx in 0..GLYPH_SCALE {
+                    img.put_pixel(x0 + dx, y0 + dy, foreground);
+                }
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    image::DynamicImage::ImageRgba8(img)
+        .write_to(&mut Cursor::new(&mut out), ImageOutputFormat::Png)
+        .map_err(|e| AppError::Unknown(format!("Failed to encode tile: {}", e)))?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_is_valid_png() {
+        let png = render_tile("Visual Studio Code").unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let img = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(img.dimensions(), (TILE_SIZE, TILE_SIZE));
+        // Corners are transparent, center area is painted
+        assert_eq!(img.get_pixel(0, 0)[3], 0);
+        assert_eq!(img.get_pixel(TILE_SIZE / 2, 2)[3], 255);
+    }
+
+    #[test]
+    fn test_color_is_deterministic() {
+        assert_eq!(tile_color("Firefox"), tile_color("Firefox"));
+        assert_eq!(tile_color("firefox"), tile_color("FIREFOX"));
+        assert_ne!(tile_color("Firefox"), tile_color("Chrome"));
+        assert_eq!(render_tile("Slack").unwrap(), render_tile("Slack").unwrap());
+    }
+
+    #[test]
+    fn test_tile_letter() {
+        assert_eq!(tile_letter("notepad"), 'N');
+        assert_eq!(tile_letter("  7-Zip"), '7');
+        assert_eq!(tile_letter("微信"), 'W');
+        assert_eq!(tile_letter("(x86) tools"), 'X');
+        assert_eq!(tile_letter("★"), '#');
+        assert_eq!(tile_letter(""), '#');
+    }
+}
@@ -1,5 +1,6 @@
 pub mod cache;
 pub mod database;
+pub mod icon_tile;
 
 pub use cache::IconCache;
-pub use database::{Database, ClipboardEntry};
+pub use database::{AppUsage, ClipboardEntry, Database};
@@ -1,3 +1,4 @@
 pub mod crypto;
 pub mod image;
 pub mod logger;
+pub mod text;
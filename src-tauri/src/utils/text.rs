@@ -0,0 +1,57 @@
+// Text folding for search
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+
+/// Lowercases `text` and strips diacritics so "Café" and "cafe" compare
+/// equal. Letters without a decomposition get their usual ASCII spelling
+/// ("ß" becomes "ss", "ø" becomes "o"). CJK text is left alone: Hangul and
+/// kana would otherwise lose their jamo and voicing marks.
+pub fn fold_for_search(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c >= '\u{2E80}' {
+            folded.extend(c.to_lowercase());
+            continue;
+        }
+        decompose_canonical(c, |d| {
+            if is_combining_mark(d) {
+                return;
+            }
+            match d {
+                'ß' | 'ẞ' => folded.push_str("ss"),
+                'Æ' | 'æ' => folded.push_str("ae"),
+                'Œ' | 'œ' => folded.push_str("oe"),
+                'Ø' | 'ø' => folded.push('o'),
+                'Ł' | 'ł' => folded.push('l'),
+                'Đ' | 'đ' => folded.push('d'),
+                _ => folded.extend(d.to_lowercase()),
+            }
+        });
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_diacritics() {
+        assert_eq!(fold_for_search("Café"), "cafe");
+        assert_eq!(fold_for_search("Mañana"), "manana");
+        assert_eq!(fold_for_search("Ångström Über"), "angstrom uber");
+        // Precomposed and combining forms fold alike
+        assert_eq!(fold_for_search("cafe\u{301}"), "cafe");
+    }
+
+    #[test]
+    fn test_letters_without_decomposition() {
+        assert_eq!(fold_for_search("Straße"), "strasse");
+        assert_eq!(fold_for_search("GROẞ"), "gross");
+        assert_eq!(fold_for_search("Smørrebrød Łódź"), "smorrebrod lodz");
+    }
+
+    #[test]
+    fn test_cjk_is_unchanged() {
+        assert_eq!(fold_for_search("한국어 が 会议"), "한국어 が 会议");
+    }
+}
@@ -1,5 +1,5 @@
 import { Component, Show } from 'solid-js'
-import { File, Calculator, Globe, Brain, Clipboard, Terminal, Package } from 'lucide-solid'
+import { File, Calculator, Globe, Brain, Clipboard, Terminal, Package, FolderOpen } from 'lucide-solid'
 import type { SearchResult } from '../../types/search'
 
 interface ResultItemProps {
@@ -9,6 +9,8 @@ interface ResultItemProps {
   onClick: () => void
   onDoubleClick: () => void
   onMouseEnter: () => void
+  /** Reveal in file manager; the button only shows for results with a path */
+  onReveal?: () => void
 }
 
 /**
@@ -114,6 +116,22 @@ export const ResultItem: Component<ResultItemProps> = (props) => {
         </Show>
       </div>
 
+      {/* Reveal in file manager */}
+      <Show when={props.onReveal && props.result.path && ['file', 'app', 'folder'].includes(props.result.type ?? '')}>
+        <button
+          type="button"
+          title="Show in folder"
+          onClick={(e) => {
+            e.stopPropagation()
+            props.onReveal?.()
+          }}
+          class="flex-shrink-0 rounded p-1 text-gray-500 transition-all hover:bg-gray-200 dark:text-gray-400 dark:hover:bg-gray-700"
+          classList={{ 'opacity-0 group-hover:opacity-100': !props.isSelected }}
+        >
+          <FolderOpen class="h-4 w-4" />
+        </button>
+      </Show>
+
       {/* Keyboard Shortcut Hint */}
       <Show when={getShortcut()}>
         <div
@@ -7,6 +7,8 @@ interface ResultListProps {
   selectedIndex: number
   onSelect: (index: number) => void
   onExecute: (result: SearchResult) => void
+  /** Show a result's file in the system file manager */
+  onReveal?: (result: SearchResult) => void
   loading?: boolean
   emptyMessage?: string
   query?: string
@@ -85,6 +87,7 @@ export const ResultList: Component<ResultListProps> = (props) => {
                       onClick={() => props.onExecute(result)}
                       onDoubleClick={() => props.onExecute(result)}
                       onMouseEnter={() => props.onSelect(globalIndex)}
+                      onReveal={props.onReveal && (() => props.onReveal?.(result))}
                     />
                   </div>
                 )
@@ -109,6 +112,7 @@ export const ResultList: Component<ResultListProps> = (props) => {
                       onClick={() => props.onExecute(result)}
                       onDoubleClick={() => props.onExecute(result)}
                       onMouseEnter={() => props.onSelect(globalIndex)}
+                      onReveal={props.onReveal && (() => props.onReveal?.(result))}
                     />
                   </div>
                 )
@@ -133,6 +137,7 @@ export const ResultList: Component<ResultListProps> = (props) => {
                       onClick={() => props.onExecute(result)}
                       onDoubleClick={() => props.onExecute(result)}
                       onMouseEnter={() => props.onSelect(globalIndex)}
+                      onReveal={props.onReveal && (() => props.onReveal?.(result))}
                     />
                   </div>
                 )
@@ -3,7 +3,7 @@ import { onMount, onCleanup } from 'solid-js'
 export interface KeyboardHandlers {
   onArrowUp?: () => void
   onArrowDown?: () => void
-  onEnter?: () => void
+  onEnter?: (event: KeyboardEvent) => void
   onEscape?: () => void
   onTab?: () => void
   onBackspace?: () => void
@@ -38,7 +38,7 @@ export function useKeyboard(handlers: KeyboardHandlers) {
     // Enter key
     else if (event.key === 'Enter') {
       event.preventDefault()
-      handlers.onEnter?.()
+      handlers.onEnter?.(event)
     }
     // Escape key
     else if (event.key === 'Escape') {
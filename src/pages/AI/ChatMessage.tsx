@@ -12,6 +12,12 @@ interface AIMessage {
     data: string
     mime_type?: string
   }>
+  usage?: {
+    prompt_tokens: number
+    completion_tokens: number
+    total_tokens: number
+    estimated: boolean
+  }
 }
 
 interface ChatMessageProps {
@@ -117,6 +123,13 @@ const ChatMessage: Component<ChatMessageProps> = (props) => {
           }`}
         >
           {formatTime(props.message.timestamp)}
+          <Show when={props.message.usage}>
+            {(usage) => (
+              <span class="ml-2" title={`${usage().prompt_tokens} prompt + ${usage().completion_tokens} completion`}>
+                · {usage().estimated ? '~' : ''}{usage().total_tokens} tokens
+              </span>
+            )}
+          </Show>
         </div>
       </div>
     </div>
@@ -11,6 +11,14 @@ export interface AIMessage {
   content: string
   timestamp: number
   attachments?: AIAttachment[]
+  usage?: TokenUsage
+}
+
+export interface TokenUsage {
+  prompt_tokens: number
+  completion_tokens: number
+  total_tokens: number
+  estimated: boolean
 }
 
 export interface AIAttachment {
@@ -27,6 +35,7 @@ export interface AIConversation {
   created_at: number
   updated_at: number
   system_prompt?: string
+  usage: TokenUsage
 }
 
 export interface PresetPrompt {
@@ -65,7 +74,7 @@ const AIPage: Component = () => {
       setStreamingContent((prev) => prev + event.payload.chunk)
     })
     
-    const unlistenEnd = await listen<{ id: string; content: string }>('ai-stream-end', async (event) => {
+    const unlistenEnd = await listen<{ id: string; content: string; usage: TokenUsage }>('ai-stream-end', async (event) => {
       setIsStreaming(false)
       const conv = currentConversation()
       if (conv) {
@@ -74,6 +83,7 @@ const AIPage: Component = () => {
           conversationId: conv.id,
           messageId: event.payload.id,
           content: event.payload.content,
+          usage: event.payload.usage,
         })
         // Reload the conversation
         await loadConversation(conv.id)
@@ -86,15 +96,28 @@ const AIPage: Component = () => {
       setStreamingContent('')
       console.error('Streaming error:', event.payload)
     })
+
+    // Reopened from the "recently closed" stack
+    const unlistenReopen = await listen<string>('ai:open-conversation', async (event) => {
+      await loadConversations()
+      await loadConversation(event.payload)
+    })
     
     onCleanup(() => {
       unlistenStart()
       unlistenChunk()
       unlistenEnd()
       unlistenError()
+      unlistenReopen()
     })
   })
 
+  // Let the backend know which conversation to restore if the panel is closed
+  createEffect(() => {
+    const id = currentConversation()?.id ?? null
+    invoke('set_active_conversation', { conversationId: id }).catch(console.error)
+  })
+
   const loadConversations = async () => {
     try {
       const convs = await invoke<AIConversation[]>('ai_get_conversations')
@@ -17,6 +17,7 @@ interface CaptureData {
   height: number
   monitorX?: number  // Window screen X offset (for multi-monitor)
   monitorY?: number  // Window screen Y offset (for multi-monitor)
+  afterCapture?: 'edit' | 'pin'  // 'pin' pins the selection immediately
 }
 
 interface ElementRect {
@@ -77,6 +78,7 @@ const CapturePage: Component = () => {
   const [elementRect, setElementRect] = createSignal<ElementRect | null>(null)
   const [autoDetectEnabled, setAutoDetectEnabled] = createSignal(true)
   const [monitorOffset, setMonitorOffset] = createSignal({ x: 0, y: 0 })  // Window screen position
+  const [afterCapture, setAfterCapture] = createSignal<'edit' | 'pin'>('edit')
   let lastDetectTime = 0
   let detectPending = false
 
@@ -166,6 +168,7 @@ const CapturePage: Component = () => {
         x: event.payload.monitorX ?? 0, 
         y: event.payload.monitorY ?? 0 
       })
+      setAfterCapture(event.payload.afterCapture ?? 'edit')
 
       // Fresh capture: clear any previous selection/overlay state first.
       setSelection(null)
@@ -370,8 +373,8 @@ const CapturePage: Component = () => {
       const elemHeight = elem.bottom - elem.top
       if (elemWidth >= MIN_SELECTION_SIZE && elemHeight >= MIN_SELECTION_SIZE) {
         setSelection({ x: elem.left, y: elem.top, w: elemWidth, h: elemHeight })
-        setStatus('editing')
         setElementRect(null)
+        completeSelection()
         return
       }
     }
@@ -461,6 +464,29 @@ const CapturePage: Component = () => {
     })
   }
 
+  // Selection finished: pin right away in "pin" mode, otherwise show the toolbar
+  const completeSelection = () => {
+    const sel = selection()
+    if (afterCapture() === 'pin' && sel) {
+      console.log('[Capture] Selection complete, pinning')
+      resetState()
+      invoke('create_pin_window_from_selection', {
+        x: Math.round(sel.x),
+        y: Math.round(sel.y),
+        width: Math.round(sel.w),
+        height: Math.round(sel.h),
+        viewportWidth: window.innerWidth,
+        viewportHeight: window.innerHeight,
+      }).catch((err) => {
+        console.error('[Capture] Pin failed:', err)
+        window.alert(`Pin 失败：${String((err as any)?.message ?? err)}`)
+      })
+      return
+    }
+    setStatus('editing')
+    console.log('[Capture] Selection complete, entering editing mode')
+  }
+
   const onMouseUp = () => {
     if (ocrOpen() || ocrLoading()) return
     if (status() !== 'selecting' || !isDragging()) return
@@ -468,8 +494,7 @@ const CapturePage: Component = () => {
     
     // Check if selection is large enough
     if (hasValidSelection()) {
-      setStatus('editing')
-      console.log('[Capture] Selection complete, entering editing mode')
+      completeSelection()
       return
     }
 
@@ -558,8 +583,25 @@ const CapturePage: Component = () => {
     })
   }
 
+  // Remember the selection so the "last region" capture mode can repeat it
+  const rememberRegion = () => {
+    const sel = selection()
+    if (!sel || sel.w <= 0 || sel.h <= 0) return
+    invoke('remember_capture_region', {
+      x: Math.round(sel.x),
+      y: Math.round(sel.y),
+      width: Math.round(sel.w),
+      height: Math.round(sel.h),
+      viewportWidth: window.innerWidth,
+      viewportHeight: window.innerHeight,
+    }).catch(() => {
+      // ignore
+    })
+  }
+
   // Toolbar handlers
   const handleCopy = async () => {
+    rememberRegion()
     try {
       const blob = await getSelectionBlob()
       if (!blob) return
@@ -601,7 +643,8 @@ const CapturePage: Component = () => {
       setOcrOpen(true)
       setOcrLoading(true)
 
-      const text = await invoke<string>('recognize_text', { base64Image: base64 })
+      const result = await invoke<{ text: string; language: string | null }>('recognize_text', { base64Image: base64 })
+      const text = result?.text ?? ''
       const trimmed = (text || '').trim()
       setOcrText(trimmed ? text : '未识别到文字')
 
@@ -623,6 +666,7 @@ const CapturePage: Component = () => {
   }
 
   const handleSave = async () => {
+    rememberRegion()
     try {
       const blob = await getSelectionBlob()
       if (!blob) return
@@ -632,7 +676,11 @@ const CapturePage: Component = () => {
       const name = `Snip-${now.getFullYear()}${pad(now.getMonth() + 1)}${pad(now.getDate())}-${pad(now.getHours())}${pad(now.getMinutes())}${pad(now.getSeconds())}.png`
 
       const filePath = await save({
-        filters: [{ name: 'PNG', extensions: ['png'] }],
+        filters: [
+          { name: 'PNG', extensions: ['png'] },
+          { name: 'JPEG', extensions: ['jpg', 'jpeg'] },
+          { name: 'WebP', extensions: ['webp'] },
+        ],
         defaultPath: name,
       })
 
@@ -650,6 +698,7 @@ const CapturePage: Component = () => {
     try {
       const sel = selection()
       if (!sel) return
+      rememberRegion()
 
       // Export the selected region including annotations.
       // Use pixelRatio=1 for PIN to keep it fast and responsive (PIN window matches selection size).
@@ -63,7 +63,15 @@ const ClipboardItem: Component<ClipboardItemProps> = (props) => {
                 {props.index + 1}
               </span>
             )}
-            <span class="text-lg flex-shrink-0">{getTypeIcon(props.item.type)}</span>
+            {props.item.thumbnail ? (
+              <img
+                src={props.item.thumbnail}
+                alt=""
+                class="w-8 h-8 flex-shrink-0 rounded object-contain bg-gray-100 dark:bg-gray-800"
+              />
+            ) : (
+              <span class="text-lg flex-shrink-0">{getTypeIcon(props.item.type)}</span>
+            )}
             <div class="flex-1 min-w-0">
               <div class="text-sm text-gray-900 dark:text-gray-100 truncate">
                 {truncateContent(props.item.content)}
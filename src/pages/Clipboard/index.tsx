@@ -11,6 +11,8 @@ export interface ClipboardItemData {
   content: string
   timestamp: number
   favorite: boolean
+  // PNG data URL, set for image items
+  thumbnail?: string | null
 }
 
 const ClipboardPage: Component = () => {
@@ -1,7 +1,9 @@
-import { Component, createSignal, createMemo, createEffect, Show } from 'solid-js'
+import { Component, createSignal, createMemo, createEffect, onCleanup, onMount, Show } from 'solid-js'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { getCurrentWindow, LogicalSize } from '@tauri-apps/api/window'
 import type { SearchResult } from '../../types/search'
+import { systemCommands, type StartupStatus } from '../../services/tauri'
 import { SearchInput } from '../../components/SearchBox'
 import { ResultList } from '../../components/ResultList'
 import { AIChatView } from '../../components/AIChatView'
@@ -43,6 +45,7 @@ export const MainPage: Component = () => {
   const [results, setResults] = createSignal<SearchResult[]>([])
   const [selectedIndex, setSelectedIndex] = createSignal(0)
   const [loading, setLoading] = createSignal(false)
+  const [appStatus, setAppStatus] = createSignal<StartupStatus>({ state: 'starting', attempt: 1 })
   
   // AI Instant Query State
   const [aiMode, setAiMode] = createSignal(false)
@@ -50,6 +53,30 @@ export const MainPage: Component = () => {
   const [aiQuestion, setAiQuestion] = createSignal('')
   const [aiLoading, setAiLoading] = createSignal(false)
 
+  // Search needs the backend state; say so while it starts or if it failed
+  onMount(async () => {
+    // An event is newer than the status fetched below, whenever it arrives
+    let heardEvent = false
+    const unlisten = await listen<StartupStatus>('app-state-status', (event) => {
+      heardEvent = true
+      setAppStatus(event.payload)
+    })
+    onCleanup(unlisten)
+    try {
+      const status = await systemCommands.getAppStateStatus()
+      if (!heardEvent) setAppStatus(status)
+    } catch (error) {
+      console.error('Failed to get app state status:', error)
+    }
+  })
+
+  const placeholder = () => {
+    const status = appStatus()
+    if (status.state === 'starting') return 'Starting up…'
+    if (status.state === 'failed') return `Search unavailable: ${status.error}`
+    return 'Search files, apps, or type a command...'
+  }
+
   // Debounced query for search (500ms to reduce API calls and prevent request pileup)
   const debouncedQuery = useDebounce(query, 500)
 
@@ -124,8 +151,18 @@ export const MainPage: Component = () => {
     const searchId = ++currentSearchId
     
     if (!q.trim()) {
-      setResults([])
+      // An empty query lists recently opened items
       setLoading(false)
+      invoke<SearchResult[]>('search', { query: '' })
+        .then((recentResults) => {
+          if (searchId === currentSearchId) {
+            setResults(recentResults)
+            setSelectedIndex(0)
+          }
+        })
+        .catch(() => {
+          if (searchId === currentSearchId) setResults([])
+        })
       return
     }
     
@@ -149,6 +186,8 @@ export const MainPage: Component = () => {
         }
       })
       .catch((error) => {
+        // Superseded by a newer query; that one renders the results
+        if (String(error).startsWith('Cancelled:')) return
         console.error('Search error:', error)
         if (searchId === currentSearchId) {
           setResults(builtinResults)
@@ -221,6 +260,20 @@ export const MainPage: Component = () => {
       })
     }
     
+    // Reopen recently closed pin / AI conversation
+    if ('reopen'.includes(query) || '恢复'.includes(query)) {
+      builtins.push({
+        id: 'builtin-reopen',
+        title: 'Reopen Closed',
+        subtitle: 'Restore the last closed pin or AI conversation',
+        icon: '↩️',
+        category: 'system',
+        path: '',
+        score: 100,
+        action: { type: 'reopen-closed' },
+      })
+    }
+    
     // AI Chat command
     if ('ai'.includes(query) || 'chat'.includes(query)) {
       builtins.push({
@@ -250,7 +303,7 @@ export const MainPage: Component = () => {
         setSelectedIndex((prev) => Math.min(results().length - 1, prev + 1))
       }
     },
-    onEnter: () => {
+    onEnter: (event) => {
       // If in AI query mode (typing "ai xxx"), execute AI query
       if (isAiQuery() && !aiMode()) {
         executeAiQuery()
@@ -259,7 +312,17 @@ export const MainPage: Component = () => {
       
       // Normal search result execution
       const selected = results()[selectedIndex()]
-      if (selected) {
+      if (!selected) return
+
+      // Modifier+Enter runs the configured alternate action
+      const modifier = [
+        (event.ctrlKey || event.metaKey) && 'ctrl',
+        event.altKey && 'alt',
+        event.shiftKey && 'shift',
+      ].filter(Boolean).join('+')
+      if (modifier && selected.type) {
+        executeModifierAction(selected, modifier)
+      } else {
         executeResult(selected)
       }
     },
@@ -290,6 +353,40 @@ export const MainPage: Component = () => {
     }
   }
 
+  // Execute the action bound to modifier+Enter (see result_actions in config)
+  const executeModifierAction = async (result: SearchResult, modifier: string) => {
+    try {
+      await invoke<string>('execute_action', {
+        target: { type: result.type, path: result.path, action: result.action },
+        modifier,
+      })
+      await hideWindow()
+    } catch (error) {
+      // The user declined a prompt (e.g. UAC for "Run as administrator")
+      if (String(error).startsWith('Cancelled:')) return
+      // The backend ran the action and it failed, e.g. a file too large to
+      // copy as a data URL; opening the item instead would be surprising
+      if (String(error).startsWith('Clipboard error:')) {
+        alert(String(error))
+        return
+      }
+      // Actions the backend doesn't run (web search, AI, ...) use the normal path
+      console.warn('Modifier action not handled by backend:', error)
+      await executeResult(result)
+    }
+  }
+
+  // Show a result's file in Explorer/Finder with the item selected
+  const revealResult = async (result: SearchResult) => {
+    if (!result.path) return
+    try {
+      await invoke('reveal_in_file_manager', { path: result.path })
+      await hideWindow()
+    } catch (error) {
+      console.error('Reveal failed:', error)
+    }
+  }
+
   // Execute selected result
   const executeResult = async (result: SearchResult) => {
     console.log('executeResult called with:', result)
@@ -309,8 +406,8 @@ export const MainPage: Component = () => {
           // Hide window FIRST before opening app (faster user experience)
           await hideWindow()
           shouldHideManually = false
-          // Then open the path
-          invoke('open_path', { path: result.path }).catch(console.error)
+          // Then launch it; the backend records the launch for ranking
+          invoke('launch_path', { path: result.path }).catch(console.error)
           break
         case 'copy':
           // Copy to clipboard using Tauri clipboard API
@@ -319,10 +416,26 @@ export const MainPage: Component = () => {
             await writeText(result.action.payload)
           }
           break
-        case 'execute':
-          // Execute command (TODO: implement shell command execution)
-          console.log('Execute command:', result.action.payload)
+        case 'execute': {
+          // Never run a shell command without an explicit confirmation
+          const cmd = result.action.payload
+          if (!cmd || !confirm(`Run "${cmd}" in the system shell?`)) {
+            shouldHideManually = false
+            break
+          }
+          try {
+            const output = await invoke<{ stdout: string; stderr: string; exit_code: number | null }>(
+              'execute_system_command',
+              { cmd }
+            )
+            const text = [output.stdout, output.stderr].filter((s) => s.trim()).join('\n')
+            alert(text || `Exited with code ${output.exit_code ?? 'unknown'}`)
+          } catch (error) {
+            alert(String(error))
+          }
+          shouldHideManually = false
           break
+        }
         case 'web-search':
           // Hide window FIRST before opening URL (faster user experience)
           await hideWindow()
@@ -342,6 +455,65 @@ export const MainPage: Component = () => {
           await invoke('show_window', { label: 'clipboard' })
           shouldHideManually = false
           break
+        case 'reopen-closed':
+          await hideWindow()
+          shouldHideManually = false
+          invoke('reopen_recently_closed').catch(console.error)
+          break
+        case 'kill-process': {
+          // Never kill without an explicit confirmation
+          const pid = Number(result.action.payload)
+          if (!Number.isFinite(pid) || !confirm(`Force quit ${result.title} (PID ${pid})?`)) {
+            shouldHideManually = false
+            break
+          }
+          try {
+            await invoke('kill_process', { pid })
+          } catch (error) {
+            alert(String(error))
+            shouldHideManually = false
+          }
+          break
+        }
+        case 'focus-window':
+          // Hide first so the launcher does not take focus back
+          await hideWindow()
+          shouldHideManually = false
+          invoke('focus_window', { hwnd: Number(result.action.payload) }).catch(console.error)
+          break
+        case 'paste-snippet':
+          // Hide first so the paste lands in the app the user came from
+          await hideWindow()
+          shouldHideManually = false
+          invoke('paste_snippet', { id: Number(result.action.payload) }).catch(console.error)
+          break
+        case 'plugin-action': {
+          // The plugin that produced the result handles it
+          const { pluginId, action } = JSON.parse(result.action.payload)
+          await hideWindow()
+          shouldHideManually = false
+          invoke('execute_plugin_action', { pluginId, action }).catch(console.error)
+          break
+        }
+        case 'palette-command': {
+          // Payload is the palette command; its handler says what to run
+          const command = JSON.parse(result.action.payload)
+          if (command.confirm && !confirm(`${command.label}?`)) {
+            shouldHideManually = false
+            break
+          }
+          await hideWindow()
+          shouldHideManually = false
+          const handler = command.handler
+          const run =
+            handler.kind === 'plugin'
+              ? invoke('execute_plugin_action', { pluginId: handler.plugin_id, action: handler.action })
+              : invoke(handler.command, handler.args ?? {})
+          run
+            .then(() => invoke('record_palette_use', { id: command.id }))
+            .catch(console.error)
+          break
+        }
         case 'settings':
           // Open settings window (backend handles hiding main window)
           await invoke('show_window', { label: 'settings' })
@@ -391,8 +563,8 @@ export const MainPage: Component = () => {
     }
   }
 
-  // Whether to show results (only when user has typed something)
-  const showResults = createMemo(() => query().trim().length > 0 && !aiMode())
+  // Whether to show results (typed query, or recent items for an empty one)
+  const showResults = createMemo(() => (query().trim().length > 0 || results().length > 0) && !aiMode())
   
   // Dynamically resize window based on results
   createEffect(async () => {
@@ -443,7 +615,7 @@ export const MainPage: Component = () => {
           value={query()}
           onInput={setQuery}
           onClear={handleClear}
-          placeholder="Search files, apps, or type a command..."
+          placeholder={placeholder()}
           inputType={inputType()}
           autofocus
         />
@@ -473,6 +645,7 @@ export const MainPage: Component = () => {
             selectedIndex={selectedIndex()}
             onSelect={setSelectedIndex}
             onExecute={executeResult}
+            onReveal={revealResult}
             loading={loading()}
             query={query().trim()}
             emptyMessage="No results found"
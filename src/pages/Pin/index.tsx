@@ -37,9 +37,9 @@ const PinPage: Component = () => {
     if (w && h) setOriginalSize({ width: parseInt(w), height: parseInt(h) })
 
     // Also support event-based payload (preferred)
-    const unlistenPromise = listen<{ data: string }>('pin:set_image', (event) => {
+    const unlistenPromise = listen<{ data: string; mime_type?: string }>('pin:set_image', (event) => {
       if (event.payload?.data) {
-        setImageUrl(`data:image/png;base64,${event.payload.data}`)
+        setImageUrl(`data:${event.payload.mime_type ?? 'image/png'};base64,${event.payload.data}`)
       }
     })
 
@@ -47,11 +47,11 @@ const PinPage: Component = () => {
     ;(async () => {
       try {
         const label = currentWindow.label
-        const payload = await invoke<{ data: string; width: number; height: number } | null>('get_pin_payload', {
+        const payload = await invoke<{ data: string; mime_type: string; width: number; height: number } | null>('get_pin_payload', {
           label,
         })
         if (payload?.data) {
-          setImageUrl(`data:image/png;base64,${payload.data}`)
+          setImageUrl(`data:${payload.mime_type};base64,${payload.data}`)
           setOriginalSize({ width: payload.width, height: payload.height })
         }
       } catch (e) {
@@ -161,6 +161,20 @@ const PinPage: Component = () => {
     await closeSelf()
   }
 
+  // The clipboard only takes PNG images, so JPEG/WebP pins are redrawn first
+  const toPngBlob = async (blob: Blob): Promise<Blob> => {
+    if (blob.type === 'image/png') return blob
+    const bitmap = await createImageBitmap(blob)
+    const canvas = document.createElement('canvas')
+    canvas.width = bitmap.width
+    canvas.height = bitmap.height
+    canvas.getContext('2d')?.drawImage(bitmap, 0, 0)
+    bitmap.close()
+    return new Promise((resolve, reject) =>
+      canvas.toBlob((png) => (png ? resolve(png) : reject(new Error('PNG encoding failed'))), 'image/png')
+    )
+  }
+
   const handleCopy = async (e: MouseEvent) => {
     e.stopPropagation()
     const img = imageUrl()
@@ -168,7 +182,7 @@ const PinPage: Component = () => {
     
     try {
       const response = await fetch(img)
-      const blob = await response.blob()
+      const blob = await toPngBlob(await response.blob())
       await navigator.clipboard.write([
         new ClipboardItem({ 'image/png': blob })
       ])
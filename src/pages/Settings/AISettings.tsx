@@ -1,6 +1,6 @@
 import { Component, For, createSignal, Show } from 'solid-js'
 import { invoke } from '@tauri-apps/api/core'
-import { Bot, Key, Server, Thermometer, Hash, RefreshCw } from 'lucide-solid'
+import { Bot, Key, Server, Thermometer, Hash, RefreshCw, Zap } from 'lucide-solid'
 
 interface AIConfig {
   provider: string
@@ -9,6 +9,22 @@ interface AIConfig {
   model: string
   temperature: number
   max_tokens: number
+  deployment?: string | null
+  api_version?: string | null
+}
+
+type ProviderTestError =
+  | { kind: 'auth' | 'network' | 'model' | 'other'; message: string }
+  | { kind: 'timeout' }
+
+type TestResult = { ok: true; latency: number } | { ok: false; error: ProviderTestError }
+
+const testErrorLabels: Record<ProviderTestError['kind'], string> = {
+  auth: 'Authentication failed',
+  network: 'Could not reach the API',
+  model: 'Model not found',
+  timeout: 'Timed out',
+  other: 'Request failed',
 }
 
 interface AISettingsProps {
@@ -20,6 +36,8 @@ const AISettings: Component<AISettingsProps> = (props) => {
   const [models, setModels] = createSignal<string[]>([])
   const [loadingModels, setLoadingModels] = createSignal(false)
   const [showApiKey, setShowApiKey] = createSignal(false)
+  const [testing, setTesting] = createSignal(false)
+  const [testResult, setTestResult] = createSignal<TestResult | null>(null)
 
   const providers = [
     {
@@ -40,6 +58,18 @@ const AISettings: Component<AISettingsProps> = (props) => {
       description: 'Local LLMs (Llama, Mistral)',
       defaultUrl: 'http://localhost:11434',
     },
+    {
+      id: 'gemini',
+      name: 'Google Gemini',
+      description: 'Gemini 2.0, Gemini 1.5',
+      defaultUrl: 'https://generativelanguage.googleapis.com/v1beta',
+    },
+    {
+      id: 'azure',
+      name: 'Azure OpenAI',
+      description: 'OpenAI models on Azure',
+      defaultUrl: 'https://<resource>.openai.azure.com',
+    },
   ]
 
   const loadModels = async () => {
@@ -62,17 +92,32 @@ const AISettings: Component<AISettingsProps> = (props) => {
         ])
       } else if (props.config.provider === 'ollama') {
         setModels(['llama3.2', 'llama3.1', 'mistral', 'codellama'])
+      } else if (props.config.provider === 'gemini') {
+        setModels(['gemini-2.0-flash', 'gemini-1.5-pro', 'gemini-1.5-flash'])
       }
     } finally {
       setLoadingModels(false)
     }
   }
 
+  const testProvider = async () => {
+    setTesting(true)
+    setTestResult(null)
+    try {
+      const latency = await invoke<number>('ai_test_provider', { config: props.config })
+      setTestResult({ ok: true, latency })
+    } catch (error) {
+      setTestResult({ ok: false, error: error as ProviderTestError })
+    } finally {
+      setTesting(false)
+    }
+  }
+
   const handleProviderChange = (provider: string) => {
     const providerInfo = providers.find((p) => p.id === provider)
     props.onChange({
       provider,
-      api_url: providerInfo?.defaultUrl || '',
+      api_url: provider === 'azure' ? '' : providerInfo?.defaultUrl || '',
       model: '', // Reset model when provider changes
     })
     loadModels()
@@ -94,7 +139,7 @@ const AISettings: Component<AISettingsProps> = (props) => {
         <h3 class="text-sm font-medium text-gray-900 dark:text-white mb-4">
           AI Provider
         </h3>
-        <div class="grid grid-cols-3 gap-3">
+        <div class="grid grid-cols-2 gap-3">
           <For each={providers}>
             {(provider) => (
               <button
@@ -132,7 +177,7 @@ const AISettings: Component<AISettingsProps> = (props) => {
                 API Key
               </h3>
               <p class="text-sm text-gray-500 dark:text-gray-400">
-                Your {props.config.provider === 'openai' ? 'OpenAI' : 'Anthropic'} API key
+                Your {providers.find((p) => p.id === props.config.provider)?.name} API key
               </p>
             </div>
           </div>
@@ -167,7 +212,9 @@ const AISettings: Component<AISettingsProps> = (props) => {
             <p class="text-sm text-gray-500 dark:text-gray-400">
               {props.config.provider === 'ollama'
                 ? 'Local Ollama server URL'
-                : 'Custom API endpoint (optional)'}
+                : props.config.provider === 'azure'
+                  ? 'Azure OpenAI resource endpoint'
+                  : 'Custom API endpoint (optional)'}
             </p>
           </div>
         </div>
@@ -180,6 +227,41 @@ const AISettings: Component<AISettingsProps> = (props) => {
         />
       </div>
 
+      {/* Azure deployment */}
+      <Show when={props.config.provider === 'azure'}>
+        <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
+          <div class="flex items-center gap-3 mb-4">
+            <div class="flex h-10 w-10 items-center justify-center rounded-lg bg-blue-100 dark:bg-blue-900">
+              <Server size={20} class="text-blue-600 dark:text-blue-400" />
+            </div>
+            <div>
+              <h3 class="text-sm font-medium text-gray-900 dark:text-white">
+                Deployment
+              </h3>
+              <p class="text-sm text-gray-500 dark:text-gray-400">
+                Deployment name and API version of your Azure resource
+              </p>
+            </div>
+          </div>
+          <div class="grid grid-cols-2 gap-3">
+            <input
+              type="text"
+              value={props.config.deployment ?? ''}
+              onInput={(e) => props.onChange({ deployment: e.currentTarget.value || null })}
+              placeholder="Deployment name"
+              class="rounded-lg border border-gray-300 px-3 py-2 text-sm focus:border-blue-500 focus:outline-none dark:border-gray-600 dark:bg-gray-800 dark:text-white"
+            />
+            <input
+              type="text"
+              value={props.config.api_version ?? ''}
+              onInput={(e) => props.onChange({ api_version: e.currentTarget.value || null })}
+              placeholder="2024-06-01"
+              class="rounded-lg border border-gray-300 px-3 py-2 text-sm focus:border-blue-500 focus:outline-none dark:border-gray-600 dark:bg-gray-800 dark:text-white"
+            />
+          </div>
+        </div>
+      </Show>
+
       {/* Model Selection */}
       <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
         <div class="flex items-center justify-between mb-4">
@@ -217,6 +299,47 @@ const AISettings: Component<AISettingsProps> = (props) => {
         </select>
       </div>
 
+      {/* Connection Test */}
+      <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
+        <div class="flex items-center justify-between">
+          <div class="flex items-center gap-3">
+            <div class="flex h-10 w-10 items-center justify-center rounded-lg bg-teal-100 dark:bg-teal-900">
+              <Zap size={20} class="text-teal-600 dark:text-teal-400" />
+            </div>
+            <div>
+              <h3 class="text-sm font-medium text-gray-900 dark:text-white">
+                Test Connection
+              </h3>
+              <p class="text-sm text-gray-500 dark:text-gray-400">
+                Send a short request with these settings
+              </p>
+            </div>
+          </div>
+          <button
+            onClick={testProvider}
+            disabled={testing() || !props.config.model}
+            class="rounded-lg bg-gray-100 px-4 py-2 text-sm text-gray-700 hover:bg-gray-200 disabled:opacity-50 dark:bg-gray-700 dark:text-gray-300"
+          >
+            {testing() ? 'Testing...' : 'Test'}
+          </button>
+        </div>
+        <Show when={testResult()}>
+          {(result) => {
+            const r = result()
+            return r.ok ? (
+              <p class="mt-3 text-sm text-green-600 dark:text-green-400">
+                Connected in {r.latency} ms
+              </p>
+            ) : (
+              <p class="mt-3 text-sm text-red-600 dark:text-red-400">
+                {testErrorLabels[r.error.kind] ?? 'Request failed'}
+                {'message' in r.error ? `: ${r.error.message}` : ''}
+              </p>
+            )
+          }}
+        </Show>
+      </div>
+
       {/* Temperature */}
       <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
         <div class="flex items-center justify-between mb-4">
@@ -1,5 +1,7 @@
-import { Component } from 'solid-js'
-import { Database, Trash2, Download, Upload, RotateCcw, Shield, Zap } from 'lucide-solid'
+import { Component, For, Show, createSignal } from 'solid-js'
+import { invoke } from '@tauri-apps/api/core'
+import { Database, Trash2, Download, Upload, RotateCcw, Shield, Zap, Image, Activity } from 'lucide-solid'
+import { systemCommands, type DiagnosticsReport } from '../../services/tauri'
 
 interface AdvancedProps {
   onExportConfig: () => void
@@ -9,6 +11,39 @@ interface AdvancedProps {
 }
 
 const Advanced: Component<AdvancedProps> = (props) => {
+  const [iconCacheStatus, setIconCacheStatus] = createSignal('')
+  const [diagnostics, setDiagnostics] = createSignal<DiagnosticsReport | null>(null)
+  const [diagnosticsRunning, setDiagnosticsRunning] = createSignal(false)
+  const [diagnosticsError, setDiagnosticsError] = createSignal('')
+
+  const runDiagnostics = async () => {
+    setDiagnosticsRunning(true)
+    setDiagnosticsError('')
+    try {
+      setDiagnostics(await systemCommands.runDiagnostics())
+    } catch (error) {
+      setDiagnosticsError(`Failed to run diagnostics: ${error}`)
+    } finally {
+      setDiagnosticsRunning(false)
+    }
+  }
+
+  const statusClass = (status: string) =>
+    status === 'pass'
+      ? 'text-green-600 dark:text-green-400'
+      : status === 'fail'
+        ? 'text-red-600 dark:text-red-400'
+        : 'text-gray-400'
+
+  const clearIconCache = async () => {
+    try {
+      const count = await invoke<number>('clear_icon_cache')
+      setIconCacheStatus(`Removed ${count} cached icons`)
+    } catch (error) {
+      setIconCacheStatus(`Failed to clear icon cache: ${error}`)
+    }
+  }
+
   return (
     <div class="space-y-6">
       <div>
@@ -89,6 +124,26 @@ const Advanced: Component<AdvancedProps> = (props) => {
         </h3>
         
         <div class="space-y-3">
+          <button
+            onClick={clearIconCache}
+            class="flex w-full items-center gap-3 rounded-lg border border-gray-200 p-3 text-left hover:bg-gray-50 dark:border-gray-700 dark:hover:bg-gray-800"
+          >
+            <div class="flex h-10 w-10 items-center justify-center rounded-lg bg-gray-100 dark:bg-gray-800">
+              <Image size={20} class="text-gray-600 dark:text-gray-400" />
+            </div>
+            <div>
+              <div class="font-medium text-gray-900 dark:text-white">
+                Clear Icon Cache
+              </div>
+              <div class="text-sm text-gray-500 dark:text-gray-400">
+                Icons are extracted again the next time they are shown
+              </div>
+            </div>
+          </button>
+          <Show when={iconCacheStatus()}>
+            <p class="text-sm text-gray-500 dark:text-gray-400">{iconCacheStatus()}</p>
+          </Show>
+
           <button
             onClick={props.onClearData}
             class="flex w-full items-center gap-3 rounded-lg border border-red-200 p-3 text-left hover:bg-red-50 dark:border-red-800 dark:hover:bg-red-900/20"
@@ -108,6 +163,52 @@ const Advanced: Component<AdvancedProps> = (props) => {
         </div>
       </div>
 
+      {/* Diagnostics */}
+      <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
+        <div class="mb-4 flex items-center justify-between">
+          <h3 class="flex items-center gap-2 text-sm font-medium text-gray-900 dark:text-white">
+            <Activity size={18} />
+            Diagnostics
+          </h3>
+          <button
+            onClick={runDiagnostics}
+            disabled={diagnosticsRunning()}
+            class="rounded-lg border border-gray-200 px-3 py-1.5 text-sm hover:bg-gray-50 disabled:opacity-50 dark:border-gray-700 dark:text-white dark:hover:bg-gray-800"
+          >
+            {diagnosticsRunning() ? 'Running...' : 'Run Checks'}
+          </button>
+        </div>
+        <p class="text-sm text-gray-500 dark:text-gray-400">
+          Checks the database, icon cache, search service, displays, OCR and settings file. Include
+          the results when reporting a problem.
+        </p>
+        <Show when={diagnosticsError()}>
+          <p class="mt-3 text-sm text-red-600 dark:text-red-400">{diagnosticsError()}</p>
+        </Show>
+        <Show when={diagnostics()}>
+          {(report) => (
+            <div class="mt-3 space-y-2">
+              <For each={report().checks}>
+                {(check) => (
+                  <div class="flex items-start justify-between gap-4 text-sm">
+                    <span class="font-medium text-gray-900 dark:text-white">{check.name}</span>
+                    <span class="text-right">
+                      <span class={`font-medium uppercase ${statusClass(check.status)}`}>
+                        {check.status}
+                      </span>
+                      <span class="ml-2 text-gray-500 dark:text-gray-400">{check.detail}</span>
+                    </span>
+                  </div>
+                )}
+              </For>
+              <p class="pt-2 text-xs text-gray-500 dark:text-gray-400">
+                {report().passed} passed, {report().failed} failed, {report().skipped} skipped
+              </p>
+            </div>
+          )}
+        </Show>
+      </div>
+
       {/* Performance */}
       <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
         <h3 class="flex items-center gap-2 text-sm font-medium text-gray-900 dark:text-white mb-4">
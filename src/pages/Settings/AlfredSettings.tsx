@@ -79,6 +79,7 @@ interface AppConfig {
     exclude_paths: string[]
     file_types: string[]
     max_file_size: number
+    file_sort?: string
   }
   clipboard: {
     enabled: boolean
@@ -1001,6 +1002,32 @@ const FeatureContent: Component<FeatureContentProps> = (props) => {
             <button class="alfred-btn alfred-btn-secondary alfred-btn-sm">Rebuild Index</button>
           </div>
         </div>
+
+        <div class="alfred-form-section">
+          <label class="alfred-form-label">Sort Results By:</label>
+          <select
+            class="alfred-select"
+            value={props.config?.indexer.file_sort || 'date_modified_descending'}
+            onChange={(e) => {
+              const cfg = props.config
+              if (cfg) {
+                props.onConfigChange({
+                  ...cfg,
+                  indexer: { ...cfg.indexer, file_sort: e.currentTarget.value },
+                })
+              }
+            }}
+          >
+            <option value="date_modified_descending">Date modified (newest first)</option>
+            <option value="date_modified_ascending">Date modified (oldest first)</option>
+            <option value="name_ascending">Name (A–Z)</option>
+            <option value="name_descending">Name (Z–A)</option>
+            <option value="size_descending">Size (largest first)</option>
+            <option value="size_ascending">Size (smallest first)</option>
+            <option value="path_ascending">Path</option>
+            <option value="run_count_descending">Most opened</option>
+          </select>
+        </div>
       </Show>
 
       {/* Default placeholder for other features */}
@@ -1,5 +1,7 @@
-import { Component, For, createSignal } from 'solid-js'
-import { Plus, Trash2 } from 'lucide-solid'
+import { Component, For, Show, createSignal } from 'solid-js'
+import { invoke } from '@tauri-apps/api/core'
+import { open, save } from '@tauri-apps/plugin-dialog'
+import { Plus, Trash2, Download, Upload } from 'lucide-solid'
 
 interface ClipboardConfig {
   enabled: boolean
@@ -7,6 +9,10 @@ interface ClipboardConfig {
   retention_days: number
   filter_sensitive: boolean
   exclude_apps: string[]
+  encrypt?: boolean
+  paste_restore_delay_ms?: number
+  sensitive_retention_minutes?: number
+  sync_folder?: string | null
 }
 
 interface ClipboardProps {
@@ -16,6 +22,45 @@ interface ClipboardProps {
 
 const Clipboard: Component<ClipboardProps> = (props) => {
   const [newApp, setNewApp] = createSignal('')
+  const [transferStatus, setTransferStatus] = createSignal('')
+
+  const exportHistory = async () => {
+    const path = await save({
+      filters: [{ name: 'Clipboard history', extensions: ['jsonl'] }],
+      defaultPath: 'omnibox-clipboard.jsonl',
+    })
+    if (!path) return
+    try {
+      const count = await invoke<number>('export_clipboard_history', { path })
+      setTransferStatus(`Exported ${count} items`)
+    } catch (error) {
+      setTransferStatus(`Export failed: ${error}`)
+    }
+  }
+
+  const importHistory = async () => {
+    const path = await open({
+      filters: [{ name: 'Clipboard history', extensions: ['jsonl'] }],
+      multiple: false,
+    })
+    if (!path) return
+    try {
+      const summary = await invoke<{ imported: number; duplicates: number }>(
+        'import_clipboard_history',
+        { path, merge: true }
+      )
+      setTransferStatus(
+        `Imported ${summary.imported} items (${summary.duplicates} already in history)`
+      )
+    } catch (error) {
+      setTransferStatus(`Import failed: ${error}`)
+    }
+  }
+
+  const chooseSyncFolder = async () => {
+    const folder = await open({ directory: true, multiple: false })
+    if (folder) props.onChange({ sync_folder: folder })
+  }
 
   const addExcludedApp = () => {
     const app = newApp().trim()
@@ -111,6 +156,81 @@ const Clipboard: Component<ClipboardProps> = (props) => {
         </div>
       </div>
 
+      {/* Sensitive Item Expiry */}
+      <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
+        <h3 class="text-sm font-medium text-gray-900 dark:text-white">Forget Sensitive Items</h3>
+        <p class="text-sm text-gray-500 dark:text-gray-400">
+          Passwords and other sensitive items are deleted after this long unless favorited.
+        </p>
+        <div class="mt-3">
+          <input
+            type="number"
+            min="1"
+            max="10080"
+            value={props.config.sensitive_retention_minutes ?? 60}
+            onChange={(e) =>
+              props.onChange({ sensitive_retention_minutes: parseInt(e.currentTarget.value) || 60 })
+            }
+            class="w-32 rounded-lg border border-gray-300 px-3 py-2 text-sm dark:border-gray-600 dark:bg-gray-800 dark:text-white"
+          />
+          <span class="ml-2 text-sm text-gray-500">minutes</span>
+        </div>
+      </div>
+
+      {/* Paste Restore Delay */}
+      <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
+        <h3 class="text-sm font-medium text-gray-900 dark:text-white">
+          Restore Clipboard After Paste
+        </h3>
+        <p class="text-sm text-gray-500 dark:text-gray-400">
+          Delay before your previous clipboard comes back after pasting an item. Raise it if slow apps paste the old contents.
+        </p>
+        <div class="mt-3">
+          <input
+            type="number"
+            min="50"
+            max="10000"
+            value={props.config.paste_restore_delay_ms ?? 500}
+            onChange={(e) =>
+              props.onChange({ paste_restore_delay_ms: parseInt(e.currentTarget.value) || 500 })
+            }
+            class="w-32 rounded-lg border border-gray-300 px-3 py-2 text-sm dark:border-gray-600 dark:bg-gray-800 dark:text-white"
+          />
+          <span class="ml-2 text-sm text-gray-500">ms</span>
+        </div>
+      </div>
+
+      {/* Sync Folder */}
+      <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
+        <h3 class="text-sm font-medium text-gray-900 dark:text-white">Sync Folder</h3>
+        <p class="text-sm text-gray-500 dark:text-gray-400">
+          Share text history with other devices through a synced folder (Syncthing, Dropbox, ...). Sensitive items are never synced, and syncing is off while history is encrypted.
+        </p>
+        <div class="mt-3 flex gap-2">
+          <input
+            type="text"
+            readOnly
+            placeholder="Not syncing"
+            value={props.config.sync_folder ?? ''}
+            class="flex-1 rounded-lg border border-gray-300 px-3 py-2 text-sm dark:border-gray-600 dark:bg-gray-800 dark:text-white"
+          />
+          <button
+            onClick={chooseSyncFolder}
+            class="rounded-lg bg-gray-100 px-3 py-2 text-sm text-gray-700 hover:bg-gray-200 dark:bg-gray-700 dark:text-gray-300"
+          >
+            Choose...
+          </button>
+          <Show when={props.config.sync_folder}>
+            <button
+              onClick={() => props.onChange({ sync_folder: null })}
+              class="rounded-lg bg-gray-100 px-3 py-2 text-sm text-gray-700 hover:bg-gray-200 dark:bg-gray-700 dark:text-gray-300"
+            >
+              Stop Syncing
+            </button>
+          </Show>
+        </div>
+      </div>
+
       {/* Filter Sensitive Content */}
       <div class="flex items-center justify-between rounded-lg border border-gray-200 p-4 dark:border-gray-700">
         <div>
@@ -134,6 +254,27 @@ const Clipboard: Component<ClipboardProps> = (props) => {
         </label>
       </div>
 
+      {/* Encrypt History */}
+      <div class="flex items-center justify-between rounded-lg border border-gray-200 p-4 dark:border-gray-700">
+        <div>
+          <h3 class="text-sm font-medium text-gray-900 dark:text-white">
+            Encrypt History
+          </h3>
+          <p class="text-sm text-gray-500 dark:text-gray-400">
+            Store clipboard contents encrypted with a key from the system keychain. Search is slower on large histories. Applies after restart.
+          </p>
+        </div>
+        <label class="relative inline-flex cursor-pointer items-center">
+          <input
+            type="checkbox"
+            checked={props.config.encrypt ?? false}
+            onChange={(e) => props.onChange({ encrypt: e.currentTarget.checked })}
+            class="peer sr-only"
+          />
+          <div class="peer h-6 w-11 rounded-full bg-gray-200 after:absolute after:left-[2px] after:top-[2px] after:h-5 after:w-5 after:rounded-full after:border after:border-gray-300 after:bg-white after:transition-all after:content-[''] peer-checked:bg-blue-600 peer-checked:after:translate-x-full peer-checked:after:border-white dark:border-gray-600 dark:bg-gray-700"></div>
+        </label>
+      </div>
+
       {/* Excluded Apps */}
       <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
         <h3 class="text-sm font-medium text-gray-900 dark:text-white">
@@ -182,6 +323,35 @@ const Clipboard: Component<ClipboardProps> = (props) => {
           )}
         </div>
       </div>
+
+      {/* Export / Import */}
+      <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
+        <h3 class="text-sm font-medium text-gray-900 dark:text-white">
+          Transfer History
+        </h3>
+        <p class="text-sm text-gray-500 dark:text-gray-400">
+          Move clipboard history to another machine. Items already in history are skipped on import.
+        </p>
+        <div class="mt-3 flex gap-2">
+          <button
+            onClick={exportHistory}
+            class="flex items-center gap-1 rounded-lg bg-gray-100 px-3 py-2 text-sm text-gray-700 hover:bg-gray-200 dark:bg-gray-700 dark:text-gray-300"
+          >
+            <Upload size={14} />
+            Export
+          </button>
+          <button
+            onClick={importHistory}
+            class="flex items-center gap-1 rounded-lg bg-gray-100 px-3 py-2 text-sm text-gray-700 hover:bg-gray-200 dark:bg-gray-700 dark:text-gray-300"
+          >
+            <Download size={14} />
+            Import
+          </button>
+        </div>
+        <Show when={transferStatus()}>
+          <p class="mt-2 text-sm text-gray-500 dark:text-gray-400">{transferStatus()}</p>
+        </Show>
+      </div>
     </div>
   )
 }
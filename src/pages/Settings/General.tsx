@@ -1,4 +1,5 @@
 import { Component } from 'solid-js'
+import type { AutohideConfig } from '../../services/tauri'
 
 interface GeneralConfig {
   language: string
@@ -9,6 +10,8 @@ interface GeneralConfig {
 interface GeneralProps {
   config: GeneralConfig
   onChange: (updates: Partial<GeneralConfig>) => void
+  autohide?: AutohideConfig
+  onAutohideChange?: (updates: Partial<AutohideConfig>) => void
 }
 
 const General: Component<GeneralProps> = (props) => {
@@ -40,6 +43,28 @@ const General: Component<GeneralProps> = (props) => {
         </label>
       </section>
 
+      {/* Launcher Settings */}
+      <section class="space-y-4">
+        <h3 class="text-sm font-medium text-gray-700 dark:text-gray-300">Launcher</h3>
+
+        <label class="flex items-center justify-between">
+          <div>
+            <div class="text-sm font-medium text-gray-900 dark:text-white">
+              Hide when focus is lost
+            </div>
+            <div class="text-xs text-gray-500 dark:text-gray-400">
+              Close the launcher when you click another window
+            </div>
+          </div>
+          <input
+            type="checkbox"
+            checked={props.autohide?.on_blur ?? true}
+            onChange={(e) => props.onAutohideChange?.({ on_blur: e.currentTarget.checked })}
+            class="h-4 w-4 rounded border-gray-300 text-blue-600 focus:ring-2 focus:ring-blue-500"
+          />
+        </label>
+      </section>
+
       {/* Language Settings */}
       <section class="space-y-4">
         <h3 class="text-sm font-medium text-gray-700 dark:text-gray-300">Language</h3>
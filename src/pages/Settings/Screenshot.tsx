@@ -6,7 +6,11 @@ interface ScreenshotConfig {
   quality: number
   save_dir: string
   auto_save: boolean
+  file_name_template?: string
   ocr_auto_copy?: boolean
+  after_capture?: 'edit' | 'pin'
+  hide_windows_delay_ms?: number
+  restore_windows_after_capture?: boolean
 }
 
 interface ScreenshotProps {
@@ -19,7 +23,7 @@ const Screenshot: Component<ScreenshotProps> = (props) => {
   const formats = [
     { value: 'png', label: 'PNG (Lossless)' },
     { value: 'jpg', label: 'JPEG (Compressed)' },
-    { value: 'webp', label: 'WebP (Modern)' },
+    { value: 'webp', label: 'WebP (Lossless)' },
   ]
 
   return (
@@ -70,8 +74,8 @@ const Screenshot: Component<ScreenshotProps> = (props) => {
         </div>
       </div>
 
-      {/* Quality (for JPEG/WebP) */}
-      {(props.config.format === 'jpg' || props.config.format === 'webp') && (
+      {/* Quality (JPEG; WebP is saved lossless) */}
+      {props.config.format === 'jpg' && (
         <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
           <div class="flex items-center justify-between mb-4">
             <div>
@@ -126,6 +130,18 @@ const Screenshot: Component<ScreenshotProps> = (props) => {
             Browse
           </button>
         </div>
+        <label class="block text-sm text-gray-700 dark:text-gray-300">
+          File name
+          <input
+            type="text"
+            value={props.config.file_name_template ?? 'Screenshot {date} {time}'}
+            onChange={(e) => props.onChange({ file_name_template: e.currentTarget.value })}
+            class="mt-1 w-full rounded-lg border border-gray-200 bg-white px-3 py-2 text-sm text-gray-900 dark:border-gray-700 dark:bg-gray-800 dark:text-white"
+          />
+        </label>
+        <p class="mt-1 text-xs text-gray-500 dark:text-gray-400">
+          Use {'{date}'}, {'{time}'} and {'{timestamp}'}; taken names get a (2), (3), ... suffix
+        </p>
       </div>
 
       {/* Auto Save */}
@@ -186,6 +202,91 @@ const Screenshot: Component<ScreenshotProps> = (props) => {
         </button>
       </div>
 
+      {/* Pin After Capture */}
+      <div class="flex items-center justify-between rounded-lg border border-gray-200 p-4 dark:border-gray-700">
+        <div class="flex items-center gap-3">
+          <div class="flex h-10 w-10 items-center justify-center rounded-lg bg-blue-100 dark:bg-blue-900">
+            <Camera size={20} class="text-blue-600 dark:text-blue-400" />
+          </div>
+          <div>
+            <h3 class="text-sm font-medium text-gray-900 dark:text-white">
+              Pin After Capture
+            </h3>
+            <p class="text-sm text-gray-500 dark:text-gray-400">
+              Pin the selected region immediately instead of showing the toolbar
+            </p>
+          </div>
+        </div>
+        <button
+          onClick={() =>
+            props.onChange({ after_capture: props.config.after_capture === 'pin' ? 'edit' : 'pin' })
+          }
+          class={`relative h-6 w-11 rounded-full transition-colors ${
+            props.config.after_capture === 'pin' ? 'bg-blue-500' : 'bg-gray-300'
+          }`}
+        >
+          <span
+            class={`absolute top-1 h-4 w-4 rounded-full bg-white transition-transform ${
+              props.config.after_capture === 'pin' ? 'left-6' : 'left-1'
+            }`}
+          />
+        </button>
+      </div>
+
+      {/* Restore Windows After Capture */}
+      <div class="flex items-center justify-between rounded-lg border border-gray-200 p-4 dark:border-gray-700">
+        <div class="flex items-center gap-3">
+          <div class="flex h-10 w-10 items-center justify-center rounded-lg bg-blue-100 dark:bg-blue-900">
+            <Camera size={20} class="text-blue-600 dark:text-blue-400" />
+          </div>
+          <div>
+            <h3 class="text-sm font-medium text-gray-900 dark:text-white">
+              Restore Windows After Capture
+            </h3>
+            <p class="text-sm text-gray-500 dark:text-gray-400">
+              OmniBox windows are hidden while capturing; show them again when done
+            </p>
+          </div>
+        </div>
+        <button
+          onClick={() =>
+            props.onChange({
+              restore_windows_after_capture: !(props.config.restore_windows_after_capture ?? true),
+            })
+          }
+          class={`relative h-6 w-11 rounded-full transition-colors ${
+            props.config.restore_windows_after_capture ?? true ? 'bg-blue-500' : 'bg-gray-300'
+          }`}
+        >
+          <span
+            class={`absolute top-1 h-4 w-4 rounded-full bg-white transition-transform ${
+              props.config.restore_windows_after_capture ?? true ? 'left-6' : 'left-1'
+            }`}
+          />
+        </button>
+      </div>
+
+      {/* Hide Delay */}
+      <div class="rounded-lg border border-gray-200 p-4 dark:border-gray-700">
+        <h3 class="text-sm font-medium text-gray-900 dark:text-white">Hide Delay</h3>
+        <p class="text-sm text-gray-500 dark:text-gray-400">
+          Time for hidden windows to disappear before the screen is captured. Raise it if they still show up in screenshots.
+        </p>
+        <div class="mt-3">
+          <input
+            type="number"
+            min="0"
+            max="2000"
+            value={props.config.hide_windows_delay_ms ?? 120}
+            onChange={(e) =>
+              props.onChange({ hide_windows_delay_ms: parseInt(e.currentTarget.value) || 0 })
+            }
+            class="w-32 rounded-lg border border-gray-300 px-3 py-2 text-sm dark:border-gray-600 dark:bg-gray-800 dark:text-white"
+          />
+          <span class="ml-2 text-sm text-gray-500">ms</span>
+        </div>
+      </div>
+
       {/* Coming Soon Notice */}
       <div class="rounded-lg bg-yellow-50 p-4 dark:bg-yellow-900/20">
         <h3 class="text-sm font-medium text-yellow-800 dark:text-yellow-200">
@@ -15,6 +15,7 @@ import { Plugins } from './Plugins'
 import { PluginMarket } from './PluginMarket'
 import Advanced from './Advanced'
 import About from './About'
+import type { AutohideConfig } from '../../services/tauri'
 
 export interface AppConfig {
   general: {
@@ -63,6 +64,7 @@ export interface AppConfig {
     quality: number
     save_dir: string
     auto_save: boolean
+    file_name_template?: string
     ocr_auto_copy?: boolean
   }
   ai: {
@@ -72,6 +74,8 @@ export interface AppConfig {
     model: string
     temperature: number
     max_tokens: number
+    deployment?: string | null
+    api_version?: string | null
   }
   web_search: {
     default_engine: string
@@ -82,6 +86,7 @@ export interface AppConfig {
       icon?: string
     }>
   }
+  autohide: AutohideConfig
 }
 
 const SettingsPage: Component = () => {
@@ -196,6 +201,8 @@ const SettingsPage: Component = () => {
         <General
           config={config()!.general}
           onChange={(updates) => handleConfigChange('general', updates)}
+          autohide={config()!.autohide}
+          onAutohideChange={(updates) => handleConfigChange('autohide', updates)}
         />
       )}
       {activeTab() === 'features' && config() && (
@@ -1,4 +1,39 @@
 import { invoke } from '@tauri-apps/api/core'
+import type { FileSortOrder, IndexSelfTest, IndexStats, WindowInfo } from '../types/search'
+
+export interface PickedColor {
+  x: number
+  y: number
+  r: number
+  g: number
+  b: number
+  a: number
+  hex: string
+  rgb: string
+  hsl: string
+}
+
+// Outcome of one startup check in run_diagnostics
+export interface DiagnosticCheck {
+  name: string
+  status: 'pass' | 'fail' | 'skipped'
+  detail: string
+}
+
+export interface DiagnosticsReport {
+  checks: DiagnosticCheck[]
+  passed: number
+  failed: number
+  skipped: number
+  healthy: boolean
+  ran_at: number
+}
+
+// Progress of the backend's startup, pushed as the app-state-status event
+export type StartupStatus =
+  | { state: 'starting'; attempt: number }
+  | { state: 'ready' }
+  | { state: 'failed'; error: string; attempts: number }
 
 // Tauri API wrapper service
 export const tauriService = {
@@ -10,24 +45,162 @@ export const tauriService = {
 
 // Search commands
 export const searchCommands = {
-  async search(query: string) {
-    return tauriService.invoke('search', { query })
+  // File/app results are paged; pass the next offset to "show more".
+  // `categories` (e.g. ['Application'] or ['folder']) narrows the results;
+  // `refine` keeps those whose title or subtitle contains all of its words.
+  async search(
+    query: string,
+    sort?: FileSortOrder,
+    limit?: number,
+    offset?: number,
+    categories?: string[],
+    refine?: string
+  ) {
+    return tauriService.invoke('search', { query, sort, limit, offset, categories, refine })
   },
   
   async calculate(expression: string) {
     return tauriService.invoke('calculate', { expression })
   },
+
+  // Inline result, or null when the input is not a calculation
+  async quickEval(expression: string) {
+    return tauriService.invoke<string | null>('quick_eval', { expression })
+  },
+
+  // Definition rows, as shown for "def <word>"
+  async defineWord(word: string) {
+    return tauriService.invoke('define_word', { word })
+  },
+
+  async searchBrowserHistory(query: string, limit?: number) {
+    return tauriService.invoke('search_browser_history', { query, limit })
+  },
+
+  async setFullTextDirectories(directories: string[]) {
+    return tauriService.invoke<number>('set_full_text_directories', { directories })
+  },
+
+  async getIndexRoots() {
+    return tauriService.invoke<string[]>('get_index_roots')
+  },
+
+  async addIndexRoot(path: string) {
+    return tauriService.invoke<number>('add_index_root', { path })
+  },
+
+  async removeIndexRoot(path: string) {
+    return tauriService.invoke<number>('remove_index_root', { path })
+  },
+
+  async indexStats() {
+    return tauriService.invoke<IndexStats>('index_stats')
+  },
+
+  async indexSelfTest() {
+    return tauriService.invoke<IndexSelfTest>('index_self_test')
+  },
+
+  // Data URL per path, or null when the path is missing or has no icon
+  async extractIconsBatch(paths: string[]) {
+    return tauriService.invoke<Record<string, string | null>>('extract_icons_batch', { paths })
+  },
+
+  async getRecentItems(limit?: number) {
+    return tauriService.invoke('get_recent_items', { limit })
+  },
+
+  async pinResult(path: string) {
+    return tauriService.invoke<string[]>('pin_result', { path })
+  },
+
+  async unpinResult(path: string) {
+    return tauriService.invoke<string[]>('unpin_result', { path })
+  },
+
+  async findDuplicates(root?: string, minSize?: number) {
+    return tauriService.invoke('find_duplicates', { root, minSize })
+  },
+
+  async cancelFindDuplicates() {
+    return tauriService.invoke('cancel_find_duplicates')
+  },
+
+  async deleteDuplicate(path: string, keep: string) {
+    return tauriService.invoke('delete_duplicate', { path, keep })
+  },
 }
 
 // Clipboard commands
 export const clipboardCommands = {
-  async getHistory() {
-    return tauriService.invoke('get_clipboard_history')
+  async getHistory(options: {
+    contentType?: 'text' | 'image' | 'files' | 'link'
+    favoritesOnly?: boolean
+    limit?: number
+    offset?: number
+  } = {}) {
+    return tauriService.invoke('get_clipboard_history', options)
   },
   
   async pasteItem(id: string) {
     return tauriService.invoke('paste_clipboard_item', { id })
   },
+
+  async pasteAsPlainText(id: string) {
+    return tauriService.invoke('paste_as_plain_text', { id })
+  },
+
+  async pasteTransformed(
+    id: string,
+    transform: 'plain' | 'markdown_to_html' | 'trim' | 'single_line' | 'uppercase' | 'lowercase'
+  ) {
+    return tauriService.invoke('paste_clipboard_item_transformed', { id, transform })
+  },
+
+  // Pastes the next older history item on each call; returns its id
+  async ringPasteNext() {
+    return tauriService.invoke<string | null>('clipboard_ring_paste_next')
+  },
+
+  async ringReset() {
+    return tauriService.invoke('clipboard_ring_reset')
+  },
+
+  // Text in an image item; `copy` also puts it on the clipboard
+  async ocrItem(id: string, copy = false) {
+    return tauriService.invoke<{ text: string; language: string | null }>('ocr_clipboard_item', { id, copy })
+  },
+
+  // Joins the text items in `ids` order and copies the result; `save` also adds it to history
+  async combineItems(ids: string[], separator: string, save = false) {
+    return tauriService.invoke<{ text: string; combined: number; skipped: number; missing: number }>(
+      'combine_clipboard_items',
+      { ids, separator, save }
+    )
+  },
+}
+
+// Snippet commands ("snip <keyword>" in the launcher)
+export interface Snippet {
+  id: number
+  keyword: string
+  expansion: string
+  created_at: number
+}
+
+export const snippetCommands = {
+  // `expansion` may use {date}, {time} and {clipboard}
+  async add(keyword: string, expansion: string) {
+    return tauriService.invoke<Snippet>('add_snippet', { keyword, expansion })
+  },
+
+  async delete(id: number) {
+    return tauriService.invoke('delete_snippet', { id })
+  },
+
+  async list() {
+    return tauriService.invoke<Snippet[]>('list_snippets')
+  },
 }
 
 // AI commands
@@ -41,6 +214,16 @@ export const aiCommands = {
   },
 }
 
+// Launcher blur graces and shortcut debounces, in milliseconds
+export interface AutohideConfig {
+  on_blur: boolean
+  show_blur_grace_ms: number
+  focus_blur_grace_ms: number
+  main_shortcut_debounce_ms: number
+  clipboard_shortcut_debounce_ms: number
+  capture_shortcut_debounce_ms: number
+}
+
 // Settings commands
 export const settingsCommands = {
   async getConfig() {
@@ -50,6 +233,11 @@ export const settingsCommands = {
   async updateConfig(config: Record<string, unknown>) {
     return tauriService.invoke('update_config', { config })
   },
+
+  // Takes effect without a restart
+  async updateAutohide(autohide: AutohideConfig) {
+    return tauriService.invoke<AutohideConfig>('update_autohide', { autohide })
+  },
 }
 
 // System commands
@@ -57,7 +245,40 @@ export const systemCommands = {
   async openPath(path: string) {
     return tauriService.invoke('open_path', { path })
   },
+
+  async launchPath(path: string) {
+    return tauriService.invoke('launch_path', { path })
+  },
+
+  // `appPath` is one of the paths from getOpenWithCandidates
+  async openWith(path: string, appPath: string) {
+    return tauriService.invoke('open_with', { path, appPath })
+  },
+
+  async getOpenWithCandidates(path: string) {
+    return tauriService.invoke<{ name: string; path: string }[]>('get_open_with_candidates', { path })
+  },
   
+  async revealInFileManager(path: string) {
+    return tauriService.invoke('reveal_in_file_manager', { path })
+  },
+
+  async copyPathToClipboard(path: string) {
+    return tauriService.invoke('copy_path_to_clipboard', { path })
+  },
+
+  async copyFileAsDataUrl(path: string) {
+    return tauriService.invoke('copy_file_as_data_url', { path })
+  },
+
+  async quickNote(text: string) {
+    return tauriService.invoke<string>('quick_note', { text })
+  },
+
+  async quickNoteFromClipboard() {
+    return tauriService.invoke<string>('quick_note_from_clipboard')
+  },
+
   async openUrl(url: string) {
     return tauriService.invoke('open_url', { url })
   },
@@ -73,6 +294,50 @@ export const systemCommands = {
   async toggleMainWindow() {
     return tauriService.invoke('toggle_main_window')
   },
+
+  async listProcesses(query: string) {
+    return tauriService.invoke<{ pid: number; name: string; memory: number }[]>('list_processes', { query })
+  },
+
+  async ocrImageFile(path: string) {
+    return tauriService.invoke<{ text: string; language: string | null }>('ocr_image_file', { path })
+  },
+
+  async killProcess(pid: number) {
+    return tauriService.invoke('kill_process', { pid })
+  },
+
+  async executeSystemCommand(cmd: string) {
+    return tauriService.invoke<{ stdout: string; stderr: string; exit_code: number | null }>(
+      'execute_system_command',
+      { cmd }
+    )
+  },
+
+  // Eyedropper; x/y are physical screen coordinates
+  async pickColorAt(x: number, y: number) {
+    return tauriService.invoke<PickedColor>('pick_color_at', { x, y })
+  },
+
+  async pickColorAtCursor() {
+    return tauriService.invoke<PickedColor>('pick_color_at_cursor')
+  },
+
+  async listWindows(query?: string) {
+    return tauriService.invoke<WindowInfo[]>('list_windows', { query })
+  },
+
+  async focusWindow(hwnd: number) {
+    return tauriService.invoke('focus_window', { hwnd })
+  },
+
+  async runDiagnostics() {
+    return tauriService.invoke<DiagnosticsReport>('run_diagnostics')
+  },
+
+  async getAppStateStatus() {
+    return tauriService.invoke<StartupStatus>('get_app_state_status')
+  },
 }
 
 // Clipboard commands
@@ -15,6 +15,7 @@ const defaultConfig: AppConfig = {
     clipboard: true,
     screenshot: true,
     ai: false,
+    enableShellCommands: false,
   },
   appearance: {
     theme: 'auto',
@@ -27,6 +28,7 @@ const defaultConfig: AppConfig = {
     clipboard: 'CommandOrControl+Shift+V',
     screenshot: 'CommandOrControl+Shift+S',
     aiChat: 'CommandOrControl+Shift+A',
+    quickNote: '',
   },
   indexer: {
     enabled: true,
@@ -5,6 +5,14 @@ export interface AIMessage {
   content: string
   timestamp: number
   attachments?: AIAttachment[]
+  usage?: TokenUsage
+}
+
+export interface TokenUsage {
+  prompt_tokens: number
+  completion_tokens: number
+  total_tokens: number
+  estimated: boolean
 }
 
 export interface AIAttachment {
@@ -25,6 +25,7 @@ export interface FeaturesConfig {
   clipboard: boolean
   screenshot: boolean
   ai: boolean
+  enableShellCommands: boolean
 }
 
 export interface AppearanceConfig {
@@ -39,6 +40,7 @@ export interface ShortcutsConfig {
   clipboard: string
   screenshot: string
   aiChat: string
+  quickNote: string
 }
 
 export interface IndexerConfig {
@@ -20,9 +20,13 @@ export type SearchResultType =
   | 'ai'
   | 'clipboard'
   | 'command'
+  | 'text-case'
+  | 'process'
+  | 'emoji'
+  | 'snippet'
 
 export interface SearchAction {
-  type: 'open' | 'copy' | 'execute' | 'web-search' | 'ai-query' | 'clipboard' | 'settings' | 'none'
+  type: 'open' | 'copy' | 'execute' | 'web-search' | 'ai-query' | 'clipboard' | 'settings' | 'reopen-closed' | 'kill-process' | 'paste-snippet' | 'palette-command' | 'none'
   payload?: string
 }
 
@@ -39,3 +43,44 @@ export interface CalculatorResult {
   result: number | string
   type: 'number' | 'unit-conversion' | 'error'
 }
+
+// Index diagnostics
+export interface IndexStats {
+  file_count: number
+  app_count: number | null
+  everything_available: boolean
+  /** Windows only */
+  everything_status?:
+    | { state: 'dll_missing'; message: string }
+    | { state: 'not_running' }
+    | { state: 'running' }
+    | { state: 'error'; message: string }
+  watcher_running: boolean
+  last_refresh: number | null
+}
+
+export interface IndexSelfTest {
+  query: string
+  result_count: number
+  duration_ms: number
+  error: string | null
+}
+
+/** Order of file results (applied by Everything on Windows) */
+export type FileSortOrder =
+  | 'name_ascending'
+  | 'name_descending'
+  | 'path_ascending'
+  | 'size_descending'
+  | 'size_ascending'
+  | 'date_modified_descending'
+  | 'date_modified_ascending'
+  | 'run_count_descending'
+
+export interface WindowInfo {
+  hwnd: number
+  title: string
+  process: string
+  pid: number
+  minimized: boolean
+}